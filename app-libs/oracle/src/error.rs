@@ -34,6 +34,36 @@ pub enum Error {
 	InvalidCryptoCurrencyId,
 	#[error("Invalid id for fiat currency")]
 	InvalidFiatCurrencyId,
+	#[error("None of the exchange rate sources returned a rate for {0:?}")]
+	NoExchangeRateSource(TradingPair),
+	#[error("Exchange rate sources disagree too much on {0:?}")]
+	ExchangeRateSourcesDisagree(TradingPair),
+	#[error("Invalid oracle source config {0}: {1}")]
+	InvalidSourceConfig(String, String),
+	#[error("Malformed oracle source configs: {0}")]
+	MalformedSourceConfigs(String),
+	#[error("Domain {0} is not allowed by the oracle domain policy")]
+	DomainNotAllowed(String),
+	#[error("Invalid template: {0}")]
+	InvalidTemplate(String),
+	#[error("Missing request parameter: {0}")]
+	MissingParameter(String),
+	#[error("Invalid request parameter: {0}")]
+	InvalidParameter(String),
+	#[error("Invalid JSONPath: {0}")]
+	InvalidJsonPath(String),
+	#[error("Nothing found at JSONPath {0}")]
+	JsonPathNotFound(String),
+	#[error("Value at JSONPath {0} is not a number")]
+	JsonPathNotANumber(String),
+	#[error("Oracle source {0} is not configured")]
+	UnknownOracleSource(String),
+	#[error("Oracle source config store lock is poisoned")]
+	LockPoisoning,
+	#[error("Codec error: {0}")]
+	Codec(#[from] codec::Error),
+	#[error("IO error: {0}")]
+	IO(#[from] std::io::Error),
 	#[error(transparent)]
 	Other(#[from] Box<dyn std::error::Error + Sync + Send + 'static>),
 }
@@ -0,0 +1,143 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Minimal JSONPath subset to extract a single value from a JSON response.
+//!
+//! Supported: the root `$`, child fields `.name` or `['name']`, and array indices `[0]`,
+//! e.g. `$.data[0]['quote'].USD.price`. Wildcards, slices and filters are not supported.
+
+use crate::error::Error;
+use serde_json::Value;
+use std::{
+	string::{String, ToString},
+	vec::Vec,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+	Field(String),
+	Index(usize),
+}
+
+/// Parse a JSONPath expression into its segments.
+pub fn parse_json_path(path: &str) -> Result<Vec<PathSegment>, Error> {
+	let invalid = || Error::InvalidJsonPath(path.to_string());
+
+	let mut rest = path.strip_prefix('$').ok_or_else(invalid)?;
+	let mut segments = Vec::new();
+
+	while !rest.is_empty() {
+		if let Some(after_dot) = rest.strip_prefix('.') {
+			let end = after_dot.find(&['.', '['][..]).unwrap_or(after_dot.len());
+			if end == 0 {
+				return Err(invalid())
+			}
+			segments.push(PathSegment::Field(after_dot[..end].to_string()));
+			rest = &after_dot[end..];
+		} else if let Some(after_bracket) = rest.strip_prefix('[') {
+			let end = after_bracket.find(']').ok_or_else(invalid)?;
+			let selector = &after_bracket[..end];
+			let quoted = selector
+				.strip_prefix('\'')
+				.and_then(|s| s.strip_suffix('\''))
+				.or_else(|| selector.strip_prefix('"').and_then(|s| s.strip_suffix('"')));
+			let segment = match quoted {
+				Some(field) => PathSegment::Field(field.to_string()),
+				None => PathSegment::Index(selector.parse().map_err(|_| invalid())?),
+			};
+			segments.push(segment);
+			rest = &after_bracket[end + 1..];
+		} else {
+			return Err(invalid())
+		}
+	}
+	Ok(segments)
+}
+
+/// Select the value at the given path, if present.
+pub fn select<'a>(value: &'a Value, segments: &[PathSegment]) -> Option<&'a Value> {
+	segments.iter().try_fold(value, |current, segment| match segment {
+		PathSegment::Field(field) => current.get(field.as_str()),
+		PathSegment::Index(index) => current.get(*index),
+	})
+}
+
+/// Extract the number at the given path. Numbers encoded as strings are accepted as well,
+/// as some APIs return prices as strings.
+pub fn extract_number(value: &Value, path: &str) -> Result<f64, Error> {
+	let segments = parse_json_path(path)?;
+	let selected =
+		select(value, &segments).ok_or_else(|| Error::JsonPathNotFound(path.to_string()))?;
+
+	match selected {
+		Value::Number(number) => number.as_f64(),
+		Value::String(string) => string.trim().parse().ok(),
+		_ => None,
+	}
+	.ok_or_else(|| Error::JsonPathNotANumber(path.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use core::assert_matches::assert_matches;
+	use serde_json::json;
+
+	#[test]
+	fn parse_dot_and_bracket_notation_works() {
+		let segments = parse_json_path("$.data[0]['quote'].USD").unwrap();
+		assert_eq!(
+			segments,
+			vec![
+				PathSegment::Field("data".to_string()),
+				PathSegment::Index(0),
+				PathSegment::Field("quote".to_string()),
+				PathSegment::Field("USD".to_string()),
+			]
+		);
+	}
+
+	#[test]
+	fn parse_invalid_paths_fails() {
+		for path in ["data.price", "$.", "$..price", "$[abc]", "$[0", "$price"] {
+			assert_matches!(parse_json_path(path), Err(Error::InvalidJsonPath(_)), "{}", path);
+		}
+	}
+
+	#[test]
+	fn extract_number_works() {
+		let response = json!({ "polkadot": { "usd": 4.2 }, "data": [{ "price": "1.5" }] });
+
+		assert_eq!(4.2, extract_number(&response, "$.polkadot.usd").unwrap());
+		assert_eq!(1.5, extract_number(&response, "$.data[0].price").unwrap());
+	}
+
+	#[test]
+	fn extract_missing_or_non_numeric_value_fails() {
+		let response = json!({ "polkadot": { "usd": null, "name": "Polkadot" } });
+
+		assert_matches!(extract_number(&response, "$.kusama.usd"), Err(Error::JsonPathNotFound(_)));
+		assert_matches!(
+			extract_number(&response, "$.polkadot.name"),
+			Err(Error::JsonPathNotANumber(_))
+		);
+		assert_matches!(
+			extract_number(&response, "$.polkadot.usd"),
+			Err(Error::JsonPathNotANumber(_))
+		);
+	}
+}
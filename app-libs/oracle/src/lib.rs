@@ -32,23 +32,40 @@ pub mod sgx_reexport_prelude {
 	pub use url_sgx as url;
 }
 
-use crate::{error::Error, metrics_exporter::MetricsExporter};
+use crate::{
+	error::Error, metrics_exporter::MetricsExporter, oracles::exchange_rate_oracle::GetExchangeRate,
+};
 use itp_ocall_api::EnclaveMetricsOCallApi;
-use std::sync::Arc;
+use std::{sync::Arc, vec::Vec};
 
 pub mod error;
+pub mod json_path;
 pub mod metrics_exporter;
+pub mod oracle_query;
+pub mod oracle_rest_client;
+pub mod response_cache;
+pub mod source_config;
+pub mod source_config_store;
 pub mod traits;
 pub mod types;
 
+#[cfg(feature = "sgx")]
+pub mod sealing;
+
 pub mod oracles;
-pub use oracles::{exchange_rate_oracle::ExchangeRateOracle, weather_oracle::WeatherOracle};
+pub use oracles::{
+	exchange_rate_aggregator::ExchangeRateAggregator, exchange_rate_oracle::ExchangeRateOracle,
+	on_demand_oracle::OnDemandOracle, weather_oracle::WeatherOracle,
+};
 
 pub mod oracle_sources;
 pub use oracle_sources::{
 	coin_gecko::CoinGeckoSource, coin_market_cap::CoinMarketCapSource,
+	configurable_oracle_source::ConfigurableOracleSource,
 	weather_oracle_source::WeatherOracleSource,
 };
+pub use source_config::OracleSourceConfig;
+pub use source_config_store::OracleSourceConfigStore;
 
 #[cfg(test)]
 mod mock;
@@ -62,6 +79,11 @@ pub type CoinGeckoExchangeRateOracle<OCallApi> =
 pub type CoinMarketCapExchangeRateOracle<OCallApi> =
 	ExchangeRateOracle<CoinMarketCapSource, MetricsExporter<OCallApi>>;
 
+pub type AggregatedExchangeRateOracle<OCallApi> = ExchangeRateAggregator<MetricsExporter<OCallApi>>;
+
+pub type ConfiguredOnDemandOracle<ConfigStore, OCallApi> =
+	OnDemandOracle<ConfigStore, MetricsExporter<OCallApi>>;
+
 pub type OpenMeteoWeatherOracle<OCallApi> =
 	WeatherOracle<WeatherOracleSource, MetricsExporter<OCallApi>>;
 
@@ -77,8 +99,40 @@ pub fn create_coin_market_cap_oracle<OCallApi: EnclaveMetricsOCallApi>(
 	ExchangeRateOracle::new(CoinMarketCapSource {}, Arc::new(MetricsExporter::new(ocall_api)))
 }
 
+/// Aggregates the exchange rates of all supported sources (CoinGecko, CoinMarketCap)
+/// and of the given runtime-configured sources.
+pub fn create_exchange_rate_aggregator<OCallApi: EnclaveMetricsOCallApi + 'static>(
+	ocall_api: Arc<OCallApi>,
+	max_deviation_percent: u32,
+	source_configs: Vec<OracleSourceConfig>,
+) -> AggregatedExchangeRateOracle<OCallApi> {
+	let mut oracles: Vec<Arc<dyn GetExchangeRate + Send + Sync>> = vec![
+		Arc::new(create_coin_gecko_oracle(ocall_api.clone())),
+		Arc::new(create_coin_market_cap_oracle(ocall_api.clone())),
+	];
+	for config in source_configs {
+		oracles.push(Arc::new(ExchangeRateOracle::new(
+			ConfigurableOracleSource::new(config),
+			Arc::new(MetricsExporter::new(ocall_api.clone())),
+		)));
+	}
+	ExchangeRateAggregator::new(
+		oracles,
+		max_deviation_percent,
+		Arc::new(MetricsExporter::new(ocall_api)),
+	)
+}
+
 pub fn create_open_meteo_weather_oracle<OCallApi: EnclaveMetricsOCallApi>(
 	ocall_api: Arc<OCallApi>,
 ) -> OpenMeteoWeatherOracle<OCallApi> {
 	WeatherOracle::new(WeatherOracleSource {}, Arc::new(MetricsExporter::new(ocall_api)))
 }
+
+/// Answers on-demand queries of the sources in the given config store.
+pub fn create_on_demand_oracle<ConfigStore, OCallApi: EnclaveMetricsOCallApi>(
+	config_store: Arc<ConfigStore>,
+	ocall_api: Arc<OCallApi>,
+) -> ConfiguredOnDemandOracle<ConfigStore, OCallApi> {
+	OnDemandOracle::new(config_store, Arc::new(MetricsExporter::new(ocall_api)))
+}
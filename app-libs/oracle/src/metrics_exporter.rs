@@ -15,30 +15,100 @@
 
 */
 
-use crate::types::{ExchangeRate, TradingPair};
-use itp_enclave_metrics::{EnclaveMetric, ExchangeRateOracleMetric, OracleMetric};
+use crate::{
+	response_cache::CacheStats,
+	source_config::EXCHANGE_RATE_FEED,
+	types::{ExchangeRate, TradingPair, WeatherReport, WeatherValue},
+};
+use itp_enclave_metrics::{EnclaveMetric, OracleMetric, OracleValue};
 use itp_ocall_api::EnclaveMetricsOCallApi;
 use log::error;
-use std::{string::String, sync::Arc, time::Instant};
+use std::{
+	string::{String, ToString},
+	sync::Arc,
+	time::Instant,
+};
+
+pub const WEATHER_TEMPERATURE_FEED: &str = "weather_temperature";
+pub const WEATHER_PRECIPITATION_FEED: &str = "weather_precipitation";
+pub const WEATHER_WIND_SPEED_FEED: &str = "weather_wind_speed";
+pub const EXCHANGE_RATE_SOURCES_FEED: &str = "exchange_rate_sources";
 
 /// Trait to export metrics for any Teeracle.
+///
+/// Feed specific metrics are mapped onto the generic [`ExportMetrics::update_value`], so any
+/// oracle ends up in the same labelled series.
 pub trait ExportMetrics<MetricsInfo> {
 	fn increment_number_requests(&self, source: String);
 
+	/// A request to the source failed for good (i.e. after all retries).
+	fn increment_number_failures(&self, source: String);
+
 	fn record_response_time(&self, source: String, timer: Instant);
 
+	/// Latest value of the `feed` for `key`, as reported by the source.
+	fn update_value(&self, source: String, feed: String, key: String, value: OracleValue);
+
+	/// A value of this source was rejected as an outlier by the aggregation.
+	fn record_rejected_value(&self, source: String, feed: String, key: String);
+
+	/// Share of the lookups in the response cache that were served without a request.
+	fn update_cache_hit_ratio(&self, source: String, hit_ratio: OracleValue);
+
 	fn update_exchange_rate(
 		&self,
 		source: String,
 		exchange_rate: ExchangeRate,
 		trading_pair: TradingPair,
-	);
+	) {
+		self.update_value(
+			source,
+			EXCHANGE_RATE_FEED.to_string(),
+			trading_pair.key(),
+			OracleValue::from_num(exchange_rate),
+		);
+	}
 
-	fn update_weather(&self, source: String, metrics_info: MetricsInfo);
-}
+	/// Number of sources an aggregated exchange rate is made up of.
+	fn update_number_of_sources(
+		&self,
+		source: String,
+		number_of_sources: u32,
+		trading_pair: TradingPair,
+	) {
+		self.update_value(
+			source,
+			EXCHANGE_RATE_SOURCES_FEED.to_string(),
+			trading_pair.key(),
+			OracleValue::from_num(number_of_sources),
+		);
+	}
 
-pub trait UpdateMetric<MetricInfo> {
-	fn update_metric(&self, metric: OracleMetric<MetricInfo>);
+	/// An exchange rate of this source was rejected as an outlier by the aggregation.
+	fn record_rejected_source(&self, source: String, trading_pair: TradingPair) {
+		self.record_rejected_value(source, EXCHANGE_RATE_FEED.to_string(), trading_pair.key());
+	}
+
+	fn update_cache_stats(&self, source: String, cache_stats: CacheStats) {
+		self.update_cache_hit_ratio(source, OracleValue::from_num(cache_stats.hit_ratio()));
+	}
+
+	/// Current weather of a location (e.g. `lat/lon`) as reported by the source.
+	fn update_weather(&self, source: String, location: String, weather_report: &WeatherReport) {
+		let values: [(&str, WeatherValue); 3] = [
+			(WEATHER_TEMPERATURE_FEED, weather_report.temperature),
+			(WEATHER_PRECIPITATION_FEED, weather_report.precipitation),
+			(WEATHER_WIND_SPEED_FEED, weather_report.wind_speed),
+		];
+		for (feed, value) in values {
+			self.update_value(
+				source.clone(),
+				feed.to_string(),
+				location.clone(),
+				OracleValue::from_num(value),
+			);
+		}
+	}
 }
 
 /// Metrics exporter implementation.
@@ -46,15 +116,6 @@ pub struct MetricsExporter<OCallApi> {
 	ocall_api: Arc<OCallApi>,
 }
 
-impl<OCallApi, MetricInfo> UpdateMetric<MetricInfo> for MetricsExporter<OCallApi>
-where
-	OCallApi: EnclaveMetricsOCallApi,
-{
-	fn update_metric(&self, _metric: OracleMetric<MetricInfo>) {
-		// TODO: Implement me
-	}
-}
-
 impl<OCallApi> MetricsExporter<OCallApi>
 where
 	OCallApi: EnclaveMetricsOCallApi,
@@ -63,8 +124,8 @@ where
 		MetricsExporter { ocall_api }
 	}
 
-	fn update_metric(&self, metric: ExchangeRateOracleMetric) {
-		if let Err(e) = self.ocall_api.update_metric(EnclaveMetric::ExchangeRateOracle(metric)) {
+	fn update_metric(&self, metric: OracleMetric) {
+		if let Err(e) = self.ocall_api.update_metric(EnclaveMetric::Oracle(metric)) {
 			error!("Failed to update enclave metric, sgx_status_t: {}", e)
 		}
 	}
@@ -75,30 +136,82 @@ where
 	OCallApi: EnclaveMetricsOCallApi,
 {
 	fn increment_number_requests(&self, source: String) {
-		self.update_metric(ExchangeRateOracleMetric::NumberRequestsIncrement(source));
+		self.update_metric(OracleMetric::NumberRequestsIncrement(source));
+	}
+
+	fn increment_number_failures(&self, source: String) {
+		self.update_metric(OracleMetric::NumberFailuresIncrement(source));
 	}
 
 	fn record_response_time(&self, source: String, timer: Instant) {
-		self.update_metric(ExchangeRateOracleMetric::ResponseTime(
-			source,
-			timer.elapsed().as_millis(),
-		));
+		self.update_metric(OracleMetric::ResponseTime(source, timer.elapsed().as_millis()));
 	}
 
-	fn update_exchange_rate(
-		&self,
-		source: String,
-		exchange_rate: ExchangeRate,
-		trading_pair: TradingPair,
-	) {
-		self.update_metric(ExchangeRateOracleMetric::ExchangeRate(
-			source,
-			trading_pair.key(),
-			exchange_rate,
-		));
+	fn update_value(&self, source: String, feed: String, key: String, value: OracleValue) {
+		self.update_metric(OracleMetric::Value(source, feed, key, value));
 	}
 
-	fn update_weather(&self, _source: String, _metrics_info: MetricsInfo) {
-		// TODO: Implement me
+	fn record_rejected_value(&self, source: String, feed: String, key: String) {
+		self.update_metric(OracleMetric::RejectedValue(source, feed, key));
+	}
+
+	fn update_cache_hit_ratio(&self, source: String, hit_ratio: OracleValue) {
+		self.update_metric(OracleMetric::CacheHitRatio(source, hit_ratio));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::MetricsExporterMock;
+
+	#[test]
+	fn feed_specific_metrics_are_exported_as_values() {
+		let metrics_exporter = MetricsExporterMock::default();
+		let trading_pair =
+			TradingPair { crypto_currency: "TEER".to_string(), fiat_currency: "USD".to_string() };
+		let weather_report = WeatherReport {
+			latitude: WeatherValue::from_num(52.52),
+			longitude: WeatherValue::from_num(13.41),
+			time: "2023-05-03T01:00".to_string(),
+			temperature: WeatherValue::from_num(-1.5),
+			precipitation: WeatherValue::from_num(0.25),
+			wind_speed: WeatherValue::from_num(10),
+			hourly: Vec::new(),
+		};
+
+		ExportMetrics::<()>::update_weather(
+			&metrics_exporter,
+			"source".to_string(),
+			"52.52/13.41".to_string(),
+			&weather_report,
+		);
+		ExportMetrics::<()>::record_rejected_source(
+			&metrics_exporter,
+			"source".to_string(),
+			trading_pair,
+		);
+
+		assert_eq!(
+			vec![
+				(
+					WEATHER_TEMPERATURE_FEED.to_string(),
+					"52.52/13.41".to_string(),
+					OracleValue::from_num(-1.5)
+				),
+				(
+					WEATHER_PRECIPITATION_FEED.to_string(),
+					"52.52/13.41".to_string(),
+					OracleValue::from_num(0.25)
+				),
+				(
+					WEATHER_WIND_SPEED_FEED.to_string(),
+					"52.52/13.41".to_string(),
+					OracleValue::from_num(10)
+				),
+			],
+			metrics_exporter.get_values()
+		);
+		assert_eq!(vec!["source".to_string()], metrics_exporter.get_rejected_sources());
 	}
 }
@@ -24,13 +24,14 @@ use std::sync::RwLock;
 use crate::{
 	error::Error,
 	metrics_exporter::ExportMetrics,
-	traits::OracleSource,
+	oracle_rest_client::OracleRestClient,
+	oracles::exchange_rate_oracle::GetExchangeRate,
+	source_config_store::OracleSourceConfigs,
+	traits::{ExecuteOracleRequest, OracleSource},
 	types::{ExchangeRate, TradingPair},
 };
-use itc_rest_client::{
-	http_client::{HttpClient, SendWithCertificateVerification},
-	rest_client::RestClient,
-};
+use itp_enclave_metrics::OracleValue;
+use itp_sgx_io::SealedIO;
 use std::{
 	time::{Duration, Instant},
 	vec::Vec,
@@ -43,6 +44,10 @@ pub(crate) struct MetricsExporterMock {
 	number_requests: RwLock<u64>,
 	response_times: RwLock<Vec<u128>>,
 	exchange_rates: RwLock<Vec<(TradingPair, ExchangeRate)>>,
+	rejected_sources: RwLock<Vec<String>>,
+	number_failures: RwLock<u64>,
+	values: RwLock<Vec<(String, String, OracleValue)>>,
+	cache_hit_ratios: RwLock<Vec<OracleValue>>,
 }
 
 impl MetricsExporterMock {
@@ -57,6 +62,23 @@ impl MetricsExporterMock {
 	pub fn get_exchange_rates(&self) -> Vec<(TradingPair, ExchangeRate)> {
 		self.exchange_rates.read().unwrap().clone()
 	}
+
+	pub fn get_rejected_sources(&self) -> Vec<String> {
+		self.rejected_sources.read().unwrap().clone()
+	}
+
+	pub fn get_number_failures(&self) -> u64 {
+		*self.number_failures.read().unwrap()
+	}
+
+	/// Values reported with `update_value`, as (feed, key, value).
+	pub fn get_values(&self) -> Vec<(String, String, OracleValue)> {
+		self.values.read().unwrap().clone()
+	}
+
+	pub fn get_cache_hit_ratios(&self) -> Vec<OracleValue> {
+		self.cache_hit_ratios.read().unwrap().clone()
+	}
 }
 
 impl<MetricsInfo> ExportMetrics<MetricsInfo> for MetricsExporterMock {
@@ -64,6 +86,10 @@ impl<MetricsInfo> ExportMetrics<MetricsInfo> for MetricsExporterMock {
 		(*self.number_requests.write().unwrap()) += 1;
 	}
 
+	fn increment_number_failures(&self, _source: String) {
+		(*self.number_failures.write().unwrap()) += 1;
+	}
+
 	fn record_response_time(&self, _source: String, timer: Instant) {
 		self.response_times.write().unwrap().push(timer.elapsed().as_millis());
 	}
@@ -77,7 +103,17 @@ impl<MetricsInfo> ExportMetrics<MetricsInfo> for MetricsExporterMock {
 		self.exchange_rates.write().unwrap().push((trading_pair, exchange_rate));
 	}
 
-	fn update_weather(&self, _source: String, _metrics_info: MetricsInfo) {}
+	fn update_value(&self, _source: String, feed: String, key: String, value: OracleValue) {
+		self.values.write().unwrap().push((feed, key, value));
+	}
+
+	fn record_rejected_value(&self, source: String, _feed: String, _key: String) {
+		self.rejected_sources.write().unwrap().push(source);
+	}
+
+	fn update_cache_hit_ratio(&self, _source: String, hit_ratio: OracleValue) {
+		self.cache_hit_ratios.write().unwrap().push(hit_ratio);
+	}
 }
 
 /// Mock oracle source.
@@ -85,8 +121,6 @@ impl<MetricsInfo> ExportMetrics<MetricsInfo> for MetricsExporterMock {
 pub(crate) struct OracleSourceMock;
 
 impl<OracleSourceInfo> OracleSource<OracleSourceInfo> for OracleSourceMock {
-	type OracleRequestResult = Result<f32, Error>;
-
 	fn metrics_id(&self) -> String {
 		"source_mock".to_string()
 	}
@@ -104,16 +138,61 @@ impl<OracleSourceInfo> OracleSource<OracleSourceInfo> for OracleSourceMock {
 	}
 	fn execute_exchange_rate_request(
 		&self,
-		_rest_client: &mut RestClient<HttpClient<SendWithCertificateVerification>>,
+		_rest_client: &mut OracleRestClient,
 		_trading_pair: TradingPair,
 	) -> Result<ExchangeRate, Error> {
 		Ok(ExchangeRate::from_num(42.3f32))
 	}
+}
+
+impl<OracleSourceInfo> ExecuteOracleRequest<OracleSourceInfo> for OracleSourceMock {
+	type OracleRequestResult = Result<f32, Error>;
 
 	fn execute_request(
-		_rest_client: &mut RestClient<HttpClient<SendWithCertificateVerification>>,
+		_rest_client: &mut OracleRestClient,
 		_source_info: OracleSourceInfo,
 	) -> Self::OracleRequestResult {
 		Ok(42.3f32)
 	}
 }
+
+/// Mock exchange rate oracle, returning a fixed rate or failing if there is none.
+pub(crate) struct ExchangeRateOracleMock {
+	base_url: Url,
+	rate: Option<f32>,
+}
+
+impl ExchangeRateOracleMock {
+	pub fn new(base_url: &str, rate: Option<f32>) -> Self {
+		ExchangeRateOracleMock { base_url: Url::parse(base_url).unwrap(), rate }
+	}
+}
+
+impl GetExchangeRate for ExchangeRateOracleMock {
+	fn get_exchange_rate(&self, trading_pair: TradingPair) -> Result<(ExchangeRate, Url), Error> {
+		match self.rate {
+			Some(rate) => Ok((ExchangeRate::from_num(rate), self.base_url.clone())),
+			None => Err(Error::NoValidData(self.base_url.to_string(), trading_pair.key())),
+		}
+	}
+}
+
+/// In-memory seal for the oracle source configs.
+#[derive(Default)]
+pub(crate) struct OracleSourceConfigSealMock {
+	configs: RwLock<OracleSourceConfigs>,
+}
+
+impl SealedIO for OracleSourceConfigSealMock {
+	type Error = Error;
+	type Unsealed = OracleSourceConfigs;
+
+	fn unseal(&self) -> Result<Self::Unsealed, Self::Error> {
+		Ok(self.configs.read().map_err(|_| Error::LockPoisoning)?.clone())
+	}
+
+	fn seal(&self, unsealed: &Self::Unsealed) -> Result<(), Self::Error> {
+		*self.configs.write().map_err(|_| Error::LockPoisoning)? = unsealed.clone();
+		Ok(())
+	}
+}
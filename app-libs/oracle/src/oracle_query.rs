@@ -0,0 +1,133 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Pull-based oracle queries: a client asks for a single value of a configured source and
+//! gets it back signed by the enclave, so it can be forwarded and verified by third parties.
+
+use crate::types::OracleValue;
+use codec::{Decode, Encode};
+use sp_core::{ed25519, Pair};
+use std::{string::String, vec::Vec};
+
+/// Prefix of the signed payload, so a signature over an oracle query response can't be passed
+/// off as one over any other message signed with the enclave key.
+pub const ORACLE_QUERY_RESPONSE_SIGNING_CONTEXT: &[u8] = b"integritee:oracle-query-response:v1";
+
+/// Query of a configured oracle source, e.g. sent over the `oracle_query` direct RPC.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+pub struct OracleQuery {
+	/// Name of the configured source.
+	pub source: String,
+	/// Values of the placeholders in the query template and JSONPath of the source.
+	pub parameters: Vec<(String, String)>,
+}
+
+/// Result of an [`OracleQuery`], including the provenance of the value.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+pub struct OracleQueryResponse {
+	pub source: String,
+	pub feed: String,
+	pub parameters: Vec<(String, String)>,
+	pub value: OracleValue,
+	/// URL the value was fetched from.
+	pub url: String,
+	/// Unix timestamp in milliseconds, taken when the response was received.
+	pub timestamp: u64,
+	/// SHA-256 fingerprints of the root certificates the TLS connection was verified against,
+	/// the server certificate chains up to one of them.
+	pub trusted_root_certificate_fingerprints: Vec<[u8; 32]>,
+}
+
+/// An [`OracleQueryResponse`] signed by the enclave.
+///
+/// The signature only proves that the holder of `signer` produced the response. Clients still
+/// have to check that `signer` is the key of a registered enclave.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+pub struct SignedOracleQueryResponse {
+	pub response: OracleQueryResponse,
+	pub signer: ed25519::Public,
+	/// Signature over the encoded response, prefixed with [`ORACLE_QUERY_RESPONSE_SIGNING_CONTEXT`].
+	pub signature: ed25519::Signature,
+}
+
+impl SignedOracleQueryResponse {
+	pub fn new_signed(response: OracleQueryResponse, signer: &ed25519::Pair) -> Self {
+		let signature = signer.sign(&Self::payload(&response));
+		SignedOracleQueryResponse { response, signer: signer.public(), signature }
+	}
+
+	pub fn verify_signature(&self) -> bool {
+		ed25519::Pair::verify(&self.signature, Self::payload(&self.response), &self.signer)
+	}
+
+	fn payload(response: &OracleQueryResponse) -> Vec<u8> {
+		[ORACLE_QUERY_RESPONSE_SIGNING_CONTEXT, response.encode().as_slice()].concat()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn response() -> OracleQueryResponse {
+		OracleQueryResponse {
+			source: "binance".into(),
+			feed: "exchange_rate".into(),
+			parameters: vec![("crypto_currency".into(), "TEER".into())],
+			value: OracleValue::from_num(1.25f64),
+			url: "https://api.binance.com/api/v3/ticker/price?symbol=TEERUSDT".into(),
+			timestamp: 1_683_115_200_000,
+			trusted_root_certificate_fingerprints: vec![[7u8; 32]],
+		}
+	}
+
+	#[test]
+	fn signed_response_verifies() {
+		let signer = ed25519::Pair::from_seed(&[1u8; 32]);
+		let signed = SignedOracleQueryResponse::new_signed(response(), &signer);
+
+		assert!(signed.verify_signature());
+		assert_eq!(signer.public(), signed.signer);
+	}
+
+	#[test]
+	fn tampered_response_fails_verification() {
+		let signer = ed25519::Pair::from_seed(&[1u8; 32]);
+		let mut signed = SignedOracleQueryResponse::new_signed(response(), &signer);
+		signed.response.value = OracleValue::from_num(2);
+
+		assert!(!signed.verify_signature());
+	}
+
+	#[test]
+	fn signature_without_signing_context_fails_verification() {
+		let signer = ed25519::Pair::from_seed(&[1u8; 32]);
+		let mut signed = SignedOracleQueryResponse::new_signed(response(), &signer);
+		signed.signature = signer.sign(&response().encode());
+
+		assert!(!signed.verify_signature());
+	}
+
+	#[test]
+	fn response_of_other_signer_fails_verification() {
+		let signer = ed25519::Pair::from_seed(&[1u8; 32]);
+		let mut signed = SignedOracleQueryResponse::new_signed(response(), &signer);
+		signed.signer = ed25519::Pair::from_seed(&[2u8; 32]).public();
+
+		assert!(!signed.verify_signature());
+	}
+}
@@ -0,0 +1,81 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! The REST client all oracle sources are queried with.
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use crate::sgx_reexport_prelude::*;
+
+use crate::response_cache::{CacheStats, OracleResponseCache, SourceResponseCache};
+use core::time::Duration;
+use itc_rest_client::{
+	certificates::SpkiPin,
+	circuit_breaker::CircuitBreakers,
+	http_client::{HttpClient, SendWithCertificateVerification},
+	rest_client::RestClient,
+	retry::{RetryHttpClient, RetryPolicy},
+};
+use lazy_static::lazy_static;
+use std::{string::String, sync::Arc, vec::Vec};
+use url::Url;
+
+pub type OracleRestClient =
+	RestClient<RetryHttpClient<HttpClient<SendWithCertificateVerification>>>;
+
+lazy_static! {
+	/// Shared by all oracle requests, since a client is created per request.
+	static ref ORACLE_CIRCUIT_BREAKERS: Arc<CircuitBreakers> = Arc::new(CircuitBreakers::default());
+	static ref ORACLE_RESPONSE_CACHE: Arc<OracleResponseCache> =
+		Arc::new(OracleResponseCache::default());
+}
+
+/// Client that only trusts the given root certificate(s) (restricted to the SPKI pins, if any),
+/// retries transient failures and stops querying hosts that keep failing for a while.
+pub fn create_oracle_rest_client(
+	base_url: Url,
+	root_certificate: String,
+	spki_pins: Vec<SpkiPin>,
+	request_timeout: Option<Duration>,
+) -> OracleRestClient {
+	let http_client = HttpClient::new(
+		SendWithCertificateVerification::new(root_certificate).with_spki_pins(spki_pins),
+		true,
+		request_timeout,
+		None,
+		None,
+	);
+	let retry_client = RetryHttpClient::new(
+		http_client,
+		RetryPolicy::default(),
+		Some(ORACLE_CIRCUIT_BREAKERS.clone()),
+	);
+	RestClient::new(retry_client, base_url)
+}
+
+/// Serve the GET requests of the client from the response cache shared by all oracle requests,
+/// as responses of `source`.
+pub fn use_oracle_response_cache(rest_client: &mut OracleRestClient, source: String) {
+	rest_client.set_response_cache(Arc::new(SourceResponseCache::new(
+		ORACLE_RESPONSE_CACHE.clone(),
+		source,
+	)));
+}
+
+/// Lookups of `source` in the shared response cache.
+pub fn oracle_response_cache_stats(source: &str) -> CacheStats {
+	ORACLE_RESPONSE_CACHE.stats(source)
+}
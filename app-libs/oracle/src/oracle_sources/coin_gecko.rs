@@ -20,19 +20,16 @@ use crate::sgx_reexport_prelude::*;
 
 use crate::{
 	error::Error,
-	traits::OracleSource,
+	oracle_rest_client::OracleRestClient,
+	traits::{ExecuteOracleRequest, OracleSource},
 	types::{ExchangeRate, TradingInfo, TradingPair},
 };
-use itc_rest_client::{
-	http_client::{HttpClient, SendWithCertificateVerification},
-	rest_client::RestClient,
-	RestGet, RestPath,
-};
+use itc_rest_client::{RestGet, RestPath};
 use lazy_static::lazy_static;
 use log::{debug, error};
 use serde::{Deserialize, Serialize};
 use std::{
-	collections::HashMap,
+	collections::{BTreeMap, HashMap},
 	string::{String, ToString},
 	time::Duration,
 	vec::Vec,
@@ -43,6 +40,8 @@ const COINGECKO_URL: &str = "https://api.coingecko.com";
 const COINGECKO_PARAM_CURRENCY: &str = "vs_currency";
 const COINGECKO_PARAM_COIN: &str = "ids";
 const COINGECKO_PATH: &str = "api/v3/coins/markets";
+const COINGECKO_SIMPLE_PRICE_PATH: &str = "api/v3/simple/price";
+const COINGECKO_PARAM_CURRENCIES: &str = "vs_currencies";
 const COINGECKO_TIMEOUT: Duration = Duration::from_secs(20u64);
 const COINGECKO_ROOT_CERTIFICATE: &str = include_str!("../certificates/lets_encrypt_root_cert.pem");
 
@@ -67,11 +66,27 @@ impl CoinGeckoSource {
 			None => Err(Error::InvalidCryptoCurrencyId),
 		}
 	}
+
+	fn exchange_rates_from_prices(
+		prices: &CoinGeckoSimplePrice,
+		trading_pairs: &[TradingPair],
+	) -> Vec<Result<ExchangeRate, Error>> {
+		trading_pairs
+			.iter()
+			.map(|trading_pair| {
+				let crypto_id = Self::map_crypto_currency_id(trading_pair)?;
+				prices
+					.0
+					.get(&crypto_id)
+					.and_then(|prices| prices.get(&trading_pair.fiat_currency.to_lowercase()))
+					.map(|price| ExchangeRate::from_num(*price))
+					.ok_or_else(|| Error::EmptyExchangeRate(trading_pair.clone()))
+			})
+			.collect()
+	}
 }
 
 impl<OracleSourceInfo: Into<TradingInfo>> OracleSource<OracleSourceInfo> for CoinGeckoSource {
-	type OracleRequestResult = Result<(), Error>;
-
 	fn metrics_id(&self) -> String {
 		"coin_gecko".to_string()
 	}
@@ -88,18 +103,9 @@ impl<OracleSourceInfo: Into<TradingInfo>> OracleSource<OracleSourceInfo> for Coi
 		COINGECKO_ROOT_CERTIFICATE.to_string()
 	}
 
-	fn execute_request(
-		_rest_client: &mut RestClient<HttpClient<SendWithCertificateVerification>>,
-		source_info: OracleSourceInfo,
-	) -> Self::OracleRequestResult {
-		let _trading_info: TradingInfo = source_info.into();
-		// TODO Implement me
-		Ok(())
-	}
-
 	fn execute_exchange_rate_request(
 		&self,
-		rest_client: &mut RestClient<HttpClient<SendWithCertificateVerification>>,
+		rest_client: &mut OracleRestClient,
 		trading_pair: TradingPair,
 	) -> Result<ExchangeRate, Error> {
 		let fiat_id = trading_pair.fiat_currency.clone();
@@ -129,6 +135,72 @@ impl<OracleSourceInfo: Into<TradingInfo>> OracleSource<OracleSourceInfo> for Coi
 			None => Err(Error::EmptyExchangeRate(trading_pair)),
 		}
 	}
+
+	/// All trading pairs with a single `simple/price` request.
+	fn execute_exchange_rates_request(
+		&self,
+		rest_client: &mut OracleRestClient,
+		trading_pairs: &[TradingPair],
+	) -> Vec<Result<ExchangeRate, Error>> {
+		let mut crypto_ids: Vec<String> = Vec::new();
+		let mut fiat_ids: Vec<String> = Vec::new();
+		for trading_pair in trading_pairs {
+			if let Ok(crypto_id) = Self::map_crypto_currency_id(trading_pair) {
+				if !crypto_ids.contains(&crypto_id) {
+					crypto_ids.push(crypto_id);
+				}
+				let fiat_id = trading_pair.fiat_currency.to_lowercase();
+				if !fiat_ids.contains(&fiat_id) {
+					fiat_ids.push(fiat_id);
+				}
+			}
+		}
+		if crypto_ids.is_empty() {
+			return trading_pairs.iter().map(|_| Err(Error::InvalidCryptoCurrencyId)).collect()
+		}
+
+		let response = rest_client.get_with::<String, CoinGeckoSimplePrice>(
+			COINGECKO_SIMPLE_PRICE_PATH.to_string(),
+			&[
+				(COINGECKO_PARAM_COIN, &crypto_ids.join(",")),
+				(COINGECKO_PARAM_CURRENCIES, &fiat_ids.join(",")),
+			],
+		);
+
+		match response {
+			Ok(prices) => {
+				debug!("coingecko received prices: {:#?}", &prices);
+				Self::exchange_rates_from_prices(&prices, trading_pairs)
+			},
+			Err(e) => {
+				error!("coingecko execute_exchange_rates_request() failed with: {:#?}", &e);
+				trading_pairs
+					.iter()
+					.map(|trading_pair| {
+						Err(Error::NoValidData(
+							COINGECKO_URL.to_string(),
+							trading_pair.clone().key(),
+						))
+					})
+					.collect()
+			},
+		}
+	}
+}
+
+impl<OracleSourceInfo: Into<TradingInfo>> ExecuteOracleRequest<OracleSourceInfo>
+	for CoinGeckoSource
+{
+	type OracleRequestResult = Result<(), Error>;
+
+	fn execute_request(
+		_rest_client: &mut OracleRestClient,
+		source_info: OracleSourceInfo,
+	) -> Self::OracleRequestResult {
+		let _trading_info: TradingInfo = source_info.into();
+		// TODO Implement me
+		Ok(())
+	}
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -149,6 +221,16 @@ impl RestPath<String> for CoinGeckoMarket {
 	}
 }
 
+/// Prices per coin id and (lower case) fiat currency, e.g. `{"polkadot": {"usd": 4.5}}`.
+#[derive(Serialize, Deserialize, Debug)]
+struct CoinGeckoSimplePrice(pub BTreeMap<String, BTreeMap<String, f64>>);
+
+impl RestPath<String> for CoinGeckoSimplePrice {
+	fn get_path(path: String) -> Result<String, itc_rest_client::error::Error> {
+		Ok(path)
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -199,6 +281,33 @@ mod tests {
 		assert_matches!(result, Err(Error::InvalidCryptoCurrencyId));
 	}
 
+	#[test]
+	fn exchange_rates_are_read_from_simple_prices() {
+		let prices: CoinGeckoSimplePrice = serde_json::from_str(
+			r#"{"polkadot": {"usd": 4.5, "chf": 4.0}, "integritee": {"usd": 0.25}}"#,
+		)
+		.unwrap();
+		let trading_pair = |crypto_currency: &str, fiat_currency: &str| TradingPair {
+			crypto_currency: crypto_currency.to_string(),
+			fiat_currency: fiat_currency.to_string(),
+		};
+
+		let exchange_rates = CoinGeckoSource::exchange_rates_from_prices(
+			&prices,
+			&[
+				trading_pair("DOT", "CHF"),
+				trading_pair("TEER", "USD"),
+				trading_pair("TEER", "CHF"),
+				trading_pair("Undefined", "USD"),
+			],
+		);
+
+		assert_eq!(exchange_rates[0].as_ref().unwrap(), &ExchangeRate::from_num(4.0));
+		assert_eq!(exchange_rates[1].as_ref().unwrap(), &ExchangeRate::from_num(0.25));
+		assert_matches!(exchange_rates[2], Err(Error::EmptyExchangeRate(_)));
+		assert_matches!(exchange_rates[3], Err(Error::InvalidCryptoCurrencyId));
+	}
+
 	#[test]
 	fn get_exchange_rate_for_undefined_fiat_currency_fails() {
 		let coin_gecko_client = create_coin_gecko_client();
@@ -19,14 +19,11 @@ use crate::sgx_reexport_prelude::*;
 
 use crate::{
 	error::Error,
-	traits::OracleSource,
+	oracle_rest_client::OracleRestClient,
+	traits::{ExecuteOracleRequest, OracleSource},
 	types::{ExchangeRate, TradingInfo, TradingPair},
 };
-use itc_rest_client::{
-	http_client::{HttpClient, SendWithCertificateVerification},
-	rest_client::RestClient,
-	RestGet, RestPath,
-};
+use itc_rest_client::{RestGet, RestPath};
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -76,9 +73,6 @@ impl CoinMarketCapSource {
 }
 
 impl<OracleSourceInfo: Into<TradingInfo>> OracleSource<OracleSourceInfo> for CoinMarketCapSource {
-	// TODO Change this to return something useful?
-	type OracleRequestResult = Result<(), Error>;
-
 	fn metrics_id(&self) -> String {
 		"coin_market_cap".to_string()
 	}
@@ -95,20 +89,9 @@ impl<OracleSourceInfo: Into<TradingInfo>> OracleSource<OracleSourceInfo> for Coi
 		COINMARKETCAP_ROOT_CERTIFICATE.to_string()
 	}
 
-	fn execute_request(
-		_rest_client: &mut RestClient<HttpClient<SendWithCertificateVerification>>,
-		source_info: OracleSourceInfo,
-	) -> Self::OracleRequestResult {
-		let trading_info: TradingInfo = source_info.into();
-		let _fiat_currency = trading_info.trading_pair.fiat_currency;
-		let _crypto_currency = trading_info.trading_pair.crypto_currency;
-		// TODO Implement me
-		Ok(())
-	}
-
 	fn execute_exchange_rate_request(
 		&self,
-		rest_client: &mut RestClient<HttpClient<SendWithCertificateVerification>>,
+		rest_client: &mut OracleRestClient,
 		trading_pair: TradingPair,
 	) -> Result<ExchangeRate, Error> {
 		let fiat_id = Self::map_fiat_currency_id(&trading_pair)?;
@@ -148,6 +131,24 @@ impl<OracleSourceInfo: Into<TradingInfo>> OracleSource<OracleSourceInfo> for Coi
 	}
 }
 
+impl<OracleSourceInfo: Into<TradingInfo>> ExecuteOracleRequest<OracleSourceInfo>
+	for CoinMarketCapSource
+{
+	// TODO Change this to return something useful?
+	type OracleRequestResult = Result<(), Error>;
+
+	fn execute_request(
+		_rest_client: &mut OracleRestClient,
+		source_info: OracleSourceInfo,
+	) -> Self::OracleRequestResult {
+		let trading_info: TradingInfo = source_info.into();
+		let _fiat_currency = trading_info.trading_pair.fiat_currency;
+		let _crypto_currency = trading_info.trading_pair.crypto_currency;
+		// TODO Implement me
+		Ok(())
+	}
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 struct DataStruct {
 	id: Option<u32>,
@@ -0,0 +1,113 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use crate::sgx_reexport_prelude::*;
+
+use crate::{
+	error::Error,
+	json_path::extract_number,
+	oracle_rest_client::OracleRestClient,
+	source_config::{OracleSourceConfig, CRYPTO_CURRENCY_PLACEHOLDER, FIAT_CURRENCY_PLACEHOLDER},
+	traits::OracleSource,
+	types::{ExchangeRate, TradingPair},
+};
+use itc_rest_client::{certificates::SpkiPin, RestGet, RestPath};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::{string::String, time::Duration, vec::Vec};
+use url::Url;
+
+/// Oracle source defined by an [`OracleSourceConfig`] instead of code.
+#[derive(Default, Clone)]
+pub struct ConfigurableOracleSource {
+	config: OracleSourceConfig,
+}
+
+impl ConfigurableOracleSource {
+	pub fn new(config: OracleSourceConfig) -> Self {
+		ConfigurableOracleSource { config }
+	}
+
+	pub fn config(&self) -> &OracleSourceConfig {
+		&self.config
+	}
+
+	/// Query the source and extract the value at the configured JSONPath.
+	pub fn execute_query(
+		&self,
+		rest_client: &mut OracleRestClient,
+		params: &[(&str, &str)],
+	) -> Result<f64, Error> {
+		let request = self.config.render_request(params)?;
+		let query: Vec<(&str, &str)> =
+			request.query.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+
+		let response = rest_client
+			.get_with::<String, JsonResponse>(request.path, query.as_slice())
+			.map_err(Error::RestClient)?;
+
+		debug!("{} received response: {:?}", self.config.name, response.0);
+		extract_number(&response.0, &request.json_path)
+	}
+}
+
+// No `ExecuteOracleRequest`, the request depends on the configuration, see `execute_query`.
+impl<OracleSourceInfo> OracleSource<OracleSourceInfo> for ConfigurableOracleSource {
+	fn metrics_id(&self) -> String {
+		self.config.name.clone()
+	}
+
+	fn request_timeout(&self) -> Option<Duration> {
+		Some(Duration::from_secs(self.config.timeout_secs))
+	}
+
+	fn base_url(&self) -> Result<Url, Error> {
+		Url::parse(&self.config.base_url).map_err(|e| Error::Other(format!("{:?}", e).into()))
+	}
+
+	fn root_certificate_content(&self) -> String {
+		self.config.root_certificate.clone()
+	}
+
+	fn spki_pins(&self) -> Vec<SpkiPin> {
+		// The pins were checked when the config was validated.
+		self.config.decoded_spki_pins().unwrap_or_default()
+	}
+
+	fn execute_exchange_rate_request(
+		&self,
+		rest_client: &mut OracleRestClient,
+		trading_pair: TradingPair,
+	) -> Result<ExchangeRate, Error> {
+		let params = [
+			(CRYPTO_CURRENCY_PLACEHOLDER, trading_pair.crypto_currency.as_str()),
+			(FIAT_CURRENCY_PLACEHOLDER, trading_pair.fiat_currency.as_str()),
+		];
+		self.execute_query(rest_client, &params).map(ExchangeRate::from_num)
+	}
+}
+
+/// Any JSON response, the value is selected by JSONPath.
+#[derive(Serialize, Deserialize, Debug)]
+struct JsonResponse(pub serde_json::Value);
+
+impl RestPath<String> for JsonResponse {
+	fn get_path(path: String) -> Result<String, itc_rest_client::error::Error> {
+		Ok(path)
+	}
+}
@@ -16,4 +16,5 @@
 */
 pub mod coin_gecko;
 pub mod coin_market_cap;
+pub mod configurable_oracle_source;
 pub mod weather_oracle_source;
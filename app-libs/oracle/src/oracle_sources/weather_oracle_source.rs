@@ -20,36 +20,33 @@ use crate::sgx_reexport_prelude::*;
 
 use crate::{
 	error::Error,
-	traits::OracleSource,
-	types::{ExchangeRate, TradingPair, WeatherInfo},
-};
-use itc_rest_client::{
-	http_client::{HttpClient, SendWithCertificateVerification},
-	rest_client::RestClient,
-	RestGet, RestPath,
+	oracle_rest_client::OracleRestClient,
+	traits::{ExecuteOracleRequest, OracleSource},
+	types::{ExchangeRate, HourlyWeather, TradingPair, WeatherInfo, WeatherReport, WeatherValue},
 };
+use itc_rest_client::{RestGet, RestPath};
 use serde::{Deserialize, Serialize};
 use std::{
 	string::{String, ToString},
 	time::Duration,
+	vec::Vec,
 };
 use url::Url;
 
 const WEATHER_URL: &str = "https://api.open-meteo.com";
 const WEATHER_PARAM_LONGITUDE: &str = "longitude";
 const WEATHER_PARAM_LATITUDE: &str = "latitude";
-// const WEATHER_PARAM_HOURLY: &str = "hourly"; // TODO: Add to Query
+const WEATHER_PARAM_CURRENT_WEATHER: &str = "current_weather";
+const WEATHER_PARAM_HOURLY: &str = "hourly";
+const WEATHER_HOURLY_VARIABLES: &str = "temperature_2m,precipitation,windspeed_10m";
 const WEATHER_PATH: &str = "v1/forecast";
 const WEATHER_TIMEOUT: Duration = Duration::from_secs(3u64);
 const WEATHER_ROOT_CERTIFICATE: &str = include_str!("../certificates/open_meteo_root.pem");
 
-// TODO: Change f32 types to appropriate Substrate Fixed Type
 #[derive(Default)]
 pub struct WeatherOracleSource;
 
 impl<OracleSourceInfo: Into<WeatherInfo>> OracleSource<OracleSourceInfo> for WeatherOracleSource {
-	type OracleRequestResult = Result<f32, Error>; // TODO: Change from f32 type
-
 	fn metrics_id(&self) -> String {
 		"weather".to_string()
 	}
@@ -69,44 +66,114 @@ impl<OracleSourceInfo: Into<WeatherInfo>> OracleSource<OracleSourceInfo> for Wea
 
 	fn execute_exchange_rate_request(
 		&self,
-		_rest_client: &mut RestClient<HttpClient<SendWithCertificateVerification>>,
+		_rest_client: &mut OracleRestClient,
 		_trading_pair: TradingPair,
 	) -> Result<ExchangeRate, Error> {
 		Err(Error::NoValidData("None".into(), "None".into()))
 	}
+}
+
+impl<OracleSourceInfo: Into<WeatherInfo>> ExecuteOracleRequest<OracleSourceInfo>
+	for WeatherOracleSource
+{
+	type OracleRequestResult = Result<WeatherReport, Error>;
 
-	// TODO: Make this take a variant perhaps or a Closure so that it is more generic
 	fn execute_request(
-		rest_client: &mut RestClient<HttpClient<SendWithCertificateVerification>>,
+		rest_client: &mut OracleRestClient,
 		source_info: OracleSourceInfo,
 	) -> Self::OracleRequestResult {
 		let weather_info: WeatherInfo = source_info.into();
 		let query = weather_info.weather_query;
 
-		// TODO:
-		// This part is opinionated towards a hard coded query need to make more generic
 		let response = rest_client
 			.get_with::<String, OpenMeteo>(
 				WEATHER_PATH.into(),
 				&[
 					(WEATHER_PARAM_LATITUDE, &query.latitude),
 					(WEATHER_PARAM_LONGITUDE, &query.longitude),
-					//(WEATHER_PARAM_HOURLY), &query.hourly),
+					(WEATHER_PARAM_CURRENT_WEATHER, "true"),
+					(WEATHER_PARAM_HOURLY, WEATHER_HOURLY_VARIABLES),
 				],
 			)
 			.map_err(Error::RestClient)?;
 
-		let open_meteo_weather_struct = response.0;
+		to_weather_report(response.0, query.forecast_hours as usize)
+	}
+}
 
-		Ok(open_meteo_weather_struct.longitude)
+/// Convert the Open-Meteo response into a report, with the hourly series starting at the
+/// hour of the current weather.
+fn to_weather_report(
+	response: OpenMeteoWeatherStruct,
+	forecast_hours: usize,
+) -> Result<WeatherReport, Error> {
+	let hourly = response.hourly;
+	let number_of_hours = hourly.time.len();
+	if hourly.temperature_2m.len() != number_of_hours
+		|| hourly.precipitation.len() != number_of_hours
+		|| hourly.windspeed_10m.len() != number_of_hours
+	{
+		return Err(no_valid_data("hourly series of different lengths"))
 	}
+
+	let current_hour = hourly
+		.time
+		.iter()
+		.position(|time| *time == response.current_weather.time)
+		.ok_or_else(|| no_valid_data("current hour is not in the hourly series"))?;
+
+	let hourly_weather = (current_hour..number_of_hours)
+		.take(forecast_hours)
+		.map(|hour| {
+			Ok(HourlyWeather {
+				time: hourly.time[hour].clone(),
+				temperature: to_weather_value(hourly.temperature_2m[hour])?,
+				precipitation: to_weather_value(hourly.precipitation[hour])?,
+				wind_speed: to_weather_value(hourly.windspeed_10m[hour])?,
+			})
+		})
+		.collect::<Result<Vec<_>, Error>>()?;
+
+	Ok(WeatherReport {
+		latitude: to_weather_value(response.latitude)?,
+		longitude: to_weather_value(response.longitude)?,
+		time: response.current_weather.time,
+		temperature: to_weather_value(response.current_weather.temperature)?,
+		precipitation: to_weather_value(hourly.precipitation[current_hour])?,
+		wind_speed: to_weather_value(response.current_weather.windspeed)?,
+		hourly: hourly_weather,
+	})
+}
+
+fn to_weather_value(value: f64) -> Result<WeatherValue, Error> {
+	WeatherValue::checked_from_num(value).ok_or_else(|| no_valid_data("value out of range"))
+}
+
+fn no_valid_data(reason: &str) -> Error {
+	Error::NoValidData(WEATHER_URL.to_string(), reason.to_string())
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 struct OpenMeteoWeatherStruct {
-	latitude: f32,
-	longitude: f32,
-	//hourly: String,
+	latitude: f64,
+	longitude: f64,
+	current_weather: OpenMeteoCurrentWeather,
+	hourly: OpenMeteoHourly,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct OpenMeteoCurrentWeather {
+	time: String,
+	temperature: f64,
+	windspeed: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct OpenMeteoHourly {
+	time: Vec<String>,
+	temperature_2m: Vec<f64>,
+	precipitation: Vec<f64>,
+	windspeed_10m: Vec<f64>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -117,3 +184,62 @@ impl RestPath<String> for OpenMeteo {
 		Ok(path)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use core::assert_matches::assert_matches;
+
+	const RESPONSE: &str = r#"{
+		"latitude": 52.52, "longitude": 13.419998,
+		"current_weather": {"time": "2023-05-03T01:00", "temperature": -1.5, "windspeed": 10.8},
+		"hourly": {
+			"time": ["2023-05-03T00:00", "2023-05-03T01:00", "2023-05-03T02:00", "2023-05-03T03:00"],
+			"temperature_2m": [-1.0, -1.5, -2.0, -2.5],
+			"precipitation": [0.0, 0.3, 0.1, 0.0],
+			"windspeed_10m": [9.0, 10.8, 12.0, 11.5]
+		}
+	}"#;
+
+	fn response() -> OpenMeteoWeatherStruct {
+		serde_json::from_str(RESPONSE).unwrap()
+	}
+
+	#[test]
+	fn weather_report_starts_at_the_current_hour() {
+		let report = to_weather_report(response(), 2).unwrap();
+
+		assert_eq!("2023-05-03T01:00", report.time);
+		assert_eq!(WeatherValue::from_num(-1.5), report.temperature);
+		assert_eq!(WeatherValue::from_num(0.3), report.precipitation);
+		assert_eq!(WeatherValue::from_num(10.8), report.wind_speed);
+		assert_eq!(WeatherValue::from_num(52.52), report.latitude);
+
+		let hours: Vec<&str> = report.hourly.iter().map(|h| h.time.as_str()).collect();
+		assert_eq!(vec!["2023-05-03T01:00", "2023-05-03T02:00"], hours);
+		assert_eq!(WeatherValue::from_num(12.0), report.hourly[1].wind_speed);
+	}
+
+	#[test]
+	fn weather_report_series_ends_with_the_response() {
+		let report = to_weather_report(response(), 24).unwrap();
+
+		assert_eq!(3, report.hourly.len());
+	}
+
+	#[test]
+	fn weather_report_fails_for_inconsistent_series() {
+		let mut response = response();
+		response.hourly.precipitation.pop();
+
+		assert_matches!(to_weather_report(response, 2), Err(Error::NoValidData(_, _)));
+	}
+
+	#[test]
+	fn weather_report_fails_if_current_hour_is_missing() {
+		let mut response = response();
+		response.current_weather.time = "2023-05-04T01:00".to_string();
+
+		assert_matches!(to_weather_report(response, 2), Err(Error::NoValidData(_, _)));
+	}
+}
@@ -0,0 +1,350 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Aggregates the exchange rates of several oracle sources into a single median rate.
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use crate::sgx_reexport_prelude::*;
+
+use crate::{
+	metrics_exporter::ExportMetrics,
+	oracles::exchange_rate_oracle::GetExchangeRate,
+	types::{ExchangeRate, TradingInfo, TradingPair},
+	Error,
+};
+use log::*;
+use std::{
+	string::{String, ToString},
+	sync::Arc,
+	thread,
+	vec::Vec,
+};
+
+/// Data source the aggregated exchange rate is submitted under. Stays the same regardless of
+/// the sources that were used, so it can be whitelisted once on the parentchain.
+pub const AGGREGATED_DATA_SOURCE: &str = "median";
+
+/// Source id of the aggregated exchange rate in the metrics.
+const AGGREGATED_METRICS_ID: &str = "median";
+
+/// Median exchange rate of all sources that did not deviate too much from the others.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AggregatedExchangeRate {
+	pub rate: ExchangeRate,
+	/// Sources whose rates make up the median.
+	pub used_sources: Vec<String>,
+	/// Sources whose rates were rejected as outliers.
+	pub rejected_sources: Vec<String>,
+}
+
+impl AggregatedExchangeRate {
+	pub fn number_of_sources(&self) -> u32 {
+		self.used_sources.len() as u32
+	}
+
+	/// Data source identifier for the parentchain. The number of sources is reported separately,
+	/// see [`AggregatedExchangeRate::number_of_sources`].
+	pub fn data_source(&self) -> String {
+		AGGREGATED_DATA_SOURCE.to_string()
+	}
+}
+
+pub trait GetAggregatedExchangeRate {
+	/// Get the median cryptocurrency/fiat_currency exchange rate over all sources.
+	fn get_aggregated_exchange_rate(
+		&self,
+		trading_pair: TradingPair,
+	) -> Result<AggregatedExchangeRate, Error>;
+
+	/// Get the median exchange rates of several trading pairs, in the same order. Every source
+	/// is queried once for all of them.
+	fn get_aggregated_exchange_rates(
+		&self,
+		trading_pairs: &[TradingPair],
+	) -> Vec<Result<AggregatedExchangeRate, Error>>;
+}
+
+/// Queries all exchange rate oracles concurrently and aggregates their rates.
+pub struct ExchangeRateAggregator<MetricsExporter> {
+	oracles: Vec<Arc<dyn GetExchangeRate + Send + Sync>>,
+	/// Rates deviating more than this from the median of all rates are rejected.
+	max_deviation_percent: u32,
+	metrics_exporter: Arc<MetricsExporter>,
+}
+
+impl<MetricsExporter> ExchangeRateAggregator<MetricsExporter> {
+	pub fn new(
+		oracles: Vec<Arc<dyn GetExchangeRate + Send + Sync>>,
+		max_deviation_percent: u32,
+		metrics_exporter: Arc<MetricsExporter>,
+	) -> Self {
+		ExchangeRateAggregator { oracles, max_deviation_percent, metrics_exporter }
+	}
+
+	/// Rates of all sources that succeeded, per trading pair.
+	fn query_oracles(&self, trading_pairs: &[TradingPair]) -> Vec<Vec<(String, ExchangeRate)>> {
+		let handles: Vec<_> = self
+			.oracles
+			.iter()
+			.map(|oracle| {
+				let oracle = oracle.clone();
+				let trading_pairs = trading_pairs.to_vec();
+				thread::spawn(move || oracle.get_exchange_rates(&trading_pairs))
+			})
+			.collect();
+
+		let mut rates_per_trading_pair = vec![Vec::new(); trading_pairs.len()];
+		for handle in handles {
+			let results = match handle.join() {
+				Ok(results) => results,
+				Err(_) => {
+					error!("Exchange rate request thread panicked for {:?}", trading_pairs);
+					continue
+				},
+			};
+			for ((rates, trading_pair), result) in
+				rates_per_trading_pair.iter_mut().zip(trading_pairs).zip(results)
+			{
+				match result {
+					Ok((rate, base_url)) => rates.push((base_url.to_string(), rate)),
+					Err(e) => warn!("Exchange rate source failed for {:?}: {}", trading_pair, e),
+				}
+			}
+		}
+		rates_per_trading_pair
+	}
+}
+
+impl<MetricsExporter> ExchangeRateAggregator<MetricsExporter>
+where
+	MetricsExporter: ExportMetrics<TradingInfo>,
+{
+	fn aggregate(
+		&self,
+		rates: Vec<(String, ExchangeRate)>,
+		trading_pair: TradingPair,
+	) -> Result<AggregatedExchangeRate, Error> {
+		let aggregated =
+			aggregate_exchange_rates(rates, self.max_deviation_percent, &trading_pair)?;
+
+		for source in aggregated.rejected_sources.iter() {
+			warn!("Rejected exchange rate of {} for {:?} as outlier", source, trading_pair);
+			self.metrics_exporter
+				.record_rejected_source(source.clone(), trading_pair.clone());
+		}
+		self.metrics_exporter.update_number_of_sources(
+			AGGREGATED_METRICS_ID.to_string(),
+			aggregated.number_of_sources(),
+			trading_pair.clone(),
+		);
+		self.metrics_exporter.update_exchange_rate(
+			AGGREGATED_METRICS_ID.to_string(),
+			aggregated.rate,
+			trading_pair,
+		);
+
+		Ok(aggregated)
+	}
+}
+
+impl<MetricsExporter> GetAggregatedExchangeRate for ExchangeRateAggregator<MetricsExporter>
+where
+	MetricsExporter: ExportMetrics<TradingInfo>,
+{
+	fn get_aggregated_exchange_rate(
+		&self,
+		trading_pair: TradingPair,
+	) -> Result<AggregatedExchangeRate, Error> {
+		self.get_aggregated_exchange_rates(&[trading_pair.clone()])
+			.pop()
+			.unwrap_or(Err(Error::NoExchangeRateSource(trading_pair)))
+	}
+
+	fn get_aggregated_exchange_rates(
+		&self,
+		trading_pairs: &[TradingPair],
+	) -> Vec<Result<AggregatedExchangeRate, Error>> {
+		self.query_oracles(trading_pairs)
+			.into_iter()
+			.zip(trading_pairs)
+			.map(|(rates, trading_pair)| self.aggregate(rates, trading_pair.clone()))
+			.collect()
+	}
+}
+
+/// Rejects all rates deviating more than `max_deviation_percent` from the median of all rates,
+/// and returns the median of the remaining ones.
+pub fn aggregate_exchange_rates(
+	rates: Vec<(String, ExchangeRate)>,
+	max_deviation_percent: u32,
+	trading_pair: &TradingPair,
+) -> Result<AggregatedExchangeRate, Error> {
+	let all_rates: Vec<ExchangeRate> = rates.iter().map(|(_, rate)| *rate).collect();
+	let overall_median =
+		median(all_rates).ok_or_else(|| Error::NoExchangeRateSource(trading_pair.clone()))?;
+
+	let (used, rejected): (Vec<_>, Vec<_>) = rates
+		.into_iter()
+		.partition(|(_, rate)| deviates_at_most(*rate, overall_median, max_deviation_percent));
+
+	let used_rates: Vec<ExchangeRate> = used.iter().map(|(_, rate)| *rate).collect();
+	let rate = median(used_rates)
+		.ok_or_else(|| Error::ExchangeRateSourcesDisagree(trading_pair.clone()))?;
+
+	Ok(AggregatedExchangeRate {
+		rate,
+		used_sources: used.into_iter().map(|(source, _)| source).collect(),
+		rejected_sources: rejected.into_iter().map(|(source, _)| source).collect(),
+	})
+}
+
+fn median(mut rates: Vec<ExchangeRate>) -> Option<ExchangeRate> {
+	rates.sort();
+	let middle = rates.len() / 2;
+	match rates.len() {
+		0 => None,
+		n if n % 2 == 1 => Some(rates[middle]),
+		_ => Some(ExchangeRate::from_num(
+			(rates[middle - 1].to_num::<f64>() + rates[middle].to_num::<f64>()) / 2.0,
+		)),
+	}
+}
+
+fn deviates_at_most(rate: ExchangeRate, median: ExchangeRate, max_deviation_percent: u32) -> bool {
+	let (rate, median) = (rate.to_num::<f64>(), median.to_num::<f64>());
+	let deviation = if rate > median { rate - median } else { median - rate };
+	deviation * 100.0 <= median * max_deviation_percent as f64
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		metrics_exporter::EXCHANGE_RATE_SOURCES_FEED,
+		mock::{ExchangeRateOracleMock, MetricsExporterMock},
+	};
+	use core::assert_matches::assert_matches;
+	use itp_enclave_metrics::OracleValue;
+
+	fn trading_pair() -> TradingPair {
+		TradingPair { crypto_currency: "TEER".to_string(), fiat_currency: "USD".to_string() }
+	}
+
+	fn rates(values: &[f32]) -> Vec<(String, ExchangeRate)> {
+		values
+			.iter()
+			.enumerate()
+			.map(|(i, value)| (format!("source_{}", i), ExchangeRate::from_num(*value)))
+			.collect()
+	}
+
+	#[test]
+	fn median_of_odd_number_of_rates_is_middle_rate() {
+		let aggregated =
+			aggregate_exchange_rates(rates(&[1.02, 0.98, 1.0]), 5, &trading_pair()).unwrap();
+
+		assert_eq!(ExchangeRate::from_num(1.0f32), aggregated.rate);
+		assert_eq!(3, aggregated.number_of_sources());
+		assert!(aggregated.rejected_sources.is_empty());
+	}
+
+	#[test]
+	fn median_of_even_number_of_rates_is_mean_of_middle_rates() {
+		let aggregated = aggregate_exchange_rates(rates(&[1.0, 1.04]), 5, &trading_pair()).unwrap();
+
+		assert!((aggregated.rate.to_num::<f64>() - 1.02).abs() < 1e-6);
+		assert_eq!(AGGREGATED_DATA_SOURCE, aggregated.data_source());
+	}
+
+	#[test]
+	fn outliers_are_rejected() {
+		let aggregated =
+			aggregate_exchange_rates(rates(&[1.0, 1.01, 5.0, 0.99]), 5, &trading_pair()).unwrap();
+
+		assert_eq!(ExchangeRate::from_num(1.0f32), aggregated.rate);
+		assert_eq!(vec!["source_0", "source_1", "source_3"], aggregated.used_sources);
+		assert_eq!(vec!["source_2"], aggregated.rejected_sources);
+	}
+
+	#[test]
+	fn no_rates_fails() {
+		let result = aggregate_exchange_rates(Vec::new(), 5, &trading_pair());
+		assert_matches!(result, Err(Error::NoExchangeRateSource(_)));
+	}
+
+	#[test]
+	fn two_disagreeing_rates_fail() {
+		let result = aggregate_exchange_rates(rates(&[1.0, 2.0]), 5, &trading_pair());
+		assert_matches!(result, Err(Error::ExchangeRateSourcesDisagree(_)));
+	}
+
+	#[test]
+	fn aggregator_skips_failing_oracles_and_records_rejected_sources() {
+		let metrics_exporter = Arc::new(MetricsExporterMock::default());
+		let oracles: Vec<Arc<dyn GetExchangeRate + Send + Sync>> = vec![
+			Arc::new(ExchangeRateOracleMock::new("https://a.url", Some(10.0))),
+			Arc::new(ExchangeRateOracleMock::new("https://b.url", Some(10.2))),
+			Arc::new(ExchangeRateOracleMock::new("https://c.url", Some(30.0))),
+			Arc::new(ExchangeRateOracleMock::new("https://d.url", None)),
+		];
+		let aggregator = ExchangeRateAggregator::new(oracles, 5, metrics_exporter.clone());
+
+		let aggregated = aggregator.get_aggregated_exchange_rate(trading_pair()).unwrap();
+
+		assert_eq!(2, aggregated.number_of_sources());
+		assert_eq!(vec!["https://c.url/".to_string()], metrics_exporter.get_rejected_sources());
+		assert_eq!(vec![(trading_pair(), aggregated.rate)], metrics_exporter.get_exchange_rates());
+		assert_eq!(
+			vec![(
+				EXCHANGE_RATE_SOURCES_FEED.to_string(),
+				trading_pair().key(),
+				OracleValue::from_num(2)
+			)],
+			metrics_exporter.get_values()
+		);
+	}
+
+	#[test]
+	fn aggregator_aggregates_every_trading_pair() {
+		let metrics_exporter = Arc::new(MetricsExporterMock::default());
+		let oracles: Vec<Arc<dyn GetExchangeRate + Send + Sync>> = vec![
+			Arc::new(ExchangeRateOracleMock::new("https://a.url", Some(10.0))),
+			Arc::new(ExchangeRateOracleMock::new("https://b.url", Some(10.2))),
+		];
+		let aggregator = ExchangeRateAggregator::new(oracles, 5, metrics_exporter.clone());
+		let other_trading_pair =
+			TradingPair { crypto_currency: "DOT".to_string(), fiat_currency: "CHF".to_string() };
+
+		let aggregated =
+			aggregator.get_aggregated_exchange_rates(&[trading_pair(), other_trading_pair.clone()]);
+
+		assert_eq!(2, aggregated.len());
+		assert!(aggregated.iter().all(|aggregated| aggregated
+			.as_ref()
+			.unwrap()
+			.number_of_sources()
+			== 2));
+		assert_eq!(
+			vec![trading_pair(), other_trading_pair],
+			metrics_exporter
+				.get_exchange_rates()
+				.into_iter()
+				.map(|(trading_pair, _)| trading_pair)
+				.collect::<Vec<_>>()
+		);
+	}
+}
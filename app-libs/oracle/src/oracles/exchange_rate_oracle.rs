@@ -20,19 +20,20 @@ use crate::sgx_reexport_prelude::*;
 
 use crate::{
 	metrics_exporter::ExportMetrics,
+	oracle_rest_client::{
+		create_oracle_rest_client, oracle_response_cache_stats, use_oracle_response_cache,
+		OracleRestClient,
+	},
 	traits::OracleSource,
 	types::{ExchangeRate, TradingInfo, TradingPair},
 	Error,
 };
-use itc_rest_client::{
-	http_client::{HttpClient, SendWithCertificateVerification},
-	rest_client::RestClient,
-};
 use log::*;
 use std::{
+	string::{String, ToString},
 	sync::Arc,
-	thread,
-	time::{Duration, Instant},
+	time::Instant,
+	vec::Vec,
 };
 use url::Url;
 
@@ -51,6 +52,36 @@ impl<OracleSourceType, MetricsExporter> ExchangeRateOracle<OracleSourceType, Met
 pub trait GetExchangeRate {
 	/// Get the cryptocurrency/fiat_currency exchange rate
 	fn get_exchange_rate(&self, trading_pair: TradingPair) -> Result<(ExchangeRate, Url), Error>;
+
+	/// Get the exchange rates of several trading pairs, in the same order.
+	fn get_exchange_rates(
+		&self,
+		trading_pairs: &[TradingPair],
+	) -> Vec<Result<(ExchangeRate, Url), Error>> {
+		trading_pairs
+			.iter()
+			.map(|trading_pair| self.get_exchange_rate(trading_pair.clone()))
+			.collect()
+	}
+}
+
+impl<OracleSourceType, MetricsExporter> ExchangeRateOracle<OracleSourceType, MetricsExporter>
+where
+	OracleSourceType: OracleSource<TradingInfo>,
+{
+	fn create_rest_client(&self, source_id: String) -> Result<(OracleRestClient, Url), Error> {
+		let base_url = self.oracle_source.base_url()?;
+
+		// Transient failures are retried by the rest client.
+		let mut rest_client = create_oracle_rest_client(
+			base_url.clone(),
+			self.oracle_source.root_certificate_content(),
+			self.oracle_source.spki_pins(),
+			self.oracle_source.request_timeout(),
+		);
+		use_oracle_response_cache(&mut rest_client, source_id);
+		Ok((rest_client, base_url))
+	}
 }
 
 impl<OracleSourceType, MetricsExporter> GetExchangeRate
@@ -63,65 +94,90 @@ where
 		let source_id = self.oracle_source.metrics_id();
 		self.metrics_exporter.increment_number_requests(source_id.clone());
 
-		let base_url = self.oracle_source.base_url()?;
-		let root_certificate = self.oracle_source.root_certificate_content();
-		let request_timeout = self.oracle_source.request_timeout();
+		let (mut rest_client, base_url) = self.create_rest_client(source_id.clone())?;
 
 		debug!("Get exchange rate from URI: {}, trading pair: {:?}", base_url, trading_pair);
 
-		let http_client = HttpClient::new(
-			SendWithCertificateVerification::new(root_certificate),
-			true,
-			request_timeout,
-			None,
-			None,
-		);
-		let mut rest_client = RestClient::new(http_client, base_url.clone());
-
-		// Due to possible failures that may be temporarily this function tries to fetch the exchange rates `number_of_tries` times.
-		// If it still fails for the last attempt, then only in that case will it be considered a non-recoverable error.
-		let number_of_tries = 3;
 		let timer_start = Instant::now();
+		let result = self
+			.oracle_source
+			.execute_exchange_rate_request(&mut rest_client, trading_pair.clone());
+		self.metrics_exporter
+			.update_cache_stats(source_id.clone(), oracle_response_cache_stats(&source_id));
+
+		match result {
+			Ok(exchange_rate) => {
+				self.metrics_exporter.record_response_time(source_id.clone(), timer_start);
+				self.metrics_exporter
+					.update_exchange_rate(source_id, exchange_rate, trading_pair);
+
+				debug!("Successfully executed exchange rate request");
+				Ok((exchange_rate, base_url))
+			},
+			Err(e) => {
+				error!("Getting exchange rate from {} failed: {}", &base_url, &e);
+				debug!("Check that the API endpoint is available, for coingecko: https://status.coingecko.com/");
+				self.metrics_exporter.increment_number_failures(source_id);
+				Err(e)
+			},
+		}
+	}
+
+	fn get_exchange_rates(
+		&self,
+		trading_pairs: &[TradingPair],
+	) -> Vec<Result<(ExchangeRate, Url), Error>> {
+		let source_id = self.oracle_source.metrics_id();
+		self.metrics_exporter.increment_number_requests(source_id.clone());
+
+		let (mut rest_client, base_url) = match self.create_rest_client(source_id.clone()) {
+			Ok(client) => client,
+			Err(e) => {
+				error!("Creating the rest client of {} failed: {}", source_id, e);
+				self.metrics_exporter.increment_number_failures(source_id);
+				return trading_pairs
+					.iter()
+					.map(|trading_pair| {
+						Err(Error::NoValidData(e.to_string(), trading_pair.clone().key()))
+					})
+					.collect()
+			},
+		};
 
-		let mut tries = 0;
-		let result = loop {
-			tries += 1;
-			let exchange_result = self
-				.oracle_source
-				.execute_exchange_rate_request(&mut rest_client, trading_pair.clone());
+		debug!("Get exchange rates from URI: {}, trading pairs: {:?}", base_url, trading_pairs);
 
-			match exchange_result {
+		let timer_start = Instant::now();
+		let results = self
+			.oracle_source
+			.execute_exchange_rates_request(&mut rest_client, trading_pairs);
+		self.metrics_exporter
+			.update_cache_stats(source_id.clone(), oracle_response_cache_stats(&source_id));
+		if results.iter().any(|result| result.is_ok()) {
+			self.metrics_exporter.record_response_time(source_id.clone(), timer_start);
+		}
+
+		trading_pairs
+			.iter()
+			.zip(results)
+			.map(|(trading_pair, result)| match result {
 				Ok(exchange_rate) => {
-					self.metrics_exporter.record_response_time(source_id.clone(), timer_start);
 					self.metrics_exporter.update_exchange_rate(
-						source_id,
+						source_id.clone(),
 						exchange_rate,
-						trading_pair,
+						trading_pair.clone(),
 					);
-
-					debug!("Successfully executed exchange rate request");
-					break Ok((exchange_rate, base_url))
+					Ok((exchange_rate, base_url.clone()))
 				},
-				Err(e) =>
-					if tries < number_of_tries {
-						error!(
-							"Getting exchange rate from {} failed with {}, trying again in {:#?}.",
-							&base_url, e, request_timeout
-						);
-						debug!("Check that the API endpoint is available, for coingecko: https://status.coingecko.com/");
-						thread::sleep(
-							request_timeout.unwrap_or_else(|| Duration::from_secs(number_of_tries)),
-						);
-					} else {
-						error!(
-							"Getting exchange rate from {} failed {} times, latest error is: {}.",
-							&base_url, number_of_tries, &e
-						);
-						break Err(e)
-					},
-			}
-		};
-		result
+				Err(e) => {
+					error!(
+						"Getting exchange rate of {:?} from {} failed: {}",
+						trading_pair, &base_url, &e
+					);
+					self.metrics_exporter.increment_number_failures(source_id.clone());
+					Err(e)
+				},
+			})
+			.collect()
 	}
 }
 
@@ -144,6 +200,7 @@ mod tests {
 		assert_eq!(1, metrics_exporter.get_number_request());
 		assert_eq!(1, metrics_exporter.get_response_times().len());
 		assert_eq!(1, metrics_exporter.get_exchange_rates().len());
+		assert_eq!(1, metrics_exporter.get_cache_hit_ratios().len());
 
 		let (metric_trading_pair, exchange_rate) =
 			metrics_exporter.get_exchange_rates().first().unwrap().clone();
@@ -151,4 +208,29 @@ mod tests {
 		assert_eq!(trading_pair, metric_trading_pair);
 		assert_eq!(ExchangeRate::from_num(42.3f32), exchange_rate);
 	}
+
+	#[test]
+	fn get_exchange_rates_counts_one_request_for_all_trading_pairs() {
+		let metrics_exporter = Arc::new(MetricsExporterMock::default());
+		let test_client = TestOracle::new(OracleSourceMock {}, metrics_exporter.clone());
+
+		let trading_pairs = [
+			TradingPair { crypto_currency: "BTC".to_string(), fiat_currency: "USD".to_string() },
+			TradingPair { crypto_currency: "DOT".to_string(), fiat_currency: "CHF".to_string() },
+		];
+		let results = test_client.get_exchange_rates(&trading_pairs);
+
+		assert_eq!(2, results.len());
+		assert!(results.iter().all(|result| result.is_ok()));
+		assert_eq!(1, metrics_exporter.get_number_request());
+		assert_eq!(1, metrics_exporter.get_response_times().len());
+		assert_eq!(
+			trading_pairs.to_vec(),
+			metrics_exporter
+				.get_exchange_rates()
+				.into_iter()
+				.map(|(trading_pair, _)| trading_pair)
+				.collect::<Vec<_>>()
+		);
+	}
 }
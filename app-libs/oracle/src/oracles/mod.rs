@@ -14,5 +14,7 @@
 	limitations under the License.
 
 */
+pub mod exchange_rate_aggregator;
 pub mod exchange_rate_oracle;
+pub mod on_demand_oracle;
 pub mod weather_oracle;
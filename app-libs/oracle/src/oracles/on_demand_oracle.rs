@@ -0,0 +1,195 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use crate::sgx_reexport_prelude::*;
+
+use crate::{
+	metrics_exporter::ExportMetrics,
+	oracle_query::{OracleQuery, OracleQueryResponse},
+	oracle_rest_client::create_oracle_rest_client,
+	source_config_store::AccessOracleSourceConfigs,
+	types::OracleValue,
+	ConfigurableOracleSource, Error,
+};
+use itp_time_utils::now_as_millis;
+use log::*;
+use std::{
+	string::ToString,
+	sync::Arc,
+	time::{Duration, Instant},
+	vec::Vec,
+};
+use url::Url;
+
+/// Metrics key of all on-demand values. The query parameters are chosen by the client, so they
+/// must not end up in a label: there would be a new series for every distinct query.
+const ON_DEMAND_METRICS_KEY: &str = "on_demand";
+
+/// Answers [`OracleQuery`]s with the configured sources. Only sources in the config store can
+/// be queried, so the domain policy of the store applies.
+pub struct OnDemandOracle<ConfigStore, MetricsExporter> {
+	config_store: Arc<ConfigStore>,
+	metrics_exporter: Arc<MetricsExporter>,
+}
+
+impl<ConfigStore, MetricsExporter> OnDemandOracle<ConfigStore, MetricsExporter> {
+	pub fn new(config_store: Arc<ConfigStore>, metrics_exporter: Arc<MetricsExporter>) -> Self {
+		OnDemandOracle { config_store, metrics_exporter }
+	}
+}
+
+pub trait ExecuteOracleQuery {
+	/// Fetch the value of the queried source now.
+	fn execute_oracle_query(&self, query: &OracleQuery) -> Result<OracleQueryResponse, Error>;
+}
+
+impl<ConfigStore, MetricsExporter> ExecuteOracleQuery
+	for OnDemandOracle<ConfigStore, MetricsExporter>
+where
+	ConfigStore: AccessOracleSourceConfigs,
+	MetricsExporter: ExportMetrics<OracleQuery>,
+{
+	fn execute_oracle_query(&self, query: &OracleQuery) -> Result<OracleQueryResponse, Error> {
+		let config = self
+			.config_store
+			.source_configs()?
+			.into_iter()
+			.find(|config| config.name == query.source)
+			.ok_or_else(|| Error::UnknownOracleSource(query.source.clone()))?;
+
+		let params: Vec<(&str, &str)> =
+			query.parameters.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+		let url = config.render_request(&params)?.url(&config.base_url)?;
+		let trusted_root_certificate_fingerprints =
+			config.trusted_root_certificate_fingerprints()?;
+
+		let source_id = config.name.clone();
+		self.metrics_exporter.increment_number_requests(source_id.clone());
+
+		debug!("Execute oracle query {:?} from URL: {}", query, url);
+
+		let base_url =
+			Url::parse(&config.base_url).map_err(|e| Error::Other(format!("{:?}", e).into()))?;
+		let mut rest_client = create_oracle_rest_client(
+			base_url,
+			config.root_certificate.clone(),
+			config.decoded_spki_pins()?,
+			Some(Duration::from_secs(config.timeout_secs)),
+		);
+		let source = ConfigurableOracleSource::new(config);
+
+		let timer_start = Instant::now();
+		let value = match source.execute_query(&mut rest_client, &params).and_then(|value| {
+			OracleValue::checked_from_num(value)
+				.ok_or_else(|| Error::Other("Oracle value is out of range".into()))
+		}) {
+			Ok(value) => value,
+			Err(e) => {
+				error!("Oracle query {:?} failed: {}", query, e);
+				self.metrics_exporter.increment_number_failures(source_id);
+				return Err(e)
+			},
+		};
+		let timestamp = now_as_millis();
+
+		self.metrics_exporter.record_response_time(source_id.clone(), timer_start);
+		let config = source.config();
+		self.metrics_exporter.update_value(
+			source_id,
+			config.feed.clone(),
+			ON_DEMAND_METRICS_KEY.to_string(),
+			value,
+		);
+
+		Ok(OracleQueryResponse {
+			source: config.name.clone(),
+			feed: config.feed.clone(),
+			parameters: query.parameters.clone(),
+			value,
+			url: url.into(),
+			timestamp,
+			trusted_root_certificate_fingerprints,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		mock::{MetricsExporterMock, OracleSourceConfigSealMock},
+		source_config::{OracleSourceConfig, ALLOWED_ORACLE_DOMAINS, EXCHANGE_RATE_FEED},
+		OracleSourceConfigStore,
+	};
+	use core::assert_matches::assert_matches;
+
+	type TestOracle =
+		OnDemandOracle<OracleSourceConfigStore<OracleSourceConfigSealMock>, MetricsExporterMock>;
+
+	fn binance_config() -> OracleSourceConfig {
+		OracleSourceConfig {
+			name: "binance".to_string(),
+			feed: EXCHANGE_RATE_FEED.to_string(),
+			base_url: "https://api.binance.com".to_string(),
+			query_template: "api/v3/ticker/price?symbol={crypto_currency}{fiat_currency}"
+				.to_string(),
+			json_path: "$.price".to_string(),
+			root_certificate: include_str!("../certificates/lets_encrypt_root_cert.pem")
+				.to_string(),
+			spki_pins: vec![],
+			timeout_secs: 10,
+		}
+	}
+
+	fn test_oracle() -> (TestOracle, Arc<MetricsExporterMock>) {
+		let config_store = OracleSourceConfigStore::new(
+			vec![binance_config()],
+			ALLOWED_ORACLE_DOMAINS,
+			Arc::new(OracleSourceConfigSealMock::default()),
+		);
+		let metrics_exporter = Arc::new(MetricsExporterMock::default());
+		(OnDemandOracle::new(Arc::new(config_store), metrics_exporter.clone()), metrics_exporter)
+	}
+
+	#[test]
+	fn query_of_unconfigured_source_is_rejected() {
+		let (oracle, metrics_exporter) = test_oracle();
+		let query = OracleQuery { source: "attacker".into(), parameters: vec![] };
+
+		assert_matches!(
+			oracle.execute_oracle_query(&query),
+			Err(Error::UnknownOracleSource(s)) if s == "attacker"
+		);
+		assert_eq!(0, metrics_exporter.get_number_request());
+	}
+
+	#[test]
+	fn query_with_missing_parameter_is_rejected_before_requesting() {
+		let (oracle, metrics_exporter) = test_oracle();
+		let query = OracleQuery {
+			source: "binance".into(),
+			parameters: vec![("crypto_currency".into(), "TEER".into())],
+		};
+
+		assert_matches!(
+			oracle.execute_oracle_query(&query),
+			Err(Error::MissingParameter(p)) if p == "fiat_currency"
+		);
+		assert_eq!(0, metrics_exporter.get_number_request());
+	}
+}
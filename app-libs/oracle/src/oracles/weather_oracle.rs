@@ -18,13 +18,17 @@
 #[cfg(all(not(feature = "std"), feature = "sgx"))]
 use crate::sgx_reexport_prelude::*;
 
-use crate::{metrics_exporter::ExportMetrics, traits::OracleSource, types::WeatherInfo, Error};
-use itc_rest_client::{
-	http_client::{HttpClient, SendWithCertificateVerification},
-	rest_client::RestClient,
+use crate::{
+	metrics_exporter::ExportMetrics,
+	oracle_rest_client::{
+		create_oracle_rest_client, oracle_response_cache_stats, use_oracle_response_cache,
+	},
+	traits::{ExecuteOracleRequest, OracleSource},
+	types::{WeatherInfo, WeatherReport},
+	Error,
 };
 use log::*;
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 use url::Url;
 
 #[allow(unused)]
@@ -46,38 +50,52 @@ where
 	}
 }
 
-pub trait GetLongitude {
-	type LongitudeResult;
-	fn get_longitude(&self, weather_info: WeatherInfo) -> Self::LongitudeResult;
+pub trait GetWeatherReport {
+	/// Get the current weather and the hourly forecast at the queried location.
+	fn get_weather_report(&self, weather_info: WeatherInfo) -> Result<WeatherReport, Error>;
 }
 
-impl<OracleSourceType, MetricsExporter> GetLongitude
+impl<OracleSourceType, MetricsExporter> GetWeatherReport
 	for WeatherOracle<OracleSourceType, MetricsExporter>
 where
-	OracleSourceType: OracleSource<WeatherInfo, OracleRequestResult = Result<f32, Error>>,
+	OracleSourceType:
+		ExecuteOracleRequest<WeatherInfo, OracleRequestResult = Result<WeatherReport, Error>>,
 	MetricsExporter: ExportMetrics<WeatherInfo>,
 {
-	type LongitudeResult = Result<f32, Error>;
+	fn get_weather_report(&self, weather_info: WeatherInfo) -> Result<WeatherReport, Error> {
+		let source_id = self.oracle_source.metrics_id();
+		self.metrics_exporter.increment_number_requests(source_id.clone());
 
-	fn get_longitude(&self, weather_info: WeatherInfo) -> Self::LongitudeResult {
 		let query = weather_info.weather_query.clone();
-
 		let base_url = self.oracle_source.base_url()?;
 		let root_certificate = self.oracle_source.root_certificate_content();
 
-		debug!("Get longitude from URI: {}, query: {:?}", base_url, query);
+		debug!("Get weather report from URI: {}, query: {:?}", base_url, query);
 
-		let http_client = HttpClient::new(
-			SendWithCertificateVerification::new(root_certificate),
-			true,
+		let mut rest_client = create_oracle_rest_client(
+			base_url,
+			root_certificate,
+			self.oracle_source.spki_pins(),
 			self.oracle_source.request_timeout(),
-			None,
-			None,
 		);
-		let mut rest_client = RestClient::new(http_client, base_url);
-		<OracleSourceType as OracleSource<WeatherInfo>>::execute_request(
+		use_oracle_response_cache(&mut rest_client, source_id.clone());
+
+		let timer_start = Instant::now();
+		let result = <OracleSourceType as ExecuteOracleRequest<WeatherInfo>>::execute_request(
 			&mut rest_client,
 			weather_info,
-		)
+		);
+		self.metrics_exporter
+			.update_cache_stats(source_id.clone(), oracle_response_cache_stats(&source_id));
+
+		let weather_report = result.map_err(|e| {
+			self.metrics_exporter.increment_number_failures(source_id.clone());
+			e
+		})?;
+
+		self.metrics_exporter.record_response_time(source_id.clone(), timer_start);
+		self.metrics_exporter.update_weather(source_id, query.key(), &weather_report);
+
+		Ok(weather_report)
 	}
 }
@@ -0,0 +1,346 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+//! Cache for the responses of the oracle sources, see [`OracleResponseCache`].
+
+#[cfg(feature = "sgx")]
+use std::sync::{SgxCondvar as Condvar, SgxMutex as Mutex};
+
+#[cfg(feature = "std")]
+use std::sync::{Condvar, Mutex};
+
+use core::time::Duration;
+use itc_rest_client::{
+	error::Error as RestClientError,
+	response_cache::{CachedResponse, ResponseCache},
+};
+use std::{
+	collections::{HashMap, HashSet},
+	string::{String, ToString},
+	sync::Arc,
+	time::Instant,
+};
+
+/// How long a response is fresh if the source doesn't send a `max-age`.
+pub const DEFAULT_RESPONSE_TTL: Duration = Duration::from_secs(60);
+pub const MAX_CACHED_RESPONSES: usize = 1024;
+
+/// (Source, request URL)
+type CacheKey = (String, String);
+
+struct CacheEntry {
+	response: CachedResponse,
+	fresh_until: Instant,
+}
+
+/// Cache lookups of a source. A lookup that had to send a request (including a
+/// revalidation) is a miss.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+	pub hits: u64,
+	pub misses: u64,
+}
+
+impl CacheStats {
+	pub fn hit_ratio(&self) -> f64 {
+		match self.hits + self.misses {
+			0 => 0.0,
+			lookups => self.hits as f64 / lookups as f64,
+		}
+	}
+}
+
+#[derive(Default)]
+struct CacheState {
+	entries: HashMap<CacheKey, CacheEntry>,
+	in_flight: HashSet<CacheKey>,
+	stats: HashMap<String, CacheStats>,
+}
+
+/// Responses of the oracle sources, keyed by source and request.
+///
+/// A response is fresh for the `max-age` the source sent (or the default TTL), afterwards it's
+/// revalidated with its `ETag`, if it has one. Concurrent lookups of the same key wait for the
+/// request in flight, rather than sending their own.
+pub struct OracleResponseCache {
+	default_ttl: Duration,
+	max_entries: usize,
+	state: Mutex<CacheState>,
+	request_completed: Condvar,
+}
+
+impl Default for OracleResponseCache {
+	fn default() -> Self {
+		OracleResponseCache::new(DEFAULT_RESPONSE_TTL, MAX_CACHED_RESPONSES)
+	}
+}
+
+impl OracleResponseCache {
+	pub fn new(default_ttl: Duration, max_entries: usize) -> Self {
+		OracleResponseCache {
+			default_ttl,
+			max_entries,
+			state: Mutex::new(CacheState::default()),
+			request_completed: Condvar::new(),
+		}
+	}
+
+	pub fn stats(&self, source: &str) -> CacheStats {
+		self.state
+			.lock()
+			.map(|state| state.stats.get(source).copied().unwrap_or_default())
+			.unwrap_or_default()
+	}
+
+	/// Body of the fresh cached response, or of the response `fetch` returns.
+	///
+	/// `fetch` is given the stale response to revalidate, if there is one. If it fails, the
+	/// lookups that waited for it send a request of their own.
+	pub fn get_or_fetch(
+		&self,
+		source: &str,
+		key: &str,
+		fetch: &mut dyn FnMut(Option<&CachedResponse>) -> Result<CachedResponse, RestClientError>,
+	) -> Result<String, RestClientError> {
+		let cache_key = (source.to_string(), key.to_string());
+
+		let mut state = self.state.lock().map_err(|_| RestClientError::LockPoisoning)?;
+		loop {
+			let now = Instant::now();
+			if let Some(entry) =
+				state.entries.get(&cache_key).filter(|entry| entry.fresh_until > now)
+			{
+				let body = entry.response.body.clone();
+				state.stats.entry(source.to_string()).or_default().hits += 1;
+				return Ok(body)
+			}
+			if !state.in_flight.contains(&cache_key) {
+				break
+			}
+			state =
+				self.request_completed.wait(state).map_err(|_| RestClientError::LockPoisoning)?;
+		}
+		state.in_flight.insert(cache_key.clone());
+		state.stats.entry(source.to_string()).or_default().misses += 1;
+		let stale_response = state.entries.get(&cache_key).map(|entry| entry.response.clone());
+		drop(state);
+
+		let result = fetch(stale_response.as_ref());
+
+		let mut state = self.state.lock().map_err(|_| RestClientError::LockPoisoning)?;
+		state.in_flight.remove(&cache_key);
+		match result.as_ref() {
+			Ok(response) if response.no_store => {
+				state.entries.remove(&cache_key);
+			},
+			Ok(response) => self.insert(&mut state, cache_key, response.clone()),
+			Err(_) => {},
+		}
+		drop(state);
+		self.request_completed.notify_all();
+
+		result.map(|response| response.body)
+	}
+
+	fn insert(&self, state: &mut CacheState, cache_key: CacheKey, response: CachedResponse) {
+		let now = Instant::now();
+		if state.entries.len() >= self.max_entries && !state.entries.contains_key(&cache_key) {
+			state.entries.retain(|_, entry| entry.fresh_until > now);
+		}
+		if state.entries.len() >= self.max_entries && !state.entries.contains_key(&cache_key) {
+			let oldest = state
+				.entries
+				.iter()
+				.min_by_key(|(_, entry)| entry.fresh_until)
+				.map(|(key, _)| key.clone());
+			if let Some(oldest) = oldest {
+				state.entries.remove(&oldest);
+			}
+		}
+
+		let fresh_until = now + response.max_age.unwrap_or(self.default_ttl);
+		state.entries.insert(cache_key, CacheEntry { response, fresh_until });
+	}
+}
+
+/// A source's view on the [`OracleResponseCache`], to hand to its rest client.
+pub struct SourceResponseCache {
+	cache: Arc<OracleResponseCache>,
+	source: String,
+}
+
+impl SourceResponseCache {
+	pub fn new(cache: Arc<OracleResponseCache>, source: String) -> Self {
+		SourceResponseCache { cache, source }
+	}
+}
+
+impl ResponseCache for SourceResponseCache {
+	fn get_or_fetch(
+		&self,
+		key: &str,
+		fetch: &mut dyn FnMut(Option<&CachedResponse>) -> Result<CachedResponse, RestClientError>,
+	) -> Result<String, RestClientError> {
+		self.cache.get_or_fetch(&self.source, key, fetch)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::{
+		sync::{
+			atomic::{AtomicUsize, Ordering},
+			Barrier,
+		},
+		thread,
+		vec::Vec,
+	};
+
+	const SOURCE: &str = "source";
+	const URL: &str = "https://api.coingecko.com/api/v3/coins/list";
+
+	fn response(body: &str, max_age: Option<Duration>) -> CachedResponse {
+		CachedResponse {
+			body: body.to_string(),
+			etag: Some("\"v1\"".to_string()),
+			max_age,
+			no_store: false,
+		}
+	}
+
+	#[test]
+	fn fresh_response_is_served_from_cache() {
+		let cache = OracleResponseCache::default();
+		let mut number_of_fetches = 0;
+		let mut fetch = |_: Option<&CachedResponse>| {
+			number_of_fetches += 1;
+			Ok(response("body", None))
+		};
+
+		assert_eq!(cache.get_or_fetch(SOURCE, URL, &mut fetch).unwrap(), "body");
+		assert_eq!(cache.get_or_fetch(SOURCE, URL, &mut fetch).unwrap(), "body");
+
+		assert_eq!(number_of_fetches, 1);
+		assert_eq!(cache.stats(SOURCE), CacheStats { hits: 1, misses: 1 });
+		assert_eq!(cache.stats(SOURCE).hit_ratio(), 0.5);
+	}
+
+	#[test]
+	fn stale_response_is_handed_to_fetch_for_revalidation() {
+		let cache = OracleResponseCache::default();
+		cache
+			.get_or_fetch(SOURCE, URL, &mut |_| Ok(response("body", Some(Duration::ZERO))))
+			.unwrap();
+
+		let mut revalidated = None;
+		let body = cache
+			.get_or_fetch(SOURCE, URL, &mut |stale| {
+				revalidated = stale.cloned();
+				Ok(response("body", None))
+			})
+			.unwrap();
+
+		assert_eq!(body, "body");
+		assert_eq!(revalidated.and_then(|response| response.etag), Some("\"v1\"".to_string()));
+		assert_eq!(cache.stats(SOURCE), CacheStats { hits: 0, misses: 2 });
+	}
+
+	#[test]
+	fn sources_and_requests_are_cached_separately() {
+		let cache = OracleResponseCache::default();
+		cache.get_or_fetch(SOURCE, URL, &mut |_| Ok(response("body", None))).unwrap();
+
+		let other_source =
+			cache.get_or_fetch("other", URL, &mut |_| Ok(response("other", None))).unwrap();
+		let other_request = cache
+			.get_or_fetch(SOURCE, "other_url", &mut |_| Ok(response("other", None)))
+			.unwrap();
+
+		assert_eq!(other_source, "other");
+		assert_eq!(other_request, "other");
+	}
+
+	#[test]
+	fn no_store_and_failed_responses_are_not_cached() {
+		let cache = OracleResponseCache::default();
+		let no_store = CachedResponse { no_store: true, ..response("body", None) };
+		cache.get_or_fetch(SOURCE, URL, &mut |_| Ok(no_store.clone())).unwrap();
+		assert!(cache
+			.get_or_fetch(SOURCE, URL, &mut |_| Err(RestClientError::TimeoutError))
+			.is_err());
+
+		let mut stale = Some(response("unexpected", None));
+		cache
+			.get_or_fetch(SOURCE, URL, &mut |cached| {
+				stale = cached.cloned();
+				Ok(response("body", None))
+			})
+			.unwrap();
+
+		assert_eq!(stale, None);
+		assert_eq!(cache.stats(SOURCE).misses, 3);
+	}
+
+	#[test]
+	fn oldest_response_is_evicted_when_full() {
+		let cache = OracleResponseCache::new(DEFAULT_RESPONSE_TTL, 2);
+		for (url, max_age) in [("a", 10), ("b", 20), ("c", 30)] {
+			cache
+				.get_or_fetch(SOURCE, url, &mut |_| {
+					Ok(response(url, Some(Duration::from_secs(max_age))))
+				})
+				.unwrap();
+		}
+
+		let state = cache.state.lock().unwrap();
+		let mut urls: Vec<_> = state.entries.keys().map(|(_, url)| url.as_str()).collect();
+		urls.sort();
+		assert_eq!(urls, vec!["b", "c"]);
+	}
+
+	#[test]
+	fn concurrent_lookups_are_coalesced_into_one_request() {
+		const LOOKUPS: usize = 8;
+		let cache = Arc::new(OracleResponseCache::default());
+		let number_of_fetches = Arc::new(AtomicUsize::new(0));
+		let barrier = Arc::new(Barrier::new(LOOKUPS));
+
+		let handles: Vec<_> = (0..LOOKUPS)
+			.map(|_| {
+				let cache = cache.clone();
+				let number_of_fetches = number_of_fetches.clone();
+				let barrier = barrier.clone();
+				thread::spawn(move || {
+					barrier.wait();
+					cache
+						.get_or_fetch(SOURCE, URL, &mut |_| {
+							number_of_fetches.fetch_add(1, Ordering::SeqCst);
+							thread::sleep(Duration::from_millis(50));
+							Ok(response("body", None))
+						})
+						.unwrap()
+				})
+			})
+			.collect();
+
+		for handle in handles {
+			assert_eq!(handle.join().unwrap(), "body");
+		}
+		assert_eq!(number_of_fetches.load(Ordering::SeqCst), 1);
+		assert_eq!(cache.stats(SOURCE), CacheStats { hits: LOOKUPS as u64 - 1, misses: 1 });
+	}
+}
@@ -0,0 +1,51 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::{error::Error, source_config_store::OracleSourceConfigs};
+use codec::{Decode, Encode};
+use itp_settings::files::ORACLE_SOURCE_CONFIGS_FILE;
+use itp_sgx_io::{seal, unseal, SealedIO};
+use log::info;
+use std::{sgxfs::SgxFile, vec::Vec};
+
+/// Seals the oracle source configs to a static file.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct OracleSourceConfigSeal;
+
+impl SealedIO for OracleSourceConfigSeal {
+	type Error = Error;
+	type Unsealed = OracleSourceConfigs;
+
+	fn unseal(&self) -> Result<Self::Unsealed, Error> {
+		Ok(unseal(ORACLE_SOURCE_CONFIGS_FILE).map(|b| Decode::decode(&mut b.as_slice()))??)
+	}
+
+	fn seal(&self, unsealed: &Self::Unsealed) -> Result<(), Error> {
+		Ok(unsealed.using_encoded(|bytes| seal(bytes, ORACLE_SOURCE_CONFIGS_FILE))?)
+	}
+}
+
+pub fn create_sealed_if_absent() -> Result<(), Error> {
+	if SgxFile::open(ORACLE_SOURCE_CONFIGS_FILE).is_err() {
+		info!(
+			"[Enclave] Oracle source configs not found, creating empty ones! {}",
+			ORACLE_SOURCE_CONFIGS_FILE
+		);
+		return OracleSourceConfigSeal.seal(&Vec::new())
+	}
+	Ok(())
+}
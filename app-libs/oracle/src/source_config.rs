@@ -0,0 +1,530 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Configuration of oracle sources that are defined at runtime by the operator.
+//!
+//! The operator is not trusted to point the enclave to arbitrary servers: every source must
+//! use TLS and a domain from [`ALLOWED_ORACLE_DOMAINS`]. Since that list is compiled into the
+//! enclave, it is covered by the MRENCLAVE.
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use crate::sgx_reexport_prelude::*;
+
+use crate::error::Error;
+use codec::{Decode, Encode};
+use itc_rest_client::{
+	certificates::{pem_certificates, SpkiPin},
+	http_client::SendWithCertificateVerification,
+};
+use serde::{Deserialize, Serialize};
+use sp_core::hashing::sha2_256;
+use std::{
+	convert::TryFrom,
+	string::{String, ToString},
+	vec::Vec,
+};
+use url::Url;
+
+/// Domains configurable oracle sources may query (including their subdomains).
+pub const ALLOWED_ORACLE_DOMAINS: &[&str] = &[
+	"api.coingecko.com",
+	"pro-api.coinmarketcap.com",
+	"api.binance.com",
+	"api.kraken.com",
+	"api.open-meteo.com",
+];
+
+/// Upper bound for the request timeout of a configurable source.
+pub const MAX_ORACLE_REQUEST_TIMEOUT_SECS: u64 = 60;
+
+/// Feed of the sources that provide exchange rates.
+pub const EXCHANGE_RATE_FEED: &str = "exchange_rate";
+/// Placeholders the exchange rate feed fills in.
+pub const CRYPTO_CURRENCY_PLACEHOLDER: &str = "crypto_currency";
+pub const FIAT_CURRENCY_PLACEHOLDER: &str = "fiat_currency";
+
+/// An oracle source defined by configuration instead of code.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+pub struct OracleSourceConfig {
+	/// Unique name of the source, also used as its metrics id.
+	pub name: String,
+	/// The data feed this source provides, e.g. [`EXCHANGE_RATE_FEED`].
+	pub feed: String,
+	pub base_url: String,
+	/// Path and query relative to the base URL. `{placeholders}` are replaced by the request
+	/// parameters, e.g. `api/v3/ticker/price?symbol={crypto_currency}{fiat_currency}`.
+	pub query_template: String,
+	/// JSONPath of the value in the response, may contain placeholders as well.
+	pub json_path: String,
+	/// PEM encoded root certificate the server certificate is verified against. May be a
+	/// bundle of several certificates, e.g. while the provider rotates its CA.
+	pub root_certificate: String,
+	/// Base64 encoded SHA-256 hashes of the `SubjectPublicKeyInfo` of the trusted root
+	/// certificates. If given, only the root certificates with a pinned key are trusted.
+	#[serde(default)]
+	pub spki_pins: Vec<String>,
+	pub timeout_secs: u64,
+}
+
+/// Parse a JSON array of oracle source configs. They still need to be validated.
+pub fn parse_source_configs(json: &[u8]) -> Result<Vec<OracleSourceConfig>, Error> {
+	serde_json::from_slice(json).map_err(|e| Error::MalformedSourceConfigs(format!("{}", e)))
+}
+
+/// A request rendered from the query template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OracleRequest {
+	pub path: String,
+	pub query: Vec<(String, String)>,
+	pub json_path: String,
+}
+
+impl OracleRequest {
+	/// Full URL of the request, composed the same way the rest client does.
+	pub fn url(&self, base_url: &str) -> Result<Url, Error> {
+		let mut url = Url::parse(base_url)
+			.and_then(|base_url| base_url.join(&self.path))
+			.map_err(|e| Error::Other(format!("{:?}", e).into()))?;
+		if !self.query.is_empty() {
+			let mut query_pairs = url.query_pairs_mut();
+			for (key, value) in self.query.iter() {
+				query_pairs.append_pair(key, value);
+			}
+		}
+		Ok(url)
+	}
+}
+
+impl OracleSourceConfig {
+	/// Check the config against the domain policy and for well-formedness.
+	pub fn validate(&self, allowed_domains: &[&str]) -> Result<(), Error> {
+		let invalid =
+			|reason: &str| Err(Error::InvalidSourceConfig(self.name.clone(), reason.into()));
+
+		if self.name.is_empty() {
+			return invalid("name is empty")
+		}
+		if self.feed.is_empty() {
+			return invalid("feed is empty")
+		}
+		if self.timeout_secs == 0 || self.timeout_secs > MAX_ORACLE_REQUEST_TIMEOUT_SECS {
+			return invalid("timeout is out of range")
+		}
+		// Parsing each certificate up to its public key rejects anything but DER encoded X.509
+		// certificates, instead of failing only when the source is queried.
+		pem_certificates(&self.root_certificate)
+			.and_then(|certificates| {
+				certificates
+					.iter()
+					.try_for_each(|certificate| certificate.spki_pin().map(|_| ()))
+			})
+			.map_err(|e| Error::InvalidSourceConfig(self.name.clone(), format!("{}", e)))?;
+		if !self.spki_pins.is_empty() {
+			self.trusted_root_certificates()?;
+		}
+
+		let base_url = Url::parse(&self.base_url)
+			.map_err(|e| Error::InvalidSourceConfig(self.name.clone(), format!("{:?}", e)))?;
+		if base_url.scheme() != "https" {
+			return invalid("base url must use https")
+		}
+		let host = base_url.host_str().unwrap_or_default();
+		if !is_allowed_domain(host, allowed_domains) {
+			return Err(Error::DomainNotAllowed(host.to_string()))
+		}
+
+		// Rendering with dummy parameters checks the placeholder syntax.
+		let dummy_params: Vec<(String, String)> = placeholders(&self.query_template)?
+			.into_iter()
+			.chain(placeholders(&self.json_path)?)
+			.map(|name| (name, "x".to_string()))
+			.collect();
+		let dummy_params: Vec<(&str, &str)> =
+			dummy_params.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+		let request = self.render_request(&dummy_params)?;
+		crate::json_path::parse_json_path(&request.json_path)?;
+
+		Ok(())
+	}
+
+	/// Fill the placeholders of the query template and JSONPath with the given parameters.
+	pub fn render_request(&self, params: &[(&str, &str)]) -> Result<OracleRequest, Error> {
+		let (path_template, query_template) =
+			self.query_template.split_once('?').unwrap_or((&self.query_template, ""));
+
+		// Query values are encoded by the rest client. Path parameters are restricted instead,
+		// such that they can't escape their path segment.
+		let path = fill_placeholders(path_template, params, true)?;
+		let query = query_template
+			.split('&')
+			.filter(|pair| !pair.is_empty())
+			.map(|pair| {
+				let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+				Ok((key.to_string(), fill_placeholders(value, params, false)?))
+			})
+			.collect::<Result<Vec<_>, Error>>()?;
+		let json_path = fill_placeholders(&self.json_path, params, false)?;
+
+		Ok(OracleRequest { path, query, json_path })
+	}
+
+	/// The decoded [`OracleSourceConfig::spki_pins`].
+	pub fn decoded_spki_pins(&self) -> Result<Vec<SpkiPin>, Error> {
+		self.spki_pins
+			.iter()
+			.map(|pin| {
+				base64::decode(pin).ok().and_then(|pin| SpkiPin::try_from(pin).ok()).ok_or_else(
+					|| Error::InvalidSourceConfig(self.name.clone(), "invalid SPKI pin".into()),
+				)
+			})
+			.collect()
+	}
+
+	/// PEM bundle of the root certificates that satisfy the SPKI pins.
+	pub fn trusted_root_certificates(&self) -> Result<String, Error> {
+		SendWithCertificateVerification::new(self.root_certificate.clone())
+			.with_spki_pins(self.decoded_spki_pins()?)
+			.trusted_root_certificates()
+			.map_err(|e| Error::InvalidSourceConfig(self.name.clone(), format!("{}", e)))
+	}
+
+	/// SHA-256 fingerprints of the (DER encoded) trusted root certificates, as shown by
+	/// `openssl x509 -fingerprint -sha256`.
+	pub fn trusted_root_certificate_fingerprints(&self) -> Result<Vec<[u8; 32]>, Error> {
+		let trusted_root_certificates = self.trusted_root_certificates()?;
+		let root_certificates = pem_certificates(&trusted_root_certificates)
+			.map_err(|e| Error::InvalidSourceConfig(self.name.clone(), format!("{}", e)))?;
+
+		Ok(root_certificates.iter().map(|certificate| sha2_256(&certificate.der)).collect())
+	}
+}
+
+fn is_allowed_domain(host: &str, allowed_domains: &[&str]) -> bool {
+	allowed_domains
+		.iter()
+		.any(|domain| host == *domain || host.ends_with(&format!(".{}", domain)))
+}
+
+/// Names of all `{placeholders}` in the template.
+fn placeholders(template: &str) -> Result<Vec<String>, Error> {
+	let mut names = Vec::new();
+	let mut rest = template;
+	while let Some(start) = rest.find('{') {
+		let end = rest[start..]
+			.find('}')
+			.ok_or_else(|| Error::InvalidTemplate(template.to_string()))?;
+		names.push(rest[start + 1..start + end].to_string());
+		rest = &rest[start + end + 1..];
+	}
+	if rest.contains('}') {
+		return Err(Error::InvalidTemplate(template.to_string()))
+	}
+	Ok(names)
+}
+
+fn fill_placeholders(
+	template: &str,
+	params: &[(&str, &str)],
+	path_segment: bool,
+) -> Result<String, Error> {
+	let mut filled = template.to_string();
+	for name in placeholders(template)? {
+		let value = params
+			.iter()
+			.find(|(key, _)| *key == name)
+			.map(|(_, value)| *value)
+			.ok_or_else(|| Error::MissingParameter(name.clone()))?;
+		if path_segment && !value.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+		{
+			return Err(Error::InvalidParameter(name))
+		}
+		filled = filled.replace(&format!("{{{}}}", name), value);
+	}
+	Ok(filled)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use core::assert_matches::assert_matches;
+
+	fn binance_config() -> OracleSourceConfig {
+		OracleSourceConfig {
+			name: "binance".to_string(),
+			feed: EXCHANGE_RATE_FEED.to_string(),
+			base_url: "https://api.binance.com".to_string(),
+			query_template: "api/v3/ticker/price?symbol={crypto_currency}{fiat_currency}"
+				.to_string(),
+			json_path: "$.price".to_string(),
+			root_certificate: include_str!("certificates/amazon_root_ca_a.pem").to_string(),
+			spki_pins: vec![],
+			timeout_secs: 10,
+		}
+	}
+
+	#[test]
+	fn source_configs_are_parsed_from_json() {
+		let json = r#"[{
+			"name": "binance", "feed": "exchange_rate", "base_url": "https://api.binance.com",
+			"query_template": "api/v3/ticker/price?symbol={crypto_currency}{fiat_currency}",
+			"json_path": "$.price", "root_certificate": "-----BEGIN CERTIFICATE-----",
+			"timeout_secs": 5
+		}]"#;
+
+		let configs = parse_source_configs(json.as_bytes()).unwrap();
+
+		assert_eq!(1, configs.len());
+		assert_eq!("binance", configs[0].name);
+		assert_matches!(
+			parse_source_configs(b"{\"name\": \"binance\"}"),
+			Err(Error::MalformedSourceConfigs(_))
+		);
+	}
+
+	#[test]
+	fn valid_config_passes_validation() {
+		assert!(binance_config().validate(ALLOWED_ORACLE_DOMAINS).is_ok());
+	}
+
+	#[test]
+	fn subdomains_of_allowed_domains_are_allowed() {
+		assert!(is_allowed_domain("eu.api.binance.com", ALLOWED_ORACLE_DOMAINS));
+		assert!(!is_allowed_domain("evilapi.binance.com.attacker.io", ALLOWED_ORACLE_DOMAINS));
+		assert!(!is_allowed_domain("notapi.binance.com", &["api.binance.com"]));
+	}
+
+	#[test]
+	fn domain_outside_policy_is_rejected() {
+		let config =
+			OracleSourceConfig { base_url: "https://attacker.io".to_string(), ..binance_config() };
+		assert_matches!(config.validate(ALLOWED_ORACLE_DOMAINS), Err(Error::DomainNotAllowed(_)));
+	}
+
+	#[test]
+	fn plain_http_is_rejected() {
+		let config = OracleSourceConfig {
+			base_url: "http://api.binance.com".to_string(),
+			..binance_config()
+		};
+		assert_matches!(
+			config.validate(ALLOWED_ORACLE_DOMAINS),
+			Err(Error::InvalidSourceConfig(_, _))
+		);
+	}
+
+	#[test]
+	fn invalid_timeout_and_certificate_are_rejected() {
+		let no_timeout = OracleSourceConfig { timeout_secs: 0, ..binance_config() };
+		let no_certificate =
+			OracleSourceConfig { root_certificate: String::new(), ..binance_config() };
+
+		assert!(no_timeout.validate(ALLOWED_ORACLE_DOMAINS).is_err());
+		assert!(no_certificate.validate(ALLOWED_ORACLE_DOMAINS).is_err());
+	}
+
+	#[test]
+	fn malformed_certificates_are_rejected() {
+		let not_base64 = OracleSourceConfig {
+			root_certificate: "-----BEGIN CERTIFICATE-----\n%%%\n-----END CERTIFICATE-----"
+				.to_string(),
+			..binance_config()
+		};
+		let not_a_certificate = OracleSourceConfig {
+			root_certificate: "-----BEGIN CERTIFICATE-----\nMII=\n-----END CERTIFICATE-----"
+				.to_string(),
+			..binance_config()
+		};
+		let unterminated = OracleSourceConfig {
+			root_certificate: "-----BEGIN CERTIFICATE-----\nMIIB".to_string(),
+			..binance_config()
+		};
+
+		for config in [not_base64, not_a_certificate, unterminated] {
+			assert_matches!(
+				config.validate(ALLOWED_ORACLE_DOMAINS),
+				Err(Error::InvalidSourceConfig(_, _))
+			);
+		}
+	}
+
+	#[test]
+	fn malformed_templates_are_rejected() {
+		let unclosed = OracleSourceConfig {
+			query_template: "api/v3/ticker/price?symbol={crypto_currency".to_string(),
+			..binance_config()
+		};
+		let bad_path = OracleSourceConfig { json_path: "price".to_string(), ..binance_config() };
+
+		assert_matches!(unclosed.validate(ALLOWED_ORACLE_DOMAINS), Err(Error::InvalidTemplate(_)));
+		assert_matches!(bad_path.validate(ALLOWED_ORACLE_DOMAINS), Err(Error::InvalidJsonPath(_)));
+	}
+
+	#[test]
+	fn render_request_fills_placeholders() {
+		let config = OracleSourceConfig {
+			query_template: "v1/{crypto_currency}/price?convert={fiat_currency}&raw=true"
+				.to_string(),
+			json_path: "$.data.{fiat_currency}".to_string(),
+			..binance_config()
+		};
+
+		let request = config
+			.render_request(&[("crypto_currency", "TEER"), ("fiat_currency", "USD")])
+			.unwrap();
+
+		assert_eq!("v1/TEER/price", request.path);
+		assert_eq!(
+			vec![
+				("convert".to_string(), "USD".to_string()),
+				("raw".to_string(), "true".to_string())
+			],
+			request.query
+		);
+		assert_eq!("$.data.USD", request.json_path);
+	}
+
+	#[test]
+	fn path_parameters_cannot_escape_their_segment() {
+		let config = OracleSourceConfig {
+			query_template: "v1/{crypto_currency}/price".to_string(),
+			..binance_config()
+		};
+
+		let result = config.render_request(&[("crypto_currency", "../admin")]);
+		assert_matches!(result, Err(Error::InvalidParameter(p)) if p == "crypto_currency");
+	}
+
+	#[test]
+	fn request_url_contains_path_and_encoded_query() {
+		let request = binance_config()
+			.render_request(&[("crypto_currency", "TEER"), ("fiat_currency", "US D")])
+			.unwrap();
+
+		assert_eq!(
+			"https://api.binance.com/api/v3/ticker/price?symbol=TEERUS+D",
+			request.url("https://api.binance.com").unwrap().as_str()
+		);
+	}
+
+	#[test]
+	fn trusted_root_certificate_fingerprints_match_openssl() {
+		let config = OracleSourceConfig {
+			root_certificate: include_str!("certificates/lets_encrypt_root_cert.pem").to_string(),
+			..binance_config()
+		};
+
+		// openssl x509 -in lets_encrypt_root_cert.pem -noout -fingerprint -sha256
+		assert_eq!(
+			vec!["96bcec06264976f37460779acf28c5a7cfe8a3c0aae11a8ffcee05c0bddf08c6".to_string()],
+			config
+				.trusted_root_certificate_fingerprints()
+				.unwrap()
+				.iter()
+				.map(|fingerprint| hex_string(fingerprint))
+				.collect::<Vec<_>>()
+		);
+	}
+
+	#[test]
+	fn fingerprint_of_malformed_certificate_fails() {
+		let config = OracleSourceConfig {
+			root_certificate: "-----BEGIN CERTIFICATE-----\n%%%\n-----END CERTIFICATE-----"
+				.to_string(),
+			..binance_config()
+		};
+		assert_matches!(
+			config.trusted_root_certificate_fingerprints(),
+			Err(Error::InvalidSourceConfig(_, _))
+		);
+	}
+
+	#[test]
+	fn only_root_certificates_with_a_pinned_key_are_trusted() {
+		let amazon_root = include_str!("certificates/amazon_root_ca_a.pem");
+		let lets_encrypt_root = include_str!("certificates/lets_encrypt_root_cert.pem");
+		let config = OracleSourceConfig {
+			root_certificate: format!("{}\n{}", amazon_root, lets_encrypt_root),
+			// openssl x509 -pubkey -noout | openssl pkey -pubin -outform der
+			//   | openssl dgst -sha256 -binary | base64
+			spki_pins: vec!["C5+lpZ7tcVwmwQIMcRtPbsQtWLABXhQzejna0wHFr8M=".to_string()],
+			..binance_config()
+		};
+
+		assert!(config.validate(ALLOWED_ORACLE_DOMAINS).is_ok());
+		assert_eq!(
+			vec!["96bcec06264976f37460779acf28c5a7cfe8a3c0aae11a8ffcee05c0bddf08c6".to_string()],
+			config
+				.trusted_root_certificate_fingerprints()
+				.unwrap()
+				.iter()
+				.map(|fingerprint| hex_string(fingerprint))
+				.collect::<Vec<_>>()
+		);
+	}
+
+	#[test]
+	fn fingerprints_of_all_trusted_root_certificates_are_reported() {
+		let amazon_root = include_str!("certificates/amazon_root_ca_a.pem");
+		let lets_encrypt_root = include_str!("certificates/lets_encrypt_root_cert.pem");
+		let config = OracleSourceConfig {
+			root_certificate: format!("{}\n{}", amazon_root, lets_encrypt_root),
+			..binance_config()
+		};
+
+		let fingerprints = config.trusted_root_certificate_fingerprints().unwrap();
+
+		assert_eq!(2, fingerprints.len());
+		assert_eq!(
+			"96bcec06264976f37460779acf28c5a7cfe8a3c0aae11a8ffcee05c0bddf08c6",
+			hex_string(&fingerprints[1])
+		);
+	}
+
+	#[test]
+	fn invalid_or_unmatched_pins_are_rejected() {
+		let root_certificate = include_str!("certificates/amazon_root_ca_a.pem").to_string();
+		let invalid_pin = OracleSourceConfig {
+			root_certificate: root_certificate.clone(),
+			spki_pins: vec!["not a pin".to_string()],
+			..binance_config()
+		};
+		let unmatched_pin = OracleSourceConfig {
+			root_certificate,
+			spki_pins: vec!["C5+lpZ7tcVwmwQIMcRtPbsQtWLABXhQzejna0wHFr8M=".to_string()],
+			..binance_config()
+		};
+
+		assert_matches!(
+			invalid_pin.validate(ALLOWED_ORACLE_DOMAINS),
+			Err(Error::InvalidSourceConfig(_, _))
+		);
+		assert_matches!(
+			unmatched_pin.validate(ALLOWED_ORACLE_DOMAINS),
+			Err(Error::InvalidSourceConfig(_, reason)) if reason.contains("SPKI pins")
+		);
+	}
+
+	fn hex_string(bytes: &[u8]) -> String {
+		bytes.iter().map(|b| format!("{:02x}", b)).collect()
+	}
+
+	#[test]
+	fn render_request_with_missing_parameter_fails() {
+		let result = binance_config().render_request(&[("crypto_currency", "TEER")]);
+		assert_matches!(result, Err(Error::MissingParameter(p)) if p == "fiat_currency");
+	}
+}
@@ -0,0 +1,204 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+#[cfg(feature = "sgx")]
+use std::sync::SgxRwLock as RwLock;
+
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+use crate::{error::Error, source_config::OracleSourceConfig};
+use itp_sgx_io::SealedIO;
+use log::*;
+use std::{collections::BTreeSet, string::String, sync::Arc, vec::Vec};
+
+pub type OracleSourceConfigs = Vec<OracleSourceConfig>;
+
+/// Read the configured oracle sources.
+pub trait AccessOracleSourceConfigs {
+	fn source_configs(&self) -> Result<OracleSourceConfigs, Error>;
+
+	/// All configured sources of the given feed.
+	fn source_configs_of_feed(&self, feed: &str) -> Result<OracleSourceConfigs, Error> {
+		Ok(self.source_configs()?.into_iter().filter(|c| c.feed == feed).collect())
+	}
+}
+
+/// Replace the configured oracle sources.
+pub trait UpdateOracleSourceConfigs {
+	fn set_source_configs(&self, configs: OracleSourceConfigs) -> Result<(), Error>;
+}
+
+/// Stores the oracle source configs in-memory and in a file backed.
+/// Uses the SealedIO trait for the file backend.
+pub struct OracleSourceConfigStore<SealedIo> {
+	configs_lock: RwLock<OracleSourceConfigs>,
+	allowed_domains: &'static [&'static str],
+	sealed_io: Arc<SealedIo>,
+}
+
+impl<SealedIo> OracleSourceConfigStore<SealedIo>
+where
+	SealedIo: SealedIO<Unsealed = OracleSourceConfigs, Error = Error>,
+{
+	pub fn new(
+		configs: OracleSourceConfigs,
+		allowed_domains: &'static [&'static str],
+		sealed_io: Arc<SealedIo>,
+	) -> Self {
+		OracleSourceConfigStore { configs_lock: RwLock::new(configs), allowed_domains, sealed_io }
+	}
+
+	/// Load the configs from the sealed file. Configs that violate the current domain policy
+	/// (e.g. sealed by an enclave with a different policy) are dropped.
+	pub fn load(
+		allowed_domains: &'static [&'static str],
+		sealed_io: Arc<SealedIo>,
+	) -> Result<Self, Error> {
+		let configs = sealed_io
+			.unseal()?
+			.into_iter()
+			.filter(|config| match config.validate(allowed_domains) {
+				Ok(()) => true,
+				Err(e) => {
+					warn!("Dropping sealed oracle source config {}: {}", config.name, e);
+					false
+				},
+			})
+			.collect();
+		Ok(Self::new(configs, allowed_domains, sealed_io))
+	}
+}
+
+impl<SealedIo> AccessOracleSourceConfigs for OracleSourceConfigStore<SealedIo>
+where
+	SealedIo: SealedIO<Unsealed = OracleSourceConfigs, Error = Error>,
+{
+	fn source_configs(&self) -> Result<OracleSourceConfigs, Error> {
+		let configs_lock = self.configs_lock.read().map_err(|_| Error::LockPoisoning)?;
+		Ok(configs_lock.clone())
+	}
+}
+
+impl<SealedIo> UpdateOracleSourceConfigs for OracleSourceConfigStore<SealedIo>
+where
+	SealedIo: SealedIO<Unsealed = OracleSourceConfigs, Error = Error>,
+{
+	fn set_source_configs(&self, configs: OracleSourceConfigs) -> Result<(), Error> {
+		let mut names = BTreeSet::<&String>::new();
+		for config in configs.iter() {
+			config.validate(self.allowed_domains)?;
+			if !names.insert(&config.name) {
+				return Err(Error::InvalidSourceConfig(config.name.clone(), "duplicate name".into()))
+			}
+		}
+
+		let mut configs_lock = self.configs_lock.write().map_err(|_| Error::LockPoisoning)?;
+		self.sealed_io.seal(&configs)?;
+		info!("Updated the oracle source configs, {} sources", configs.len());
+		*configs_lock = configs;
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		mock::OracleSourceConfigSealMock,
+		source_config::{ALLOWED_ORACLE_DOMAINS, EXCHANGE_RATE_FEED},
+	};
+	use core::assert_matches::assert_matches;
+
+	type TestStore = OracleSourceConfigStore<OracleSourceConfigSealMock>;
+
+	fn config(name: &str, feed: &str, base_url: &str) -> OracleSourceConfig {
+		OracleSourceConfig {
+			name: name.to_string(),
+			feed: feed.to_string(),
+			base_url: base_url.to_string(),
+			query_template: "v1/price?symbol={crypto_currency}{fiat_currency}".to_string(),
+			json_path: "$.price".to_string(),
+			root_certificate: include_str!("certificates/amazon_root_ca_a.pem").to_string(),
+			spki_pins: vec![],
+			timeout_secs: 5,
+		}
+	}
+
+	#[test]
+	fn set_source_configs_seals_and_filters_by_feed() {
+		let seal = Arc::new(OracleSourceConfigSealMock::default());
+		let store = TestStore::new(Vec::new(), ALLOWED_ORACLE_DOMAINS, seal.clone());
+		let configs = vec![
+			config("binance", EXCHANGE_RATE_FEED, "https://api.binance.com"),
+			config("meteo", "weather", "https://api.open-meteo.com"),
+		];
+
+		store.set_source_configs(configs.clone()).unwrap();
+
+		assert_eq!(configs, seal.unseal().unwrap());
+		assert_eq!(
+			vec![configs[0].clone()],
+			store.source_configs_of_feed(EXCHANGE_RATE_FEED).unwrap()
+		);
+	}
+
+	#[test]
+	fn set_source_configs_rejects_the_whole_update_on_policy_violation() {
+		let seal = Arc::new(OracleSourceConfigSealMock::default());
+		let store = TestStore::new(Vec::new(), ALLOWED_ORACLE_DOMAINS, seal.clone());
+		let configs = vec![
+			config("binance", EXCHANGE_RATE_FEED, "https://api.binance.com"),
+			config("evil", EXCHANGE_RATE_FEED, "https://attacker.io"),
+		];
+
+		assert_matches!(store.set_source_configs(configs), Err(Error::DomainNotAllowed(_)));
+		assert!(store.source_configs().unwrap().is_empty());
+		assert!(seal.unseal().unwrap().is_empty());
+	}
+
+	#[test]
+	fn set_source_configs_rejects_duplicate_names() {
+		let store = TestStore::new(
+			Vec::new(),
+			ALLOWED_ORACLE_DOMAINS,
+			Arc::new(OracleSourceConfigSealMock::default()),
+		);
+		let configs = vec![
+			config("binance", EXCHANGE_RATE_FEED, "https://api.binance.com"),
+			config("binance", EXCHANGE_RATE_FEED, "https://api.kraken.com"),
+		];
+
+		assert_matches!(store.set_source_configs(configs), Err(Error::InvalidSourceConfig(_, _)));
+	}
+
+	#[test]
+	fn load_drops_configs_violating_the_policy() {
+		let seal = Arc::new(OracleSourceConfigSealMock::default());
+		seal.seal(&vec![
+			config("binance", EXCHANGE_RATE_FEED, "https://api.binance.com"),
+			config("kraken", EXCHANGE_RATE_FEED, "https://api.kraken.com"),
+		])
+		.unwrap();
+
+		let store = TestStore::load(&["api.kraken.com"], seal).unwrap();
+
+		let names: Vec<String> =
+			store.source_configs().unwrap().into_iter().map(|c| c.name).collect();
+		assert_eq!(vec!["kraken".to_string()], names);
+	}
+}
@@ -20,6 +20,9 @@
 
 use crate::{
 	error::Error,
+	metrics_exporter::{
+		WEATHER_PRECIPITATION_FEED, WEATHER_TEMPERATURE_FEED, WEATHER_WIND_SPEED_FEED,
+	},
 	mock::MetricsExporterMock,
 	oracle_sources::{
 		coin_gecko::CoinGeckoSource, coin_market_cap::CoinMarketCapSource,
@@ -27,17 +30,17 @@ use crate::{
 	},
 	oracles::{
 		exchange_rate_oracle::{ExchangeRateOracle, GetExchangeRate},
-		weather_oracle::{GetLongitude, WeatherOracle},
+		weather_oracle::{GetWeatherReport, WeatherOracle},
 	},
 	traits::OracleSource,
 	types::{TradingInfo, TradingPair, WeatherInfo, WeatherQuery},
 };
 use core::assert_matches::assert_matches;
+use itp_enclave_metrics::OracleValue;
 use std::sync::Arc;
 use substrate_fixed::transcendental::ZERO;
 
 type TestOracle<OracleSource> = ExchangeRateOracle<OracleSource, MetricsExporterMock>;
-type TestWeatherOracle<OracleSource> = WeatherOracle<OracleSource, MetricsExporterMock>;
 
 #[test]
 #[ignore = "requires API key for CoinMarketCap"]
@@ -52,16 +55,41 @@ fn get_exchange_rate_from_coin_gecko_works() {
 }
 
 #[test]
-fn get_longitude_from_open_meteo_works() {
-	let oracle = create_weather_oracle::<WeatherOracleSource>();
+fn get_weather_report_from_open_meteo_works() {
+	let metrics_exporter = Arc::new(MetricsExporterMock::default());
+	let oracle = WeatherOracle::new(WeatherOracleSource::default(), metrics_exporter.clone());
 	let weather_query =
-		WeatherQuery { latitude: "52.52".into(), longitude: "13.41".into(), hourly: "none".into() };
-	// Todo: hourly param is temperature_2m to get temp or relativehumidity_2m to get humidity
+		WeatherQuery { latitude: "52.52".into(), longitude: "13.41".into(), forecast_hours: 6 };
 	let weather_info = WeatherInfo { weather_query };
-	let expected_longitude = 13.41f32;
-	let response_longitude =
-		oracle.get_longitude(weather_info).expect("Can grab longitude from oracle");
-	assert!((response_longitude - expected_longitude) < 0.5);
+
+	let weather_report = oracle
+		.get_weather_report(weather_info)
+		.expect("Can grab weather report from oracle");
+
+	assert!((weather_report.longitude.to_num::<f32>() - 13.41f32).abs() < 0.5);
+	assert_eq!(6, weather_report.hourly.len());
+	assert_eq!(weather_report.time, weather_report.hourly[0].time);
+	assert_eq!(1, metrics_exporter.get_number_request());
+	assert_eq!(
+		vec![
+			(
+				WEATHER_TEMPERATURE_FEED.to_string(),
+				"52.52/13.41".to_string(),
+				OracleValue::from_num(weather_report.temperature)
+			),
+			(
+				WEATHER_PRECIPITATION_FEED.to_string(),
+				"52.52/13.41".to_string(),
+				OracleValue::from_num(weather_report.precipitation)
+			),
+			(
+				WEATHER_WIND_SPEED_FEED.to_string(),
+				"52.52/13.41".to_string(),
+				OracleValue::from_num(weather_report.wind_speed)
+			),
+		],
+		metrics_exporter.get_values()
+	);
 }
 
 #[test]
@@ -74,12 +102,6 @@ fn get_exchange_rate_for_undefined_coin_gecko_crypto_currency_fails() {
 	get_exchange_rate_for_undefined_crypto_currency_fails::<CoinGeckoSource>();
 }
 
-fn create_weather_oracle<OracleSourceType: OracleSource<WeatherInfo>>(
-) -> TestWeatherOracle<OracleSourceType> {
-	let oracle_source = OracleSourceType::default();
-	WeatherOracle::new(oracle_source, Arc::new(MetricsExporterMock::default()))
-}
-
 fn create_exchange_rate_oracle<OracleSourceType: OracleSource<TradingInfo>>(
 ) -> TestOracle<OracleSourceType> {
 	let oracle_source = OracleSourceType::default();
@@ -19,20 +19,16 @@
 use crate::sgx_reexport_prelude::*;
 
 use crate::{
+	oracle_rest_client::OracleRestClient,
 	types::{ExchangeRate, TradingPair},
 	Error,
 };
 use core::time::Duration;
-use itc_rest_client::{
-	http_client::{HttpClient, SendWithCertificateVerification},
-	rest_client::RestClient,
-};
-use std::string::String;
+use itc_rest_client::certificates::SpkiPin;
+use std::{string::String, vec::Vec};
 use url::Url;
 
 pub trait OracleSource<OracleSourceInfo>: Default {
-	type OracleRequestResult;
-
 	fn metrics_id(&self) -> String;
 
 	fn request_timeout(&self) -> Option<Duration>;
@@ -42,14 +38,39 @@ pub trait OracleSource<OracleSourceInfo>: Default {
 	/// The server's root certificate. A valid certificate is required to open a tls connection
 	fn root_certificate_content(&self) -> String;
 
+	/// SPKI pins restricting the trusted root certificates, none by default.
+	fn spki_pins(&self) -> Vec<SpkiPin> {
+		Vec::new()
+	}
+
 	fn execute_exchange_rate_request(
 		&self,
-		rest_client: &mut RestClient<HttpClient<SendWithCertificateVerification>>,
+		rest_client: &mut OracleRestClient,
 		trading_pair: TradingPair,
 	) -> Result<ExchangeRate, Error>;
 
+	/// Exchange rates of several trading pairs, in the same order. Sources with a bulk
+	/// endpoint query all of them with a single request, by default they're queried one by one.
+	fn execute_exchange_rates_request(
+		&self,
+		rest_client: &mut OracleRestClient,
+		trading_pairs: &[TradingPair],
+	) -> Vec<Result<ExchangeRate, Error>> {
+		trading_pairs
+			.iter()
+			.map(|trading_pair| {
+				self.execute_exchange_rate_request(rest_client, trading_pair.clone())
+			})
+			.collect()
+	}
+}
+
+/// Oracle source with a request of its own, independent of any configuration.
+pub trait ExecuteOracleRequest<OracleSourceInfo>: OracleSource<OracleSourceInfo> {
+	type OracleRequestResult;
+
 	fn execute_request(
-		rest_client: &mut RestClient<HttpClient<SendWithCertificateVerification>>,
+		rest_client: &mut OracleRestClient,
 		source_info: OracleSourceInfo,
 	) -> Self::OracleRequestResult;
 }
@@ -16,8 +16,8 @@
 */
 
 use codec::{Decode, Encode};
-use std::string::String;
-use substrate_fixed::types::U32F32;
+use std::{string::String, vec::Vec};
+use substrate_fixed::types::{I32F32, U32F32};
 
 #[derive(Debug, Clone, Encode, Decode, Eq, PartialEq)]
 pub struct WeatherInfo {
@@ -28,7 +28,8 @@ pub struct WeatherInfo {
 pub struct WeatherQuery {
 	pub longitude: String,
 	pub latitude: String,
-	pub hourly: String,
+	/// Number of hours of the forecast series, starting with the current hour.
+	pub forecast_hours: u32,
 }
 
 impl WeatherQuery {
@@ -37,6 +38,32 @@ impl WeatherQuery {
 	}
 }
 
+/// Current weather and forecast at a location, as published on the parentchain.
+#[derive(Debug, Clone, Encode, Decode, Eq, PartialEq)]
+pub struct WeatherReport {
+	/// Coordinates of the forecast grid cell, may slightly differ from the queried ones.
+	pub latitude: WeatherValue,
+	pub longitude: WeatherValue,
+	/// Hour of the current weather, ISO 8601 in UTC, e.g. `2023-05-03T12:00`.
+	pub time: String,
+	/// Temperature 2m above ground in °C.
+	pub temperature: WeatherValue,
+	/// Precipitation (rain, showers and snow) of the preceding hour in mm.
+	pub precipitation: WeatherValue,
+	/// Wind speed 10m above ground in km/h.
+	pub wind_speed: WeatherValue,
+	pub hourly: Vec<HourlyWeather>,
+}
+
+/// Forecast for a single hour.
+#[derive(Debug, Clone, Encode, Decode, Eq, PartialEq)]
+pub struct HourlyWeather {
+	pub time: String,
+	pub temperature: WeatherValue,
+	pub precipitation: WeatherValue,
+	pub wind_speed: WeatherValue,
+}
+
 #[derive(Debug, Clone, Encode, Decode, Eq, PartialEq)]
 pub struct TradingInfo {
 	pub trading_pair: TradingPair,
@@ -58,4 +85,8 @@ impl TradingPair {
 /// TODO Fix https://github.com/integritee-network/pallets/issues/71 and get it from https://github.com/integritee-network/pallets.git
 /// Teeracle types
 pub type ExchangeRate = U32F32;
-// pub type Coordinate = U32F32;
+/// Signed, since temperatures and coordinates may be negative.
+pub type WeatherValue = I32F32;
+
+/// Value of any feed, e.g. the result of an on-demand query.
+pub use itp_enclave_metrics::OracleValue;
@@ -9,8 +9,9 @@ use sp_core::{H160, U256};
 use sp_runtime::traits::BlakeTwo256;
 
 pub use pallet_evm::{
-	AddressMapping, Call as EvmCall, EnsureAddressTruncated, FeeCalculator, GasWeightMapping,
-	HashedAddressMapping as GenericHashedAddressMapping, SubstrateBlockHashMapping,
+	AddressMapping, Call as EvmCall, EnsureAddressTruncated, Event as EvmEvent, FeeCalculator,
+	GasWeightMapping, HashedAddressMapping as GenericHashedAddressMapping,
+	SubstrateBlockHashMapping,
 };
 
 pub type HashedAddressMapping = GenericHashedAddressMapping<BlakeTwo256>;
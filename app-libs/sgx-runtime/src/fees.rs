@@ -0,0 +1,83 @@
+//! Fee handling of the `sgx-runtime`, based on `pallet-transaction-payment`.
+//!
+//! Trusted calls are not dispatched as extrinsics, so the fee is computed and withdrawn
+//! explicitly by the STF with the helpers of this module.
+
+// Import types from the crate root including the ones generated by the `construct_runtime!` macro.
+use crate::{AccountId, Balance, Balances, ExistentialDeposit, TransactionPayment};
+use frame_support::{
+	dispatch::{DispatchInfo, DispatchResult},
+	pallet_prelude::Weight,
+	storage_alias,
+	traits::{Currency, ExistenceRequirement, Get, OnUnbalanced, WithdrawReasons},
+	weights::{constants::WEIGHT_REF_TIME_PER_MICROS, WeightToFee as WeightToFeeT},
+};
+use sp_runtime::traits::Zero;
+
+pub type NegativeImbalance = <Balances as Currency<AccountId>>::NegativeImbalance;
+
+/// Account the fees of trusted calls are credited to. Fees are burned as long as it is unset.
+#[storage_alias]
+pub type FeeTreasury = StorageValue<Fees, AccountId>;
+
+/// Charges one unit per microsecond of execution time.
+pub struct WeightToFee;
+impl WeightToFeeT for WeightToFee {
+	type Balance = Balance;
+
+	fn weight_to_fee(weight: &Weight) -> Self::Balance {
+		Balance::from(weight.ref_time() / WEIGHT_REF_TIME_PER_MICROS)
+	}
+}
+
+/// Credits the fees to the [`FeeTreasury`].
+///
+/// The fees are burned if no treasury is set, or if they are not enough to create
+/// the treasury account.
+pub struct DealWithFees;
+impl OnUnbalanced<NegativeImbalance> for DealWithFees {
+	fn on_nonzero_unbalanced(amount: NegativeImbalance) {
+		if let Some(treasury) = FeeTreasury::get() {
+			Balances::resolve_creating(&treasury, amount);
+		}
+	}
+}
+
+/// Fee of a call with the given dispatch info and encoded length, the tip included.
+pub fn compute_fee(len: u32, info: &DispatchInfo, tip: Balance) -> Balance {
+	TransactionPayment::compute_fee(len, info, tip)
+}
+
+/// Check whether `who` can pay `fee` without its account being reaped.
+pub fn can_pay_fee(who: &AccountId, fee: Balance) -> bool {
+	if fee.is_zero() {
+		return true
+	}
+
+	Balances::free_balance(who).checked_sub(fee).map_or(false, |new_free| {
+		new_free.saturating_add(Balances::reserved_balance(who)) >= ExistentialDeposit::get()
+			&& Balances::ensure_can_withdraw(
+				who,
+				fee,
+				WithdrawReasons::TRANSACTION_PAYMENT,
+				new_free,
+			)
+			.is_ok()
+	})
+}
+
+/// Withdraw `fee` from `who`, keeping its account alive, and hand it to [`DealWithFees`].
+pub fn charge_fee(who: &AccountId, fee: Balance) -> DispatchResult {
+	if fee.is_zero() {
+		return Ok(())
+	}
+
+	let imbalance = Balances::withdraw(
+		who,
+		fee,
+		WithdrawReasons::TRANSACTION_PAYMENT,
+		ExistenceRequirement::KeepAlive,
+	)?;
+	DealWithFees::on_unbalanced(imbalance);
+	Ok(())
+}
@@ -30,16 +30,28 @@
 
 #[cfg(feature = "evm")]
 mod evm;
+mod fees;
+mod shielded_assets;
 
 #[cfg(feature = "evm")]
 pub use evm::{
-	AddressMapping, EnsureAddressTruncated, EvmCall, FeeCalculator, FixedGasPrice,
+	AddressMapping, EnsureAddressTruncated, EvmCall, EvmEvent, FeeCalculator, FixedGasPrice,
 	FixedGasWeightMapping, GasWeightMapping, HashedAddressMapping, IntoAddressMapping,
 	SubstrateBlockHashMapping, GAS_PER_SECOND, MAXIMUM_BLOCK_WEIGHT, WEIGHT_PER_GAS,
 };
 
+pub use fees::{
+	can_pay_fee, charge_fee, compute_fee, DealWithFees, FeeTreasury, NegativeImbalance,
+	WeightToFee,
+};
+pub use shielded_assets::ShieldedAssetSupply;
+
 use core::convert::{TryFrom, TryInto};
-use frame_support::weights::ConstantMultiplier;
+use frame_support::{
+	traits::{AsEnsureOriginWithArg, ConstU128},
+	weights::ConstantMultiplier,
+};
+use frame_system::{EnsureRoot, EnsureSigned};
 use pallet_transaction_payment::CurrencyAdapter;
 use sp_api::impl_runtime_apis;
 use sp_core::OpaqueMetadata;
@@ -54,7 +66,8 @@ use sp_version::RuntimeVersion;
 pub use itp_sgx_runtime_primitives::{
 	constants::SLOT_DURATION,
 	types::{
-		AccountData, AccountId, Address, Balance, BlockNumber, Hash, Header, Index, Signature,
+		AccountData, AccountId, Address, AssetId, Balance, BlockNumber, Hash, Header, Index,
+		Signature,
 	},
 };
 
@@ -70,6 +83,7 @@ pub use frame_support::{
 	},
 	StorageValue,
 };
+pub use pallet_assets::Call as AssetsCall;
 pub use pallet_balances::Call as BalancesCall;
 pub use pallet_parentchain::Call as ParentchainCall;
 pub use pallet_timestamp::Call as TimestampCall;
@@ -243,13 +257,42 @@ parameter_types! {
 
 impl pallet_transaction_payment::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
-	type OnChargeTransaction = CurrencyAdapter<Balances, ()>;
+	type OnChargeTransaction = CurrencyAdapter<Balances, DealWithFees>;
 	type OperationalFeeMultiplier = OperationalFeeMultiplier;
-	type WeightToFee = IdentityFee<Balance>;
+	type WeightToFee = WeightToFee;
 	type LengthToFee = ConstantMultiplier<Balance, TransactionByteFee>;
 	type FeeMultiplierUpdate = ();
 }
 
+parameter_types! {
+	pub const AssetsStringLimit: u32 = 50;
+	pub const AssetsRemoveItemsLimit: u32 = 1000;
+}
+
+impl pallet_assets::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Balance = Balance;
+	type RemoveItemsLimit = AssetsRemoveItemsLimit;
+	type AssetId = AssetId;
+	type AssetIdParameter = codec::Compact<AssetId>;
+	type Currency = Balances;
+	type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<AccountId>>;
+	type ForceOrigin = EnsureRoot<AccountId>;
+	/// Storage is not scarce in the sgx-runtime, so no deposits are taken.
+	type AssetDeposit = ConstU128<0>;
+	type AssetAccountDeposit = ConstU128<0>;
+	type MetadataDepositBase = ConstU128<0>;
+	type MetadataDepositPerByte = ConstU128<0>;
+	type ApprovalDeposit = ConstU128<0>;
+	type StringLimit = AssetsStringLimit;
+	type Freezer = ();
+	type Extra = ();
+	type CallbackHandle = ();
+	type WeightInfo = ();
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = ();
+}
+
 impl pallet_sudo::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type RuntimeCall = RuntimeCall;
@@ -273,6 +316,7 @@ construct_runtime!(
 		TransactionPayment: pallet_transaction_payment::{Pallet, Storage, Event<T>},
 		Sudo: pallet_sudo::{Pallet, Call, Config<T>, Storage, Event<T>},
 		Parentchain: pallet_parentchain::{Pallet, Call, Storage},
+		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
 	}
 );
 
@@ -293,6 +337,7 @@ construct_runtime!(
 		TransactionPayment: pallet_transaction_payment::{Pallet, Storage, Event<T>},
 		Sudo: pallet_sudo::{Pallet, Call, Config<T>, Storage, Event<T>},
 		Parentchain: pallet_parentchain::{Pallet, Call, Storage},
+		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
 
 		Evm: pallet_evm::{Pallet, Call, Storage, Config, Event<T>},
 	}
@@ -0,0 +1,13 @@
+//! Bookkeeping of the assets shielded from the parentchain.
+//!
+//! Assets can be minted on the sidechain as well, but only shielded units are backed by the
+//! parentchain vault. Unshielding is therefore limited to the [`ShieldedAssetSupply`].
+
+// Import types from the crate root.
+use crate::{AssetId, Balance};
+use frame_support::{pallet_prelude::ValueQuery, storage_alias, Blake2_128Concat};
+
+/// Units of an asset that were shielded and not yet unshielded again.
+#[storage_alias]
+pub type ShieldedAssetSupply =
+	StorageMap<ShieldedAssets, Blake2_128Concat, AssetId, Balance, ValueQuery>;
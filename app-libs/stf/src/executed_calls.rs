@@ -0,0 +1,68 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+//! Trusted calls executed on behalf of an account.
+//!
+//! Sidechain blocks only list the hashes of their trusted operations. The latest calls of an
+//! account are kept in the state, so its holder can look up the details of its own operations
+//! with the `call_details` trusted getter.
+
+use crate::{hash::Hash, TrustedCallSigned, TrustedOperation};
+use codec::{Decode, Encode};
+use itp_stf_primitives::types::AccountId;
+use itp_storage::{storage_map_key, StorageHasher};
+use sp_core::H256;
+use std::prelude::v1::*;
+
+const EXECUTED_CALLS_PREFIX: &str = "Stf";
+const EXECUTED_CALLS_KEY: &str = "ExecutedCalls";
+
+/// Number of calls kept per account, older ones are dropped.
+pub const MAX_EXECUTED_CALLS_PER_ACCOUNT: usize = 32;
+
+/// The latest successfully executed calls signed by `account`, the oldest first.
+pub fn get_executed_calls(account: &AccountId) -> Vec<TrustedCallSigned> {
+	sp_io::storage::get(&executed_calls_key(account))
+		.and_then(|encoded| Decode::decode(&mut encoded.as_slice()).ok())
+		.unwrap_or_default()
+}
+
+/// The executed call of `account` that was included in a sidechain block as the trusted
+/// operation with `operation_hash`.
+pub fn get_executed_call(account: &AccountId, operation_hash: &H256) -> Option<TrustedCallSigned> {
+	get_executed_calls(account).into_iter().find(|call| {
+		TrustedOperation::direct_call(call.clone()).hash() == *operation_hash
+			|| TrustedOperation::indirect_call(call.clone()).hash() == *operation_hash
+	})
+}
+
+pub fn record_executed_call(call: TrustedCallSigned) {
+	let account = call.call.sender_account().clone();
+	let mut calls = get_executed_calls(&account);
+	calls.push(call);
+	let excess = calls.len().saturating_sub(MAX_EXECUTED_CALLS_PER_ACCOUNT);
+	calls.drain(..excess);
+	sp_io::storage::set(&executed_calls_key(&account), &calls.encode());
+}
+
+fn executed_calls_key(account: &AccountId) -> Vec<u8> {
+	storage_map_key(
+		EXECUTED_CALLS_PREFIX,
+		EXECUTED_CALLS_KEY,
+		account,
+		&StorageHasher::Blake2_128Concat,
+	)
+}
@@ -0,0 +1,136 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+//! Fees of trusted calls.
+//!
+//! The fee is computed by the `pallet-transaction-payment` of the sgx-runtime from the weight
+//! of the runtime call a trusted call dispatches and the encoded length of the trusted call.
+//! It is withdrawn from the sender before the call is executed and credited to the fee treasury.
+
+use crate::{Balance, StfError, StfResult, TrustedCall, TrustedCallSigned};
+use codec::Encode;
+use frame_support::{
+	dispatch::{DispatchInfo, GetDispatchInfo},
+	traits::Get,
+};
+use ita_sgx_runtime::Runtime;
+use itp_utils::stringify::account_id_to_string;
+use log::*;
+use sp_runtime::MultiAddress;
+
+/// Dispatch info the fee of a trusted call is derived from, `None` if the call is free of charge.
+pub fn fee_dispatch_info(call: &TrustedCall) -> Option<DispatchInfo> {
+	match call {
+		TrustedCall::balance_set_balance(_, who, free_balance, reserved_balance) => Some(
+			ita_sgx_runtime::BalancesCall::<Runtime>::set_balance {
+				who: MultiAddress::Id(who.clone()),
+				new_free: *free_balance,
+				new_reserved: *reserved_balance,
+			}
+			.get_dispatch_info(),
+		),
+		TrustedCall::balance_transfer(_, to, value) => Some(
+			ita_sgx_runtime::BalancesCall::<Runtime>::transfer {
+				dest: MultiAddress::Id(to.clone()),
+				value: *value,
+			}
+			.get_dispatch_info(),
+		),
+		// Unshielding sets the balance of the incognito account.
+		TrustedCall::balance_unshield(account_incognito, _, value, _) => Some(
+			ita_sgx_runtime::BalancesCall::<Runtime>::set_balance {
+				who: MultiAddress::Id(account_incognito.clone()),
+				new_free: *value,
+				new_reserved: 0,
+			}
+			.get_dispatch_info(),
+		),
+		// Shielding is signed by the enclave and backed by a deposit on the parentchain.
+		TrustedCall::balance_shield(..) | TrustedCall::assets_shield(..) => None,
+		TrustedCall::fees_set_treasury(..) => Some(DispatchInfo {
+			weight: <Runtime as frame_system::Config>::DbWeight::get().writes(1),
+			..Default::default()
+		}),
+		TrustedCall::assets_create(_, owner, asset_id, min_balance) => Some(
+			ita_sgx_runtime::AssetsCall::<Runtime>::force_create {
+				id: (*asset_id).into(),
+				owner: MultiAddress::Id(owner.clone()),
+				is_sufficient: true,
+				min_balance: *min_balance,
+			}
+			.get_dispatch_info(),
+		),
+		TrustedCall::assets_mint(_, beneficiary, asset_id, amount) => Some(
+			ita_sgx_runtime::AssetsCall::<Runtime>::mint {
+				id: (*asset_id).into(),
+				beneficiary: MultiAddress::Id(beneficiary.clone()),
+				amount: *amount,
+			}
+			.get_dispatch_info(),
+		),
+		TrustedCall::assets_transfer(_, to, asset_id, amount) => Some(
+			ita_sgx_runtime::AssetsCall::<Runtime>::transfer {
+				id: (*asset_id).into(),
+				target: MultiAddress::Id(to.clone()),
+				amount: *amount,
+			}
+			.get_dispatch_info(),
+		),
+		TrustedCall::assets_burn(_, who, asset_id, amount) => Some(
+			ita_sgx_runtime::AssetsCall::<Runtime>::burn {
+				id: (*asset_id).into(),
+				who: MultiAddress::Id(who.clone()),
+				amount: *amount,
+			}
+			.get_dispatch_info(),
+		),
+		// Unshielding burns the assets of the incognito account.
+		TrustedCall::assets_unshield(account_incognito, _, asset_id, amount, _) => Some(
+			ita_sgx_runtime::AssetsCall::<Runtime>::burn {
+				id: (*asset_id).into(),
+				who: MultiAddress::Id(account_incognito.clone()),
+				amount: *amount,
+			}
+			.get_dispatch_info(),
+		),
+		// The gas of evm calls is metered by the evm pallet, only the base and length fee apply.
+		#[cfg(feature = "evm")]
+		_ => Some(DispatchInfo::default()),
+	}
+}
+
+/// Fee the sender of a trusted call is charged, the tip included.
+pub fn call_fee(call: &TrustedCall, tip: Balance) -> Balance {
+	fee_dispatch_info(call)
+		.map_or(0, |info| ita_sgx_runtime::compute_fee(call.encoded_size() as u32, &info, tip))
+}
+
+/// Check whether the sender of a trusted call can pay its fee.
+pub fn can_pay_call_fee(call: &TrustedCallSigned) -> bool {
+	ita_sgx_runtime::can_pay_fee(call.call.sender_account(), call_fee(&call.call, call.tip()))
+}
+
+/// Withdraw the fee of a trusted call from its sender.
+pub fn charge_call_fee(call: &TrustedCallSigned) -> StfResult<()> {
+	let sender = call.call.sender_account();
+	let fee = call_fee(&call.call, call.tip());
+	debug!("Charging a fee of {} to {}", fee, account_id_to_string(sender));
+
+	ita_sgx_runtime::charge_fee(sender, fee).map_err(|e| {
+		warn!("Failed to charge the fee of {} to {}: {:?}", fee, account_id_to_string(sender), e);
+		StfError::MissingFunds
+	})
+}
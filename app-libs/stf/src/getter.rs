@@ -15,12 +15,17 @@
 
 */
 
+use crate::{
+	executed_calls::get_executed_call, fees::call_fee, stf_events::get_stf_events_visible_to,
+	AssetId, TrustedCall,
+};
 use codec::{Decode, Encode};
-use ita_sgx_runtime::System;
+use ita_sgx_runtime::{Assets, System};
 use itp_stf_interface::ExecuteGetter;
 use itp_stf_primitives::types::{AccountId, KeyPair, Signature};
 use itp_utils::stringify::account_id_to_string;
 use log::*;
+use sp_core::H256;
 use sp_runtime::traits::Verify;
 use std::prelude::v1::*;
 
@@ -31,7 +36,7 @@ use ita_sgx_runtime::{AddressMapping, HashedAddressMapping};
 use crate::evm_helpers::{get_evm_account, get_evm_account_codes, get_evm_account_storages};
 
 #[cfg(feature = "evm")]
-use sp_core::{H160, H256};
+use sp_core::H160;
 
 #[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
 #[allow(non_camel_case_types)]
@@ -64,6 +69,11 @@ pub enum TrustedGetter {
 	free_balance(AccountId),
 	reserved_balance(AccountId),
 	nonce(AccountId),
+	events(AccountId),
+	subscribe_events(AccountId, u64), // (Account, Timestamp in milliseconds)
+	fee_estimate(AccountId, TrustedCall),
+	asset_balance(AccountId, AssetId),
+	call_details(AccountId, H256), // (Signer of the call, hash of the trusted operation)
 	#[cfg(feature = "evm")]
 	evm_nonce(AccountId),
 	#[cfg(feature = "evm")]
@@ -78,6 +88,11 @@ impl TrustedGetter {
 			TrustedGetter::free_balance(sender_account) => sender_account,
 			TrustedGetter::reserved_balance(sender_account) => sender_account,
 			TrustedGetter::nonce(sender_account) => sender_account,
+			TrustedGetter::events(sender_account) => sender_account,
+			TrustedGetter::subscribe_events(sender_account, _) => sender_account,
+			TrustedGetter::fee_estimate(sender_account, _) => sender_account,
+			TrustedGetter::asset_balance(sender_account, _) => sender_account,
+			TrustedGetter::call_details(sender_account, _) => sender_account,
 			#[cfg(feature = "evm")]
 			TrustedGetter::evm_nonce(sender_account) => sender_account,
 			#[cfg(feature = "evm")]
@@ -149,6 +164,48 @@ impl ExecuteGetter for TrustedGetterSigned {
 				debug!("Account nonce is {}", nonce);
 				Some(nonce.encode())
 			},
+			TrustedGetter::events(who) => {
+				let events = get_stf_events_visible_to(&who);
+				debug!("TrustedGetter events");
+				debug!("{} events visible to {}", events.len(), account_id_to_string(&who));
+				Some(events.encode())
+			},
+			// Serves as credential of an event subscription, executed it behaves like `events`.
+			TrustedGetter::subscribe_events(who, _) => {
+				let events = get_stf_events_visible_to(&who);
+				debug!("TrustedGetter subscribe_events");
+				debug!("{} events visible to {}", events.len(), account_id_to_string(&who));
+				Some(events.encode())
+			},
+			TrustedGetter::fee_estimate(_who, call) => {
+				let fee = call_fee(&call, 0);
+				debug!("TrustedGetter fee_estimate");
+				debug!("Fee of {:?} is {}, without tip", call, fee);
+				Some(fee.encode())
+			},
+			TrustedGetter::asset_balance(who, asset_id) => {
+				let balance = Assets::balance(asset_id, &who);
+				debug!("TrustedGetter asset_balance");
+				debug!(
+					"Asset {} balance of {} is {}",
+					asset_id,
+					account_id_to_string(&who),
+					balance
+				);
+				Some(balance.encode())
+			},
+			// Only calls signed by `who` are found, so nobody else learns the details.
+			TrustedGetter::call_details(who, operation_hash) => {
+				let maybe_call = get_executed_call(&who, &operation_hash);
+				debug!("TrustedGetter call_details");
+				debug!(
+					"Call of {} in operation {:?} is {:?}",
+					account_id_to_string(&who),
+					operation_hash,
+					maybe_call
+				);
+				Some(maybe_call.encode())
+			},
 			#[cfg(feature = "evm")]
 			TrustedGetter::evm_nonce(who) => {
 				let evm_account = get_evm_account(&who);
@@ -44,9 +44,12 @@ pub use trusted_call::*;
 
 #[cfg(feature = "evm")]
 pub mod evm_helpers;
+pub mod executed_calls;
+pub mod fees;
 pub mod getter;
 pub mod hash;
 pub mod helpers;
+pub mod stf_events;
 pub mod stf_sgx;
 pub mod stf_sgx_primitives;
 #[cfg(all(feature = "test", feature = "sgx"))]
@@ -69,6 +72,8 @@ pub enum StfError {
 	Dispatch(String),
 	#[display(fmt = "Not enough funds to perform operation")]
 	MissingFunds,
+	#[display(fmt = "Not enough units of asset {} were shielded to unshield them", _0)]
+	MissingShieldedAssets(AssetId),
 	#[display(fmt = "Invalid Nonce {:?} != {:?}", _0, _1)]
 	InvalidNonce(Index, Index),
 	StorageHashMismatch,
@@ -0,0 +1,176 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+//! Events emitted by executed trusted calls.
+//!
+//! The events of a sidechain block are kept in the state, so they are part of the (encrypted)
+//! state diff of the block. They are reset at the beginning of each block.
+
+use crate::{AssetId, Balance};
+use codec::{Decode, Encode};
+use itp_stf_primitives::types::AccountId;
+use itp_storage::storage_value_key;
+use sp_core::H256;
+use std::prelude::v1::*;
+
+#[cfg(feature = "evm")]
+use ita_sgx_runtime::{EvmEvent, RuntimeEvent, System};
+#[cfg(feature = "evm")]
+use sp_core::H160;
+
+const STF_EVENTS_PREFIX: &str = "Stf";
+const STF_EVENTS_KEY: &str = "Events";
+
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+pub enum StfEvent {
+	BalanceSet {
+		who: AccountId,
+		free: Balance,
+		reserved: Balance,
+	},
+	Transfer {
+		from: AccountId,
+		to: AccountId,
+		amount: Balance,
+	},
+	Shielded {
+		who: AccountId,
+		amount: Balance,
+	},
+	Unshielded {
+		who: AccountId,
+		beneficiary: AccountId,
+		amount: Balance,
+	},
+	AssetCreated {
+		asset_id: AssetId,
+		owner: AccountId,
+	},
+	AssetIssued {
+		asset_id: AssetId,
+		who: AccountId,
+		amount: Balance,
+	},
+	AssetTransfer {
+		asset_id: AssetId,
+		from: AccountId,
+		to: AccountId,
+		amount: Balance,
+	},
+	AssetBurned {
+		asset_id: AssetId,
+		who: AccountId,
+		amount: Balance,
+	},
+	AssetShielded {
+		asset_id: AssetId,
+		who: AccountId,
+		amount: Balance,
+	},
+	AssetUnshielded {
+		asset_id: AssetId,
+		who: AccountId,
+		beneficiary: AccountId,
+		amount: Balance,
+	},
+	#[cfg(feature = "evm")]
+	EvmLog {
+		origin: AccountId,
+		address: H160,
+		topics: Vec<H256>,
+		data: Vec<u8>,
+	},
+}
+
+impl StfEvent {
+	/// Whether the holder of `account` is authorised to see this event.
+	pub fn is_visible_to(&self, account: &AccountId) -> bool {
+		match self {
+			StfEvent::BalanceSet { who, .. } => who == account,
+			StfEvent::Transfer { from, to, .. } => from == account || to == account,
+			StfEvent::Shielded { who, .. } => who == account,
+			StfEvent::Unshielded { who, .. } => who == account,
+			StfEvent::AssetCreated { owner, .. } => owner == account,
+			StfEvent::AssetIssued { who, .. } => who == account,
+			StfEvent::AssetTransfer { from, to, .. } => from == account || to == account,
+			StfEvent::AssetBurned { who, .. } => who == account,
+			StfEvent::AssetShielded { who, .. } => who == account,
+			StfEvent::AssetUnshielded { who, .. } => who == account,
+			#[cfg(feature = "evm")]
+			StfEvent::EvmLog { origin, .. } => origin == account,
+		}
+	}
+}
+
+/// An event together with the trusted call that emitted it.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+pub struct StfEventRecord {
+	/// Hash of the emitting trusted call, the same hash is used in the parentchain proofs.
+	pub call_hash: H256,
+	pub event: StfEvent,
+}
+
+/// Get all events emitted in the current sidechain block.
+pub fn get_stf_events() -> Vec<StfEventRecord> {
+	// Not read with `get_storage_value`, because an empty event list is the common case.
+	sp_io::storage::get(&stf_events_key())
+		.and_then(|encoded| Decode::decode(&mut encoded.as_slice()).ok())
+		.unwrap_or_default()
+}
+
+/// Get the events emitted in the current sidechain block that `account` is authorised to see.
+pub fn get_stf_events_visible_to(account: &AccountId) -> Vec<StfEventRecord> {
+	get_stf_events()
+		.into_iter()
+		.filter(|r| r.event.is_visible_to(account))
+		.collect()
+}
+
+pub fn deposit_stf_event(call_hash: H256, event: StfEvent) {
+	let mut events = get_stf_events();
+	events.push(StfEventRecord { call_hash, event });
+	sp_io::storage::set(&stf_events_key(), &events.encode());
+}
+
+pub fn reset_stf_events() {
+	sp_io::storage::clear(&stf_events_key());
+}
+
+fn stf_events_key() -> Vec<u8> {
+	storage_value_key(STF_EVENTS_PREFIX, STF_EVENTS_KEY)
+}
+
+/// Deposit the logs the EVM has emitted since the system event with index `first_event_index`.
+#[cfg(feature = "evm")]
+pub(crate) fn deposit_evm_logs(call_hash: H256, origin: &AccountId, first_event_index: u32) {
+	System::read_events_no_consensus()
+		.skip(first_event_index as usize)
+		.filter_map(|record| match record.event {
+			RuntimeEvent::Evm(EvmEvent::Log { log }) => Some(log),
+			_ => None,
+		})
+		.for_each(|log| {
+			deposit_stf_event(
+				call_hash,
+				StfEvent::EvmLog {
+					origin: origin.clone(),
+					address: log.address,
+					topics: log.topics,
+					data: log.data,
+				},
+			)
+		});
+}
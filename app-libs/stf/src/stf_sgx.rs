@@ -18,7 +18,10 @@
 #[cfg(feature = "test")]
 use crate::test_genesis::test_genesis_setup;
 
-use crate::{helpers::enclave_signer_account, Stf, StfError, ENCLAVE_ACCOUNT_KEY};
+use crate::{
+	fees::can_pay_call_fee, helpers::enclave_signer_account, stf_events::reset_stf_events, Stf,
+	StfError, TrustedCallSigned, ENCLAVE_ACCOUNT_KEY,
+};
 use codec::Encode;
 use frame_support::traits::{OriginTrait, UnfilteredDispatchable};
 use itp_node_api::metadata::{provider::AccessNodeMetadata, NodeMetadataTrait};
@@ -27,6 +30,7 @@ use itp_stf_interface::{
 	parentchain_pallet::ParentchainPalletInterface,
 	sudo_pallet::SudoPalletInterface,
 	system_pallet::{SystemPalletAccountInterface, SystemPalletEventInterface},
+	transaction_payment_pallet::TransactionPaymentPalletInterface,
 	ExecuteCall, ExecuteGetter, InitState, StateCallInterface, StateGetterInterface, UpdateState,
 };
 use itp_stf_primitives::types::ShardIdentifier;
@@ -220,7 +224,20 @@ where
 	}
 
 	fn reset_events(state: &mut State) {
-		state.execute_with(|| frame_system::Pallet::<Runtime>::reset_events())
+		state.execute_with(|| {
+			frame_system::Pallet::<Runtime>::reset_events();
+			reset_stf_events();
+		})
+	}
+}
+
+impl<Getter, State, Runtime> TransactionPaymentPalletInterface<State, TrustedCallSigned>
+	for Stf<TrustedCallSigned, Getter, State, Runtime>
+where
+	State: SgxExternalitiesTrait,
+{
+	fn can_pay_fee(state: &mut State, call: &TrustedCallSigned) -> bool {
+		state.execute_with(|| can_pay_call_fee(call))
 	}
 }
 
@@ -15,14 +15,24 @@
 
 */
 
-use crate::{Getter, State, Stf, TrustedCall, TrustedCallSigned};
-use ita_sgx_runtime::Runtime;
-use itp_node_api::metadata::{metadata_mocks::NodeMetadataMock, provider::NodeMetadataRepository};
+use crate::{
+	executed_calls::get_executed_call,
+	hash::Hash,
+	stf_events::{get_stf_events, get_stf_events_visible_to, StfEvent},
+	Getter, State, Stf, TrustedCall, TrustedCallSigned, TrustedOperation,
+};
+use ita_sgx_runtime::{Assets, Runtime};
+use itp_node_api::metadata::{
+	metadata_mocks::NodeMetadataMock, pallet_teerex::TeerexCallIndexes,
+	provider::NodeMetadataRepository,
+};
+use itp_sgx_externalities::SgxExternalitiesTrait;
 use itp_stf_interface::{
-	sudo_pallet::SudoPalletInterface, system_pallet::SystemPalletAccountInterface, InitState,
-	StateCallInterface,
+	sudo_pallet::SudoPalletInterface,
+	system_pallet::{SystemPalletAccountInterface, SystemPalletEventInterface},
+	InitState, StateCallInterface,
 };
-use itp_stf_primitives::types::{AccountId, Signature};
+use itp_stf_primitives::types::{AccountId, ShardIdentifier, Signature};
 use sp_core::{
 	ed25519::{Pair as Ed25519Pair, Signature as Ed25519Signature},
 	Pair,
@@ -70,3 +80,134 @@ pub fn test_root_account_exists_after_initialization() {
 	let account_data = StfState::get_account_data(&mut state, &root_account);
 	assert!(account_data.free > 0);
 }
+
+pub fn shield_funds_emits_event_visible_to_beneficiary() {
+	let enclave_call_signer = Ed25519Pair::from_seed(b"14672678901234567890123456789012");
+	let mut state = StfState::init_state(enclave_call_signer.public().into());
+	let beneficiary = AccountId::new([1u8; 32]);
+	let other_account = AccountId::new([3u8; 32]);
+
+	let shield_funds_call = TrustedCallSigned::new(
+		TrustedCall::balance_shield(
+			enclave_call_signer.public().into(),
+			beneficiary.clone(),
+			500u128,
+		),
+		0,
+		Signature::Ed25519(Ed25519Signature([0u8; 64])),
+	);
+
+	let repo = Arc::new(NodeMetadataRepository::new(NodeMetadataMock::new()));
+	StfState::execute_call(&mut state, shield_funds_call, &mut Vec::new(), repo).unwrap();
+
+	let visible_events = state.execute_with(|| get_stf_events_visible_to(&beneficiary));
+	assert_eq!(1, visible_events.len());
+	assert_eq!(StfEvent::Shielded { who: beneficiary, amount: 500u128 }, visible_events[0].event);
+	assert!(state.execute_with(|| get_stf_events_visible_to(&other_account)).is_empty());
+
+	StfState::reset_events(&mut state);
+	assert!(state.execute_with(get_stf_events).is_empty());
+}
+
+pub fn executed_call_is_only_found_for_its_signer() {
+	let enclave_call_signer = Ed25519Pair::from_seed(b"14672678901234567890123456789012");
+	let signer: AccountId = enclave_call_signer.public().into();
+	let mut state = StfState::init_state(signer.clone());
+	let beneficiary = AccountId::new([1u8; 32]);
+
+	let shield_funds_call = TrustedCallSigned::new(
+		TrustedCall::balance_shield(signer.clone(), beneficiary.clone(), 500u128),
+		0,
+		Signature::Ed25519(Ed25519Signature([0u8; 64])),
+	);
+	let operation_hash = TrustedOperation::indirect_call(shield_funds_call.clone()).hash();
+
+	let repo = Arc::new(NodeMetadataRepository::new(NodeMetadataMock::new()));
+	StfState::execute_call(&mut state, shield_funds_call.clone(), &mut Vec::new(), repo).unwrap();
+
+	assert_eq!(
+		Some(shield_funds_call),
+		state.execute_with(|| get_executed_call(&signer, &operation_hash))
+	);
+	assert!(state
+		.execute_with(|| get_executed_call(&beneficiary, &operation_hash))
+		.is_none());
+}
+
+pub fn shield_transfer_and_unshield_assets_works() {
+	let enclave_call_signer = Ed25519Pair::from_seed(b"14672678901234567890123456789012");
+	let enclave_account: AccountId = enclave_call_signer.public().into();
+	let mut state = StfState::init_state(enclave_account.clone());
+	let root = StfState::get_root(&mut state);
+	let owner = AccountId::new([1u8; 32]);
+	let receiver = AccountId::new([3u8; 32]);
+	let parentchain_beneficiary = AccountId::new([4u8; 32]);
+	let asset_id = 7;
+
+	let repo = Arc::new(NodeMetadataRepository::new(NodeMetadataMock::new()));
+	let mut calls = Vec::new();
+	let mut execute = |call: TrustedCall, nonce| {
+		let signed_call =
+			TrustedCallSigned::new(call, nonce, Signature::Ed25519(Ed25519Signature([0u8; 64])));
+		StfState::execute_call(&mut state, signed_call, &mut calls, repo.clone()).unwrap();
+	};
+
+	execute(TrustedCall::assets_create(root, owner.clone(), asset_id, 1), 0);
+	// The owner pays the fees of its calls with shielded native funds.
+	execute(TrustedCall::balance_shield(enclave_account.clone(), owner.clone(), 100_000), 0);
+	execute(TrustedCall::assets_shield(enclave_account, owner.clone(), asset_id, 500), 1);
+	execute(TrustedCall::assets_transfer(owner.clone(), receiver.clone(), asset_id, 100), 0);
+	execute(
+		TrustedCall::assets_unshield(
+			owner.clone(),
+			parentchain_beneficiary,
+			asset_id,
+			200,
+			ShardIdentifier::default(),
+		),
+		1,
+	);
+
+	assert_eq!(200, state.execute_with(|| Assets::balance(asset_id, &owner)));
+	assert_eq!(100, state.execute_with(|| Assets::balance(asset_id, &receiver)));
+	let unshield_call_indexes = NodeMetadataMock::new().unshield_assets_call_indexes().unwrap();
+	assert!(calls.last().unwrap().0.starts_with(&unshield_call_indexes));
+}
+
+pub fn unshielding_assets_minted_on_the_sidechain_fails() {
+	let enclave_call_signer = Ed25519Pair::from_seed(b"14672678901234567890123456789012");
+	let enclave_account: AccountId = enclave_call_signer.public().into();
+	let mut state = StfState::init_state(enclave_account.clone());
+	let root = StfState::get_root(&mut state);
+	let owner = AccountId::new([1u8; 32]);
+	let asset_id = 7;
+
+	let repo = Arc::new(NodeMetadataRepository::new(NodeMetadataMock::new()));
+	let mut calls = Vec::new();
+	let mut execute = |call: TrustedCall, nonce| {
+		let signed_call =
+			TrustedCallSigned::new(call, nonce, Signature::Ed25519(Ed25519Signature([0u8; 64])));
+		StfState::execute_call(&mut state, signed_call, &mut calls, repo.clone())
+	};
+
+	execute(TrustedCall::assets_create(root, owner.clone(), asset_id, 1), 0).unwrap();
+	execute(TrustedCall::balance_shield(enclave_account.clone(), owner.clone(), 100_000), 0)
+		.unwrap();
+	execute(TrustedCall::assets_shield(enclave_account, owner.clone(), asset_id, 500), 1).unwrap();
+	// The owner is the issuer of the asset, so it can mint units that are not backed.
+	execute(TrustedCall::assets_mint(owner.clone(), owner.clone(), asset_id, 1000), 0).unwrap();
+
+	let unshield = |amount| {
+		TrustedCall::assets_unshield(
+			owner.clone(),
+			AccountId::new([4u8; 32]),
+			asset_id,
+			amount,
+			ShardIdentifier::default(),
+		)
+	};
+	// The nonce is incremented even if the call fails.
+	assert!(execute(unshield(600), 1).is_err());
+	execute(unshield(500), 2).unwrap();
+	assert!(execute(unshield(1), 3).is_err());
+}
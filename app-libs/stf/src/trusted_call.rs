@@ -16,21 +16,30 @@
 */
 
 #[cfg(feature = "evm")]
-use sp_core::{H160, H256, U256};
+use sp_core::{H160, U256};
 
 #[cfg(feature = "evm")]
 use std::vec::Vec;
 
-use crate::{helpers::ensure_enclave_signer_account, StfError, TrustedOperation};
+use crate::{
+	executed_calls::record_executed_call,
+	fees::charge_call_fee,
+	helpers::ensure_enclave_signer_account,
+	stf_events::{deposit_stf_event, StfEvent},
+	StfError, TrustedOperation,
+};
 use codec::{Decode, Encode};
-use frame_support::{ensure, traits::UnfilteredDispatchable};
-pub use ita_sgx_runtime::{Balance, Index};
-use ita_sgx_runtime::{Runtime, System};
+use frame_support::{
+	ensure,
+	traits::{tokens::fungibles, UnfilteredDispatchable},
+};
+pub use ita_sgx_runtime::{AssetId, Balance, Index};
+use ita_sgx_runtime::{Assets, FeeTreasury, Runtime, ShieldedAssetSupply, System};
 use itp_node_api::metadata::{provider::AccessNodeMetadata, NodeMetadataTrait};
 use itp_node_api_metadata::pallet_teerex::TeerexCallIndexes;
 use itp_stf_interface::ExecuteCall;
 use itp_stf_primitives::types::{AccountId, KeyPair, ShardIdentifier, Signature};
-use itp_types::OpaqueCall;
+use itp_types::{OpaqueCall, H256};
 use itp_utils::stringify::account_id_to_string;
 use log::*;
 use sp_io::hashing::blake2_256;
@@ -43,6 +52,9 @@ use ita_sgx_runtime::{AddressMapping, HashedAddressMapping};
 #[cfg(feature = "evm")]
 use crate::evm_helpers::{create_code_hash, evm_create2_address, evm_create_address};
 
+#[cfg(feature = "evm")]
+use crate::stf_events::deposit_evm_logs;
+
 #[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
 #[allow(non_camel_case_types)]
 pub enum TrustedCall {
@@ -50,6 +62,13 @@ pub enum TrustedCall {
 	balance_transfer(AccountId, AccountId, Balance),
 	balance_unshield(AccountId, AccountId, Balance, ShardIdentifier), // (AccountIncognito, BeneficiaryPublicAccount, Amount, Shard)
 	balance_shield(AccountId, AccountId, Balance), // (Root, AccountIncognito, Amount)
+	fees_set_treasury(AccountId, AccountId),       // (Root, Treasury)
+	assets_create(AccountId, AccountId, AssetId, Balance), // (Root, Owner, AssetId, MinBalance)
+	assets_mint(AccountId, AccountId, AssetId, Balance), // (Issuer, Beneficiary, AssetId, Amount)
+	assets_transfer(AccountId, AccountId, AssetId, Balance), // (From, To, AssetId, Amount)
+	assets_burn(AccountId, AccountId, AssetId, Balance), // (Admin, Who, AssetId, Amount)
+	assets_unshield(AccountId, AccountId, AssetId, Balance, ShardIdentifier), // (AccountIncognito, BeneficiaryPublicAccount, AssetId, Amount, Shard)
+	assets_shield(AccountId, AccountId, AssetId, Balance), // (Root, AccountIncognito, AssetId, Amount)
 	#[cfg(feature = "evm")]
 	evm_withdraw(AccountId, H160, Balance), // (Origin, Address EVM Account, Value)
 	// (Origin, Source, Target, Input, Value, Gas limit, Max fee per gas, Max priority fee per gas, Nonce, Access list)
@@ -102,6 +121,13 @@ impl TrustedCall {
 			TrustedCall::balance_transfer(sender_account, ..) => sender_account,
 			TrustedCall::balance_unshield(sender_account, ..) => sender_account,
 			TrustedCall::balance_shield(sender_account, ..) => sender_account,
+			TrustedCall::fees_set_treasury(sender_account, ..) => sender_account,
+			TrustedCall::assets_create(sender_account, ..) => sender_account,
+			TrustedCall::assets_mint(sender_account, ..) => sender_account,
+			TrustedCall::assets_transfer(sender_account, ..) => sender_account,
+			TrustedCall::assets_burn(sender_account, ..) => sender_account,
+			TrustedCall::assets_unshield(sender_account, ..) => sender_account,
+			TrustedCall::assets_shield(sender_account, ..) => sender_account,
 			#[cfg(feature = "evm")]
 			TrustedCall::evm_withdraw(sender_account, ..) => sender_account,
 			#[cfg(feature = "evm")]
@@ -120,12 +146,26 @@ impl TrustedCall {
 		mrenclave: &[u8; 32],
 		shard: &ShardIdentifier,
 	) -> TrustedCallSigned {
-		let mut payload = self.encode();
-		payload.append(&mut nonce.encode());
-		payload.append(&mut mrenclave.encode());
-		payload.append(&mut shard.encode());
+		self.sign_with_tip(pair, nonce, None, mrenclave, shard)
+	}
+
+	/// Sign the call with an optional tip, which raises its priority in the TOP pool.
+	pub fn sign_with_tip(
+		&self,
+		pair: &KeyPair,
+		nonce: Index,
+		tip: Option<Balance>,
+		mrenclave: &[u8; 32],
+		shard: &ShardIdentifier,
+	) -> TrustedCallSigned {
+		let payload = signature_payload(self, nonce, tip, mrenclave, shard);
 
-		TrustedCallSigned { call: self.clone(), nonce, signature: pair.sign(payload.as_slice()) }
+		TrustedCallSigned {
+			call: self.clone(),
+			nonce,
+			tip,
+			signature: pair.sign(payload.as_slice()),
+		}
 	}
 }
 
@@ -133,22 +173,25 @@ impl TrustedCall {
 pub struct TrustedCallSigned {
 	pub call: TrustedCall,
 	pub nonce: Index,
+	pub tip: Option<Balance>,
 	pub signature: Signature,
 }
 
 impl TrustedCallSigned {
 	pub fn new(call: TrustedCall, nonce: Index, signature: Signature) -> Self {
-		TrustedCallSigned { call, nonce, signature }
+		TrustedCallSigned { call, nonce, tip: None, signature }
 	}
 
 	pub fn verify_signature(&self, mrenclave: &[u8; 32], shard: &ShardIdentifier) -> bool {
-		let mut payload = self.call.encode();
-		payload.append(&mut self.nonce.encode());
-		payload.append(&mut mrenclave.encode());
-		payload.append(&mut shard.encode());
+		let payload = signature_payload(&self.call, self.nonce, self.tip, mrenclave, shard);
 		self.signature.verify(payload.as_slice(), self.call.sender_account())
 	}
 
+	/// The tip offered by the sender, zero if none is given.
+	pub fn tip(&self) -> Balance {
+		self.tip.unwrap_or_default()
+	}
+
 	pub fn into_trusted_operation(self, direct: bool) -> TrustedOperation {
 		match direct {
 			true => TrustedOperation::direct_call(self),
@@ -157,6 +200,25 @@ impl TrustedCallSigned {
 	}
 }
 
+/// The tip is only part of the payload if one is given, so signatures of calls
+/// without a tip stay the same as before tips were introduced.
+fn signature_payload(
+	call: &TrustedCall,
+	nonce: Index,
+	tip: Option<Balance>,
+	mrenclave: &[u8; 32],
+	shard: &ShardIdentifier,
+) -> Vec<u8> {
+	let mut payload = call.encode();
+	payload.append(&mut nonce.encode());
+	if let Some(tip) = tip {
+		payload.append(&mut tip.encode());
+	}
+	payload.append(&mut mrenclave.encode());
+	payload.append(&mut shard.encode());
+	payload
+}
+
 // TODO: #91 signed return value
 /*
 pub struct TrustedReturnValue<T> {
@@ -181,6 +243,7 @@ where
 	) -> Result<(), Self::Error> {
 		let sender = self.call.sender_account().clone();
 		let call_hash = blake2_256(&self.call.encode());
+		let event_call_hash = H256::from(call_hash);
 		let system_nonce = System::account_nonce(&sender);
 		ensure!(self.nonce == system_nonce, Self::Error::InvalidNonce(self.nonce, system_nonce));
 
@@ -189,6 +252,12 @@ where
 		// so it should be considered as valid
 		System::inc_account_nonce(&sender);
 
+		// The fee is kept as well, even if the call fails.
+		charge_call_fee(&self)?;
+
+		// Only recorded once it succeeded, just like it is only then included in a block.
+		let executed_call = self.clone();
+
 		match self.call {
 			TrustedCall::balance_set_balance(root, who, free_balance, reserved_balance) => {
 				ensure!(is_root::<Runtime, AccountId>(&root), Self::Error::MissingPrivileges(root));
@@ -199,7 +268,7 @@ where
 					reserved_balance
 				);
 				ita_sgx_runtime::BalancesCall::<Runtime>::set_balance {
-					who: MultiAddress::Id(who),
+					who: MultiAddress::Id(who.clone()),
 					new_free: free_balance,
 					new_reserved: reserved_balance,
 				}
@@ -207,6 +276,10 @@ where
 				.map_err(|e| {
 					Self::Error::Dispatch(format!("Balance Set Balance error: {:?}", e.error))
 				})?;
+				deposit_stf_event(
+					event_call_hash,
+					StfEvent::BalanceSet { who, free: free_balance, reserved: reserved_balance },
+				);
 				// This explicit Error type is somehow still needed, otherwise the compiler complains
 				// 	multiple `impl`s satisfying `StfError: std::convert::From<_>`
 				// 		note: and another `impl` found in the `core` crate: `impl<T> std::convert::From<T> for T;`
@@ -225,13 +298,14 @@ where
 					value
 				);
 				ita_sgx_runtime::BalancesCall::<Runtime>::transfer {
-					dest: MultiAddress::Id(to),
+					dest: MultiAddress::Id(to.clone()),
 					value,
 				}
 				.dispatch_bypass_filter(origin)
 				.map_err(|e| {
 					Self::Error::Dispatch(format!("Balance Transfer error: {:?}", e.error))
 				})?;
+				deposit_stf_event(event_call_hash, StfEvent::Transfer { from, to, amount: value });
 				Ok(())
 			},
 			TrustedCall::balance_unshield(account_incognito, beneficiary, value, shard) => {
@@ -242,20 +316,24 @@ where
 					value,
 					shard
 				);
-				unshield_funds(account_incognito, value)?;
+				unshield_funds(account_incognito.clone(), value)?;
 				calls.push(OpaqueCall::from_tuple(&(
 					node_metadata_repo.get_from_metadata(|m| m.unshield_funds_call_indexes())??,
-					beneficiary,
+					beneficiary.clone(),
 					value,
 					shard,
 					call_hash,
 				)));
+				deposit_stf_event(
+					event_call_hash,
+					StfEvent::Unshielded { who: account_incognito, beneficiary, amount: value },
+				);
 				Ok(())
 			},
 			TrustedCall::balance_shield(enclave_account, who, value) => {
 				ensure_enclave_signer_account(&enclave_account)?;
 				debug!("balance_shield({}, {})", account_id_to_string(&who), value);
-				shield_funds(who, value)?;
+				shield_funds(who.clone(), value)?;
 
 				// Send proof of execution on chain.
 				calls.push(OpaqueCall::from_tuple(&(
@@ -264,6 +342,151 @@ where
 					Vec::<itp_types::H256>::new(),
 					b"shielded some funds!".to_vec(),
 				)));
+				deposit_stf_event(event_call_hash, StfEvent::Shielded { who, amount: value });
+				Ok(())
+			},
+			TrustedCall::fees_set_treasury(root, treasury) => {
+				ensure!(is_root::<Runtime, AccountId>(&root), Self::Error::MissingPrivileges(root));
+				debug!("fees_set_treasury({})", account_id_to_string(&treasury));
+				FeeTreasury::put(treasury);
+				Ok(())
+			},
+			TrustedCall::assets_create(root, owner, asset_id, min_balance) => {
+				ensure!(is_root::<Runtime, AccountId>(&root), Self::Error::MissingPrivileges(root));
+				debug!(
+					"assets_create({}, {}, {})",
+					account_id_to_string(&owner),
+					asset_id,
+					min_balance
+				);
+				// Sufficient, so incognito accounts can hold the asset without a native balance.
+				ita_sgx_runtime::AssetsCall::<Runtime>::force_create {
+					id: asset_id.into(),
+					owner: MultiAddress::Id(owner),
+					is_sufficient: true,
+					min_balance,
+				}
+				.dispatch_bypass_filter(ita_sgx_runtime::RuntimeOrigin::root())
+				.map_err(|e| {
+					Self::Error::Dispatch(format!("Assets Create error: {:?}", e.error))
+				})?;
+				deposit_stf_event(event_call_hash, StfEvent::AssetCreated { asset_id, owner });
+				Ok(())
+			},
+			TrustedCall::assets_mint(issuer, beneficiary, asset_id, amount) => {
+				debug!(
+					"assets_mint({}, {}, {}, {})",
+					account_id_to_string(&issuer),
+					account_id_to_string(&beneficiary),
+					asset_id,
+					amount
+				);
+				ita_sgx_runtime::AssetsCall::<Runtime>::mint {
+					id: asset_id.into(),
+					beneficiary: MultiAddress::Id(beneficiary.clone()),
+					amount,
+				}
+				.dispatch_bypass_filter(ita_sgx_runtime::RuntimeOrigin::signed(issuer))
+				.map_err(|e| Self::Error::Dispatch(format!("Assets Mint error: {:?}", e.error)))?;
+				deposit_stf_event(
+					event_call_hash,
+					StfEvent::AssetIssued { asset_id, who: beneficiary, amount },
+				);
+				Ok(())
+			},
+			TrustedCall::assets_transfer(from, to, asset_id, amount) => {
+				debug!(
+					"assets_transfer({}, {}, {}, {})",
+					account_id_to_string(&from),
+					account_id_to_string(&to),
+					asset_id,
+					amount
+				);
+				ita_sgx_runtime::AssetsCall::<Runtime>::transfer {
+					id: asset_id.into(),
+					target: MultiAddress::Id(to.clone()),
+					amount,
+				}
+				.dispatch_bypass_filter(ita_sgx_runtime::RuntimeOrigin::signed(from.clone()))
+				.map_err(|e| {
+					Self::Error::Dispatch(format!("Assets Transfer error: {:?}", e.error))
+				})?;
+				deposit_stf_event(
+					event_call_hash,
+					StfEvent::AssetTransfer { asset_id, from, to, amount },
+				);
+				Ok(())
+			},
+			TrustedCall::assets_burn(admin, who, asset_id, amount) => {
+				debug!(
+					"assets_burn({}, {}, {}, {})",
+					account_id_to_string(&admin),
+					account_id_to_string(&who),
+					asset_id,
+					amount
+				);
+				ita_sgx_runtime::AssetsCall::<Runtime>::burn {
+					id: asset_id.into(),
+					who: MultiAddress::Id(who.clone()),
+					amount,
+				}
+				.dispatch_bypass_filter(ita_sgx_runtime::RuntimeOrigin::signed(admin))
+				.map_err(|e| Self::Error::Dispatch(format!("Assets Burn error: {:?}", e.error)))?;
+				deposit_stf_event(event_call_hash, StfEvent::AssetBurned { asset_id, who, amount });
+				Ok(())
+			},
+			TrustedCall::assets_unshield(
+				account_incognito,
+				beneficiary,
+				asset_id,
+				amount,
+				shard,
+			) => {
+				debug!(
+					"assets_unshield({}, {}, {}, {}, {})",
+					account_id_to_string(&account_incognito),
+					account_id_to_string(&beneficiary),
+					asset_id,
+					amount,
+					shard
+				);
+				unshield_assets(&account_incognito, asset_id, amount)?;
+				calls.push(OpaqueCall::from_tuple(&(
+					node_metadata_repo
+						.get_from_metadata(|m| m.unshield_assets_call_indexes())??,
+					beneficiary.clone(),
+					asset_id,
+					amount,
+					shard,
+					call_hash,
+				)));
+				deposit_stf_event(
+					event_call_hash,
+					StfEvent::AssetUnshielded {
+						asset_id,
+						who: account_incognito,
+						beneficiary,
+						amount,
+					},
+				);
+				Ok(())
+			},
+			TrustedCall::assets_shield(enclave_account, who, asset_id, amount) => {
+				ensure_enclave_signer_account(&enclave_account)?;
+				debug!("assets_shield({}, {}, {})", account_id_to_string(&who), asset_id, amount);
+				shield_assets(&who, asset_id, amount)?;
+
+				// Send proof of execution on chain.
+				calls.push(OpaqueCall::from_tuple(&(
+					node_metadata_repo.get_from_metadata(|m| m.publish_hash_call_indexes())??,
+					call_hash,
+					Vec::<itp_types::H256>::new(),
+					b"shielded some assets!".to_vec(),
+				)));
+				deposit_stf_event(
+					event_call_hash,
+					StfEvent::AssetShielded { asset_id, who, amount },
+				);
 				Ok(())
 			},
 			#[cfg(feature = "evm")]
@@ -295,6 +518,7 @@ where
 					source,
 					target
 				);
+				let first_event_index = System::event_count();
 				ita_sgx_runtime::EvmCall::<Runtime>::call {
 					source,
 					target,
@@ -306,8 +530,9 @@ where
 					nonce,
 					access_list,
 				}
-				.dispatch_bypass_filter(ita_sgx_runtime::RuntimeOrigin::signed(from))
+				.dispatch_bypass_filter(ita_sgx_runtime::RuntimeOrigin::signed(from.clone()))
 				.map_err(|e| Self::Error::Dispatch(format!("Evm Call error: {:?}", e.error)))?;
+				deposit_evm_logs(event_call_hash, &from, first_event_index);
 				Ok(())
 			},
 			#[cfg(feature = "evm")]
@@ -330,6 +555,7 @@ where
 				);
 				let nonce_evm_account =
 					System::account_nonce(&HashedAddressMapping::into_account_id(source));
+				let first_event_index = System::event_count();
 				ita_sgx_runtime::EvmCall::<Runtime>::create {
 					source,
 					init,
@@ -340,8 +566,9 @@ where
 					nonce,
 					access_list,
 				}
-				.dispatch_bypass_filter(ita_sgx_runtime::RuntimeOrigin::signed(from))
+				.dispatch_bypass_filter(ita_sgx_runtime::RuntimeOrigin::signed(from.clone()))
 				.map_err(|e| Self::Error::Dispatch(format!("Evm Create error: {:?}", e.error)))?;
+				deposit_evm_logs(event_call_hash, &from, first_event_index);
 				let contract_address = evm_create_address(source, nonce_evm_account);
 				info!("Trying to create evm contract with address {:?}", contract_address);
 				Ok(())
@@ -366,6 +593,7 @@ where
 					value
 				);
 				let code_hash = create_code_hash(&init);
+				let first_event_index = System::event_count();
 				ita_sgx_runtime::EvmCall::<Runtime>::create2 {
 					source,
 					init,
@@ -377,13 +605,15 @@ where
 					nonce,
 					access_list,
 				}
-				.dispatch_bypass_filter(ita_sgx_runtime::RuntimeOrigin::signed(from))
+				.dispatch_bypass_filter(ita_sgx_runtime::RuntimeOrigin::signed(from.clone()))
 				.map_err(|e| Self::Error::Dispatch(format!("Evm Create2 error: {:?}", e.error)))?;
+				deposit_evm_logs(event_call_hash, &from, first_event_index);
 				let contract_address = evm_create2_address(source, salt, code_hash);
 				info!("Trying to create evm contract with address {:?}", contract_address);
 				Ok(())
 			},
 		}?;
+		record_executed_call(executed_call);
 		Ok(())
 	}
 
@@ -394,6 +624,13 @@ where
 			TrustedCall::balance_transfer(_, _, _) => debug!("No storage updates needed..."),
 			TrustedCall::balance_unshield(_, _, _, _) => debug!("No storage updates needed..."),
 			TrustedCall::balance_shield(_, _, _) => debug!("No storage updates needed..."),
+			TrustedCall::fees_set_treasury(_, _) => debug!("No storage updates needed..."),
+			TrustedCall::assets_create(..) => debug!("No storage updates needed..."),
+			TrustedCall::assets_mint(..) => debug!("No storage updates needed..."),
+			TrustedCall::assets_transfer(..) => debug!("No storage updates needed..."),
+			TrustedCall::assets_burn(..) => debug!("No storage updates needed..."),
+			TrustedCall::assets_unshield(..) => debug!("No storage updates needed..."),
+			TrustedCall::assets_shield(..) => debug!("No storage updates needed..."),
 			#[cfg(feature = "evm")]
 			_ => debug!("No storage updates needed..."),
 		};
@@ -430,6 +667,33 @@ fn shield_funds(account: AccountId, amount: u128) -> Result<(), StfError> {
 	Ok(())
 }
 
+fn unshield_assets(
+	account: &AccountId,
+	asset_id: AssetId,
+	amount: Balance,
+) -> Result<(), StfError> {
+	if Assets::balance(asset_id, account) < amount {
+		return Err(StfError::MissingFunds)
+	}
+	// Units minted on the sidechain are not backed by the parentchain vault.
+	let shielded_supply = ShieldedAssetSupply::get(asset_id);
+	if shielded_supply < amount {
+		return Err(StfError::MissingShieldedAssets(asset_id))
+	}
+
+	<Assets as fungibles::Mutate<AccountId>>::burn_from(asset_id, account, amount)
+		.map_err(|e| StfError::Dispatch(format!("Unshield assets error: {:?}", e)))?;
+	ShieldedAssetSupply::insert(asset_id, shielded_supply - amount);
+	Ok(())
+}
+
+fn shield_assets(account: &AccountId, asset_id: AssetId, amount: Balance) -> Result<(), StfError> {
+	<Assets as fungibles::Mutate<AccountId>>::mint_into(asset_id, account, amount)
+		.map_err(|e| StfError::Dispatch(format!("Shield assets error: {:?}", e)))?;
+	ShieldedAssetSupply::mutate(asset_id, |supply| *supply = supply.saturating_add(amount));
+	Ok(())
+}
+
 fn is_root<Runtime, AccountId>(account: &AccountId) -> bool
 where
 	Runtime: frame_system::Config<AccountId = AccountId> + pallet_sudo::Config,
@@ -465,4 +729,32 @@ mod tests {
 
 		assert!(signed_call.verify_signature(&mrenclave, &shard));
 	}
+
+	#[test]
+	fn tip_is_covered_by_signature() {
+		let mrenclave = [0u8; 32];
+		let shard = ShardIdentifier::default();
+
+		let call = TrustedCall::balance_transfer(
+			AccountKeyring::Alice.public().into(),
+			AccountKeyring::Bob.public().into(),
+			42,
+		);
+		let mut signed_call = call.sign_with_tip(
+			&KeyPair::Sr25519(Box::new(AccountKeyring::Alice.pair())),
+			3,
+			Some(10),
+			&mrenclave,
+			&shard,
+		);
+
+		assert!(signed_call.verify_signature(&mrenclave, &shard));
+		assert_eq!(10, signed_call.tip());
+
+		signed_call.tip = Some(1000);
+		assert!(!signed_call.verify_signature(&mrenclave, &shard));
+
+		signed_call.tip = None;
+		assert!(!signed_call.verify_signature(&mrenclave, &shard));
+	}
 }
@@ -72,6 +72,9 @@ AMOUNT_SHIELD=$(( 6 * UNIT ))
 AMOUNT_TRANSFER=$(( 2 * UNIT ))
 AMOUNT_UNSHIELD=$(( 1 * UNIT ))
 
+# upper bound of the fee a single trusted call is charged
+MAX_FEE=1000000
+
 CLIENT="${CLIENT_BIN} -p ${NPORT} -P ${WORKER1PORT} -u ${NODEURL} -U ${WORKER1URL}"
 
 # offchain-worker only suppports indirect calls
@@ -107,6 +110,25 @@ function wait_assert_state()
     exit 1
 }
 
+# Poll and assert the given account's balance is equal to expected, minus the fees
+# of at most <max-number-of-fees> trusted calls,
+# with timeout WAIT_INTERVAL_SECONDS * WAIT_ROUNDS
+# usage:
+#   wait_assert_balance_minus_fees <mrenclave> <account> <expected-balance> <max-number-of-fees>
+function wait_assert_balance_minus_fees()
+{
+    for i in $(seq 1 $WAIT_ROUNDS); do
+        sleep $WAIT_INTERVAL_SECONDS
+        balance=$(${CLIENT} trusted --mrenclave "$1" balance "$2")
+        if [ $balance -le "$3" ] && [ $balance -ge $(( $3 - $4 * MAX_FEE )) ]; then
+            return
+        fi
+    done
+    echo
+    echo "Assert $2 balance failed, expected = $3 minus at most $4 fees, actual = $balance"
+    exit 1
+}
+
 # Do a live query and assert the given account's state is equal to expected
 # usage:
 #   assert_state <mrenclave> <account> <state-name> <expected-state>
@@ -158,8 +180,9 @@ case $TEST in
     first)
         wait_assert_state ${MRENCLAVE} ${ICGACCOUNTALICE} balance 0 ;;
     second)
-        wait_assert_state ${MRENCLAVE} ${ICGACCOUNTALICE} balance $(( AMOUNT_SHIELD - AMOUNT_TRANSFER - AMOUNT_UNSHIELD ))
-        BALANCE_INCOGNITO_ALICE=$(( AMOUNT_SHIELD - AMOUNT_TRANSFER - AMOUNT_UNSHIELD )) ;;
+        # Alice paid the fees of the transfer and the unshielding in the first round
+        wait_assert_balance_minus_fees ${MRENCLAVE} ${ICGACCOUNTALICE} $(( AMOUNT_SHIELD - AMOUNT_TRANSFER - AMOUNT_UNSHIELD )) 2
+        BALANCE_INCOGNITO_ALICE=$(${CLIENT} trusted --mrenclave ${MRENCLAVE} balance ${ICGACCOUNTALICE}) ;;
     *)
         echo "unsupported test mode"
         exit 1 ;;
@@ -183,7 +206,7 @@ $CLIENT trusted --mrenclave ${MRENCLAVE} transfer ${ICGACCOUNTALICE} ${ICGACCOUN
 echo ""
 
 echo "* Wait and assert Alice's incognito account balance... "
-wait_assert_state ${MRENCLAVE} ${ICGACCOUNTALICE} balance $(( BALANCE_INCOGNITO_ALICE + AMOUNT_SHIELD - AMOUNT_TRANSFER ))
+wait_assert_balance_minus_fees ${MRENCLAVE} ${ICGACCOUNTALICE} $(( BALANCE_INCOGNITO_ALICE + AMOUNT_SHIELD - AMOUNT_TRANSFER )) 1
 echo "✔ ok"
 
 echo "* Wait and assert Bob's incognito account balance... "
@@ -196,7 +219,7 @@ ${CLIENT} trusted --mrenclave ${MRENCLAVE} --xt-signer //Alice unshield-funds ${
 echo ""
 
 echo "* Wait and assert Alice's incognito account balance... "
-wait_assert_state ${MRENCLAVE} ${ICGACCOUNTALICE} balance $(( BALANCE_INCOGNITO_ALICE + AMOUNT_SHIELD - AMOUNT_TRANSFER - AMOUNT_UNSHIELD ))
+wait_assert_balance_minus_fees ${MRENCLAVE} ${ICGACCOUNTALICE} $(( BALANCE_INCOGNITO_ALICE + AMOUNT_SHIELD - AMOUNT_TRANSFER - AMOUNT_UNSHIELD )) 2
 echo "✔ ok"
 
 echo "* Wait and assert Bob's incognito account balance... "
@@ -248,8 +271,8 @@ echo "✔ ok"
 echo ""
 
 echo "* Wait and assert Bob's incognito account balance... "
-# in total 4 balance transfer should go through => 1.2 UNIT remaining
-wait_assert_state ${MRENCLAVE} ${ICGACCOUNTBOB} balance $(( AMOUNT_TRANSFER * 6 / 10 ))
+# in total 4 balance transfer should go through => 1.2 UNIT remaining, minus the fees of all 5 transfers
+wait_assert_balance_minus_fees ${MRENCLAVE} ${ICGACCOUNTBOB} $(( AMOUNT_TRANSFER * 6 / 10 )) 5
 echo "✔ ok"
 
 echo ""
@@ -142,10 +142,12 @@ echo ""
 
 ALICE_EXPECTED_BALANCE=10000000000
 BOB_EXPECTED_BALANCE=40000000000
+# Alice pays the fees of both transfers, each of them is well below this bound.
+ALICE_MAX_FEES=2000000
 
 echo "* Verifying Alice's balance"
-if [ "$ALICE_BALANCE" -ne "$ALICE_EXPECTED_BALANCE" ]; then
-  echo "Alice's balance is wrong (expected: $ALICE_EXPECTED_BALANCE, actual: $ALICE_BALANCE)"
+if [ "$ALICE_BALANCE" -gt "$ALICE_EXPECTED_BALANCE" ] || [ "$ALICE_BALANCE" -lt $(( ALICE_EXPECTED_BALANCE - ALICE_MAX_FEES )) ]; then
+  echo "Alice's balance is wrong (expected: $ALICE_EXPECTED_BALANCE minus at most $ALICE_MAX_FEES fees, actual: $ALICE_BALANCE)"
   exit 1
 else
     echo "Alice's balance is correct ($ALICE_BALANCE)"
@@ -65,6 +65,7 @@ INTERVAL=${INTERVAL:-86400}
 
 LISTEN_TO_ORACLE_EVENTS_CMD="oracle listen-to-oracle-events"
 ADD_TO_WHITELIST_CMD="oracle add-to-whitelist"
+GET_WEATHER_REPORT_CMD="oracle get-weather-report"
 
 echo "Using client binary ${CLIENT_BIN}"
 echo "Using node uri ${NODEURL}:${NPORT}"
@@ -112,6 +113,10 @@ read EVENTS_COUNT <<< $($CLIENT ${LISTEN_TO_ORACLE_EVENTS_CMD} ${DURATION} | awk
 echo "Got ${EVENTS_COUNT} oracle updates from the trusted oracle source in ${DURATION} second(s)"
 echo ""
 
+echo "Latest weather report published by the teeracle:"
+${CLIENT} ${GET_WEATHER_REPORT_CMD} 52.52 13.41
+echo ""
+
 echo "Results :"
 
 # the following test is for automated CI
@@ -73,13 +73,10 @@ echo "Using worker market data update interval ${INTERVAL}"
 echo "Count the update events for ${DURATION}"
 echo ""
 
-COIN_GECKO="https://api.coingecko.com/"
-COIN_MARKET_CAP="https://pro-api.coinmarketcap.com/"
+# The teeracle submits the median of all its sources under this data source
+AGGREGATED_SOURCE="median"
 let "MIN_EXPECTED_NUM_OF_EVENTS=$DURATION/$INTERVAL-3"
-echo "Minimum expected number of events with a single oracle source: ${MIN_EXPECTED_NUM_OF_EVENTS}"
-
-let "MIN_EXPECTED_NUM_OF_EVENTS_2 = 2*$MIN_EXPECTED_NUM_OF_EVENTS"
-echo "Minimum expected number of events with two oracle sources: ${MIN_EXPECTED_NUM_OF_EVENTS_2}"
+echo "Minimum expected number of events: ${MIN_EXPECTED_NUM_OF_EVENTS}"
 
 CLIENT="${CLIENT_BIN} -p ${NPORT} -P ${WORKER1PORT} -u ${NODEURL} -U ${WORKER1URL}"
 
@@ -102,12 +99,12 @@ read NO_EVENTS <<< $(${CLIENT} ${LISTEN_TO_EXCHANGE_RATE_EVENTS_CMD} ${DURATION}
 echo "Got ${NO_EVENTS} exchange rate updates when no trusted oracle source is in the whitelist"
 echo ""
 
-echo "Add ${COIN_GECKO} for ${MRENCLAVE} as trusted oracle source"
-${CLIENT} ${ADD_TO_WHITELIST_CMD} //Alice ${COIN_GECKO} ${MRENCLAVE}
-echo "MRENCLAVE in whitelist for ${COIN_GECKO}"
+echo "Add ${AGGREGATED_SOURCE} for ${MRENCLAVE} as trusted oracle source"
+${CLIENT} ${ADD_TO_WHITELIST_CMD} //Alice ${AGGREGATED_SOURCE} ${MRENCLAVE}
+echo "MRENCLAVE in whitelist for ${AGGREGATED_SOURCE}"
 echo ""
 
-echo "Listen to ExchangeRateUpdated events for ${DURATION} seconds, after a trusted oracle source has been added to the whitelist."
+echo "Listen to ExchangeRateUpdated events for ${DURATION} seconds, after the trusted oracle source has been added to the whitelist."
 #${CLIENT} ${LISTEN_TO_EXCHANGE_RATE_EVENTS_CMD} ${DURATION}
 #echo ""
 
@@ -115,38 +112,20 @@ read EVENTS_COUNT <<< $($CLIENT ${LISTEN_TO_EXCHANGE_RATE_EVENTS_CMD} ${DURATION
 echo "Got ${EVENTS_COUNT} exchange rate updates from the trusted oracle source in ${DURATION} second(s)"
 echo ""
 
-echo "Add ${COIN_MARKET_CAP} for ${MRENCLAVE} as trusted oracle source"
-${CLIENT} ${ADD_TO_WHITELIST_CMD} //Alice ${COIN_MARKET_CAP} ${MRENCLAVE}
-echo "MRENCLAVE in whitelist for ${COIN_MARKET_CAP}"
-echo ""
-
-echo "Listen to ExchangeRateUpdated events for ${DURATION} seconds, after a second trusted oracle source has been added to the whitelist."
-#${CLIENT} ${LISTEN_TO_EXCHANGE_RATE_EVENTS_CMD} ${DURATION}
-#echo ""
-
-read EVENTS_COUNT_2 <<< $($CLIENT ${LISTEN_TO_EXCHANGE_RATE_EVENTS_CMD} ${DURATION} | awk '/  EVENTS_COUNT: / { print $2; exit }')
-echo "Got ${EVENTS_COUNT_2} exchange rate updates from 2 trusted oracle sources in ${DURATION} second(s)"
-echo ""
-
 echo "Results :"
 
 # the following test is for automated CI
 # it only works if the teeracle's whitelist is empty at the start (run it from genesis)
-if [ $EVENTS_COUNT_2 -ge $MIN_EXPECTED_NUM_OF_EVENTS_2 ]; then
-   if [ $EVENTS_COUNT -ge $MIN_EXPECTED_NUM_OF_EVENTS ]; then
-       if [ 0 -eq $NO_EVENTS ]; then
-           echo "test passed"
-           exit 0
-       else
-           echo "The test ran through but we received ExchangeRateUpdated events before the enclave was added to the whitelist. Was the enclave previously whitelisted? Perhaps by another teeracle?"
-           exit 1
-      fi
+if [ $EVENTS_COUNT -ge $MIN_EXPECTED_NUM_OF_EVENTS ]; then
+   if [ 0 -eq $NO_EVENTS ]; then
+       echo "test passed"
+       exit 0
    else
-    echo "test failed: Not enough events received for single oracle source: $EVENTS_COUNT. Should be greater than $MIN_EXPECTED_NUM_OF_EVENTS"
-    exit 1
+       echo "The test ran through but we received ExchangeRateUpdated events before the enclave was added to the whitelist. Was the enclave previously whitelisted? Perhaps by another teeracle?"
+       exit 1
    fi
 else
-    echo "test failed: Not enough events received for 2 oracle sources: $EVENTS_COUNT_2. Should be greater than $MIN_EXPECTED_NUM_OF_EVENTS_2"
+    echo "test failed: Not enough events received: $EVENTS_COUNT. Should be greater than $MIN_EXPECTED_NUM_OF_EVENTS"
     exit 1
 fi
 
@@ -1,5 +1,8 @@
 pub mod balance;
 pub mod faucet;
 pub mod listen;
+pub mod register_oauth_client;
+pub mod shield_assets;
 pub mod shield_funds;
 pub mod transfer;
+pub mod verify_audit_log;
@@ -0,0 +1,95 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+use crate::{
+	command_utils::{get_chain_api, *},
+	Cli, CliError, CliResult, CliResultOk,
+};
+use codec::Encode;
+use itp_node_api::api_client::{ParentchainExtrinsicSigner, OAUTH, REGISTER_CLIENT};
+use itp_oauth_client_registry::{OAuthClient, OAuthGrantType};
+use itp_sgx_crypto::ShieldingCryptoEncrypt;
+use sp_core::sr25519 as sr25519_core;
+use substrate_api_client::{compose_call, compose_extrinsic, SubmitAndWatch, XtStatus};
+
+/// Register (or update) an OAuth client with the enclave through the parentchain.
+#[derive(Parser)]
+pub struct RegisterOAuthClientCommand {
+	/// Sender's on-chain AccountId in ss58check format.
+	///
+	/// It has to be a sudo account.
+	from: String,
+	/// OAuth client id.
+	client_id: String,
+	/// Bcrypt hash (`$2b$...`) of the plaintext client secret.
+	/// The client presents the plaintext secret, which the enclave verifies against this hash.
+	client_secret_hash: String,
+	/// Allowed redirect URI, can be given multiple times.
+	#[clap(long = "redirect-uri")]
+	redirect_uris: Vec<String>,
+	/// Allowed grant type, can be given multiple times. One of: authorization_code, implicit,
+	/// password, client_credentials, refresh_token.
+	#[clap(long = "grant")]
+	grants: Vec<String>,
+	/// Allowed scope, can be given multiple times.
+	#[clap(long = "scope")]
+	scopes: Vec<String>,
+}
+
+impl RegisterOAuthClientCommand {
+	pub(crate) fn run(&self, cli: &Cli) -> CliResult {
+		let mut chain_api = get_chain_api(cli);
+
+		let from = get_pair_from_str(&self.from);
+		chain_api.set_signer(ParentchainExtrinsicSigner::new(sr25519_core::Pair::from(from)));
+
+		let grants =
+			self.grants.iter().map(|g| parse_grant_type(g)).collect::<Result<Vec<_>, _>>()?;
+		let client = OAuthClient::new(
+			self.client_id.clone(),
+			self.redirect_uris.clone(),
+			grants,
+			self.scopes.clone(),
+			self.client_secret_hash.clone(),
+		);
+
+		// The client definition contains the secret hash, so only the enclave may read it.
+		let encryption_key = get_shielding_key(cli).unwrap();
+		let encrypted_client = encryption_key.encrypt(&client.encode()).unwrap();
+
+		let call = compose_call!(chain_api.metadata(), OAUTH, REGISTER_CLIENT, encrypted_client);
+		let xt = compose_extrinsic!(chain_api, "Sudo", "sudo", call);
+
+		let report = chain_api.submit_and_watch_extrinsic_until(xt, XtStatus::Finalized).unwrap();
+		println!(
+			"[+] OAuth client registration got finalized. Hash: {:?}\n",
+			report.extrinsic_hash
+		);
+
+		Ok(CliResultOk::None)
+	}
+}
+
+fn parse_grant_type(grant: &str) -> Result<OAuthGrantType, CliError> {
+	match grant {
+		"authorization_code" => Ok(OAuthGrantType::AuthorizationCode),
+		"implicit" => Ok(OAuthGrantType::Implicit),
+		"password" => Ok(OAuthGrantType::Password),
+		"client_credentials" => Ok(OAuthGrantType::ClientCredentials),
+		"refresh_token" => Ok(OAuthGrantType::RefreshToken),
+		_ => Err(CliError::OAuthClient { msg: format!("unsupported grant type: {}", grant) }),
+	}
+}
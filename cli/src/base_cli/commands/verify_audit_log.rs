@@ -0,0 +1,60 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::{Cli, CliError, CliResult, CliResultOk};
+use itp_audit_log::{decode_audit_log, verify_audit_log};
+use sp_core::{crypto::Ss58Codec, ed25519};
+use std::fs;
+
+/// Verify the integrity of the audit log written by a worker.
+#[derive(Parser)]
+pub struct VerifyAuditLogCommand {
+	/// Path to the audit log file of the worker.
+	log_file: String,
+	/// AccountId of the enclave that signed the log, in ss58check format (see `list-workers`).
+	enclave_account: String,
+}
+
+impl VerifyAuditLogCommand {
+	pub(crate) fn run(&self, _cli: &Cli) -> CliResult {
+		let enclave_public = ed25519::Public::from_ss58check(&self.enclave_account)
+			.map_err(|e| audit_log_error(format!("invalid enclave account: {:?}", e)))?;
+
+		let log_file = fs::read(&self.log_file)
+			.map_err(|e| audit_log_error(format!("could not read {}: {}", self.log_file, e)))?;
+		let records = decode_audit_log(&log_file).map_err(|e| audit_log_error(e.to_string()))?;
+
+		verify_audit_log(&records, &enclave_public).map_err(|e| audit_log_error(e.to_string()))?;
+
+		match (records.first(), records.last()) {
+			(Some(first), Some(last)) => println!(
+				"[+] Audit log is intact: {} records, index {} to {}",
+				records.len(),
+				first.index,
+				last.index
+			),
+			_ => println!("[+] Audit log is empty"),
+		}
+
+		Ok(CliResultOk::None)
+	}
+}
+
+fn audit_log_error(msg: String) -> CliError {
+	println!("[-] Audit log verification failed: {}", msg);
+	CliError::AuditLog { msg }
+}
@@ -18,7 +18,9 @@
 use crate::{
 	base_cli::commands::{
 		balance::BalanceCommand, faucet::FaucetCommand, listen::ListenCommand,
+		register_oauth_client::RegisterOAuthClientCommand, shield_assets::ShieldAssetsCommand,
 		shield_funds::ShieldFundsCommand, transfer::TransferCommand,
+		verify_audit_log::VerifyAuditLogCommand,
 	},
 	command_utils::*,
 	Cli, CliResult, CliResultOk,
@@ -70,6 +72,15 @@ pub enum BaseCommand {
 
 	/// Transfer funds from an parentchain account to an incognito account
 	ShieldFunds(ShieldFundsCommand),
+
+	/// Transfer assets from an parentchain account to an incognito account
+	ShieldAssets(ShieldAssetsCommand),
+
+	/// register or update an OAuth client of the enclave's authorization server
+	RegisterOAuthClient(RegisterOAuthClientCommand),
+
+	/// verify the integrity of a worker's audit log
+	VerifyAuditLog(VerifyAuditLogCommand),
 }
 
 impl BaseCommand {
@@ -85,6 +96,9 @@ impl BaseCommand {
 			BaseCommand::ListWorkers => list_workers(cli),
 			BaseCommand::Listen(cmd) => cmd.run(cli),
 			BaseCommand::ShieldFunds(cmd) => cmd.run(cli),
+			BaseCommand::ShieldAssets(cmd) => cmd.run(cli),
+			BaseCommand::RegisterOAuthClient(cmd) => cmd.run(cli),
+			BaseCommand::VerifyAuditLog(cmd) => cmd.run(cli),
 		}
 	}
 }
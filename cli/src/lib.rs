@@ -110,6 +110,10 @@ pub enum CliError {
 	TrustedOp { msg: String },
 	#[error("EvmReadCommands error: {:?}", msg)]
 	EvmRead { msg: String },
+	#[error("audit log error: {:?}", msg)]
+	AuditLog { msg: String },
+	#[error("OAuth client error: {:?}", msg)]
+	OAuthClient { msg: String },
 }
 
 pub type CliResult = Result<CliResultOk, CliError>;
@@ -0,0 +1,75 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::{command_utils::get_chain_api, Cli};
+use codec::Decode;
+use ita_oracle::types::{WeatherQuery, WeatherReport};
+use itp_node_api::api_client::PalletTeeracleApi;
+
+/// Source the teeracle publishes the weather of.
+const OPEN_METEO_SOURCE: &str = "https://api.open-meteo.com/";
+
+/// Get the latest weather report the teeracle published for a location.
+#[derive(Debug, Clone, Parser)]
+pub struct GetWeatherReportCmd {
+	/// Latitude, as given to the teeracle's `--weather-location`.
+	latitude: String,
+
+	/// Longitude, as given to the teeracle's `--weather-location`.
+	longitude: String,
+
+	/// Data source the report was fetched from.
+	#[clap(long, default_value = OPEN_METEO_SOURCE)]
+	source: String,
+}
+
+impl GetWeatherReportCmd {
+	pub fn run(&self, cli: &Cli) {
+		let api = get_chain_api(cli);
+		let oracle_name = WeatherQuery {
+			latitude: self.latitude.clone(),
+			longitude: self.longitude.clone(),
+			forecast_hours: 0,
+		}
+		.key();
+
+		let blob = match api.oracle_data(&oracle_name, &self.source, None).unwrap() {
+			Some(blob) => blob,
+			None => {
+				println!("No weather report published for {} by {}", oracle_name, self.source);
+				return
+			},
+		};
+		let report = WeatherReport::decode(&mut blob.as_slice())
+			.expect("Oracle data of the weather is a weather report");
+
+		println!(
+			"Weather at {} ({}, {}) at {} UTC",
+			oracle_name, report.latitude, report.longitude, report.time
+		);
+		println!(
+			"   TEMPERATURE: {} °C, PRECIPITATION: {} mm, WIND_SPEED: {} km/h",
+			report.temperature, report.precipitation, report.wind_speed
+		);
+		for hour in report.hourly {
+			println!(
+				"   {}: {} °C, {} mm, {} km/h",
+				hour.time, hour.temperature, hour.precipitation, hour.wind_speed
+			);
+		}
+	}
+}
@@ -16,10 +16,13 @@
 */
 
 mod add_to_whitelist;
+mod get_weather_report;
 mod listen_to_exchange;
 mod listen_to_oracle;
+mod query_oracle;
 
 pub use self::{
-	add_to_whitelist::AddToWhitelistCmd, listen_to_exchange::ListenToExchangeRateEventsCmd,
-	listen_to_oracle::ListenToOracleEventsCmd,
+	add_to_whitelist::AddToWhitelistCmd, get_weather_report::GetWeatherReportCmd,
+	listen_to_exchange::ListenToExchangeRateEventsCmd, listen_to_oracle::ListenToOracleEventsCmd,
+	query_oracle::QueryOracleCmd,
 };
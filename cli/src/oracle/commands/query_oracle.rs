@@ -0,0 +1,85 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+use crate::{command_utils::get_worker_api_direct, Cli};
+use codec::Decode;
+use ita_oracle::oracle_query::{OracleQuery, SignedOracleQueryResponse};
+use itc_rpc_client::direct_client::DirectApi;
+use itp_rpc::{RpcRequest, RpcResponse, RpcReturnValue};
+use itp_types::DirectRequestStatus;
+use itp_utils::{FromHexPrefixed, ToHexPrefixed};
+
+/// Query a configured oracle source on demand and print the response signed by the enclave.
+#[derive(Debug, Clone, Parser)]
+pub struct QueryOracleCmd {
+	/// Name of the configured oracle source.
+	source: String,
+
+	/// Parameters of the source's query template, e.g. `crypto_currency=TEER`.
+	#[clap(long = "param", value_parser = parse_parameter)]
+	parameters: Vec<(String, String)>,
+}
+
+impl QueryOracleCmd {
+	pub fn run(&self, cli: &Cli) {
+		let direct_api = get_worker_api_direct(cli);
+		let query =
+			OracleQuery { source: self.source.clone(), parameters: self.parameters.clone() };
+
+		let jsonrpc_call: String =
+			RpcRequest::compose_jsonrpc_call("oracle_query".to_owned(), vec![query.to_hex()])
+				.unwrap();
+		let rpc_response_str = direct_api.get(&jsonrpc_call).unwrap();
+
+		let Ok(rpc_response) = serde_json::from_str::<RpcResponse>(&rpc_response_str) else {
+			panic!("Can't parse RPC response: '{rpc_response_str}'");
+		};
+		let rpc_return_value = match RpcReturnValue::from_hex(&rpc_response.result) {
+			Ok(rpc_return_value) => rpc_return_value,
+			Err(e) => panic!("Failed to decode RpcReturnValue: {:?}", e),
+		};
+		if rpc_return_value.status != DirectRequestStatus::Ok {
+			let error_msg = String::decode(&mut rpc_return_value.value.as_slice())
+				.unwrap_or_else(|_| "unknown error".into());
+			println!("Oracle query failed: {}", error_msg);
+			return
+		}
+
+		let signed_response =
+			SignedOracleQueryResponse::decode(&mut rpc_return_value.value.as_slice())
+				.expect("Oracle query returns a signed response");
+		let response = &signed_response.response;
+
+		println!("{} ({}): {}", response.source, response.feed, response.value);
+		println!("   URL: {}", response.url);
+		println!("   TIMESTAMP: {}", response.timestamp);
+		for fingerprint in response.trusted_root_certificate_fingerprints.iter() {
+			println!("   TRUSTED ROOT CERTIFICATE SHA-256: {}", hex::encode(fingerprint));
+		}
+		println!("   SIGNER: {}", signed_response.signer);
+		println!(
+			"   SIGNATURE: {}",
+			if signed_response.verify_signature() { "valid" } else { "INVALID" }
+		);
+	}
+}
+
+fn parse_parameter(parameter: &str) -> Result<(String, String), String> {
+	parameter
+		.split_once('=')
+		.map(|(key, value)| (key.to_string(), value.to_string()))
+		.ok_or_else(|| format!("Expected `key=value`, got `{}`", parameter))
+}
@@ -21,7 +21,10 @@
 //! https://github.com/integritee-network/worker/issues/852
 
 use crate::Cli;
-use commands::{AddToWhitelistCmd, ListenToExchangeRateEventsCmd, ListenToOracleEventsCmd};
+use commands::{
+	AddToWhitelistCmd, GetWeatherReportCmd, ListenToExchangeRateEventsCmd, ListenToOracleEventsCmd,
+	QueryOracleCmd,
+};
 
 mod commands;
 
@@ -36,6 +39,12 @@ pub enum OracleCommand {
 
 	/// Listen to all oracles event updates
 	ListenToOracleEvents(ListenToOracleEventsCmd),
+
+	/// Get the latest weather report published for a location
+	GetWeatherReport(GetWeatherReportCmd),
+
+	/// Query a configured oracle source on demand, signed by the enclave
+	Query(QueryOracleCmd),
 }
 
 impl OracleCommand {
@@ -44,6 +53,8 @@ impl OracleCommand {
 			OracleCommand::AddToWhitelist(cmd) => cmd.run(cli),
 			OracleCommand::ListenToExchangeRateEvents(cmd) => cmd.run(cli),
 			OracleCommand::ListenToOracleEvents(cmd) => cmd.run(cli),
+			OracleCommand::GetWeatherReport(cmd) => cmd.run(cli),
+			OracleCommand::Query(cmd) => cmd.run(cli),
 		}
 	}
 }
@@ -0,0 +1,50 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::{
+	trusted_cli::TrustedCli,
+	trusted_command_utils::{decode_balance, get_pair_from_str},
+	trusted_operation::perform_trusted_operation,
+	Cli, CliResult, CliResultOk,
+};
+use ita_stf::{AssetId, TrustedGetter, TrustedOperation};
+use itp_stf_primitives::types::KeyPair;
+use sp_core::Pair;
+use std::boxed::Box;
+
+#[derive(Parser)]
+pub struct AssetBalanceCommand {
+	/// AccountId in ss58check format
+	account: String,
+
+	/// Id of the asset
+	asset_id: AssetId,
+}
+
+impl AssetBalanceCommand {
+	pub(crate) fn run(&self, cli: &Cli, trusted_args: &TrustedCli) -> CliResult {
+		let who = get_pair_from_str(trusted_args, &self.account);
+		let top: TrustedOperation =
+			TrustedGetter::asset_balance(who.public().into(), self.asset_id)
+				.sign(&KeyPair::Sr25519(Box::new(who)))
+				.into();
+		let res = perform_trusted_operation(cli, trusted_args, &top).unwrap_or(None);
+		let balance = decode_balance(res).unwrap_or_default();
+		println!("{}", balance);
+		Ok(CliResultOk::Balance { balance })
+	}
+}
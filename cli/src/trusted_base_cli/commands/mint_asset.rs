@@ -0,0 +1,71 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::{
+	get_layer_two_nonce,
+	trusted_cli::TrustedCli,
+	trusted_command_utils::{get_accountid_from_str, get_identifiers, get_pair_from_str},
+	trusted_operation::perform_trusted_operation,
+	Cli, CliResult, CliResultOk,
+};
+use codec::Decode;
+use ita_stf::{AssetId, Index, TrustedCall, TrustedGetter, TrustedOperation};
+use itp_stf_primitives::types::KeyPair;
+use log::*;
+use my_node_runtime::Balance;
+use sp_core::{crypto::Ss58Codec, Pair};
+use std::boxed::Box;
+
+#[derive(Parser)]
+pub struct MintAssetCommand {
+	/// Issuer's AccountId in ss58check format
+	issuer: String,
+
+	/// Beneficiary's AccountId in ss58check format
+	beneficiary: String,
+
+	/// Id of the asset
+	asset_id: AssetId,
+
+	/// amount to be minted
+	amount: Balance,
+}
+
+impl MintAssetCommand {
+	pub(crate) fn run(&self, cli: &Cli, trusted_args: &TrustedCli) -> CliResult {
+		let issuer = get_pair_from_str(trusted_args, &self.issuer);
+		let beneficiary = get_accountid_from_str(&self.beneficiary);
+		info!("issuer ss58 is {}", issuer.public().to_ss58check());
+		info!("beneficiary ss58 is {}", beneficiary.to_ss58check());
+
+		let (mrenclave, shard) = get_identifiers(trusted_args);
+		let nonce = get_layer_two_nonce!(issuer, cli, trusted_args);
+		println!(
+			"send trusted call mint-asset {} of asset {} to {}, nonce: {}",
+			self.amount, self.asset_id, beneficiary, nonce
+		);
+		let top: TrustedOperation = TrustedCall::assets_mint(
+			issuer.public().into(),
+			beneficiary,
+			self.asset_id,
+			self.amount,
+		)
+		.sign(&KeyPair::Sr25519(Box::new(issuer)), nonce, &mrenclave, &shard)
+		.into_trusted_operation(trusted_args.direct);
+		Ok(perform_trusted_operation(cli, trusted_args, &top).map(|_| CliResultOk::None)?)
+	}
+}
@@ -1,5 +1,11 @@
+pub mod asset_balance;
 pub mod balance;
+pub mod burn_asset;
+pub mod create_asset;
+pub mod mint_asset;
 pub mod nonce;
 pub mod set_balance;
 pub mod transfer;
+pub mod transfer_asset;
+pub mod unshield_assets;
 pub mod unshield_funds;
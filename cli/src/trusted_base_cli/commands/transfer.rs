@@ -40,6 +40,10 @@ pub struct TransferCommand {
 
 	/// amount to be transferred
 	amount: Balance,
+
+	/// optional tip to raise the priority of the call in the worker's pool
+	#[clap(long)]
+	tip: Option<Balance>,
 }
 
 impl TransferCommand {
@@ -60,7 +64,13 @@ impl TransferCommand {
 		);
 		let top: TrustedOperation =
 			TrustedCall::balance_transfer(from.public().into(), to, self.amount)
-				.sign(&KeyPair::Sr25519(Box::new(from)), nonce, &mrenclave, &shard)
+				.sign_with_tip(
+					&KeyPair::Sr25519(Box::new(from)),
+					nonce,
+					self.tip,
+					&mrenclave,
+					&shard,
+				)
 				.into_trusted_operation(trusted_args.direct);
 		let res = perform_trusted_operation(cli, trusted_args, &top).map(|_| CliResultOk::None)?;
 		info!("trusted call transfer executed");
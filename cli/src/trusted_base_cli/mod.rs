@@ -17,8 +17,11 @@
 
 use crate::{
 	trusted_base_cli::commands::{
-		balance::BalanceCommand, nonce::NonceCommand, set_balance::SetBalanceCommand,
-		transfer::TransferCommand, unshield_funds::UnshieldFundsCommand,
+		asset_balance::AssetBalanceCommand, balance::BalanceCommand, burn_asset::BurnAssetCommand,
+		create_asset::CreateAssetCommand, mint_asset::MintAssetCommand, nonce::NonceCommand,
+		set_balance::SetBalanceCommand, transfer::TransferCommand,
+		transfer_asset::TransferAssetCommand, unshield_assets::UnshieldAssetsCommand,
+		unshield_funds::UnshieldFundsCommand,
 	},
 	trusted_cli::TrustedCli,
 	trusted_command_utils::get_keystore_path,
@@ -51,6 +54,24 @@ pub enum TrustedBaseCommand {
 	/// Transfer funds from an incognito account to an parentchain account
 	UnshieldFunds(UnshieldFundsCommand),
 
+	/// ROOT call to create a new asset
+	CreateAsset(CreateAssetCommand),
+
+	/// mint assets to an incognito account, signed by the issuer of the asset
+	MintAsset(MintAssetCommand),
+
+	/// send assets from one incognito account to another
+	TransferAsset(TransferAssetCommand),
+
+	/// burn assets of an incognito account, signed by the admin of the asset
+	BurnAsset(BurnAssetCommand),
+
+	/// query the asset balance for incognito account in keystore
+	AssetBalance(AssetBalanceCommand),
+
+	/// Transfer assets from an incognito account to an parentchain account
+	UnshieldAssets(UnshieldAssetsCommand),
+
 	/// gets the nonce of a given account, taking the pending trusted calls
 	/// in top pool in consideration
 	Nonce(NonceCommand),
@@ -65,6 +86,12 @@ impl TrustedBaseCommand {
 			TrustedBaseCommand::SetBalance(cmd) => cmd.run(cli, trusted_cli),
 			TrustedBaseCommand::Balance(cmd) => cmd.run(cli, trusted_cli),
 			TrustedBaseCommand::UnshieldFunds(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::CreateAsset(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::MintAsset(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::TransferAsset(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::BurnAsset(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::AssetBalance(cmd) => cmd.run(cli, trusted_cli),
+			TrustedBaseCommand::UnshieldAssets(cmd) => cmd.run(cli, trusted_cli),
 			TrustedBaseCommand::Nonce(cmd) => cmd.run(cli, trusted_cli),
 		}
 	}
@@ -0,0 +1,189 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+#[cfg(feature = "sgx")]
+use std::sync::SgxRwLock as RwLock;
+
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+use crate::{
+	error::{Error, Result},
+	AppendAuditEvent, AuditEvent, AuditLogHead, AuditRecord,
+};
+use codec::Encode;
+use itp_ocall_api::EnclaveAuditLogOCallApi;
+use itp_sgx_io::SealedIO;
+use itp_time_utils::now_as_millis;
+use log::*;
+use sp_core::ed25519;
+use std::{format, sync::Arc};
+
+/// Audit log that signs and hash-chains events and hands them to the untrusted side
+/// for persistence. Only the head of the chain is kept (sealed) inside the enclave.
+pub struct AuditLog<OCallApi, SealedIo> {
+	head_lock: RwLock<AuditLogHead>,
+	signer: ed25519::Pair,
+	ocall_api: Arc<OCallApi>,
+	sealed_io: Arc<SealedIo>,
+}
+
+impl<OCallApi, SealedIo> AuditLog<OCallApi, SealedIo>
+where
+	OCallApi: EnclaveAuditLogOCallApi,
+	SealedIo: SealedIO<Unsealed = AuditLogHead, Error = Error>,
+{
+	pub fn new(
+		head: AuditLogHead,
+		signer: ed25519::Pair,
+		ocall_api: Arc<OCallApi>,
+		sealed_io: Arc<SealedIo>,
+	) -> Self {
+		AuditLog { head_lock: RwLock::new(head), signer, ocall_api, sealed_io }
+	}
+
+	/// Load the head of the log from the sealed file.
+	///
+	/// Nothing is persisted yet, the untrusted side may not be ready to take records at this
+	/// point. See [`AuditLog::log_started`].
+	pub fn load(
+		signer: ed25519::Pair,
+		ocall_api: Arc<OCallApi>,
+		sealed_io: Arc<SealedIo>,
+	) -> Result<Self> {
+		let head = sealed_io.unseal()?;
+		Ok(Self::new(head, signer, ocall_api, sealed_io))
+	}
+
+	/// Mark the start of a new log segment, i.e. the first record after an enclave (re-)start.
+	pub fn log_started(&self) -> Result<()> {
+		self.append_event(AuditEvent::LogStarted)
+	}
+
+	pub fn head(&self) -> Result<AuditLogHead> {
+		self.head_lock.read().map_err(|_| Error::LockPoisoning).map(|h| *h)
+	}
+}
+
+impl<OCallApi, SealedIo> AppendAuditEvent for AuditLog<OCallApi, SealedIo>
+where
+	OCallApi: EnclaveAuditLogOCallApi,
+	SealedIo: SealedIO<Unsealed = AuditLogHead, Error = Error>,
+{
+	fn append_event(&self, event: AuditEvent) -> Result<()> {
+		// Hold the write lock until the head is updated, appends have to be strictly sequential.
+		let mut head_lock = self.head_lock.write().map_err(|_| Error::LockPoisoning)?;
+
+		let record = AuditRecord::new_signed(
+			head_lock.next_index,
+			now_as_millis(),
+			head_lock.last_hash,
+			event,
+			&self.signer,
+		);
+
+		self.ocall_api
+			.append_audit_record(record.encode())
+			.map_err(|e| Error::Persist(format!("{:?}", e)))?;
+
+		// If sealing fails here, the next record re-uses the index. This is detected
+		// by the verification, so we never silently lose track of a record.
+		let head = AuditLogHead { next_index: record.index + 1, last_hash: record.hash() };
+		self.sealed_io.seal(&head)?;
+		*head_lock = head;
+
+		trace!("Appended audit record {}", record.index);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		mocks::{AuditLogHeadSealMock, AuditLogOCallMock},
+		verify_audit_log,
+	};
+	use sp_core::{Pair, H256};
+	use std::string::ToString;
+
+	type TestAuditLog = AuditLog<AuditLogOCallMock, AuditLogHeadSealMock>;
+
+	#[test]
+	fn appended_events_are_persisted_as_a_verifiable_chain() {
+		let signer = test_signer();
+		let ocall_api = Arc::new(AuditLogOCallMock::default());
+		let seal_mock = Arc::new(AuditLogHeadSealMock::default());
+
+		let audit_log =
+			TestAuditLog::load(signer.clone(), ocall_api.clone(), seal_mock.clone()).unwrap();
+		audit_log.log_started().unwrap();
+		audit_log.append_event(login_failed()).unwrap();
+		audit_log.append_event(login_failed()).unwrap();
+
+		let records = ocall_api.persisted_records();
+		assert_eq!(3, records.len());
+		assert_eq!(AuditEvent::LogStarted, records[0].event);
+		assert_eq!(H256::zero(), records[0].previous_hash);
+		assert!(verify_audit_log(&records, &signer.public()).is_ok());
+
+		let head = seal_mock.unseal().unwrap();
+		assert_eq!(3, head.next_index);
+		assert_eq!(records[2].hash(), head.last_hash);
+	}
+
+	#[test]
+	fn reloaded_log_continues_the_chain() {
+		let signer = test_signer();
+		let ocall_api = Arc::new(AuditLogOCallMock::default());
+		let seal_mock = Arc::new(AuditLogHeadSealMock::default());
+
+		let audit_log =
+			TestAuditLog::load(signer.clone(), ocall_api.clone(), seal_mock.clone()).unwrap();
+		audit_log.log_started().unwrap();
+		audit_log.append_event(login_failed()).unwrap();
+		drop(audit_log);
+
+		let reloaded = TestAuditLog::load(signer.clone(), ocall_api.clone(), seal_mock).unwrap();
+		reloaded.log_started().unwrap();
+
+		let records = ocall_api.persisted_records();
+		assert_eq!(3, records.len());
+		assert_eq!(AuditEvent::LogStarted, records[2].event);
+		assert!(verify_audit_log(&records, &signer.public()).is_ok());
+	}
+
+	#[test]
+	fn loading_does_not_persist_any_record() {
+		let ocall_api = Arc::new(AuditLogOCallMock::default());
+		let seal_mock = Arc::new(AuditLogHeadSealMock::default());
+
+		let audit_log =
+			TestAuditLog::load(test_signer(), ocall_api.clone(), seal_mock.clone()).unwrap();
+
+		assert!(ocall_api.persisted_records().is_empty());
+		assert_eq!(0, audit_log.head().unwrap().next_index);
+	}
+
+	fn test_signer() -> ed25519::Pair {
+		ed25519::Pair::from_seed(&[3u8; 32])
+	}
+
+	fn login_failed() -> AuditEvent {
+		AuditEvent::LoginFailed { client_id: "client".to_string(), username: "user".to_string() }
+	}
+}
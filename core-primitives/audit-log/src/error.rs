@@ -0,0 +1,44 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use crate::sgx_reexport_prelude::*;
+
+use std::{boxed::Box, string::String};
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// Audit log error
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	#[error("Audit log lock is poisoned")]
+	LockPoisoning,
+	#[error("Failed to persist audit record: {0}")]
+	Persist(String),
+	#[error("Audit record {found} is out of order, expected index {expected}")]
+	UnexpectedIndex { expected: u64, found: u64 },
+	#[error("Audit record {0} does not reference the hash of its predecessor")]
+	BrokenChain(u64),
+	#[error("Audit record {0} has an invalid signature")]
+	InvalidSignature(u64),
+	#[error("Codec error: {0}")]
+	Codec(#[from] codec::Error),
+	#[error("IO error: {0}")]
+	IO(#[from] std::io::Error),
+	#[error(transparent)]
+	Other(#[from] Box<dyn std::error::Error + Sync + Send + 'static>),
+}
@@ -0,0 +1,91 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use codec::{Decode, Encode};
+use core::fmt;
+use sp_core::hashing::blake2_256;
+use std::string::String;
+
+/// Redacted representation of an access token.
+///
+/// Tokens must never end up in the audit log. The fingerprint still allows correlating
+/// all events concerning the same token.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq)]
+pub struct TokenFingerprint(pub [u8; 8]);
+
+impl TokenFingerprint {
+	pub fn from_token(token: &str) -> Self {
+		let mut fingerprint = [0u8; 8];
+		fingerprint.copy_from_slice(&blake2_256(token.as_bytes())[..8]);
+		TokenFingerprint(fingerprint)
+	}
+}
+
+impl fmt::Display for TokenFingerprint {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for byte in self.0.iter() {
+			write!(f, "{:02x}", byte)?;
+		}
+		Ok(())
+	}
+}
+
+impl fmt::Debug for TokenFingerprint {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "TokenFingerprint({})", self)
+	}
+}
+
+/// Authentication related events that are recorded in the audit log.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+pub enum AuditEvent {
+	/// A new segment of the log is started, i.e. the enclave (re-)started.
+	LogStarted,
+	/// A user successfully authenticated.
+	LoginSucceeded { client_id: String, username: String },
+	/// A user failed to authenticate.
+	LoginFailed { client_id: String, username: String },
+	/// A client failed to authenticate or is not allowed to use the requested grant.
+	ClientAuthenticationFailed { client_id: String },
+	/// An access token was issued.
+	TokenIssued { client_id: String, token: TokenFingerprint },
+	/// An access token was refreshed.
+	TokenRefreshed { client_id: String, previous: TokenFingerprint, token: TokenFingerprint },
+	/// An access token was revoked before it expired.
+	TokenRevoked { token: TokenFingerprint },
+	/// A user enrolled (or re-enrolled) for TOTP as second factor.
+	TotpEnrolled { username: String },
+	/// A TOTP enrollment was rejected because the user could not authenticate.
+	TotpEnrollmentRejected { username: String },
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::{format, string::ToString};
+
+	#[test]
+	fn token_fingerprint_does_not_contain_the_token() {
+		let token = "SuperSecretAccessToken0123456789";
+		let fingerprint = TokenFingerprint::from_token(token);
+
+		assert_eq!(fingerprint, TokenFingerprint::from_token(token));
+		assert_ne!(fingerprint, TokenFingerprint::from_token("another_token"));
+		assert_eq!(16, fingerprint.to_string().len());
+		assert!(!format!("{:?}", fingerprint).contains("Secret"));
+	}
+}
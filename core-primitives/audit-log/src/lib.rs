@@ -0,0 +1,63 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Tamper-evident audit log of security relevant enclave events.
+//!
+//! Every event is wrapped in an [`AuditRecord`] that references the hash of its predecessor
+//! and is signed by the enclave signing key. The records are persisted on the untrusted side
+//! (via OCall), where anyone holding the enclave's public key can verify that the log has
+//! neither been altered nor had records removed from or inserted into it.
+//!
+//! The head of the chain (last hash and next index) is sealed inside the enclave, so the
+//! chain continues seamlessly across restarts.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(all(feature = "std", feature = "sgx"))]
+compile_error!("feature \"std\" and feature \"sgx\" cannot be enabled at the same time");
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+extern crate sgx_tstd as std;
+
+// re-export module to properly feature gate sgx and regular std environment
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+pub mod sgx_reexport_prelude {
+	pub use thiserror_sgx as thiserror;
+}
+
+pub mod audit_log;
+pub mod error;
+pub mod event;
+pub mod record;
+pub mod verification;
+
+#[cfg(feature = "sgx")]
+pub mod sealing;
+
+#[cfg(any(test, feature = "mocks"))]
+pub mod mocks;
+
+pub use audit_log::AuditLog;
+pub use error::{Error, Result};
+pub use event::{AuditEvent, TokenFingerprint};
+pub use record::{AuditLogHead, AuditRecord};
+pub use verification::{decode_audit_log, verify_audit_log};
+
+/// Append an event to the audit log.
+pub trait AppendAuditEvent {
+	fn append_event(&self, event: AuditEvent) -> Result<()>;
+}
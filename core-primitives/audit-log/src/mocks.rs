@@ -0,0 +1,96 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+#[cfg(feature = "sgx")]
+use std::sync::SgxRwLock as RwLock;
+
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+use crate::{
+	error::{Error, Result},
+	AppendAuditEvent, AuditEvent, AuditLogHead, AuditRecord,
+};
+use codec::Decode;
+use itp_ocall_api::EnclaveAuditLogOCallApi;
+use itp_sgx_io::SealedIO;
+use sgx_types::SgxResult;
+use std::{sync::Arc, vec::Vec};
+
+/// Sealing mock that keeps the audit log head in memory.
+#[derive(Default)]
+pub struct AuditLogHeadSealMock {
+	sealed: RwLock<AuditLogHead>,
+}
+
+impl SealedIO for AuditLogHeadSealMock {
+	type Error = Error;
+	type Unsealed = AuditLogHead;
+
+	fn unseal(&self) -> Result<Self::Unsealed> {
+		self.sealed.read().map_err(|_| Error::LockPoisoning).map(|l| *l)
+	}
+
+	fn seal(&self, unsealed: &Self::Unsealed) -> Result<()> {
+		let mut sealed_lock = self.sealed.write().map_err(|_| Error::LockPoisoning)?;
+		*sealed_lock = *unsealed;
+		Ok(())
+	}
+}
+
+/// OCall mock that keeps the persisted audit records in memory.
+#[derive(Clone, Default)]
+pub struct AuditLogOCallMock {
+	records: Arc<RwLock<Vec<Vec<u8>>>>,
+}
+
+impl AuditLogOCallMock {
+	pub fn persisted_records(&self) -> Vec<AuditRecord> {
+		self.records
+			.read()
+			.unwrap()
+			.iter()
+			.map(|r| AuditRecord::decode(&mut r.as_slice()).unwrap())
+			.collect()
+	}
+}
+
+impl EnclaveAuditLogOCallApi for AuditLogOCallMock {
+	fn append_audit_record(&self, encoded_record: Vec<u8>) -> SgxResult<()> {
+		self.records.write().unwrap().push(encoded_record);
+		Ok(())
+	}
+}
+
+/// Audit log mock that records all appended events.
+#[derive(Default)]
+pub struct AuditLogMock {
+	events: RwLock<Vec<AuditEvent>>,
+}
+
+impl AuditLogMock {
+	pub fn appended_events(&self) -> Vec<AuditEvent> {
+		self.events.read().unwrap().clone()
+	}
+}
+
+impl AppendAuditEvent for AuditLogMock {
+	fn append_event(&self, event: AuditEvent) -> Result<()> {
+		self.events.write().map_err(|_| Error::LockPoisoning)?.push(event);
+		Ok(())
+	}
+}
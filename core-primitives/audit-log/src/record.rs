@@ -0,0 +1,70 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::event::AuditEvent;
+use codec::{Decode, Encode};
+use sp_core::{ed25519, hashing::blake2_256, Pair, H256};
+use std::vec::Vec;
+
+/// Head of the audit log hash chain, sealed inside the enclave.
+#[derive(Encode, Decode, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AuditLogHead {
+	/// Index the next record will get.
+	pub next_index: u64,
+	/// Hash of the last record, zero if the log is empty.
+	pub last_hash: H256,
+}
+
+/// A single, signed entry of the audit log.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+pub struct AuditRecord {
+	pub index: u64,
+	/// Unix timestamp in milliseconds.
+	pub timestamp: u64,
+	/// Hash of the preceding record, zero for the very first record.
+	pub previous_hash: H256,
+	pub event: AuditEvent,
+	/// Enclave signature over all of the fields above.
+	pub signature: ed25519::Signature,
+}
+
+impl AuditRecord {
+	pub fn new_signed(
+		index: u64,
+		timestamp: u64,
+		previous_hash: H256,
+		event: AuditEvent,
+		signer: &ed25519::Pair,
+	) -> Self {
+		let signature = signer.sign(&Self::payload(index, timestamp, &previous_hash, &event));
+		AuditRecord { index, timestamp, previous_hash, event, signature }
+	}
+
+	/// Hash of the complete record, including the signature.
+	pub fn hash(&self) -> H256 {
+		blake2_256(&self.encode()).into()
+	}
+
+	pub fn verify_signature(&self, signer: &ed25519::Public) -> bool {
+		let payload = Self::payload(self.index, self.timestamp, &self.previous_hash, &self.event);
+		ed25519::Pair::verify(&self.signature, payload, signer)
+	}
+
+	fn payload(index: u64, timestamp: u64, previous_hash: &H256, event: &AuditEvent) -> Vec<u8> {
+		(index, timestamp, previous_hash, event).encode()
+	}
+}
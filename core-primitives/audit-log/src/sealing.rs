@@ -0,0 +1,54 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::{
+	error::{Error, Result},
+	AuditLogHead,
+};
+use codec::{Decode, Encode};
+use itp_settings::files::AUDIT_LOG_HEAD_FILE;
+use itp_sgx_io::{seal, unseal, SealedIO};
+use log::info;
+use std::sgxfs::SgxFile;
+
+/// Seals the head of the audit log hash chain to a static file.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct AuditLogHeadSeal;
+
+impl SealedIO for AuditLogHeadSeal {
+	type Error = Error;
+	type Unsealed = AuditLogHead;
+
+	fn unseal(&self) -> Result<Self::Unsealed> {
+		Ok(unseal(AUDIT_LOG_HEAD_FILE).map(|b| Decode::decode(&mut b.as_slice()))??)
+	}
+
+	fn seal(&self, unsealed: &Self::Unsealed) -> Result<()> {
+		Ok(unsealed.using_encoded(|bytes| seal(bytes, AUDIT_LOG_HEAD_FILE))?)
+	}
+}
+
+pub fn create_sealed_if_absent() -> Result<()> {
+	if SgxFile::open(AUDIT_LOG_HEAD_FILE).is_err() {
+		info!(
+			"[Enclave] Audit log head not found, starting a new audit log! {}",
+			AUDIT_LOG_HEAD_FILE
+		);
+		return AuditLogHeadSeal.seal(&AuditLogHead::default())
+	}
+	Ok(())
+}
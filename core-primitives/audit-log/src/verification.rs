@@ -0,0 +1,168 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::{
+	error::{Error, Result},
+	AuditRecord,
+};
+use codec::Decode;
+use sp_core::{ed25519, H256};
+use std::vec::Vec;
+
+/// Decode the audit log file written by the untrusted side.
+///
+/// The file is a plain concatenation of the SCALE encoded (and hence length prefixed)
+/// records as they were handed out by the enclave.
+pub fn decode_audit_log(mut log_file: &[u8]) -> Result<Vec<AuditRecord>> {
+	let mut records = Vec::new();
+	while !log_file.is_empty() {
+		let encoded_record = Vec::<u8>::decode(&mut log_file)?;
+		records.push(AuditRecord::decode(&mut encoded_record.as_slice())?);
+	}
+	Ok(records)
+}
+
+/// Verify that the records form an unbroken hash chain signed by `signer`.
+///
+/// The records do not have to start at the beginning of the log, but a record with
+/// index 0 must not reference a predecessor.
+pub fn verify_audit_log(records: &[AuditRecord], signer: &ed25519::Public) -> Result<()> {
+	let mut previous: Option<&AuditRecord> = None;
+
+	for record in records {
+		if !record.verify_signature(signer) {
+			return Err(Error::InvalidSignature(record.index))
+		}
+
+		match previous {
+			Some(previous) => {
+				let expected = previous.index + 1;
+				if record.index != expected {
+					return Err(Error::UnexpectedIndex { expected, found: record.index })
+				}
+				if record.previous_hash != previous.hash() {
+					return Err(Error::BrokenChain(record.index))
+				}
+			},
+			None =>
+				if record.index == 0 && record.previous_hash != H256::zero() {
+					return Err(Error::BrokenChain(record.index))
+				},
+		}
+
+		previous = Some(record);
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{AuditEvent, TokenFingerprint};
+	use codec::Encode;
+	use sp_core::Pair;
+	use std::{string::ToString, vec};
+
+	#[test]
+	fn valid_log_is_verified() {
+		let signer = test_signer();
+		let records = signed_chain(&signer, 4);
+
+		assert!(verify_audit_log(&records, &signer.public()).is_ok());
+		// A suffix of the log is valid as well.
+		assert!(verify_audit_log(&records[2..], &signer.public()).is_ok());
+	}
+
+	#[test]
+	fn modified_record_is_detected() {
+		let signer = test_signer();
+		let mut records = signed_chain(&signer, 3);
+		records[1].event = AuditEvent::LogStarted;
+
+		assert!(matches!(
+			verify_audit_log(&records, &signer.public()),
+			Err(Error::InvalidSignature(1))
+		));
+	}
+
+	#[test]
+	fn removed_record_is_detected() {
+		let signer = test_signer();
+		let mut records = signed_chain(&signer, 3);
+		records.remove(1);
+
+		assert!(matches!(
+			verify_audit_log(&records, &signer.public()),
+			Err(Error::UnexpectedIndex { expected: 1, found: 2 })
+		));
+	}
+
+	#[test]
+	fn replaced_record_is_detected() {
+		let signer = test_signer();
+		let mut records = signed_chain(&signer, 3);
+		// Validly signed, but not the record the chain refers to.
+		records[1] =
+			AuditRecord::new_signed(1, 0, records[0].hash(), AuditEvent::LogStarted, &signer);
+
+		assert!(matches!(verify_audit_log(&records, &signer.public()), Err(Error::BrokenChain(2))));
+	}
+
+	#[test]
+	fn records_of_another_signer_are_rejected() {
+		let records = signed_chain(&test_signer(), 2);
+		let other_signer = ed25519::Pair::from_seed(&[7u8; 32]);
+
+		assert!(matches!(
+			verify_audit_log(&records, &other_signer.public()),
+			Err(Error::InvalidSignature(0))
+		));
+	}
+
+	#[test]
+	fn decoding_log_file_works() {
+		let records = signed_chain(&test_signer(), 3);
+		let log_file: Vec<u8> =
+			records.iter().flat_map(|r| r.encode().encode()).collect::<Vec<u8>>();
+
+		assert_eq!(records, decode_audit_log(&log_file).unwrap());
+		assert!(decode_audit_log(&log_file[..log_file.len() - 1]).is_err());
+	}
+
+	fn signed_chain(signer: &ed25519::Pair, length: u64) -> Vec<AuditRecord> {
+		let mut records: Vec<AuditRecord> = vec![];
+		for index in 0..length {
+			let previous_hash = records.last().map(|r| r.hash()).unwrap_or_default();
+			let event = AuditEvent::TokenIssued {
+				client_id: "client".to_string(),
+				token: TokenFingerprint::from_token("token"),
+			};
+			records.push(AuditRecord::new_signed(
+				index,
+				index * 1000,
+				previous_hash,
+				event,
+				signer,
+			));
+		}
+		records
+	}
+
+	fn test_signer() -> ed25519::Pair {
+		ed25519::Pair::from_seed(&[3u8; 32])
+	}
+}
@@ -25,11 +25,17 @@ extern "C" {
 		mu_ra_addr_size: u32,
 		untrusted_worker_addr: *const u8,
 		untrusted_worker_addr_size: u32,
+		snapshot_retention: *const u8,
+		snapshot_retention_size: u32,
 	) -> sgx_status_t;
 
+	pub fn start_audit_log(eid: sgx_enclave_id_t, retval: *mut sgx_status_t) -> sgx_status_t;
+
 	pub fn init_enclave_sidechain_components(
 		eid: sgx_enclave_id_t,
 		retval: *mut sgx_status_t,
+		top_pool_limits: *const u8,
+		top_pool_limits_size: u32,
 	) -> sgx_status_t;
 
 	pub fn init_direct_invocation_server(
@@ -62,6 +68,8 @@ extern "C" {
 
 	pub fn execute_trusted_calls(eid: sgx_enclave_id_t, retval: *mut sgx_status_t) -> sgx_status_t;
 
+	pub fn persist_top_pool(eid: sgx_enclave_id_t, retval: *mut sgx_status_t) -> sgx_status_t;
+
 	pub fn sync_parentchain(
 		eid: sgx_enclave_id_t,
 		retval: *mut sgx_status_t,
@@ -108,6 +116,42 @@ extern "C" {
 		mrenclave_size: u32,
 	) -> sgx_status_t;
 
+	pub fn export_state(
+		eid: sgx_enclave_id_t,
+		retval: *mut sgx_status_t,
+		shard: *const u8,
+		shard_size: u32,
+		target_shielding_key: *const u8,
+		target_shielding_key_size: u32,
+		archive: *mut u8,
+		archive_size: u32,
+	) -> sgx_status_t;
+
+	pub fn import_state(
+		eid: sgx_enclave_id_t,
+		retval: *mut sgx_status_t,
+		archive: *const u8,
+		archive_size: u32,
+		shard: *mut u8,
+		shard_size: u32,
+	) -> sgx_status_t;
+
+	pub fn verify_state(
+		eid: sgx_enclave_id_t,
+		retval: *mut sgx_status_t,
+		rollback: c_int,
+		reports: *mut u8,
+		reports_size: u32,
+	) -> sgx_status_t;
+
+	pub fn rotate_state_key(eid: sgx_enclave_id_t, retval: *mut sgx_status_t) -> sgx_status_t;
+
+	pub fn re_encrypt_state(
+		eid: sgx_enclave_id_t,
+		retval: *mut sgx_status_t,
+		re_encrypted_snapshots: *mut u32,
+	) -> sgx_status_t;
+
 	pub fn generate_ias_ra_extrinsic(
 		eid: sgx_enclave_id_t,
 		retval: *mut sgx_status_t,
@@ -188,14 +232,20 @@ extern "C" {
 	pub fn update_market_data_xt(
 		eid: sgx_enclave_id_t,
 		retval: *mut sgx_status_t,
-		crypto_currency: *const u8,
-		crypto_currency_size: u32,
-		fiat_currency: *const u8,
-		fiat_currency_size: u32,
+		trading_pairs: *const u8,
+		trading_pairs_size: u32,
+		max_deviation_percent: u32,
 		unchecked_extrinsic: *mut u8,
 		unchecked_extrinsic_size: u32,
 	) -> sgx_status_t;
 
+	pub fn set_oracle_source_configs(
+		eid: sgx_enclave_id_t,
+		retval: *mut sgx_status_t,
+		configs_json: *const u8,
+		configs_json_size: u32,
+	) -> sgx_status_t;
+
 	pub fn update_weather_data_xt(
 		eid: sgx_enclave_id_t,
 		retval: *mut sgx_status_t,
@@ -207,10 +257,7 @@ extern "C" {
 		unchecked_extrinsic_size: u32,
 	) -> sgx_status_t;
 
-	pub fn start_oauth(
-		eid: sgx_enclave_id_t,
-		retval: *mut sgx_status_t,
-	) -> sgx_status_t;
+	pub fn start_oauth(eid: sgx_enclave_id_t, retval: *mut sgx_status_t) -> sgx_status_t;
 
 	pub fn run_state_provisioning_server(
 		eid: sgx_enclave_id_t,
@@ -23,8 +23,10 @@ use frame_support::ensure;
 use itc_parentchain::primitives::ParentchainInitParams;
 use itp_enclave_api_ffi as ffi;
 use itp_settings::worker::{
-	HEADER_MAX_SIZE, MR_ENCLAVE_SIZE, SHIELDING_KEY_SIZE, SIGNING_KEY_SIZE,
+	HEADER_MAX_SIZE, MR_ENCLAVE_SIZE, SHIELDING_KEY_SIZE, SIGNING_KEY_SIZE, STATE_ARCHIVE_MAX_SIZE,
+	STATE_VERIFICATION_REPORT_MAX_SIZE,
 };
+use itp_types::{ShardIdentifier, StateSnapshotRetention, StateVerificationReport, TopPoolLimits};
 use log::*;
 use sgx_crypto_helper::rsa3072::Rsa3072PubKey;
 use sgx_types::*;
@@ -33,10 +35,22 @@ use sp_core::ed25519;
 /// Trait for base/common Enclave API functions
 pub trait EnclaveBase: Send + Sync + 'static {
 	/// Initialize the enclave (needs to be called once at application startup).
-	fn init(&self, mu_ra_addr: &str, untrusted_worker_addr: &str) -> EnclaveResult<()>;
+	fn init(
+		&self,
+		mu_ra_addr: &str,
+		untrusted_worker_addr: &str,
+		snapshot_retention: &StateSnapshotRetention,
+	) -> EnclaveResult<()>;
+
+	/// Start the audit log of the enclave. Its records are persisted through OCalls, so the
+	/// OCall bridge has to be initialized first.
+	fn start_audit_log(&self) -> EnclaveResult<()>;
 
 	/// Initialize the enclave sidechain components.
-	fn init_enclave_sidechain_components(&self) -> EnclaveResult<()>;
+	fn init_enclave_sidechain_components(
+		&self,
+		top_pool_limits: &TopPoolLimits,
+	) -> EnclaveResult<()>;
 
 	/// Initialize the direct invocation RPC server.
 	fn init_direct_invocation_server(&self, rpc_server_addr: String) -> EnclaveResult<()>;
@@ -63,15 +77,54 @@ pub trait EnclaveBase: Send + Sync + 'static {
 	fn get_ecc_signing_pubkey(&self) -> EnclaveResult<ed25519::Public>;
 
 	fn get_mrenclave(&self) -> EnclaveResult<[u8; MR_ENCLAVE_SIZE]>;
+
+	/// Export the current state of a shard as a signed archive, which only the enclave
+	/// with the `target_shielding_key` can import. Returns the encoded archive.
+	fn export_state(
+		&self,
+		shard: &ShardIdentifier,
+		target_shielding_key: &Rsa3072PubKey,
+	) -> EnclaveResult<Vec<u8>>;
+
+	/// Import an archive created with `export_state`, replacing the state of its shard.
+	///
+	/// Only archives exported by this enclave, or by an enclave registered on the parentchain
+	/// with the same MRENCLAVE, are accepted. Returns the shard of the imported state.
+	fn import_state(&self, archive: &[u8]) -> EnclaveResult<ShardIdentifier>;
+
+	/// Verify the integrity of the state snapshots of all shards.
+	///
+	/// With `rollback`, each shard with corrupt snapshots is rolled back to its newest valid
+	/// snapshot and the corrupt snapshots are removed.
+	fn verify_state(&self, rollback: bool) -> EnclaveResult<Vec<StateVerificationReport>>;
+
+	/// Replace the key the state files are encrypted with by a new random key.
+	///
+	/// The state key shared with the other validateers of a shard is not affected. State files
+	/// encrypted with the replaced key stay readable, until they are re-encrypted with
+	/// `re_encrypt_state`.
+	fn rotate_state_key(&self) -> EnclaveResult<()>;
+
+	/// Re-encrypt all state snapshots that are not encrypted with the current state file key yet,
+	/// i.e. after a key rotation or legacy state files.
+	///
+	/// Returns the number of re-encrypted snapshots.
+	fn re_encrypt_state(&self) -> EnclaveResult<u32>;
 }
 
 /// EnclaveApi implementation for Enclave struct
 impl EnclaveBase for Enclave {
-	fn init(&self, mu_ra_addr: &str, untrusted_worker_addr: &str) -> EnclaveResult<()> {
+	fn init(
+		&self,
+		mu_ra_addr: &str,
+		untrusted_worker_addr: &str,
+		snapshot_retention: &StateSnapshotRetention,
+	) -> EnclaveResult<()> {
 		let mut retval = sgx_status_t::SGX_SUCCESS;
 
 		let encoded_mu_ra_addr = mu_ra_addr.encode();
 		let encoded_untrusted_worker_addr = untrusted_worker_addr.encode();
+		let encoded_snapshot_retention = snapshot_retention.encode();
 
 		let result = unsafe {
 			ffi::init(
@@ -81,6 +134,8 @@ impl EnclaveBase for Enclave {
 				encoded_mu_ra_addr.len() as u32,
 				encoded_untrusted_worker_addr.as_ptr(),
 				encoded_untrusted_worker_addr.len() as u32,
+				encoded_snapshot_retention.as_ptr(),
+				encoded_snapshot_retention.len() as u32,
 			)
 		};
 
@@ -90,10 +145,33 @@ impl EnclaveBase for Enclave {
 		Ok(())
 	}
 
-	fn init_enclave_sidechain_components(&self) -> EnclaveResult<()> {
+	fn start_audit_log(&self) -> EnclaveResult<()> {
 		let mut retval = sgx_status_t::SGX_SUCCESS;
 
-		let result = unsafe { ffi::init_enclave_sidechain_components(self.eid, &mut retval) };
+		let result = unsafe { ffi::start_audit_log(self.eid, &mut retval) };
+
+		ensure!(result == sgx_status_t::SGX_SUCCESS, Error::Sgx(result));
+		ensure!(retval == sgx_status_t::SGX_SUCCESS, Error::Sgx(retval));
+
+		Ok(())
+	}
+
+	fn init_enclave_sidechain_components(
+		&self,
+		top_pool_limits: &TopPoolLimits,
+	) -> EnclaveResult<()> {
+		let mut retval = sgx_status_t::SGX_SUCCESS;
+
+		let encoded_top_pool_limits = top_pool_limits.encode();
+
+		let result = unsafe {
+			ffi::init_enclave_sidechain_components(
+				self.eid,
+				&mut retval,
+				encoded_top_pool_limits.as_ptr(),
+				encoded_top_pool_limits.len() as u32,
+			)
+		};
 
 		ensure!(result == sgx_status_t::SGX_SUCCESS, Error::Sgx(result));
 		ensure!(retval == sgx_status_t::SGX_SUCCESS, Error::Sgx(retval));
@@ -241,6 +319,103 @@ impl EnclaveBase for Enclave {
 
 		Ok(mr_enclave)
 	}
+
+	fn export_state(
+		&self,
+		shard: &ShardIdentifier,
+		target_shielding_key: &Rsa3072PubKey,
+	) -> EnclaveResult<Vec<u8>> {
+		let mut retval = sgx_status_t::SGX_SUCCESS;
+
+		let encoded_target_shielding_key =
+			serde_json::to_vec(target_shielding_key).map_err(|e| Error::Other(e.into()))?;
+		let mut archive = vec![0u8; STATE_ARCHIVE_MAX_SIZE];
+
+		let result = unsafe {
+			ffi::export_state(
+				self.eid,
+				&mut retval,
+				shard.as_bytes().as_ptr(),
+				shard.as_bytes().len() as u32,
+				encoded_target_shielding_key.as_ptr(),
+				encoded_target_shielding_key.len() as u32,
+				archive.as_mut_ptr(),
+				archive.len() as u32,
+			)
+		};
+
+		ensure!(result == sgx_status_t::SGX_SUCCESS, Error::Sgx(result));
+		ensure!(retval == sgx_status_t::SGX_SUCCESS, Error::Sgx(retval));
+
+		// The archive is length-prefixed, which strips the padding of the buffer.
+		Ok(Decode::decode(&mut archive.as_slice())?)
+	}
+
+	fn import_state(&self, archive: &[u8]) -> EnclaveResult<ShardIdentifier> {
+		let mut retval = sgx_status_t::SGX_SUCCESS;
+
+		let mut shard = ShardIdentifier::default();
+
+		let result = unsafe {
+			ffi::import_state(
+				self.eid,
+				&mut retval,
+				archive.as_ptr(),
+				archive.len() as u32,
+				shard.as_bytes_mut().as_mut_ptr(),
+				shard.as_bytes().len() as u32,
+			)
+		};
+
+		ensure!(result == sgx_status_t::SGX_SUCCESS, Error::Sgx(result));
+		ensure!(retval == sgx_status_t::SGX_SUCCESS, Error::Sgx(retval));
+
+		Ok(shard)
+	}
+
+	fn verify_state(&self, rollback: bool) -> EnclaveResult<Vec<StateVerificationReport>> {
+		let mut retval = sgx_status_t::SGX_SUCCESS;
+		let mut reports = vec![0u8; STATE_VERIFICATION_REPORT_MAX_SIZE];
+
+		let result = unsafe {
+			ffi::verify_state(
+				self.eid,
+				&mut retval,
+				rollback.into(),
+				reports.as_mut_ptr(),
+				reports.len() as u32,
+			)
+		};
+
+		ensure!(result == sgx_status_t::SGX_SUCCESS, Error::Sgx(result));
+		ensure!(retval == sgx_status_t::SGX_SUCCESS, Error::Sgx(retval));
+
+		Ok(Decode::decode(&mut reports.as_slice())?)
+	}
+
+	fn rotate_state_key(&self) -> EnclaveResult<()> {
+		let mut retval = sgx_status_t::SGX_SUCCESS;
+
+		let result = unsafe { ffi::rotate_state_key(self.eid, &mut retval) };
+
+		ensure!(result == sgx_status_t::SGX_SUCCESS, Error::Sgx(result));
+		ensure!(retval == sgx_status_t::SGX_SUCCESS, Error::Sgx(retval));
+
+		Ok(())
+	}
+
+	fn re_encrypt_state(&self) -> EnclaveResult<u32> {
+		let mut retval = sgx_status_t::SGX_SUCCESS;
+		let mut re_encrypted_snapshots = 0u32;
+
+		let result =
+			unsafe { ffi::re_encrypt_state(self.eid, &mut retval, &mut re_encrypted_snapshots) };
+
+		ensure!(result == sgx_status_t::SGX_SUCCESS, Error::Sgx(result));
+		ensure!(retval == sgx_status_t::SGX_SUCCESS, Error::Sgx(retval));
+
+		Ok(re_encrypted_snapshots)
+	}
 }
 
 fn init_parentchain_components_ffi(
@@ -37,6 +37,9 @@ pub trait Sidechain: Send + Sync + 'static {
 	) -> EnclaveResult<()>;
 
 	fn execute_trusted_calls(&self) -> EnclaveResult<()>;
+
+	/// Seal the pending trusted calls of the TOP pool, so they survive a restart.
+	fn persist_top_pool(&self) -> EnclaveResult<()>;
 }
 
 impl Sidechain for Enclave {
@@ -80,6 +83,16 @@ impl Sidechain for Enclave {
 		ensure!(result == sgx_status_t::SGX_SUCCESS, Error::Sgx(result));
 		ensure!(retval == sgx_status_t::SGX_SUCCESS, Error::Sgx(retval));
 
+		Ok(())
+	}
+	fn persist_top_pool(&self) -> EnclaveResult<()> {
+		let mut retval = sgx_status_t::SGX_SUCCESS;
+
+		let result = unsafe { ffi::persist_top_pool(self.eid, &mut retval) };
+
+		ensure!(result == sgx_status_t::SGX_SUCCESS, Error::Sgx(result));
+		ensure!(retval == sgx_status_t::SGX_SUCCESS, Error::Sgx(retval));
+
 		Ok(())
 	}
 }
@@ -23,42 +23,45 @@ use log::*;
 use sgx_types::*;
 
 pub trait TeeracleApi: Send + Sync + 'static {
-	/// Update the currency market data for the token oracle.
+	/// Update the currency market data for the token oracle, for all the
+	/// (crypto currency, fiat currency) trading pairs at once. Rates deviating more than
+	/// `max_deviation_percent` from the median of all sources are rejected.
 	fn update_market_data_xt(
 		&self,
-		crypto_currency: &str,
-		fiat_currency: &str,
+		trading_pairs: &[(String, String)],
+		max_deviation_percent: u32,
 	) -> EnclaveResult<Vec<u8>>;
 
 	/// Update weather data for the corresponding coordinates.
 	fn update_weather_data_xt(&self, longitude: &str, latitude: &str) -> EnclaveResult<Vec<u8>>;
+
+	/// Replace the runtime-configured oracle sources with the given JSON array of configs.
+	fn set_oracle_source_configs(&self, configs_json: &str) -> EnclaveResult<()>;
 }
 
 impl TeeracleApi for Enclave {
 	fn update_market_data_xt(
 		&self,
-		crypto_currency: &str,
-		fiat_currency: &str,
+		trading_pairs: &[(String, String)],
+		max_deviation_percent: u32,
 	) -> EnclaveResult<Vec<u8>> {
 		info!(
-			"TeeracleApi update_market_data_xt in with crypto {} and fiat {}",
-			crypto_currency, fiat_currency
+			"TeeracleApi update_market_data_xt in with trading pairs {:?}, max deviation {}%",
+			trading_pairs, max_deviation_percent
 		);
 		let mut retval = sgx_status_t::SGX_SUCCESS;
 		let response_len = 8192;
 		let mut response: Vec<u8> = vec![0u8; response_len as usize];
 
-		let crypto_curr = crypto_currency.encode();
-		let fiat_curr = fiat_currency.encode();
+		let trading_pairs_encoded = trading_pairs.encode();
 
 		let res = unsafe {
 			ffi::update_market_data_xt(
 				self.eid,
 				&mut retval,
-				crypto_curr.as_ptr(),
-				crypto_curr.len() as u32,
-				fiat_curr.as_ptr(),
-				fiat_curr.len() as u32,
+				trading_pairs_encoded.as_ptr(),
+				trading_pairs_encoded.len() as u32,
+				max_deviation_percent,
 				response.as_mut_ptr(),
 				response_len,
 			)
@@ -98,4 +101,21 @@ impl TeeracleApi for Enclave {
 		ensure!(retval == sgx_status_t::SGX_SUCCESS, Error::Sgx(retval));
 		Ok(response)
 	}
+
+	fn set_oracle_source_configs(&self, configs_json: &str) -> EnclaveResult<()> {
+		let mut retval = sgx_status_t::SGX_SUCCESS;
+
+		let res = unsafe {
+			ffi::set_oracle_source_configs(
+				self.eid,
+				&mut retval,
+				configs_json.as_ptr(),
+				configs_json.len() as u32,
+			)
+		};
+
+		ensure!(res == sgx_status_t::SGX_SUCCESS, Error::Sgx(res));
+		ensure!(retval == sgx_status_t::SGX_SUCCESS, Error::Sgx(retval));
+		Ok(())
+	}
 }
@@ -25,10 +25,11 @@ extern crate sgx_tstd as std;
 
 use codec::{Decode, Encode};
 use std::string::String;
-use substrate_fixed::types::U32F32;
+use substrate_fixed::types::I64F64;
 
-// FIXME: Copied from ita-oracle because of cyclic deps. Should be removed after integritee-network/pallets#71
-pub type ExchangeRate = U32F32;
+/// Value of an oracle feed. Wide and signed, so that any feed (exchange rates,
+/// temperatures, ..) fits without loss.
+pub type OracleValue = I64F64;
 
 #[derive(Encode, Decode, Debug)]
 pub enum EnclaveMetric {
@@ -36,23 +37,23 @@ pub enum EnclaveMetric {
 	TopPoolSizeSet(u64),
 	TopPoolSizeIncrement,
 	TopPoolSizeDecrement,
-	ExchangeRateOracle(ExchangeRateOracleMetric),
-	// OracleMetric(OracleMetric<MetricsInfo>),
+	Oracle(OracleMetric),
 }
 
-#[derive(Encode, Decode, Debug)]
-pub enum ExchangeRateOracleMetric {
-	/// Exchange Rate from CoinGecko - (Source, TradingPair, ExchangeRate)
-	ExchangeRate(String, String, ExchangeRate),
-	/// Response time of the request in [ms]. (Source, ResponseTime)
-	ResponseTime(String, u128),
-	/// Increment the number of requests (Source)
-	NumberRequestsIncrement(String),
-}
-
-#[derive(Encode, Decode, Debug)]
-pub enum OracleMetric<MetricsInfo> {
-	OracleSpecificMetric(MetricsInfo),
+/// Source-agnostic oracle metric. A feed (e.g. `exchange_rate`) has values per key
+/// (e.g. the trading pair `TEER/USD`), which every source reports separately.
+#[derive(Encode, Decode, Debug, Clone, PartialEq)]
+pub enum OracleMetric {
+	/// Latest value of a feed reported by a source - (Source, Feed, Key, Value)
+	Value(String, String, String, OracleValue),
+	/// Response time of the request in [ms] - (Source, ResponseTime)
 	ResponseTime(String, u128),
+	/// Increment the number of requests - (Source)
 	NumberRequestsIncrement(String),
+	/// Increment the number of failed requests - (Source)
+	NumberFailuresIncrement(String),
+	/// Value of a source was rejected as outlier - (Source, Feed, Key)
+	RejectedValue(String, String, String),
+	/// Share of the response cache lookups served without a request - (Source, HitRatio)
+	CacheHitRatio(String, OracleValue),
 }
@@ -30,16 +30,16 @@ pub mod sgx_reexport_prelude {
 }
 
 use codec::Encode;
-use error::Result;
+use error::{Error, Result};
 use itp_node_api::{
 	api_client::{ParentchainAdditionalParams, ParentchainExtrinsicParams},
-	metadata::{provider::AccessNodeMetadata, NodeMetadata},
+	metadata::{pallet_utility::UtilityCallIndexes, provider::AccessNodeMetadata, NodeMetadata},
 };
 use itp_nonce_cache::{MutateNonce, Nonce};
 use itp_types::{parentchain::AccountId, OpaqueCall};
 use sp_core::H256;
 use sp_runtime::{generic::Era, OpaqueExtrinsic};
-use std::{sync::Arc, vec::Vec};
+use std::{format, sync::Arc, vec::Vec};
 use substrate_api_client::{compose_extrinsic_offline, ExtrinsicParams, SignExtrinsic};
 
 pub mod error;
@@ -56,6 +56,15 @@ pub trait CreateExtrinsics {
 		calls: &[OpaqueCall],
 		extrinsics_params: Option<ParentchainAdditionalParams>,
 	) -> Result<Vec<OpaqueExtrinsic>>;
+
+	/// Create a single extrinsic that dispatches all calls with `Utility::batch`
+	///
+	/// Increases the nonce counter once.
+	fn create_batch_extrinsic(
+		&self,
+		calls: &[OpaqueCall],
+		extrinsics_params: Option<ParentchainAdditionalParams>,
+	) -> Result<OpaqueExtrinsic>;
 }
 
 /// Extrinsics factory
@@ -136,6 +145,22 @@ where
 
 		Ok(extrinsics_buffer)
 	}
+
+	fn create_batch_extrinsic(
+		&self,
+		calls: &[OpaqueCall],
+		extrinsics_params: Option<ParentchainAdditionalParams>,
+	) -> Result<OpaqueExtrinsic> {
+		let batch_call_indexes = self
+			.node_metadata_repository
+			.get_from_metadata(|m| m.batch_call_indexes())?
+			.map_err(|e| Error::Other(format!("{:?}", e).into()))?;
+
+		let batch_call = OpaqueCall::from_tuple(&(batch_call_indexes, calls));
+		self.create_extrinsics(&[batch_call], extrinsics_params)?
+			.pop()
+			.ok_or_else(|| Error::Other("No extrinsic was created for the batch call".into()))
+	}
 }
 
 #[cfg(test)]
@@ -168,6 +193,24 @@ pub mod tests {
 		assert_eq!(nonce_cache.get_nonce().unwrap(), Nonce(opaque_calls.len() as NonceValue));
 	}
 
+	#[test]
+	pub fn batch_extrinsic_without_utility_metadata_fails_without_using_a_nonce() {
+		let nonce_cache = Arc::new(NonceCache::default());
+		let node_metadata_repo = Arc::new(NodeMetadataRepository::new(NodeMetadata::default()));
+		let extrinsics_factory = ExtrinsicsFactory::new(
+			test_genesis_hash(),
+			StaticExtrinsicSigner::<_, PairSignature>::new(test_account()),
+			nonce_cache.clone(),
+			node_metadata_repo,
+		);
+
+		let opaque_calls = [OpaqueCall(vec![3u8; 42]), OpaqueCall(vec![12u8, 78])];
+		let result = extrinsics_factory.create_batch_extrinsic(&opaque_calls, None);
+
+		assert!(result.is_err());
+		assert_eq!(nonce_cache.get_nonce().unwrap(), Nonce(0));
+	}
+
 	// #[test]
 	// pub fn xts_have_increasing_nonce() {
 	// 	let nonce_cache = Arc::new(NonceCache::default());
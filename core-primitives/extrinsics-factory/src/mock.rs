@@ -43,4 +43,13 @@ impl CreateExtrinsics for ExtrinsicsFactoryMock {
 		// 	.collect())
 		Ok(Vec::new())
 	}
+
+	fn create_batch_extrinsic(
+		&self,
+		_calls: &[OpaqueCall],
+		_additional_params: Option<ParentchainAdditionalParams>,
+	) -> Result<OpaqueExtrinsic> {
+		// An encoded empty vector is the smallest valid `OpaqueExtrinsic`.
+		Ok(OpaqueExtrinsic::from_bytes(&[0u8]).expect("Empty vector has valid codec; qed."))
+	}
 }
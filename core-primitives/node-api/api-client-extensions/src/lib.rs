@@ -21,12 +21,14 @@ pub use substrate_api_client::{api::Error as ApiClientError, rpc::WsRpcClient, A
 
 pub mod account;
 pub mod chain;
+pub mod pallet_oauth;
 pub mod pallet_teeracle;
 pub mod pallet_teerex;
 pub mod pallet_teerex_api_mock;
 
 pub use account::*;
 pub use chain::*;
+pub use pallet_oauth::*;
 pub use pallet_teeracle::*;
 pub use pallet_teerex::*;
 
@@ -15,5 +15,42 @@
 
 */
 
+use crate::ApiResult;
+use itp_types::parentchain::Hash;
+use substrate_api_client::{rpc::Request, Api, ExtrinsicParams, FrameSystemConfig, GetStorage};
+
 pub const TEERACLE: &str = "Teeracle";
 pub const ADD_TO_WHITELIST: &str = "add_to_whitelist";
+
+/// ApiClient extension that enables communication with the `teeracle` pallet.
+pub trait PalletTeeracleApi {
+	/// The latest blob an oracle published for `oracle_name` from `data_source`.
+	fn oracle_data(
+		&self,
+		oracle_name: &str,
+		data_source: &str,
+		at_block: Option<Hash>,
+	) -> ApiResult<Option<Vec<u8>>>;
+}
+
+impl<Signer, Client, Params, Runtime> PalletTeeracleApi for Api<Signer, Client, Params, Runtime>
+where
+	Client: Request,
+	Runtime: FrameSystemConfig<Hash = Hash>,
+	Params: ExtrinsicParams<Runtime::Index, Runtime::Hash>,
+{
+	fn oracle_data(
+		&self,
+		oracle_name: &str,
+		data_source: &str,
+		at_block: Option<Hash>,
+	) -> ApiResult<Option<Vec<u8>>> {
+		self.get_storage_double_map(
+			TEERACLE,
+			"OracleData",
+			oracle_name.to_string(),
+			data_source.to_string(),
+			at_block,
+		)
+	}
+}
@@ -20,7 +20,8 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use crate::{
-	error::Result, pallet_sidechain::SidechainCallIndexes, pallet_teerex::TeerexCallIndexes,
+	error::Result, pallet_oauth::OAuthCallIndexes, pallet_sidechain::SidechainCallIndexes,
+	pallet_sudo::SudoCallIndexes, pallet_teerex::TeerexCallIndexes,
 };
 use codec::{Decode, Encode};
 use sp_core::storage::StorageKey;
@@ -29,15 +30,24 @@ pub use crate::error::Error;
 pub use itp_api_client_types::{Metadata, MetadataError};
 
 pub mod error;
+pub mod pallet_oauth;
 pub mod pallet_sidechain;
+pub mod pallet_sudo;
 pub mod pallet_teeracle;
 pub mod pallet_teerex;
+pub mod pallet_utility;
 
 #[cfg(feature = "mocks")]
 pub mod metadata_mocks;
 
-pub trait NodeMetadataTrait: TeerexCallIndexes + SidechainCallIndexes {}
-impl<T: TeerexCallIndexes + SidechainCallIndexes> NodeMetadataTrait for T {}
+pub trait NodeMetadataTrait:
+	TeerexCallIndexes + SidechainCallIndexes + OAuthCallIndexes + SudoCallIndexes
+{
+}
+impl<T: TeerexCallIndexes + SidechainCallIndexes + OAuthCallIndexes + SudoCallIndexes>
+	NodeMetadataTrait for T
+{
+}
 
 impl TryFrom<NodeMetadata> for Metadata {
 	type Error = crate::error::Error;
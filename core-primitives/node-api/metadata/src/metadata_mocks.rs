@@ -16,7 +16,8 @@
 */
 
 use crate::{
-	error::Result, pallet_sidechain::SidechainCallIndexes, pallet_teerex::TeerexCallIndexes,
+	error::Result, pallet_oauth::OAuthCallIndexes, pallet_sidechain::SidechainCallIndexes,
+	pallet_sudo::SudoCallIndexes, pallet_teerex::TeerexCallIndexes,
 };
 use codec::{Decode, Encode};
 
@@ -43,8 +44,14 @@ pub struct NodeMetadataMock {
 	shield_funds: u8,
 	unshield_funds: u8,
 	publish_hash: u8,
+	shield_assets: u8,
+	unshield_assets: u8,
 	sidechain_module: u8,
 	imported_sidechain_block: u8,
+	oauth_module: u8,
+	register_oauth_client: u8,
+	sudo_module: u8,
+	sudo: u8,
 	runtime_spec_version: u32,
 	runtime_transaction_version: u32,
 }
@@ -63,8 +70,14 @@ impl NodeMetadataMock {
 			shield_funds: 4u8,
 			unshield_funds: 5u8,
 			publish_hash: 9u8,
+			shield_assets: 10u8,
+			unshield_assets: 11u8,
 			sidechain_module: 53u8,
 			imported_sidechain_block: 0u8,
+			oauth_module: 60u8,
+			register_oauth_client: 0u8,
+			sudo_module: 10u8,
+			sudo: 0u8,
 			runtime_spec_version: 25,
 			runtime_transaction_version: 4,
 		}
@@ -111,6 +124,14 @@ impl TeerexCallIndexes for NodeMetadataMock {
 	fn publish_hash_call_indexes(&self) -> Result<[u8; 2]> {
 		Ok([self.teerex_module, self.unshield_funds])
 	}
+
+	fn shield_assets_call_indexes(&self) -> Result<[u8; 2]> {
+		Ok([self.teerex_module, self.shield_assets])
+	}
+
+	fn unshield_assets_call_indexes(&self) -> Result<[u8; 2]> {
+		Ok([self.teerex_module, self.unshield_assets])
+	}
 }
 
 impl SidechainCallIndexes for NodeMetadataMock {
@@ -118,3 +139,15 @@ impl SidechainCallIndexes for NodeMetadataMock {
 		Ok([self.sidechain_module, self.imported_sidechain_block])
 	}
 }
+
+impl OAuthCallIndexes for NodeMetadataMock {
+	fn register_oauth_client_call_indexes(&self) -> Result<[u8; 2]> {
+		Ok([self.oauth_module, self.register_oauth_client])
+	}
+}
+
+impl SudoCallIndexes for NodeMetadataMock {
+	fn sudo_call_indexes(&self) -> Result<[u8; 2]> {
+		Ok([self.sudo_module, self.sudo])
+	}
+}
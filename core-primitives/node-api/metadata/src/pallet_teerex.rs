@@ -39,6 +39,10 @@ pub trait TeerexCallIndexes {
 
 	fn unshield_funds_call_indexes(&self) -> Result<[u8; 2]>;
 
+	fn shield_assets_call_indexes(&self) -> Result<[u8; 2]>;
+
+	fn unshield_assets_call_indexes(&self) -> Result<[u8; 2]>;
+
 	fn publish_hash_call_indexes(&self) -> Result<[u8; 2]>;
 }
 
@@ -85,6 +89,14 @@ impl TeerexCallIndexes for NodeMetadata {
 		self.call_indexes(TEEREX, "unshield_funds")
 	}
 
+	fn shield_assets_call_indexes(&self) -> Result<[u8; 2]> {
+		self.call_indexes(TEEREX, "shield_assets")
+	}
+
+	fn unshield_assets_call_indexes(&self) -> Result<[u8; 2]> {
+		self.call_indexes(TEEREX, "unshield_assets")
+	}
+
 	fn publish_hash_call_indexes(&self) -> Result<[u8; 2]> {
 		self.call_indexes(TEEREX, "publish_hash")
 	}
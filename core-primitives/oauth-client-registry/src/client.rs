@@ -0,0 +1,92 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use codec::{Decode, Encode};
+use std::{string::String, vec::Vec};
+
+/// Grant types a client may be allowed to use, see:
+/// https://datatracker.ietf.org/doc/html/rfc6749#section-4
+#[derive(Debug, Clone, Copy, Encode, Decode, Eq, PartialEq)]
+pub enum OAuthGrantType {
+	AuthorizationCode,
+	Implicit,
+	Password,
+	ClientCredentials,
+	RefreshToken,
+}
+
+/// An OAuth client (relying party), as registered through the parentchain.
+///
+/// The client secret itself is never part of the registration, only its hash.
+#[derive(Debug, Clone, Encode, Decode, Eq, PartialEq)]
+pub struct OAuthClient {
+	pub client_id: String,
+	pub redirect_uris: Vec<String>,
+	pub allowed_grants: Vec<OAuthGrantType>,
+	pub allowed_scopes: Vec<String>,
+	pub client_secret_hash: String,
+}
+
+impl OAuthClient {
+	pub fn new(
+		client_id: String,
+		redirect_uris: Vec<String>,
+		allowed_grants: Vec<OAuthGrantType>,
+		allowed_scopes: Vec<String>,
+		client_secret_hash: String,
+	) -> Self {
+		OAuthClient { client_id, redirect_uris, allowed_grants, allowed_scopes, client_secret_hash }
+	}
+
+	pub fn allows_grant(&self, grant_type: OAuthGrantType) -> bool {
+		self.allowed_grants.contains(&grant_type)
+	}
+
+	/// Redirect URIs have to match exactly, see:
+	/// https://datatracker.ietf.org/doc/html/rfc6749#section-3.1.2.3
+	pub fn allows_redirect_uri(&self, redirect_uri: &str) -> bool {
+		self.redirect_uris.iter().any(|uri| uri == redirect_uri)
+	}
+
+	pub fn allows_scope(&self, scope: &str) -> bool {
+		self.allowed_scopes.iter().any(|s| s == scope)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::string::ToString;
+
+	#[test]
+	fn allowed_grants_redirect_uris_and_scopes_are_matched_exactly() {
+		let client = OAuthClient::new(
+			"client_id".to_string(),
+			vec!["https://relying.party/callback".to_string()],
+			vec![OAuthGrantType::Password],
+			vec!["profile".to_string()],
+			"hash".to_string(),
+		);
+
+		assert!(client.allows_grant(OAuthGrantType::Password));
+		assert!(!client.allows_grant(OAuthGrantType::ClientCredentials));
+		assert!(client.allows_redirect_uri("https://relying.party/callback"));
+		assert!(!client.allows_redirect_uri("https://relying.party/callback/evil"));
+		assert!(client.allows_scope("profile"));
+		assert!(!client.allows_scope("email"));
+	}
+}
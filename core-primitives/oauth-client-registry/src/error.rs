@@ -0,0 +1,38 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use crate::sgx_reexport_prelude::*;
+
+use std::{boxed::Box, string::String};
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// OAuth client registry error
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	#[error("OAuth client registry lock is poisoned")]
+	LockPoisoning,
+	#[error("Invalid OAuth client registration: {0}")]
+	InvalidClient(String),
+	#[error("Codec error: {0}")]
+	Codec(#[from] codec::Error),
+	#[error("IO error: {0}")]
+	IO(#[from] std::io::Error),
+	#[error(transparent)]
+	Other(#[from] Box<dyn std::error::Error + Sync + Send + 'static>),
+}
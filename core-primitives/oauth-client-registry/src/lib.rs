@@ -0,0 +1,60 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Registry of the OAuth clients (relying parties) the enclave accepts.
+//!
+//! Clients are registered and updated through an indirect call from the parentchain,
+//! such that governance on the parentchain controls which relying parties exist.
+//! The registry is kept in memory and sealed to disk on every change.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(all(feature = "std", feature = "sgx"))]
+compile_error!("feature \"std\" and feature \"sgx\" cannot be enabled at the same time");
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+extern crate sgx_tstd as std;
+
+// re-export module to properly feature gate sgx and regular std environment
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+pub mod sgx_reexport_prelude {
+	pub use thiserror_sgx as thiserror;
+}
+
+pub mod client;
+pub mod error;
+pub mod registry;
+
+#[cfg(feature = "sgx")]
+pub mod sealing;
+
+#[cfg(any(test, feature = "mocks"))]
+pub mod mocks;
+
+pub use client::{OAuthClient, OAuthGrantType};
+pub use error::{Error, Result};
+pub use registry::{OAuthClientRegistry, OAuthClients};
+
+/// Look up registered OAuth clients.
+pub trait AccessOAuthClients {
+	fn get_client(&self, client_id: &str) -> Result<Option<OAuthClient>>;
+}
+
+/// Register a new OAuth client or update an existing one.
+pub trait RegisterOAuthClient {
+	fn register_client(&self, client: OAuthClient) -> Result<()>;
+}
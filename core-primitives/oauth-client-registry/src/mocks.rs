@@ -0,0 +1,78 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+#[cfg(feature = "sgx")]
+use std::sync::SgxRwLock as RwLock;
+
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+use crate::{
+	error::{Error, Result},
+	registry::OAuthClients,
+	AccessOAuthClients, OAuthClient, RegisterOAuthClient,
+};
+use itp_sgx_io::SealedIO;
+use std::vec::Vec;
+
+/// Sealing mock that keeps the sealed registry in memory.
+#[derive(Default)]
+pub struct OAuthClientRegistrySealMock {
+	sealed: RwLock<OAuthClients>,
+}
+
+impl SealedIO for OAuthClientRegistrySealMock {
+	type Error = Error;
+	type Unsealed = OAuthClients;
+
+	fn unseal(&self) -> Result<Self::Unsealed> {
+		self.sealed.read().map_err(|_| Error::LockPoisoning).map(|l| l.clone())
+	}
+
+	fn seal(&self, unsealed: &Self::Unsealed) -> Result<()> {
+		let mut sealed_lock = self.sealed.write().map_err(|_| Error::LockPoisoning)?;
+		*sealed_lock = unsealed.clone();
+		Ok(())
+	}
+}
+
+/// Registry mock that records all registrations.
+#[derive(Default)]
+pub struct OAuthClientRegistryMock {
+	registered: RwLock<Vec<OAuthClient>>,
+}
+
+impl OAuthClientRegistryMock {
+	pub fn registered_clients(&self) -> Vec<OAuthClient> {
+		self.registered.read().unwrap().clone()
+	}
+}
+
+impl AccessOAuthClients for OAuthClientRegistryMock {
+	fn get_client(&self, client_id: &str) -> Result<Option<OAuthClient>> {
+		let registered_lock = self.registered.read().map_err(|_| Error::LockPoisoning)?;
+		Ok(registered_lock.iter().rev().find(|c| c.client_id == client_id).cloned())
+	}
+}
+
+impl RegisterOAuthClient for OAuthClientRegistryMock {
+	fn register_client(&self, client: OAuthClient) -> Result<()> {
+		let mut registered_lock = self.registered.write().map_err(|_| Error::LockPoisoning)?;
+		registered_lock.push(client);
+		Ok(())
+	}
+}
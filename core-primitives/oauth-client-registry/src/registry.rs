@@ -0,0 +1,148 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+#[cfg(feature = "sgx")]
+use std::sync::SgxRwLock as RwLock;
+
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+use crate::{
+	error::{Error, Result},
+	AccessOAuthClients, OAuthClient, RegisterOAuthClient,
+};
+use itp_sgx_io::SealedIO;
+use log::*;
+use std::{collections::BTreeMap, string::String, sync::Arc};
+
+/// All registered clients, by client id.
+pub type OAuthClients = BTreeMap<String, OAuthClient>;
+
+/// Registry implementation. Stores the OAuth clients in-memory and in a file backed.
+/// Uses the SealedIO trait for the file backend.
+pub struct OAuthClientRegistry<SealedIo> {
+	clients_lock: RwLock<OAuthClients>,
+	sealed_io: Arc<SealedIo>,
+}
+
+impl<SealedIo> OAuthClientRegistry<SealedIo>
+where
+	SealedIo: SealedIO<Unsealed = OAuthClients, Error = Error>,
+{
+	pub fn new(clients: OAuthClients, sealed_io: Arc<SealedIo>) -> Self {
+		OAuthClientRegistry { clients_lock: RwLock::new(clients), sealed_io }
+	}
+
+	/// Load the registry from the sealed file.
+	pub fn load(sealed_io: Arc<SealedIo>) -> Result<Self> {
+		let clients = sealed_io.unseal()?;
+		Ok(Self::new(clients, sealed_io))
+	}
+}
+
+impl<SealedIo> AccessOAuthClients for OAuthClientRegistry<SealedIo>
+where
+	SealedIo: SealedIO<Unsealed = OAuthClients, Error = Error>,
+{
+	fn get_client(&self, client_id: &str) -> Result<Option<OAuthClient>> {
+		let clients_lock = self.clients_lock.read().map_err(|_| Error::LockPoisoning)?;
+		Ok(clients_lock.get(client_id).cloned())
+	}
+}
+
+impl<SealedIo> RegisterOAuthClient for OAuthClientRegistry<SealedIo>
+where
+	SealedIo: SealedIO<Unsealed = OAuthClients, Error = Error>,
+{
+	fn register_client(&self, client: OAuthClient) -> Result<()> {
+		if client.client_id.is_empty() {
+			return Err(Error::InvalidClient("client id is empty".into()))
+		}
+		if client.client_secret_hash.is_empty() {
+			return Err(Error::InvalidClient("client secret hash is empty".into()))
+		}
+
+		let mut clients_lock = self.clients_lock.write().map_err(|_| Error::LockPoisoning)?;
+
+		// Seal first, so the in-memory registry never diverges from the sealed one.
+		let mut updated_clients = clients_lock.clone();
+		let is_update = updated_clients.insert(client.client_id.clone(), client).is_some();
+		self.sealed_io.seal(&updated_clients)?;
+		*clients_lock = updated_clients;
+
+		debug!("OAuth client registry updated (existing client: {})", is_update);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{mocks::OAuthClientRegistrySealMock, OAuthGrantType};
+	use std::string::ToString;
+
+	type TestRegistry = OAuthClientRegistry<OAuthClientRegistrySealMock>;
+
+	#[test]
+	fn register_and_get_client_works() {
+		let seal_mock = Arc::new(OAuthClientRegistrySealMock::default());
+		let registry = TestRegistry::load(seal_mock.clone()).unwrap();
+
+		registry.register_client(test_client("client_id", "hash")).unwrap();
+
+		assert_eq!(
+			Some(test_client("client_id", "hash")),
+			registry.get_client("client_id").unwrap()
+		);
+		assert_eq!(None, registry.get_client("unknown_client").unwrap());
+		assert_eq!(1, seal_mock.unseal().unwrap().len());
+	}
+
+	#[test]
+	fn registering_existing_client_id_updates_the_client() {
+		let seal_mock = Arc::new(OAuthClientRegistrySealMock::default());
+		let registry = TestRegistry::load(seal_mock.clone()).unwrap();
+
+		registry.register_client(test_client("client_id", "hash")).unwrap();
+		registry.register_client(test_client("client_id", "rotated_hash")).unwrap();
+
+		assert_eq!(
+			"rotated_hash",
+			registry.get_client("client_id").unwrap().unwrap().client_secret_hash
+		);
+		assert_eq!(1, seal_mock.unseal().unwrap().len());
+	}
+
+	#[test]
+	fn registering_client_without_id_or_secret_fails() {
+		let registry = TestRegistry::load(Arc::new(Default::default())).unwrap();
+
+		assert!(registry.register_client(test_client("", "hash")).is_err());
+		assert!(registry.register_client(test_client("client_id", "")).is_err());
+		assert_eq!(None, registry.get_client("client_id").unwrap());
+	}
+
+	fn test_client(client_id: &str, secret_hash: &str) -> OAuthClient {
+		OAuthClient::new(
+			client_id.to_string(),
+			vec!["https://relying.party/callback".to_string()],
+			vec![OAuthGrantType::Password],
+			vec![],
+			secret_hash.to_string(),
+		)
+	}
+}
@@ -0,0 +1,54 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::{
+	error::{Error, Result},
+	registry::OAuthClients,
+};
+use codec::{Decode, Encode};
+use itp_settings::files::OAUTH_CLIENT_REGISTRY_FILE;
+use itp_sgx_io::{seal, unseal, SealedIO};
+use log::info;
+use std::sgxfs::SgxFile;
+
+/// Seals the OAuth client registry to a static file.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct OAuthClientRegistrySeal;
+
+impl SealedIO for OAuthClientRegistrySeal {
+	type Error = Error;
+	type Unsealed = OAuthClients;
+
+	fn unseal(&self) -> Result<Self::Unsealed> {
+		Ok(unseal(OAUTH_CLIENT_REGISTRY_FILE).map(|b| Decode::decode(&mut b.as_slice()))??)
+	}
+
+	fn seal(&self, unsealed: &Self::Unsealed) -> Result<()> {
+		Ok(unsealed.using_encoded(|bytes| seal(bytes, OAUTH_CLIENT_REGISTRY_FILE))?)
+	}
+}
+
+pub fn create_sealed_if_absent() -> Result<()> {
+	if SgxFile::open(OAUTH_CLIENT_REGISTRY_FILE).is_err() {
+		info!(
+			"[Enclave] OAuth client registry not found, creating empty one! {}",
+			OAUTH_CLIENT_REGISTRY_FILE
+		);
+		return OAuthClientRegistrySeal.seal(&OAuthClients::new())
+	}
+	Ok(())
+}
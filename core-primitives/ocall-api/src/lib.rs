@@ -25,8 +25,8 @@ use core::result::Result as StdResult;
 use derive_more::{Display, From};
 use itp_storage::Error as StorageError;
 use itp_types::{
-	storage::StorageEntryVerified, BlockHash, ShardIdentifier, TrustedOperationStatus,
-	WorkerRequest, WorkerResponse,
+	storage::StorageEntryVerified, BlockHash, PalletString, Request, ShardIdentifier,
+	TrustedOperationStatus, WorkerRequest, WorkerResponse,
 };
 use sgx_types::*;
 use sp_core::H256;
@@ -131,6 +131,13 @@ pub trait EnclaveSidechainOCallApi: Clone + Send + Sync {
 		maybe_until_block_hash: Option<BlockHash>,
 		shard_identifier: ShardIdentifier,
 	) -> SgxResult<Vec<SignedSidechainBlock>>;
+
+	/// Send (still encrypted) trusted operations to the trusted RPC servers of peer validateers.
+	fn send_trusted_operations_to_peers(
+		&self,
+		peer_urls: Vec<PalletString>,
+		requests: Vec<Request>,
+	) -> SgxResult<()>;
 }
 
 /// Newtype for IPFS CID
@@ -142,3 +149,9 @@ pub trait EnclaveIpfsOCallApi: Clone + Send + Sync {
 
 	fn read_ipfs(&self, cid: &IpfsCid) -> SgxResult<()>;
 }
+
+/// Trait for persisting audit log records on the untrusted side.
+pub trait EnclaveAuditLogOCallApi: Clone + Send + Sync {
+	/// Append a (signed and encoded) audit record to the audit log file.
+	fn append_audit_record(&self, encoded_record: Vec<u8>) -> SgxResult<()>;
+}
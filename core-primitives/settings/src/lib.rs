@@ -45,13 +45,23 @@ pub mod files {
 	pub const RSA3072_SEALED_KEY_FILE: &str = "rsa3072_key_sealed.bin";
 	pub const SEALED_SIGNER_SEED_FILE: &str = "ed25519_key_sealed.bin";
 	pub const AES_KEY_FILE_AND_INIT_V: &str = "aes_key_sealed.bin";
+	pub const STATE_FILE_KEY_FILE: &str = "state_file_key_sealed.bin";
+	pub const RETIRED_AES_KEYS_FILE: &str = "aes_retired_keys_sealed.bin";
 	pub const LIGHT_CLIENT_DB: &str = "light_client_db.bin";
+	pub const OAUTH_CLIENT_REGISTRY_FILE: &str = "oauth_client_registry_sealed.bin";
+	pub const TOTP_SECRETS_FILE: &str = "totp_secrets_sealed.bin";
+	pub const ORACLE_SOURCE_CONFIGS_FILE: &str = "oracle_source_configs_sealed.bin";
+	pub const AUDIT_LOG_HEAD_FILE: &str = "audit_log_head_sealed.bin";
+	pub const TOP_POOL_FILE: &str = "top_pool_sealed.bin";
+	// the audit log itself lives on the untrusted side, its records are signed by the enclave
+	pub const AUDIT_LOG_FILE: &str = "audit_log.bin";
 
 	pub const RA_DUMP_CERT_DER_FILE: &str = "ra_dump_cert.der";
 
 	// used by worker and enclave
 	pub const SHARDS_PATH: &str = "shards";
 	pub const ENCRYPTED_STATE_FILE: &str = "state.bin";
+	pub const ENCRYPTED_STATE_DELTA_FILE: &str = "state_delta.bin";
 	pub const LAST_SLOT_BIN: &str = "last_slot.bin";
 
 	#[cfg(feature = "production")]
@@ -65,7 +75,9 @@ pub mod files {
 	pub static RA_API_KEY_FILE: &str = "key.txt";
 
 	pub const SPID_MIN_LENGTH: usize = 32;
-	pub const STATE_SNAPSHOTS_CACHE_SIZE: usize = 4;
+	// Defaults of the state snapshot retention, configurable with the service's CLI
+	pub const DEFAULT_MAX_STATE_SNAPSHOTS: u32 = 4;
+	pub const DEFAULT_MAX_STATE_DELTA_CHAIN_LENGTH: u32 = 0;
 }
 
 /// Settings concerning the worker
@@ -80,6 +92,10 @@ pub mod worker {
 	pub const SIGNING_KEY_SIZE: usize = 32;
 	// size of the MR enclave
 	pub const MR_ENCLAVE_SIZE: usize = 32;
+	// maximum size of an exported state archive
+	pub const STATE_ARCHIVE_MAX_SIZE: usize = 16 * 1024 * 1024;
+	// maximum size of the state verification reports of all shards
+	pub const STATE_VERIFICATION_REPORT_MAX_SIZE: usize = 64 * 1024;
 	// Factors to tune the initial amount of enclave funding:
 	// Should be set to a value that ensures that the enclave can register itself
 	// and the worker can run for a certain time. Only for development.
@@ -96,6 +112,8 @@ pub mod sidechain {
 	use core::time::Duration;
 
 	pub static SLOT_DURATION: Duration = Duration::from_millis(1000);
+	// interval in which the pending operations of the TOP pool are sealed to disk
+	pub static TOP_POOL_PERSIST_INTERVAL: Duration = Duration::from_secs(60);
 }
 
 /// Settings concerning the enclave
@@ -107,4 +125,13 @@ pub mod teeracle {
 	use core::time::Duration;
 	// Send extrinsic to update market exchange rate on the parentchain once per day
 	pub static DEFAULT_MARKET_DATA_UPDATE_INTERVAL: Duration = Duration::from_secs(86400);
+	// Exchange rates deviating more than this from the median of all sources are rejected,
+	// unless another deviation is configured
+	pub const DEFAULT_MAX_EXCHANGE_RATE_DEVIATION_PERCENT: u32 = 5;
+	// Upper bound of a configured deviation, so outliers can't be let through wholesale
+	pub const MAX_EXCHANGE_RATE_DEVIATION_PERCENT_LIMIT: u32 = 50;
+	// Hours of hourly forecast published with a weather report, keeps the oracle blob small
+	pub const WEATHER_FORECAST_HOURS: u32 = 24;
+	// Trading pairs (crypto, fiat) the exchange rates are updated of, if none are configured
+	pub const DEFAULT_TRADING_PAIRS: &[(&str, &str)] = &[("TEER", "USD")];
 }
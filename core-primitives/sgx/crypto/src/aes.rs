@@ -17,19 +17,30 @@
 
 use crate::{
 	error::{Error, Result},
-	traits::StateCrypto,
+	traits::{AeadNonce, StateAeadCrypto, StateCrypto},
 };
 use aes::Aes128;
+use aes_gcm::{
+	aead::{generic_array::GenericArray, Aead, NewAead, Payload},
+	Aes128Gcm,
+};
 use codec::{Decode, Encode};
 use derive_more::Display;
 use ofb::{
 	cipher::{NewStreamCipher, SyncStreamCipher},
 	Ofb,
 };
-use std::convert::{TryFrom, TryInto};
+use sp_core::hashing::{blake2_128, blake2_64};
+use std::{
+	convert::{TryFrom, TryInto},
+	vec::Vec,
+};
 
 type AesOfb = Ofb<Aes128>;
 
+/// Context for deriving the AES-GCM key, so the key is not used with two different cipher modes.
+const AEAD_KEY_CONTEXT: &[u8] = b"state-aead-key";
+
 #[derive(Debug, Default, Encode, Decode, Clone, Copy, PartialEq, Eq)]
 pub struct Aes {
 	pub key: [u8; 16],
@@ -40,11 +51,28 @@ impl Aes {
 	pub fn new(key: [u8; 16], init_vec: [u8; 16]) -> Self {
 		Self { key, init_vec }
 	}
+
+	fn aead_cipher(&self) -> Aes128Gcm {
+		let aead_key = blake2_128(&(AEAD_KEY_CONTEXT, self.key).encode());
+		Aes128Gcm::new(GenericArray::from_slice(&aead_key))
+	}
 }
 
 #[derive(Copy, Clone, Debug, Display)]
 pub struct AesSeal;
 
+/// Seals the key the state files are encrypted with.
+///
+/// Unlike the state key sealed by [`AesSeal`], which is shared with the other validateers of
+/// the shard, it is local to this enclave, so it can be rotated without coordination.
+#[derive(Copy, Clone, Debug, Display)]
+pub struct StateFileKeySeal;
+
+/// Seals the state file keys that were replaced by a key rotation, but still protect some
+/// state files.
+#[derive(Copy, Clone, Debug, Display)]
+pub struct RetiredAesKeysSeal;
+
 impl StateCrypto for Aes {
 	type Error = Error;
 
@@ -57,6 +85,26 @@ impl StateCrypto for Aes {
 	}
 }
 
+impl StateAeadCrypto for Aes {
+	type Error = Error;
+
+	fn key_id(&self) -> [u8; 8] {
+		blake2_64(&self.encode())
+	}
+
+	fn encrypt_with_aad(&self, nonce: &AeadNonce, aad: &[u8], data: &[u8]) -> Result<Vec<u8>> {
+		self.aead_cipher()
+			.encrypt(GenericArray::from_slice(nonce), Payload { msg: data, aad })
+			.map_err(|_| Error::Other("AES-GCM encryption failed".into()))
+	}
+
+	fn decrypt_with_aad(&self, nonce: &AeadNonce, aad: &[u8], data: &[u8]) -> Result<Vec<u8>> {
+		self.aead_cipher()
+			.decrypt(GenericArray::from_slice(nonce), Payload { msg: data, aad })
+			.map_err(|_| Error::InvalidAuthenticationTag)
+	}
+}
+
 impl TryFrom<&Aes> for AesOfb {
 	type Error = Error;
 
@@ -77,7 +125,9 @@ pub use sgx::*;
 pub mod sgx {
 
 	use super::*;
-	use itp_settings::files::AES_KEY_FILE_AND_INIT_V;
+	use itp_settings::files::{
+		AES_KEY_FILE_AND_INIT_V, RETIRED_AES_KEYS_FILE, STATE_FILE_KEY_FILE,
+	};
 	use itp_sgx_io::{seal, unseal, SealedIO, StaticSealedIO};
 	use log::info;
 	use sgx_rand::{Rng, StdRng};
@@ -109,6 +159,58 @@ pub mod sgx {
 		}
 	}
 
+	impl StaticSealedIO for StateFileKeySeal {
+		type Error = Error;
+		type Unsealed = Aes;
+
+		fn unseal_from_static_file() -> Result<Self::Unsealed> {
+			Ok(unseal(STATE_FILE_KEY_FILE).map(|b| Decode::decode(&mut b.as_slice()))??)
+		}
+
+		fn seal_to_static_file(unsealed: &Self::Unsealed) -> Result<()> {
+			Ok(unsealed.using_encoded(|bytes| seal(bytes, STATE_FILE_KEY_FILE))?)
+		}
+	}
+
+	impl SealedIO for StateFileKeySeal {
+		type Error = Error;
+		type Unsealed = Aes;
+
+		fn unseal(&self) -> Result<Self::Unsealed> {
+			Self::unseal_from_static_file()
+		}
+
+		fn seal(&self, unsealed: &Self::Unsealed) -> Result<()> {
+			Self::seal_to_static_file(unsealed)
+		}
+	}
+
+	impl StaticSealedIO for RetiredAesKeysSeal {
+		type Error = Error;
+		type Unsealed = Vec<Aes>;
+
+		fn unseal_from_static_file() -> Result<Self::Unsealed> {
+			Ok(unseal(RETIRED_AES_KEYS_FILE).map(|b| Decode::decode(&mut b.as_slice()))??)
+		}
+
+		fn seal_to_static_file(unsealed: &Self::Unsealed) -> Result<()> {
+			Ok(unsealed.using_encoded(|bytes| seal(bytes, RETIRED_AES_KEYS_FILE))?)
+		}
+	}
+
+	impl SealedIO for RetiredAesKeysSeal {
+		type Error = Error;
+		type Unsealed = Vec<Aes>;
+
+		fn unseal(&self) -> Result<Self::Unsealed> {
+			Self::unseal_from_static_file()
+		}
+
+		fn seal(&self, unsealed: &Self::Unsealed) -> Result<()> {
+			Self::seal_to_static_file(unsealed)
+		}
+	}
+
 	pub fn create_sealed_if_absent() -> Result<()> {
 		if SgxFile::open(AES_KEY_FILE_AND_INIT_V).is_err() {
 			info!("[Enclave] Keyfile not found, creating new! {}", AES_KEY_FILE_AND_INIT_V);
@@ -118,6 +220,42 @@ pub mod sgx {
 	}
 
 	pub fn create_sealed() -> Result<()> {
+		AesSeal::seal_to_static_file(&random_key()?)
+	}
+
+	/// Creates the state file key, if it does not exist yet.
+	///
+	/// The state files were encrypted with the state key before, so the state key is retired
+	/// to keep them readable until they are re-encrypted. Requires the state key and the
+	/// retired keys to exist.
+	pub fn create_state_file_key_sealed_if_absent() -> Result<()> {
+		if SgxFile::open(STATE_FILE_KEY_FILE).is_ok() {
+			return Ok(())
+		}
+		info!("[Enclave] State file key not found, creating new! {}", STATE_FILE_KEY_FILE);
+
+		let state_key = AesSeal::unseal_from_static_file()?;
+		let mut retired_keys = RetiredAesKeysSeal::unseal_from_static_file()?;
+		if !retired_keys.contains(&state_key) {
+			retired_keys.push(state_key);
+			RetiredAesKeysSeal::seal_to_static_file(&retired_keys)?;
+		}
+		StateFileKeySeal::seal_to_static_file(&random_key()?)
+	}
+
+	pub fn create_retired_keys_sealed_if_absent() -> Result<()> {
+		if SgxFile::open(RETIRED_AES_KEYS_FILE).is_err() {
+			info!(
+				"[Enclave] No retired state keys found, creating empty list! {}",
+				RETIRED_AES_KEYS_FILE
+			);
+			return RetiredAesKeysSeal::seal_to_static_file(&Vec::new())
+		}
+		Ok(())
+	}
+
+	/// Creates a random AES key and initialization vector.
+	pub fn random_key() -> Result<Aes> {
 		let mut key = [0u8; 16];
 		let mut iv = [0u8; 16];
 
@@ -125,6 +263,65 @@ pub mod sgx {
 
 		rand.fill_bytes(&mut key);
 		rand.fill_bytes(&mut iv);
-		AesSeal::seal_to_static_file(&Aes::new(key, iv))
+		Ok(Aes::new(key, iv))
+	}
+
+	/// Creates a random nonce for an authenticated state encryption.
+	pub fn random_nonce() -> Result<AeadNonce> {
+		let mut nonce = AeadNonce::default();
+		StdRng::new()?.fill_bytes(&mut nonce);
+		Ok(nonce)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const NONCE: AeadNonce = [7u8; 12];
+
+	fn aes() -> Aes {
+		Aes::new([1u8; 16], [2u8; 16])
+	}
+
+	#[test]
+	fn aead_encrypt_decrypt_round_trip_works() {
+		let ciphertext = aes().encrypt_with_aad(&NONCE, b"shard", b"state").unwrap();
+
+		assert_ne!(b"state".to_vec(), ciphertext);
+		assert_eq!(
+			b"state".to_vec(),
+			aes().decrypt_with_aad(&NONCE, b"shard", &ciphertext).unwrap()
+		);
+	}
+
+	#[test]
+	fn aead_decrypt_fails_for_modified_ciphertext() {
+		let mut ciphertext = aes().encrypt_with_aad(&NONCE, b"shard", b"state").unwrap();
+		ciphertext[0] ^= 1;
+
+		assert!(matches!(
+			aes().decrypt_with_aad(&NONCE, b"shard", &ciphertext),
+			Err(Error::InvalidAuthenticationTag)
+		));
+	}
+
+	#[test]
+	fn aead_decrypt_fails_for_other_associated_data() {
+		let ciphertext = aes().encrypt_with_aad(&NONCE, b"shard", b"state").unwrap();
+
+		assert!(matches!(
+			aes().decrypt_with_aad(&NONCE, b"other shard", &ciphertext),
+			Err(Error::InvalidAuthenticationTag)
+		));
+	}
+
+	#[test]
+	fn aead_decrypt_fails_with_other_key() {
+		let ciphertext = aes().encrypt_with_aad(&NONCE, b"shard", b"state").unwrap();
+		let other_key = Aes::new([3u8; 16], [2u8; 16]);
+
+		assert_ne!(aes().key_id(), other_key.key_id());
+		assert!(other_key.decrypt_with_aad(&NONCE, b"shard", &ciphertext).is_err());
 	}
 }
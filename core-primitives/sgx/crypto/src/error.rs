@@ -26,6 +26,7 @@ use std::prelude::v1::Box;
 pub enum Error {
 	IO(std::io::Error),
 	InvalidNonceKeyLength,
+	InvalidAuthenticationTag,
 	Codec(codec::Error),
 	Serialization(serde_json::Error),
 	LockPoisoning,
@@ -23,7 +23,7 @@ use std::sync::RwLock;
 
 use crate::error::{Error, Result};
 use itp_sgx_io::SealedIO;
-use std::sync::Arc;
+use std::{sync::Arc, vec::Vec};
 
 /// Access a cryptographic key.
 pub trait AccessKey {
@@ -77,10 +77,49 @@ where
 	}
 }
 
+/// Rotate a key, by replacing it with `new_key`.
+///
+/// The replaced key is added to the retired keys first, so that data encrypted with it
+/// stays readable until it is re-encrypted with the new key.
+pub fn rotate_key<KeyType, KeyRepo, RetiredKeysRepo>(
+	key_repository: &KeyRepo,
+	retired_keys_repository: &RetiredKeysRepo,
+	new_key: KeyType,
+) -> Result<()>
+where
+	KeyRepo: AccessKey<KeyType = KeyType> + MutateKey<KeyType>,
+	RetiredKeysRepo: AccessKey<KeyType = Vec<KeyType>> + MutateKey<Vec<KeyType>>,
+{
+	let mut retired_keys = retired_keys_repository.retrieve_key()?;
+	retired_keys.push(key_repository.retrieve_key()?);
+	retired_keys_repository.update_key(retired_keys)?;
+
+	key_repository.update_key(new_key)
+}
+
+/// Remove the given keys from the retired keys, once no data is encrypted with them anymore.
+///
+/// Keys that were retired in the meantime are kept.
+pub fn forget_retired_keys<KeyType, RetiredKeysRepo>(
+	retired_keys_repository: &RetiredKeysRepo,
+	keys: &[KeyType],
+) -> Result<()>
+where
+	KeyType: PartialEq,
+	RetiredKeysRepo: AccessKey<KeyType = Vec<KeyType>> + MutateKey<Vec<KeyType>>,
+{
+	let mut retired_keys = retired_keys_repository.retrieve_key()?;
+	retired_keys.retain(|key| !keys.contains(key));
+	retired_keys_repository.update_key(retired_keys)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::{aes::Aes, mocks::AesSealMock};
+	use crate::{
+		aes::Aes,
+		mocks::{AesSealMock, KeyRepositoryMock},
+	};
 
 	type TestKeyRepository = KeyRepository<Aes, AesSealMock>;
 
@@ -97,4 +136,28 @@ mod tests {
 		assert_eq!(updated_key, key_repository.retrieve_key().unwrap());
 		assert_eq!(updated_key, seal_mock.unseal().unwrap());
 	}
+
+	#[test]
+	fn rotate_key_retires_the_replaced_key() {
+		let initial_key = Aes::new([1u8; 16], [0u8; 16]);
+		let key_repository = KeyRepositoryMock::new(initial_key);
+		let retired_keys_repository = KeyRepositoryMock::<Vec<Aes>>::default();
+
+		let new_key = Aes::new([2u8; 16], [0u8; 16]);
+		rotate_key(&key_repository, &retired_keys_repository, new_key).unwrap();
+
+		assert_eq!(new_key, key_repository.retrieve_key().unwrap());
+		assert_eq!(vec![initial_key], retired_keys_repository.retrieve_key().unwrap());
+	}
+
+	#[test]
+	fn forget_retired_keys_keeps_keys_retired_in_the_meantime() {
+		let first_key = Aes::new([1u8; 16], [0u8; 16]);
+		let second_key = Aes::new([2u8; 16], [0u8; 16]);
+		let retired_keys_repository = KeyRepositoryMock::new(vec![first_key, second_key]);
+
+		forget_retired_keys(&retired_keys_repository, &[first_key]).unwrap();
+
+		assert_eq!(vec![second_key], retired_keys_repository.retrieve_key().unwrap());
+	}
 }
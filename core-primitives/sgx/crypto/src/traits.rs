@@ -24,6 +24,28 @@ pub trait StateCrypto {
 	fn decrypt(&self, data: &mut [u8]) -> Result<(), Self::Error>;
 }
 
+/// Nonce of an authenticated state encryption, must never be reused with the same key.
+pub type AeadNonce = [u8; 12];
+
+/// Authenticated state encryption, binding the ciphertext to associated data.
+pub trait StateAeadCrypto {
+	type Error: Debug;
+	/// Identifies the key without revealing it, so a ciphertext can be matched to its key.
+	fn key_id(&self) -> [u8; 8];
+	fn encrypt_with_aad(
+		&self,
+		nonce: &AeadNonce,
+		aad: &[u8],
+		data: &[u8],
+	) -> Result<Vec<u8>, Self::Error>;
+	fn decrypt_with_aad(
+		&self,
+		nonce: &AeadNonce,
+		aad: &[u8],
+		data: &[u8],
+	) -> Result<Vec<u8>, Self::Error>;
+}
+
 pub trait ShieldingCryptoEncrypt {
 	type Error: Debug;
 	fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>, Self::Error>;
@@ -32,6 +32,7 @@ pub mod mocks;
 pub mod parentchain_pallet;
 pub mod sudo_pallet;
 pub mod system_pallet;
+pub mod transaction_payment_pallet;
 
 /// Interface to initialize a new state.
 pub trait InitState<State, AccountId> {
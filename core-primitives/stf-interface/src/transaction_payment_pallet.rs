@@ -0,0 +1,22 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+/// Interface trait of the transaction payment pallet.
+pub trait TransactionPaymentPalletInterface<State, Call> {
+	/// Check whether the sender of a call can pay its fee, without its account being reaped.
+	fn can_pay_fee(state: &mut State, call: &Call) -> bool;
+}
@@ -27,6 +27,7 @@ use base58::FromBase58Error;
 use crate::state_snapshot_primitives::StateId;
 use itp_types::ShardIdentifier;
 use sgx_types::sgx_status_t;
+use sp_core::ed25519;
 use std::{boxed::Box, format, string::String};
 
 pub type Result<T> = core::result::Result<T, Error>;
@@ -41,6 +42,18 @@ pub enum Error {
 	InvalidShard(ShardIdentifier),
 	#[error("State with hash {0} could not be found in the state repository")]
 	StateNotFoundInRepository(String),
+	#[error("No full state snapshot found, the delta snapshot {0} is based on")]
+	MissingBaseSnapshot(StateId),
+	#[error("State file {0} does not match the state hash it was sealed with")]
+	StateFileHashMismatch(StateId),
+	#[error("State file {0} is encrypted with an unknown state key")]
+	UnknownStateKey(StateId),
+	#[error("State archive signature is invalid")]
+	InvalidStateArchiveSignature,
+	#[error("State archive was signed by the untrusted key {0:?}")]
+	UntrustedStateArchiveSource(ed25519::Public),
+	#[error("State in archive does not match its state hash")]
+	StateArchiveHashMismatch,
 	#[error("State observer error: {0}")]
 	StateObserver(#[from] itp_stf_state_observer::error::Error),
 	#[error("Cache size for registry is zero")]
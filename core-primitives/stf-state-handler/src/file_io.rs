@@ -25,7 +25,7 @@ use rust_base58::base58::ToBase58;
 use base58::ToBase58;
 
 #[cfg(any(test, feature = "sgx"))]
-use itp_settings::files::ENCRYPTED_STATE_FILE;
+use itp_settings::files::{ENCRYPTED_STATE_DELTA_FILE, ENCRYPTED_STATE_FILE};
 
 #[cfg(any(test, feature = "sgx"))]
 use std::string::String;
@@ -69,6 +69,8 @@ pub trait StateFileIo {
 	) -> Result<Self::HashType>;
 
 	/// Write the state.
+	///
+	/// Replaces a delta snapshot with the same state ID, if there is one.
 	fn write(
 		&self,
 		shard_identifier: &ShardIdentifier,
@@ -76,7 +78,28 @@ pub trait StateFileIo {
 		state: &Self::StateType,
 	) -> Result<Self::HashType>;
 
-	/// Remove a state.
+	/// Write a delta snapshot, storing only the difference of the state to the previous state.
+	fn write_delta(
+		&self,
+		shard_identifier: &ShardIdentifier,
+		state_id: StateId,
+		previous_state: &Self::StateType,
+		state: &Self::StateType,
+	) -> Result<Self::HashType>;
+
+	/// Load a delta snapshot, by applying it to the state of the previous snapshot
+	/// (returns error if it does not exist).
+	fn load_delta(
+		&self,
+		shard_identifier: &ShardIdentifier,
+		state_id: StateId,
+		previous_state: Self::StateType,
+	) -> Result<Self::StateType>;
+
+	/// Checks if the snapshot with a given state ID is a delta snapshot.
+	fn is_delta(&self, shard_identifier: &ShardIdentifier, state_id: StateId) -> bool;
+
+	/// Remove a state, regardless if it's a full or a delta snapshot.
 	fn remove(&self, shard_identifier: &ShardIdentifier, state_id: StateId) -> Result<()>;
 
 	/// Checks if a given shard directory exists and contains at least one state instance.
@@ -85,75 +108,209 @@ pub trait StateFileIo {
 	/// Lists all shards.
 	fn list_shards(&self) -> Result<Vec<ShardIdentifier>>;
 
-	/// List all states for a shard.
+	/// List all states for a shard, full and delta snapshots.
 	fn list_state_ids_for_shard(&self, shard_identifier: &ShardIdentifier) -> Result<Vec<StateId>>;
 }
 
+/// Trait to re-encrypt state files with the current state key, e.g. after a key rotation.
+pub trait ReEncryptStateFile: StateFileIo {
+	/// Re-encrypt a state, full or delta snapshot, unless it is already encrypted with the current
+	/// state key. `state_hash` is the hash of the state the snapshot represents.
+	///
+	/// Returns whether the state was re-encrypted.
+	fn re_encrypt(
+		&self,
+		shard_identifier: &ShardIdentifier,
+		state_id: StateId,
+		state_hash: Self::HashType,
+	) -> Result<bool>;
+}
+
 #[cfg(feature = "sgx")]
 pub mod sgx {
 
 	use super::*;
-	use crate::error::Error;
+	use crate::{
+		error::Error,
+		state_delta::{apply_state_delta, compute_state_delta},
+	};
 	use base58::FromBase58;
-	use codec::Decode;
+	use codec::{Decode, Encode};
 	use core::fmt::Debug;
 	use itp_hashing::Hash;
-	use itp_sgx_crypto::{key_repository::AccessKey, StateCrypto};
-	use itp_sgx_externalities::SgxExternalitiesTrait;
+	use itp_sgx_crypto::{
+		aes::random_nonce, key_repository::AccessKey, AeadNonce, StateAeadCrypto, StateCrypto,
+	};
+	use itp_sgx_externalities::{
+		SgxExternalitiesDiffType, SgxExternalitiesTrait, SgxExternalitiesType,
+	};
 	use itp_sgx_io::{read as io_read, write as io_write};
 	use itp_types::H256;
 	use log::*;
 	use std::{fs, marker::PhantomData, path::Path, sync::Arc};
 
+	/// Prefix of state files with authenticated encryption.
+	///
+	/// State files without it are legacy files, encrypted with AES-OFB and without integrity protection.
+	const SEALED_STATE_FILE_PREFIX: &[u8; 4] = b"SST1";
+
+	/// State file with authenticated encryption. The shard, state ID and state hash are bound
+	/// to the ciphertext as associated data, so the file can't be modified or swapped unnoticed.
+	#[derive(Encode, Decode)]
+	struct SealedStateFile {
+		key_id: [u8; 8],
+		nonce: AeadNonce,
+		state_hash: H256,
+		ciphertext: Vec<u8>,
+	}
+
 	/// SGX state file I/O.
-	pub struct SgxStateFileIo<StateKeyRepository, State> {
+	pub struct SgxStateFileIo<StateKeyRepository, RetiredStateKeysRepository, State> {
 		state_key_repository: Arc<StateKeyRepository>,
+		retired_state_keys_repository: Arc<RetiredStateKeysRepository>,
 		_phantom: PhantomData<State>,
 	}
 
-	impl<StateKeyRepository, State> SgxStateFileIo<StateKeyRepository, State>
+	impl<StateKeyRepository, RetiredStateKeysRepository, State>
+		SgxStateFileIo<StateKeyRepository, RetiredStateKeysRepository, State>
 	where
 		StateKeyRepository: AccessKey,
-		<StateKeyRepository as AccessKey>::KeyType: StateCrypto,
+		<StateKeyRepository as AccessKey>::KeyType: StateCrypto + StateAeadCrypto,
+		RetiredStateKeysRepository:
+			AccessKey<KeyType = Vec<<StateKeyRepository as AccessKey>::KeyType>>,
 		State: SgxExternalitiesTrait,
 	{
-		pub fn new(state_key_repository: Arc<StateKeyRepository>) -> Self {
-			SgxStateFileIo { state_key_repository, _phantom: PhantomData }
+		pub fn new(
+			state_key_repository: Arc<StateKeyRepository>,
+			retired_state_keys_repository: Arc<RetiredStateKeysRepository>,
+		) -> Self {
+			SgxStateFileIo {
+				state_key_repository,
+				retired_state_keys_repository,
+				_phantom: PhantomData,
+			}
 		}
 
-		fn read(&self, path: &Path) -> Result<Vec<u8>> {
+		/// Reads and decrypts a state file.
+		///
+		/// Returns the state hash the file was sealed with, unless it is a legacy file.
+		fn read(
+			&self,
+			path: &Path,
+			shard_identifier: &ShardIdentifier,
+			state_id: StateId,
+		) -> Result<(Vec<u8>, Option<H256>)> {
 			let mut bytes = io_read(path)?;
 
 			if bytes.is_empty() {
-				return Ok(bytes)
+				return Ok((bytes, None))
 			}
 
-			let state_key = self.state_key_repository.retrieve_key()?;
-
-			state_key
-				.decrypt(&mut bytes)
+			let mut sealed_state_file =
+				match bytes.strip_prefix(SEALED_STATE_FILE_PREFIX.as_slice()) {
+					Some(sealed_state_file) => sealed_state_file,
+					None => {
+						self.legacy_state_key()?
+							.decrypt(&mut bytes)
+							.map_err(|e| Error::Other(format!("{:?}", e).into()))?;
+						trace!("legacy buffer decrypted = {:?}", bytes);
+						return Ok((bytes, None))
+					},
+				};
+
+			let sealed_state_file = SealedStateFile::decode(&mut sealed_state_file)?;
+			let state_key = self.state_key_with_id(&sealed_state_file.key_id, state_id)?;
+			let bytes = state_key
+				.decrypt_with_aad(
+					&sealed_state_file.nonce,
+					&associated_data(shard_identifier, state_id, &sealed_state_file.state_hash),
+					&sealed_state_file.ciphertext,
+				)
 				.map_err(|e| Error::Other(format!("{:?}", e).into()))?;
 			trace!("buffer decrypted = {:?}", bytes);
 
+			Ok((bytes, Some(sealed_state_file.state_hash)))
+		}
+
+		/// Encrypts a state with the current state key, using a fresh nonce.
+		fn encrypt(
+			&self,
+			shard_identifier: &ShardIdentifier,
+			state_id: StateId,
+			state_hash: H256,
+			state: Vec<u8>,
+		) -> Result<Vec<u8>> {
+			let state_key = self.state_key_repository.retrieve_key()?;
+			let nonce = random_nonce()?;
+
+			let ciphertext = state_key
+				.encrypt_with_aad(
+					&nonce,
+					&associated_data(shard_identifier, state_id, &state_hash),
+					&state,
+				)
+				.map_err(|e| Error::Other(format!("{:?}", e).into()))?;
+
+			let mut bytes = SEALED_STATE_FILE_PREFIX.to_vec();
+			SealedStateFile { key_id: state_key.key_id(), nonce, state_hash, ciphertext }
+				.encode_to(&mut bytes);
 			Ok(bytes)
 		}
 
-		fn encrypt(&self, mut state: Vec<u8>) -> Result<Vec<u8>> {
+		/// The current or a retired state key, depending on which one the state file was sealed with.
+		fn state_key_with_id(
+			&self,
+			key_id: &[u8; 8],
+			state_id: StateId,
+		) -> Result<<StateKeyRepository as AccessKey>::KeyType> {
 			let state_key = self.state_key_repository.retrieve_key()?;
+			if state_key.key_id() == *key_id {
+				return Ok(state_key)
+			}
+			self.retired_state_keys_repository
+				.retrieve_key()?
+				.into_iter()
+				.find(|retired_key| retired_key.key_id() == *key_id)
+				.ok_or(Error::UnknownStateKey(state_id))
+		}
 
-			state_key
-				.encrypt(&mut state)
-				.map_err(|e| Error::Other(format!("{:?}", e).into()))?;
-			Ok(state)
+		/// Legacy state files are encrypted with the oldest state key, all later keys were
+		/// introduced by a key rotation, which only seals with authenticated encryption.
+		fn legacy_state_key(&self) -> Result<<StateKeyRepository as AccessKey>::KeyType> {
+			match self.retired_state_keys_repository.retrieve_key()?.into_iter().next() {
+				Some(oldest_key) => Ok(oldest_key),
+				None => Ok(self.state_key_repository.retrieve_key()?),
+			}
+		}
+
+		fn is_encrypted_with_current_key(&self, bytes: &[u8]) -> Result<bool> {
+			let mut sealed_state_file =
+				match bytes.strip_prefix(SEALED_STATE_FILE_PREFIX.as_slice()) {
+					Some(sealed_state_file) => sealed_state_file,
+					None => return Ok(false),
+				};
+			let sealed_state_file = SealedStateFile::decode(&mut sealed_state_file)?;
+			Ok(self.state_key_repository.retrieve_key()?.key_id() == sealed_state_file.key_id)
 		}
 	}
 
-	impl<StateKeyRepository, State> StateFileIo for SgxStateFileIo<StateKeyRepository, State>
+	fn associated_data(
+		shard_identifier: &ShardIdentifier,
+		state_id: StateId,
+		state_hash: &H256,
+	) -> Vec<u8> {
+		(shard_identifier, state_id, state_hash).encode()
+	}
+
+	impl<StateKeyRepository, RetiredStateKeysRepository, State> StateFileIo
+		for SgxStateFileIo<StateKeyRepository, RetiredStateKeysRepository, State>
 	where
 		StateKeyRepository: AccessKey,
-		<StateKeyRepository as AccessKey>::KeyType: StateCrypto,
-		State: SgxExternalitiesTrait + Hash<H256> + Debug,
-		<State as SgxExternalitiesTrait>::SgxExternalitiesType: Encode + Decode,
+		<StateKeyRepository as AccessKey>::KeyType: StateCrypto + StateAeadCrypto,
+		RetiredStateKeysRepository:
+			AccessKey<KeyType = Vec<<StateKeyRepository as AccessKey>::KeyType>>,
+		State:
+			SgxExternalitiesTrait<SgxExternalitiesType = SgxExternalitiesType> + Hash<H256> + Debug,
 	{
 		type StateType = State;
 		type HashType = H256;
@@ -169,7 +326,8 @@ pub mod sgx {
 
 			let state_path = state_file_path(shard_identifier, state_id);
 			trace!("loading state from: {:?}", state_path);
-			let state_encoded = self.read(&state_path)?;
+			let (state_encoded, sealed_state_hash) =
+				self.read(&state_path, shard_identifier, state_id)?;
 
 			// State is now decrypted.
 			debug!(
@@ -185,6 +343,7 @@ pub mod sgx {
 			// Add empty state-diff.
 			let state_with_diff = State::new(state);
 			trace!("New state created: {:?}", state_with_diff);
+			ensure_sealed_state_hash(&state_with_diff, sealed_state_hash, state_id)?;
 			Ok(state_with_diff)
 		}
 
@@ -218,19 +377,77 @@ pub mod sgx {
 			let state_path = state_file_path(shard_identifier, state_id);
 			trace!("writing state to: {:?}", state_path);
 
+			let state_hash = state.hash();
+
 			// Only save the state, the state diff is pruned.
-			let cyphertext = self.encrypt(state.state().encode())?;
+			let cyphertext =
+				self.encrypt(shard_identifier, state_id, state_hash, state.state().encode())?;
+
+			io_write(&cyphertext, &state_path)?;
+
+			// A full snapshot replaces a delta snapshot of the same state, e.g. on compaction.
+			if self.is_delta(shard_identifier, state_id) {
+				fs::remove_file(delta_file_path(shard_identifier, state_id))
+					.map_err(|e| Error::Other(e.into()))?;
+			}
+
+			Ok(state_hash)
+		}
+
+		fn write_delta(
+			&self,
+			shard_identifier: &ShardIdentifier,
+			state_id: StateId,
+			previous_state: &Self::StateType,
+			state: &Self::StateType,
+		) -> Result<Self::HashType> {
+			let delta_path = delta_file_path(shard_identifier, state_id);
+			trace!("writing state delta to: {:?}", delta_path);
 
 			let state_hash = state.hash();
+			let delta = compute_state_delta(previous_state.state(), state.state());
+			let cyphertext =
+				self.encrypt(shard_identifier, state_id, state_hash, delta.encode())?;
 
-			io_write(&cyphertext, &state_path)?;
+			io_write(&cyphertext, &delta_path)?;
 
 			Ok(state_hash)
 		}
 
+		fn load_delta(
+			&self,
+			shard_identifier: &ShardIdentifier,
+			state_id: StateId,
+			previous_state: Self::StateType,
+		) -> Result<Self::StateType> {
+			if !self.is_delta(shard_identifier, state_id) {
+				return Err(Error::InvalidStateId(state_id))
+			}
+
+			let delta_path = delta_file_path(shard_identifier, state_id);
+			trace!("loading state delta from: {:?}", delta_path);
+			let (delta_encoded, sealed_state_hash) =
+				self.read(&delta_path, shard_identifier, state_id)?;
+			let delta = SgxExternalitiesDiffType::decode(&mut delta_encoded.as_slice())?;
+
+			let mut state = previous_state.state().clone();
+			apply_state_delta(&mut state, delta);
+			let state = State::new(state);
+			ensure_sealed_state_hash(&state, sealed_state_hash, state_id)?;
+			Ok(state)
+		}
+
+		fn is_delta(&self, shard_identifier: &ShardIdentifier, state_id: StateId) -> bool {
+			delta_file_path(shard_identifier, state_id).exists()
+		}
+
 		fn remove(&self, shard_identifier: &ShardIdentifier, state_id: StateId) -> Result<()> {
-			fs::remove_file(state_file_path(shard_identifier, state_id))
-				.map_err(|e| Error::Other(e.into()))
+			let file_path = if self.is_delta(shard_identifier, state_id) {
+				delta_file_path(shard_identifier, state_id)
+			} else {
+				state_file_path(shard_identifier, state_id)
+			};
+			fs::remove_file(file_path).map_err(|e| Error::Other(e.into()))
 		}
 
 		fn shard_exists(&self, shard_identifier: &ShardIdentifier) -> bool {
@@ -261,12 +478,72 @@ pub mod sgx {
 		}
 	}
 
-	fn state_file_path(shard: &ShardIdentifier, state_id: StateId) -> PathBuf {
+	impl<StateKeyRepository, RetiredStateKeysRepository, State> ReEncryptStateFile
+		for SgxStateFileIo<StateKeyRepository, RetiredStateKeysRepository, State>
+	where
+		StateKeyRepository: AccessKey,
+		<StateKeyRepository as AccessKey>::KeyType: StateCrypto + StateAeadCrypto,
+		RetiredStateKeysRepository:
+			AccessKey<KeyType = Vec<<StateKeyRepository as AccessKey>::KeyType>>,
+		State:
+			SgxExternalitiesTrait<SgxExternalitiesType = SgxExternalitiesType> + Hash<H256> + Debug,
+	{
+		fn re_encrypt(
+			&self,
+			shard_identifier: &ShardIdentifier,
+			state_id: StateId,
+			state_hash: Self::HashType,
+		) -> Result<bool> {
+			let file_path = if self.is_delta(shard_identifier, state_id) {
+				delta_file_path(shard_identifier, state_id)
+			} else {
+				state_file_path(shard_identifier, state_id)
+			};
+
+			if self.is_encrypted_with_current_key(&io_read(&file_path)?)? {
+				return Ok(false)
+			}
+
+			let (state_encoded, sealed_state_hash) =
+				self.read(&file_path, shard_identifier, state_id)?;
+			if sealed_state_hash.map_or(false, |sealed_state_hash| sealed_state_hash != state_hash)
+			{
+				return Err(Error::StateFileHashMismatch(state_id))
+			}
+
+			let cyphertext = self.encrypt(shard_identifier, state_id, state_hash, state_encoded)?;
+			io_write(&cyphertext, &file_path)?;
+
+			debug!("Re-encrypted state {} of shard {:?}", state_id, shard_identifier);
+			Ok(true)
+		}
+	}
+
+	/// Ensures a state matches the state hash its file was sealed with, if any.
+	fn ensure_sealed_state_hash<State: Hash<H256>>(
+		state: &State,
+		sealed_state_hash: Option<H256>,
+		state_id: StateId,
+	) -> Result<()> {
+		match sealed_state_hash {
+			Some(sealed_state_hash) if sealed_state_hash != state.hash() =>
+				Err(Error::StateFileHashMismatch(state_id)),
+			_ => Ok(()),
+		}
+	}
+
+	pub(crate) fn state_file_path(shard: &ShardIdentifier, state_id: StateId) -> PathBuf {
 		let mut shard_file_path = shard_path(shard);
 		shard_file_path.push(to_file_name(state_id));
 		shard_file_path
 	}
 
+	fn delta_file_path(shard: &ShardIdentifier, state_id: StateId) -> PathBuf {
+		let mut shard_file_path = shard_path(shard);
+		shard_file_path.push(to_delta_file_name(state_id));
+		shard_file_path
+	}
+
 	fn file_for_state_exists(shard: &ShardIdentifier, state_id: StateId) -> bool {
 		state_file_path(shard, state_id).exists()
 	}
@@ -336,9 +613,17 @@ fn to_file_name(state_id: StateId) -> String {
 	format!("{}_{}", state_id, ENCRYPTED_STATE_FILE)
 }
 
+#[cfg(any(test, feature = "sgx"))]
+fn to_delta_file_name(state_id: StateId) -> String {
+	format!("{}_{}", state_id, ENCRYPTED_STATE_DELTA_FILE)
+}
+
+/// Extracts the state ID of full and delta snapshot file names.
 #[cfg(any(test, feature = "sgx"))]
 fn extract_state_id_from_file_name(file_name: &str) -> Option<StateId> {
-	let state_id_str = file_name.strip_suffix(format!("_{}", ENCRYPTED_STATE_FILE).as_str())?;
+	let state_id_str = file_name
+		.strip_suffix(format!("_{}", ENCRYPTED_STATE_FILE).as_str())
+		.or_else(|| file_name.strip_suffix(format!("_{}", ENCRYPTED_STATE_DELTA_FILE).as_str()))?;
 	state_id_str.parse::<StateId>().ok()
 }
 
@@ -387,4 +672,18 @@ mod tests {
 		)
 		.is_none());
 	}
+
+	#[test]
+	fn extract_timestamp_from_delta_file_name_works() {
+		let now_time_stamp = generate_current_timestamp_state_id();
+		assert_eq!(
+			extract_state_id_from_file_name(to_delta_file_name(now_time_stamp).as_str()).unwrap(),
+			now_time_stamp
+		);
+		assert_ne!(to_file_name(now_time_stamp), to_delta_file_name(now_time_stamp));
+		assert!(extract_state_id_from_file_name(
+			format!("1234{}", ENCRYPTED_STATE_DELTA_FILE).as_str()
+		)
+		.is_none());
+	}
 }
@@ -30,11 +30,17 @@ use codec::Encode;
 use itp_sgx_externalities::{SgxExternalities, SgxExternalitiesType};
 use itp_types::{ShardIdentifier, H256};
 use sp_core::blake2_256;
-use std::{boxed::Box, collections::HashMap, sync::Arc, vec::Vec};
+use std::{
+	boxed::Box,
+	collections::{HashMap, HashSet},
+	sync::Arc,
+	vec::Vec,
+};
 
 type StateHash = H256;
 type ShardDirectory<State> = HashMap<StateId, (StateHash, State)>;
 type ShardsRootDirectory<State> = HashMap<ShardIdentifier, ShardDirectory<State>>;
+type DeltaStateIds = HashMap<ShardIdentifier, HashSet<StateId>>;
 type InnerStateSelector<State, ExternalState> =
 	Box<dyn Fn(&ExternalState) -> State + Send + Sync + 'static>;
 type ExternalStateGenerator<State, ExternalState> =
@@ -42,11 +48,14 @@ type ExternalStateGenerator<State, ExternalState> =
 
 /// State file I/O using (unencrypted) in-memory representation of the state files.
 /// Can be used as mock for testing.
+///
+/// Delta snapshots are emulated, they store the entire state and are only marked as delta.
 pub struct InMemoryStateFileIo<State, ExternalState>
 where
 	State: Clone + Default + Encode,
 {
 	emulated_shard_directory: RwLock<ShardsRootDirectory<State>>,
+	delta_state_ids: RwLock<DeltaStateIds>,
 	state_selector: InnerStateSelector<State, ExternalState>,
 	external_state_generator: ExternalStateGenerator<State, ExternalState>,
 }
@@ -66,6 +75,7 @@ where
 
 		InMemoryStateFileIo {
 			emulated_shard_directory: RwLock::new(shard_hash_map),
+			delta_state_ids: Default::default(),
 			state_selector,
 			external_state_generator,
 		}
@@ -92,6 +102,23 @@ where
 		let state_hash = self.compute_state_hash(&state);
 		(state_hash, state)
 	}
+
+	fn set_delta(
+		&self,
+		shard_identifier: &ShardIdentifier,
+		state_id: StateId,
+		is_delta: bool,
+	) -> Result<()> {
+		let mut delta_state_ids_lock =
+			self.delta_state_ids.write().map_err(|_| Error::LockPoisoning)?;
+		let delta_state_ids = delta_state_ids_lock.entry(*shard_identifier).or_default();
+		if is_delta {
+			delta_state_ids.insert(state_id);
+		} else {
+			delta_state_ids.remove(&state_id);
+		}
+		Ok(())
+	}
 }
 
 impl<State, ExternalState> StateFileIo for InMemoryStateFileIo<State, ExternalState>
@@ -159,10 +186,44 @@ where
 		let state_hash = self.compute_state_hash(&inner_state);
 
 		*states_for_shard.entry(state_id).or_default() = (state_hash, inner_state);
+		drop(directory_lock);
 
+		self.set_delta(shard_identifier, state_id, false)?;
 		Ok(state_hash)
 	}
 
+	fn write_delta(
+		&self,
+		shard_identifier: &ShardIdentifier,
+		state_id: StateId,
+		_previous_state: &Self::StateType,
+		external_state: &Self::StateType,
+	) -> Result<Self::HashType> {
+		let state_hash = self.write(shard_identifier, state_id, external_state)?;
+		self.set_delta(shard_identifier, state_id, true)?;
+		Ok(state_hash)
+	}
+
+	fn load_delta(
+		&self,
+		shard_identifier: &ShardIdentifier,
+		state_id: StateId,
+		_previous_state: Self::StateType,
+	) -> Result<Self::StateType> {
+		if !self.is_delta(shard_identifier, state_id) {
+			return Err(Error::InvalidStateId(state_id))
+		}
+		self.load(shard_identifier, state_id)
+	}
+
+	fn is_delta(&self, shard_identifier: &ShardIdentifier, state_id: StateId) -> bool {
+		let delta_state_ids_lock = self.delta_state_ids.read().unwrap();
+		delta_state_ids_lock
+			.get(shard_identifier)
+			.map(|delta_state_ids| delta_state_ids.contains(&state_id))
+			.unwrap_or(false)
+	}
+
 	fn remove(&self, shard_identifier: &ShardIdentifier, state_id: StateId) -> Result<()> {
 		let mut directory_lock =
 			self.emulated_shard_directory.write().map_err(|_| Error::LockPoisoning)?;
@@ -173,8 +234,10 @@ where
 
 		states_for_shard
 			.remove(&state_id)
-			.ok_or_else(|| Error::InvalidStateId(state_id))
-			.map(|_| {})
+			.ok_or_else(|| Error::InvalidStateId(state_id))?;
+		drop(directory_lock);
+
+		self.set_delta(shard_identifier, state_id, false)
 	}
 
 	fn shard_exists(&self, shard_identifier: &ShardIdentifier) -> bool {
@@ -367,6 +430,31 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn delta_snapshots_are_marked_until_overwritten_or_removed() {
+		let state_file_io = create_empty_in_memory_state_file_io();
+		let shard_id = ShardIdentifier::random();
+		let _ = state_file_io.initialize_shard(&shard_id, 1u128, &Default::default()).unwrap();
+
+		let _ = state_file_io.write_delta(&shard_id, 2u128, &0u64, &42u64).unwrap();
+		let _ = state_file_io.write_delta(&shard_id, 3u128, &42u64, &43u64).unwrap();
+
+		assert!(!state_file_io.is_delta(&shard_id, 1u128));
+		assert!(state_file_io.is_delta(&shard_id, 2u128));
+		assert_eq!(42u64, state_file_io.load_delta(&shard_id, 2u128, 0u64).unwrap());
+		assert_matches!(
+			state_file_io.load_delta(&shard_id, 1u128, 0u64),
+			Err(Error::InvalidStateId(1u128))
+		);
+
+		let _ = state_file_io.write(&shard_id, 2u128, &42u64).unwrap();
+		state_file_io.remove(&shard_id, 3u128).unwrap();
+
+		assert!(!state_file_io.is_delta(&shard_id, 2u128));
+		assert!(!state_file_io.is_delta(&shard_id, 3u128));
+		assert_eq!(2, state_file_io.list_state_ids_for_shard(&shard_id).unwrap().len());
+	}
+
 	#[test]
 	fn initialize_with_shards_creates_empty_maps() {
 		let shards = vec![ShardIdentifier::random(), ShardIdentifier::random()];
@@ -36,11 +36,15 @@ pub mod file_io;
 pub mod handle_state;
 pub mod in_memory_state_file_io;
 pub mod query_shard_state;
+pub mod re_encrypt_state;
+pub mod state_archive;
+pub mod state_delta;
 pub mod state_handler;
 pub mod state_initializer;
 mod state_snapshot_primitives;
 pub mod state_snapshot_repository;
 pub mod state_snapshot_repository_loader;
 pub mod test;
+pub mod verify_state;
 
 pub use state_handler::StateHandler;
@@ -0,0 +1,27 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+use crate::error::Result;
+
+/// Trait to re-encrypt the stored states with the current state key, e.g. after a key rotation
+/// or to migrate legacy state files to authenticated encryption.
+pub trait ReEncryptState {
+	/// Re-encrypt the state snapshots of all shards, one shard at a time, so state updates
+	/// of the other shards are not blocked meanwhile.
+	///
+	/// Returns the number of re-encrypted snapshots.
+	fn re_encrypt_states(&self) -> Result<usize>;
+}
@@ -0,0 +1,230 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+//! Portable archive of a shard's state, used to back up a shard or migrate it to another enclave.
+//!
+//! The state is encrypted with a fresh state key, which in turn is encrypted to the shielding key
+//! of the target enclave. The source enclave signs the archive with its signing key. The archive
+//! does not vouch for the MRENCLAVE of its source itself; the target decides which signing keys
+//! it trusts, based on trusted data like the verified enclave registry of the parentchain.
+
+use crate::error::{Error, Result};
+use codec::{Decode, Encode};
+use itp_sgx_crypto::{Aes, ShieldingCryptoDecrypt, ShieldingCryptoEncrypt, StateCrypto};
+use itp_types::{ShardIdentifier, H256};
+use sp_core::{blake2_256, ed25519, Pair};
+use std::{format, vec::Vec};
+
+/// Signed state archive, as exported by a source enclave.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+pub struct StateArchive {
+	pub payload: StateArchivePayload,
+	/// Signature of the source enclave over the encoded payload.
+	pub signature: ed25519::Signature,
+}
+
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+pub struct StateArchivePayload {
+	pub shard: ShardIdentifier,
+	/// Signing key of the enclave that exported the state.
+	pub source_signer: ed25519::Public,
+	/// Hash of the encoded state, before encryption.
+	pub state_hash: H256,
+	/// State key, encrypted to the shielding key of the target enclave.
+	pub encrypted_state_key: Vec<u8>,
+	/// Encoded state, encrypted with the state key.
+	pub encrypted_state: Vec<u8>,
+}
+
+impl StateArchive {
+	/// Encrypt an encoded state to the `target_shielding_key` and sign the result.
+	pub fn create<ShieldingKey: ShieldingCryptoEncrypt>(
+		shard: ShardIdentifier,
+		mut encoded_state: Vec<u8>,
+		state_key: Aes,
+		target_shielding_key: &ShieldingKey,
+		signer: &ed25519::Pair,
+	) -> Result<Self> {
+		let state_hash = blake2_256(&encoded_state).into();
+
+		let encrypted_state_key = target_shielding_key
+			.encrypt(&state_key.encode())
+			.map_err(|e| Error::Other(format!("{:?}", e).into()))?;
+		state_key.encrypt(&mut encoded_state)?;
+
+		let payload = StateArchivePayload {
+			shard,
+			source_signer: signer.public(),
+			state_hash,
+			encrypted_state_key,
+			encrypted_state: encoded_state,
+		};
+		let signature = signer.sign(&payload.encode());
+
+		Ok(StateArchive { payload, signature })
+	}
+
+	pub fn verify_signature(&self) -> bool {
+		ed25519::Pair::verify(&self.signature, self.payload.encode(), &self.payload.source_signer)
+	}
+
+	/// Check signature and origin of the archive and decrypt the state with our `shielding_key`.
+	///
+	/// Only archives signed by one of the `trusted_signers` are accepted, which must come from
+	/// a trusted source, never from the archive itself. Returns the shard and its encoded state.
+	pub fn open<ShieldingKey: ShieldingCryptoDecrypt>(
+		self,
+		shielding_key: &ShieldingKey,
+		trusted_signers: &[ed25519::Public],
+	) -> Result<(ShardIdentifier, Vec<u8>)> {
+		if !trusted_signers.contains(&self.payload.source_signer) {
+			return Err(Error::UntrustedStateArchiveSource(self.payload.source_signer))
+		}
+		if !self.verify_signature() {
+			return Err(Error::InvalidStateArchiveSignature)
+		}
+
+		let encoded_state_key = shielding_key
+			.decrypt(&self.payload.encrypted_state_key)
+			.map_err(|e| Error::Other(format!("{:?}", e).into()))?;
+		let state_key = Aes::decode(&mut encoded_state_key.as_slice())?;
+
+		let mut encoded_state = self.payload.encrypted_state;
+		state_key.decrypt(&mut encoded_state)?;
+
+		if H256::from(blake2_256(&encoded_state)) != self.payload.state_hash {
+			return Err(Error::StateArchiveHashMismatch)
+		}
+
+		Ok((self.payload.shard, encoded_state))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::assert_matches::assert_matches;
+
+	/// Shielding key stub, 'encrypts' by inverting all bytes.
+	struct InvertingShieldingKey;
+
+	impl ShieldingCryptoEncrypt for InvertingShieldingKey {
+		type Error = ();
+
+		fn encrypt(&self, data: &[u8]) -> core::result::Result<Vec<u8>, ()> {
+			Ok(data.iter().map(|b| !b).collect())
+		}
+	}
+
+	impl ShieldingCryptoDecrypt for InvertingShieldingKey {
+		type Error = ();
+
+		fn decrypt(&self, data: &[u8]) -> core::result::Result<Vec<u8>, ()> {
+			Ok(data.iter().map(|b| !b).collect())
+		}
+	}
+
+	#[test]
+	fn archive_can_be_opened_with_target_shielding_key() {
+		let shard = ShardIdentifier::from([1u8; 32]);
+		let state = vec![4u8, 5, 6, 7];
+
+		let archive = create_archive(shard, state.clone());
+
+		assert_ne!(archive.payload.encrypted_state, state);
+		assert!(archive.verify_signature());
+		assert_eq!(
+			archive.open(&InvertingShieldingKey, &[signer().public()]).unwrap(),
+			(shard, state)
+		);
+	}
+
+	#[test]
+	fn archive_survives_encoding_round_trip() {
+		let archive = create_archive(ShardIdentifier::default(), vec![1u8, 2, 3]);
+
+		let decoded = StateArchive::decode(&mut archive.encode().as_slice()).unwrap();
+
+		assert_eq!(decoded, archive);
+	}
+
+	#[test]
+	fn opening_archive_of_untrusted_signer_fails() {
+		let archive = create_archive(ShardIdentifier::default(), vec![1u8, 2, 3]);
+		let other_signer = ed25519::Pair::from_seed(&[8u8; 32]);
+
+		assert_matches!(
+			archive.open(&InvertingShieldingKey, &[other_signer.public()]),
+			Err(Error::UntrustedStateArchiveSource(s)) if s == signer().public()
+		);
+	}
+
+	#[test]
+	fn opening_archive_re_signed_by_untrusted_signer_fails() {
+		let mut archive = create_archive(ShardIdentifier::default(), vec![1u8, 2, 3]);
+		// A forger can produce a valid signature, but only with a key of its own.
+		let forger = ed25519::Pair::from_seed(&[9u8; 32]);
+		archive.payload.source_signer = forger.public();
+		archive.signature = forger.sign(&archive.payload.encode());
+
+		assert!(archive.verify_signature());
+		assert_matches!(
+			archive.open(&InvertingShieldingKey, &[signer().public()]),
+			Err(Error::UntrustedStateArchiveSource(_))
+		);
+	}
+
+	#[test]
+	fn opening_tampered_archive_fails() {
+		let mut archive = create_archive(ShardIdentifier::default(), vec![1u8, 2, 3]);
+		archive.payload.encrypted_state[0] ^= 1;
+
+		assert_matches!(
+			archive.open(&InvertingShieldingKey, &[signer().public()]),
+			Err(Error::InvalidStateArchiveSignature)
+		);
+	}
+
+	#[test]
+	fn opening_archive_with_wrong_state_key_fails() {
+		let mut archive = create_archive(ShardIdentifier::default(), vec![1u8, 2, 3]);
+		// Re-sign, so only the state hash check can catch the wrong key.
+		let signer = signer();
+		archive.payload.encrypted_state_key =
+			InvertingShieldingKey.encrypt(&Aes::new([9u8; 16], [9u8; 16]).encode()).unwrap();
+		archive.signature = signer.sign(&archive.payload.encode());
+
+		assert_matches!(
+			archive.open(&InvertingShieldingKey, &[signer().public()]),
+			Err(Error::StateArchiveHashMismatch)
+		);
+	}
+
+	fn create_archive(shard: ShardIdentifier, state: Vec<u8>) -> StateArchive {
+		StateArchive::create(
+			shard,
+			state,
+			Aes::new([2u8; 16], [3u8; 16]),
+			&InvertingShieldingKey,
+			&signer(),
+		)
+		.unwrap()
+	}
+
+	fn signer() -> ed25519::Pair {
+		ed25519::Pair::from_seed(&[42u8; 32])
+	}
+}
@@ -0,0 +1,100 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Difference between two versions of a state, as stored in delta snapshots.
+
+use itp_sgx_externalities::{SgxExternalitiesDiffType, SgxExternalitiesType};
+
+/// Compute the delta that turns the `previous` state into `state`.
+///
+/// Contains the new value of every added or changed key, and `None` for every removed key.
+pub fn compute_state_delta(
+	previous: &SgxExternalitiesType,
+	state: &SgxExternalitiesType,
+) -> SgxExternalitiesDiffType {
+	let mut delta = SgxExternalitiesDiffType::default();
+
+	for (key, value) in state.iter() {
+		if previous.get(key) != Some(value) {
+			delta.insert(key.clone(), Some(value.clone()));
+		}
+	}
+	for key in previous.keys() {
+		if !state.contains_key(key) {
+			delta.insert(key.clone(), None);
+		}
+	}
+	delta
+}
+
+/// Apply a delta computed with [`compute_state_delta`] to the previous state.
+pub fn apply_state_delta(state: &mut SgxExternalitiesType, delta: SgxExternalitiesDiffType) {
+	for (key, maybe_value) in delta.into_iter() {
+		match maybe_value {
+			Some(value) => {
+				state.insert(key, value);
+			},
+			None => {
+				state.remove(&key);
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn delta_contains_changed_added_and_removed_keys_only() {
+		let previous = state(&[(b"unchanged", b"1"), (b"changed", b"2"), (b"removed", b"3")]);
+		let new_state = state(&[(b"unchanged", b"1"), (b"changed", b"4"), (b"added", b"5")]);
+
+		let delta = compute_state_delta(&previous, &new_state);
+
+		assert_eq!(3, delta.len());
+		assert_eq!(Some(&Some(b"4".to_vec())), delta.get(b"changed".as_slice()));
+		assert_eq!(Some(&Some(b"5".to_vec())), delta.get(b"added".as_slice()));
+		assert_eq!(Some(&None), delta.get(b"removed".as_slice()));
+	}
+
+	#[test]
+	fn applying_delta_to_previous_state_restores_new_state() {
+		let previous = state(&[(b"a", b"1"), (b"b", b"2"), (b"c", b"3")]);
+		let new_state = state(&[(b"a", b"1"), (b"b", b"20"), (b"d", b"4")]);
+
+		let mut restored_state = previous.clone();
+		apply_state_delta(&mut restored_state, compute_state_delta(&previous, &new_state));
+
+		assert_eq!(new_state, restored_state);
+	}
+
+	#[test]
+	fn delta_of_equal_states_is_empty() {
+		let previous = state(&[(b"a", b"1")]);
+
+		assert!(compute_state_delta(&previous, &previous.clone()).is_empty());
+	}
+
+	fn state(entries: &[(&[u8], &[u8])]) -> SgxExternalitiesType {
+		let mut state = SgxExternalitiesType::default();
+		for (key, value) in entries {
+			state.insert(key.to_vec(), value.to_vec());
+		}
+		state
+	}
+}
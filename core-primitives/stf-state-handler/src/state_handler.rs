@@ -25,13 +25,17 @@ use crate::{
 	error::{Error, Result},
 	handle_state::HandleState,
 	query_shard_state::QueryShardState,
+	re_encrypt_state::ReEncryptState,
 	state_initializer::InitializeState,
-	state_snapshot_repository::VersionedStateAccess,
+	state_snapshot_repository::{
+		ReEncryptStateSnapshots, VerifyStateSnapshots, VersionedStateAccess,
+	},
+	verify_state::VerifyState,
 };
 use itp_hashing::Hash;
 use itp_sgx_externalities::SgxExternalitiesTrait;
 use itp_stf_state_observer::traits::UpdateState;
-use itp_types::ShardIdentifier;
+use itp_types::{ShardIdentifier, StateVerificationReport};
 use std::{collections::HashMap, sync::Arc, vec::Vec};
 
 type StatesMap<State, Hash> = HashMap<ShardIdentifier, (State, Hash)>;
@@ -242,6 +246,58 @@ where
 	}
 }
 
+impl<Repository, StateObserver, StateInitializer> VerifyState
+	for StateHandler<Repository, StateObserver, StateInitializer>
+where
+	Repository: VersionedStateAccess + VerifyStateSnapshots,
+	Repository::StateType: Hash<Repository::HashType>,
+	StateObserver: UpdateState<Repository::StateType>,
+	StateInitializer: InitializeState<StateType = Repository::StateType>,
+{
+	fn verify_states(&self, rollback: bool) -> Result<Vec<StateVerificationReport>> {
+		// Hold both locks, so no state is written while verifying.
+		let mut states_map_lock = self.states_map_lock.write().map_err(|_| Error::LockPoisoning)?;
+		let mut state_snapshots_lock =
+			self.state_snapshot_repository.write().map_err(|_| Error::LockPoisoning)?;
+
+		let mut reports = Vec::new();
+		for shard in state_snapshots_lock.list_shards()? {
+			let report = state_snapshots_lock.verify_snapshots(&shard, rollback)?;
+			if report.rolled_back {
+				let state = state_snapshots_lock.load_latest(&shard)?;
+				let state_hash = state.hash();
+				states_map_lock.insert(shard, (state.clone(), state_hash));
+				self.state_observer.queue_state_update(shard, state)?;
+			}
+			reports.push(report);
+		}
+		Ok(reports)
+	}
+}
+
+impl<Repository, StateObserver, StateInitializer> ReEncryptState
+	for StateHandler<Repository, StateObserver, StateInitializer>
+where
+	Repository: VersionedStateAccess + ReEncryptStateSnapshots,
+{
+	fn re_encrypt_states(&self) -> Result<usize> {
+		let shards = self
+			.state_snapshot_repository
+			.read()
+			.map_err(|_| Error::LockPoisoning)?
+			.list_shards()?;
+
+		let mut re_encrypted_snapshots = 0;
+		for shard in shards {
+			// Hold the write lock, so no snapshot of this shard is written or pruned meanwhile.
+			let state_snapshots_lock =
+				self.state_snapshot_repository.write().map_err(|_| Error::LockPoisoning)?;
+			re_encrypted_snapshots += state_snapshots_lock.re_encrypt_snapshots(&shard)?;
+		}
+		Ok(re_encrypted_snapshots)
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -25,16 +25,26 @@ pub type StateId = u128;
 pub(crate) type SnapshotHistory<HashType> =
 	HashMap<ShardIdentifier, VecDeque<StateSnapshotMetaData<HashType>>>;
 
-/// Internal wrapper for a state hash and state ID.
+/// Kind of a state snapshot file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SnapshotKind {
+	/// Contains the entire state.
+	Full,
+	/// Contains only the difference to the state of the previous snapshot.
+	Delta,
+}
+
+/// Internal wrapper for a state hash, state ID and the kind of snapshot.
 #[derive(Clone)]
 pub(crate) struct StateSnapshotMetaData<HashType> {
 	pub(crate) state_hash: HashType,
 	pub(crate) state_id: StateId,
+	pub(crate) kind: SnapshotKind,
 }
 
 impl<HashType> StateSnapshotMetaData<HashType> {
-	pub fn new(state_hash: HashType, state_id: StateId) -> Self {
-		StateSnapshotMetaData { state_hash, state_id }
+	pub fn new(state_hash: HashType, state_id: StateId, kind: SnapshotKind) -> Self {
+		StateSnapshotMetaData { state_hash, state_id, kind }
 	}
 }
 
@@ -48,7 +58,7 @@ where
 {
 	let state_id = generate_current_timestamp_state_id();
 	let state_hash = file_io.initialize_shard(shard_identifier, state_id, state)?;
-	Ok(StateSnapshotMetaData::new(state_hash, state_id))
+	Ok(StateSnapshotMetaData::new(state_hash, state_id, SnapshotKind::Full))
 }
 
 pub(crate) fn generate_current_timestamp_state_id() -> StateId {
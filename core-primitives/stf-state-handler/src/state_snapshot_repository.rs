@@ -17,16 +17,25 @@
 
 use crate::{
 	error::{Error, Result},
-	file_io::StateFileIo,
+	file_io::{ReEncryptStateFile, StateFileIo},
 	state_snapshot_primitives::{
 		generate_current_timestamp_state_id, initialize_shard_with_snapshot, SnapshotHistory,
-		StateId, StateSnapshotMetaData,
+		SnapshotKind, StateId, StateSnapshotMetaData,
 	},
 };
-use core::ops::RangeBounds;
-use itp_types::ShardIdentifier;
+use core::{ops::RangeBounds, time::Duration};
+use itp_hashing::Hash;
+use itp_types::{
+	ShardIdentifier, StateSnapshotCorruption, StateSnapshotRetention, StateVerificationReport,
+};
 use log::*;
-use std::{collections::VecDeque, fmt::Debug, format, sync::Arc, vec::Vec};
+use std::{
+	collections::{HashMap, HashSet, VecDeque},
+	fmt::Debug,
+	format,
+	sync::Arc,
+	vec::Vec,
+};
 
 /// Trait for versioned state access. Manages history of state snapshots.
 pub trait VersionedStateAccess {
@@ -67,11 +76,37 @@ pub trait VersionedStateAccess {
 	fn list_shards(&self) -> Result<Vec<ShardIdentifier>>;
 }
 
+/// Trait to verify the integrity of the state snapshots.
+pub trait VerifyStateSnapshots {
+	/// Verify that all snapshots of a shard can be loaded and match their hash in the
+	/// snapshot history. Snapshot files that are not part of the history are reported as corrupt.
+	///
+	/// With `rollback`, the corrupt snapshots are removed, which makes the newest valid snapshot
+	/// the latest state. A shard without any valid snapshot is not rolled back.
+	fn verify_snapshots(
+		&mut self,
+		shard_identifier: &ShardIdentifier,
+		rollback: bool,
+	) -> Result<StateVerificationReport>;
+}
+
+/// Trait to re-encrypt the state snapshots with the current state key.
+pub trait ReEncryptStateSnapshots {
+	/// Re-encrypt all snapshots of a shard that are not encrypted with the current state key yet.
+	///
+	/// Returns the number of re-encrypted snapshots.
+	fn re_encrypt_snapshots(&self, shard_identifier: &ShardIdentifier) -> Result<usize>;
+}
+
 /// State snapshot repository.
 ///
-/// Keeps versions of state snapshots, cycles them in a fixed-size circular buffer.
+/// Keeps versions of state snapshots, bounded by a retention policy (number and age of snapshots).
 /// Creates a state snapshot for each write/update operation. Allows reverting to a specific snapshot,
 /// identified by a state hash. Snapshot files names includes a timestamp to be unique.
+///
+/// Optionally writes delta snapshots, which only store the difference to the previous snapshot.
+/// When the full snapshot a delta snapshot is based on is pruned, the delta snapshots are compacted
+/// into a new full snapshot.
 pub struct StateSnapshotRepository<FileIo>
 where
 	FileIo: StateFileIo,
@@ -79,8 +114,10 @@ where
 	<FileIo as StateFileIo>::StateType: Clone,
 {
 	file_io: Arc<FileIo>,
-	snapshot_history_cache_size: usize,
+	snapshot_retention: StateSnapshotRetention,
 	snapshot_history: SnapshotHistory<FileIo::HashType>,
+	/// Latest state per shard, to compute delta snapshots without loading the previous state.
+	latest_states: HashMap<ShardIdentifier, FileIo::StateType>,
 }
 
 impl<FileIo> StateSnapshotRepository<FileIo>
@@ -90,8 +127,8 @@ where
 	<FileIo as StateFileIo>::StateType: Clone,
 {
 	/// Constructor, initialized with no shards or snapshot history.
-	pub fn empty(file_io: Arc<FileIo>, snapshot_history_cache_size: usize) -> Result<Self> {
-		Self::new(file_io, snapshot_history_cache_size, SnapshotHistory::default())
+	pub fn empty(file_io: Arc<FileIo>, snapshot_retention: StateSnapshotRetention) -> Result<Self> {
+		Self::new(file_io, snapshot_retention, SnapshotHistory::default())
 	}
 
 	/// Constructor to initialize the repository with shards and snapshot history.
@@ -99,14 +136,19 @@ where
 	/// Crate private, to be used by the loader.
 	pub(crate) fn new(
 		file_io: Arc<FileIo>,
-		snapshot_history_cache_size: usize,
+		snapshot_retention: StateSnapshotRetention,
 		snapshot_history: SnapshotHistory<FileIo::HashType>,
 	) -> Result<Self> {
-		if snapshot_history_cache_size == 0usize {
+		if snapshot_retention.max_snapshots == 0 {
 			return Err(Error::ZeroCacheSize)
 		}
 
-		Ok(StateSnapshotRepository { file_io, snapshot_history_cache_size, snapshot_history })
+		Ok(StateSnapshotRepository {
+			file_io,
+			snapshot_retention,
+			snapshot_history,
+			latest_states: Default::default(),
+		})
 	}
 
 	fn get_snapshot_history_mut(
@@ -127,12 +169,80 @@ where
 			.ok_or_else(|| Error::InvalidShard(*shard_identifier))
 	}
 
-	fn get_latest_snapshot_metadata(
-		&self,
+	fn writes_delta_snapshots(&self) -> bool {
+		self.snapshot_retention.max_delta_chain_length > 0
+	}
+
+	fn cache_latest_state(
+		&mut self,
 		shard_identifier: &ShardIdentifier,
-	) -> Result<&StateSnapshotMetaData<FileIo::HashType>> {
+		state: &FileIo::StateType,
+	) {
+		if self.writes_delta_snapshots() {
+			self.latest_states.insert(*shard_identifier, state.clone());
+		}
+	}
+
+	/// Number of delta snapshots written since the latest full snapshot.
+	fn delta_chain_length(&self, shard_identifier: &ShardIdentifier) -> Result<usize> {
+		Ok(self
+			.get_snapshot_history(shard_identifier)?
+			.iter()
+			.take_while(|snapshot_metadata| snapshot_metadata.kind == SnapshotKind::Delta)
+			.count())
+	}
+
+	/// Removes the snapshots exceeding the retention policy, the latest snapshot is always kept.
+	fn prune_snapshot_history(&mut self, shard_identifier: &ShardIdentifier) -> Result<()> {
 		let snapshot_history = self.get_snapshot_history(shard_identifier)?;
-		snapshot_history.front().ok_or(Error::EmptyRepository)
+
+		let mut retained_snapshots =
+			snapshot_history.len().min(self.snapshot_retention.max_snapshots as usize);
+		if let Some(max_age_secs) = self.snapshot_retention.max_age_secs {
+			// State IDs are timestamps (in ns) of when the snapshot was written.
+			let min_state_id = generate_current_timestamp_state_id()
+				.saturating_sub(Duration::from_secs(max_age_secs).as_nanos());
+			while retained_snapshots > 1
+				&& snapshot_history[retained_snapshots - 1].state_id < min_state_id
+			{
+				retained_snapshots -= 1;
+			}
+		}
+
+		if retained_snapshots == snapshot_history.len() {
+			return Ok(())
+		}
+
+		self.compact(shard_identifier, retained_snapshots - 1)?;
+		self.prune_snapshot_history_by_range(shard_identifier, retained_snapshots..)
+	}
+
+	/// Merges the snapshot at the given index of the history with all the (older) snapshots
+	/// it's based on into a full snapshot. Afterwards, the older snapshots can be removed.
+	fn compact(&mut self, shard_identifier: &ShardIdentifier, snapshot_index: usize) -> Result<()> {
+		let snapshot_metadata = self
+			.get_snapshot_history(shard_identifier)?
+			.get(snapshot_index)
+			.cloned()
+			.ok_or(Error::EmptyRepository)?;
+
+		if snapshot_metadata.kind == SnapshotKind::Full {
+			return Ok(())
+		}
+
+		let state = self.load_state(shard_identifier, snapshot_index)?;
+		self.file_io.write(shard_identifier, snapshot_metadata.state_id, &state)?;
+
+		if let Some(compacted_snapshot_metadata) =
+			self.get_snapshot_history_mut(shard_identifier)?.get_mut(snapshot_index)
+		{
+			compacted_snapshot_metadata.kind = SnapshotKind::Full;
+		}
+		debug!(
+			"Compacted delta snapshot {} of shard {:?} into a full snapshot",
+			snapshot_metadata.state_id, shard_identifier
+		);
+		Ok(())
 	}
 
 	fn prune_snapshot_history_by_range<R: RangeBounds<usize>>(
@@ -166,14 +276,26 @@ where
 		}
 	}
 
+	/// Writes a delta snapshot, unless deltas are disabled or the maximum number of delta
+	/// snapshots in a row is reached, then a full snapshot is written.
 	fn write_new_state(
 		&self,
 		shard_identifier: &ShardIdentifier,
 		state: &FileIo::StateType,
-	) -> Result<(FileIo::HashType, StateId)> {
+	) -> Result<(StateId, SnapshotKind)> {
 		let state_id = generate_current_timestamp_state_id();
-		let state_hash = self.file_io.write(shard_identifier, state_id, state)?;
-		Ok((state_hash, state_id))
+
+		if self.writes_delta_snapshots()
+			&& self.delta_chain_length(shard_identifier)?
+				< self.snapshot_retention.max_delta_chain_length as usize
+		{
+			let previous_state = self.load_latest(shard_identifier)?;
+			self.file_io.write_delta(shard_identifier, state_id, &previous_state, state)?;
+			return Ok((state_id, SnapshotKind::Delta))
+		}
+
+		self.file_io.write(shard_identifier, state_id, state)?;
+		Ok((state_id, SnapshotKind::Full))
 	}
 
 	fn initialize_shard_with_snapshot(
@@ -187,15 +309,34 @@ where
 		let state_hash = snapshot_metadata.state_hash;
 		self.snapshot_history
 			.insert(*shard_identifier, VecDeque::from([snapshot_metadata]));
+		self.cache_latest_state(shard_identifier, state);
 		Ok(state_hash)
 	}
 
+	/// Load the state of the snapshot at the given index of the history. Delta snapshots are
+	/// applied to the closest older full snapshot.
 	fn load_state(
 		&self,
 		shard_identifier: &ShardIdentifier,
-		snapshot_metadata: &StateSnapshotMetaData<FileIo::HashType>,
+		snapshot_index: usize,
 	) -> Result<FileIo::StateType> {
-		self.file_io.load(shard_identifier, snapshot_metadata.state_id)
+		let snapshot_history = self.get_snapshot_history(shard_identifier)?;
+		let snapshot_metadata =
+			snapshot_history.get(snapshot_index).ok_or(Error::EmptyRepository)?;
+
+		let base_index = snapshot_history
+			.iter()
+			.skip(snapshot_index)
+			.position(|base_metadata| base_metadata.kind == SnapshotKind::Full)
+			.map(|position| snapshot_index + position)
+			.ok_or(Error::MissingBaseSnapshot(snapshot_metadata.state_id))?;
+
+		let mut state =
+			self.file_io.load(shard_identifier, snapshot_history[base_index].state_id)?;
+		for delta_metadata in snapshot_history.range(snapshot_index..base_index).rev() {
+			state = self.file_io.load_delta(shard_identifier, delta_metadata.state_id, state)?;
+		}
+		Ok(state)
 	}
 }
 
@@ -209,8 +350,10 @@ where
 	type HashType = FileIo::HashType;
 
 	fn load_latest(&self, shard_identifier: &ShardIdentifier) -> Result<Self::StateType> {
-		let latest_snapshot_metadata = self.get_latest_snapshot_metadata(shard_identifier)?;
-		self.file_io.load(shard_identifier, latest_snapshot_metadata.state_id)
+		if let Some(state) = self.latest_states.get(shard_identifier) {
+			return Ok(state.clone())
+		}
+		self.load_state(shard_identifier, 0)
 	}
 
 	fn update(
@@ -224,18 +367,14 @@ where
 			return Ok(())
 		}
 
-		let (_state_hash, state_id) = self.write_new_state(shard_identifier, state)?;
-		let cache_size = self.snapshot_history_cache_size;
+		let (state_id, snapshot_kind) = self.write_new_state(shard_identifier, state)?;
 
-		let snapshot_history = self.get_snapshot_history_mut(shard_identifier)?;
-		snapshot_history.push_front(StateSnapshotMetaData::new(state_hash, state_id));
+		self.get_snapshot_history_mut(shard_identifier)?
+			.push_front(StateSnapshotMetaData::new(state_hash, state_id, snapshot_kind));
+		self.cache_latest_state(shard_identifier, state);
 
-		// In case we're above max queue size we remove the oldest entries and corresponding files
-		if snapshot_history.len() > cache_size {
-			self.prune_snapshot_history_by_range(shard_identifier, cache_size..)?;
-		}
-
-		Ok(())
+		// Remove the oldest entries and corresponding files, in case we're above the retention limits
+		self.prune_snapshot_history(shard_identifier)
 	}
 
 	fn revert_to(
@@ -252,16 +391,12 @@ where
 			.position(|fmd| fmd.state_hash == *state_hash)
 			.ok_or_else(|| Error::StateNotFoundInRepository(format!("{:?}", state_hash)))?;
 
-		// Should never fail, since we got the index from above, with `position()`.
-		let snapshot_metadata = snapshot_history
-			.get(snapshot_metadata_index)
-			.ok_or_else(|| Error::StateNotFoundInRepository(format!("{:?}", state_hash)))?;
-
-		let state = self.load_state(shard_identifier, snapshot_metadata)?;
+		let state = self.load_state(shard_identifier, snapshot_metadata_index)?;
 
 		// Remove any state versions newer than the one we're resetting to
 		// (do this irreversible operation last, to ensure the loading has succeeded)
 		self.prune_snapshot_history_by_range(shard_identifier, ..snapshot_metadata_index)?;
+		self.cache_latest_state(shard_identifier, &state);
 
 		Ok(state)
 	}
@@ -283,6 +418,111 @@ where
 	}
 }
 
+impl<FileIo> VerifyStateSnapshots for StateSnapshotRepository<FileIo>
+where
+	FileIo: StateFileIo,
+	<FileIo as StateFileIo>::HashType: Copy + Eq + Debug,
+	<FileIo as StateFileIo>::StateType: Clone + Hash<FileIo::HashType>,
+{
+	fn verify_snapshots(
+		&mut self,
+		shard_identifier: &ShardIdentifier,
+		rollback: bool,
+	) -> Result<StateVerificationReport> {
+		let snapshot_history = self.get_snapshot_history(shard_identifier)?;
+
+		let mut valid_snapshots = Vec::new();
+		let mut corrupt_snapshots = Vec::new();
+		// State of the previous snapshot, if it is valid.
+		let mut previous_state: Option<FileIo::StateType> = None;
+
+		// Oldest first, delta snapshots are applied to the state of the previous snapshot.
+		for snapshot_metadata in snapshot_history.iter().rev() {
+			let state_id = snapshot_metadata.state_id;
+			let maybe_state = match (snapshot_metadata.kind, previous_state.take()) {
+				(SnapshotKind::Full, _) => self
+					.file_io
+					.load(shard_identifier, state_id)
+					.map_err(|_| StateSnapshotCorruption::Unreadable),
+				(SnapshotKind::Delta, Some(previous_state)) => self
+					.file_io
+					.load_delta(shard_identifier, state_id, previous_state)
+					.map_err(|_| StateSnapshotCorruption::Unreadable),
+				(SnapshotKind::Delta, None) => Err(StateSnapshotCorruption::CorruptBase),
+			};
+
+			match maybe_state {
+				Ok(state) if state.hash() == snapshot_metadata.state_hash => {
+					valid_snapshots.push(state_id);
+					previous_state = Some(state);
+				},
+				Ok(_) => corrupt_snapshots.push((state_id, StateSnapshotCorruption::HashMismatch)),
+				Err(corruption) => corrupt_snapshots.push((state_id, corruption)),
+			}
+		}
+
+		let state_ids_in_history: HashSet<StateId> = snapshot_history
+			.iter()
+			.map(|snapshot_metadata| snapshot_metadata.state_id)
+			.collect();
+		for state_id in self.file_io.list_state_ids_for_shard(shard_identifier)? {
+			if !state_ids_in_history.contains(&state_id) {
+				corrupt_snapshots.push((state_id, StateSnapshotCorruption::NotInHistory));
+			}
+		}
+
+		valid_snapshots.reverse();
+		corrupt_snapshots.sort_unstable_by(|a, b| b.0.cmp(&a.0));
+
+		let rolled_back = rollback && !corrupt_snapshots.is_empty() && !valid_snapshots.is_empty();
+		if rolled_back {
+			self.get_snapshot_history_mut(shard_identifier)?
+				.retain(|snapshot_metadata| valid_snapshots.contains(&snapshot_metadata.state_id));
+			for (state_id, _) in corrupt_snapshots.iter() {
+				if let Err(e) = self.file_io.remove(shard_identifier, *state_id) {
+					error!("Failed to remove corrupt state, with id '{}': {:?}", state_id, e);
+				}
+			}
+			// The cached latest state might have been one of the corrupt snapshots.
+			self.latest_states.remove(shard_identifier);
+			info!(
+				"Rolled back shard {:?} to state snapshot {}, removed {} corrupt snapshot(s)",
+				shard_identifier,
+				valid_snapshots[0],
+				corrupt_snapshots.len()
+			);
+		}
+
+		Ok(StateVerificationReport {
+			shard: *shard_identifier,
+			valid_snapshots,
+			corrupt_snapshots,
+			rolled_back,
+		})
+	}
+}
+
+impl<FileIo> ReEncryptStateSnapshots for StateSnapshotRepository<FileIo>
+where
+	FileIo: ReEncryptStateFile,
+	<FileIo as StateFileIo>::HashType: Copy + Eq + Debug,
+	<FileIo as StateFileIo>::StateType: Clone,
+{
+	fn re_encrypt_snapshots(&self, shard_identifier: &ShardIdentifier) -> Result<usize> {
+		let mut re_encrypted_snapshots = 0;
+		for snapshot_metadata in self.get_snapshot_history(shard_identifier)?.iter() {
+			if self.file_io.re_encrypt(
+				shard_identifier,
+				snapshot_metadata.state_id,
+				snapshot_metadata.state_hash,
+			)? {
+				re_encrypted_snapshots += 1;
+			}
+		}
+		Ok(re_encrypted_snapshots)
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -317,7 +557,7 @@ mod tests {
 			vec![ShardIdentifier::random(), ShardIdentifier::random(), ShardIdentifier::random()];
 		let file_io = create_test_file_io(shards.as_slice());
 
-		assert!(TestSnapshotRepository::empty(file_io.clone(), 0usize).is_err());
+		assert!(TestSnapshotRepository::empty(file_io.clone(), snapshot_retention(0usize)).is_err());
 	}
 
 	#[test]
@@ -467,15 +707,289 @@ mod tests {
 		assert_eq!(1, state_snapshot_repository.list_shards().unwrap().len());
 	}
 
+	#[test]
+	fn update_writes_delta_snapshots_up_to_max_chain_length() {
+		let shard_id = ShardIdentifier::random();
+		let (file_io, mut state_snapshot_repository) =
+			create_state_snapshot_repository_with_retention(
+				&[shard_id],
+				StateSnapshotRetention { max_delta_chain_length: 2, ..snapshot_retention(10) },
+			);
+
+		for i in 1u64..=4u64 {
+			state_snapshot_repository
+				.update(&shard_id, &TestState(i), TestState(i).hash())
+				.unwrap();
+		}
+
+		let snapshot_kinds = snapshot_kinds(&state_snapshot_repository, &shard_id);
+		assert_eq!(
+			vec![
+				SnapshotKind::Delta,
+				SnapshotKind::Full,
+				SnapshotKind::Delta,
+				SnapshotKind::Delta,
+				SnapshotKind::Full
+			],
+			snapshot_kinds
+		);
+		assert_delta_files_match_history(&file_io, &state_snapshot_repository, &shard_id);
+		assert_eq!(TestState(4u64), state_snapshot_repository.load_latest(&shard_id).unwrap());
+	}
+
+	#[test]
+	fn pruning_compacts_delta_snapshots_into_full_snapshot() {
+		let shard_id = ShardIdentifier::random();
+		let (file_io, mut state_snapshot_repository) =
+			create_state_snapshot_repository_with_retention(
+				&[shard_id],
+				StateSnapshotRetention { max_delta_chain_length: 10, ..snapshot_retention(3) },
+			);
+
+		for i in 1u64..=5u64 {
+			state_snapshot_repository
+				.update(&shard_id, &TestState(i), TestState(i).hash())
+				.unwrap();
+		}
+
+		assert_eq!(
+			vec![SnapshotKind::Delta, SnapshotKind::Delta, SnapshotKind::Full],
+			snapshot_kinds(&state_snapshot_repository, &shard_id)
+		);
+		assert_eq!(3, file_io.get_states_for_shard(&shard_id).unwrap().len());
+		assert_delta_files_match_history(&file_io, &state_snapshot_repository, &shard_id);
+
+		let oldest_state_id =
+			state_snapshot_repository.snapshot_history.get(&shard_id).unwrap()[2].state_id;
+		assert_eq!(TestState(3u64), file_io.load(&shard_id, oldest_state_id).unwrap());
+	}
+
+	#[test]
+	fn revert_to_delta_snapshot_works() {
+		let shard_id = ShardIdentifier::random();
+		let (file_io, mut state_snapshot_repository) =
+			create_state_snapshot_repository_with_retention(
+				&[shard_id],
+				StateSnapshotRetention { max_delta_chain_length: 10, ..snapshot_retention(10) },
+			);
+
+		let state_hashes = [1u64, 2u64, 3u64]
+			.into_iter()
+			.map(|i| {
+				let state_hash = TestState(i).hash();
+				state_snapshot_repository.update(&shard_id, &TestState(i), state_hash).unwrap();
+				state_hash
+			})
+			.collect::<Vec<_>>();
+
+		let reverted_state =
+			state_snapshot_repository.revert_to(&shard_id, &state_hashes[1]).unwrap();
+
+		assert_eq!(TestState(2u64), reverted_state);
+		assert_eq!(TestState(2u64), state_snapshot_repository.load_latest(&shard_id).unwrap());
+		assert_eq!(3, file_io.get_states_for_shard(&shard_id).unwrap().len());
+	}
+
+	#[test]
+	fn update_prunes_snapshots_older_than_max_age() {
+		let shard_id = ShardIdentifier::random();
+		let file_io = create_test_file_io(&[shard_id]);
+		// State IDs are timestamps, these are from 1970.
+		for state_id in [1_000u128, 2_000u128] {
+			file_io.write(&shard_id, state_id, &TestState(state_id as u64)).unwrap();
+		}
+		let mut state_snapshot_repository = load_state_snapshot_repository(
+			file_io.clone(),
+			StateSnapshotRetention { max_age_secs: Some(3600), ..snapshot_retention(10) },
+		);
+		assert_eq!(2, state_snapshot_repository.snapshot_history.get(&shard_id).unwrap().len());
+
+		state_snapshot_repository
+			.update(&shard_id, &TestState(3u64), TestState(3u64).hash())
+			.unwrap();
+
+		assert_eq!(1, state_snapshot_repository.snapshot_history.get(&shard_id).unwrap().len());
+		assert_eq!(1, file_io.get_states_for_shard(&shard_id).unwrap().len());
+		assert_eq!(TestState(3u64), state_snapshot_repository.load_latest(&shard_id).unwrap());
+	}
+
+	#[test]
+	fn verify_snapshots_reports_intact_snapshots_as_valid() {
+		let shard_id = ShardIdentifier::random();
+		let (_, mut state_snapshot_repository) = create_state_snapshot_repository_with_retention(
+			&[shard_id],
+			StateSnapshotRetention { max_delta_chain_length: 1, ..snapshot_retention(10) },
+		);
+		let state_ids = update_with_states(&mut state_snapshot_repository, &shard_id, 3);
+
+		let report = state_snapshot_repository.verify_snapshots(&shard_id, true).unwrap();
+
+		assert_eq!(state_ids, report.valid_snapshots);
+		assert!(report.corrupt_snapshots.is_empty());
+		assert!(!report.rolled_back);
+	}
+
+	#[test]
+	fn verify_snapshots_reports_tampered_and_missing_snapshots() {
+		let shard_id = ShardIdentifier::random();
+		let (file_io, mut state_snapshot_repository) =
+			create_state_snapshot_repository(&[shard_id], 10);
+		let state_ids = update_with_states(&mut state_snapshot_repository, &shard_id, 3);
+
+		file_io.write(&shard_id, state_ids[0], &TestState(42u64)).unwrap();
+		file_io.remove(&shard_id, state_ids[1]).unwrap();
+
+		let report = state_snapshot_repository.verify_snapshots(&shard_id, false).unwrap();
+
+		assert_eq!(vec![state_ids[2], state_ids[3]], report.valid_snapshots);
+		assert_eq!(
+			vec![
+				(state_ids[0], StateSnapshotCorruption::HashMismatch),
+				(state_ids[1], StateSnapshotCorruption::Unreadable)
+			],
+			report.corrupt_snapshots
+		);
+		assert!(!report.rolled_back);
+		assert_eq!(TestState(42u64), state_snapshot_repository.load_latest(&shard_id).unwrap());
+	}
+
+	#[test]
+	fn verify_snapshots_reports_deltas_of_corrupt_snapshot() {
+		let shard_id = ShardIdentifier::random();
+		let (file_io, mut state_snapshot_repository) =
+			create_state_snapshot_repository_with_retention(
+				&[shard_id],
+				StateSnapshotRetention { max_delta_chain_length: 10, ..snapshot_retention(10) },
+			);
+		let state_ids = update_with_states(&mut state_snapshot_repository, &shard_id, 2);
+
+		file_io
+			.write_delta(&shard_id, state_ids[1], &TestState(0), &TestState(42u64))
+			.unwrap();
+
+		let report = state_snapshot_repository.verify_snapshots(&shard_id, false).unwrap();
+
+		assert_eq!(vec![state_ids[2]], report.valid_snapshots);
+		assert_eq!(
+			vec![
+				(state_ids[0], StateSnapshotCorruption::CorruptBase),
+				(state_ids[1], StateSnapshotCorruption::HashMismatch)
+			],
+			report.corrupt_snapshots
+		);
+	}
+
+	#[test]
+	fn verify_snapshots_with_rollback_removes_corrupt_snapshots() {
+		let shard_id = ShardIdentifier::random();
+		let (file_io, mut state_snapshot_repository) =
+			create_state_snapshot_repository_with_retention(
+				&[shard_id],
+				StateSnapshotRetention { max_delta_chain_length: 10, ..snapshot_retention(10) },
+			);
+		let state_ids = update_with_states(&mut state_snapshot_repository, &shard_id, 2);
+		let orphan_state_id = state_ids[0] + 1;
+		file_io.write(&shard_id, orphan_state_id, &TestState(7u64)).unwrap();
+		file_io
+			.write_delta(&shard_id, state_ids[0], &TestState(1), &TestState(42u64))
+			.unwrap();
+
+		let report = state_snapshot_repository.verify_snapshots(&shard_id, true).unwrap();
+
+		assert_eq!(vec![state_ids[1], state_ids[2]], report.valid_snapshots);
+		assert_eq!(
+			vec![
+				(orphan_state_id, StateSnapshotCorruption::NotInHistory),
+				(state_ids[0], StateSnapshotCorruption::HashMismatch)
+			],
+			report.corrupt_snapshots
+		);
+		assert!(report.rolled_back);
+		assert_eq!(TestState(1u64), state_snapshot_repository.load_latest(&shard_id).unwrap());
+		assert_eq!(2, file_io.get_states_for_shard(&shard_id).unwrap().len());
+		assert_eq!(2, state_snapshot_repository.snapshot_history.get(&shard_id).unwrap().len());
+	}
+
+	/// Updates the shard with the states 1 to `number_of_states`.
+	/// Returns the state IDs of all snapshots, newest first.
+	fn update_with_states(
+		state_snapshot_repository: &mut TestSnapshotRepository,
+		shard_id: &ShardIdentifier,
+		number_of_states: u64,
+	) -> Vec<StateId> {
+		for i in 1u64..=number_of_states {
+			state_snapshot_repository
+				.update(shard_id, &TestState(i), TestState(i).hash())
+				.unwrap();
+		}
+		state_snapshot_repository
+			.snapshot_history
+			.get(shard_id)
+			.unwrap()
+			.iter()
+			.map(|snapshot_metadata| snapshot_metadata.state_id)
+			.collect()
+	}
+
+	fn snapshot_kinds(
+		state_snapshot_repository: &TestSnapshotRepository,
+		shard_id: &ShardIdentifier,
+	) -> Vec<SnapshotKind> {
+		state_snapshot_repository
+			.snapshot_history
+			.get(shard_id)
+			.unwrap()
+			.iter()
+			.map(|snapshot_metadata| snapshot_metadata.kind)
+			.collect()
+	}
+
+	fn assert_delta_files_match_history(
+		file_io: &TestFileIo,
+		state_snapshot_repository: &TestSnapshotRepository,
+		shard_id: &ShardIdentifier,
+	) {
+		for snapshot_metadata in state_snapshot_repository.snapshot_history.get(shard_id).unwrap() {
+			assert_eq!(
+				snapshot_metadata.kind == SnapshotKind::Delta,
+				file_io.is_delta(shard_id, snapshot_metadata.state_id)
+			);
+		}
+	}
+
+	fn snapshot_retention(max_snapshots: usize) -> StateSnapshotRetention {
+		StateSnapshotRetention {
+			max_snapshots: max_snapshots as u32,
+			max_age_secs: None,
+			max_delta_chain_length: 0,
+		}
+	}
+
 	fn create_state_snapshot_repository(
 		shards: &[ShardIdentifier],
 		snapshot_history_size: usize,
+	) -> (Arc<TestFileIo>, TestSnapshotRepository) {
+		create_state_snapshot_repository_with_retention(
+			shards,
+			snapshot_retention(snapshot_history_size),
+		)
+	}
+
+	fn create_state_snapshot_repository_with_retention(
+		shards: &[ShardIdentifier],
+		snapshot_retention: StateSnapshotRetention,
 	) -> (Arc<TestFileIo>, TestSnapshotRepository) {
 		let file_io = create_test_file_io(shards);
+		(file_io.clone(), load_state_snapshot_repository(file_io, snapshot_retention))
+	}
+
+	fn load_state_snapshot_repository(
+		file_io: Arc<TestFileIo>,
+		snapshot_retention: StateSnapshotRetention,
+	) -> TestSnapshotRepository {
 		let state_initializer = Arc::new(TestStateInitializer::new(Default::default()));
-		let repository_loader =
-			StateSnapshotRepositoryLoader::new(file_io.clone(), state_initializer);
-		(file_io, repository_loader.load_snapshot_repository(snapshot_history_size).unwrap())
+		let repository_loader = StateSnapshotRepositoryLoader::new(file_io, state_initializer);
+		repository_loader.load_snapshot_repository(snapshot_retention).unwrap()
 	}
 
 	fn create_test_file_io(shards: &[ShardIdentifier]) -> Arc<TestFileIo> {
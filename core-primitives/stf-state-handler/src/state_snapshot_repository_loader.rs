@@ -20,12 +20,13 @@ use crate::{
 	file_io::StateFileIo,
 	state_initializer::InitializeState,
 	state_snapshot_primitives::{
-		initialize_shard_with_snapshot, SnapshotHistory, StateId, StateSnapshotMetaData,
+		initialize_shard_with_snapshot, SnapshotHistory, SnapshotKind, StateId,
+		StateSnapshotMetaData,
 	},
 	state_snapshot_repository::StateSnapshotRepository,
 };
 use itp_hashing::Hash;
-use itp_types::ShardIdentifier;
+use itp_types::{ShardIdentifier, StateSnapshotRetention};
 use log::*;
 use std::{collections::VecDeque, fmt::Debug, iter::FromIterator, sync::Arc, vec::Vec};
 
@@ -49,15 +50,11 @@ where
 	/// Load a state snapshot repository from an existing set of files and directories.
 	pub fn load_snapshot_repository(
 		&self,
-		snapshot_history_cache_size: usize,
+		snapshot_retention: StateSnapshotRetention,
 	) -> Result<StateSnapshotRepository<FileIo>> {
 		let snapshot_history = self.load_and_initialize_state_snapshot_history()?;
 
-		StateSnapshotRepository::new(
-			self.file_io.clone(),
-			snapshot_history_cache_size,
-			snapshot_history,
-		)
+		StateSnapshotRepository::new(self.file_io.clone(), snapshot_retention, snapshot_history)
 	}
 
 	fn load_and_initialize_state_snapshot_history(
@@ -70,11 +67,12 @@ where
 
 		for shard in shards {
 			let mut state_ids = self.file_io.list_state_ids_for_shard(&shard)?;
-			// Sort by id (which are timestamp), highest, i.e. newest, first
+			// Sort by id (which are timestamp), lowest, i.e. oldest, first
 			state_ids.sort_unstable();
-			state_ids.reverse();
 
 			let mut snapshot_metadata: Vec<_> = self.map_to_snapshot_metadata(&shard, state_ids);
+			// Snapshot history has the newest snapshot first
+			snapshot_metadata.reverse();
 
 			if snapshot_metadata.is_empty() {
 				warn!(
@@ -101,24 +99,73 @@ where
 		Ok(repository)
 	}
 
+	/// Maps the state IDs, oldest first, to snapshot metadata.
+	///
+	/// Delta snapshots are applied to the state of the previous snapshot to compute their hash.
+	/// Any delta snapshot without a valid previous snapshot is ignored.
 	fn map_to_snapshot_metadata(
 		&self,
 		shard: &ShardIdentifier,
 		state_ids: Vec<StateId>,
 	) -> Vec<StateSnapshotMetaData<FileIo::HashType>> {
-		state_ids
-			.into_iter()
-			.flat_map(|state_id| match self.file_io.compute_hash(shard, state_id) {
-				Ok(hash) => Some(StateSnapshotMetaData::new(hash, state_id)),
+		let mut snapshot_metadata = Vec::with_capacity(state_ids.len());
+		// ID of the previous valid snapshot, with its state if it has been loaded already.
+		let mut previous_snapshot: Option<(StateId, Option<FileIo::StateType>)> = None;
+
+		for state_id in state_ids {
+			if !self.file_io.is_delta(shard, state_id) {
+				match self.file_io.compute_hash(shard, state_id) {
+					Ok(hash) => {
+						snapshot_metadata.push(StateSnapshotMetaData::new(
+							hash,
+							state_id,
+							SnapshotKind::Full,
+						));
+						previous_snapshot = Some((state_id, None));
+					},
+					Err(e) => {
+						warn!(
+							"Failed to compute hash for state snapshot with id {}: {:?}, ignoring snapshot as a result",
+							state_id, e
+						);
+						previous_snapshot = None;
+					},
+				}
+				continue
+			}
+
+			let previous_state = match previous_snapshot.take() {
+				Some((_, Some(previous_state))) => Ok(previous_state),
+				Some((previous_state_id, None)) => self.file_io.load(shard, previous_state_id),
+				None => {
+					warn!(
+						"Found delta snapshot with id {} without a valid previous snapshot, ignoring it",
+						state_id
+					);
+					continue
+				},
+			};
+
+			match previous_state
+				.and_then(|previous_state| self.file_io.load_delta(shard, state_id, previous_state))
+			{
+				Ok(state) => {
+					snapshot_metadata.push(StateSnapshotMetaData::new(
+						state.hash(),
+						state_id,
+						SnapshotKind::Delta,
+					));
+					previous_snapshot = Some((state_id, Some(state)));
+				},
 				Err(e) => {
 					warn!(
-								"Failed to compute hash for state snapshot with id {}: {:?}, ignoring snapshot as a result",
-								state_id, e
-							);
-					None
+						"Failed to load delta snapshot with id {}: {:?}, ignoring snapshot as a result",
+						state_id, e
+					);
 				},
-			})
-			.collect()
+			}
+		}
+		snapshot_metadata
 	}
 }
 
@@ -190,6 +237,30 @@ mod tests {
 		assert_latest_state_id(&snapshot_history, &shards[2], 14_000_000);
 	}
 
+	#[test]
+	fn loading_delta_snapshots_ignores_deltas_without_previous_snapshot() {
+		let shard = ShardIdentifier::random();
+		let (file_io, loader) = create_test_fixtures(&[shard]);
+
+		file_io.write_delta(&shard, 1_000_000, &TestState(0), &TestState(1)).unwrap();
+		add_snapshot_with_state_ids(file_io.as_ref(), &shard, 2_000_000);
+		file_io.write_delta(&shard, 3_000_000, &TestState(0), &TestState(3)).unwrap();
+		file_io.write_delta(&shard, 4_000_000, &TestState(3), &TestState(4)).unwrap();
+
+		let snapshot_history = loader.load_and_initialize_state_snapshot_history().unwrap();
+		let snapshots = snapshot_history.get(&shard).unwrap();
+
+		assert_eq!(
+			vec![
+				(4_000_000, SnapshotKind::Delta),
+				(3_000_000, SnapshotKind::Delta),
+				(2_000_000, SnapshotKind::Full)
+			],
+			snapshots.iter().map(|s| (s.state_id, s.kind)).collect::<Vec<_>>()
+		);
+		assert_eq!(TestState(4).hash(), snapshots.front().unwrap().state_hash);
+	}
+
 	fn add_state_snapshots(file_io: &TestFileIo, shard: &ShardIdentifier, state_ids: &[StateId]) {
 		for state_id in state_ids {
 			add_snapshot_with_state_ids(file_io, shard, *state_id);
@@ -19,8 +19,8 @@ use crate::{
 	error::{Error, Result},
 	file_io::{
 		purge_shard_dir,
-		sgx::{init_shard, shard_exists, SgxStateFileIo},
-		shard_path, StateFileIo,
+		sgx::{init_shard, shard_exists, state_file_path, SgxStateFileIo},
+		shard_path, ReEncryptStateFile, StateFileIo,
 	},
 	handle_state::HandleState,
 	in_memory_state_file_io::sgx::create_in_memory_state_io_from_shards_directories,
@@ -29,22 +29,34 @@ use crate::{
 	state_snapshot_repository::{StateSnapshotRepository, VersionedStateAccess},
 	state_snapshot_repository_loader::StateSnapshotRepositoryLoader,
 	test::mocks::initialize_state_mock::InitializeStateMock,
+	verify_state::VerifyState,
 };
 use codec::{Decode, Encode};
 use ita_stf::{State as StfState, StateType as StfStateType};
 use itp_hashing::Hash;
-use itp_sgx_crypto::{mocks::KeyRepositoryMock, Aes, AesSeal, StateCrypto};
+use itp_sgx_crypto::{
+	key_repository::{forget_retired_keys, rotate_key},
+	mocks::KeyRepositoryMock,
+	Aes, AesSeal, StateCrypto,
+};
 use itp_sgx_externalities::{SgxExternalities, SgxExternalitiesTrait};
-use itp_sgx_io::{write, StaticSealedIO};
+use itp_sgx_io::{read, write, StaticSealedIO};
 use itp_stf_state_observer::state_observer::StateObserver;
-use itp_types::{ShardIdentifier, H256};
+use itp_types::{ShardIdentifier, StateSnapshotRetention, H256};
 use std::{sync::Arc, thread, vec::Vec};
 
 const STATE_SNAPSHOTS_CACHE_SIZE: usize = 3;
+const STATE_SNAPSHOT_RETENTION: StateSnapshotRetention = StateSnapshotRetention {
+	max_snapshots: STATE_SNAPSHOTS_CACHE_SIZE as u32,
+	max_age_secs: None,
+	max_delta_chain_length: 0,
+};
 
 type StateKeyRepositoryMock = KeyRepositoryMock<Aes>;
+type RetiredStateKeysRepositoryMock = KeyRepositoryMock<Vec<Aes>>;
 type TestStateInitializer = InitializeStateMock<StfState>;
-type TestStateFileIo = SgxStateFileIo<StateKeyRepositoryMock, SgxExternalities>;
+type TestStateFileIo =
+	SgxStateFileIo<StateKeyRepositoryMock, RetiredStateKeysRepositoryMock, SgxExternalities>;
 type TestStateRepository = StateSnapshotRepository<TestStateFileIo>;
 type TestStateRepositoryLoader =
 	StateSnapshotRepositoryLoader<TestStateFileIo, TestStateInitializer>;
@@ -237,7 +249,8 @@ pub fn test_file_io_get_state_hash_works() {
 	let state_key_access =
 		Arc::new(StateKeyRepositoryMock::new(AesSeal::unseal_from_static_file().unwrap()));
 
-	let file_io = TestStateFileIo::new(state_key_access);
+	let file_io =
+		TestStateFileIo::new(state_key_access, Arc::new(RetiredStateKeysRepositoryMock::default()));
 
 	let state_id = 1234u128;
 	let state_hash = file_io
@@ -278,13 +291,133 @@ pub fn test_state_files_from_handler_can_be_loaded_again() {
 	);
 }
 
+pub fn test_delta_snapshots_from_handler_can_be_loaded_again() {
+	let shard: ShardIdentifier = [16u8; 32].into();
+	let _shard_dir_handle = ShardDirectoryHandle::new(shard).unwrap();
+	let snapshot_retention =
+		StateSnapshotRetention { max_delta_chain_length: 5, ..STATE_SNAPSHOT_RETENTION };
+	let state_handler = initialize_state_handler_with_retention(snapshot_retention);
+
+	update_state(state_handler.as_ref(), &shard, ("test_key_1".encode(), "value1".encode()));
+	update_state(state_handler.as_ref(), &shard, ("test_key_2".encode(), "value2".encode()));
+	let (lock, mut state_to_mutate) = state_handler.load_for_mutation(&shard).unwrap();
+	state_to_mutate.remove("test_key_1".encode().as_slice());
+	let latest_hash = state_handler.write_after_mutation(state_to_mutate, lock, &shard).unwrap();
+
+	// Oldest delta snapshot has been compacted into a full snapshot, the others are kept.
+	assert_eq!(STATE_SNAPSHOTS_CACHE_SIZE, number_of_files_in_shard_dir(&shard).unwrap());
+
+	let (loaded_state, loaded_hash) = initialize_state_handler_with_retention(snapshot_retention)
+		.load_cloned(&shard)
+		.unwrap();
+	assert_eq!(latest_hash, loaded_hash);
+	assert!(loaded_state.state().get("test_key_1".encode().as_slice()).is_none());
+	assert_eq!(
+		&"value2".encode(),
+		loaded_state.state().get("test_key_2".encode().as_slice()).unwrap()
+	);
+}
+
+pub fn test_verify_states_rolls_back_truncated_state_snapshot() {
+	let shard: ShardIdentifier = [17u8; 32].into();
+	let (state_handler, _shard_dir_handle) = initialize_state_handler_with_directory_handle(&shard);
+	let state_key_access =
+		Arc::new(StateKeyRepositoryMock::new(AesSeal::unseal_from_static_file().unwrap()));
+	let file_io =
+		TestStateFileIo::new(state_key_access, Arc::new(RetiredStateKeysRepositoryMock::default()));
+
+	let valid_state_hash =
+		update_state(state_handler.as_ref(), &shard, ("test_key_1".encode(), "value1".encode()));
+	update_state(state_handler.as_ref(), &shard, ("test_key_2".encode(), "value2".encode()));
+
+	let latest_state_id =
+		file_io.list_state_ids_for_shard(&shard).unwrap().into_iter().max().unwrap();
+	write(&[0, 1, 2], state_file_path(&shard, latest_state_id)).unwrap();
+
+	let reports = state_handler.verify_states(true).unwrap();
+	let report = reports.into_iter().find(|report| report.shard == shard).unwrap();
+
+	assert!(report.rolled_back);
+	assert_eq!(1, report.corrupt_snapshots.len());
+	assert_eq!(latest_state_id, report.corrupt_snapshots[0].0);
+	assert_eq!(valid_state_hash, state_handler.load_cloned(&shard).unwrap().1);
+	assert_eq!(2, number_of_files_in_shard_dir(&shard).unwrap());
+}
+
+pub fn test_modified_state_file_fails_to_load() {
+	let shard: ShardIdentifier = [18u8; 32].into();
+	let _shard_dir_handle = ShardDirectoryHandle::new(shard).unwrap();
+	let state_key_access =
+		Arc::new(StateKeyRepositoryMock::new(AesSeal::unseal_from_static_file().unwrap()));
+	let file_io =
+		TestStateFileIo::new(state_key_access, Arc::new(RetiredStateKeysRepositoryMock::default()));
+
+	let state_id = 1234u128;
+	file_io.write(&shard, state_id, &given_hello_world_state()).unwrap();
+
+	let mut state_file = read(state_file_path(&shard, state_id)).unwrap();
+	*state_file.last_mut().unwrap() ^= 1;
+	write(&state_file, state_file_path(&shard, state_id)).unwrap();
+
+	assert!(file_io.load(&shard, state_id).is_err());
+}
+
+pub fn test_legacy_state_file_is_loaded_and_re_encrypted() {
+	let shard: ShardIdentifier = [19u8; 32].into();
+	let _shard_dir_handle = ShardDirectoryHandle::new(shard).unwrap();
+	let state_key = AesSeal::unseal_from_static_file().unwrap();
+	let file_io = TestStateFileIo::new(
+		Arc::new(StateKeyRepositoryMock::new(state_key)),
+		Arc::new(RetiredStateKeysRepositoryMock::default()),
+	);
+
+	let state = given_hello_world_state();
+	let state_id = 1234u128;
+	let mut legacy_state_file = state.state().encode();
+	state_key.encrypt(&mut legacy_state_file).unwrap();
+	write(&legacy_state_file, state_file_path(&shard, state_id)).unwrap();
+
+	assert_eq!(state.state(), file_io.load(&shard, state_id).unwrap().state());
+	assert!(file_io.re_encrypt(&shard, state_id, state.hash()).unwrap());
+	assert!(!file_io.re_encrypt(&shard, state_id, state.hash()).unwrap());
+	assert_eq!(state.state(), file_io.load(&shard, state_id).unwrap().state());
+}
+
+pub fn test_state_file_is_re_encrypted_after_key_rotation() {
+	let shard: ShardIdentifier = [20u8; 32].into();
+	let _shard_dir_handle = ShardDirectoryHandle::new(shard).unwrap();
+	let initial_state_key = AesSeal::unseal_from_static_file().unwrap();
+	let state_key_repository = Arc::new(StateKeyRepositoryMock::new(initial_state_key));
+	let retired_state_keys_repository = Arc::new(RetiredStateKeysRepositoryMock::default());
+	let file_io =
+		TestStateFileIo::new(state_key_repository.clone(), retired_state_keys_repository.clone());
+
+	let state = given_hello_world_state();
+	let state_id = 1234u128;
+	let state_hash = file_io.write(&shard, state_id, &state).unwrap();
+
+	let new_state_key = Aes::new([3u8; 16], [4u8; 16]);
+	rotate_key(
+		state_key_repository.as_ref(),
+		retired_state_keys_repository.as_ref(),
+		new_state_key,
+	)
+	.unwrap();
+	assert_eq!(state.state(), file_io.load(&shard, state_id).unwrap().state());
+
+	assert!(file_io.re_encrypt(&shard, state_id, state_hash).unwrap());
+	forget_retired_keys(retired_state_keys_repository.as_ref(), &[initial_state_key]).unwrap();
+	assert_eq!(state.state(), file_io.load(&shard, state_id).unwrap().state());
+}
+
 pub fn test_list_state_ids_ignores_files_not_matching_the_pattern() {
 	let shard: ShardIdentifier = [21u8; 32].into();
 	let _shard_dir_handle = ShardDirectoryHandle::new(shard).unwrap();
 	let state_key_access =
 		Arc::new(StateKeyRepositoryMock::new(AesSeal::unseal_from_static_file().unwrap()));
 
-	let file_io = TestStateFileIo::new(state_key_access);
+	let file_io =
+		TestStateFileIo::new(state_key_access, Arc::new(RetiredStateKeysRepositoryMock::default()));
 
 	let mut invalid_state_file_path = shard_path(&shard);
 	invalid_state_file_path.push("invalid-state.bin");
@@ -306,7 +439,7 @@ pub fn test_in_memory_state_initializes_from_shard_directory() {
 	let state_repository_loader =
 		StateSnapshotRepositoryLoader::new(file_io.clone(), state_initializer);
 	let state_snapshot_repository = state_repository_loader
-		.load_snapshot_repository(STATE_SNAPSHOTS_CACHE_SIZE)
+		.load_snapshot_repository(STATE_SNAPSHOT_RETENTION)
 		.unwrap();
 
 	assert_eq!(1, file_io.get_states_for_shard(&shard).unwrap().len());
@@ -321,16 +454,24 @@ fn initialize_state_handler_with_directory_handle(
 }
 
 fn initialize_state_handler() -> Arc<TestStateHandler> {
+	initialize_state_handler_with_retention(STATE_SNAPSHOT_RETENTION)
+}
+
+fn initialize_state_handler_with_retention(
+	snapshot_retention: StateSnapshotRetention,
+) -> Arc<TestStateHandler> {
 	let state_key_access =
 		Arc::new(StateKeyRepositoryMock::new(AesSeal::unseal_from_static_file().unwrap()));
-	let file_io = Arc::new(TestStateFileIo::new(state_key_access));
+	let file_io = Arc::new(TestStateFileIo::new(
+		state_key_access,
+		Arc::new(RetiredStateKeysRepositoryMock::default()),
+	));
 	let state_initializer = Arc::new(TestStateInitializer::new(StfState::new(Default::default())));
 	let state_repository_loader =
 		TestStateRepositoryLoader::new(file_io, state_initializer.clone());
 	let state_observer = Arc::new(TestStateObserver::default());
-	let state_snapshot_repository = state_repository_loader
-		.load_snapshot_repository(STATE_SNAPSHOTS_CACHE_SIZE)
-		.unwrap();
+	let state_snapshot_repository =
+		state_repository_loader.load_snapshot_repository(snapshot_retention).unwrap();
 	Arc::new(
 		TestStateHandler::load_from_repository(
 			state_snapshot_repository,
@@ -0,0 +1,28 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::error::Result;
+use itp_types::StateVerificationReport;
+use std::vec::Vec;
+
+/// Trait to verify the integrity of the stored states, and to recover from corrupt state snapshots.
+pub trait VerifyState {
+	/// Verify the state snapshots of all shards.
+	///
+	/// With `rollback`, each shard with corrupt snapshots is rolled back to its newest valid snapshot.
+	fn verify_states(&self, rollback: bool) -> Result<Vec<StateVerificationReport>>;
+}
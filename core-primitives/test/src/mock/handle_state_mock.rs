@@ -27,6 +27,10 @@ use itp_stf_state_handler::{
 	handle_state::HandleState,
 	query_shard_state::QueryShardState,
 };
+use itp_stf_state_observer::{
+	error::{Error as ObserverError, Result as ObserverResult},
+	traits::ObserveState,
+};
 use itp_types::{ShardIdentifier, H256};
 use std::{collections::HashMap, format, vec::Vec};
 
@@ -116,6 +120,25 @@ impl QueryShardState for HandleStateMock {
 	}
 }
 
+/// Observes a copy of the current state, so observations never alter the mocked state.
+impl ObserveState for HandleStateMock {
+	type StateType = StfState;
+
+	fn observe_state<F, R>(&self, shard: &ShardIdentifier, observation_func: F) -> ObserverResult<R>
+	where
+		F: FnOnce(&mut Self::StateType) -> R,
+	{
+		let mut state = self
+			.state_map
+			.read()
+			.map_err(|_| ObserverError::LockPoisoning)?
+			.get(shard)
+			.cloned()
+			.ok_or(ObserverError::CurrentStateEmpty)?;
+		Ok(observation_func(&mut state))
+	}
+}
+
 // Since the mock itself has quite a bit of complexity, we also have tests for the mock.
 #[cfg(feature = "sgx")]
 pub mod tests {
@@ -25,8 +25,8 @@ use itp_ocall_api::{
 use itp_storage::Error::StorageValueUnavailable;
 use itp_teerex_storage::{TeeRexStorage, TeerexStorageKeys};
 use itp_types::{
-	storage::StorageEntryVerified, BlockHash, Enclave, ShardIdentifier, WorkerRequest,
-	WorkerResponse,
+	storage::StorageEntryVerified, BlockHash, Enclave, PalletString, Request, ShardIdentifier,
+	WorkerRequest, WorkerResponse,
 };
 use sgx_types::*;
 use sp_core::H256;
@@ -158,6 +158,14 @@ impl EnclaveSidechainOCallApi for OnchainMock {
 	) -> SgxResult<Vec<SignedSidechainBlock>> {
 		Ok(Vec::new())
 	}
+
+	fn send_trusted_operations_to_peers(
+		&self,
+		_peer_urls: Vec<PalletString>,
+		_requests: Vec<Request>,
+	) -> SgxResult<()> {
+		Ok(())
+	}
 }
 
 impl EnclaveMetricsOCallApi for OnchainMock {
@@ -24,7 +24,7 @@ use std::sync::RwLock;
 use codec::{Decode, Encode};
 use core::marker::PhantomData;
 use itp_ocall_api::EnclaveSidechainOCallApi;
-use itp_types::{BlockHash, ShardIdentifier};
+use itp_types::{BlockHash, PalletString, Request, ShardIdentifier};
 use sgx_types::{sgx_status_t, SgxResult};
 use std::vec::Vec;
 
@@ -107,4 +107,12 @@ where
 			None => Err(sgx_status_t::SGX_ERROR_UNEXPECTED),
 		}
 	}
+
+	fn send_trusted_operations_to_peers(
+		&self,
+		_peer_urls: Vec<PalletString>,
+		_requests: Vec<Request>,
+	) -> SgxResult<()> {
+		Ok(())
+	}
 }
@@ -23,55 +23,106 @@ use crate::sgx_reexport_prelude::*;
 use crate::error;
 use codec::Encode;
 use ita_stf::{Getter, TrustedCallSigned, TrustedOperation as StfTrustedOperation};
-use itp_stf_primitives::types::ShardIdentifier;
+use itp_stf_interface::{
+	system_pallet::SystemPalletAccountInterface,
+	transaction_payment_pallet::TransactionPaymentPalletInterface,
+};
+use itp_stf_primitives::types::{AccountId, ShardIdentifier};
+use itp_stf_state_observer::traits::ObserveState;
 use itp_top_pool::{
 	pool::{ChainApi, ExtrinsicHash, NumberFor},
 	primitives::TrustedOperationSource,
 };
-use itp_types::BlockHash as SidechainBlockHash;
+use itp_types::{BlockHash as SidechainBlockHash, Index};
 use jsonrpc_core::futures::future::{ready, Future, Ready};
 use log::*;
 use sp_runtime::{
 	generic::BlockId,
 	traits::{Block as BlockT, Hash as HashT, Header as HeaderT},
 	transaction_validity::{
-		TransactionValidity, TransactionValidityError, UnknownTransaction, ValidTransaction,
+		InvalidTransaction, TransactionPriority, TransactionValidity, TransactionValidityError,
+		UnknownTransaction, ValidTransaction,
 	},
 };
-use std::{boxed::Box, marker::PhantomData, pin::Pin, vec, vec::Vec};
+use std::{boxed::Box, marker::PhantomData, pin::Pin, sync::Arc, vec, vec::Vec};
 
 /// Future that resolves to account nonce.
 pub type Result<T> = core::result::Result<T, ()>;
 
+/// Base priority of every trusted operation, a tip is added on top of it.
+const BASE_PRIORITY: TransactionPriority = 1 << 20;
+
 /// The operation pool logic for full client.
-pub struct SidechainApi<Block> {
-	_marker: PhantomData<Block>,
+pub struct SidechainApi<Block, StateObserver, Stf> {
+	state_observer: Arc<StateObserver>,
+	_marker: PhantomData<(Block, Stf)>,
 }
 
-impl<Block> SidechainApi<Block> {
+impl<Block, StateObserver, Stf> SidechainApi<Block, StateObserver, Stf>
+where
+	StateObserver: ObserveState,
+	Stf: SystemPalletAccountInterface<StateObserver::StateType, AccountId>
+		+ TransactionPaymentPalletInterface<StateObserver::StateType, TrustedCallSigned>,
+	Stf::Index: Into<Index>,
+{
 	/// Create new operation pool logic.
-	pub fn new() -> Self {
-		SidechainApi { _marker: Default::default() }
+	pub fn new(state_observer: Arc<StateObserver>) -> Self {
+		SidechainApi { state_observer, _marker: Default::default() }
 	}
 
-	fn validate_trusted_call(trusted_call_signed: TrustedCallSigned) -> ValidTransaction {
-		let from = trusted_call_signed.call.sender_account();
-		let requires = vec![];
-		let provides = vec![(from, trusted_call_signed.nonce).encode()];
+	/// Validate a trusted call against the sender's nonce and balance in the current state.
+	///
+	/// Calls with a nonce ahead of the state nonce require the call with the preceding nonce,
+	/// so the pool keeps them in the future queue until their predecessor arrives.
+	/// Calls whose sender cannot pay the fee are rejected.
+	fn validate_trusted_call(
+		&self,
+		trusted_call_signed: TrustedCallSigned,
+		shard: &ShardIdentifier,
+	) -> TransactionValidity {
+		let from = trusted_call_signed.call.sender_account().clone();
+		let nonce = trusted_call_signed.nonce;
 
-		ValidTransaction { priority: 1 << 20, requires, provides, longevity: 64, propagate: true }
-	}
-}
+		let (state_nonce, can_pay_fee): (Index, bool) = self
+			.state_observer
+			.observe_state(shard, |state| {
+				(
+					Stf::get_account_nonce(state, &from).into(),
+					Stf::can_pay_fee(state, &trusted_call_signed),
+				)
+			})
+			.map_err(|e| {
+				warn!("Failed to get the nonce of {:?} from state: {:?}", from, e);
+				TransactionValidityError::Unknown(UnknownTransaction::CannotLookup)
+			})?;
+
+		if nonce < state_nonce {
+			return Err(TransactionValidityError::Invalid(InvalidTransaction::Stale))
+		}
 
-impl<Block> Default for SidechainApi<Block> {
-	fn default() -> Self {
-		Self::new()
+		if !can_pay_fee {
+			return Err(TransactionValidityError::Invalid(InvalidTransaction::Payment))
+		}
+
+		let requires = if nonce > state_nonce { vec![(&from, nonce - 1).encode()] } else { vec![] };
+		let provides = vec![(&from, nonce).encode()];
+		let priority = BASE_PRIORITY.saturating_add(
+			trusted_call_signed.tip().try_into().unwrap_or(TransactionPriority::MAX),
+		);
+
+		Ok(ValidTransaction { priority, requires, provides, longevity: 64, propagate: true })
 	}
 }
 
-impl<Block> ChainApi for SidechainApi<Block>
+impl<Block, StateObserver, Stf> ChainApi for SidechainApi<Block, StateObserver, Stf>
 where
 	Block: BlockT,
+	StateObserver: ObserveState + Send + Sync,
+	Stf: SystemPalletAccountInterface<StateObserver::StateType, AccountId>
+		+ TransactionPaymentPalletInterface<StateObserver::StateType, TrustedCallSigned>
+		+ Send
+		+ Sync,
+	Stf::Index: Into<Index>,
 {
 	type Block = Block;
 	type Error = error::Error;
@@ -83,28 +134,26 @@ where
 		&self,
 		_source: TrustedOperationSource,
 		uxt: StfTrustedOperation,
-		_shard: ShardIdentifier,
+		shard: ShardIdentifier,
 	) -> Self::ValidationFuture {
-		let operation = match uxt {
+		let validity = match uxt {
 			StfTrustedOperation::direct_call(signed_call) =>
-				Self::validate_trusted_call(signed_call),
+				self.validate_trusted_call(signed_call, &shard),
 			StfTrustedOperation::indirect_call(signed_call) =>
-				Self::validate_trusted_call(signed_call),
+				self.validate_trusted_call(signed_call, &shard),
 			StfTrustedOperation::get(getter) => match getter {
 				Getter::public(_) =>
-					return Box::pin(ready(Ok(Err(TransactionValidityError::Unknown(
-						UnknownTransaction::CannotLookup,
-					))))),
-				Getter::trusted(trusted_getter) => ValidTransaction {
-					priority: 1 << 20,
+					Err(TransactionValidityError::Unknown(UnknownTransaction::CannotLookup)),
+				Getter::trusted(trusted_getter) => Ok(ValidTransaction {
+					priority: BASE_PRIORITY,
 					requires: vec![],
 					provides: vec![trusted_getter.signature.encode()],
 					longevity: 64,
 					propagate: true,
-				},
+				}),
 			},
 		};
-		Box::pin(ready(Ok(Ok(operation))))
+		Box::pin(ready(Ok(validity)))
 	}
 
 	fn block_id_to_number(
@@ -143,57 +192,167 @@ where
 mod tests {
 	use super::*;
 	use futures::executor;
-	use ita_stf::{PublicGetter, TrustedCall, TrustedOperation};
+	use ita_stf::{Balance, PublicGetter, TrustedCall, TrustedOperation};
 	use itp_stf_primitives::types::{KeyPair, ShardIdentifier};
+	use itp_stf_state_observer::mock::ObserveStateMock;
 	use itp_types::Block as ParentchainBlock;
 	use sp_core::{ed25519, Pair};
 	use sp_keyring::AccountKeyring;
 
-	type TestChainApi = SidechainApi<ParentchainBlock>;
+	type TestChainApi =
+		SidechainApi<ParentchainBlock, ObserveStateMock<AccountStateMock>, AccountStfMock>;
 
 	type Seed = [u8; 32];
 	const TEST_SEED: Seed = *b"12345678901234567890123456789012";
 
+	/// State of every account, as seen by the `AccountStfMock`.
+	#[derive(Debug, Default)]
+	struct AccountStateMock {
+		nonce: Index,
+		can_pay_fee: bool,
+	}
+
+	/// Stf stub that takes the nonce and fee solvency of every account from the observed state.
+	struct AccountStfMock;
+
+	impl SystemPalletAccountInterface<AccountStateMock, AccountId> for AccountStfMock {
+		type Index = Index;
+		type AccountData = ();
+
+		fn get_account_nonce(state: &mut AccountStateMock, _account_id: &AccountId) -> Self::Index {
+			state.nonce
+		}
+
+		fn get_account_data(
+			_state: &mut AccountStateMock,
+			_account_id: &AccountId,
+		) -> Self::AccountData {
+		}
+	}
+
+	impl TransactionPaymentPalletInterface<AccountStateMock, TrustedCallSigned> for AccountStfMock {
+		fn can_pay_fee(state: &mut AccountStateMock, _call: &TrustedCallSigned) -> bool {
+			state.can_pay_fee
+		}
+	}
+
 	#[test]
 	fn indirect_calls_are_valid() {
-		let chain_api = TestChainApi::default();
-		let operation = create_indirect_trusted_operation();
+		let chain_api = chain_api_with_state_nonce(1);
+		let operation = create_indirect_trusted_operation(1, None);
 
-		let validation = executor::block_on(chain_api.validate_transaction(
-			TrustedOperationSource::Local,
-			operation,
-			ShardIdentifier::default(),
-		))
-		.unwrap();
+		let validation = validate(&chain_api, operation);
 
-		assert!(validation.is_ok());
+		let valid_operation = validation.unwrap();
+		assert!(valid_operation.requires.is_empty());
+		assert_eq!(valid_operation.provides, vec![(alice(), 1 as Index).encode()]);
+	}
+
+	#[test]
+	fn calls_with_future_nonce_require_their_predecessor() {
+		let chain_api = chain_api_with_state_nonce(1);
+		let operation = create_indirect_trusted_operation(3, None);
+
+		let valid_operation = validate(&chain_api, operation).unwrap();
+
+		assert_eq!(valid_operation.requires, vec![(alice(), 2 as Index).encode()]);
+		assert_eq!(valid_operation.provides, vec![(alice(), 3 as Index).encode()]);
+	}
+
+	#[test]
+	fn calls_with_stale_nonce_are_rejected() {
+		let chain_api = chain_api_with_state_nonce(2);
+		let operation = create_indirect_trusted_operation(1, None);
+
+		let validation = validate(&chain_api, operation);
+
+		assert_eq!(validation, Err(TransactionValidityError::Invalid(InvalidTransaction::Stale)));
+	}
+
+	#[test]
+	fn calls_whose_fee_cannot_be_paid_are_rejected() {
+		let chain_api = TestChainApi::new(Arc::new(ObserveStateMock::new(AccountStateMock {
+			nonce: 1,
+			can_pay_fee: false,
+		})));
+		let operation = create_indirect_trusted_operation(1, None);
+
+		let validation = validate(&chain_api, operation);
+
+		assert_eq!(validation, Err(TransactionValidityError::Invalid(InvalidTransaction::Payment)));
+	}
+
+	#[test]
+	fn tip_raises_priority() {
+		let chain_api = chain_api_with_state_nonce(1);
+
+		let without_tip = validate(&chain_api, create_indirect_trusted_operation(1, None)).unwrap();
+		let with_tip =
+			validate(&chain_api, create_indirect_trusted_operation(1, Some(500))).unwrap();
+		let with_huge_tip =
+			validate(&chain_api, create_indirect_trusted_operation(1, Some(Balance::MAX))).unwrap();
+
+		assert_eq!(without_tip.priority, BASE_PRIORITY);
+		assert_eq!(with_tip.priority, BASE_PRIORITY + 500);
+		assert_eq!(with_huge_tip.priority, TransactionPriority::MAX);
+	}
+
+	#[test]
+	fn calls_are_not_valid_without_shard_state() {
+		let chain_api = TestChainApi::new(Arc::new(ObserveStateMock::default()));
+		let operation = create_indirect_trusted_operation(0, None);
+
+		let validation = validate(&chain_api, operation);
+
+		assert_eq!(
+			validation,
+			Err(TransactionValidityError::Unknown(UnknownTransaction::CannotLookup))
+		);
 	}
 
 	#[test]
 	fn public_getters_are_not_valid() {
-		let chain_api = TestChainApi::default();
+		let chain_api = chain_api_with_state_nonce(0);
 		let public_getter = TrustedOperation::get(Getter::public(PublicGetter::some_value));
 
-		let validation = executor::block_on(chain_api.validate_transaction(
+		let validation = validate(&chain_api, public_getter);
+
+		assert!(validation.is_err());
+	}
+
+	fn validate(chain_api: &TestChainApi, operation: TrustedOperation) -> TransactionValidity {
+		executor::block_on(chain_api.validate_transaction(
 			TrustedOperationSource::Local,
-			public_getter,
+			operation,
 			ShardIdentifier::default(),
 		))
-		.unwrap();
+		.unwrap()
+	}
 
-		assert!(validation.is_err());
+	fn chain_api_with_state_nonce(nonce: Index) -> TestChainApi {
+		TestChainApi::new(Arc::new(ObserveStateMock::new(AccountStateMock {
+			nonce,
+			can_pay_fee: true,
+		})))
 	}
 
-	fn create_indirect_trusted_operation() -> TrustedOperation {
-		let trusted_call_signed = TrustedCall::balance_transfer(
-			AccountKeyring::Alice.public().into(),
-			AccountKeyring::Bob.public().into(),
-			1000u128,
-		)
-		.sign(&KeyPair::Ed25519(Box::new(signer())), 1, &[1u8; 32], &ShardIdentifier::default());
+	fn create_indirect_trusted_operation(nonce: Index, tip: Option<Balance>) -> TrustedOperation {
+		let trusted_call_signed =
+			TrustedCall::balance_transfer(alice(), AccountKeyring::Bob.public().into(), 1000u128)
+				.sign_with_tip(
+					&KeyPair::Ed25519(Box::new(signer())),
+					nonce,
+					tip,
+					&[1u8; 32],
+					&ShardIdentifier::default(),
+				);
 		TrustedOperation::indirect_call(trusted_call_signed)
 	}
 
+	fn alice() -> AccountId {
+		AccountKeyring::Alice.public().into()
+	}
+
 	fn signer() -> ed25519::Pair {
 		ed25519::Pair::from_seed(&TEST_SEED)
 	}
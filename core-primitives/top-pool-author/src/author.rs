@@ -18,6 +18,12 @@
 #[cfg(all(not(feature = "std"), feature = "sgx"))]
 use crate::sgx_reexport_prelude::*;
 
+#[cfg(feature = "sgx")]
+use std::sync::SgxRwLock as RwLock;
+
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
 use crate::{
 	client_error::Error as ClientError,
 	error::{Error as StateRpcError, Result},
@@ -34,18 +40,18 @@ use itp_stf_state_handler::query_shard_state::QueryShardState;
 use itp_top_pool::{
 	error::{Error as PoolError, IntoPoolError},
 	primitives::{
-		BlockHash, InPoolOperation, PoolFuture, TrustedOperationPool, TrustedOperationSource,
-		TxHash,
+		BlockHash, InPoolOperation, PersistedOperation, PoolFuture, TrustedOperationPool,
+		TrustedOperationSource, TxHash,
 	},
 };
-use itp_types::{BlockHash as SidechainBlockHash, ShardIdentifier};
+use itp_types::{BlockHash as SidechainBlockHash, Request, ShardIdentifier, TopPoolLimits};
 use jsonrpc_core::{
 	futures::future::{ready, TryFutureExt},
 	Error as RpcError,
 };
 use log::*;
 use sp_runtime::generic;
-use std::{boxed::Box, sync::Arc, vec::Vec};
+use std::{boxed::Box, collections::HashMap, string::String, sync::Arc, vec::Vec};
 
 /// Define type of TOP filter that is used in the Author
 #[cfg(feature = "sidechain")]
@@ -65,6 +71,10 @@ pub type AuthorTopFilter = crate::top_filter::CallsOnlyFilter;
 /// some unique operations via RPC and have them included in the pool.
 const TX_SOURCE: TrustedOperationSource = TrustedOperationSource::External;
 
+/// Upper bound of operations queued for gossiping, the oldest ones are dropped beyond it.
+/// Peers still learn about a dropped operation from the sidechain block including it.
+pub(crate) const MAX_TOPS_TO_GOSSIP: usize = 1024;
+
 /// Authoring API for RPC calls
 ///
 ///
@@ -81,6 +91,7 @@ where
 	state_facade: Arc<StateFacade>,
 	shielding_key_repo: Arc<ShieldingKeyRepository>,
 	ocall_api: Arc<OCallApi>,
+	tops_to_gossip: Arc<RwLock<Vec<(TxHash<TopPool>, Request)>>>,
 }
 
 impl<TopPool, TopFilter, StateFacade, ShieldingKeyRepository, OCallApi>
@@ -101,13 +112,22 @@ where
 		encryption_key: Arc<ShieldingKeyRepository>,
 		ocall_api: Arc<OCallApi>,
 	) -> Self {
-		Author { top_pool, top_filter, state_facade, shielding_key_repo: encryption_key, ocall_api }
+		Author {
+			top_pool,
+			top_filter,
+			state_facade,
+			shielding_key_repo: encryption_key,
+			ocall_api,
+			tops_to_gossip: Default::default(),
+		}
 	}
 }
 
 enum TopSubmissionMode {
 	Submit,
 	SubmitWatch,
+	/// Submit an operation gossiped by a peer, without gossiping it any further.
+	SubmitGossiped,
 }
 
 impl<TopPool, TopFilter, StateFacade, ShieldingKeyRepository, OCallApi>
@@ -156,6 +176,13 @@ where
 			return Box::pin(ready(Err(ClientError::UnsupportedOperation.into())))
 		}
 
+		// Direct calls submitted by clients are gossiped to the peer validateers, still encrypted.
+		let gossip_request = match submission_mode {
+			TopSubmissionMode::SubmitGossiped => None,
+			_ => matches!(trusted_operation, TrustedOperation::direct_call(_))
+				.then(|| Request { shard, cyphertext: ext.clone() }),
+		};
+
 		//let best_block_hash = self.client.info().best_hash;
 		// dummy block hash
 		let best_block_hash = Default::default();
@@ -181,29 +208,21 @@ where
 			);
 		}
 
-		match submission_mode {
-			TopSubmissionMode::Submit => Box::pin(
-				self.top_pool
-					.submit_one(
-						&generic::BlockId::hash(best_block_hash),
-						TX_SOURCE,
-						trusted_operation,
-						shard,
-					)
-					.map_err(map_top_error::<TopPool>),
-			),
-
-			TopSubmissionMode::SubmitWatch => Box::pin(
-				self.top_pool
-					.submit_and_watch(
-						&generic::BlockId::hash(best_block_hash),
-						TX_SOURCE,
-						trusted_operation,
-						shard,
-					)
-					.map_err(map_top_error::<TopPool>),
-			),
-		}
+		let at = generic::BlockId::hash(best_block_hash);
+		let submission = match submission_mode {
+			TopSubmissionMode::Submit | TopSubmissionMode::SubmitGossiped =>
+				self.top_pool.submit_one(&at, TX_SOURCE, trusted_operation, shard),
+			TopSubmissionMode::SubmitWatch =>
+				self.top_pool.submit_and_watch(&at, TX_SOURCE, trusted_operation, shard),
+		};
+
+		let tops_to_gossip = self.tops_to_gossip.clone();
+		Box::pin(submission.map_err(map_top_error::<TopPool>).map_ok(move |hash| {
+			if let Some(request) = gossip_request {
+				queue_for_gossip(&tops_to_gossip, hash.clone(), request);
+			}
+			hash
+		}))
 	}
 
 	fn remove_top(
@@ -245,6 +264,23 @@ where
 	}
 }
 
+pub(crate) fn queue_for_gossip<Hash: PartialEq>(
+	tops_to_gossip: &RwLock<Vec<(Hash, Request)>>,
+	hash: Hash,
+	request: Request,
+) {
+	match tops_to_gossip.write() {
+		Ok(mut queue) =>
+			if !queue.iter().any(|(queued_hash, _)| queued_hash == &hash) {
+				if queue.len() >= MAX_TOPS_TO_GOSSIP {
+					queue.remove(0);
+				}
+				queue.push((hash, request));
+			},
+		Err(e) => warn!("Failed to queue trusted operation for gossiping: {:?}", e),
+	}
+}
+
 fn map_top_error<P: TrustedOperationPool>(error: P::Error) -> RpcError {
 	StateRpcError::PoolError(
 		error
@@ -279,6 +315,13 @@ where
 		self.top_pool.hash_of(xt)
 	}
 
+	fn is_pending(&self, hash: &TxHash<TopPool>) -> bool {
+		self.top_pool
+			.shards()
+			.into_iter()
+			.any(|shard| self.top_pool.is_imported(hash, shard))
+	}
+
 	fn pending_tops(&self, shard: ShardIdentifier) -> Result<Vec<Vec<u8>>> {
 		Ok(self.top_pool.ready(shard).map(|top| top.data().encode()).collect())
 	}
@@ -319,6 +362,87 @@ where
 		self.top_pool.shards()
 	}
 
+	fn set_pool_limits(&self, limits: &TopPoolLimits) {
+		self.top_pool.set_limits(limits)
+	}
+
+	fn submit_gossiped_top(
+		&self,
+		ext: Vec<u8>,
+		shard: ShardIdentifier,
+	) -> PoolFuture<TxHash<TopPool>, RpcError> {
+		self.process_top(ext, shard, TopSubmissionMode::SubmitGossiped)
+	}
+
+	fn take_tops_to_gossip(&self) -> Vec<(TxHash<TopPool>, Request)> {
+		match self.tops_to_gossip.write() {
+			// Operations that were executed or dropped in the meantime are not gossiped anymore.
+			Ok(mut queue) => queue
+				.drain(..)
+				.filter(|(hash, request)| self.top_pool.is_imported(hash, request.shard))
+				.collect(),
+			Err(e) => {
+				warn!("Failed to take trusted operations to gossip: {:?}", e);
+				Vec::new()
+			},
+		}
+	}
+
+	fn on_tops_gossiped(&self, hashes: &[TxHash<TopPool>], peers: Vec<String>) {
+		self.top_pool
+			.on_broadcasted(hashes.iter().map(|hash| (hash.clone(), peers.clone())).collect());
+	}
+
+	fn pending_operations(&self) -> Vec<PersistedOperation> {
+		self.top_pool
+			.pending_operations()
+			.into_iter()
+			.filter(|o| !matches!(o.operation, TrustedOperation::get(_)))
+			.collect()
+	}
+
+	fn restore_operations(
+		&self,
+		operations: Vec<PersistedOperation>,
+	) -> PoolFuture<Vec<core::result::Result<TxHash<TopPool>, RpcError>>, RpcError> {
+		let operations: Vec<PersistedOperation> = operations
+			.into_iter()
+			.filter(|o| {
+				let shard_exists = self.state_facade.shard_exists(&o.shard).unwrap_or(false);
+				if !shard_exists || !self.top_filter.filter(&o.operation) {
+					warn!(
+						"Dropping persisted operation {:?} of shard {:?}, it is no longer accepted",
+						self.hash_of(&o.operation),
+						o.shard
+					);
+					return false
+				}
+				true
+			})
+			.collect();
+
+		for _ in &operations {
+			if let Err(e) = self.ocall_api.update_metric(EnclaveMetric::TopPoolSizeIncrement) {
+				warn!("Failed to update metric for top pool size: {:?}", e);
+			}
+		}
+
+		// dummy block hash
+		let best_block_hash = Default::default();
+
+		Box::pin(
+			self.top_pool
+				.restore_operations(&generic::BlockId::hash(best_block_hash), operations)
+				.map_ok(|results| {
+					results
+						.into_iter()
+						.map(|result| result.map_err(map_top_error::<TopPool>))
+						.collect()
+				})
+				.map_err(map_top_error::<TopPool>),
+		)
+	}
+
 	fn remove_calls_from_pool(
 		&self,
 		shard: ShardIdentifier,
@@ -16,7 +16,7 @@
 */
 
 use crate::{
-	author::Author,
+	author::{queue_for_gossip, Author, MAX_TOPS_TO_GOSSIP},
 	test_fixtures::{
 		create_indirect_trusted_operation, shard_id, trusted_call_signed, trusted_getter_signed,
 	},
@@ -32,10 +32,14 @@ use itp_test::mock::{
 	handle_state_mock::HandleStateMock, metrics_ocall_mock::MetricsOCallMock,
 	shielding_crypto_mock::ShieldingCryptoMock,
 };
-use itp_top_pool::mocks::trusted_operation_pool_mock::TrustedOperationPoolMock;
+use itp_top_pool::{
+	mocks::trusted_operation_pool_mock::TrustedOperationPoolMock,
+	primitives::{PersistedOperation, TrustedOperationPool},
+};
+use itp_types::{Request, ShardIdentifier};
 use sgx_crypto_helper::{rsa3072::Rsa3072KeyPair, RsaKeyPair};
 use sp_core::H256;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 type TestAuthor<Filter> = Author<
 	TrustedOperationPoolMock,
@@ -123,6 +127,92 @@ fn submitting_indirect_call_works() {
 	assert_eq!(1, author.get_pending_trusted_calls(shard_id()).len());
 }
 
+#[test]
+fn only_submitted_calls_are_pending() {
+	let (author, _, shielding_key) = create_author_with_filter(AllowAllTopsFilter);
+	let trusted_call = TrustedOperation::direct_call(trusted_call_signed());
+
+	let hash =
+		submit_operation_to_top_pool(&author, &trusted_call, &shielding_key, shard_id()).unwrap();
+
+	assert!(author.is_pending(&hash));
+	assert!(!author.is_pending(&H256::repeat_byte(1)));
+}
+
+#[test]
+fn submitted_direct_calls_are_queued_for_gossip_once() {
+	let (author, _, shielding_key) = create_author_with_filter(AllowAllTopsFilter);
+	let trusted_call = TrustedOperation::direct_call(trusted_call_signed());
+	let trusted_getter = TrustedOperation::from(trusted_getter_signed());
+
+	submit_operation_to_top_pool(&author, &trusted_getter, &shielding_key, shard_id()).unwrap();
+	let hash =
+		submit_operation_to_top_pool(&author, &trusted_call, &shielding_key, shard_id()).unwrap();
+	submit_operation_to_top_pool(&author, &trusted_call, &shielding_key, shard_id()).unwrap();
+
+	let tops_to_gossip = author.take_tops_to_gossip();
+	assert_eq!(1, tops_to_gossip.len());
+	assert_eq!(hash, tops_to_gossip[0].0);
+	assert_eq!(shard_id(), tops_to_gossip[0].1.shard);
+	assert!(author.take_tops_to_gossip().is_empty());
+}
+
+#[test]
+fn calls_no_longer_in_the_pool_are_not_gossiped() {
+	let (author, top_pool, shielding_key) = create_author_with_filter(AllowAllTopsFilter);
+	let trusted_call = TrustedOperation::direct_call(trusted_call_signed());
+
+	let hash =
+		submit_operation_to_top_pool(&author, &trusted_call, &shielding_key, shard_id()).unwrap();
+	top_pool.remove_invalid(&[hash], shard_id(), true);
+
+	assert!(author.take_tops_to_gossip().is_empty());
+}
+
+#[test]
+fn gossip_queue_drops_the_oldest_calls_when_full() {
+	let queue = RwLock::new(Vec::new());
+	let request = || Request { shard: shard_id(), cyphertext: vec![] };
+
+	for hash in 0..=MAX_TOPS_TO_GOSSIP {
+		queue_for_gossip(&queue, hash, request());
+	}
+
+	let queue = queue.into_inner().unwrap();
+	assert_eq!(MAX_TOPS_TO_GOSSIP, queue.len());
+	assert_eq!(1, queue[0].0);
+	assert_eq!(MAX_TOPS_TO_GOSSIP, queue[MAX_TOPS_TO_GOSSIP - 1].0);
+}
+
+#[test]
+fn gossiped_calls_are_not_gossiped_again() {
+	let (author, top_pool, shielding_key) = create_author_with_filter(AllowAllTopsFilter);
+	let trusted_call = TrustedOperation::direct_call(trusted_call_signed());
+	let encrypted_call = shielding_key.encrypt(&trusted_call.encode()).unwrap();
+
+	futures::executor::block_on(author.submit_gossiped_top(encrypted_call, shard_id())).unwrap();
+
+	assert_eq!(1, top_pool.get_last_submitted_transactions().len());
+	assert!(author.take_tops_to_gossip().is_empty());
+}
+
+#[test]
+fn restoring_operations_drops_unknown_shards_and_filtered_operations() {
+	let (author, top_pool, _) = create_author_with_filter(GettersOnlyFilter);
+	let persisted = |shard, operation| PersistedOperation { shard, operation, watched: false };
+	let getter = TrustedOperation::from(trusted_getter_signed());
+	let operations = vec![
+		persisted(shard_id(), getter.clone()),
+		persisted(ShardIdentifier::repeat_byte(7), getter),
+		persisted(shard_id(), TrustedOperation::direct_call(trusted_call_signed())),
+	];
+
+	let restored = futures::executor::block_on(author.restore_operations(operations)).unwrap();
+
+	assert_eq!(1, restored.len());
+	assert_eq!(1, top_pool.get_last_submitted_transactions().len());
+}
+
 fn create_author_with_filter<F: Filter<Value = TrustedOperation>>(
 	filter: F,
 ) -> (TestAuthor<F>, Arc<TrustedOperationPoolMock>, ShieldingCryptoMock) {
@@ -104,6 +104,8 @@ const POOL_CYCLE_DETECTED: i64 = POOL_INVALID_TX + 5;
 const POOL_IMMEDIATELY_DROPPED: i64 = POOL_INVALID_TX + 6;
 /// The key type crypto is not known.
 const UNSUPPORTED_KEY_TYPE: i64 = POOL_INVALID_TX + 7;
+/// The sender has too many operations in the pool.
+const POOL_SENDER_QUOTA_EXCEEDED: i64 = POOL_INVALID_TX + 8;
 
 impl From<Error> for rpc_core::Error {
 	fn from(e: Error) -> Self {
@@ -165,6 +167,11 @@ impl From<Error> for rpc_core::Error {
 				message: "Immediately Dropped".into(),
 				data: Some("The Trusted Operation couldn't enter the pool because of the limit".into()),
 			},
+			Error::Pool(PoolError::SenderQuotaExceeded) => rpc_core::Error {
+				code: rpc_core::ErrorCode::ServerError(POOL_SENDER_QUOTA_EXCEEDED),
+				message: "Sender Quota Exceeded".into(),
+				data: Some("The sender has reached its limit of Trusted Operations in the pool".into()),
+			},
 			Error::UnsupportedKeyType => rpc_core::Error {
 				code: rpc_core::ErrorCode::ServerError(UNSUPPORTED_KEY_TYPE),
 				message: "Unknown key type crypto" .into(),
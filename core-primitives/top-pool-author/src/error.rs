@@ -65,6 +65,9 @@ pub enum Error {
 
 	#[display(fmt = "Codec error: {}", _0)]
 	CodecError(codec::Error),
+
+	#[display(fmt = "IO error: {}", _0)]
+	IO(std::io::Error),
 }
 
 impl error::Error for Error {
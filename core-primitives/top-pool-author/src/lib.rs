@@ -39,6 +39,9 @@ pub mod error;
 pub mod top_filter;
 pub mod traits;
 
+#[cfg(feature = "sgx")]
+pub mod sealing;
+
 #[cfg(test)]
 mod author_tests;
 
@@ -28,17 +28,17 @@ use crate::{
 	error::Result,
 	traits::{AuthorApi, OnBlockImported},
 };
-use codec::Decode;
+use codec::{Decode, Encode};
 use ita_stf::{
 	hash::{Hash, TrustedOperationOrHash},
 	Getter, TrustedGetterSigned, TrustedOperation,
 };
 use itp_stf_primitives::types::AccountId;
-use itp_top_pool::primitives::PoolFuture;
-use itp_types::ShardIdentifier;
+use itp_top_pool::primitives::{PersistedOperation, PoolFuture};
+use itp_types::{Request, ShardIdentifier, TopPoolLimits};
 use jsonrpc_core::{futures::future::ready, Error as RpcError};
 use sp_core::{blake2_256, H256};
-use std::{boxed::Box, collections::HashMap, marker::PhantomData, vec, vec::Vec};
+use std::{boxed::Box, collections::HashMap, marker::PhantomData, string::String, vec, vec::Vec};
 
 #[derive(Default)]
 pub struct AuthorApiMock<Hash, BlockHash> {
@@ -98,10 +98,31 @@ impl AuthorApi<H256, H256> for AuthorApiMock<H256, H256> {
 		Box::pin(ready(Ok(H256::default())))
 	}
 
+	fn submit_gossiped_top(
+		&self,
+		extrinsic: Vec<u8>,
+		shard: ShardIdentifier,
+	) -> PoolFuture<H256, RpcError> {
+		self.submit_top(extrinsic, shard)
+	}
+
+	fn take_tops_to_gossip(&self) -> Vec<(H256, Request)> {
+		Vec::new()
+	}
+
+	fn on_tops_gossiped(&self, _hashes: &[H256], _peers: Vec<String>) {}
+
 	fn hash_of(&self, xt: &TrustedOperation) -> H256 {
 		xt.hash()
 	}
 
+	fn is_pending(&self, hash: &H256) -> bool {
+		self.tops.read().unwrap().values().flatten().any(|encoded_operation| {
+			Self::decode_trusted_operation(encoded_operation)
+				.map_or(false, |operation| operation.hash() == *hash)
+		})
+	}
+
 	fn pending_tops(&self, shard: ShardIdentifier) -> Result<Vec<Vec<u8>>> {
 		let extrinsics = self.tops.read().unwrap().get(&shard).cloned();
 		Ok(extrinsics.unwrap_or_default())
@@ -168,6 +189,39 @@ impl AuthorApi<H256, H256> for AuthorApiMock<H256, H256> {
 		self.tops.read().unwrap().keys().cloned().collect()
 	}
 
+	fn set_pool_limits(&self, _limits: &TopPoolLimits) {}
+
+	fn pending_operations(&self) -> Vec<PersistedOperation> {
+		self.tops
+			.read()
+			.unwrap()
+			.iter()
+			.flat_map(|(shard, encoded_operations)| {
+				encoded_operations.iter().filter_map(|encoded_operation| {
+					Self::decode_trusted_operation(encoded_operation).map(|operation| {
+						PersistedOperation { shard: *shard, operation, watched: false }
+					})
+				})
+			})
+			.collect()
+	}
+
+	fn restore_operations(
+		&self,
+		operations: Vec<PersistedOperation>,
+	) -> PoolFuture<Vec<core::result::Result<H256, RpcError>>, RpcError> {
+		let mut write_lock = self.tops.write().unwrap();
+		let hashes = operations
+			.into_iter()
+			.map(|PersistedOperation { shard, operation, .. }| {
+				let hash = operation.hash();
+				write_lock.entry(shard).or_default().push(operation.encode());
+				Ok(hash)
+			})
+			.collect();
+		Box::pin(ready(Ok(hashes)))
+	}
+
 	fn remove_calls_from_pool(
 		&self,
 		shard: ShardIdentifier,
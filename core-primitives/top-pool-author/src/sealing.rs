@@ -0,0 +1,47 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::error::{Error, Result};
+use codec::{Decode, Encode};
+use itp_settings::files::TOP_POOL_FILE;
+use itp_sgx_io::{seal, unseal, SealedIO};
+use itp_top_pool::primitives::PersistedOperation;
+use std::{sgxfs::SgxFile, vec::Vec};
+
+/// Seals the pending operations of the TOP pool to a static file, so they survive restarts.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct TopPoolSeal;
+
+impl TopPoolSeal {
+	/// Returns true if the TOP pool has been sealed before.
+	pub fn exists(&self) -> bool {
+		SgxFile::open(TOP_POOL_FILE).is_ok()
+	}
+}
+
+impl SealedIO for TopPoolSeal {
+	type Error = Error;
+	type Unsealed = Vec<PersistedOperation>;
+
+	fn unseal(&self) -> Result<Self::Unsealed> {
+		Ok(unseal(TOP_POOL_FILE).map(|b| Decode::decode(&mut b.as_slice()))??)
+	}
+
+	fn seal(&self, unsealed: &Self::Unsealed) -> Result<()> {
+		Ok(unsealed.using_encoded(|bytes| seal(bytes, TOP_POOL_FILE))?)
+	}
+}
@@ -21,10 +21,10 @@ use crate::sgx_reexport_prelude::*;
 use crate::error::Result;
 use ita_stf::{hash, TrustedOperation};
 use itp_stf_primitives::types::AccountId;
-use itp_top_pool::primitives::PoolFuture;
-use itp_types::{BlockHash as SidechainBlockHash, ShardIdentifier, H256};
+use itp_top_pool::primitives::{PersistedOperation, PoolFuture};
+use itp_types::{BlockHash as SidechainBlockHash, Request, ShardIdentifier, TopPoolLimits, H256};
 use jsonrpc_core::Error as RpcError;
-use std::vec::Vec;
+use std::{string::String, vec::Vec};
 
 /// Trait alias for a full STF author API
 pub trait FullAuthor = AuthorApi<H256, H256> + OnBlockImported<Hash = H256> + Send + Sync + 'static;
@@ -34,9 +34,28 @@ pub trait AuthorApi<Hash, BlockHash> {
 	/// Submit encoded extrinsic for inclusion in block.
 	fn submit_top(&self, extrinsic: Vec<u8>, shard: ShardIdentifier) -> PoolFuture<Hash, RpcError>;
 
+	/// Submit encoded extrinsic that was gossiped by a peer validateer.
+	/// In contrast to [`AuthorApi::submit_top`], it is not gossiped any further.
+	fn submit_gossiped_top(
+		&self,
+		extrinsic: Vec<u8>,
+		shard: ShardIdentifier,
+	) -> PoolFuture<Hash, RpcError>;
+
+	/// Take the (still encrypted) trusted calls that were submitted since the last call and are
+	/// still in the pool, to be gossiped to the peer validateers.
+	fn take_tops_to_gossip(&self) -> Vec<(Hash, Request)>;
+
+	/// Notify the watchers of the given operations that they were gossiped to `peers`.
+	fn on_tops_gossiped(&self, hashes: &[Hash], peers: Vec<String>);
+
 	/// Return hash of Trusted Operation
 	fn hash_of(&self, xt: &TrustedOperation) -> Hash;
 
+	/// Whether the operation is still in the pool of any shard, i.e. waiting to be included
+	/// in a sidechain block.
+	fn is_pending(&self, hash: &Hash) -> bool;
+
 	/// Returns all pending operations, potentially grouped by sender.
 	fn pending_tops(&self, shard: ShardIdentifier) -> Result<Vec<Vec<u8>>>;
 
@@ -55,6 +74,20 @@ pub trait AuthorApi<Hash, BlockHash> {
 
 	fn get_shards(&self) -> Vec<ShardIdentifier>;
 
+	/// Replace the limits of the trusted operation pool.
+	fn set_pool_limits(&self, limits: &TopPoolLimits);
+
+	/// Returns all pending trusted calls of all shards, in the form they are persisted
+	/// across restarts. Trusted getters are not included.
+	fn pending_operations(&self) -> Vec<PersistedOperation>;
+
+	/// Re-submit previously persisted operations, revalidating them against the current state.
+	/// Operations that are no longer valid are dropped and their watchers notified.
+	fn restore_operations(
+		&self,
+		operations: Vec<PersistedOperation>,
+	) -> PoolFuture<Vec<core::result::Result<Hash, RpcError>>, RpcError>;
+
 	/// Remove a collection of trusted operations from the pool.
 	/// Return operations that were not successfully removed.
 	fn remove_calls_from_pool(
@@ -38,7 +38,7 @@ use sp_runtime::{
 		TransactionLongevity as Longevity, TransactionPriority as Priority, TransactionTag as Tag,
 	},
 };
-use std::collections::HashSet;
+use std::{collections::HashSet, time::Instant};
 
 /// Successful import result.
 #[derive(Debug, PartialEq, Eq)]
@@ -415,64 +415,111 @@ impl<Hash: hash::Hash + Member + Ord, Ex: fmt::Debug> BasePool<Hash, Ex> {
 
 	/// Makes sure that the operations in the queues stay within provided limits.
 	///
+	/// The `ready` and `future` limits apply to the whole pool, across all shards, whereas
+	/// `max_per_shard` caps the number of operations (ready and future) of the given `shard`.
+	///
 	/// Removes and returns worst operations from the queues and all operations that depend on them.
 	/// Technically the worst operation should be evaluated by computing the entire pending set.
-	/// We use a simplified approach to remove the operation that occupies the pool for the longest time.
+	/// We use a simplified approach and remove the operation with the lowest priority, preferring
+	/// the one that occupies the pool for the longest time if several share the same priority.
 	pub fn enforce_limits(
 		&mut self,
 		ready: &Limit,
 		future: &Limit,
+		max_per_shard: usize,
 		shard: ShardIdentifier,
 	) -> Vec<Arc<TrustedOperation<Hash, Ex>>> {
 		let mut removed = vec![];
 
-		while ready.is_exceeded(self.ready.len(shard), self.ready.bytes(shard)) {
-			// find the worst operation
-			let minimal = self.ready.fold(
-				|minimal, current| {
-					let operation = &current.operation;
-					match minimal {
-						None => Some(operation.clone()),
-						Some(ref tx) if tx.insertion_id > operation.insertion_id =>
-							Some(operation.clone()),
-						other => other,
-					}
+		// future operations are evicted first, they are the least likely to be executed
+		while self.ready.len(shard) + self.future.len(shard) > max_per_shard {
+			let worst = match self.worst_future(shard) {
+				Some((_, _, hash)) => hash,
+				None => match self.worst_ready(shard) {
+					Some((_, _, hash)) => hash,
+					None => break,
 				},
-				shard,
-			);
+			};
+			removed.append(&mut self.remove_subtree(&[worst], shard));
+		}
 
-			if let Some(minimal) = minimal {
-				removed.append(&mut self.remove_subtree(&[minimal.operation.hash.clone()], shard))
-			} else {
+		loop {
+			let status = self.total_status();
+			if !ready.is_exceeded(status.ready, status.ready_bytes) {
 				break
 			}
+			let worst = self
+				.all_shards()
+				.into_iter()
+				.filter_map(|shard| self.worst_ready(shard).map(|worst| (worst, shard)))
+				.min_by(|(a, _), (b, _)| (a.0, a.1).cmp(&(b.0, b.1)));
+
+			match worst {
+				Some(((_, _, hash), shard)) =>
+					removed.append(&mut self.remove_subtree(&[hash], shard)),
+				None => break,
+			}
 		}
 
-		while future.is_exceeded(self.future.len(shard), self.future.bytes(shard)) {
-			// find the worst operation
-			let minimal = self.future.fold(
-				|minimal, current| {
-					match minimal {
-						None => Some(current.clone()),
-						/*Some(ref tx) if tx.imported_at > current.imported_at => {
-							Some(current.clone())
-						},*/
-						other => other,
-					}
-				},
-				shard,
-			);
-
-			if let Some(minimal) = minimal {
-				removed.append(&mut self.remove_subtree(&[minimal.operation.hash.clone()], shard))
-			} else {
+		loop {
+			let status = self.total_status();
+			if !future.is_exceeded(status.future, status.future_bytes) {
 				break
 			}
+			let worst = self
+				.all_shards()
+				.into_iter()
+				.filter_map(|shard| self.worst_future(shard).map(|worst| (worst, shard)))
+				.min_by(|(a, _), (b, _)| (a.0, a.1).cmp(&(b.0, b.1)));
+
+			match worst {
+				Some(((_, _, hash), shard)) =>
+					removed.append(&mut self.remove_subtree(&[hash], shard)),
+				None => break,
+			}
 		}
 
 		removed
 	}
 
+	/// Returns priority, insertion id and hash of the worst ready operation of a shard.
+	fn worst_ready(&mut self, shard: ShardIdentifier) -> Option<(Priority, u64, Hash)> {
+		self.ready.fold(
+			|worst: Option<(Priority, u64, Hash)>, current| {
+				let operation = &current.operation;
+				let key = (operation.operation.priority, operation.insertion_id);
+				match worst {
+					Some(worst) if (worst.0, worst.1) <= key => Some(worst),
+					_ => Some((key.0, key.1, operation.operation.hash.clone())),
+				}
+			},
+			shard,
+		)
+	}
+
+	/// Returns priority, import time and hash of the worst future operation of a shard.
+	fn worst_future(&mut self, shard: ShardIdentifier) -> Option<(Priority, Instant, Hash)> {
+		self.future.fold(
+			|worst: Option<(Priority, Instant, Hash)>, current| {
+				let key = (current.operation.priority, current.imported_at);
+				match worst {
+					Some(worst) if (worst.0, worst.1) <= key => Some(worst),
+					_ => Some((key.0, key.1, current.operation.hash.clone())),
+				}
+			},
+			shard,
+		)
+	}
+
+	/// Returns all shards that have either ready or future operations.
+	pub fn all_shards(&self) -> Vec<ShardIdentifier> {
+		let mut shards: Vec<ShardIdentifier> =
+			self.ready.get_shards().chain(self.future.get_shards()).copied().collect();
+		shards.sort();
+		shards.dedup();
+		shards
+	}
+
 	/// Removes all operations represented by the hashes and all other operations
 	/// that depend on them.
 	///
@@ -547,6 +594,19 @@ impl<Hash: hash::Hash + Member + Ord, Ex: fmt::Debug> BasePool<Hash, Ex> {
 			future_bytes: self.future.bytes(shard),
 		}
 	}
+
+	/// Returns pool status summed up over all shards.
+	pub fn total_status(&self) -> PoolStatus {
+		self.all_shards().into_iter().map(|shard| self.status(shard)).fold(
+			PoolStatus { ready: 0, ready_bytes: 0, future: 0, future_bytes: 0 },
+			|total, status| PoolStatus {
+				ready: total.ready + status.ready,
+				ready_bytes: total.ready_bytes + status.ready_bytes,
+				future: total.future + status.future,
+				future_bytes: total.future_bytes + status.future_bytes,
+			},
+		)
+	}
 }
 
 /// Queue limits
@@ -1379,4 +1439,123 @@ source: External, requires: [03,02], provides: [04], data: [4]}"
 		assert!(pool.reject_future_operations);
 		assert_eq!(pool.future.len(shard), 1);
 	}
+
+	fn operation(
+		hash: Hash,
+		priority: Priority,
+		requires: Vec<Tag>,
+		provides: Vec<Tag>,
+	) -> TrustedOperation<Hash, Vec<u8>> {
+		TrustedOperation {
+			data: vec![hash as u8],
+			bytes: 1,
+			hash,
+			priority,
+			valid_till: 64u64,
+			requires,
+			provides,
+			propagate: true,
+			source: Source::External,
+		}
+	}
+
+	#[test]
+	pub fn test_should_evict_lowest_priority_and_oldest_ready_operation() {
+		// given
+		let mut pool = test_pool();
+		let shard = ShardIdentifier::default();
+		let limit = Limit { count: 2, total_bytes: 100 };
+		pool.import(operation(1, 5u64, vec![], vec![vec![1]]), shard).unwrap();
+		pool.import(operation(2, 1u64, vec![], vec![vec![2]]), shard).unwrap();
+		pool.import(operation(3, 1u64, vec![], vec![vec![3]]), shard).unwrap();
+
+		// when
+		let removed = pool.enforce_limits(&limit, &limit, usize::MAX, shard);
+
+		// then
+		assert_eq!(removed.iter().map(|tx| tx.hash).collect::<Vec<_>>(), vec![2]);
+		assert_eq!(pool.ready.len(shard), 2);
+		assert!(pool.ready.contains(&1, shard));
+		assert!(pool.ready.contains(&3, shard));
+	}
+
+	#[test]
+	pub fn test_should_evict_dependent_operations_together_with_the_worst_one() {
+		// given
+		let mut pool = test_pool();
+		let shard = ShardIdentifier::default();
+		let limit = Limit { count: 2, total_bytes: 100 };
+		pool.import(operation(1, 1u64, vec![], vec![vec![1]]), shard).unwrap();
+		pool.import(operation(2, 5u64, vec![vec![1]], vec![vec![2]]), shard).unwrap();
+		pool.import(operation(3, 3u64, vec![], vec![vec![3]]), shard).unwrap();
+
+		// when
+		let removed = pool.enforce_limits(&limit, &limit, usize::MAX, shard);
+
+		// then
+		let removed_hashes: HashSet<_> = removed.iter().map(|tx| tx.hash).collect();
+		assert_eq!(removed_hashes, HashSet::from([1, 2]));
+		assert_eq!(pool.ready.len(shard), 1);
+		assert!(pool.ready.contains(&3, shard));
+	}
+
+	#[test]
+	pub fn test_should_enforce_ready_limit_across_shards() {
+		// given
+		let mut pool = test_pool();
+		let shard_a = ShardIdentifier::repeat_byte(1);
+		let shard_b = ShardIdentifier::repeat_byte(2);
+		let limit = Limit { count: 2, total_bytes: 100 };
+		pool.import(operation(1, 3u64, vec![], vec![vec![1]]), shard_a).unwrap();
+		pool.import(operation(2, 5u64, vec![], vec![vec![2]]), shard_a).unwrap();
+		pool.import(operation(3, 1u64, vec![], vec![vec![3]]), shard_b).unwrap();
+
+		// when
+		let removed = pool.enforce_limits(&limit, &limit, usize::MAX, shard_a);
+
+		// then
+		assert_eq!(removed.iter().map(|tx| tx.hash).collect::<Vec<_>>(), vec![3]);
+		assert_eq!(pool.ready.len(shard_a), 2);
+		assert_eq!(pool.ready.len(shard_b), 0);
+		assert_eq!(pool.total_status().ready, 2);
+	}
+
+	#[test]
+	pub fn test_should_evict_future_operations_first_when_shard_is_full() {
+		// given
+		let mut pool = test_pool();
+		let shard = ShardIdentifier::default();
+		let limit = Limit { count: 100, total_bytes: 100 };
+		pool.import(operation(1, 1u64, vec![], vec![vec![1]]), shard).unwrap();
+		pool.import(operation(2, 5u64, vec![vec![0]], vec![vec![2]]), shard).unwrap();
+		pool.import(operation(3, 1u64, vec![], vec![vec![3]]), shard).unwrap();
+
+		// when
+		let removed = pool.enforce_limits(&limit, &limit, 2, shard);
+
+		// then
+		assert_eq!(removed.iter().map(|tx| tx.hash).collect::<Vec<_>>(), vec![2]);
+		assert_eq!(pool.ready.len(shard), 2);
+		assert_eq!(pool.future.len(shard), 0);
+	}
+
+	#[test]
+	pub fn test_should_not_touch_other_shards_when_shard_is_full() {
+		// given
+		let mut pool = test_pool();
+		let shard_a = ShardIdentifier::repeat_byte(1);
+		let shard_b = ShardIdentifier::repeat_byte(2);
+		let limit = Limit { count: 100, total_bytes: 100 };
+		pool.import(operation(1, 5u64, vec![], vec![vec![1]]), shard_a).unwrap();
+		pool.import(operation(2, 1u64, vec![], vec![vec![2]]), shard_a).unwrap();
+		pool.import(operation(3, 1u64, vec![], vec![vec![3]]), shard_b).unwrap();
+
+		// when
+		let removed = pool.enforce_limits(&limit, &limit, 1, shard_a);
+
+		// then
+		assert_eq!(removed.iter().map(|tx| tx.hash).collect::<Vec<_>>(), vec![2]);
+		assert_eq!(pool.ready.len(shard_a), 1);
+		assert_eq!(pool.ready.len(shard_b), 1);
+	}
 }
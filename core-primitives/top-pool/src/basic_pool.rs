@@ -31,7 +31,7 @@ use crate::{
 	error::IntoPoolError,
 	pool::{ChainApi, ExtrinsicHash, Options as PoolOptions, Pool},
 	primitives::{
-		ImportNotificationStream, PoolFuture, PoolStatus, TrustedOperationPool,
+		ImportNotificationStream, PersistedOperation, PoolFuture, PoolStatus, TrustedOperationPool,
 		TrustedOperationSource, TxHash,
 	},
 };
@@ -40,6 +40,7 @@ use core::pin::Pin;
 use ita_stf::TrustedOperation as StfTrustedOperation;
 use itc_direct_rpc_server::SendRpcResponse;
 use itp_stf_primitives::types::ShardIdentifier;
+use itp_types::TopPoolLimits;
 use its_primitives::types::BlockHash as SidechainBlockHash;
 use jsonrpc_core::futures::{
 	channel::oneshot,
@@ -222,6 +223,24 @@ where
 		self.pool.validated_pool().status(shard)
 	}
 
+	fn pending_operations(&self) -> Vec<PersistedOperation> {
+		self.pool.validated_pool().pending_operations()
+	}
+
+	fn restore_operations(
+		&self,
+		at: &BlockId<Self::Block>,
+		operations: Vec<PersistedOperation>,
+	) -> PoolFuture<Vec<Result<TxHash<Self>, Self::Error>>, Self::Error> {
+		let pool = self.pool.clone();
+		let at = *at;
+		async move { Ok(pool.restore(&at, operations).await) }.boxed()
+	}
+
+	fn set_limits(&self, limits: &TopPoolLimits) {
+		self.pool.validated_pool().set_limits(limits)
+	}
+
 	fn import_notification_stream(&self) -> ImportNotificationStream<TxHash<Self>> {
 		self.pool.validated_pool().import_notification_stream()
 	}
@@ -234,6 +253,10 @@ where
 		self.pool.hash_of(xt)
 	}
 
+	fn is_imported(&self, hash: &TxHash<Self>, shard: ShardIdentifier) -> bool {
+		self.pool.validated_pool().is_imported(hash, shard)
+	}
+
 	fn ready_transaction(
 		&self,
 		hash: &TxHash<Self>,
@@ -58,6 +58,9 @@ pub enum Error {
 	#[display(fmt = "TrustedOperation couldn't enter the pool because of the limit")]
 	ImmediatelyDropped,
 
+	#[display(fmt = "The sender has reached its limit of trusted operations in the pool")]
+	SenderQuotaExceeded,
+
 	#[from(ignore)]
 	#[display(fmt = "Invalid Block")]
 	InvalidBlockId(String),
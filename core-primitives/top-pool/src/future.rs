@@ -260,6 +260,11 @@ impl<Hash: hash::Hash + Eq + Clone, Ex> FutureTrustedOperations<Hash, Ex> {
 		removed
 	}
 
+	/// Returns an iterator over all shards that have a future queue.
+	pub fn get_shards(&self) -> Box<dyn Iterator<Item = &ShardIdentifier> + '_> {
+		Box::new(self.waiting.keys())
+	}
+
 	/// Fold a list of future operations to compute a single value.
 	pub fn fold<R, F: FnMut(Option<R>, &WaitingTrustedOperations<Hash, Ex>) -> Option<R>>(
 		&mut self,
@@ -80,6 +80,11 @@ where
 		self.watchers.insert(hash, new_watcher);
 	}
 
+	/// Returns true if there is a watcher for the given extrinsic.
+	pub fn is_watched(&self, hash: &H) -> bool {
+		self.watchers.contains_key(hash)
+	}
+
 	/// Notify the listeners about extrinsic broadcast.
 	pub fn broadcasted(&mut self, hash: &H, peers: Vec<String>) {
 		trace!(target: "txpool", "[{:?}] Broadcasted", hash);
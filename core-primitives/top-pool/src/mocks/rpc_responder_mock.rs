@@ -15,21 +15,32 @@
 
 */
 
+#[cfg(feature = "sgx")]
+use std::sync::SgxRwLock as RwLock;
+
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
 use itc_direct_rpc_server::{DirectRpcResult, RpcHash, SendRpcResponse};
 use itp_types::TrustedOperationStatus;
-use std::{marker::PhantomData, vec::Vec};
+use std::vec::Vec;
 
 pub struct RpcResponderMock<Hash> {
-	_hash: PhantomData<Hash>,
+	status_updates: RwLock<Vec<(Hash, TrustedOperationStatus)>>,
 }
 
-impl<Hash> RpcResponderMock<Hash> {
+impl<Hash: Clone> RpcResponderMock<Hash> {
 	pub fn new() -> Self {
-		RpcResponderMock { _hash: PhantomData }
+		RpcResponderMock { status_updates: RwLock::new(Vec::new()) }
+	}
+
+	/// Returns all status updates that were sent, in order.
+	pub fn sent_status_updates(&self) -> Vec<(Hash, TrustedOperationStatus)> {
+		self.status_updates.read().unwrap().clone()
 	}
 }
 
-impl<Hash> Default for RpcResponderMock<Hash> {
+impl<Hash: Clone> Default for RpcResponderMock<Hash> {
 	fn default() -> Self {
 		Self::new()
 	}
@@ -43,13 +54,22 @@ where
 
 	fn update_status_event(
 		&self,
-		_hash: Self::Hash,
-		_status_update: TrustedOperationStatus,
+		hash: Self::Hash,
+		status_update: TrustedOperationStatus,
 	) -> DirectRpcResult<()> {
+		self.status_updates.write().unwrap().push((hash, status_update));
 		Ok(())
 	}
 
 	fn send_state(&self, _hash: Self::Hash, _state_encoded: Vec<u8>) -> DirectRpcResult<()> {
 		Ok(())
 	}
+
+	fn send_subscription_update(
+		&self,
+		_hash: Self::Hash,
+		_update_encoded: Vec<u8>,
+	) -> DirectRpcResult<()> {
+		Ok(())
+	}
 }
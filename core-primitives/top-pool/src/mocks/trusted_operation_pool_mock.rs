@@ -29,14 +29,14 @@ use crate::{
 	base_pool::TrustedOperation,
 	error::Error,
 	primitives::{
-		ImportNotificationStream, PoolFuture, PoolStatus, TrustedOperationPool,
+		ImportNotificationStream, PersistedOperation, PoolFuture, PoolStatus, TrustedOperationPool,
 		TrustedOperationSource, TxHash,
 	},
 };
 use codec::Encode;
 use core::{future::Future, pin::Pin};
 use ita_stf::TrustedOperation as StfTrustedOperation;
-use itp_types::{Block, BlockHash as SidechainBlockHash, ShardIdentifier, H256};
+use itp_types::{Block, BlockHash as SidechainBlockHash, ShardIdentifier, TopPoolLimits, H256};
 use jsonrpc_core::futures::future::ready;
 use sp_runtime::{
 	generic::BlockId,
@@ -171,11 +171,19 @@ impl TrustedOperationPool for TrustedOperationPoolMock {
 
 	fn remove_invalid(
 		&self,
-		_hashes: &[TxHash<Self>],
-		_shard: ShardIdentifier,
+		hashes: &[TxHash<Self>],
+		shard: ShardIdentifier,
 		_inblock: bool,
 	) -> Vec<Arc<Self::InPoolOperation>> {
-		Vec::new()
+		let mut transactions = self.submitted_transactions.write().unwrap();
+		let payload = match transactions.get_mut(&shard) {
+			Some(payload) => payload,
+			None => return Vec::new(),
+		};
+		let (removed, kept): (Vec<_>, Vec<_>) =
+			payload.xts.drain(..).partition(|top| hashes.contains(&hash_of_top(top)));
+		payload.xts = kept;
+		removed.iter().map(Self::map_stf_top_to_tx).collect()
 	}
 
 	fn status(&self, shard: ShardIdentifier) -> PoolStatus {
@@ -191,18 +199,67 @@ impl TrustedOperationPool for TrustedOperationPoolMock {
 			.unwrap_or_else(default_pool_status)
 	}
 
-	fn import_notification_stream(&self) -> ImportNotificationStream<TxHash<Self>> {
-		unimplemented!()
+	fn pending_operations(&self) -> Vec<PersistedOperation> {
+		let transactions = self.submitted_transactions.read().unwrap();
+		transactions
+			.values()
+			.flat_map(|payload| {
+				payload.xts.iter().map(|xt| PersistedOperation {
+					shard: payload.shard,
+					operation: xt.clone(),
+					watched: false,
+				})
+			})
+			.collect()
 	}
 
-	fn on_broadcasted(&self, _propagations: HashMap<TxHash<Self>, Vec<String>>) {
+	fn restore_operations(
+		&self,
+		at: &BlockId<Self::Block>,
+		operations: Vec<PersistedOperation>,
+	) -> PoolFuture<Vec<Result<TxHash<Self>, Self::Error>>, Self::Error> {
+		let mut transactions = self.submitted_transactions.write().unwrap();
+		let top_hashes = operations
+			.into_iter()
+			.map(|PersistedOperation { shard, operation, .. }| {
+				let top_hash = hash_of_top(&operation);
+				transactions
+					.entry(shard)
+					.or_insert_with(|| TxPayload {
+						block_id: *at,
+						source: TrustedOperationSource::External,
+						xts: vec![],
+						shard,
+					})
+					.xts
+					.push(operation);
+				Ok(top_hash)
+			})
+			.collect();
+
+		Box::pin(ready(Ok(top_hashes)))
+	}
+
+	fn set_limits(&self, _limits: &TopPoolLimits) {}
+
+	fn import_notification_stream(&self) -> ImportNotificationStream<TxHash<Self>> {
 		unimplemented!()
 	}
 
+	fn on_broadcasted(&self, _propagations: HashMap<TxHash<Self>, Vec<String>>) {}
+
 	fn hash_of(&self, xt: &StfTrustedOperation) -> TxHash<Self> {
 		hash_of_top(xt)
 	}
 
+	fn is_imported(&self, hash: &TxHash<Self>, shard: ShardIdentifier) -> bool {
+		self.submitted_transactions
+			.read()
+			.unwrap()
+			.get(&shard)
+			.map_or(false, |payload| payload.xts.iter().any(|top| hash_of_top(top) == *hash))
+	}
+
 	fn ready_transaction(
 		&self,
 		_hash: &TxHash<Self>,
@@ -21,14 +21,14 @@ use crate::sgx_reexport_prelude::*;
 
 use crate::{
 	base_pool as base, error,
-	primitives::TrustedOperationSource,
+	primitives::{PersistedOperation, TrustedOperationSource},
 	validated_pool::{ValidatedOperation, ValidatedPool},
 };
 use core::matches;
 use ita_stf::TrustedOperation as StfTrustedOperation;
 use itc_direct_rpc_server::SendRpcResponse;
 use itp_stf_primitives::types::ShardIdentifier;
-use itp_types::BlockHash as SidechainBlockHash;
+use itp_types::{BlockHash as SidechainBlockHash, TopPoolLimits};
 use jsonrpc_core::futures::{channel::mpsc::Receiver, future, Future};
 use sp_runtime::{
 	generic::BlockId,
@@ -98,21 +98,47 @@ pub trait ChainApi: Send + Sync {
 /// Pool configuration options.
 #[derive(Debug, Clone)]
 pub struct Options {
-	/// Ready queue limits.
+	/// Ready queue limits, across all shards.
 	pub ready: base::Limit,
-	/// Future queue limits.
+	/// Future queue limits, across all shards.
 	pub future: base::Limit,
+	/// Maximum number of operations (ready and future) of a single shard.
+	pub max_per_shard: usize,
+	/// Maximum number of operations (ready and future) of a single sender within a shard.
+	pub max_per_sender: usize,
 	/// Reject future operations.
 	pub reject_future_operations: bool,
 }
 
+impl Options {
+	/// Returns the options with the limits replaced by the given ones.
+	pub fn with_limits(self, limits: &TopPoolLimits) -> Self {
+		Options {
+			ready: base::Limit {
+				count: limits.max_ready as usize,
+				total_bytes: limits.max_ready_bytes as usize,
+			},
+			future: base::Limit {
+				count: limits.max_future as usize,
+				total_bytes: limits.max_future_bytes as usize,
+			},
+			max_per_shard: limits.max_per_shard as usize,
+			max_per_sender: limits.max_per_sender as usize,
+			..self
+		}
+	}
+}
+
 impl Default for Options {
 	fn default() -> Self {
 		Options {
-			ready: base::Limit { count: 8192, total_bytes: 20 * 1024 * 1024 },
-			future: base::Limit { count: 512, total_bytes: 1024 * 1024 },
+			ready: base::Limit { count: 0, total_bytes: 0 },
+			future: base::Limit { count: 0, total_bytes: 0 },
+			max_per_shard: 0,
+			max_per_sender: 0,
 			reject_future_operations: false,
 		}
+		.with_limits(&TopPoolLimits::default())
 	}
 }
 
@@ -203,6 +229,40 @@ where
 		self.validated_pool.submit_and_watch(tx, shard)
 	}
 
+	/// Re-import operations that were persisted before the pool was restarted.
+	///
+	/// Every operation is validated again against the current state. Watchers of operations
+	/// that are no longer valid are notified with `Invalid`.
+	pub async fn restore(
+		&self,
+		at: &BlockId<B::Block>,
+		operations: Vec<PersistedOperation>,
+	) -> Vec<Result<ExtrinsicHash<B>, B::Error>> {
+		let mut results = Vec::with_capacity(operations.len());
+		for PersistedOperation { shard, operation, watched } in operations {
+			let hash = self.hash_of(&operation);
+			let result = if watched {
+				self.submit_and_watch(at, TrustedOperationSource::External, operation, shard)
+					.await
+			} else {
+				self.submit_one(at, TrustedOperationSource::External, operation, shard).await
+			};
+			let result = match result.map_err(error::IntoPoolError::into_pool_error) {
+				Ok(hash) => Ok(hash),
+				Err(Ok(error::Error::AlreadyImported)) => Err(error::Error::AlreadyImported.into()),
+				Err(e) => {
+					log::debug!(target: "txpool", "[{:?}] Dropping restored operation", hash);
+					if watched {
+						self.validated_pool.report_invalid(&hash);
+					}
+					Err(e.map_or_else(|e| e, Into::into))
+				},
+			};
+			results.push(result);
+		}
+		results
+	}
+
 	/// Resubmit some operation that were validated elsewhere.
 	pub fn resubmit(
 		&self,
@@ -479,7 +539,7 @@ pub mod tests {
 	};
 	use codec::{Decode, Encode};
 	use ita_stf::{Index, TrustedCall, TrustedCallSigned, TrustedOperation};
-	use itp_types::Header;
+	use itp_types::{Header, TrustedOperationStatus};
 	use jsonrpc_core::{futures, futures::executor::block_on};
 	use parity_util_mem::MallocSizeOf;
 	use serde::Serialize;
@@ -859,6 +919,80 @@ pub mod tests {
 		assert!(pool.validated_pool.rotator().is_banned(&hash1));
 	}
 
+	#[test]
+	pub fn test_should_keep_dependent_operations_when_removing_included() {
+		// given
+		let pool = test_pool();
+		let shard = ShardIdentifier::default();
+		let submit = |nonce| {
+			block_on(pool.submit_one(
+				&BlockId::Number(0),
+				SOURCE,
+				to_top(
+					TrustedCall::balance_transfer(
+						tests::AccountId::from_h256(from_low_u64_to_be_h256(1)).into(),
+						tests::AccountId::from_h256(from_low_u64_to_be_h256(2)).into(),
+						5,
+					),
+					nonce,
+				),
+				shard,
+			))
+			.unwrap()
+		};
+		let hash0 = submit(0);
+		let hash1 = submit(1);
+		let _hash3 = submit(3);
+		assert_eq!(pool.validated_pool().status(shard).ready, 2);
+		assert_eq!(pool.validated_pool().status(shard).future, 1);
+
+		// when
+		let removed = pool.validated_pool().remove_invalid(&[hash0], shard, true);
+
+		// then
+		assert_eq!(removed.iter().map(|tx| tx.hash).collect::<Vec<_>>(), vec![hash0]);
+		assert_eq!(
+			pool.validated_pool().ready(shard).map(|tx| tx.hash).collect::<Vec<_>>(),
+			vec![hash1]
+		);
+		assert_eq!(pool.validated_pool().status(shard).future, 1);
+		assert!(!pool.validated_pool.rotator().is_banned(&hash0));
+	}
+
+	#[test]
+	pub fn test_should_remove_dependent_operations_when_removing_invalid() {
+		// given
+		let pool = test_pool();
+		let shard = ShardIdentifier::default();
+		let submit = |nonce| {
+			block_on(pool.submit_one(
+				&BlockId::Number(0),
+				SOURCE,
+				to_top(
+					TrustedCall::balance_transfer(
+						tests::AccountId::from_h256(from_low_u64_to_be_h256(1)).into(),
+						tests::AccountId::from_h256(from_low_u64_to_be_h256(2)).into(),
+						5,
+					),
+					nonce,
+				),
+				shard,
+			))
+			.unwrap()
+		};
+		let hash0 = submit(0);
+		let hash1 = submit(1);
+
+		// when
+		let removed = pool.validated_pool().remove_invalid(&[hash0], shard, false);
+
+		// then
+		let removed_hashes: HashSet<_> = removed.iter().map(|tx| tx.hash).collect();
+		assert_eq!(removed_hashes, HashSet::from([hash0, hash1]));
+		assert_eq!(pool.validated_pool().status(shard).ready, 0);
+		assert!(pool.validated_pool.rotator().is_banned(&hash0));
+	}
+
 	#[test]
 	#[ignore] // flaky, fails sometimes
 	pub fn test_should_limit_futures() {
@@ -968,4 +1102,97 @@ pub mod tests {
 		assert_eq!(pool.validated_pool().status(shard).future, 0);
 		assert!(matches!(err, error::Error::NoTagsProvided));
 	}
+
+	#[test]
+	pub fn test_should_reject_operations_exceeding_the_sender_quota() {
+		// given
+		let pool = test_pool();
+		let shard = ShardIdentifier::default();
+		pool.validated_pool()
+			.set_limits(&TopPoolLimits { max_per_sender: 1, ..Default::default() });
+		let transfer = |from: u64, nonce: Index| {
+			to_top(
+				TrustedCall::balance_transfer(
+					tests::AccountId::from_h256(from_low_u64_to_be_h256(from)).into(),
+					tests::AccountId::from_h256(from_low_u64_to_be_h256(3)).into(),
+					5,
+				),
+				nonce,
+			)
+		};
+		block_on(pool.submit_one(&BlockId::Number(0), SOURCE, transfer(1, 0), shard)).unwrap();
+
+		// when
+		let err = block_on(pool.submit_one(&BlockId::Number(0), SOURCE, transfer(1, 1), shard))
+			.unwrap_err();
+		block_on(pool.submit_one(&BlockId::Number(0), SOURCE, transfer(2, 2), shard)).unwrap();
+
+		// then
+		assert!(matches!(err, error::Error::SenderQuotaExceeded));
+		assert_eq!(pool.validated_pool().status(shard).ready, 1);
+		assert_eq!(pool.validated_pool().status(shard).future, 1);
+	}
+
+	#[test]
+	pub fn test_should_restore_pending_operations() {
+		// given
+		let pool = test_pool();
+		let shard = ShardIdentifier::default();
+		let transfer = |nonce: Index| {
+			to_top(
+				TrustedCall::balance_transfer(
+					tests::AccountId::from_h256(from_low_u64_to_be_h256(1)).into(),
+					tests::AccountId::from_h256(from_low_u64_to_be_h256(2)).into(),
+					5,
+				),
+				nonce,
+			)
+		};
+		block_on(pool.submit_one(&BlockId::Number(0), SOURCE, transfer(0), shard)).unwrap();
+		block_on(pool.submit_and_watch(&BlockId::Number(0), SOURCE, transfer(2), shard)).unwrap();
+		let pending = pool.validated_pool().pending_operations();
+		let restored_pool = test_pool();
+
+		// when
+		let results = block_on(restored_pool.restore(&BlockId::Number(0), pending.clone()));
+
+		// then
+		assert_eq!(pending.len(), 2);
+		assert_eq!(pending.iter().filter(|op| op.watched).count(), 1);
+		assert!(results.iter().all(|result| result.is_ok()));
+		assert_eq!(restored_pool.validated_pool().status(shard).ready, 1);
+		assert_eq!(restored_pool.validated_pool().status(shard).future, 1);
+		assert_eq!(restored_pool.validated_pool().pending_operations(), pending);
+	}
+
+	#[test]
+	pub fn test_should_report_invalid_restored_operations_to_watchers() {
+		// given
+		let rpc_responder = Arc::new(TestRpcResponder::new());
+		let pool = Pool::new(Default::default(), TestApi::default().into(), rpc_responder.clone());
+		let shard = ShardIdentifier::default();
+		let operation = to_top(
+			TrustedCall::balance_transfer(
+				tests::AccountId::from_h256(from_low_u64_to_be_h256(1)).into(),
+				tests::AccountId::from_h256(from_low_u64_to_be_h256(2)).into(),
+				5,
+			),
+			INVALID_NONCE + 1,
+		);
+		let hash = pool.hash_of(&operation);
+
+		// when
+		let results = block_on(pool.restore(
+			&BlockId::Number(0),
+			vec![PersistedOperation { shard, operation, watched: true }],
+		));
+
+		// then
+		assert!(results[0].is_err());
+		assert_eq!(pool.validated_pool().status(shard).ready, 0);
+		assert_eq!(pool.validated_pool().status(shard).future, 0);
+		assert!(rpc_responder
+			.sent_status_updates()
+			.contains(&(hash, TrustedOperationStatus::Invalid)));
+	}
 }
@@ -11,7 +11,7 @@ use codec::{Decode, Encode};
 use core::{hash::Hash, pin::Pin};
 use ita_stf::TrustedOperation as StfTrustedOperation;
 use itp_stf_primitives::types::ShardIdentifier;
-use itp_types::BlockHash as SidechainBlockHash;
+use itp_types::{BlockHash as SidechainBlockHash, TopPoolLimits};
 use jsonrpc_core::futures::{channel::mpsc::Receiver, Future, Stream};
 use sp_core::H256;
 use sp_runtime::{
@@ -41,6 +41,17 @@ impl PoolStatus {
 	}
 }
 
+/// Pending operation of the pool, as it is persisted across restarts.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+pub struct PersistedOperation {
+	/// Shard the operation was submitted to.
+	pub shard: ShardIdentifier,
+	/// The operation itself.
+	pub operation: StfTrustedOperation,
+	/// Whether a client was watching the operation.
+	pub watched: bool,
+}
+
 /// Possible operation status events.
 ///
 /// This events are being emitted by `TrustedOperationPool` watchers,
@@ -229,6 +240,8 @@ pub trait TrustedOperationPool: Send + Sync {
 
 	// *** Block production
 	/// Remove operations identified by given hashes (and dependent operations) from the pool.
+	///
+	/// Dependent operations are kept if the removed operations were included in a block (`inblock`).
 	fn remove_invalid(
 		&self,
 		hashes: &[TxHash<Self>],
@@ -240,6 +253,21 @@ pub trait TrustedOperationPool: Send + Sync {
 	/// Returns pool status.
 	fn status(&self, shard: ShardIdentifier) -> PoolStatus;
 
+	// *** persistence
+	/// Returns the ready and future operations of all shards.
+	fn pending_operations(&self) -> Vec<PersistedOperation>;
+
+	/// Re-import persisted operations, validating them against the current state.
+	fn restore_operations(
+		&self,
+		at: &BlockId<Self::Block>,
+		operations: Vec<PersistedOperation>,
+	) -> PoolFuture<Vec<Result<TxHash<Self>, Self::Error>>, Self::Error>;
+
+	// *** configuration
+	/// Replace the limits of the pool. Operations exceeding them are dropped on the next import.
+	fn set_limits(&self, limits: &TopPoolLimits);
+
 	// *** logging / RPC / networking
 	/// Return an event stream of operations imported to the pool.
 	fn import_notification_stream(&self) -> ImportNotificationStream<TxHash<Self>>;
@@ -251,6 +279,9 @@ pub trait TrustedOperationPool: Send + Sync {
 	/// Returns operation hash
 	fn hash_of(&self, xt: &StfTrustedOperation) -> TxHash<Self>;
 
+	/// Whether the operation is in the pool, be it ready or future.
+	fn is_imported(&self, hash: &TxHash<Self>, shard: ShardIdentifier) -> bool;
+
 	/// Return specific ready operation by hash, if there is one.
 	fn ready_transaction(
 		&self,
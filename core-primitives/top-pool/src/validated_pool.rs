@@ -35,7 +35,7 @@ use crate::{
 	error,
 	listener::Listener,
 	pool::{ChainApi, EventStream, ExtrinsicHash, Options, TransactionFor},
-	primitives::{PoolStatus, TrustedOperationSource},
+	primitives::{PersistedOperation, PoolStatus, TrustedOperationSource},
 	rotator::PoolRotator,
 };
 use codec::Encode;
@@ -43,7 +43,7 @@ use core::{hash, result::Result};
 use ita_stf::TrustedOperation as StfTrustedOperation;
 use itc_direct_rpc_server::SendRpcResponse;
 use itp_stf_primitives::types::ShardIdentifier;
-use itp_types::BlockHash as SidechainBlockHash;
+use itp_types::{BlockHash as SidechainBlockHash, TopPoolLimits};
 use jsonrpc_core::futures::channel::mpsc::{channel, Sender};
 use sp_runtime::{
 	generic::BlockId,
@@ -107,7 +107,7 @@ where
 	R: SendRpcResponse<Hash = ExtrinsicHash<B>>,
 {
 	api: Arc<B>,
-	options: Options,
+	options: RwLock<Options>,
 	listener: RwLock<Listener<ExtrinsicHash<B>, R>>,
 	pool: RwLock<base::BasePool<ExtrinsicHash<B>, StfTrustedOperation>>,
 	import_notification_sinks: Mutex<Vec<Sender<ExtrinsicHash<B>>>>,
@@ -122,7 +122,7 @@ where
 	pub fn new(options: Options, api: Arc<B>, rpc_response_sender: Arc<R>) -> Self {
 		let base_pool = base::BasePool::new(options.reject_future_operations);
 		ValidatedPool {
-			options,
+			options: RwLock::new(options),
 			listener: RwLock::new(Listener::new(rpc_response_sender)),
 			api,
 			pool: RwLock::new(base_pool),
@@ -131,6 +131,12 @@ where
 		}
 	}
 
+	/// Replaces the pool limits, they are enforced on the next import.
+	pub fn set_limits(&self, limits: &TopPoolLimits) {
+		let mut options = self.options.write().unwrap();
+		*options = options.clone().with_limits(limits);
+	}
+
 	/// Bans given set of hashes.
 	pub fn ban(&self, now: &Instant, hashes: impl IntoIterator<Item = ExtrinsicHash<B>>) {
 		self.rotator.ban(now, hashes)
@@ -197,8 +203,25 @@ where
 	) -> Result<ExtrinsicHash<B>, B::Error> {
 		match tx {
 			ValidatedOperation::Valid(tx) => {
-				let imported =
-					self.pool.write().map_err(|_| error::Error::UnlockError)?.import(tx, shard)?;
+				let max_per_sender =
+					self.options.read().map_err(|_| error::Error::UnlockError)?.max_per_sender;
+				let imported = {
+					let mut pool = self.pool.write().map_err(|_| error::Error::UnlockError)?;
+					if let Some(sender) = tx.data.signed_caller_account() {
+						// operations replaced by this one do not count towards the quota
+						let is_counted = |op: &base::TrustedOperation<_, StfTrustedOperation>| {
+							op.data.signed_caller_account() == Some(sender)
+								&& !op.provides.iter().any(|tag| tx.provides.contains(tag))
+						};
+						let sender_operations =
+							pool.ready(shard).filter(|op| is_counted(op)).count()
+								+ pool.futures(shard).filter(|op| is_counted(op)).count();
+						if sender_operations >= max_per_sender {
+							return Err(error::Error::SenderQuotaExceeded.into())
+						}
+					}
+					pool.import(tx, shard)?
+				};
 
 				if let base::Imported::Ready { ref hash, .. } = imported {
 					self.import_notification_sinks
@@ -232,26 +255,32 @@ where
 	}
 
 	fn enforce_limits(&self, shard: ShardIdentifier) -> HashSet<ExtrinsicHash<B>> {
-		let status = self.pool.read().unwrap().status(shard);
-		let ready_limit = &self.options.ready;
-		let future_limit = &self.options.future;
+		let (status, shard_status) = {
+			let pool = self.pool.read().unwrap();
+			(pool.total_status(), pool.status(shard))
+		};
+		let options = self.options.read().unwrap().clone();
+		let ready_limit = &options.ready;
+		let future_limit = &options.future;
 
-		log::debug!(target: "txpool", "Pool Status: {:?}", status);
+		log::debug!(target: "txpool", "Pool Status: {:?}, shard status: {:?}", status, shard_status);
 		if ready_limit.is_exceeded(status.ready, status.ready_bytes)
 			|| future_limit.is_exceeded(status.future, status.future_bytes)
+			|| shard_status.ready + shard_status.future > options.max_per_shard
 		{
 			log::debug!(
 				target: "txpool",
-				"Enforcing limits ({}/{}kB ready, {}/{}kB future",
+				"Enforcing limits ({}/{}kB ready, {}/{}kB future, {} per shard)",
 				ready_limit.count, ready_limit.total_bytes / 1024,
 				future_limit.count, future_limit.total_bytes / 1024,
+				options.max_per_shard,
 			);
 
 			// clean up the pool
 			let removed = {
 				let mut pool = self.pool.write().unwrap();
 				let removed = pool
-					.enforce_limits(ready_limit, future_limit, shard)
+					.enforce_limits(ready_limit, future_limit, options.max_per_shard, shard)
 					.into_iter()
 					.map(|x| x.hash)
 					.collect::<HashSet<_>>();
@@ -451,6 +480,11 @@ where
 			.collect()
 	}
 
+	/// Whether the operation is in the pool, be it ready or future.
+	pub fn is_imported(&self, hash: &ExtrinsicHash<B>, shard: ShardIdentifier) -> bool {
+		self.pool.read().unwrap().is_imported(hash, shard)
+	}
+
 	/// Get ready operation by hash
 	pub fn ready_by_hash(
 		&self,
@@ -619,6 +653,9 @@ where
 	/// to prevent them from entering the pool right away.
 	/// Note this is not the case for the dependent operations - those may
 	/// still be valid so we want to be able to re-import them.
+	///
+	/// Operations that were included in a block (`inblock`) are pruned instead,
+	/// see `prune_included`.
 	pub fn remove_invalid(
 		&self,
 		hashes: &[ExtrinsicHash<B>],
@@ -630,31 +667,87 @@ where
 			return vec![]
 		}
 
+		if inblock {
+			return self.prune_included(hashes, shard)
+		}
+
 		let invalid = self.pool.write().unwrap().remove_subtree(hashes, shard);
 
 		log::debug!(target: "txpool", "Removed invalid operations: {:?}", invalid);
 
+		// temporarily ban invalid operations
+		self.rotator.ban(&Instant::now(), hashes.iter().cloned());
 		let mut listener = self.listener.write().unwrap();
-		if inblock {
-			for _tx in &invalid {
-				//listener.in_block(&tx.hash);
-			}
-		} else {
-			// temporarily ban invalid operations
-			self.rotator.ban(&Instant::now(), hashes.iter().cloned());
-			for tx in &invalid {
-				listener.invalid(&tx.hash);
-			}
+		for tx in &invalid {
+			listener.invalid(&tx.hash);
 		}
 
 		invalid
 	}
 
+	/// Remove operations that were included in a block by pruning the tags they provide.
+	///
+	/// Unlike `remove_subtree`, this keeps the operations depending on them (e.g. calls with
+	/// a subsequent nonce) and promotes those that now have all requirements satisfied.
+	fn prune_included(
+		&self,
+		hashes: &[ExtrinsicHash<B>],
+		shard: ShardIdentifier,
+	) -> Vec<TransactionFor<B>> {
+		let tags = self.extrinsics_tags(hashes, shard).into_iter().flatten().flatten();
+		let mut included = match self.prune_tags(tags, shard) {
+			Ok(status) => status.pruned,
+			Err(_) => vec![],
+		};
+
+		// Operations still in the future queue are not touched by tag pruning.
+		let remaining: Vec<_> = hashes
+			.iter()
+			.filter(|hash| !included.iter().any(|tx| &tx.hash == *hash))
+			.cloned()
+			.collect();
+		if !remaining.is_empty() {
+			included.extend(self.pool.write().unwrap().remove_subtree(&remaining, shard));
+		}
+
+		log::debug!(target: "txpool", "Removed included operations: {:?}", included);
+
+		included
+	}
+
 	/// Get an iterator for ready operations ordered by priority
 	pub fn ready(&self, shard: ShardIdentifier) -> impl Iterator<Item = TransactionFor<B>> + Send {
 		self.pool.read().unwrap().ready(shard)
 	}
 
+	/// Returns the ready and future operations of all shards, ready ones first.
+	pub fn pending_operations(&self) -> Vec<PersistedOperation> {
+		let pool = self.pool.read().unwrap();
+		let listener = self.listener.read().unwrap();
+		let mut operations = vec![];
+		for shard in pool.all_shards() {
+			let ready = pool.ready(shard).map(|tx| (*tx).clone());
+			let future = pool.futures(shard).cloned();
+			operations.extend(ready.chain(future).map(|tx| PersistedOperation {
+				shard,
+				watched: listener.is_watched(&tx.hash),
+				operation: tx.data,
+			}));
+		}
+		operations
+	}
+
+	/// Notifies the watcher of an operation that is not part of the pool that it is invalid.
+	///
+	/// Creates the watcher first, for operations that were watched before the pool was restored.
+	pub fn report_invalid(&self, hash: &ExtrinsicHash<B>) {
+		let mut listener = self.listener.write().unwrap();
+		if !listener.is_watched(hash) {
+			listener.create_watcher(*hash);
+		}
+		listener.invalid(hash);
+	}
+
 	/// Get an iterator for all shards
 	pub fn shards(&self) -> Vec<ShardIdentifier> {
 		let mut shards = vec![];
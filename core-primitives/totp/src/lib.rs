@@ -0,0 +1,63 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Time-based one-time passwords (TOTP, RFC 6238) as second authentication factor.
+//!
+//! The per-user TOTP secrets are generated inside the enclave and sealed to disk. Apart from
+//! the provisioning URI that is handed out once on enrollment, they never leave the enclave.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(all(feature = "std", feature = "sgx"))]
+compile_error!("feature \"std\" and feature \"sgx\" cannot be enabled at the same time");
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+extern crate sgx_tstd as std;
+
+// re-export module to properly feature gate sgx and regular std environment
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+pub mod sgx_reexport_prelude {
+	pub use thiserror_sgx as thiserror;
+}
+
+pub mod error;
+pub mod store;
+pub mod totp;
+
+#[cfg(feature = "sgx")]
+pub mod sealing;
+
+#[cfg(any(test, feature = "mocks"))]
+pub mod mocks;
+
+pub use error::{Error, Result};
+pub use store::{TotpEnrollments, TotpSecretStore};
+pub use totp::{provisioning_uri, TotpSecret};
+
+/// Enroll users for TOTP.
+pub trait EnrollTotp {
+	/// Enroll a user with a new secret, replacing any previous enrollment.
+	fn enroll(&self, username: &str, secret: TotpSecret) -> Result<()>;
+
+	fn is_enrolled(&self, username: &str) -> Result<bool>;
+}
+
+/// Verify TOTP codes.
+pub trait VerifyTotp {
+	/// Verify a code for the given unix time (in seconds). Every code is only accepted once.
+	fn verify_code(&self, username: &str, code: &str, unix_time_secs: u64) -> Result<bool>;
+}
@@ -0,0 +1,49 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+#[cfg(feature = "sgx")]
+use std::sync::SgxRwLock as RwLock;
+
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+use crate::{
+	error::{Error, Result},
+	store::TotpEnrollments,
+};
+use itp_sgx_io::SealedIO;
+
+/// Sealing mock that keeps the TOTP secrets in memory.
+#[derive(Default)]
+pub struct TotpSecretsSealMock {
+	sealed: RwLock<TotpEnrollments>,
+}
+
+impl SealedIO for TotpSecretsSealMock {
+	type Error = Error;
+	type Unsealed = TotpEnrollments;
+
+	fn unseal(&self) -> Result<Self::Unsealed> {
+		self.sealed.read().map_err(|_| Error::LockPoisoning).map(|l| l.clone())
+	}
+
+	fn seal(&self, unsealed: &Self::Unsealed) -> Result<()> {
+		let mut sealed_lock = self.sealed.write().map_err(|_| Error::LockPoisoning)?;
+		*sealed_lock = unsealed.clone();
+		Ok(())
+	}
+}
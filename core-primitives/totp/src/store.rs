@@ -0,0 +1,201 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+#[cfg(feature = "sgx")]
+use std::sync::SgxRwLock as RwLock;
+
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+use crate::{
+	error::{Error, Result},
+	totp::{time_step, TotpSecret, TOTP_ALLOWED_DRIFT_STEPS, TOTP_DIGITS},
+	EnrollTotp, VerifyTotp,
+};
+use itp_sgx_io::SealedIO;
+use log::*;
+use std::{collections::BTreeMap, string::String, sync::Arc};
+
+/// TOTP secrets of all enrolled users, by username.
+pub type TotpEnrollments = BTreeMap<String, TotpSecret>;
+
+/// Stores the TOTP secrets in-memory and sealed to a file, using the SealedIO trait.
+pub struct TotpSecretStore<SealedIo> {
+	enrollments_lock: RwLock<TotpEnrollments>,
+	/// Last time step a code was accepted for, per user. Prevents replay of a code.
+	last_used_steps_lock: RwLock<BTreeMap<String, u64>>,
+	sealed_io: Arc<SealedIo>,
+}
+
+impl<SealedIo> TotpSecretStore<SealedIo>
+where
+	SealedIo: SealedIO<Unsealed = TotpEnrollments, Error = Error>,
+{
+	pub fn new(enrollments: TotpEnrollments, sealed_io: Arc<SealedIo>) -> Self {
+		TotpSecretStore {
+			enrollments_lock: RwLock::new(enrollments),
+			last_used_steps_lock: RwLock::new(BTreeMap::new()),
+			sealed_io,
+		}
+	}
+
+	/// Load the store from the sealed file.
+	pub fn load(sealed_io: Arc<SealedIo>) -> Result<Self> {
+		let enrollments = sealed_io.unseal()?;
+		Ok(Self::new(enrollments, sealed_io))
+	}
+}
+
+impl<SealedIo> EnrollTotp for TotpSecretStore<SealedIo>
+where
+	SealedIo: SealedIO<Unsealed = TotpEnrollments, Error = Error>,
+{
+	fn enroll(&self, username: &str, secret: TotpSecret) -> Result<()> {
+		let mut enrollments_lock =
+			self.enrollments_lock.write().map_err(|_| Error::LockPoisoning)?;
+
+		// Seal first, so the in-memory store never diverges from the sealed one.
+		let mut updated_enrollments = enrollments_lock.clone();
+		updated_enrollments.insert(username.into(), secret);
+		self.sealed_io.seal(&updated_enrollments)?;
+		*enrollments_lock = updated_enrollments;
+
+		self.last_used_steps_lock
+			.write()
+			.map_err(|_| Error::LockPoisoning)?
+			.remove(username);
+
+		debug!("Enrolled a user for TOTP");
+		Ok(())
+	}
+
+	fn is_enrolled(&self, username: &str) -> Result<bool> {
+		let enrollments_lock = self.enrollments_lock.read().map_err(|_| Error::LockPoisoning)?;
+		Ok(enrollments_lock.contains_key(username))
+	}
+}
+
+impl<SealedIo> VerifyTotp for TotpSecretStore<SealedIo>
+where
+	SealedIo: SealedIO<Unsealed = TotpEnrollments, Error = Error>,
+{
+	fn verify_code(&self, username: &str, code: &str, unix_time_secs: u64) -> Result<bool> {
+		let secret = self
+			.enrollments_lock
+			.read()
+			.map_err(|_| Error::LockPoisoning)?
+			.get(username)
+			.cloned()
+			.ok_or(Error::NotEnrolled)?;
+
+		if code.len() != TOTP_DIGITS as usize || !code.bytes().all(|b| b.is_ascii_digit()) {
+			return Ok(false)
+		}
+		let code: u32 = match code.parse() {
+			Ok(c) => c,
+			Err(_) => return Ok(false),
+		};
+
+		let mut last_used_steps_lock =
+			self.last_used_steps_lock.write().map_err(|_| Error::LockPoisoning)?;
+		let last_used_step = last_used_steps_lock.get(username).copied();
+
+		let current_step = time_step(unix_time_secs);
+		let mut first_step = current_step.saturating_sub(TOTP_ALLOWED_DRIFT_STEPS);
+		if let Some(last_used_step) = last_used_step {
+			first_step = first_step.max(last_used_step + 1);
+		}
+		let matching_step = (first_step..=current_step + TOTP_ALLOWED_DRIFT_STEPS)
+			.find(|step| secret.code_at_time_step(*step, TOTP_DIGITS) == code);
+
+		match matching_step {
+			Some(step) => {
+				last_used_steps_lock.insert(username.into(), step);
+				Ok(true)
+			},
+			None => Ok(false),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mocks::TotpSecretsSealMock;
+
+	type TestStore = TotpSecretStore<TotpSecretsSealMock>;
+
+	const NOW: u64 = 1_700_000_000;
+
+	#[test]
+	fn enrolled_user_can_verify_current_code() {
+		let seal_mock = Arc::new(TotpSecretsSealMock::default());
+		let store = TestStore::load(seal_mock.clone()).unwrap();
+
+		store.enroll("alice", test_secret()).unwrap();
+
+		assert!(store.is_enrolled("alice").unwrap());
+		assert!(!store.is_enrolled("bob").unwrap());
+		assert!(store.verify_code("alice", &test_secret().code_at_time(NOW), NOW).unwrap());
+		assert_eq!(1, seal_mock.unseal().unwrap().len());
+	}
+
+	#[test]
+	fn codes_of_adjacent_time_steps_are_accepted() {
+		let store = TestStore::load(Arc::new(Default::default())).unwrap();
+		store.enroll("alice", test_secret()).unwrap();
+
+		let previous_code = test_secret().code_at_time(NOW - 30);
+		let next_code = test_secret().code_at_time(NOW + 30);
+
+		assert!(store.verify_code("alice", &previous_code, NOW).unwrap());
+		assert!(store.verify_code("alice", &next_code, NOW).unwrap());
+	}
+
+	#[test]
+	fn outdated_or_wrong_codes_are_rejected() {
+		let store = TestStore::load(Arc::new(Default::default())).unwrap();
+		store.enroll("alice", test_secret()).unwrap();
+
+		let outdated_code = test_secret().code_at_time(NOW - 90);
+
+		assert!(!store.verify_code("alice", &outdated_code, NOW).unwrap());
+		assert!(!store.verify_code("alice", "12345", NOW).unwrap());
+		assert!(!store.verify_code("alice", "abcdef", NOW).unwrap());
+	}
+
+	#[test]
+	fn code_cannot_be_replayed() {
+		let store = TestStore::load(Arc::new(Default::default())).unwrap();
+		store.enroll("alice", test_secret()).unwrap();
+		let code = test_secret().code_at_time(NOW);
+
+		assert!(store.verify_code("alice", &code, NOW).unwrap());
+		assert!(!store.verify_code("alice", &code, NOW).unwrap());
+	}
+
+	#[test]
+	fn verifying_without_enrollment_fails() {
+		let store = TestStore::load(Arc::new(Default::default())).unwrap();
+
+		assert!(matches!(store.verify_code("alice", "123456", NOW), Err(Error::NotEnrolled)));
+	}
+
+	fn test_secret() -> TotpSecret {
+		TotpSecret::new(b"12345678901234567890".to_vec()).unwrap()
+	}
+}
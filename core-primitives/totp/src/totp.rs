@@ -0,0 +1,169 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! TOTP code generation as specified in RFC 6238 (HMAC-SHA1, 6 digits, 30 second steps).
+
+use crate::error::{Error, Result};
+use codec::{Decode, Encode};
+use core::fmt;
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use std::{format, string::String, vec::Vec};
+
+/// Number of digits of a TOTP code.
+pub const TOTP_DIGITS: u32 = 6;
+/// Validity period of a single TOTP code.
+pub const TOTP_PERIOD_SECS: u64 = 30;
+/// Length of a TOTP secret (160 bits, as recommended by RFC 4226).
+pub const TOTP_SECRET_LENGTH: usize = 20;
+/// Number of time steps a code may be off, to compensate for clock drift.
+pub const TOTP_ALLOWED_DRIFT_STEPS: u64 = 1;
+
+/// Shared secret of a TOTP enrollment.
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+pub struct TotpSecret(Vec<u8>);
+
+impl TotpSecret {
+	pub fn new(secret: Vec<u8>) -> Result<Self> {
+		if secret.len() < TOTP_SECRET_LENGTH {
+			return Err(Error::InvalidSecretLength(secret.len()))
+		}
+		Ok(TotpSecret(secret))
+	}
+
+	/// Base32 representation, as expected by authenticator apps.
+	pub fn to_base32(&self) -> String {
+		BASE32_NOPAD.encode(&self.0)
+	}
+
+	pub fn code_at_time_step(&self, time_step: u64, digits: u32) -> u32 {
+		// HMAC accepts keys of any length, this cannot fail.
+		let mut mac =
+			Hmac::<Sha1>::new_from_slice(&self.0).expect("HMAC can take a key of any size");
+		mac.update(&time_step.to_be_bytes());
+		let hash = mac.finalize().into_bytes();
+
+		// Dynamic truncation, RFC 4226 section 5.3.
+		let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+		let binary = u32::from_be_bytes([
+			hash[offset] & 0x7f,
+			hash[offset + 1],
+			hash[offset + 2],
+			hash[offset + 3],
+		]);
+		binary % 10u32.pow(digits)
+	}
+
+	pub fn code_at_time(&self, unix_time_secs: u64) -> String {
+		format_code(self.code_at_time_step(time_step(unix_time_secs), TOTP_DIGITS), TOTP_DIGITS)
+	}
+}
+
+// Never print the secret itself.
+impl fmt::Debug for TotpSecret {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "TotpSecret(<redacted>)")
+	}
+}
+
+pub fn time_step(unix_time_secs: u64) -> u64 {
+	unix_time_secs / TOTP_PERIOD_SECS
+}
+
+pub fn format_code(code: u32, digits: u32) -> String {
+	format!("{:0width$}", code, width = digits as usize)
+}
+
+/// Key URI (as used for QR codes) to provision an authenticator app, see
+/// https://github.com/google/google-authenticator/wiki/Key-Uri-Format
+pub fn provisioning_uri(secret: &TotpSecret, issuer: &str, account_name: &str) -> String {
+	format!(
+		"otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+		percent_encode(issuer),
+		percent_encode(account_name),
+		secret.to_base32(),
+		percent_encode(issuer),
+		TOTP_DIGITS,
+		TOTP_PERIOD_SECS
+	)
+}
+
+fn percent_encode(value: &str) -> String {
+	let mut encoded = String::with_capacity(value.len());
+	for byte in value.bytes() {
+		match byte {
+			b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' =>
+				encoded.push(byte as char),
+			_ => encoded.push_str(&format!("%{:02X}", byte)),
+		}
+	}
+	encoded
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// Test vectors of RFC 6238, appendix B (SHA1 variant).
+	const RFC_SECRET: &[u8] = b"12345678901234567890";
+
+	#[test]
+	fn codes_match_rfc_6238_test_vectors() {
+		let secret = TotpSecret::new(RFC_SECRET.to_vec()).unwrap();
+		let test_vectors = [
+			(59u64, "94287082"),
+			(1111111109, "07081804"),
+			(1111111111, "14050471"),
+			(1234567890, "89005924"),
+			(2000000000, "69279037"),
+			(20000000000, "65353130"),
+		];
+
+		for (unix_time, expected_code) in test_vectors {
+			let code = secret.code_at_time_step(time_step(unix_time), 8);
+			assert_eq!(expected_code, format_code(code, 8));
+		}
+	}
+
+	#[test]
+	fn six_digit_code_is_zero_padded_suffix() {
+		let secret = TotpSecret::new(RFC_SECRET.to_vec()).unwrap();
+		assert_eq!("081804", secret.code_at_time(1111111109));
+	}
+
+	#[test]
+	fn short_secret_is_rejected() {
+		assert!(TotpSecret::new(vec![1u8; 10]).is_err());
+	}
+
+	#[test]
+	fn provisioning_uri_contains_base32_secret_and_escaped_names() {
+		let secret = TotpSecret::new(RFC_SECRET.to_vec()).unwrap();
+		assert_eq!(
+			"otpauth://totp/Integritee:alice%40example.com?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ\
+			&issuer=Integritee&algorithm=SHA1&digits=6&period=30",
+			provisioning_uri(&secret, "Integritee", "alice@example.com")
+		);
+	}
+
+	#[test]
+	fn debug_output_does_not_contain_secret() {
+		let secret = TotpSecret::new(RFC_SECRET.to_vec()).unwrap();
+		assert_eq!("TotpSecret(<redacted>)", format!("{:?}", secret));
+	}
+}
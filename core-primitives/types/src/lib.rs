@@ -20,6 +20,7 @@
 
 use crate::storage::StorageEntry;
 use codec::{Decode, Encode};
+use serde::{Deserialize, Serialize};
 #[cfg(feature = "sgx")]
 use sgx_tstd as std;
 use sp_std::vec::Vec;
@@ -45,7 +46,9 @@ pub type MrEnclave = [u8; 32];
 
 pub type ConfirmCallFn = ([u8; 2], ShardIdentifier, H256, Vec<u8>);
 pub type ShieldFundsFn = ([u8; 2], Vec<u8>, Balance, ShardIdentifier);
+pub type ShieldAssetsFn = ([u8; 2], Vec<u8>, AssetId, Balance, ShardIdentifier);
 pub type CallWorkerFn = ([u8; 2], Request);
+pub type RegisterOAuthClientFn = ([u8; 2], Vec<u8>);
 
 pub type Enclave = EnclaveGen<AccountId>;
 
@@ -128,6 +131,103 @@ pub enum TrustedOperationStatus {
 	Invalid,
 }
 
+/// Retention policy of the state snapshots that are kept per shard.
+#[derive(Encode, Decode, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateSnapshotRetention {
+	/// Maximum number of snapshots per shard, must be at least 1.
+	pub max_snapshots: u32,
+	/// Snapshots older than this (in seconds) are removed, except for the latest one.
+	pub max_age_secs: Option<u64>,
+	/// Maximum number of delta snapshots written in a row before a full snapshot is written
+	/// again. A delta snapshot only stores the difference to the previous state, 0 disables them.
+	pub max_delta_chain_length: u32,
+}
+
+/// Limits of the trusted operation pool.
+///
+/// The ready and future limits apply to the whole pool, i.e. to all shards together.
+#[derive(Encode, Decode, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TopPoolLimits {
+	/// Maximum number of ready operations.
+	pub max_ready: u32,
+	/// Maximum size (in bytes) of all ready operations.
+	pub max_ready_bytes: u32,
+	/// Maximum number of future operations.
+	pub max_future: u32,
+	/// Maximum size (in bytes) of all future operations.
+	pub max_future_bytes: u32,
+	/// Maximum number of operations (ready and future) of a single shard.
+	pub max_per_shard: u32,
+	/// Maximum number of operations (ready and future) of a single sender within a shard.
+	pub max_per_sender: u32,
+}
+
+impl Default for TopPoolLimits {
+	fn default() -> Self {
+		TopPoolLimits {
+			max_ready: 8192,
+			max_ready_bytes: 20 * 1024 * 1024,
+			max_future: 512,
+			max_future_bytes: 1024 * 1024,
+			max_per_shard: 8192 + 512,
+			max_per_sender: 256,
+		}
+	}
+}
+
+/// Reason a state snapshot failed verification.
+#[derive(Encode, Decode, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StateSnapshotCorruption {
+	/// The snapshot could not be read, decrypted or decoded.
+	Unreadable,
+	/// The state of the snapshot does not match the hash recorded in the snapshot history.
+	HashMismatch,
+	/// The snapshot is a delta of a corrupt snapshot.
+	CorruptBase,
+	/// The snapshot was ignored when the snapshot history was loaded, because it was not valid.
+	NotInHistory,
+}
+
+/// Result of verifying the state snapshots of a shard.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+pub struct StateVerificationReport {
+	pub shard: ShardIdentifier,
+	/// IDs of the snapshots that passed verification, newest first.
+	pub valid_snapshots: Vec<u128>,
+	/// IDs of the corrupt snapshots, newest first.
+	pub corrupt_snapshots: Vec<(u128, StateSnapshotCorruption)>,
+	/// Whether the corrupt snapshots were removed, rolling the shard back to its newest valid
+	/// snapshot.
+	pub rolled_back: bool,
+}
+
+/// Health of a worker, as reported by the `system_health` RPC method.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+pub struct SystemHealth {
+	/// Whether the worker has sidechain blocks queued for import.
+	pub is_syncing: bool,
+	/// Number of peer validateers.
+	pub peers: u32,
+	/// Number of trusted calls pending in the TOP pool.
+	pub pending_trusted_calls: u64,
+	/// Last sidechain block, of the shard with the highest block number.
+	pub last_sidechain_block: Option<LastSidechainBlock>,
+}
+
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+pub struct LastSidechainBlock {
+	pub shard: ShardIdentifier,
+	pub number: u64,
+	pub hash: H256,
+}
+
+/// Version of a worker, as reported by the `system_version` RPC method.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+pub struct SystemVersion {
+	pub version: PalletString,
+	pub mrenclave: MrEnclave,
+}
+
 #[derive(Encode, Decode, Clone, Debug, PartialEq)]
 pub enum WorkerRequest {
 	ChainStorage(Vec<u8>, Option<BlockHash>), // (storage_key, at_block)
@@ -51,6 +51,7 @@ mod builders;
 pub mod response_channel;
 pub mod rpc_connection_registry;
 pub mod rpc_responder;
+pub mod rpc_subscriptions;
 pub mod rpc_watch_extractor;
 pub mod rpc_ws_handler;
 
@@ -96,6 +97,13 @@ pub trait SendRpcResponse: Send + Sync {
 	) -> DirectRpcResult<()>;
 
 	fn send_state(&self, hash: Self::Hash, state_encoded: Vec<u8>) -> DirectRpcResult<()>;
+
+	/// Sends an update to a subscription, keeping the connection open for further updates.
+	fn send_subscription_update(
+		&self,
+		hash: Self::Hash,
+		update_encoded: Vec<u8>,
+	) -> DirectRpcResult<()>;
 }
 
 /// Determines if a given connection must be watched (i.e. kept alive),
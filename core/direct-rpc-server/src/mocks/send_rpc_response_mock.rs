@@ -29,6 +29,7 @@ use std::vec::Vec;
 #[derive(Default)]
 pub struct SendRpcResponseMock<HashType> {
 	pub sent_states: RwLock<Vec<(HashType, Vec<u8>)>>,
+	pub sent_subscription_updates: RwLock<Vec<(HashType, Vec<u8>)>>,
 }
 
 impl<HashType> SendRpcResponse for SendRpcResponseMock<HashType>
@@ -50,4 +51,14 @@ where
 		states_lock.push((hash, state_encoded));
 		Ok(())
 	}
+
+	fn send_subscription_update(
+		&self,
+		hash: Self::Hash,
+		update_encoded: Vec<u8>,
+	) -> DirectRpcResult<()> {
+		let mut updates_lock = self.sent_subscription_updates.write().unwrap();
+		updates_lock.push((hash, update_encoded));
+		Ok(())
+	}
 }
@@ -127,6 +127,23 @@ where
 		debug!("sending state successful");
 		Ok(())
 	}
+
+	fn send_subscription_update(&self, hash: Hash, update_encoded: Vec<u8>) -> DirectRpcResult<()> {
+		// withdraw removes it from the registry
+		let (connection_token, mut response) = self
+			.connection_registry
+			.withdraw(&hash)
+			.ok_or(DirectRpcError::InvalidConnectionHash)?;
+
+		response.result =
+			RpcReturnValue::new(update_encoded, true, DirectRequestStatus::Ok).to_hex();
+
+		self.encode_and_send_response(connection_token, &response)?;
+
+		// Only keep the connection if the update could be sent, a closed connection is dropped.
+		self.connection_registry.store(hash, connection_token, response);
+		Ok(())
+	}
 }
 
 fn continue_watching(status: &TrustedOperationStatus) -> bool {
@@ -236,6 +253,26 @@ pub mod tests {
 		assert_eq!(1, websocket_responder.number_of_updates());
 	}
 
+	#[test]
+	fn sending_subscription_update_keeps_connection() {
+		let connection_hash = String::from("conn_hash");
+		let connection_registry = create_registry_with_single_connection(connection_hash.clone());
+
+		let websocket_responder = Arc::new(TestResponseChannel::default());
+		let rpc_responder =
+			RpcResponder::new(connection_registry.clone(), websocket_responder.clone());
+
+		assert!(rpc_responder
+			.send_subscription_update(connection_hash.clone(), "header_1".encode())
+			.is_ok());
+		assert!(rpc_responder
+			.send_subscription_update(connection_hash.clone(), "header_2".encode())
+			.is_ok());
+
+		verify_open_connection(&connection_hash, connection_registry);
+		assert_eq!(2, websocket_responder.number_of_updates());
+	}
+
 	#[test]
 	fn test_continue_watching() {
 		assert!(!continue_watching(&TrustedOperationStatus::Invalid));
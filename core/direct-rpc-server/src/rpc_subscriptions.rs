@@ -0,0 +1,209 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use std::sync::SgxRwLock as RwLock;
+
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+use crate::SendRpcResponse;
+use core::sync::atomic::{AtomicU64, Ordering};
+use itp_types::H256;
+use log::*;
+use std::{sync::Arc, vec::Vec};
+
+/// Source of the subscription IDs, shared by all subscription instances since their
+/// connections are stored in the same connection registry.
+static NEXT_SUBSCRIPTION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Subscriptions of RPC clients to a stream of updates (e.g. imported block headers).
+///
+/// A subscription is identified by the hash its connection is stored with in the
+/// connection registry. Subscriptions whose connection is gone are dropped on the next update.
+/// Each subscription carries a `Filter`, which decides what updates it receives.
+pub struct RpcSubscriptions<Responder, Filter = ()>
+where
+	Responder: SendRpcResponse<Hash = H256>,
+{
+	rpc_responder: Arc<Responder>,
+	subscriptions: RwLock<Vec<(H256, Filter)>>,
+}
+
+impl<Responder, Filter> RpcSubscriptions<Responder, Filter>
+where
+	Responder: SendRpcResponse<Hash = H256>,
+{
+	pub fn new(rpc_responder: Arc<Responder>) -> Self {
+		RpcSubscriptions { rpc_responder, subscriptions: RwLock::new(Vec::new()) }
+	}
+
+	/// Adds a new subscription with a filter and returns its ID.
+	///
+	/// The RPC response must be watched with this ID, so the connection is kept open.
+	pub fn subscribe_with_filter(&self, filter: Filter) -> H256 {
+		let subscription_id =
+			H256::from_low_u64_be(NEXT_SUBSCRIPTION_ID.fetch_add(1, Ordering::Relaxed));
+		self.subscriptions
+			.write()
+			.expect("Lock poisoning")
+			.push((subscription_id, filter));
+		subscription_id
+	}
+
+	/// Sends an individual update to each subscriber.
+	///
+	/// `update_for` returns the encoded update for a subscription filter, or `None` if the
+	/// subscriber should not receive anything.
+	pub fn notify_with<F>(&self, update_for: F)
+	where
+		F: Fn(&Filter) -> Option<Vec<u8>>,
+	{
+		let mut subscriptions = self.subscriptions.write().expect("Lock poisoning");
+		subscriptions.retain(|(subscription_id, filter)| {
+			let update_encoded = match update_for(filter) {
+				Some(update) => update,
+				None => return true,
+			};
+			match self.rpc_responder.send_subscription_update(*subscription_id, update_encoded) {
+				Ok(()) => true,
+				Err(e) => {
+					debug!("Dropping subscription {:?}: {:?}", subscription_id, e);
+					false
+				},
+			}
+		});
+	}
+
+	/// Sends an update to all subscribers.
+	pub fn notify(&self, update_encoded: Vec<u8>) {
+		self.notify_with(|_| Some(update_encoded.clone()))
+	}
+
+	pub fn number_of_subscriptions(&self) -> usize {
+		self.subscriptions.read().expect("Lock poisoning").len()
+	}
+}
+
+impl<Responder> RpcSubscriptions<Responder>
+where
+	Responder: SendRpcResponse<Hash = H256>,
+{
+	/// Adds a new subscription and returns its ID.
+	///
+	/// The RPC response must be watched with this ID, so the connection is kept open.
+	pub fn subscribe(&self) -> H256 {
+		self.subscribe_with_filter(())
+	}
+}
+
+#[cfg(test)]
+pub mod tests {
+
+	use super::*;
+	use crate::{
+		mocks::response_channel_mock::ResponseChannelMock,
+		rpc_connection_registry::ConnectionRegistry, rpc_responder::RpcResponder,
+		RpcConnectionRegistry,
+	};
+	use codec::Encode;
+	use itp_rpc::RpcResponse;
+
+	type TestConnectionToken = u64;
+	type TestResponseChannel = ResponseChannelMock<TestConnectionToken>;
+	type TestConnectionRegistry = ConnectionRegistry<H256, TestConnectionToken>;
+	type TestRpcResponder = RpcResponder<TestConnectionRegistry, H256, TestResponseChannel>;
+
+	#[test]
+	fn subscription_ids_are_unique() {
+		let (subscriptions, _, _) = create_subscriptions();
+
+		assert_ne!(subscriptions.subscribe(), subscriptions.subscribe());
+		assert_eq!(2, subscriptions.number_of_subscriptions());
+	}
+
+	#[test]
+	fn notify_sends_update_to_all_subscribers() {
+		let (subscriptions, connection_registry, response_channel) = create_subscriptions();
+
+		for token in 0..2u64 {
+			let subscription_id = subscriptions.subscribe();
+			connection_registry.store(subscription_id, token, rpc_response());
+		}
+
+		subscriptions.notify("header_1".encode());
+		subscriptions.notify("header_2".encode());
+
+		assert_eq!(4, response_channel.number_of_updates());
+		assert_eq!(2, subscriptions.number_of_subscriptions());
+	}
+
+	#[test]
+	fn subscription_ids_are_unique_across_instances() {
+		let (subscriptions, _, _) = create_subscriptions();
+		let (other_subscriptions, _, _) = create_subscriptions();
+
+		assert_ne!(subscriptions.subscribe(), other_subscriptions.subscribe());
+	}
+
+	#[test]
+	fn filtered_updates_are_only_sent_to_matching_subscribers() {
+		let connection_registry = Arc::new(TestConnectionRegistry::new());
+		let response_channel = Arc::new(TestResponseChannel::default());
+		let rpc_responder =
+			Arc::new(RpcResponder::new(connection_registry.clone(), response_channel.clone()));
+		let subscriptions = RpcSubscriptions::<TestRpcResponder, u8>::new(rpc_responder);
+
+		for (token, filter) in [(1u64, 1u8), (2u64, 2u8), (3u64, 2u8)] {
+			let subscription_id = subscriptions.subscribe_with_filter(filter);
+			connection_registry.store(subscription_id, token, rpc_response());
+		}
+
+		subscriptions.notify_with(|filter| (*filter == 2).then(|| "update".encode()));
+
+		assert_eq!(2, response_channel.number_of_updates());
+		assert_eq!(3, subscriptions.number_of_subscriptions());
+	}
+
+	#[test]
+	fn subscriptions_without_connection_are_dropped() {
+		let (subscriptions, connection_registry, response_channel) = create_subscriptions();
+
+		let subscription_id = subscriptions.subscribe();
+		connection_registry.store(subscription_id, 1, rpc_response());
+		let _closed_subscription_id = subscriptions.subscribe();
+
+		subscriptions.notify("header".encode());
+
+		assert_eq!(1, response_channel.number_of_updates());
+		assert_eq!(1, subscriptions.number_of_subscriptions());
+	}
+
+	fn create_subscriptions(
+	) -> (RpcSubscriptions<TestRpcResponder>, Arc<TestConnectionRegistry>, Arc<TestResponseChannel>)
+	{
+		let connection_registry = Arc::new(TestConnectionRegistry::new());
+		let response_channel = Arc::new(TestResponseChannel::default());
+		let rpc_responder =
+			Arc::new(RpcResponder::new(connection_registry.clone(), response_channel.clone()));
+		(RpcSubscriptions::new(rpc_responder), connection_registry, response_channel)
+	}
+
+	fn rpc_response() -> RpcResponse {
+		RpcResponse { id: 1u32, jsonrpc: String::from("2.0"), result: String::new() }
+	}
+}
@@ -36,6 +36,8 @@ pub enum Error {
 	NodeMetadataProvider(#[from] itp_node_api::metadata::provider::Error),
 	#[error("Crypto error: {0}")]
 	Crypto(itp_sgx_crypto::Error),
+	#[error("OAuth client registry error: {0}")]
+	OAuthClientRegistry(#[from] itp_oauth_client_registry::Error),
 	#[error(transparent)]
 	Other(#[from] Box<dyn std::error::Error + Sync + Send + 'static>),
 }
@@ -18,8 +18,10 @@
 
 use crate::error::Result;
 use codec::{Decode, Encode};
+use core::mem;
 use itp_api_client_types::{Events, StaticEvent};
 use itp_types::H256;
+use sp_runtime::DispatchResult;
 use std::vec::Vec;
 
 #[derive(Encode, Decode, Debug)]
@@ -38,7 +40,18 @@ impl StaticEvent for ExtrinsicFailed {
 	const EVENT: &'static str = "ExtrinsicFailed";
 }
 
-#[derive(Debug)]
+/// Emitted within a `Sudo::sudo` extrinsic, with the result of the wrapped call.
+#[derive(Encode, Decode, Debug)]
+pub struct Sudid {
+	pub sudo_result: DispatchResult,
+}
+
+impl StaticEvent for Sudid {
+	const PALLET: &'static str = "Sudo";
+	const EVENT: &'static str = "Sudid";
+}
+
+#[derive(Debug, PartialEq, Eq)]
 pub enum ExtrinsicStatus {
 	Success,
 	Failed,
@@ -49,27 +62,63 @@ pub trait FilterEvents {
 
 impl FilterEvents for Events<H256> {
 	fn get_extrinsic_statuses(&self) -> Result<Vec<ExtrinsicStatus>> {
-		Ok(self
-			.iter()
-			.filter_map(|ev| {
-				ev.and_then(|ev| {
-					if (ev.as_event::<ExtrinsicSuccess>()?).is_some() {
-						return Ok(Some(ExtrinsicStatus::Success))
-					}
-
-					if (ev.as_event::<ExtrinsicFailed>()?).is_some() {
-						return Ok(Some(ExtrinsicStatus::Failed))
-					}
-
-					Ok(None)
-				})
-				.ok()
-				.flatten()
+		let status_events = self.iter().filter_map(|ev| {
+			ev.and_then(|ev| {
+				if (ev.as_event::<ExtrinsicSuccess>()?).is_some() {
+					return Ok(Some(StatusEvent::ExtrinsicSuccess))
+				}
+
+				if (ev.as_event::<ExtrinsicFailed>()?).is_some() {
+					return Ok(Some(StatusEvent::ExtrinsicFailed))
+				}
+
+				if let Some(sudid) = ev.as_event::<Sudid>()? {
+					return Ok(Some(StatusEvent::Sudid { succeeded: sudid.sudo_result.is_ok() }))
+				}
+
+				Ok(None)
 			})
-			.collect())
+			.ok()
+			.flatten()
+		});
+
+		Ok(extrinsic_statuses(status_events))
 	}
 }
 
+/// The events of a block that determine the status of its extrinsics.
+#[derive(Debug, Clone, Copy)]
+enum StatusEvent {
+	ExtrinsicSuccess,
+	ExtrinsicFailed,
+	Sudid { succeeded: bool },
+}
+
+/// One status per extrinsic, in the order of the extrinsics.
+///
+/// A `Sudo::sudo` extrinsic succeeds even if the wrapped call fails, which is only told by
+/// the `Sudid` event emitted before the extrinsic's `ExtrinsicSuccess`. Such an extrinsic
+/// counts as failed, so that the wrapped call is not executed as indirect call either.
+fn extrinsic_statuses(events: impl Iterator<Item = StatusEvent>) -> Vec<ExtrinsicStatus> {
+	let mut wrapped_call_failed = false;
+	events
+		.filter_map(|event| match event {
+			StatusEvent::Sudid { succeeded } => {
+				wrapped_call_failed |= !succeeded;
+				None
+			},
+			StatusEvent::ExtrinsicSuccess => match mem::take(&mut wrapped_call_failed) {
+				true => Some(ExtrinsicStatus::Failed),
+				false => Some(ExtrinsicStatus::Success),
+			},
+			StatusEvent::ExtrinsicFailed => {
+				wrapped_call_failed = false;
+				Some(ExtrinsicStatus::Failed)
+			},
+		})
+		.collect()
+}
+
 pub struct MockEvents;
 
 impl FilterEvents for MockEvents {
@@ -77,3 +126,37 @@ impl FilterEvents for MockEvents {
 		Ok(Vec::from([ExtrinsicStatus::Success]))
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn extrinsic_with_failed_sudo_call_counts_as_failed() {
+		let events = [
+			StatusEvent::ExtrinsicSuccess,
+			StatusEvent::Sudid { succeeded: false },
+			StatusEvent::ExtrinsicSuccess,
+			StatusEvent::ExtrinsicSuccess,
+		];
+
+		assert_eq!(
+			extrinsic_statuses(events.into_iter()),
+			vec![ExtrinsicStatus::Success, ExtrinsicStatus::Failed, ExtrinsicStatus::Success]
+		);
+	}
+
+	#[test]
+	fn extrinsic_with_successful_sudo_call_counts_as_success() {
+		let events = [
+			StatusEvent::Sudid { succeeded: true },
+			StatusEvent::ExtrinsicSuccess,
+			StatusEvent::ExtrinsicFailed,
+		];
+
+		assert_eq!(
+			extrinsic_statuses(events.into_iter()),
+			vec![ExtrinsicStatus::Success, ExtrinsicStatus::Failed]
+		);
+	}
+}
@@ -32,6 +32,7 @@ use ita_stf::{TrustedCall, TrustedCallSigned};
 use itp_node_api::metadata::{
 	pallet_teerex::TeerexCallIndexes, provider::AccessNodeMetadata, NodeMetadataTrait,
 };
+use itp_oauth_client_registry::{OAuthClient, RegisterOAuthClient};
 use itp_sgx_crypto::{key_repository::AccessKey, ShieldingCryptoDecrypt, ShieldingCryptoEncrypt};
 use itp_stf_executor::traits::StfEnclaveSigning;
 use itp_stf_primitives::types::AccountId;
@@ -47,6 +48,7 @@ pub struct IndirectCallsExecutor<
 	StfEnclaveSigner,
 	TopPoolAuthor,
 	NodeMetadataProvider,
+	OAuthClientRegistry,
 	IndirectCallsFilter,
 	EventCreator,
 > {
@@ -54,6 +56,7 @@ pub struct IndirectCallsExecutor<
 	pub(crate) stf_enclave_signer: Arc<StfEnclaveSigner>,
 	pub(crate) top_pool_author: Arc<TopPoolAuthor>,
 	pub(crate) node_meta_data_provider: Arc<NodeMetadataProvider>,
+	pub(crate) oauth_client_registry: Arc<OAuthClientRegistry>,
 	_phantom: PhantomData<(IndirectCallsFilter, EventCreator)>,
 }
 impl<
@@ -61,6 +64,7 @@ impl<
 		StfEnclaveSigner,
 		TopPoolAuthor,
 		NodeMetadataProvider,
+		OAuthClientRegistry,
 		IndirectCallsFilter,
 		EventCreator,
 	>
@@ -69,6 +73,7 @@ impl<
 		StfEnclaveSigner,
 		TopPoolAuthor,
 		NodeMetadataProvider,
+		OAuthClientRegistry,
 		IndirectCallsFilter,
 		EventCreator,
 	>
@@ -78,12 +83,14 @@ impl<
 		stf_enclave_signer: Arc<StfEnclaveSigner>,
 		top_pool_author: Arc<TopPoolAuthor>,
 		node_meta_data_provider: Arc<NodeMetadataProvider>,
+		oauth_client_registry: Arc<OAuthClientRegistry>,
 	) -> Self {
 		IndirectCallsExecutor {
 			shielding_key_repo,
 			stf_enclave_signer,
 			top_pool_author,
 			node_meta_data_provider,
+			oauth_client_registry,
 			_phantom: Default::default(),
 		}
 	}
@@ -94,6 +101,7 @@ impl<
 		StfEnclaveSigner,
 		TopPoolAuthor,
 		NodeMetadataProvider,
+		OAuthClientRegistry,
 		FilterIndirectCalls,
 		EventCreator,
 	> ExecuteIndirectCalls
@@ -102,6 +110,7 @@ impl<
 		StfEnclaveSigner,
 		TopPoolAuthor,
 		NodeMetadataProvider,
+		OAuthClientRegistry,
 		FilterIndirectCalls,
 		EventCreator,
 	> where
@@ -111,6 +120,7 @@ impl<
 	StfEnclaveSigner: StfEnclaveSigning,
 	TopPoolAuthor: AuthorApi<H256, H256> + Send + Sync + 'static,
 	NodeMetadataProvider: AccessNodeMetadata,
+	OAuthClientRegistry: RegisterOAuthClient,
 	FilterIndirectCalls: FilterIntoDataFrom<NodeMetadataProvider::MetadataType>,
 	NodeMetadataProvider::MetadataType: NodeMetadataTrait + Clone,
 	FilterIndirectCalls::Output: IndirectDispatch<Self> + Encode + Debug,
@@ -200,6 +210,7 @@ impl<
 		StfEnclaveSigner,
 		TopPoolAuthor,
 		NodeMetadataProvider,
+		OAuthClientRegistry,
 		FilterIndirectCalls,
 		EventFilter,
 	> IndirectExecutor
@@ -208,6 +219,7 @@ impl<
 		StfEnclaveSigner,
 		TopPoolAuthor,
 		NodeMetadataProvider,
+		OAuthClientRegistry,
 		FilterIndirectCalls,
 		EventFilter,
 	> where
@@ -216,6 +228,7 @@ impl<
 		+ ShieldingCryptoEncrypt<Error = itp_sgx_crypto::Error>,
 	StfEnclaveSigner: StfEnclaveSigning,
 	TopPoolAuthor: AuthorApi<H256, H256> + Send + Sync + 'static,
+	OAuthClientRegistry: RegisterOAuthClient,
 {
 	fn submit_trusted_call(&self, shard: ShardIdentifier, encrypted_trusted_call: Vec<u8>) {
 		if let Err(e) = futures::executor::block_on(
@@ -246,6 +259,10 @@ impl<
 	) -> Result<TrustedCallSigned> {
 		Ok(self.stf_enclave_signer.sign_call_with_self(trusted_call, shard)?)
 	}
+
+	fn register_oauth_client(&self, client: OAuthClient) -> Result<()> {
+		Ok(self.oauth_client_registry.register_client(client)?)
+	}
 }
 
 pub(crate) fn hash_of<T: Encode>(xt: &T) -> H256 {
@@ -260,22 +277,27 @@ mod test {
 		parentchain_parser::ParentchainExtrinsicParser,
 	};
 	use codec::{Decode, Encode};
-	use ita_stf::TrustedOperation;
+	use ita_stf::{TrustedCall, TrustedOperation};
 	use itc_parentchain_test::ParentchainBlockBuilder;
 	use itp_node_api::{
 		api_client::{
 			ExtrinsicParams, ParentchainAdditionalParams, ParentchainExtrinsicParams,
 			ParentchainUncheckedExtrinsic,
 		},
-		metadata::{metadata_mocks::NodeMetadataMock, provider::NodeMetadataRepository},
+		metadata::{
+			metadata_mocks::NodeMetadataMock, pallet_oauth::OAuthCallIndexes,
+			pallet_sudo::SudoCallIndexes, provider::NodeMetadataRepository,
+		},
 	};
+	use itp_oauth_client_registry::{mocks::OAuthClientRegistryMock, OAuthGrantType};
 	use itp_sgx_crypto::mocks::KeyRepositoryMock;
 	use itp_stf_executor::mocks::StfEnclaveSignerMock;
 	use itp_stf_primitives::types::AccountId;
 	use itp_test::mock::shielding_crypto_mock::ShieldingCryptoMock;
 	use itp_top_pool_author::mocks::AuthorApiMock;
 	use itp_types::{
-		parentchain::Address, Block, CallWorkerFn, Request, ShardIdentifier, ShieldFundsFn,
+		parentchain::Address, Block, CallWorkerFn, RegisterOAuthClientFn, Request, ShardIdentifier,
+		ShieldAssetsFn, ShieldFundsFn,
 	};
 	use sp_core::{ed25519, Pair};
 	use sp_runtime::{MultiSignature, OpaqueExtrinsic};
@@ -285,15 +307,19 @@ mod test {
 	type TestStfEnclaveSigner = StfEnclaveSignerMock;
 	type TestTopPoolAuthor = AuthorApiMock<H256, H256>;
 	type TestNodeMetadataRepository = NodeMetadataRepository<NodeMetadataMock>;
+	type TestOAuthClientRegistry = OAuthClientRegistryMock;
 	type TestIndirectCallExecutor = IndirectCallsExecutor<
 		TestShieldingKeyRepo,
 		TestStfEnclaveSigner,
 		TestTopPoolAuthor,
 		TestNodeMetadataRepository,
+		TestOAuthClientRegistry,
 		ShieldFundsAndCallWorkerFilter<ParentchainExtrinsicParser>,
 		TestEventCreator,
 	>;
 
+	type SudoFn<Call> = ([u8; 2], Call);
+
 	type Seed = [u8; 32];
 	const TEST_SEED: Seed = *b"12345678901234567890123456789012";
 
@@ -301,7 +327,7 @@ mod test {
 	fn indirect_call_can_be_added_to_pool_successfully() {
 		let _ = env_logger::builder().is_test(true).try_init();
 
-		let (indirect_calls_executor, top_pool_author, _) =
+		let (indirect_calls_executor, top_pool_author, _, _) =
 			test_fixtures([0u8; 32], NodeMetadataMock::new());
 
 		let opaque_extrinsic =
@@ -324,7 +350,7 @@ mod test {
 		let _ = env_logger::builder().is_test(true).try_init();
 
 		let mr_enclave = [33u8; 32];
-		let (indirect_calls_executor, top_pool_author, shielding_key_repo) =
+		let (indirect_calls_executor, top_pool_author, shielding_key_repo, _) =
 			test_fixtures(mr_enclave.clone(), NodeMetadataMock::new());
 		let shielding_key = shielding_key_repo.retrieve_key().unwrap();
 
@@ -352,11 +378,99 @@ mod test {
 		assert!(trusted_call_signed.verify_signature(&mr_enclave, &shard_id()));
 	}
 
+	#[test]
+	fn shield_assets_call_is_added_to_pool_as_assets_shield() {
+		let _ = env_logger::builder().is_test(true).try_init();
+
+		let mr_enclave = [34u8; 32];
+		let (indirect_calls_executor, top_pool_author, shielding_key_repo, _) =
+			test_fixtures(mr_enclave.clone(), NodeMetadataMock::new());
+		let shielding_key = shielding_key_repo.retrieve_key().unwrap();
+
+		let opaque_extrinsic = OpaqueExtrinsic::from_bytes(
+			shield_assets_unchecked_extrinsic(&shielding_key).encode().as_slice(),
+		)
+		.unwrap();
+
+		let parentchain_block = ParentchainBlockBuilder::default()
+			.with_extrinsics(vec![opaque_extrinsic])
+			.build();
+
+		indirect_calls_executor
+			.execute_indirect_calls_in_extrinsics(&parentchain_block, &Vec::new())
+			.unwrap();
+
+		let submitted_extrinsic =
+			top_pool_author.pending_tops(shard_id()).unwrap().first().cloned().unwrap();
+		let decrypted_extrinsic = shielding_key.decrypt(&submitted_extrinsic).unwrap();
+		let trusted_call_signed = TrustedOperation::decode(&mut decrypted_extrinsic.as_slice())
+			.unwrap()
+			.to_call()
+			.unwrap()
+			.clone();
+		assert!(trusted_call_signed.verify_signature(&mr_enclave, &shard_id()));
+		assert_matches!(
+			trusted_call_signed.call,
+			TrustedCall::assets_shield(_, account, 7, 1000) if account == AccountId::new([2u8; 32])
+		);
+	}
+
+	#[test]
+	fn register_oauth_client_call_is_applied_to_client_registry() {
+		let _ = env_logger::builder().is_test(true).try_init();
+
+		let (indirect_calls_executor, top_pool_author, shielding_key_repo, oauth_client_registry) =
+			test_fixtures([0u8; 32], NodeMetadataMock::new());
+		let shielding_key = shielding_key_repo.retrieve_key().unwrap();
+
+		let opaque_extrinsic = OpaqueExtrinsic::from_bytes(
+			register_oauth_client_unchecked_extrinsic(&shielding_key).encode().as_slice(),
+		)
+		.unwrap();
+
+		let parentchain_block = ParentchainBlockBuilder::default()
+			.with_extrinsics(vec![opaque_extrinsic])
+			.build();
+
+		indirect_calls_executor
+			.execute_indirect_calls_in_extrinsics(&parentchain_block, &Vec::new())
+			.unwrap();
+
+		assert_eq!(vec![oauth_client()], oauth_client_registry.registered_clients());
+		assert!(top_pool_author.pending_tops(shard_id()).unwrap().is_empty());
+	}
+
+	#[test]
+	fn register_oauth_client_call_without_sudo_is_ignored() {
+		let _ = env_logger::builder().is_test(true).try_init();
+
+		let (indirect_calls_executor, _, shielding_key_repo, oauth_client_registry) =
+			test_fixtures([0u8; 32], NodeMetadataMock::new());
+		let shielding_key = shielding_key_repo.retrieve_key().unwrap();
+
+		let opaque_extrinsic = OpaqueExtrinsic::from_bytes(
+			unwrapped_register_oauth_client_unchecked_extrinsic(&shielding_key)
+				.encode()
+				.as_slice(),
+		)
+		.unwrap();
+
+		let parentchain_block = ParentchainBlockBuilder::default()
+			.with_extrinsics(vec![opaque_extrinsic])
+			.build();
+
+		indirect_calls_executor
+			.execute_indirect_calls_in_extrinsics(&parentchain_block, &Vec::new())
+			.unwrap();
+
+		assert!(oauth_client_registry.registered_clients().is_empty());
+	}
+
 	#[test]
 	fn ensure_empty_extrinsic_vec_triggers_zero_filled_merkle_root() {
 		// given
 		let dummy_metadata = NodeMetadataMock::new();
-		let (indirect_calls_executor, _, _) = test_fixtures([38u8; 32], dummy_metadata.clone());
+		let (indirect_calls_executor, _, _, _) = test_fixtures([38u8; 32], dummy_metadata.clone());
 
 		let block_hash = H256::from([1; 32]);
 		let extrinsics = Vec::new();
@@ -378,7 +492,7 @@ mod test {
 	fn ensure_non_empty_extrinsic_vec_triggers_non_zero_merkle_root() {
 		// given
 		let dummy_metadata = NodeMetadataMock::new();
-		let (indirect_calls_executor, _, _) = test_fixtures([39u8; 32], dummy_metadata.clone());
+		let (indirect_calls_executor, _, _, _) = test_fixtures([39u8; 32], dummy_metadata.clone());
 
 		let block_hash = H256::from([1; 32]);
 		let extrinsics = vec![H256::from([4; 32]), H256::from([9; 32])];
@@ -412,6 +526,21 @@ mod test {
 		)
 	}
 
+	fn shield_assets_unchecked_extrinsic(
+		shielding_key: &ShieldingCryptoMock,
+	) -> ParentchainUncheckedExtrinsic<ShieldAssetsFn> {
+		let target_account = shielding_key.encrypt(&AccountId::new([2u8; 32]).encode()).unwrap();
+		let dummy_metadata = NodeMetadataMock::new();
+
+		let shield_assets_indexes = dummy_metadata.shield_assets_call_indexes().unwrap();
+		ParentchainUncheckedExtrinsic::<ShieldAssetsFn>::new_signed(
+			(shield_assets_indexes, target_account, 7u32, 1000u128, shard_id()),
+			Address::Address32([1u8; 32]),
+			MultiSignature::Ed25519(default_signature()),
+			default_extrinsic_params().signed_extra(),
+		)
+	}
+
 	fn call_worker_unchecked_extrinsic() -> ParentchainUncheckedExtrinsic<CallWorkerFn> {
 		let request = Request { shard: shard_id(), cyphertext: vec![1u8, 2u8] };
 		let dummy_metadata = NodeMetadataMock::new();
@@ -425,6 +554,47 @@ mod test {
 		)
 	}
 
+	fn register_oauth_client_unchecked_extrinsic(
+		shielding_key: &ShieldingCryptoMock,
+	) -> ParentchainUncheckedExtrinsic<SudoFn<RegisterOAuthClientFn>> {
+		let sudo_indexes = NodeMetadataMock::new().sudo_call_indexes().unwrap();
+
+		ParentchainUncheckedExtrinsic::<SudoFn<RegisterOAuthClientFn>>::new_signed(
+			(sudo_indexes, register_oauth_client_call(shielding_key)),
+			Address::Address32([1u8; 32]),
+			MultiSignature::Ed25519(default_signature()),
+			default_extrinsic_params().signed_extra(),
+		)
+	}
+
+	fn unwrapped_register_oauth_client_unchecked_extrinsic(
+		shielding_key: &ShieldingCryptoMock,
+	) -> ParentchainUncheckedExtrinsic<RegisterOAuthClientFn> {
+		ParentchainUncheckedExtrinsic::<RegisterOAuthClientFn>::new_signed(
+			register_oauth_client_call(shielding_key),
+			Address::Address32([1u8; 32]),
+			MultiSignature::Ed25519(default_signature()),
+			default_extrinsic_params().signed_extra(),
+		)
+	}
+
+	fn register_oauth_client_call(shielding_key: &ShieldingCryptoMock) -> RegisterOAuthClientFn {
+		let client_encrypted = shielding_key.encrypt(&oauth_client().encode()).unwrap();
+		let register_oauth_client_indexes =
+			NodeMetadataMock::new().register_oauth_client_call_indexes().unwrap();
+		(register_oauth_client_indexes, client_encrypted)
+	}
+
+	fn oauth_client() -> OAuthClient {
+		OAuthClient::new(
+			"client_id".to_string(),
+			vec!["https://relying.party/callback".to_string()],
+			vec![OAuthGrantType::Password],
+			vec!["profile".to_string()],
+			"client_secret_hash".to_string(),
+		)
+	}
+
 	fn default_signature() -> ed25519::Signature {
 		signer().sign(&[0u8])
 	}
@@ -449,19 +619,26 @@ mod test {
 	fn test_fixtures(
 		mr_enclave: [u8; 32],
 		metadata: NodeMetadataMock,
-	) -> (TestIndirectCallExecutor, Arc<TestTopPoolAuthor>, Arc<TestShieldingKeyRepo>) {
+	) -> (
+		TestIndirectCallExecutor,
+		Arc<TestTopPoolAuthor>,
+		Arc<TestShieldingKeyRepo>,
+		Arc<TestOAuthClientRegistry>,
+	) {
 		let shielding_key_repo = Arc::new(TestShieldingKeyRepo::default());
 		let stf_enclave_signer = Arc::new(TestStfEnclaveSigner::new(mr_enclave));
 		let top_pool_author = Arc::new(TestTopPoolAuthor::default());
 		let node_metadata_repo = Arc::new(NodeMetadataRepository::new(metadata));
+		let oauth_client_registry = Arc::new(TestOAuthClientRegistry::default());
 
 		let executor = IndirectCallsExecutor::new(
 			shielding_key_repo.clone(),
 			stf_enclave_signer,
 			top_pool_author.clone(),
 			node_metadata_repo,
+			oauth_client_registry.clone(),
 		);
 
-		(executor, top_pool_author, shielding_key_repo)
+		(executor, top_pool_author, shielding_key_repo, oauth_client_registry)
 	}
 }
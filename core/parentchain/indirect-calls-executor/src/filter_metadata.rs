@@ -18,13 +18,13 @@
 use crate::{
 	error::Result,
 	event_filter::{FilterEvents, MockEvents},
-	indirect_calls::{CallWorkerArgs, ShiedFundsArgs},
+	indirect_calls::{CallWorkerArgs, RegisterOAuthClientArgs, ShiedFundsArgs, ShieldAssetsArgs},
 	parentchain_parser::ParseExtrinsic,
 	IndirectDispatch, IndirectExecutor,
 };
 use codec::{Decode, Encode};
 use core::marker::PhantomData;
-use itp_api_client_types::{Events, Metadata};
+use itp_api_client_types::{CallIndex, Events, Metadata};
 use itp_node_api::metadata::{NodeMetadata, NodeMetadataTrait};
 use itp_types::H256;
 
@@ -130,9 +130,25 @@ where
 		if index == metadata.shield_funds_call_indexes().ok()? {
 			let args = decode_and_log_error::<ShiedFundsArgs>(call_args)?;
 			Some(IndirectCall::ShieldFunds(args))
+		} else if metadata.shield_assets_call_indexes().ok() == Some(index) {
+			// Not every node has `Teerex::shield_assets`, which must not stop the other calls
+			// from being filtered.
+			let args = decode_and_log_error::<ShieldAssetsArgs>(call_args)?;
+			Some(IndirectCall::ShieldAssets(args))
 		} else if index == metadata.call_worker_call_indexes().ok()? {
 			let args = decode_and_log_error::<CallWorkerArgs>(call_args)?;
 			Some(IndirectCall::CallWorker(args))
+		} else if metadata.sudo_call_indexes().ok() == Some(index) {
+			// OAuth clients may only be registered by the parentchain's sudo key. Only the
+			// `Sudo::sudo` wrapped call is accepted, because `Sudo::sudo` fails for any other
+			// signer and failed extrinsics are not dispatched. A failure of the wrapped call
+			// itself only shows in the `Sudid` event, see `FilterEvents`.
+			let inner_index = decode_and_log_error::<CallIndex>(call_args)?;
+			if metadata.register_oauth_client_call_indexes().ok() != Some(inner_index) {
+				return None
+			}
+			let args = decode_and_log_error::<RegisterOAuthClientArgs>(call_args)?;
+			Some(IndirectCall::RegisterOAuthClient(args))
 		} else {
 			None
 		}
@@ -146,14 +162,19 @@ where
 #[derive(Debug, Clone, Encode, Decode, Eq, PartialEq)]
 pub enum IndirectCall {
 	ShieldFunds(ShiedFundsArgs),
+	ShieldAssets(ShieldAssetsArgs),
 	CallWorker(CallWorkerArgs),
+	RegisterOAuthClient(RegisterOAuthClientArgs),
 }
 
 impl<Executor: IndirectExecutor> IndirectDispatch<Executor> for IndirectCall {
 	fn dispatch(&self, executor: &Executor) -> Result<()> {
 		match self {
 			IndirectCall::ShieldFunds(shieldfunds) => shieldfunds.dispatch(executor),
+			IndirectCall::ShieldAssets(shield_assets) => shield_assets.dispatch(executor),
 			IndirectCall::CallWorker(call_worker) => call_worker.dispatch(executor),
+			IndirectCall::RegisterOAuthClient(register_oauth_client) =>
+				register_oauth_client.dispatch(executor),
 		}
 	}
 }
@@ -16,7 +16,11 @@
 */
 
 mod call_worker;
+mod register_oauth_client;
+mod shield_assets;
 mod shield_funds;
 
 pub use call_worker::CallWorkerArgs;
+pub use register_oauth_client::RegisterOAuthClientArgs;
+pub use shield_assets::ShieldAssetsArgs;
 pub use shield_funds::ShiedFundsArgs;
@@ -0,0 +1,47 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::{error::Result, IndirectDispatch, IndirectExecutor};
+use codec::{Decode, Encode};
+use itp_oauth_client_registry::OAuthClient;
+use log::{debug, info};
+use std::vec::Vec;
+
+/// Arguments of the parentchain's register OAuth client dispatchable.
+///
+/// The client registration is encrypted with the shielding key, such that the hashed
+/// client secret is not exposed on the parentchain.
+///
+/// The dispatchable is only accepted when wrapped in a successful `Sudo::sudo` call, which
+/// proves that it was signed by the parentchain's sudo key.
+#[derive(Debug, Clone, Encode, Decode, Eq, PartialEq)]
+pub struct RegisterOAuthClientArgs {
+	client_encrypted: Vec<u8>,
+}
+
+impl<Executor: IndirectExecutor> IndirectDispatch<Executor> for RegisterOAuthClientArgs {
+	fn dispatch(&self, executor: &Executor) -> Result<()> {
+		info!("Found RegisterOAuthClient extrinsic in block");
+
+		debug!("decrypt the OAuth client registration");
+		let client_vec = executor.decrypt(&self.client_encrypted)?;
+		let client = OAuthClient::decode(&mut client_vec.as_slice())?;
+
+		info!("Registering OAuth client: {}", client.client_id);
+		executor.register_oauth_client(client)
+	}
+}
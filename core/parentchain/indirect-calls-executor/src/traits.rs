@@ -17,6 +17,7 @@
 
 use crate::error::Result;
 use ita_stf::{TrustedCall, TrustedCallSigned};
+use itp_oauth_client_registry::OAuthClient;
 use itp_stf_primitives::types::AccountId;
 use itp_types::{OpaqueCall, ShardIdentifier, H256};
 use sp_runtime::traits::{Block as ParentchainBlockTrait, Header};
@@ -69,4 +70,6 @@ pub trait IndirectExecutor {
 		trusted_call: &TrustedCall,
 		shard: &ShardIdentifier,
 	) -> Result<TrustedCallSigned>;
+
+	fn register_oauth_client(&self, client: OAuthClient) -> Result<()>;
 }
@@ -0,0 +1,182 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Root certificates and SPKI pins for [`SendWithCertificateVerification`].
+//!
+//! An SPKI pin is the SHA-256 hash of a certificate's DER encoded `SubjectPublicKeyInfo`, as
+//! used by HPKP. It stays the same when a CA re-issues its certificate with the same key.
+//!
+//! [`SendWithCertificateVerification`]: crate::http_client::SendWithCertificateVerification
+
+use crate::error::Error;
+use sha2::{Digest, Sha256};
+use std::{
+	string::{String, ToString},
+	vec::Vec,
+};
+
+pub type SpkiPin = [u8; 32];
+
+const PEM_CERTIFICATE_BEGIN: &str = "-----BEGIN CERTIFICATE-----";
+const PEM_CERTIFICATE_END: &str = "-----END CERTIFICATE-----";
+
+const DER_SEQUENCE: u8 = 0x30;
+/// Explicit tag of the optional version field of the `TBSCertificate`.
+const DER_VERSION: u8 = 0xa0;
+/// Fields of the `TBSCertificate` between the version and the `SubjectPublicKeyInfo`:
+/// serial number, signature algorithm, issuer, validity and subject.
+const FIELDS_BEFORE_SPKI: usize = 5;
+
+/// A PEM encoded certificate, split off a bundle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PemCertificate {
+	pub pem: String,
+	pub der: Vec<u8>,
+}
+
+impl PemCertificate {
+	pub fn spki_pin(&self) -> Result<SpkiPin, Error> {
+		spki_pin(&self.der)
+	}
+}
+
+/// All certificates of a PEM bundle, i.e. one or more concatenated PEM certificates.
+pub fn pem_certificates(bundle: &str) -> Result<Vec<PemCertificate>, Error> {
+	let mut certificates = Vec::new();
+	let mut rest = bundle;
+	while let Some(start) = rest.find(PEM_CERTIFICATE_BEGIN) {
+		let body_and_rest = &rest[start + PEM_CERTIFICATE_BEGIN.len()..];
+		let end = body_and_rest.find(PEM_CERTIFICATE_END).ok_or_else(|| {
+			Error::InvalidCertificate("PEM certificate is not terminated".to_string())
+		})?;
+
+		let body: String =
+			body_and_rest[..end].chars().filter(|c| !c.is_ascii_whitespace()).collect();
+		let der = base64::decode(&body)
+			.map_err(|e| Error::InvalidCertificate(format!("Invalid base64: {}", e)))?;
+		let pem_end = start + PEM_CERTIFICATE_BEGIN.len() + end + PEM_CERTIFICATE_END.len();
+		certificates.push(PemCertificate { pem: format!("{}\n", &rest[start..pem_end]), der });
+
+		rest = &rest[pem_end..];
+	}
+
+	if certificates.is_empty() {
+		return Err(Error::InvalidCertificate("No PEM certificate found".to_string()))
+	}
+	Ok(certificates)
+}
+
+/// SHA-256 of the DER encoded `SubjectPublicKeyInfo` of an X.509 certificate.
+pub fn spki_pin(certificate_der: &[u8]) -> Result<SpkiPin, Error> {
+	let (certificate, _) = der_element(certificate_der, DER_SEQUENCE)?;
+	let (tbs_certificate, _) = der_element(certificate.content, DER_SEQUENCE)?;
+
+	let mut fields = tbs_certificate.content;
+	if fields.first() == Some(&DER_VERSION) {
+		fields = der_element(fields, DER_VERSION)?.1;
+	}
+	for _ in 0..FIELDS_BEFORE_SPKI {
+		fields = der_any_element(fields)?.1;
+	}
+	let (spki, _) = der_element(fields, DER_SEQUENCE)?;
+
+	Ok(Sha256::digest(spki.encoded).into())
+}
+
+/// A DER element, `encoded` includes tag and length.
+struct DerElement<'a> {
+	encoded: &'a [u8],
+	content: &'a [u8],
+}
+
+fn der_element(der: &[u8], expected_tag: u8) -> Result<(DerElement<'_>, &[u8]), Error> {
+	match der.first() {
+		Some(tag) if *tag == expected_tag => der_any_element(der),
+		_ => Err(Error::InvalidCertificate(format!("Expected DER tag {:#x}", expected_tag))),
+	}
+}
+
+/// Split the first element off `der`. Only low tag numbers are supported, which suffices for
+/// the fields of a certificate up to the `SubjectPublicKeyInfo`.
+fn der_any_element(der: &[u8]) -> Result<(DerElement<'_>, &[u8]), Error> {
+	let malformed = || Error::InvalidCertificate("Malformed DER".to_string());
+
+	let first_length_byte = *der.get(1).ok_or_else(malformed)?;
+	let (header_length, content_length) = if first_length_byte < 0x80 {
+		(2, first_length_byte as usize)
+	} else {
+		let number_of_length_bytes = (first_length_byte & 0x7f) as usize;
+		if number_of_length_bytes == 0 || number_of_length_bytes > 4 {
+			return Err(malformed())
+		}
+		let length_bytes = der.get(2..2 + number_of_length_bytes).ok_or_else(malformed)?;
+		let content_length =
+			length_bytes.iter().fold(0usize, |length, byte| (length << 8) | *byte as usize);
+		(2 + number_of_length_bytes, content_length)
+	};
+
+	let end = header_length.checked_add(content_length).ok_or_else(malformed)?;
+	let encoded = der.get(..end).ok_or_else(malformed)?;
+	Ok((DerElement { encoded, content: &encoded[header_length..] }, &der[end..]))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use core::assert_matches::assert_matches;
+
+	const AMAZON_ROOT_CERT: &str = include_str!("fixtures/amazon_root_ca_1_v3.pem");
+	const LETS_ENCRYPT_ROOT_CERT: &str = include_str!("fixtures/lets_encrypt_root_cert.pem");
+
+	// openssl x509 -in <cert> -pubkey -noout | openssl pkey -pubin -outform der
+	//   | openssl dgst -sha256 -binary | base64
+	const AMAZON_ROOT_PIN: &str = "++MBgDH5WGvL9Bcn5Be30cRcL0f5O+NyoXuWtQdX1aI=";
+	const LETS_ENCRYPT_ROOT_PIN: &str = "C5+lpZ7tcVwmwQIMcRtPbsQtWLABXhQzejna0wHFr8M=";
+
+	#[test]
+	fn spki_pins_match_openssl() {
+		let amazon = &pem_certificates(AMAZON_ROOT_CERT).unwrap()[0];
+		let lets_encrypt = &pem_certificates(LETS_ENCRYPT_ROOT_CERT).unwrap()[0];
+
+		assert_eq!(base64::decode(AMAZON_ROOT_PIN).unwrap(), amazon.spki_pin().unwrap());
+		assert_eq!(
+			base64::decode(LETS_ENCRYPT_ROOT_PIN).unwrap(),
+			lets_encrypt.spki_pin().unwrap()
+		);
+	}
+
+	#[test]
+	fn bundle_is_split_into_its_certificates() {
+		let bundle = format!("{}\n{}", AMAZON_ROOT_CERT, LETS_ENCRYPT_ROOT_CERT);
+
+		let certificates = pem_certificates(&bundle).unwrap();
+
+		assert_eq!(2, certificates.len());
+		assert_eq!(pem_certificates(&certificates[1].pem).unwrap(), vec![certificates[1].clone()]);
+	}
+
+	#[test]
+	fn malformed_certificates_are_rejected() {
+		assert_matches!(pem_certificates("no certificate"), Err(Error::InvalidCertificate(_)));
+		assert_matches!(
+			pem_certificates("-----BEGIN CERTIFICATE-----\nMIIB"),
+			Err(Error::InvalidCertificate(_))
+		);
+		assert_matches!(spki_pin(&[0x30, 0x82, 0x01]), Err(Error::InvalidCertificate(_)));
+		assert_matches!(spki_pin(&[0x02, 0x01, 0x00]), Err(Error::InvalidCertificate(_)));
+	}
+}
@@ -0,0 +1,196 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Per-host circuit breaker.
+//!
+//! After `failure_threshold` consecutive failed requests to a host, requests to it are
+//! rejected right away for the `cooldown` duration. Afterwards a single trial request is let
+//! through: if it succeeds the circuit closes again, otherwise it opens for another cooldown.
+
+#[cfg(feature = "sgx")]
+use std::sync::SgxRwLock as RwLock;
+
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+use crate::error::Error;
+use log::*;
+use std::{
+	collections::HashMap,
+	string::{String, ToString},
+	time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreakerConfig {
+	/// Number of consecutive failures that open the circuit.
+	pub failure_threshold: u32,
+	/// How long the circuit stays open before a trial request is allowed.
+	pub cooldown: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+	fn default() -> Self {
+		CircuitBreakerConfig { failure_threshold: 5, cooldown: Duration::from_secs(30) }
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CircuitState {
+	Closed {
+		consecutive_failures: u32,
+	},
+	Open {
+		since: Instant,
+	},
+	/// A trial request is in flight.
+	HalfOpen,
+}
+
+/// Circuit breakers of all hosts. Share it (e.g. in an `Arc`) between the clients that talk
+/// to the same hosts, since clients are often created per request.
+pub struct CircuitBreakers {
+	config: CircuitBreakerConfig,
+	circuits: RwLock<HashMap<String, CircuitState>>,
+}
+
+impl CircuitBreakers {
+	pub fn new(config: CircuitBreakerConfig) -> Self {
+		CircuitBreakers { config, circuits: RwLock::new(HashMap::new()) }
+	}
+
+	/// Check whether a request to `host` may be sent now.
+	pub fn try_acquire(&self, host: &str) -> Result<(), Error> {
+		let mut circuits = self.circuits.write().map_err(|_| Error::LockPoisoning)?;
+		let state = circuits
+			.entry(host.to_string())
+			.or_insert(CircuitState::Closed { consecutive_failures: 0 });
+
+		match *state {
+			CircuitState::Closed { .. } => Ok(()),
+			CircuitState::Open { since } if since.elapsed() >= self.config.cooldown => {
+				debug!("Circuit of {} is half-open, sending a trial request", host);
+				*state = CircuitState::HalfOpen;
+				Ok(())
+			},
+			CircuitState::Open { .. } | CircuitState::HalfOpen =>
+				Err(Error::CircuitOpen(host.to_string())),
+		}
+	}
+
+	pub fn record_success(&self, host: &str) -> Result<(), Error> {
+		let mut circuits = self.circuits.write().map_err(|_| Error::LockPoisoning)?;
+		circuits.insert(host.to_string(), CircuitState::Closed { consecutive_failures: 0 });
+		Ok(())
+	}
+
+	pub fn record_failure(&self, host: &str) -> Result<(), Error> {
+		let mut circuits = self.circuits.write().map_err(|_| Error::LockPoisoning)?;
+		let state = circuits
+			.entry(host.to_string())
+			.or_insert(CircuitState::Closed { consecutive_failures: 0 });
+
+		*state = match *state {
+			CircuitState::Closed { consecutive_failures }
+				if consecutive_failures + 1 < self.config.failure_threshold =>
+				CircuitState::Closed { consecutive_failures: consecutive_failures + 1 },
+			_ => {
+				warn!("Opening circuit of {} for {:?}", host, self.config.cooldown);
+				CircuitState::Open { since: Instant::now() }
+			},
+		};
+		Ok(())
+	}
+
+	/// Whether requests to `host` are currently rejected.
+	pub fn is_open(&self, host: &str) -> Result<bool, Error> {
+		let circuits = self.circuits.read().map_err(|_| Error::LockPoisoning)?;
+		Ok(match circuits.get(host) {
+			Some(CircuitState::Open { since }) => since.elapsed() < self.config.cooldown,
+			Some(CircuitState::HalfOpen) => true,
+			_ => false,
+		})
+	}
+}
+
+impl Default for CircuitBreakers {
+	fn default() -> Self {
+		Self::new(CircuitBreakerConfig::default())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use core::assert_matches::assert_matches;
+
+	const HOST: &str = "api.coingecko.com";
+
+	fn circuit_breakers(cooldown: Duration) -> CircuitBreakers {
+		CircuitBreakers::new(CircuitBreakerConfig { failure_threshold: 2, cooldown })
+	}
+
+	#[test]
+	fn circuit_opens_after_consecutive_failures() {
+		let breakers = circuit_breakers(Duration::from_secs(60));
+
+		breakers.record_failure(HOST).unwrap();
+		assert!(breakers.try_acquire(HOST).is_ok());
+		breakers.record_failure(HOST).unwrap();
+
+		assert!(breakers.is_open(HOST).unwrap());
+		assert_matches!(breakers.try_acquire(HOST), Err(Error::CircuitOpen(h)) if h == HOST);
+		assert!(breakers.try_acquire("api.binance.com").is_ok());
+	}
+
+	#[test]
+	fn success_resets_the_failure_count() {
+		let breakers = circuit_breakers(Duration::from_secs(60));
+
+		breakers.record_failure(HOST).unwrap();
+		breakers.record_success(HOST).unwrap();
+		breakers.record_failure(HOST).unwrap();
+
+		assert!(!breakers.is_open(HOST).unwrap());
+	}
+
+	#[test]
+	fn only_a_single_trial_request_is_allowed_after_the_cooldown() {
+		let breakers = circuit_breakers(Duration::ZERO);
+		breakers.record_failure(HOST).unwrap();
+		breakers.record_failure(HOST).unwrap();
+
+		assert!(breakers.try_acquire(HOST).is_ok());
+		assert_matches!(breakers.try_acquire(HOST), Err(Error::CircuitOpen(_)));
+
+		breakers.record_success(HOST).unwrap();
+		assert!(breakers.try_acquire(HOST).is_ok());
+	}
+
+	#[test]
+	fn failed_trial_request_opens_the_circuit_again() {
+		let breakers = circuit_breakers(Duration::from_millis(20));
+		breakers.record_failure(HOST).unwrap();
+		breakers.record_failure(HOST).unwrap();
+		std::thread::sleep(Duration::from_millis(30));
+		breakers.try_acquire(HOST).unwrap();
+
+		breakers.record_failure(HOST).unwrap();
+
+		assert_matches!(breakers.try_acquire(HOST), Err(Error::CircuitOpen(_)));
+	}
+}
@@ -55,4 +55,19 @@ pub enum Error {
 
 	#[error("Invalid parameter value")]
 	InvalidValue,
+
+	#[error("Invalid certificate: {0}")]
+	InvalidCertificate(String),
+
+	#[error("None of the root certificates matches the SPKI pins")]
+	NoPinnedRootCertificate,
+
+	#[error("Server certificate verification failed: {0}")]
+	CertificateVerificationError(String),
+
+	#[error("Circuit breaker of {0} is open")]
+	CircuitOpen(String),
+
+	#[error("Circuit breaker lock is poisoned")]
+	LockPoisoning,
 }
@@ -18,7 +18,11 @@
 #[cfg(all(not(feature = "std"), feature = "sgx"))]
 use crate::sgx_reexport_prelude::*;
 
-use crate::{error::Error, Query, RestPath};
+use crate::{
+	certificates::{pem_certificates, SpkiPin},
+	error::Error,
+	Query, RestPath,
+};
 use http::{
 	header::{HeaderName, AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, USER_AGENT},
 	HeaderValue,
@@ -50,6 +54,7 @@ pub trait SendHttpRequest {
 		params: U,
 		query: Option<&Query<'_>>,
 		maybe_body: Option<String>,
+		headers: Option<&Headers>,
 	) -> Result<(Response, EncodedBody), Error>
 	where
 		T: RestPath<U>;
@@ -91,16 +96,49 @@ impl Send for DefaultSend {
 	}
 }
 
-/// Sends a HTTPs request with the server's root certificate.
-/// The connection will only be established if the supplied certificate
-/// matches the server's root certificate.
+/// Sends a HTTPs request with the server's root certificates.
+/// The connection will only be established if the server's certificate chain
+/// ends in one of the supplied root certificates.
+///
+/// With SPKI pins, only the root certificates whose public key is pinned are trusted. This
+/// allows the set of roots to be updated (e.g. by an operator) while the pins stay in control
+/// of the keys that are trusted.
 pub struct SendWithCertificateVerification {
-	root_certificate: String,
+	root_certificates: Vec<String>,
+	spki_pins: Vec<SpkiPin>,
 }
 
 impl SendWithCertificateVerification {
+	/// `root_certificate` is a PEM certificate or a bundle of them.
 	pub fn new(root_certificate: String) -> Self {
-		SendWithCertificateVerification { root_certificate }
+		Self::with_root_certificates(vec![root_certificate])
+	}
+
+	pub fn with_root_certificates(root_certificates: Vec<String>) -> Self {
+		SendWithCertificateVerification { root_certificates, spki_pins: Vec::new() }
+	}
+
+	/// Only trust the root certificates with one of these SPKI pins.
+	pub fn with_spki_pins(mut self, spki_pins: Vec<SpkiPin>) -> Self {
+		self.spki_pins = spki_pins;
+		self
+	}
+
+	/// PEM bundle of the root certificates that satisfy the pins.
+	pub fn trusted_root_certificates(&self) -> Result<String, Error> {
+		let mut trusted = Vec::new();
+		for bundle in self.root_certificates.iter() {
+			for certificate in pem_certificates(bundle)? {
+				if self.spki_pins.is_empty() || self.spki_pins.contains(&certificate.spki_pin()?) {
+					trusted.push(certificate.pem);
+				}
+			}
+		}
+
+		if trusted.is_empty() {
+			return Err(Error::NoPinnedRootCertificate)
+		}
+		Ok(trusted.concat())
 	}
 }
 
@@ -110,19 +148,30 @@ impl Send for SendWithCertificateVerification {
 		request: &mut Request,
 		writer: &mut Vec<u8>,
 	) -> Result<Response, Error> {
-		match request.send_with_pem_certificate(writer, Some(self.root_certificate.to_string())) {
+		let root_certificates = self.trusted_root_certificates()?;
+		match request.send_with_pem_certificate(writer, Some(root_certificates)) {
 			Ok(response) => Ok(response),
 			Err(e) => {
 				error!(
 					"SendWithCertificateVerification::execute_send_request received error: {:#?}",
 					&e
 				);
-				Err(Error::HttpReqError(e))
+				if is_certificate_error(&e) {
+					Err(Error::CertificateVerificationError(format!("{}", e)))
+				} else {
+					Err(Error::HttpReqError(e))
+				}
 			},
 		}
 	}
 }
 
+/// TLS errors of the server certificate are reported by the TLS library through `http_req`'s
+/// error message, e.g. `invalid certificate: UnknownIssuer`.
+fn is_certificate_error(error: &http_req::error::Error) -> bool {
+	format!("{}", error).to_lowercase().contains("certificate")
+}
+
 impl<SendType> HttpClient<SendType>
 where
 	SendType: Send,
@@ -180,6 +229,7 @@ where
 		params: U,
 		query: Option<&Query<'_>>,
 		maybe_body: Option<String>,
+		headers: Option<&Headers>,
 	) -> Result<(Response, EncodedBody), Error>
 	where
 		T: RestPath<U>,
@@ -227,6 +277,13 @@ where
 			request_headers.insert(key, &value.clone());
 		}
 
+		// add the headers of this particular request
+		if let Some(headers) = headers {
+			for (key, value) in headers.iter() {
+				request_headers.insert(key, &value.clone());
+			}
+		}
+
 		// add user agent header
 		let pkg_version = env!("CARGO_PKG_VERSION");
 		add_to_headers(
@@ -254,7 +311,7 @@ where
 	}
 }
 
-fn join_url(base_url: Url, path: &str, params: Option<&Query>) -> Result<Url, Error> {
+pub(crate) fn join_url(base_url: Url, path: &str, params: Option<&Query>) -> Result<Url, Error> {
 	let mut url = base_url.join(path).map_err(|_| Error::UrlError)?;
 
 	if let Some(params) = params {
@@ -361,6 +418,7 @@ mod tests {
 				(),
 				Some(&query_parameters),
 				None,
+				None,
 			)
 			.unwrap();
 
@@ -398,7 +456,7 @@ mod tests {
 		let base_url = Url::parse("https://httpbin.org").unwrap();
 
 		let (response, encoded_body) = http_client
-			.send_request::<(), HttpBinAnything>(base_url, Method::GET, (), None, None)
+			.send_request::<(), HttpBinAnything>(base_url, Method::GET, (), None, None, None)
 			.unwrap();
 
 		let response_body: HttpBinAnything =
@@ -442,6 +500,7 @@ mod tests {
 				(),
 				None,
 				Some(body_test.clone()),
+				None,
 			)
 			.unwrap();
 
@@ -475,7 +534,14 @@ mod tests {
 		let base_url = Url::parse("https://api.coingecko.com").unwrap();
 
 		let (response, encoded_body) = http_client
-			.send_request::<(), Vec<CoinGeckoCoinsList>>(base_url, Method::GET, (), None, None)
+			.send_request::<(), Vec<CoinGeckoCoinsList>>(
+				base_url,
+				Method::GET,
+				(),
+				None,
+				None,
+				None,
+			)
 			.unwrap();
 
 		let coins_list: Vec<CoinGeckoCoinsList> =
@@ -503,7 +569,7 @@ mod tests {
 		let root_certificate = HTTPBIN_ROOT_CERT.to_string();
 
 		let http_client = HttpClient::new(
-			SendWithCertificateVerification { root_certificate },
+			SendWithCertificateVerification::new(root_certificate),
 			true,
 			Some(Duration::from_secs(3u64)),
 			Some(headers_connection_close()),
@@ -511,7 +577,7 @@ mod tests {
 		);
 
 		let (response, encoded_body) = http_client
-			.send_request::<(), HttpBinAnything>(base_url, Method::GET, (), None, None)
+			.send_request::<(), HttpBinAnything>(base_url, Method::GET, (), None, None, None)
 			.unwrap();
 
 		let response_body: HttpBinAnything =
@@ -541,18 +607,53 @@ mod tests {
 		let root_certificate = COINGECKO_ROOT_CERTIFICATE.to_string();
 
 		let http_client = HttpClient::new(
-			SendWithCertificateVerification { root_certificate },
+			SendWithCertificateVerification::new(root_certificate),
 			true,
 			Some(Duration::from_secs(3u64)),
 			Some(headers_connection_close()),
 			None,
 		);
 
-		let result =
-			http_client.send_request::<(), HttpBinAnything>(base_url, Method::GET, (), None, None);
-		assert_matches!(result, Err(Error::HttpReqError(_)));
-		let msg = format!("error {:?}", result.err());
-		assert!(msg.contains("UnknownIssuer"));
+		let result = http_client.send_request::<(), HttpBinAnything>(
+			base_url,
+			Method::GET,
+			(),
+			None,
+			None,
+			None,
+		);
+		assert_matches!(
+			result,
+			Err(Error::CertificateVerificationError(msg)) if msg.contains("UnknownIssuer")
+		);
+	}
+
+	#[test]
+	fn only_pinned_root_certificates_are_trusted() {
+		let send = SendWithCertificateVerification::with_root_certificates(vec![
+			HTTPBIN_ROOT_CERT.to_string(),
+			COINGECKO_ROOT_CERTIFICATE.to_string(),
+		]);
+		let coingecko_pin =
+			pem_certificates(COINGECKO_ROOT_CERTIFICATE).unwrap()[0].spki_pin().unwrap();
+
+		let all_roots = pem_certificates(&send.trusted_root_certificates().unwrap()).unwrap();
+		let pinned_roots = pem_certificates(
+			&send.with_spki_pins(vec![coingecko_pin]).trusted_root_certificates().unwrap(),
+		)
+		.unwrap();
+
+		assert_eq!(2, all_roots.len());
+		assert_eq!(1, pinned_roots.len());
+		assert_eq!(coingecko_pin, pinned_roots[0].spki_pin().unwrap());
+	}
+
+	#[test]
+	fn no_pinned_root_certificate_is_reported() {
+		let send = SendWithCertificateVerification::new(HTTPBIN_ROOT_CERT.to_string())
+			.with_spki_pins(vec![[0u8; 32]]);
+
+		assert_matches!(send.trusted_root_certificates(), Err(Error::NoPinnedRootCertificate));
 	}
 
 	fn headers_connection_close() -> Headers {
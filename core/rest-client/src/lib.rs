@@ -36,10 +36,14 @@ pub mod sgx_reexport_prelude {
 	pub use url_sgx as url;
 }
 
+pub mod certificates;
+pub mod circuit_breaker;
 pub mod error;
 pub mod http_client;
 pub mod http_client_builder;
+pub mod response_cache;
 pub mod rest_client;
+pub mod retry;
 
 #[cfg(test)]
 pub mod mocks;
@@ -72,6 +76,15 @@ pub trait RestPath<T> {
 	fn get_path(par: T) -> Result<String, Error>;
 }
 
+/// Path that was already resolved from the caller's params.
+pub(crate) struct ResolvedPath;
+
+impl RestPath<String> for ResolvedPath {
+	fn get_path(path: String) -> Result<String, Error> {
+		Ok(path)
+	}
+}
+
 /// REST HTTP GET trait
 ///
 /// Provides the GET verb for a REST API
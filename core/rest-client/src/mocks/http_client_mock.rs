@@ -20,8 +20,12 @@ use crate::{
 	http_client::{EncodedBody, SendHttpRequest},
 	Query, RestPath,
 };
-use http_req::{request::Method, response::Response};
+use http_req::{
+	request::Method,
+	response::{Headers, Response},
+};
 use serde::{Deserialize, Serialize};
+use std::{collections::VecDeque, sync::RwLock};
 use url::Url;
 
 const DEFAULT_HEAD: &[u8; 102] = b"HTTP/1.1 200 OK\r\n\
@@ -48,11 +52,34 @@ impl RestPath<String> for ResponseBodyMock {
 /// HTTP client mock - to be used in unit tests
 pub struct HttpClientMock {
 	response: Option<Response>,
+	/// Results of the next requests, `response` is used once they are used up.
+	sequence: RwLock<VecDeque<Result<Response, Error>>>,
+	number_of_requests: RwLock<usize>,
+	last_request_headers: RwLock<Option<Headers>>,
 }
 
 impl HttpClientMock {
 	pub fn new(response: Option<Response>) -> Self {
-		HttpClientMock { response }
+		HttpClientMock {
+			response,
+			sequence: RwLock::new(VecDeque::new()),
+			number_of_requests: RwLock::new(0),
+			last_request_headers: RwLock::new(None),
+		}
+	}
+
+	/// Mock that returns the given results in order, one per request.
+	pub fn with_sequence(sequence: Vec<Result<Response, Error>>) -> Self {
+		HttpClientMock { sequence: RwLock::new(sequence.into()), ..Self::new(None) }
+	}
+
+	pub fn number_of_requests(&self) -> usize {
+		*self.number_of_requests.read().unwrap()
+	}
+
+	/// Headers passed in with the last request.
+	pub fn last_request_headers(&self) -> Option<Headers> {
+		self.last_request_headers.read().unwrap().clone()
 	}
 }
 
@@ -64,15 +91,22 @@ impl SendHttpRequest for HttpClientMock {
 		params: U,
 		query: Option<&Query<'_>>,
 		maybe_body: Option<String>,
+		headers: Option<&Headers>,
 	) -> Result<(Response, EncodedBody), Error>
 	where
 		T: RestPath<U>,
 	{
 		let path = T::get_path(params)?;
-		let response = self
-			.response
-			.clone()
-			.unwrap_or_else(|| Response::from_head(DEFAULT_HEAD).unwrap());
+		*self.number_of_requests.write().unwrap() += 1;
+		*self.last_request_headers.write().unwrap() = headers.cloned();
+
+		let response = match self.sequence.write().unwrap().pop_front() {
+			Some(result) => result?,
+			None => self
+				.response
+				.clone()
+				.unwrap_or_else(|| Response::from_head(DEFAULT_HEAD).unwrap()),
+		};
 		let base_url_str = String::from(base_url.as_str());
 
 		let query_parameters = query
@@ -132,6 +166,7 @@ mod tests {
 				"/api/v1/get".to_string(),
 				None,
 				None,
+				None,
 			)
 			.unwrap();
 
@@ -0,0 +1,161 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+//! Hook to cache GET responses of the [`RestClient`](crate::rest_client::RestClient),
+//! revalidated with conditional requests.
+
+use crate::error::Error;
+use http_req::response::Headers;
+use std::{
+	string::{String, ToString},
+	time::Duration,
+};
+
+pub const CACHE_CONTROL: &str = "Cache-Control";
+pub const ETAG: &str = "ETag";
+pub const IF_NONE_MATCH: &str = "If-None-Match";
+pub const NOT_MODIFIED: u16 = 304;
+
+/// Response body with the caching directives the server sent along.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedResponse {
+	pub body: String,
+	/// Entity tag to revalidate the response with, once it's stale.
+	pub etag: Option<String>,
+	/// How long the response is fresh, the cache's default TTL applies if the server didn't say.
+	pub max_age: Option<Duration>,
+	/// The server doesn't allow the response to be stored.
+	pub no_store: bool,
+}
+
+impl CachedResponse {
+	pub fn from_headers(body: String, headers: &Headers) -> Self {
+		let mut response = CachedResponse {
+			body,
+			etag: headers.get(ETAG).cloned(),
+			max_age: None,
+			no_store: false,
+		};
+
+		let cache_control = match headers.get(CACHE_CONTROL) {
+			Some(cache_control) => cache_control.to_ascii_lowercase(),
+			None => return response,
+		};
+		let mut no_cache = false;
+		for directive in cache_control.split(',').map(str::trim) {
+			match directive.split_once('=') {
+				Some(("max-age", seconds)) =>
+					if let Ok(seconds) = seconds.trim_matches('"').parse::<u64>() {
+						response.max_age = Some(Duration::from_secs(seconds));
+					},
+				None if directive == "no-cache" => no_cache = true,
+				None if directive == "no-store" => response.no_store = true,
+				_ => {},
+			}
+		}
+		// Stored, but revalidated before every use.
+		if no_cache {
+			response.max_age = Some(Duration::ZERO);
+		}
+		response
+	}
+
+	/// Same body, with the directives of the `304 Not Modified` response that revalidated it.
+	pub fn revalidated(&self, headers: &Headers) -> Self {
+		let mut response = CachedResponse::from_headers(self.body.clone(), headers);
+		if response.etag.is_none() {
+			response.etag = self.etag.clone();
+		}
+		response
+	}
+
+	pub fn conditional_request_headers(&self) -> Headers {
+		let mut headers = Headers::new();
+		if let Some(etag) = self.etag.as_ref() {
+			headers.insert(IF_NONE_MATCH, &etag.to_string());
+		}
+		headers
+	}
+}
+
+/// Cache for the GET responses of a [`RestClient`](crate::rest_client::RestClient).
+///
+/// The cache decides whether a request has to be sent at all; `fetch` sends it, given
+/// the stale response to revalidate, if there is one.
+pub trait ResponseCache {
+	fn get_or_fetch(
+		&self,
+		key: &str,
+		fetch: &mut dyn FnMut(Option<&CachedResponse>) -> Result<CachedResponse, Error>,
+	) -> Result<String, Error>;
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn headers(headers: &[(&str, &str)]) -> Headers {
+		let mut result = Headers::new();
+		for (key, value) in headers {
+			result.insert(key, value);
+		}
+		result
+	}
+
+	#[test]
+	fn cache_control_directives_are_parsed() {
+		let response = CachedResponse::from_headers(
+			"{}".to_string(),
+			&headers(&[("Cache-Control", "public, max-age=60"), ("ETag", "\"abc\"")]),
+		);
+
+		assert_eq!(response.max_age, Some(Duration::from_secs(60)));
+		assert_eq!(response.etag, Some("\"abc\"".to_string()));
+		assert!(!response.no_store);
+	}
+
+	#[test]
+	fn no_cache_and_no_store_are_honoured() {
+		let no_cache = CachedResponse::from_headers(
+			"{}".to_string(),
+			&headers(&[("cache-control", "max-age=60, no-cache")]),
+		);
+		let no_store = CachedResponse::from_headers(
+			"{}".to_string(),
+			&headers(&[("Cache-Control", "no-store")]),
+		);
+
+		assert_eq!(no_cache.max_age, Some(Duration::ZERO));
+		assert!(no_store.no_store);
+		assert_eq!(no_store.max_age, None);
+	}
+
+	#[test]
+	fn revalidated_response_keeps_body_and_etag() {
+		let response =
+			CachedResponse::from_headers("{}".to_string(), &headers(&[("ETag", "\"abc\"")]));
+
+		let revalidated = response.revalidated(&headers(&[("Cache-Control", "max-age=10")]));
+
+		assert_eq!(revalidated.body, "{}");
+		assert_eq!(revalidated.etag, Some("\"abc\"".to_string()));
+		assert_eq!(revalidated.max_age, Some(Duration::from_secs(10)));
+		assert_eq!(
+			revalidated.conditional_request_headers().get(IF_NONE_MATCH),
+			Some(&"\"abc\"".to_string())
+		);
+	}
+}
@@ -22,12 +22,18 @@ pub use http_req::{request::Method, response::Headers};
 pub use url::Url;
 
 use crate::{
-	error::Error, http_client::SendHttpRequest, Query, RestDelete, RestGet, RestPatch, RestPath,
-	RestPost, RestPut,
+	error::Error,
+	http_client::{join_url, EncodedBody, SendHttpRequest},
+	response_cache::{CachedResponse, ResponseCache, NOT_MODIFIED},
+	Query, ResolvedPath, RestDelete, RestGet, RestPatch, RestPath, RestPost, RestPut,
 };
 
+use http_req::response::Response;
 use log::*;
-use std::string::{String, ToString};
+use std::{
+	string::{String, ToString},
+	sync::Arc,
+};
 
 /// REST client to make HTTP GET and POST requests.
 pub struct RestClient<H> {
@@ -35,6 +41,7 @@ pub struct RestClient<H> {
 	baseurl: Url,
 	response_headers: Headers,
 	body_wash_fn: fn(String) -> String,
+	response_cache: Option<Arc<dyn ResponseCache + Send + Sync>>,
 }
 
 impl<H> RestClient<H>
@@ -50,6 +57,7 @@ where
 			baseurl,
 			response_headers: Headers::new(),
 			body_wash_fn: std::convert::identity,
+			response_cache: None,
 		}
 	}
 
@@ -58,7 +66,16 @@ where
 		self.body_wash_fn = func;
 	}
 
+	/// Serve GET requests from the given cache, keyed by their URL.
+	///
+	/// Stale responses with an `ETag` are revalidated with `If-None-Match`.
+	pub fn set_response_cache(&mut self, response_cache: Arc<dyn ResponseCache + Send + Sync>) {
+		self.response_cache = Some(response_cache);
+	}
+
 	/// Response headers captured from previous request
+	///
+	/// Not updated by GET requests that were served from the response cache.
 	pub fn response_headers(&mut self) -> &Headers {
 		&self.response_headers
 	}
@@ -132,14 +149,62 @@ where
 	where
 		T: RestPath<U>,
 	{
-		let (response, encoded_body) = self.http_client.send_request::<U, T>(
+		let path = T::get_path(params)?;
+
+		if method == Method::GET {
+			if let Some(response_cache) = self.response_cache.clone() {
+				let key = join_url(self.baseurl.clone(), &path, query)?.to_string();
+				return response_cache
+					.get_or_fetch(&key, &mut |cached| self.fetch(path.clone(), query, cached))
+			}
+		}
+
+		let (response, encoded_body) = self.http_client.send_request::<String, ResolvedPath>(
 			self.baseurl.clone(),
 			method,
-			params,
+			path,
 			query,
 			maybe_body,
+			None,
 		)?;
+		self.read_response(response, encoded_body)
+	}
 
+	/// GET request for the response cache, conditional if there is a cached response.
+	fn fetch(
+		&mut self,
+		path: String,
+		query: Option<&Query<'_>>,
+		cached: Option<&CachedResponse>,
+	) -> Result<CachedResponse, Error> {
+		let conditional_headers = cached.map(CachedResponse::conditional_request_headers);
+		let (response, encoded_body) = self.http_client.send_request::<String, ResolvedPath>(
+			self.baseurl.clone(),
+			Method::GET,
+			path,
+			query,
+			None,
+			conditional_headers.as_ref(),
+		)?;
+
+		match cached {
+			Some(cached) if u16::from(response.status_code()) == NOT_MODIFIED => {
+				trace!("cached response is not modified");
+				self.response_headers = response.headers().clone();
+				Ok(cached.revalidated(&self.response_headers))
+			},
+			_ => {
+				let body = self.read_response(response, encoded_body)?;
+				Ok(CachedResponse::from_headers(body, &self.response_headers))
+			},
+		}
+	}
+
+	fn read_response(
+		&mut self,
+		response: Response,
+		encoded_body: EncodedBody,
+	) -> Result<String, Error> {
 		self.response_headers = response.headers().clone();
 		let status_code = response.status_code();
 
@@ -319,7 +384,11 @@ where
 mod tests {
 
 	use super::*;
-	use crate::mocks::http_client_mock::{HttpClientMock, ResponseBodyMock};
+	use crate::{
+		mocks::http_client_mock::{HttpClientMock, ResponseBodyMock},
+		response_cache::IF_NONE_MATCH,
+	};
+	use std::sync::Mutex;
 
 	#[test]
 	pub fn get_sends_proper_request() {
@@ -346,6 +415,88 @@ mod tests {
 		assert_eq!(2, get_response.query_parameters.len());
 	}
 
+	#[test]
+	pub fn fresh_cached_response_is_served_without_request() {
+		let mut rest_client = create_default_rest_client();
+		let response_cache = Arc::new(SingleEntryCache::default());
+		rest_client.set_response_cache(response_cache.clone());
+
+		let first = rest_client.get::<String, ResponseBodyMock>("/api/v1/get".to_string()).unwrap();
+		*response_cache.fresh.lock().unwrap() = true;
+		let second =
+			rest_client.get::<String, ResponseBodyMock>("/api/v1/get".to_string()).unwrap();
+
+		assert_eq!(first, second);
+		assert_eq!(rest_client.http_client.number_of_requests(), 1);
+	}
+
+	#[test]
+	pub fn stale_cached_response_is_revalidated_with_etag() {
+		let base_url = Url::parse("https://example.com").unwrap();
+		let http_client = HttpClientMock::with_sequence(vec![
+			Ok(response("200 OK", "ETag: \"v1\"\r\nCache-Control: max-age=0\r\n")),
+			Ok(response("304 Not Modified", "Cache-Control: max-age=30\r\n")),
+		]);
+		let mut rest_client = RestClient::new(http_client, base_url);
+		let response_cache = Arc::new(SingleEntryCache::default());
+		rest_client.set_response_cache(response_cache.clone());
+
+		let first = rest_client.get::<String, ResponseBodyMock>("/api/v1/get".to_string()).unwrap();
+		let second =
+			rest_client.get::<String, ResponseBodyMock>("/api/v1/get".to_string()).unwrap();
+
+		assert_eq!(first, second);
+		assert_eq!(rest_client.http_client.number_of_requests(), 2);
+		let request_headers = rest_client.http_client.last_request_headers().unwrap();
+		assert_eq!(request_headers.get(IF_NONE_MATCH), Some(&"\"v1\"".to_string()));
+
+		let cached = response_cache.entry.lock().unwrap().clone().unwrap();
+		assert_eq!(cached.etag, Some("\"v1\"".to_string()));
+		assert_eq!(cached.max_age, Some(std::time::Duration::from_secs(30)));
+	}
+
+	#[test]
+	pub fn not_modified_without_cached_response_is_an_error() {
+		let base_url = Url::parse("https://example.com").unwrap();
+		let http_client = HttpClientMock::with_sequence(vec![Ok(response("304 Not Modified", ""))]);
+		let mut rest_client = RestClient::new(http_client, base_url);
+		rest_client.set_response_cache(Arc::new(SingleEntryCache::default()));
+
+		let result = rest_client.get::<String, ResponseBodyMock>("/api/v1/get".to_string());
+
+		assert!(matches!(result, Err(Error::HttpError(304, _))));
+	}
+
+	/// Caches one response, which is stale unless marked `fresh`.
+	#[derive(Default)]
+	struct SingleEntryCache {
+		entry: Mutex<Option<CachedResponse>>,
+		fresh: Mutex<bool>,
+	}
+
+	impl ResponseCache for SingleEntryCache {
+		fn get_or_fetch(
+			&self,
+			_key: &str,
+			fetch: &mut dyn FnMut(Option<&CachedResponse>) -> Result<CachedResponse, Error>,
+		) -> Result<String, Error> {
+			let mut entry = self.entry.lock().unwrap();
+			if *self.fresh.lock().unwrap() {
+				if let Some(cached) = entry.as_ref() {
+					return Ok(cached.body.clone())
+				}
+			}
+			let response = fetch(entry.as_ref())?;
+			*entry = Some(response.clone());
+			Ok(response.body)
+		}
+	}
+
+	fn response(status_line: &str, headers: &str) -> Response {
+		let head = format!("HTTP/1.1 {}\r\n{}Content-Length: 0\r\n\r\n", status_line, headers);
+		Response::from_head(head.as_bytes()).unwrap()
+	}
+
 	fn create_default_rest_client() -> RestClient<HttpClientMock> {
 		let base_url = Url::parse("https://example.com").unwrap();
 		let http_client = HttpClientMock::new(None);
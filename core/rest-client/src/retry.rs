@@ -0,0 +1,387 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Retries of transient failures with exponential backoff, see [`RetryHttpClient`].
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use crate::sgx_reexport_prelude::*;
+
+use crate::{
+	circuit_breaker::CircuitBreakers,
+	error::Error,
+	http_client::{EncodedBody, SendHttpRequest},
+	Query, ResolvedPath, RestPath,
+};
+use core::hash::{BuildHasher, Hasher};
+use http_req::{
+	request::Method,
+	response::{Headers, Response},
+};
+use log::*;
+use std::{
+	collections::hash_map::RandomState,
+	string::{String, ToString},
+	sync::Arc,
+	thread,
+	time::Duration,
+};
+use url::Url;
+
+const TOO_MANY_REQUESTS: u16 = 429;
+const RETRY_AFTER: &str = "Retry-After";
+
+/// When and how often a failed request is retried.
+///
+/// Retried are transport errors (connection, TLS, timeout), server errors (5xx) and
+/// `429 Too Many Requests`. A `Retry-After` header (in seconds) replaces the backoff,
+/// limited by `max_backoff`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+	/// Number of attempts, including the first one.
+	pub max_attempts: u32,
+	/// Backoff after the first failed attempt, doubled after each further one.
+	pub initial_backoff: Duration,
+	pub max_backoff: Duration,
+	/// Wait a random duration between half and the full backoff, so clients that failed
+	/// together don't retry together.
+	pub jitter: bool,
+}
+
+impl RetryPolicy {
+	/// Single attempt, i.e. the behaviour of a plain `HttpClient`.
+	pub fn no_retry() -> Self {
+		RetryPolicy { max_attempts: 1, ..Default::default() }
+	}
+
+	/// Backoff before the attempt after `failed_attempts` failed ones.
+	pub fn backoff(&self, failed_attempts: u32) -> Duration {
+		let exponent = failed_attempts.saturating_sub(1).min(31);
+		let backoff = self
+			.initial_backoff
+			.checked_mul(1 << exponent)
+			.unwrap_or(self.max_backoff)
+			.min(self.max_backoff);
+
+		if self.jitter {
+			let half = backoff / 2;
+			half + half.mul_f64(random_fraction())
+		} else {
+			backoff
+		}
+	}
+}
+
+impl Default for RetryPolicy {
+	fn default() -> Self {
+		RetryPolicy {
+			max_attempts: 3,
+			initial_backoff: Duration::from_millis(500),
+			max_backoff: Duration::from_secs(10),
+			jitter: true,
+		}
+	}
+}
+
+/// HTTP client decorator that retries transient failures according to a [`RetryPolicy`]
+/// and, optionally, stops sending requests to failing hosts with [`CircuitBreakers`].
+pub struct RetryHttpClient<H> {
+	http_client: H,
+	retry_policy: RetryPolicy,
+	circuit_breakers: Option<Arc<CircuitBreakers>>,
+}
+
+impl<H> RetryHttpClient<H> {
+	pub fn new(
+		http_client: H,
+		retry_policy: RetryPolicy,
+		circuit_breakers: Option<Arc<CircuitBreakers>>,
+	) -> Self {
+		RetryHttpClient { http_client, retry_policy, circuit_breakers }
+	}
+
+	pub fn inner(&self) -> &H {
+		&self.http_client
+	}
+
+	pub fn inner_mut(&mut self) -> &mut H {
+		&mut self.http_client
+	}
+}
+
+impl<H> SendHttpRequest for RetryHttpClient<H>
+where
+	H: SendHttpRequest,
+{
+	fn send_request<U, T>(
+		&self,
+		base_url: Url,
+		method: Method,
+		params: U,
+		query: Option<&Query<'_>>,
+		maybe_body: Option<String>,
+		headers: Option<&Headers>,
+	) -> Result<(Response, EncodedBody), Error>
+	where
+		T: RestPath<U>,
+	{
+		// The path params are consumed, so resolve the path once for all attempts.
+		let path = T::get_path(params)?;
+		let host = base_url.host_str().unwrap_or_default().to_string();
+
+		let mut failed_attempts = 0;
+		loop {
+			if let Some(circuit_breakers) = self.circuit_breakers.as_ref() {
+				circuit_breakers.try_acquire(&host)?;
+			}
+
+			let result = self.http_client.send_request::<String, ResolvedPath>(
+				base_url.clone(),
+				method,
+				path.clone(),
+				query,
+				maybe_body.clone(),
+				headers,
+			);
+
+			let retry_after = match &result {
+				Ok((response, _)) if !is_retryable_response(response) => {
+					self.record_success(&host)?;
+					return result
+				},
+				Ok((response, _)) => retry_after(response),
+				Err(e) if !is_retryable_error(e) => return result,
+				Err(_) => None,
+			};
+
+			self.record_failure(&host)?;
+			failed_attempts += 1;
+			if failed_attempts >= self.retry_policy.max_attempts {
+				warn!("Request to {} failed {} times, giving up", base_url, failed_attempts);
+				return result
+			}
+
+			let backoff = retry_after
+				.map(|retry_after| retry_after.min(self.retry_policy.max_backoff))
+				.unwrap_or_else(|| self.retry_policy.backoff(failed_attempts));
+			debug!(
+				"Request to {} failed ({}), retrying in {:?}",
+				base_url,
+				describe(&result),
+				backoff
+			);
+			thread::sleep(backoff);
+		}
+	}
+}
+
+impl<H> RetryHttpClient<H> {
+	fn record_success(&self, host: &str) -> Result<(), Error> {
+		match self.circuit_breakers.as_ref() {
+			Some(circuit_breakers) => circuit_breakers.record_success(host),
+			None => Ok(()),
+		}
+	}
+
+	fn record_failure(&self, host: &str) -> Result<(), Error> {
+		match self.circuit_breakers.as_ref() {
+			Some(circuit_breakers) => circuit_breakers.record_failure(host),
+			None => Ok(()),
+		}
+	}
+}
+
+fn is_retryable_response(response: &Response) -> bool {
+	let status_code = response.status_code();
+	status_code.is_server_err() || u16::from(status_code) == TOO_MANY_REQUESTS
+}
+
+fn is_retryable_error(error: &Error) -> bool {
+	matches!(
+		error,
+		Error::HttpReqError(_) | Error::IoError(_) | Error::TimeoutError | Error::RequestError
+	)
+}
+
+fn retry_after(response: &Response) -> Option<Duration> {
+	// HTTP dates are not supported, the exponential backoff is used instead.
+	response
+		.headers()
+		.get(RETRY_AFTER)
+		.and_then(|seconds| seconds.trim().parse::<u64>().ok())
+		.map(Duration::from_secs)
+}
+
+fn describe(result: &Result<(Response, EncodedBody), Error>) -> String {
+	match result {
+		Ok((response, _)) => format!("status {}", u16::from(response.status_code())),
+		Err(e) => format!("{}", e),
+	}
+}
+
+/// Random number in `[0, 1)`, good enough for jitter. `RandomState` is randomly seeded
+/// for each instance, both in std and in the enclave.
+fn random_fraction() -> f64 {
+	let random = RandomState::new().build_hasher().finish();
+	(random >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		circuit_breaker::CircuitBreakerConfig,
+		mocks::http_client_mock::{HttpClientMock, ResponseBodyMock},
+	};
+	use core::assert_matches::assert_matches;
+
+	const BASE_URL: &str = "https://api.coingecko.com";
+
+	fn response(status_line: &str, headers: &str) -> Response {
+		let head = format!("HTTP/1.1 {}\r\n{}Content-Length: 0\r\n\r\n", status_line, headers);
+		Response::from_head(head.as_bytes()).unwrap()
+	}
+
+	fn fast_retry_policy(max_attempts: u32) -> RetryPolicy {
+		RetryPolicy {
+			max_attempts,
+			initial_backoff: Duration::ZERO,
+			max_backoff: Duration::from_millis(10),
+			jitter: false,
+		}
+	}
+
+	fn send(client: &RetryHttpClient<HttpClientMock>) -> Result<(Response, EncodedBody), Error> {
+		client.send_request::<String, ResponseBodyMock>(
+			Url::parse(BASE_URL).unwrap(),
+			Method::GET,
+			"api/v3/ping".to_string(),
+			None,
+			None,
+			None,
+		)
+	}
+
+	#[test]
+	fn server_errors_are_retried_until_success() {
+		let http_client = HttpClientMock::with_sequence(vec![
+			Ok(response("503 Service Unavailable", "")),
+			Err(Error::TimeoutError),
+			Ok(response("200 OK", "")),
+		]);
+		let client = RetryHttpClient::new(http_client, fast_retry_policy(3), None);
+
+		let (response, _) = send(&client).unwrap();
+
+		assert!(response.status_code().is_success());
+		assert_eq!(3, client.inner().number_of_requests());
+	}
+
+	#[test]
+	fn last_response_is_returned_when_attempts_are_exhausted() {
+		let http_client = HttpClientMock::with_sequence(vec![
+			Ok(response("500 Internal Server Error", "")),
+			Ok(response("502 Bad Gateway", "")),
+			Ok(response("200 OK", "")),
+		]);
+		let client = RetryHttpClient::new(http_client, fast_retry_policy(2), None);
+
+		let (response, _) = send(&client).unwrap();
+
+		assert_eq!(502, u16::from(response.status_code()));
+		assert_eq!(2, client.inner().number_of_requests());
+	}
+
+	#[test]
+	fn client_errors_are_not_retried() {
+		let http_client = HttpClientMock::with_sequence(vec![
+			Ok(response("404 Not Found", "")),
+			Ok(response("200 OK", "")),
+		]);
+		let client = RetryHttpClient::new(http_client, fast_retry_policy(3), None);
+
+		let (response, _) = send(&client).unwrap();
+
+		assert_eq!(404, u16::from(response.status_code()));
+		assert_eq!(1, client.inner().number_of_requests());
+	}
+
+	#[test]
+	fn too_many_requests_are_retried_honouring_retry_after() {
+		let http_client = HttpClientMock::with_sequence(vec![
+			Ok(response("429 Too Many Requests", "Retry-After: 0\r\n")),
+			Ok(response("200 OK", "")),
+		]);
+		let client = RetryHttpClient::new(
+			http_client,
+			RetryPolicy { initial_backoff: Duration::from_secs(60), ..fast_retry_policy(2) },
+			None,
+		);
+
+		// With the 60s backoff the test would block, the `Retry-After` of 0s is used instead.
+		assert!(send(&client).unwrap().0.status_code().is_success());
+		assert_eq!(
+			Some(Duration::from_secs(120)),
+			retry_after(&response("429 Too Many Requests", "Retry-After: 120\r\n"))
+		);
+	}
+
+	#[test]
+	fn backoff_grows_exponentially_up_to_the_maximum() {
+		let policy = RetryPolicy {
+			max_attempts: 10,
+			initial_backoff: Duration::from_millis(100),
+			max_backoff: Duration::from_millis(1000),
+			jitter: false,
+		};
+
+		assert_eq!(Duration::from_millis(100), policy.backoff(1));
+		assert_eq!(Duration::from_millis(200), policy.backoff(2));
+		assert_eq!(Duration::from_millis(800), policy.backoff(4));
+		assert_eq!(Duration::from_millis(1000), policy.backoff(5));
+		assert_eq!(Duration::from_millis(1000), policy.backoff(100));
+	}
+
+	#[test]
+	fn jittered_backoff_is_between_half_and_full_backoff() {
+		let policy = RetryPolicy { jitter: true, ..RetryPolicy::default() };
+
+		for _ in 0..100 {
+			let backoff = policy.backoff(2);
+			assert!(backoff >= Duration::from_millis(500));
+			assert!(backoff <= Duration::from_millis(1000));
+		}
+	}
+
+	#[test]
+	fn open_circuit_rejects_requests_without_sending_them() {
+		let circuit_breakers = Arc::new(CircuitBreakers::new(CircuitBreakerConfig {
+			failure_threshold: 2,
+			cooldown: Duration::from_secs(60),
+		}));
+		let http_client = HttpClientMock::with_sequence(vec![
+			Ok(response("500 Internal Server Error", "")),
+			Ok(response("500 Internal Server Error", "")),
+			Ok(response("200 OK", "")),
+		]);
+		let client =
+			RetryHttpClient::new(http_client, fast_retry_policy(3), Some(circuit_breakers.clone()));
+
+		assert_matches!(send(&client), Err(Error::CircuitOpen(host)) if host == "api.coingecko.com");
+		assert_eq!(2, client.inner().number_of_requests());
+		assert!(circuit_breakers.is_open("api.coingecko.com").unwrap());
+	}
+}
@@ -0,0 +1,133 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+use itp_enclave_api::direct_request::DirectRequest;
+use itp_rpc::RpcRequest;
+use itp_utils::ToHexPrefixed;
+use its_primitives::types::{
+	block_summary::{BlockSummary, OperationInclusion, OperationStatus},
+	BlockHash, BlockNumber, ShardIdentifier, SignedBlock,
+};
+use its_rpc_handler::constants::{
+	RPC_METHOD_NAME_GET_BLOCK_BY_HASH, RPC_METHOD_NAME_GET_BLOCK_BY_NUMBER,
+	RPC_METHOD_NAME_GET_BLOCK_OPERATIONS, RPC_METHOD_NAME_GET_OPERATION_INCLUSION,
+	RPC_METHOD_NAME_IS_OPERATION_PENDING,
+};
+use its_storage::interface::QueryBlocks;
+use jsonrpsee::{
+	types::{error::CallError, Error as RpcError},
+	RpcModule,
+};
+use log::*;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// RPC server module builder for querying sidechain blocks and the inclusion of trusted operations.
+///
+/// Blocks are returned without their encrypted state diff (see [`BlockSummary`]). Operations
+/// that are not included in a block yet are looked up in the trusted operation pool of the enclave.
+pub struct BlockQueryModuleBuilder<QueryBlocksFromStorage, Enclave> {
+	sidechain_block_query: Arc<QueryBlocksFromStorage>,
+	enclave: Arc<Enclave>,
+}
+
+impl<QueryBlocksFromStorage, Enclave> BlockQueryModuleBuilder<QueryBlocksFromStorage, Enclave>
+where
+	QueryBlocksFromStorage: QueryBlocks<SignedBlock> + Send + Sync + 'static,
+	Enclave: DirectRequest,
+{
+	pub fn new(sidechain_block_query: Arc<QueryBlocksFromStorage>, enclave: Arc<Enclave>) -> Self {
+		BlockQueryModuleBuilder { sidechain_block_query, enclave }
+	}
+
+	pub fn build(self) -> Result<RpcModule<Self>, RpcError> {
+		let mut module = RpcModule::new(self);
+
+		module.register_method(RPC_METHOD_NAME_GET_BLOCK_BY_HASH, |params, context| {
+			let block_hash = params.one::<BlockHash>()?;
+			let maybe_block = context
+				.sidechain_block_query
+				.block_by_hash(&block_hash)
+				.map_err(map_storage_error)?;
+			Ok(maybe_block.as_ref().map(BlockSummary::from))
+		})?;
+
+		module.register_method(RPC_METHOD_NAME_GET_BLOCK_BY_NUMBER, |params, context| {
+			let (shard, block_number) = params.one::<(ShardIdentifier, BlockNumber)>()?;
+			let maybe_block = context
+				.sidechain_block_query
+				.block_by_number(&shard, block_number)
+				.map_err(map_storage_error)?;
+			Ok(maybe_block.as_ref().map(BlockSummary::from))
+		})?;
+
+		module.register_method(RPC_METHOD_NAME_GET_BLOCK_OPERATIONS, |params, context| {
+			let block_hash = params.one::<BlockHash>()?;
+			let maybe_block = context
+				.sidechain_block_query
+				.block_by_hash(&block_hash)
+				.map_err(map_storage_error)?;
+			Ok(maybe_block.map(|b| b.block.block_data.signed_top_hashes))
+		})?;
+
+		module.register_method(RPC_METHOD_NAME_GET_OPERATION_INCLUSION, |params, context| {
+			let operation_hash = params.one::<BlockHash>()?;
+			let maybe_block = context
+				.sidechain_block_query
+				.block_including_operation(&operation_hash)
+				.map_err(map_storage_error)?;
+			if let Some(block) = maybe_block {
+				return Ok(OperationStatus::Included(OperationInclusion::from(&block)))
+			}
+			if is_operation_pending(context.enclave.as_ref(), &operation_hash)? {
+				return Ok(OperationStatus::Pending)
+			}
+			Ok(OperationStatus::Unknown)
+		})?;
+
+		Ok(module)
+	}
+}
+
+/// Ask the enclave whether the operation is still in its trusted operation pool.
+fn is_operation_pending<Enclave: DirectRequest>(
+	enclave: &Enclave,
+	operation_hash: &BlockHash,
+) -> Result<bool, CallError> {
+	let request = RpcRequest::compose_jsonrpc_call(
+		RPC_METHOD_NAME_IS_OPERATION_PENDING.into(),
+		vec![operation_hash.to_hex()],
+	)
+	.map_err(|e| CallError::Failed(e.into()))?;
+	let response = enclave.rpc(request.into_bytes()).map_err(|e| {
+		error!("Failed to query the trusted operation pool: {:?}", e);
+		CallError::Failed(e.into())
+	})?;
+
+	// The enclave pads the response with whitespace, which the JSON parser skips.
+	serde_json::from_slice::<Value>(&response)
+		.ok()
+		.and_then(|response| response.get("result").and_then(Value::as_bool))
+		.ok_or_else(|| {
+			error!("Unexpected response of the trusted operation pool: {:?}", response);
+			CallError::Failed("Unexpected response of the trusted operation pool".into())
+		})
+}
+
+fn map_storage_error(e: its_storage::Error) -> CallError {
+	error!("Failed to query sidechain blocks from storage: {:?}", e);
+	CallError::Failed(e.into())
+}
@@ -15,13 +15,14 @@
 
 */
 
+use crate::block_query::BlockQueryModuleBuilder;
 use itp_enclave_api::direct_request::DirectRequest;
 use itp_rpc::RpcRequest;
 use itp_utils::ToHexPrefixed;
 use its_peer_fetch::block_fetch_server::BlockFetchServerModuleBuilder;
 use its_primitives::types::block::SignedBlock;
 use its_rpc_handler::constants::RPC_METHOD_NAME_IMPORT_BLOCKS;
-use its_storage::interface::FetchBlocks;
+use its_storage::interface::{FetchBlocks, QueryBlocks};
 use jsonrpsee::{
 	types::error::CallError,
 	ws_server::{RpcModule, WsServerBuilder},
@@ -30,6 +31,8 @@ use log::debug;
 use std::{net::SocketAddr, sync::Arc};
 use tokio::net::ToSocketAddrs;
 
+pub mod block_query;
+
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
@@ -42,12 +45,13 @@ pub async fn run_server<Enclave, FetchSidechainBlocks>(
 ) -> anyhow::Result<SocketAddr>
 where
 	Enclave: DirectRequest,
-	FetchSidechainBlocks: FetchBlocks<SignedBlock> + Send + Sync + 'static,
+	FetchSidechainBlocks:
+		FetchBlocks<SignedBlock> + QueryBlocks<SignedBlock> + Send + Sync + 'static,
 {
 	let mut server = WsServerBuilder::default().build(addr).await?;
 
 	// FIXME: import block should be moved to trusted side.
-	let mut import_sidechain_block_module = RpcModule::new(enclave);
+	let mut import_sidechain_block_module = RpcModule::new(enclave.clone());
 	import_sidechain_block_module.register_method(
 		RPC_METHOD_NAME_IMPORT_BLOCKS,
 		|params, enclave| {
@@ -66,6 +70,12 @@ where
 	)?;
 	server.register_module(import_sidechain_block_module).unwrap();
 
+	let query_sidechain_blocks_module =
+		BlockQueryModuleBuilder::new(sidechain_block_fetcher.clone(), enclave.clone())
+			.build()
+			.map_err(|e| CallError::Failed(e.to_string().into()))?;
+	server.register_module(query_sidechain_blocks_module).unwrap();
+
 	let fetch_sidechain_blocks_module = BlockFetchServerModuleBuilder::new(sidechain_block_fetcher)
 		.build()
 		.map_err(|e| CallError::Failed(e.to_string().into()))?; // `to_string` necessary due to no all errors implementing Send + Sync.
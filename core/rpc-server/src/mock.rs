@@ -20,15 +20,24 @@ use itp_rpc::RpcResponse;
 use itp_utils::ToHexPrefixed;
 use its_primitives::{
 	traits::ShardIdentifierFor,
-	types::{BlockHash, SignedBlock, SignedBlock as SignedSidechainBlock},
+	types::{BlockHash, BlockNumber, SignedBlock, SignedBlock as SignedSidechainBlock},
 };
-use its_storage::interface::FetchBlocks;
+use its_rpc_handler::constants::RPC_METHOD_NAME_IS_OPERATION_PENDING;
+use its_storage::interface::{FetchBlocks, QueryBlocks};
 use parity_scale_codec::Encode;
 
 pub struct TestEnclave;
 
+/// Hash of the only operation in the trusted operation pool of the [`TestEnclave`].
+pub const PENDING_OPERATION_HASH: BlockHash = BlockHash::repeat_byte(1);
+
 impl DirectRequest for TestEnclave {
-	fn rpc(&self, _request: Vec<u8>) -> EnclaveResult<Vec<u8>> {
+	fn rpc(&self, request: Vec<u8>) -> EnclaveResult<Vec<u8>> {
+		let request = String::from_utf8(request).unwrap();
+		if request.contains(RPC_METHOD_NAME_IS_OPERATION_PENDING) {
+			let is_pending = request.contains(&PENDING_OPERATION_HASH.to_hex());
+			return Ok(format!(r#"{{"jsonrpc":"2.0","result":{},"id":1}}   "#, is_pending).into())
+		}
 		Ok(RpcResponse { jsonrpc: "mock_response".into(), result: "null".to_hex(), id: 1 }.encode())
 	}
 }
@@ -53,3 +62,24 @@ impl FetchBlocks<SignedSidechainBlock> for MockSidechainBlockFetcher {
 		Ok(Vec::new())
 	}
 }
+
+impl QueryBlocks<SignedSidechainBlock> for MockSidechainBlockFetcher {
+	fn block_by_hash(&self, _block_hash: &BlockHash) -> its_storage::Result<Option<SignedBlock>> {
+		Ok(None)
+	}
+
+	fn block_by_number(
+		&self,
+		_shard_identifier: &ShardIdentifierFor<SignedBlock>,
+		_block_number: BlockNumber,
+	) -> its_storage::Result<Option<SignedBlock>> {
+		Ok(None)
+	}
+
+	fn block_including_operation(
+		&self,
+		_operation_hash: &BlockHash,
+	) -> its_storage::Result<Option<SignedBlock>> {
+		Ok(None)
+	}
+}
@@ -16,9 +16,16 @@
 */
 
 use super::*;
-use crate::mock::MockSidechainBlockFetcher;
+use crate::mock::{MockSidechainBlockFetcher, PENDING_OPERATION_HASH};
 use itp_rpc::RpcResponse;
-use its_rpc_handler::constants::RPC_METHOD_NAME_IMPORT_BLOCKS;
+use its_primitives::types::{
+	block_summary::{BlockSummary, OperationStatus},
+	BlockHash, ShardIdentifier,
+};
+use its_rpc_handler::constants::{
+	RPC_METHOD_NAME_GET_BLOCK_BY_NUMBER, RPC_METHOD_NAME_GET_OPERATION_INCLUSION,
+	RPC_METHOD_NAME_IMPORT_BLOCKS,
+};
 use its_test::sidechain_block_builder::{SidechainBlockBuilder, SidechainBlockBuilderTrait};
 use jsonrpsee::{
 	types::{to_json_value, traits::Client},
@@ -54,3 +61,53 @@ async fn test_client_calls() {
 
 	assert!(RpcResponse::decode(&mut response.as_slice()).is_ok());
 }
+
+#[tokio::test]
+async fn querying_unknown_block_returns_none() {
+	init();
+	let addr =
+		run_server("127.0.0.1:0", Arc::new(TestEnclave), Arc::new(MockSidechainBlockFetcher))
+			.await
+			.unwrap();
+
+	let url = format!("ws://{}", addr);
+	let client = WsClientBuilder::default().build(&url).await.unwrap();
+	let response: Option<BlockSummary> = client
+		.request(
+			RPC_METHOD_NAME_GET_BLOCK_BY_NUMBER,
+			vec![to_json_value((ShardIdentifier::default(), 1u64)).unwrap()].into(),
+		)
+		.await
+		.unwrap();
+
+	assert!(response.is_none());
+}
+
+#[tokio::test]
+async fn operation_inclusion_tells_pending_from_unknown_operations() {
+	init();
+	let addr =
+		run_server("127.0.0.1:0", Arc::new(TestEnclave), Arc::new(MockSidechainBlockFetcher))
+			.await
+			.unwrap();
+
+	let url = format!("ws://{}", addr);
+	let client = WsClientBuilder::default().build(&url).await.unwrap();
+	let pending_status: OperationStatus = client
+		.request(
+			RPC_METHOD_NAME_GET_OPERATION_INCLUSION,
+			vec![to_json_value(PENDING_OPERATION_HASH).unwrap()].into(),
+		)
+		.await
+		.unwrap();
+	let unknown_status: OperationStatus = client
+		.request(
+			RPC_METHOD_NAME_GET_OPERATION_INCLUSION,
+			vec![to_json_value(BlockHash::repeat_byte(2)).unwrap()].into(),
+		)
+		.await
+		.unwrap();
+
+	assert_eq!(OperationStatus::Pending, pending_status);
+	assert_eq!(OperationStatus::Unknown, unknown_status);
+}
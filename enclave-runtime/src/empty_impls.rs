@@ -26,10 +26,9 @@ pub extern "C" fn test_main_entrance() -> sgx_types::size_t {
 #[cfg(not(feature = "teeracle"))]
 #[no_mangle]
 pub unsafe extern "C" fn update_market_data_xt(
-	_crypto_currency_ptr: *const u8,
-	_crypto_currency_size: u32,
-	_fiat_currency_ptr: *const u8,
-	_fiat_currency_size: u32,
+	_trading_pairs_ptr: *const u8,
+	_trading_pairs_size: u32,
+	_max_deviation_percent: u32,
 	_unchecked_extrinsic: *mut u8,
 	_unchecked_extrinsic_size: u32,
 ) -> sgx_types::sgx_status_t {
@@ -49,3 +48,13 @@ pub unsafe extern "C" fn update_weather_data_xt(
 ) -> sgx_types::sgx_status_t {
 	unreachable!("Cannot update weather data, teeracle feature is not enabled.")
 }
+
+/// Empty Teeracle oracle source config implementation.
+#[cfg(not(feature = "teeracle"))]
+#[no_mangle]
+pub unsafe extern "C" fn set_oracle_source_configs(
+	_configs_json: *const u8,
+	_configs_json_size: u32,
+) -> sgx_types::sgx_status_t {
+	unreachable!("Cannot set oracle source configs, teeracle feature is not enabled.")
+}
@@ -34,6 +34,7 @@ pub enum Error {
 	NodeMetadataProvider(itp_node_api::metadata::provider::Error),
 	Sgx(sgx_status_t),
 	Consensus(its_sidechain::consensus_common::Error),
+	ValidateerFetch(its_sidechain::validateer_fetch::Error),
 	Stf(String),
 	StfStateHandler(itp_stf_state_handler::error::Error),
 	StfExecution(itp_stf_executor::error::Error),
@@ -47,6 +48,9 @@ pub enum Error {
 	MutexAccess,
 	Attestation(itp_attestation_handler::error::Error),
 	Metadata(itp_node_api_metadata::error::Error),
+	OAuthClientRegistry(itp_oauth_client_registry::Error),
+	AuditLog(itp_audit_log::Error),
+	Totp(itp_totp::Error),
 	Other(Box<dyn std::error::Error>),
 }
 
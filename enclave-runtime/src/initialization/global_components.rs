@@ -25,14 +25,21 @@ use crate::{
 		parachain::FullParachainHandler, solochain::FullSolochainHandler,
 	},
 	ocall::OcallApi,
-	rpc::rpc_response_channel::RpcResponseChannel,
+	rpc::{
+		event_subscriptions::{EventPublishingImportConfirmation, EventSubscriptionFilter},
+		header_subscriptions::HeaderPublishingImportConfirmation,
+		rpc_response_channel::RpcResponseChannel,
+	},
 	tls_ra::seal_handler::SealHandler,
 };
+#[cfg(feature = "teeracle")]
+use ita_oracle::{sealing::OracleSourceConfigSeal, OracleSourceConfigStore};
 use ita_sgx_runtime::Runtime;
 use ita_stf::{Getter, State as StfState, Stf, TrustedCallSigned};
 use itc_direct_rpc_server::{
 	rpc_connection_registry::ConnectionRegistry, rpc_responder::RpcResponder,
-	rpc_watch_extractor::RpcWatchExtractor, rpc_ws_handler::RpcWsHandler,
+	rpc_subscriptions::RpcSubscriptions, rpc_watch_extractor::RpcWatchExtractor,
+	rpc_ws_handler::RpcWsHandler,
 };
 use itc_parentchain::{
 	block_import_dispatcher::{
@@ -54,6 +61,7 @@ use itc_tls_websocket_server::{
 	config_provider::FromFileConfigProvider, ws_server::TungsteniteWsServer, ConnectionToken,
 };
 use itp_attestation_handler::IntelAttestationHandler;
+use itp_audit_log::{sealing::AuditLogHeadSeal, AuditLog};
 use itp_component_container::ComponentContainer;
 use itp_extrinsics_factory::ExtrinsicsFactory;
 use itp_import_queue::ImportQueue;
@@ -62,7 +70,10 @@ use itp_node_api::{
 	metadata::{provider::NodeMetadataRepository, NodeMetadata},
 };
 use itp_nonce_cache::NonceCache;
-use itp_sgx_crypto::{key_repository::KeyRepository, Aes, AesSeal, Rsa3072Seal};
+use itp_oauth_client_registry::{sealing::OAuthClientRegistrySeal, OAuthClientRegistry};
+use itp_sgx_crypto::{
+	key_repository::KeyRepository, Aes, AesSeal, RetiredAesKeysSeal, Rsa3072Seal, StateFileKeySeal,
+};
 use itp_stf_executor::{
 	enclave_signer::StfEnclaveSigner, executor::StfExecutor, getter_executor::GetterExecutor,
 	state_getter::StfStateGetter,
@@ -78,6 +89,7 @@ use itp_top_pool_author::{
 	api::SidechainApi,
 	author::{Author, AuthorTopFilter},
 };
+use itp_totp::{sealing::TotpSecretsSeal, TotpSecretStore};
 use itp_types::{Block as ParentchainBlock, SignedBlock as SignedParentchainBlock};
 use its_primitives::{
 	traits::{Block as SidechainBlockTrait, SignedBlock as SignedSidechainBlockTrait},
@@ -99,8 +111,11 @@ pub type EnclaveGetter = Getter;
 pub type EnclaveTrustedCallSigned = TrustedCallSigned;
 pub type EnclaveStf = Stf<EnclaveTrustedCallSigned, EnclaveGetter, StfState, Runtime>;
 pub type EnclaveStateKeyRepository = KeyRepository<Aes, AesSeal>;
+pub type EnclaveStateFileKeyRepository = KeyRepository<Aes, StateFileKeySeal>;
+pub type EnclaveRetiredStateKeysRepository = KeyRepository<Vec<Aes>, RetiredAesKeysSeal>;
 pub type EnclaveShieldingKeyRepository = KeyRepository<Rsa3072KeyPair, Rsa3072Seal>;
-pub type EnclaveStateFileIo = SgxStateFileIo<EnclaveStateKeyRepository, StfState>;
+pub type EnclaveStateFileIo =
+	SgxStateFileIo<EnclaveStateFileKeyRepository, EnclaveRetiredStateKeysRepository, StfState>;
 pub type EnclaveStateSnapshotRepository = StateSnapshotRepository<EnclaveStateFileIo>;
 pub type EnclaveStateObserver = StateObserver<StfState>;
 pub type EnclaveStateInitializer =
@@ -120,13 +135,20 @@ pub type EnclaveStfEnclaveSigner = StfEnclaveSigner<
 	EnclaveTopPoolAuthor,
 >;
 pub type EnclaveAttestationHandler = IntelAttestationHandler<EnclaveOCallApi>;
+pub type EnclaveOAuthClientRegistry = OAuthClientRegistry<OAuthClientRegistrySeal>;
+pub type EnclaveAuditLog = AuditLog<EnclaveOCallApi, AuditLogHeadSeal>;
+pub type EnclaveTotpSecretStore = TotpSecretStore<TotpSecretsSeal>;
+#[cfg(feature = "teeracle")]
+pub type EnclaveOracleSourceConfigStore = OracleSourceConfigStore<OracleSourceConfigSeal>;
 
 pub type EnclaveRpcConnectionRegistry = ConnectionRegistry<Hash, ConnectionToken>;
 pub type EnclaveRpcWsHandler =
 	RpcWsHandler<RpcWatchExtractor<Hash>, EnclaveRpcConnectionRegistry, Hash>;
 pub type EnclaveWebSocketServer = TungsteniteWsServer<EnclaveRpcWsHandler, FromFileConfigProvider>;
 pub type EnclaveRpcResponder = RpcResponder<EnclaveRpcConnectionRegistry, Hash, RpcResponseChannel>;
-pub type EnclaveSidechainApi = SidechainApi<ParentchainBlock>;
+pub type EnclaveHeaderSubscriptions = RpcSubscriptions<EnclaveRpcResponder>;
+pub type EnclaveEventSubscriptions = RpcSubscriptions<EnclaveRpcResponder, EventSubscriptionFilter>;
+pub type EnclaveSidechainApi = SidechainApi<ParentchainBlock, EnclaveStateObserver, EnclaveStf>;
 
 // Parentchain types
 pub type EnclaveLightClientSeal =
@@ -138,6 +160,7 @@ pub type EnclaveIndirectCallsExecutor = IndirectCallsExecutor<
 	EnclaveStfEnclaveSigner,
 	EnclaveTopPoolAuthor,
 	EnclaveNodeMetadataRepository,
+	EnclaveOAuthClientRegistry,
 	ShieldFundsAndCallWorkerFilter<ParentchainExtrinsicParser>,
 	EventCreator,
 >;
@@ -200,12 +223,19 @@ pub type EnclaveBlockImportConfirmationHandler = BlockImportConfirmationHandler<
 	EnclaveExtrinsicsFactory,
 	EnclaveValidatorAccessor,
 >;
+pub type EnclaveHeaderPublishingImportConfirmation =
+	HeaderPublishingImportConfirmation<EnclaveBlockImportConfirmationHandler, EnclaveRpcResponder>;
+pub type EnclaveEventPublishingImportConfirmation = EventPublishingImportConfirmation<
+	EnclaveHeaderPublishingImportConfirmation,
+	EnclaveRpcResponder,
+	EnclaveStateObserver,
+>;
 pub type EnclaveSidechainBlockSyncer = PeerBlockSync<
 	ParentchainBlock,
 	SignedSidechainBlock,
 	EnclaveSidechainBlockImporter,
 	EnclaveOCallApi,
-	EnclaveBlockImportConfirmationHandler,
+	EnclaveEventPublishingImportConfirmation,
 >;
 pub type EnclaveSidechainBlockImportQueueWorker = BlockImportQueueWorker<
 	ParentchainBlock,
@@ -232,6 +262,16 @@ pub type EnclaveOffchainWorkerExecutor = itc_offchain_worker_executor::executor:
 pub static GLOBAL_STATE_KEY_REPOSITORY_COMPONENT: ComponentContainer<EnclaveStateKeyRepository> =
 	ComponentContainer::new("State key repository");
 
+/// State file key repository, the key the state files are encrypted with
+pub static GLOBAL_STATE_FILE_KEY_REPOSITORY_COMPONENT: ComponentContainer<
+	EnclaveStateFileKeyRepository,
+> = ComponentContainer::new("State file key repository");
+
+/// Retired state keys repository, keys replaced by a rotation that still encrypt some state files
+pub static GLOBAL_RETIRED_STATE_KEYS_REPOSITORY_COMPONENT: ComponentContainer<
+	EnclaveRetiredStateKeysRepository,
+> = ComponentContainer::new("Retired state keys repository");
+
 /// Shielding key repository
 pub static GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT: ComponentContainer<
 	EnclaveShieldingKeyRepository,
@@ -261,6 +301,24 @@ pub static GLOBAL_TOP_POOL_AUTHOR_COMPONENT: ComponentContainer<EnclaveTopPoolAu
 pub static GLOBAL_ATTESTATION_HANDLER_COMPONENT: ComponentContainer<EnclaveAttestationHandler> =
 	ComponentContainer::new("Attestation handler");
 
+/// OAuth client registry
+pub static GLOBAL_OAUTH_CLIENT_REGISTRY_COMPONENT: ComponentContainer<EnclaveOAuthClientRegistry> =
+	ComponentContainer::new("OAuth client registry");
+
+/// Audit log
+pub static GLOBAL_AUDIT_LOG_COMPONENT: ComponentContainer<EnclaveAuditLog> =
+	ComponentContainer::new("Audit log");
+
+/// TOTP secret store
+pub static GLOBAL_TOTP_SECRET_STORE_COMPONENT: ComponentContainer<EnclaveTotpSecretStore> =
+	ComponentContainer::new("TOTP secret store");
+
+/// Oracle source config store
+#[cfg(feature = "teeracle")]
+pub static GLOBAL_ORACLE_SOURCE_CONFIG_STORE_COMPONENT: ComponentContainer<
+	EnclaveOracleSourceConfigStore,
+> = ComponentContainer::new("Oracle source config store");
+
 /// Parentchain component instances
 ///-------------------------------------------------------------------------------------------------
 
@@ -278,6 +336,14 @@ pub static GLOBAL_FULL_PARACHAIN_HANDLER_COMPONENT: ComponentContainer<FullParac
 pub static GLOBAL_RPC_WS_HANDLER_COMPONENT: ComponentContainer<EnclaveRpcWsHandler> =
 	ComponentContainer::new("rpc_ws_handler");
 
+/// Subscriptions to the headers of imported sidechain blocks.
+pub static GLOBAL_HEADER_SUBSCRIPTIONS_COMPONENT: ComponentContainer<EnclaveHeaderSubscriptions> =
+	ComponentContainer::new("header_subscriptions");
+
+/// Subscriptions to the STF events of imported sidechain blocks.
+pub static GLOBAL_EVENT_SUBSCRIPTIONS_COMPONENT: ComponentContainer<EnclaveEventSubscriptions> =
+	ComponentContainer::new("event_subscriptions");
+
 /// Sidechain import queue.
 pub static GLOBAL_SIDECHAIN_IMPORT_QUEUE_COMPONENT: ComponentContainer<
 	EnclaveSidechainBlockImportQueue,
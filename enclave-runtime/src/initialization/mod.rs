@@ -18,26 +18,38 @@
 pub mod global_components;
 pub mod parentchain;
 
+#[cfg(feature = "teeracle")]
+use crate::initialization::global_components::{
+	EnclaveOracleSourceConfigStore, GLOBAL_ORACLE_SOURCE_CONFIG_STORE_COMPONENT,
+};
 use crate::{
 	error::{Error, Result as EnclaveResult},
 	initialization::global_components::{
-		EnclaveBlockImportConfirmationHandler, EnclaveGetterExecutor, EnclaveOCallApi,
+		EnclaveAuditLog, EnclaveBlockImportConfirmationHandler,
+		EnclaveEventPublishingImportConfirmation, EnclaveEventSubscriptions, EnclaveGetterExecutor,
+		EnclaveHeaderPublishingImportConfirmation, EnclaveHeaderSubscriptions,
+		EnclaveOAuthClientRegistry, EnclaveOCallApi, EnclaveRetiredStateKeysRepository,
 		EnclaveRpcConnectionRegistry, EnclaveRpcResponder, EnclaveShieldingKeyRepository,
 		EnclaveSidechainApi, EnclaveSidechainBlockImportQueue,
 		EnclaveSidechainBlockImportQueueWorker, EnclaveSidechainBlockImporter,
-		EnclaveSidechainBlockSyncer, EnclaveStateFileIo, EnclaveStateHandler,
-		EnclaveStateInitializer, EnclaveStateKeyRepository, EnclaveStateObserver,
-		EnclaveStateSnapshotRepository, EnclaveStfEnclaveSigner, EnclaveTopPool,
-		EnclaveTopPoolAuthor, GLOBAL_ATTESTATION_HANDLER_COMPONENT, GLOBAL_OCALL_API_COMPONENT,
-		GLOBAL_RPC_WS_HANDLER_COMPONENT, GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT,
-		GLOBAL_SIDECHAIN_BLOCK_COMPOSER_COMPONENT, GLOBAL_SIDECHAIN_BLOCK_SYNCER_COMPONENT,
-		GLOBAL_SIDECHAIN_IMPORT_QUEUE_COMPONENT, GLOBAL_SIDECHAIN_IMPORT_QUEUE_WORKER_COMPONENT,
+		EnclaveSidechainBlockSyncer, EnclaveStateFileIo, EnclaveStateFileKeyRepository,
+		EnclaveStateHandler, EnclaveStateInitializer, EnclaveStateKeyRepository,
+		EnclaveStateObserver, EnclaveStateSnapshotRepository, EnclaveStfEnclaveSigner,
+		EnclaveTopPool, EnclaveTopPoolAuthor, EnclaveTotpSecretStore,
+		GLOBAL_ATTESTATION_HANDLER_COMPONENT, GLOBAL_AUDIT_LOG_COMPONENT,
+		GLOBAL_EVENT_SUBSCRIPTIONS_COMPONENT, GLOBAL_HEADER_SUBSCRIPTIONS_COMPONENT,
+		GLOBAL_OAUTH_CLIENT_REGISTRY_COMPONENT, GLOBAL_OCALL_API_COMPONENT,
+		GLOBAL_RETIRED_STATE_KEYS_REPOSITORY_COMPONENT, GLOBAL_RPC_WS_HANDLER_COMPONENT,
+		GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT, GLOBAL_SIDECHAIN_BLOCK_COMPOSER_COMPONENT,
+		GLOBAL_SIDECHAIN_BLOCK_SYNCER_COMPONENT, GLOBAL_SIDECHAIN_IMPORT_QUEUE_COMPONENT,
+		GLOBAL_SIDECHAIN_IMPORT_QUEUE_WORKER_COMPONENT, GLOBAL_STATE_FILE_KEY_REPOSITORY_COMPONENT,
 		GLOBAL_STATE_HANDLER_COMPONENT, GLOBAL_STATE_KEY_REPOSITORY_COMPONENT,
 		GLOBAL_STATE_OBSERVER_COMPONENT, GLOBAL_TOP_POOL_AUTHOR_COMPONENT,
-		GLOBAL_WEB_SOCKET_SERVER_COMPONENT,
+		GLOBAL_TOTP_SECRET_STORE_COMPONENT, GLOBAL_WEB_SOCKET_SERVER_COMPONENT,
 	},
 	ocall::OcallApi,
 	rpc::{rpc_response_channel::RpcResponseChannel, worker_api_direct::public_api_rpc_handler},
+	top_pool_persistence::restore_top_pool,
 	utils::{
 		get_extrinsic_factory_from_solo_or_parachain,
 		get_node_metadata_repository_from_solo_or_parachain,
@@ -48,6 +60,13 @@ use crate::{
 };
 use base58::ToBase58;
 use codec::Encode;
+#[cfg(feature = "teeracle")]
+use ita_oracle::{
+	sealing::{
+		create_sealed_if_absent as create_oracle_source_configs_if_absent, OracleSourceConfigSeal,
+	},
+	source_config::ALLOWED_ORACLE_DOMAINS,
+};
 use itc_direct_rpc_server::{
 	create_determine_watch, rpc_connection_registry::ConnectionRegistry,
 	rpc_ws_handler::RpcWsHandler,
@@ -57,10 +76,17 @@ use itc_tls_websocket_server::{
 	WebSocketServer,
 };
 use itp_attestation_handler::IntelAttestationHandler;
+use itp_audit_log::sealing::{
+	create_sealed_if_absent as create_audit_log_head_if_absent, AuditLogHeadSeal,
+};
 use itp_component_container::{ComponentGetter, ComponentInitializer};
+use itp_oauth_client_registry::sealing::{
+	create_sealed_if_absent as create_oauth_client_registry_if_absent, OAuthClientRegistrySeal,
+};
 use itp_primitives_cache::GLOBAL_PRIMITIVES_CACHE;
-use itp_settings::files::STATE_SNAPSHOTS_CACHE_SIZE;
-use itp_sgx_crypto::{aes, ed25519, rsa3072, AesSeal, Ed25519Seal, Rsa3072Seal};
+use itp_sgx_crypto::{
+	aes, ed25519, rsa3072, AesSeal, Ed25519Seal, RetiredAesKeysSeal, Rsa3072Seal, StateFileKeySeal,
+};
 use itp_sgx_io::StaticSealedIO;
 use itp_stf_state_handler::{
 	handle_state::HandleState, query_shard_state::QueryShardState,
@@ -68,14 +94,21 @@ use itp_stf_state_handler::{
 	state_snapshot_repository_loader::StateSnapshotRepositoryLoader, StateHandler,
 };
 use itp_top_pool::pool::Options as PoolOptions;
-use itp_top_pool_author::author::AuthorTopFilter;
-use itp_types::ShardIdentifier;
+use itp_top_pool_author::{author::AuthorTopFilter, traits::AuthorApi};
+use itp_totp::sealing::{
+	create_sealed_if_absent as create_totp_secrets_if_absent, TotpSecretsSeal,
+};
+use itp_types::{ShardIdentifier, StateSnapshotRetention, TopPoolLimits};
 use its_sidechain::block_composer::BlockComposer;
 use log::*;
 use sp_core::crypto::Pair;
 use std::{collections::HashMap, string::String, sync::Arc};
 
-pub(crate) fn init_enclave(mu_ra_url: String, untrusted_worker_url: String) -> EnclaveResult<()> {
+pub(crate) fn init_enclave(
+	mu_ra_url: String,
+	untrusted_worker_url: String,
+	snapshot_retention: StateSnapshotRetention,
+) -> EnclaveResult<()> {
 	// Initialize the logging environment in the enclave.
 	env_logger::init();
 
@@ -100,7 +133,47 @@ pub(crate) fn init_enclave(mu_ra_url: String, untrusted_worker_url: String) -> E
 		Arc::new(EnclaveStateKeyRepository::new(state_key, Arc::new(AesSeal)));
 	GLOBAL_STATE_KEY_REPOSITORY_COMPONENT.initialize(state_key_repository.clone());
 
-	let state_file_io = Arc::new(EnclaveStateFileIo::new(state_key_repository));
+	aes::create_retired_keys_sealed_if_absent().map_err(Error::Crypto)?;
+	let retired_state_keys = RetiredAesKeysSeal::unseal_from_static_file()?;
+	let retired_state_keys_repository = Arc::new(EnclaveRetiredStateKeysRepository::new(
+		retired_state_keys,
+		Arc::new(RetiredAesKeysSeal),
+	));
+	GLOBAL_RETIRED_STATE_KEYS_REPOSITORY_COMPONENT
+		.initialize(retired_state_keys_repository.clone());
+
+	// The state files are encrypted with a key of their own, such that it can be rotated without
+	// affecting the state key shared with the other validateers.
+	aes::create_state_file_key_sealed_if_absent().map_err(Error::Crypto)?;
+	let state_file_key = StateFileKeySeal::unseal_from_static_file()?;
+	let state_file_key_repository =
+		Arc::new(EnclaveStateFileKeyRepository::new(state_file_key, Arc::new(StateFileKeySeal)));
+	GLOBAL_STATE_FILE_KEY_REPOSITORY_COMPONENT.initialize(state_file_key_repository.clone());
+
+	create_oauth_client_registry_if_absent()?;
+	let oauth_client_registry =
+		Arc::new(EnclaveOAuthClientRegistry::load(Arc::new(OAuthClientRegistrySeal))?);
+	GLOBAL_OAUTH_CLIENT_REGISTRY_COMPONENT.initialize(oauth_client_registry);
+
+	create_totp_secrets_if_absent()?;
+	let totp_secret_store = Arc::new(EnclaveTotpSecretStore::load(Arc::new(TotpSecretsSeal))?);
+	GLOBAL_TOTP_SECRET_STORE_COMPONENT.initialize(totp_secret_store);
+
+	#[cfg(feature = "teeracle")]
+	{
+		create_oracle_source_configs_if_absent().map_err(|e| Error::Other(e.into()))?;
+		let oracle_source_config_store = Arc::new(
+			EnclaveOracleSourceConfigStore::load(
+				ALLOWED_ORACLE_DOMAINS,
+				Arc::new(OracleSourceConfigSeal),
+			)
+			.map_err(|e| Error::Other(e.into()))?,
+		);
+		GLOBAL_ORACLE_SOURCE_CONFIG_STORE_COMPONENT.initialize(oracle_source_config_store);
+	}
+
+	let state_file_io =
+		Arc::new(EnclaveStateFileIo::new(state_file_key_repository, retired_state_keys_repository));
 	let state_initializer =
 		Arc::new(EnclaveStateInitializer::new(shielding_key_repository.clone()));
 	let state_snapshot_repository_loader = StateSnapshotRepositoryLoader::<
@@ -109,7 +182,7 @@ pub(crate) fn init_enclave(mu_ra_url: String, untrusted_worker_url: String) -> E
 	>::new(state_file_io, state_initializer.clone());
 
 	let state_snapshot_repository =
-		state_snapshot_repository_loader.load_snapshot_repository(STATE_SNAPSHOTS_CACHE_SIZE)?;
+		state_snapshot_repository_loader.load_snapshot_repository(snapshot_retention)?;
 	let state_observer = initialize_state_observer(&state_snapshot_repository)?;
 	GLOBAL_STATE_OBSERVER_COMPONENT.initialize(state_observer.clone());
 
@@ -124,6 +197,11 @@ pub(crate) fn init_enclave(mu_ra_url: String, untrusted_worker_url: String) -> E
 	let ocall_api = Arc::new(OcallApi);
 	GLOBAL_OCALL_API_COMPONENT.initialize(ocall_api.clone());
 
+	create_audit_log_head_if_absent()?;
+	let audit_log =
+		Arc::new(EnclaveAuditLog::load(signer, ocall_api.clone(), Arc::new(AuditLogHeadSeal))?);
+	GLOBAL_AUDIT_LOG_COMPONENT.initialize(audit_log);
+
 	// For debug purposes, list shards. no problem to panic if fails.
 	let shards = state_handler.list_shards().unwrap();
 	debug!("found the following {} shards on disk:", shards.len());
@@ -152,13 +230,33 @@ pub(crate) fn init_enclave(mu_ra_url: String, untrusted_worker_url: String) -> E
 	let top_pool_author = create_top_pool_author(
 		connection_registry.clone(),
 		state_handler,
+		state_observer.clone(),
 		ocall_api.clone(),
 		shielding_key_repository,
 	);
 	GLOBAL_TOP_POOL_AUTHOR_COMPONENT.initialize(top_pool_author.clone());
 
+	let header_rpc_responder = Arc::new(EnclaveRpcResponder::new(
+		connection_registry.clone(),
+		Arc::new(RpcResponseChannel::default()),
+	));
+	let header_subscriptions = Arc::new(EnclaveHeaderSubscriptions::new(header_rpc_responder));
+	GLOBAL_HEADER_SUBSCRIPTIONS_COMPONENT.initialize(header_subscriptions.clone());
+
+	let event_rpc_responder = Arc::new(EnclaveRpcResponder::new(
+		connection_registry.clone(),
+		Arc::new(RpcResponseChannel::default()),
+	));
+	let event_subscriptions = Arc::new(EnclaveEventSubscriptions::new(event_rpc_responder));
+	GLOBAL_EVENT_SUBSCRIPTIONS_COMPONENT.initialize(event_subscriptions.clone());
+
 	let getter_executor = Arc::new(EnclaveGetterExecutor::new(state_observer));
-	let io_handler = public_api_rpc_handler(top_pool_author, getter_executor);
+	let io_handler = public_api_rpc_handler(
+		top_pool_author,
+		getter_executor,
+		header_subscriptions,
+		event_subscriptions,
+	);
 	let rpc_handler = Arc::new(RpcWsHandler::new(io_handler, watch_extractor, connection_registry));
 	GLOBAL_RPC_WS_HANDLER_COMPONENT.initialize(rpc_handler);
 
@@ -186,10 +284,14 @@ fn initialize_state_observer(
 	Ok(Arc::new(EnclaveStateObserver::from_map(states_map)))
 }
 
-pub(crate) fn init_enclave_sidechain_components() -> EnclaveResult<()> {
+pub(crate) fn init_enclave_sidechain_components(
+	top_pool_limits: TopPoolLimits,
+) -> EnclaveResult<()> {
 	let state_handler = GLOBAL_STATE_HANDLER_COMPONENT.get()?;
 	let ocall_api = GLOBAL_OCALL_API_COMPONENT.get()?;
 	let top_pool_author = GLOBAL_TOP_POOL_AUTHOR_COMPONENT.get()?;
+	top_pool_author.set_pool_limits(&top_pool_limits);
+	restore_top_pool()?;
 
 	let parentchain_block_import_dispatcher = get_triggered_dispatcher_from_solo_or_parachain()?;
 
@@ -216,6 +318,17 @@ pub(crate) fn init_enclave_sidechain_components() -> EnclaveResult<()> {
 			extrinsics_factory,
 			validator_accessor,
 		));
+	let sidechain_block_import_confirmation_handler =
+		Arc::new(EnclaveHeaderPublishingImportConfirmation::new(
+			sidechain_block_import_confirmation_handler,
+			GLOBAL_HEADER_SUBSCRIPTIONS_COMPONENT.get()?,
+		));
+	let sidechain_block_import_confirmation_handler =
+		Arc::new(EnclaveEventPublishingImportConfirmation::new(
+			sidechain_block_import_confirmation_handler,
+			GLOBAL_EVENT_SUBSCRIPTIONS_COMPONENT.get()?,
+			GLOBAL_STATE_OBSERVER_COMPONENT.get()?,
+		));
 
 	let sidechain_block_syncer = Arc::new(EnclaveSidechainBlockSyncer::new(
 		sidechain_block_importer,
@@ -264,6 +377,14 @@ pub(crate) fn init_direct_invocation_server(server_addr: String) -> EnclaveResul
 	Ok(())
 }
 
+/// Records the start of the audit log segment of this enclave run. The untrusted side persists
+/// the record, so its OCall bridge has to be initialized already.
+pub(crate) fn start_audit_log() -> EnclaveResult<()> {
+	let audit_log = GLOBAL_AUDIT_LOG_COMPONENT.get()?;
+	audit_log.log_started()?;
+	Ok(())
+}
+
 pub(crate) fn init_shard(shard: ShardIdentifier) -> EnclaveResult<()> {
 	let state_handler = GLOBAL_STATE_HANDLER_COMPONENT.get()?;
 	let _ = state_handler.initialize_shard(shard)?;
@@ -274,13 +395,14 @@ pub(crate) fn init_shard(shard: ShardIdentifier) -> EnclaveResult<()> {
 pub fn create_top_pool_author(
 	connection_registry: Arc<EnclaveRpcConnectionRegistry>,
 	state_handler: Arc<EnclaveStateHandler>,
+	state_observer: Arc<EnclaveStateObserver>,
 	ocall_api: Arc<EnclaveOCallApi>,
 	shielding_key_repository: Arc<EnclaveShieldingKeyRepository>,
 ) -> Arc<EnclaveTopPoolAuthor> {
 	let response_channel = Arc::new(RpcResponseChannel::default());
 	let rpc_responder = Arc::new(EnclaveRpcResponder::new(connection_registry, response_channel));
 
-	let side_chain_api = Arc::new(EnclaveSidechainApi::new());
+	let side_chain_api = Arc::new(EnclaveSidechainApi::new(state_observer));
 	let top_pool =
 		Arc::new(EnclaveTopPool::create(PoolOptions::default(), side_chain_api, rpc_responder));
 
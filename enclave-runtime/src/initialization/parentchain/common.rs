@@ -25,9 +25,9 @@ use crate::{
 			EnclaveParentchainBlockImportQueue, EnclaveParentchainBlockImporter,
 			EnclaveParentchainEventImportQueue, EnclaveParentchainSigner, EnclaveStfExecutor,
 			EnclaveTriggeredParentchainBlockImportDispatcher, EnclaveValidatorAccessor,
-			GLOBAL_OCALL_API_COMPONENT, GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT,
-			GLOBAL_STATE_HANDLER_COMPONENT, GLOBAL_STATE_OBSERVER_COMPONENT,
-			GLOBAL_TOP_POOL_AUTHOR_COMPONENT,
+			GLOBAL_OAUTH_CLIENT_REGISTRY_COMPONENT, GLOBAL_OCALL_API_COMPONENT,
+			GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT, GLOBAL_STATE_HANDLER_COMPONENT,
+			GLOBAL_STATE_OBSERVER_COMPONENT, GLOBAL_TOP_POOL_AUTHOR_COMPONENT,
 		},
 		EnclaveStfEnclaveSigner,
 	},
@@ -50,6 +50,7 @@ pub(crate) fn create_parentchain_block_importer(
 	let top_pool_author = GLOBAL_TOP_POOL_AUTHOR_COMPONENT.get()?;
 	let shielding_key_repository = GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT.get()?;
 	let ocall_api = GLOBAL_OCALL_API_COMPONENT.get()?;
+	let oauth_client_registry = GLOBAL_OAUTH_CLIENT_REGISTRY_COMPONENT.get()?;
 
 	let stf_enclave_signer = Arc::new(EnclaveStfEnclaveSigner::new(
 		state_observer,
@@ -62,6 +63,7 @@ pub(crate) fn create_parentchain_block_importer(
 		stf_enclave_signer,
 		top_pool_author,
 		node_metadata_repository,
+		oauth_client_registry,
 	));
 	Ok(EnclaveParentchainBlockImporter::new(
 		validator_access,
@@ -34,6 +34,7 @@ use crate::{
 	initialization::global_components::{
 		GLOBAL_FULL_PARACHAIN_HANDLER_COMPONENT, GLOBAL_FULL_SOLOCHAIN_HANDLER_COMPONENT,
 		GLOBAL_SIDECHAIN_IMPORT_QUEUE_COMPONENT, GLOBAL_STATE_HANDLER_COMPONENT,
+		GLOBAL_TOP_POOL_AUTHOR_COMPONENT,
 	},
 	rpc::worker_api_direct::sidechain_io_handler,
 	utils::{
@@ -41,7 +42,7 @@ use crate::{
 		get_triggered_dispatcher_from_solo_or_parachain, utf8_str_from_raw, DecodeRaw,
 	},
 };
-use codec::{alloc::string::String, Decode};
+use codec::{alloc::string::String, Decode, Encode};
 use itc_parentchain::block_import_dispatcher::{
 	triggered_dispatcher::TriggerParentchainBlockImport, DispatchBlockImport,
 };
@@ -52,11 +53,14 @@ use itp_nonce_cache::{MutateNonce, Nonce, GLOBAL_NONCE_CACHE};
 use itp_settings::worker_mode::{ProvideWorkerMode, WorkerMode, WorkerModeProvider};
 use itp_sgx_crypto::{ed25519, Ed25519Seal, Rsa3072Seal};
 use itp_sgx_io::StaticSealedIO;
+use itp_stf_state_handler::verify_state::VerifyState;
 use itp_storage::{StorageProof, StorageProofChecker};
-use itp_types::{ShardIdentifier, SignedBlock};
+use itp_top_pool_author::traits::AuthorApi;
+use itp_types::{ShardIdentifier, SignedBlock, StateSnapshotRetention, TopPoolLimits};
 use itp_utils::write_slice_and_whitespace_pad;
 use log::*;
-use sgx_types::sgx_status_t;
+use sgx_crypto_helper::rsa3072::Rsa3072PubKey;
+use sgx_types::{c_int, sgx_status_t};
 use sp_core::crypto::Pair;
 use sp_runtime::traits::BlakeTwo256;
 use std::{boxed::Box, slice, vec::Vec};
@@ -73,9 +77,12 @@ mod utils;
 
 pub mod error;
 pub mod rpc;
+mod state_archive;
+mod state_key_rotation;
 mod sync;
 mod tls_ra;
 pub mod top_pool_execution;
+pub mod top_pool_persistence;
 
 #[cfg(feature = "teeracle")]
 pub mod teeracle;
@@ -93,6 +100,8 @@ pub unsafe extern "C" fn init(
 	mu_ra_addr_size: u32,
 	untrusted_worker_addr: *const u8,
 	untrusted_worker_addr_size: u32,
+	snapshot_retention: *const u8,
+	snapshot_retention_size: u32,
 ) -> sgx_status_t {
 	let mu_ra_url =
 		match String::decode(&mut slice::from_raw_parts(mu_ra_addr, mu_ra_addr_size as usize))
@@ -112,12 +121,33 @@ pub unsafe extern "C" fn init(
 		Err(e) => return e.into(),
 	};
 
-	match initialization::init_enclave(mu_ra_url, untrusted_worker_url) {
+	let snapshot_retention = match StateSnapshotRetention::decode(&mut slice::from_raw_parts(
+		snapshot_retention,
+		snapshot_retention_size as usize,
+	))
+	.map_err(Error::Codec)
+	{
+		Ok(retention) => retention,
+		Err(e) => return e.into(),
+	};
+
+	match initialization::init_enclave(mu_ra_url, untrusted_worker_url, snapshot_retention) {
 		Err(e) => e.into(),
 		Ok(()) => sgx_status_t::SGX_SUCCESS,
 	}
 }
 
+/// Start the audit log, once the untrusted side is ready to persist its records.
+#[no_mangle]
+pub unsafe extern "C" fn start_audit_log() -> sgx_status_t {
+	if let Err(e) = initialization::start_audit_log() {
+		error!("Failed to start the audit log: {:?}", e);
+		return e.into()
+	}
+
+	sgx_status_t::SGX_SUCCESS
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn get_rsa_encryption_pubkey(
 	pubkey: *mut u8,
@@ -251,10 +281,12 @@ pub unsafe extern "C" fn call_rpc_methods(
 
 fn sidechain_rpc_int(request: &str) -> Result<String> {
 	let sidechain_block_import_queue = GLOBAL_SIDECHAIN_IMPORT_QUEUE_COMPONENT.get()?;
+	let top_pool_author = GLOBAL_TOP_POOL_AUTHOR_COMPONENT.get()?;
 
-	let io = sidechain_io_handler(move |signed_block| {
-		sidechain_block_import_queue.push_single(signed_block)
-	});
+	let io = sidechain_io_handler(
+		move |signed_block| sidechain_block_import_queue.push_single(signed_block),
+		move |operation_hash| top_pool_author.is_pending(operation_hash),
+	);
 
 	// note: errors are still returned as Option<String>
 	Ok(io
@@ -268,8 +300,21 @@ fn sidechain_rpc_int(request: &str) -> Result<String> {
 /// (parentchain components) have been initialized (because we need the parentchain
 /// block import dispatcher).
 #[no_mangle]
-pub unsafe extern "C" fn init_enclave_sidechain_components() -> sgx_status_t {
-	if let Err(e) = initialization::init_enclave_sidechain_components() {
+pub unsafe extern "C" fn init_enclave_sidechain_components(
+	top_pool_limits: *const u8,
+	top_pool_limits_size: u32,
+) -> sgx_status_t {
+	let top_pool_limits = match TopPoolLimits::decode(&mut slice::from_raw_parts(
+		top_pool_limits,
+		top_pool_limits_size as usize,
+	))
+	.map_err(Error::Codec)
+	{
+		Ok(limits) => limits,
+		Err(e) => return e.into(),
+	};
+
+	if let Err(e) = initialization::init_enclave_sidechain_components(top_pool_limits) {
 		error!("Failed to initialize sidechain components: {:?}", e);
 		return sgx_status_t::SGX_ERROR_UNEXPECTED
 	}
@@ -345,6 +390,121 @@ pub unsafe extern "C" fn init_shard(shard: *const u8, shard_size: u32) -> sgx_st
 	sgx_status_t::SGX_SUCCESS
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn export_state(
+	shard: *const u8,
+	shard_size: u32,
+	target_shielding_key: *const u8,
+	target_shielding_key_size: u32,
+	archive: *mut u8,
+	archive_size: u32,
+) -> sgx_status_t {
+	let shard_identifier =
+		ShardIdentifier::from_slice(slice::from_raw_parts(shard, shard_size as usize));
+
+	let target_shielding_key: Rsa3072PubKey = match serde_json::from_slice(slice::from_raw_parts(
+		target_shielding_key,
+		target_shielding_key_size as usize,
+	)) {
+		Ok(k) => k,
+		Err(e) => return Error::Other(e.into()).into(),
+	};
+
+	let encoded_archive =
+		match state_archive::export_state(&shard_identifier, &target_shielding_key) {
+			Ok(a) => a,
+			Err(e) => {
+				error!("Failed to export state of shard ({:?}): {:?}", shard_identifier, e);
+				return e.into()
+			},
+		};
+
+	// Length-prefix the archive, so the untrusted side can strip the padding again.
+	let archive_slice = slice::from_raw_parts_mut(archive, archive_size as usize);
+	if let Err(e) = write_slice_and_whitespace_pad(archive_slice, encoded_archive.encode()) {
+		return Error::Other(Box::new(e)).into()
+	};
+
+	sgx_status_t::SGX_SUCCESS
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn import_state(
+	archive: *const u8,
+	archive_size: u32,
+	shard: *mut u8,
+	shard_size: u32,
+) -> sgx_status_t {
+	let archive = slice::from_raw_parts(archive, archive_size as usize);
+
+	let shard_identifier = match state_archive::import_state(archive) {
+		Ok(s) => s,
+		Err(e) => {
+			error!("Failed to import state archive: {:?}", e);
+			return e.into()
+		},
+	};
+
+	let shard_slice = slice::from_raw_parts_mut(shard, shard_size as usize);
+	if let Err(e) = write_slice_and_whitespace_pad(shard_slice, shard_identifier.encode()) {
+		return Error::Other(Box::new(e)).into()
+	};
+
+	sgx_status_t::SGX_SUCCESS
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn verify_state(
+	rollback: c_int,
+	reports: *mut u8,
+	reports_size: u32,
+) -> sgx_status_t {
+	let state_handler = match GLOBAL_STATE_HANDLER_COMPONENT.get() {
+		Ok(s) => s,
+		Err(e) => return Error::ComponentContainer(e).into(),
+	};
+
+	let verification_reports = match state_handler.verify_states(rollback == 1) {
+		Ok(r) => r,
+		Err(e) => {
+			error!("Failed to verify the state snapshots: {:?}", e);
+			return Error::StfStateHandler(e).into()
+		},
+	};
+
+	let reports_slice = slice::from_raw_parts_mut(reports, reports_size as usize);
+	if let Err(e) = write_slice_and_whitespace_pad(reports_slice, verification_reports.encode()) {
+		return Error::Other(Box::new(e)).into()
+	};
+
+	sgx_status_t::SGX_SUCCESS
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rotate_state_key() -> sgx_status_t {
+	if let Err(e) = state_key_rotation::rotate_state_key() {
+		error!("Failed to rotate the state key: {:?}", e);
+		return e.into()
+	}
+
+	sgx_status_t::SGX_SUCCESS
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn re_encrypt_state(re_encrypted_snapshots: *mut u32) -> sgx_status_t {
+	let number_of_snapshots = match state_key_rotation::re_encrypt_state() {
+		Ok(n) => n,
+		Err(e) => {
+			error!("Failed to re-encrypt the state snapshots: {:?}", e);
+			return e.into()
+		},
+	};
+
+	*re_encrypted_snapshots = number_of_snapshots as u32;
+
+	sgx_status_t::SGX_SUCCESS
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn sync_parentchain(
 	blocks_to_sync: *const u8,
@@ -0,0 +1,19 @@
+use itp_audit_log::{AppendAuditEvent, AuditEvent};
+use itp_component_container::ComponentGetter;
+use crate::initialization::global_components::GLOBAL_AUDIT_LOG_COMPONENT;
+
+/// Records an authentication event in the enclave's audit log.
+/// A failure to audit must not break the authorization flow, so errors are only logged.
+pub fn audit(event: AuditEvent) {
+    let audit_log = match GLOBAL_AUDIT_LOG_COMPONENT.get() {
+        Ok(audit_log) => audit_log,
+        Err(e) => {
+            log::error!("Could not access the audit log: {:?}", e);
+            return
+        }
+    };
+
+    if let Err(e) = audit_log.append_event(event) {
+        log::error!("Could not append to the audit log: {:?}", e);
+    }
+}
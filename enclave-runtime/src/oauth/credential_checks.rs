@@ -1,16 +1,16 @@
 extern crate sgx_tstd as sgx;
 use std::string::{String, ToString};
-use bcrypt::{hash_with_salt};
+use bcrypt::{hash_with_salt, verify};
 use bcrypt::Version::TwoB;
+use itp_component_container::ComponentGetter;
+use itp_oauth_client_registry::{AccessOAuthClients, OAuthClient, OAuthGrantType};
+use crate::initialization::global_components::GLOBAL_OAUTH_CLIENT_REGISTRY_COMPONENT;
 use super::types::*;
 
 
 static USERNAME: &str = r#"user"#;
 static PASSWORD: &str = r#"asdf"#;
 
-static CLIENT_ID: &str = r#"client_id"#;
-static CLIENT_SECRET: &str = r#"client_secret"#;
-
 // Some salt... 
 // (It says: "helloworlditsme!" - you're welcome)
 static SALT: [u8; 16] = [0x68, 0x65, 0x6C, 0x6C, 0x6F, 0x77, 0x6F, 0x72, 0x6C, 0x64, 0x69, 0x74, 0x73, 0x6D, 0x65, 0x21];
@@ -22,13 +22,63 @@ pub fn hash_value(value: &str) -> String {
     }
 }
 
-pub fn verify_client(client_id: &str, client_secret: &str) -> Result<(), (ErrorCode, String, String)> {
-    match client_id == CLIENT_ID && client_secret == hash_value(CLIENT_SECRET) {
-            true => Ok(()),
-            false => Err((ErrorCode::InvalidGrant,
-                "Client credentials not valid".to_string(),
-                "https://datatracker.ietf.org/doc/html/rfc6749#section-2.1".to_string()))
+/// Clients are registered on the parentchain, see the `RegisterOAuthClient` indirect call.
+/// Only the bcrypt hash of the secret is registered, so the presented (plaintext) secret is
+/// hashed and compared against it (in constant time).
+/// A requested redirect URI and scopes have to be registered for the client as well.
+pub fn verify_client(access_token_request: &AccessTokenRequest) -> Result<(), (ErrorCode, String, String)> {
+    match GLOBAL_OAUTH_CLIENT_REGISTRY_COMPONENT.get() {
+        Ok(registry) => verify_client_in(registry.as_ref(), access_token_request),
+        Err(e) => {
+            log::error!("Could not access the OAuth client registry: {:?}", e);
+            Err(invalid_client())
+        }
+    }
+}
+
+pub fn verify_client_in<Registry: AccessOAuthClients>(registry: &Registry, access_token_request: &AccessTokenRequest) -> Result<(), (ErrorCode, String, String)> {
+    let client = authenticate_client_in(registry, &access_token_request.client_id, &access_token_request.client_secret)?;
+
+    match to_registry_grant_type(&access_token_request.grant_type) {
+        Some(grant_type) if client.allows_grant(grant_type) => {}
+        _ => return Err((ErrorCode::UnauthorizedClient,
+                "Client is not authorized to use this grant type".to_string(),
+                "https://datatracker.ietf.org/doc/html/rfc6749#section-5.2".to_string()))
+    }
+
+    let redirect_uri = access_token_request.redirect_uri.as_str();
+    if !redirect_uri.is_empty() && !client.allows_redirect_uri(redirect_uri) {
+        return Err((ErrorCode::InvalidRequest,
+                format!("Redirect URI {} is not registered for the client", redirect_uri),
+                "https://datatracker.ietf.org/doc/html/rfc6749#section-3.1.2.4".to_string()))
+    }
+
+    // Scopes are a space-delimited list, see:
+    // https://datatracker.ietf.org/doc/html/rfc6749#section-3.3
+    match access_token_request.scope.split_whitespace().find(|scope| !client.allows_scope(scope)) {
+        Some(scope) => Err((ErrorCode::InvalidScope,
+                format!("Scope {} is not allowed for the client", scope),
+                "https://datatracker.ietf.org/doc/html/rfc6749#section-5.2".to_string())),
+        None => Ok(())
+    }
+}
+
+/// Only checks the client credentials, e.g. for the revocation of a token.
+pub fn authenticate_client(client_id: &str, client_secret: &str) -> Result<(), (ErrorCode, String, String)> {
+    match GLOBAL_OAUTH_CLIENT_REGISTRY_COMPONENT.get() {
+        Ok(registry) => authenticate_client_in(registry.as_ref(), client_id, client_secret).map(|_| ()),
+        Err(e) => {
+            log::error!("Could not access the OAuth client registry: {:?}", e);
+            Err(invalid_client())
         }
+    }
+}
+
+fn authenticate_client_in<Registry: AccessOAuthClients>(registry: &Registry, client_id: &str, client_secret: &str) -> Result<OAuthClient, (ErrorCode, String, String)> {
+    match get_registered_client(registry, client_id) {
+        Some(client) if verify(client_secret, &client.client_secret_hash).unwrap_or(false) => Ok(client),
+        _ => Err(invalid_client())
+    }
 }
 
 pub fn verify_user(username: &str, password: &str) -> Result<(), (ErrorCode, String, String)> {
@@ -38,4 +88,32 @@ pub fn verify_user(username: &str, password: &str) -> Result<(), (ErrorCode, Str
                 "User credentials not valid".to_string(),
                 "https://datatracker.ietf.org/doc/html/rfc6749#section-4.3".to_string()))
         }
+}
+
+fn invalid_client() -> (ErrorCode, String, String) {
+    (ErrorCode::InvalidGrant,
+        "Client credentials not valid".to_string(),
+        "https://datatracker.ietf.org/doc/html/rfc6749#section-2.1".to_string())
+}
+
+fn get_registered_client<Registry: AccessOAuthClients>(registry: &Registry, client_id: &str) -> Option<OAuthClient> {
+    match registry.get_client(client_id) {
+        Ok(client) => client,
+        Err(e) => {
+            log::error!("Could not read from the OAuth client registry: {:?}", e);
+            None
+        }
+    }
+}
+
+fn to_registry_grant_type(grant_type: &GrantType) -> Option<OAuthGrantType> {
+    match grant_type {
+        GrantType::AuthorizationCode => Some(OAuthGrantType::AuthorizationCode),
+        GrantType::Implicit => Some(OAuthGrantType::Implicit),
+        // The second factor completes a password grant.
+        GrantType::ResourceOwnerPasswordCredentials | GrantType::MfaOtp => Some(OAuthGrantType::Password),
+        GrantType::ClientCredentials => Some(OAuthGrantType::ClientCredentials),
+        GrantType::RefreshToken => Some(OAuthGrantType::RefreshToken),
+        GrantType::Extension => None,
+    }
 }
\ No newline at end of file
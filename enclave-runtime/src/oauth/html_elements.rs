@@ -27,6 +27,27 @@ pub fn html_authorization_prompt(error: &str) -> String {
 }
 
 
+pub fn html_totp_prompt(mfa_token: &str) -> String {
+  format!(
+      "{}
+    <form action='/authorize' method='post'>
+      <input type='hidden' name='mfa_token' value='{}'>
+      <label for='totp_code'>Code</label>
+      <input type='text' id='totp_code' name='totp_code' inputmode='numeric' autocomplete='one-time-code' required>
+      <input type='submit' value='Verify'>
+    </form>
+  </body>
+</html>",
+      HTML_TOTP_PROMPT, mfa_token
+    )
+}
+
+
+pub fn html_mfa_enrollment(content: &str) -> String {
+  format!("{}\n\n{}\n</body>\n</html>", HTML_MFA_ENROLLMENT, content)
+}
+
+
 /*******************
  * HTML Elements
  *******************/
@@ -90,3 +111,41 @@ pub static HTML_AUTHORIZATION_PROMPT: &str = "
       <input type='submit' value='Authorize'>
     </form>
 ";
+
+
+/*******************
+ * Second factor
+ *******************/
+
+pub static HTML_TOTP_PROMPT: &str = "
+<!DOCTYPE html>
+<html lang='en'>
+  <head>
+    <meta charset='utf-8'>
+    <title>Two-Factor Authentication</title>
+  </head>
+  <body>
+    <h1>Two-Factor Authentication</h1>
+    <p>Please enter the 6-digit code shown in your authenticator app:</p>
+";
+
+pub static HTML_MFA_ENROLLMENT: &str = "
+<!DOCTYPE html>
+<html lang='en'>
+  <head>
+    <meta charset='utf-8'>
+    <title>Two-Factor Enrollment</title>
+  </head>
+  <body>
+    <h1>Two-Factor Enrollment</h1>
+    <p>Enroll for time-based one-time passwords (TOTP). If you are already enrolled, a code of your current authenticator is required:</p>
+    <form action='/mfa' method='post'>
+      <label for='username'>Username</label>
+      <input type='text' id='username' name='username' required>
+      <label for='password'>Password</label>
+      <input type='password' id='password' name='password' required>
+      <label for='totp_code'>Current code</label>
+      <input type='text' id='totp_code' name='totp_code' inputmode='numeric' autocomplete='one-time-code'>
+      <input type='submit' value='Enroll'>
+    </form>
+";
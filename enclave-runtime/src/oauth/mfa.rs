@@ -0,0 +1,111 @@
+extern crate sgx_tstd as std;
+use std::collections::HashMap;
+use std::time::{SystemTime, Duration};
+use std::string::{String, ToString};
+use std::sync::SgxMutex;
+use sgx_rand::{Rng, StdRng};
+use lazy_static::lazy_static;
+use itp_component_container::ComponentGetter;
+use itp_totp::{provisioning_uri, totp::TOTP_SECRET_LENGTH, EnrollTotp, TotpSecret, VerifyTotp};
+use crate::initialization::global_components::GLOBAL_TOTP_SECRET_STORE_COMPONENT;
+
+use super::token_base::generate_random_token;
+
+/// Issuer shown in the authenticator app of the user.
+static MFA_ISSUER: &str = "Integritee";
+
+/// Time a user has to enter the TOTP code after a successful password grant.
+const MFA_CHALLENGE_VALIDITY: Duration = Duration::from_secs(300);
+
+/// A pending second factor: the password grant of `username` through
+/// `client_id` succeeded, but a TOTP code is still missing.
+struct MfaChallenge {
+    client_id: String,
+    username: String,
+    expiry: SystemTime,
+}
+
+// Pending challenges, keyed by the `mfa_token` handed out to the client
+lazy_static! {
+    static ref MFA_CHALLENGES: SgxMutex<HashMap<String, MfaChallenge>> = SgxMutex::new(HashMap::new());
+}
+
+/// Creates a challenge for a user that passed the password check and returns its `mfa_token`.
+pub fn create_mfa_challenge(client_id: &str, username: &str) -> String {
+    let mut challenges = MFA_CHALLENGES.lock().unwrap();
+    let now = SystemTime::now();
+    challenges.retain(|_, challenge| challenge.expiry > now);
+
+    let mut mfa_token = generate_random_token();
+    while challenges.contains_key(&mfa_token) {
+        mfa_token = generate_random_token();
+    }
+
+    challenges.insert(mfa_token.clone(), MfaChallenge {
+        client_id: client_id.to_string(),
+        username: username.to_string(),
+        expiry: now + MFA_CHALLENGE_VALIDITY,
+    });
+    mfa_token
+}
+
+/// Consumes the challenge and returns the username it was issued for.
+/// A challenge can only be redeemed once, by the client it was issued to and before it expires.
+pub fn redeem_mfa_challenge(mfa_token: &str, client_id: &str) -> Option<String> {
+    let challenge = MFA_CHALLENGES.lock().unwrap().remove(mfa_token)?;
+
+    match challenge.client_id == client_id && challenge.expiry > SystemTime::now() {
+        true => Some(challenge.username),
+        false => None,
+    }
+}
+
+/// Whether the user has to provide a TOTP code. Fails closed: if the store
+/// can't be read, the second factor is required.
+pub fn is_mfa_enrolled(username: &str) -> bool {
+    let store = match GLOBAL_TOTP_SECRET_STORE_COMPONENT.get() {
+        Ok(store) => store,
+        Err(e) => {
+            log::error!("Could not access the TOTP secret store: {:?}", e);
+            return true
+        }
+    };
+
+    store.is_enrolled(username).unwrap_or_else(|e| {
+        log::error!("Could not read from the TOTP secret store: {:?}", e);
+        true
+    })
+}
+
+pub fn verify_totp_code(username: &str, totp_code: &str) -> bool {
+    let store = match GLOBAL_TOTP_SECRET_STORE_COMPONENT.get() {
+        Ok(store) => store,
+        Err(e) => {
+            log::error!("Could not access the TOTP secret store: {:?}", e);
+            return false
+        }
+    };
+
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
+    store.verify_code(username, totp_code, now).unwrap_or_else(|e| {
+        log::error!("Could not verify the TOTP code: {:?}", e);
+        false
+    })
+}
+
+/// Generates and seals a new TOTP secret for the user. Returns the `otpauth://`
+/// provisioning URI, which is the payload of the QR code scanned by authenticator apps.
+pub fn enroll_totp(username: &str) -> Result<String, String> {
+    let mut rng = StdRng::new().map_err(|e| format!("Could not create a random generator: {:?}", e))?;
+    let mut secret_bytes = vec![0u8; TOTP_SECRET_LENGTH];
+    rng.fill_bytes(&mut secret_bytes);
+
+    let secret = TotpSecret::new(secret_bytes).map_err(|e| format!("{:?}", e))?;
+    let uri = provisioning_uri(&secret, MFA_ISSUER, username);
+
+    let store = GLOBAL_TOTP_SECRET_STORE_COMPONENT.get()
+        .map_err(|e| format!("Could not access the TOTP secret store: {:?}", e))?;
+    store.enroll(username, secret).map_err(|e| format!("Could not enroll the TOTP secret: {:?}", e))?;
+
+    Ok(uri)
+}
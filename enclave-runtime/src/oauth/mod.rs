@@ -12,14 +12,19 @@ mod oauth_authorizer_config;
 mod oauth_client;
 mod oauth_client_config;
 
+mod audit;
 mod credential_checks;
 mod html_elements;
+mod mfa;
 mod token_base;
 mod parser;
 mod types;
 mod tools;
 mod token;
 
+#[cfg(feature = "test")]
+pub mod tests;
+
 use crate::oauth::oauth_authorizer_config::*;
 use crate::oauth::oauth_client_config::*;
 
@@ -1,18 +1,24 @@
 extern crate sgx_tstd as std;
 use std::io::Write;
 use std::net::TcpStream;
-use std::string::ToString;
+use std::string::{String, ToString};
+use itp_audit_log::{AuditEvent, TokenFingerprint};
 
 use super::types::*;
 use super::parser::*;
 use super::tools::*;
 use super::token::*;
 use super::token_base::*;
+use super::credential_checks::*;
+use super::mfa::*;
+use super::audit::audit;
 
 
 /// The authorization server must respond to two routes:
 /// /resource       => for delivering the resource
 /// /token          => for delivering/validating a token 
+/// /revoke         => for revoking an access or refresh token
+/// /mfa/enroll     => for enrolling a user for TOTP as second factor
 pub fn handle_connection(mut stream: TcpStream) {
     let request: Request = parse_request(&stream);
     println!("[AUTHOR]: Received:\t\t {:?}", request.request_line);
@@ -21,6 +27,8 @@ pub fn handle_connection(mut stream: TcpStream) {
         "/resource" => handle_resource(&request),
         "/token" => handle_token(&request),
         "/expiry" => handle_expiry(&request),
+        "/revoke" => handle_revoke(&request),
+        "/mfa/enroll" => handle_mfa_enroll(&request),
         _ => Ok(handle_404(&request)),
     };
 
@@ -51,8 +59,8 @@ fn handle_resource(request: &Request) -> Result<Response, ErrorResponse> {
     match access_token {
         Some(token) => {
             if get_token_validity(&token) {
-                println!("[AUTHOR]: Validated the token: {}", token);
-                println!("[AUTHOR]: It expires at: {:?}", get_token_expiry(&token).unwrap());
+                log::debug!("Validated the token {}, it expires at {:?}",
+                            TokenFingerprint::from_token(&token), get_token_expiry(&token));
                 Ok(resource_response())
             } else {
                 Err(invalid_token_response())
@@ -66,8 +74,20 @@ fn handle_token(request: &Request) -> Result<Response, ErrorResponse> {
     match parse_access_token_request(request) {
         Ok(access_token_request) => {
             match validate_access_token_request(&access_token_request) {
-                Ok(()) => {
-                    Ok(access_token_response())
+                Ok(()) => match access_token_request.grant_type {
+                    GrantType::RefreshToken => {
+                        refresh_token_response(&access_token_request.client_id,
+                                               &access_token_request.refresh_token)
+                            .map_err(|(error, error_description, error_uri)| {
+                                error_response(error, error_description, error_uri)
+                            })
+                    }
+                    _ => Ok(access_token_response(&access_token_request.client_id)),
+                }
+                Err((ErrorCode::MfaRequired, error_description, error_uri)) => {
+                    let mfa_token = create_mfa_challenge(&access_token_request.client_id,
+                                                         &access_token_request.username);
+                    Ok(mfa_required_response(&mfa_token, &error_description, &error_uri))
                 }
                 Err((error, error_description, error_uri)) => {
                     Err(error_response(error,
@@ -100,8 +120,8 @@ fn handle_expiry(request: &Request) -> Result<Response, ErrorResponse> {
         Some(token) => {
             
             if get_token_validity(&token) {
-                println!("[AUTHOR]: Validated the token: {}", token);
-                println!("[AUTHOR]: It expires at: {:?}", get_token_expiry(&token).unwrap());
+                log::debug!("Validated the token {}, it expires at {:?}",
+                            TokenFingerprint::from_token(&token), get_token_expiry(&token));
                 Ok(expiry_response(&token))
             } else {
                 Err(invalid_token_response())
@@ -109,4 +129,60 @@ fn handle_expiry(request: &Request) -> Result<Response, ErrorResponse> {
         }
         None => Err(access_denied_response()),
     }
-}
\ No newline at end of file
+}
+
+/// Revokes an access or refresh token of the authenticated client see:
+/// https://datatracker.ietf.org/doc/html/rfc7009
+fn handle_revoke(request: &Request) -> Result<Response, ErrorResponse> {
+    let body_field = |field_name: &str| -> String {
+        request.body.get(field_name).and_then(|val| val.as_str()).unwrap_or_default().to_string()
+    };
+    let client_id = body_field("client_id");
+    let token = body_field("token");
+
+    if let Err((error, error_description, error_uri)) = authenticate_client(&client_id, &body_field("client_secret")) {
+        audit(AuditEvent::ClientAuthenticationFailed { client_id });
+        return Err(error_response(error, error_description, error_uri))
+    }
+
+    // Tokens that are unknown or not issued to the client are not an error
+    if revoke_token(&token, &client_id) {
+        audit(AuditEvent::TokenRevoked { token: TokenFingerprint::from_token(&token) });
+    }
+
+    Ok(revocation_response())
+}
+
+/// Enrolls the user for TOTP. Users that are already enrolled have to provide a valid
+/// TOTP code of their current secret to re-enroll.
+fn handle_mfa_enroll(request: &Request) -> Result<Response, ErrorResponse> {
+    let body_field = |field_name: &str| -> String {
+        request.body.get(field_name).and_then(|val| val.as_str()).unwrap_or_default().to_string()
+    };
+    let username = body_field("username");
+    let password = body_field("password");
+    let totp_code = body_field("totp_code");
+
+    let authenticated = verify_user(&username, &password).is_ok()
+        && (!is_mfa_enrolled(&username) || verify_totp_code(&username, &totp_code));
+
+    if !authenticated {
+        audit(AuditEvent::TotpEnrollmentRejected { username });
+        return Err(error_response(ErrorCode::InvalidGrant,
+                            "User credentials not valid".to_string(),
+                            "https://datatracker.ietf.org/doc/html/rfc6238".to_string()))
+    }
+
+    match enroll_totp(&username) {
+        Ok(provisioning_uri) => {
+            audit(AuditEvent::TotpEnrolled { username });
+            Ok(mfa_enrollment_response(&provisioning_uri))
+        }
+        Err(e) => {
+            log::error!("TOTP enrollment failed: {}", e);
+            Err(error_response(ErrorCode::InvalidRequest,
+                            "TOTP enrollment failed".to_string(),
+                            "https://datatracker.ietf.org/doc/html/rfc6238".to_string()))
+        }
+    }
+}
@@ -16,6 +16,7 @@ use super::credential_checks::*;
 /// The client must respond to two routes:
 /// /authorize      => authorizing an access token request
 /// /service        => access to the service that needs a resource
+/// /mfa            => enrolling the user for TOTP as second factor
 pub fn handle_connection(mut stream: TcpStream) {
     let request: Request = parse_request(&stream);
     println!("[CLIENT]: Received:\t\t {:?}", request.request_line);
@@ -23,6 +24,7 @@ pub fn handle_connection(mut stream: TcpStream) {
     let result = match request.request_line.path.as_str() {
         "/authorize" => handle_authorize(&request),
         "/service" => handle_service(&request),
+        "/mfa" => handle_mfa(&request),
         _ => Ok(handle_404(&request)),
     };
 
@@ -96,26 +98,8 @@ fn handle_authorize(request: &Request) -> Result<Response, ErrorResponse>{
             let mut headers = HashMap::new();
             headers.insert("Content-Type".to_string(), "application/json".to_string());
             
-            let username = request
-                    .body
-                    .get("username")
-                    .ok_or((ErrorCode::InvalidGrant, "Missing username header", "https://datatracker.ietf.org/doc/html/rfc6749#section-4.3"))
-                    .unwrap().as_str().unwrap();
-
-            let password = request
-                    .body
-                    .get("password")
-                    .ok_or((ErrorCode::InvalidGrant, "Missing password header", "https://datatracker.ietf.org/doc/html/rfc6749#section-4.3"))
-                    .unwrap().as_str().unwrap();
+            let request_body = token_request_body(&request.body);
 
-            let request_body = serde_json::json!({
-                "grant_type": GrantType::ResourceOwnerPasswordCredentials.to_string(),
-                "client_id": CLIENT_ID,
-                "client_secret": hash_value(CLIENT_SECRET),
-                "username": username,
-                "password": hash_value(password),
-            });
-            
             let access_token_request = Request {
                 request_line,
                 headers,
@@ -124,6 +108,11 @@ fn handle_authorize(request: &Request) -> Result<Response, ErrorResponse>{
 
             let response = send(&access_token_request, AUTHOR_URL);
 
+            if let Some(mfa_token) = response.body.get("mfa_token").and_then(|val| val.as_str()) {
+                // The password was correct, but the user has to provide a TOTP code
+                return Ok(response_with_totp_prompt(mfa_token));
+            }
+
             let values = match response.body.get("access_token") { 
                 Some(access_token) => {
                     serde_json::json!({
@@ -144,7 +133,7 @@ fn handle_authorize(request: &Request) -> Result<Response, ErrorResponse>{
             };
             
             let mut headers = HashMap::new();
-            headers.insert("Location".to_string(), "/service".to_string());
+            headers.insert("Location".to_string(), CLIENT_REDIRECT_URI.to_string());
             let token = values["access_token"].as_str().unwrap();
 
             headers.insert("Cookie".to_string(), format!("access_token={}",token));
@@ -168,6 +157,100 @@ fn handle_authorize(request: &Request) -> Result<Response, ErrorResponse>{
     }
 }
 
+/// Body of the token request for the login form of the authorization prompt.
+///
+/// The client secret is sent as is, the authorizer verifies it against the bcrypt hash
+/// registered for the client.
+pub(super) fn token_request_body(login_form: &serde_json::Value) -> serde_json::Value {
+    match login_form.get("mfa_token").and_then(|val| val.as_str()) {
+        // Second step of the login of a user enrolled for TOTP
+        Some(mfa_token) => {
+            let totp_code = login_form
+                .get("totp_code")
+                .and_then(|val| val.as_str())
+                .unwrap_or_default();
+
+            serde_json::json!({
+                "grant_type": GrantType::MfaOtp.to_string(),
+                "client_id": CLIENT_ID,
+                "client_secret": CLIENT_SECRET,
+                "redirect_uri": CLIENT_REDIRECT_URI,
+                "mfa_token": mfa_token,
+                "totp_code": totp_code,
+            })
+        }
+        None => {
+            let username = login_form
+                .get("username")
+                .ok_or((ErrorCode::InvalidGrant, "Missing username header", "https://datatracker.ietf.org/doc/html/rfc6749#section-4.3"))
+                .unwrap().as_str().unwrap();
+
+            let password = login_form
+                .get("password")
+                .ok_or((ErrorCode::InvalidGrant, "Missing password header", "https://datatracker.ietf.org/doc/html/rfc6749#section-4.3"))
+                .unwrap().as_str().unwrap();
+
+            serde_json::json!({
+                "grant_type": GrantType::ResourceOwnerPasswordCredentials.to_string(),
+                "client_id": CLIENT_ID,
+                "client_secret": CLIENT_SECRET,
+                "redirect_uri": CLIENT_REDIRECT_URI,
+                "username": username,
+                "password": hash_value(password),
+            })
+        }
+    }
+}
+
+fn handle_mfa(request: &Request) -> Result<Response, ErrorResponse> {
+    match request.request_line.method {
+        HttpMethod::Get => Ok(response_with_mfa_enrollment("")),
+        HttpMethod::Post => {
+            let request_line = RequestLine {
+                method: HttpMethod::Post,
+                path: "/mfa/enroll".to_string(),
+                http_version: "HTTP/1.1".to_string(),
+            };
+
+            let mut headers = HashMap::new();
+            headers.insert("Content-Type".to_string(), "application/json".to_string());
+
+            let body_field = |field_name: &str| {
+                request.body.get(field_name).and_then(|val| val.as_str()).unwrap_or_default()
+            };
+
+            let request_body = serde_json::json!({
+                "username": body_field("username"),
+                "password": hash_value(body_field("password")),
+                "totp_code": body_field("totp_code"),
+            });
+
+            let enrollment_request = Request {
+                request_line,
+                headers,
+                body: request_body
+            };
+
+            let response = send(&enrollment_request, AUTHOR_URL);
+
+            let html_content = match response.body.get("provisioning_uri").and_then(|val| val.as_str()) {
+                // The provisioning URI is the payload of the QR code scanned by authenticator apps
+                Some(provisioning_uri) => format!("<br>
+                                <p>Enrolled! Add the following URI to your authenticator app (e.g. as QR code):<br>
+                                <code>{}</code><br>
+                                It is only shown once.</p>",
+                                provisioning_uri),
+                None => format!("<br>
+                                <p>Enrollment failed: {}</p>",
+                                response.body.get("error_description").unwrap_or(&serde_json::Value::Null)),
+            };
+
+            Ok(response_with_mfa_enrollment(&html_content))
+        }
+        _ => Ok(handle_404(request))
+    }
+}
+
 fn request_resource(token: &str) -> Response {
     let request_line = RequestLine {
         method: HttpMethod::Get,
@@ -1,4 +1,6 @@
 // config file for client
 pub static CLIENT_URL: &str = "localhost:7879";
 pub static CLIENT_ID: &str =  r#"client_id"#;
-pub static CLIENT_SECRET: &str =  r#"client_secret"#;
\ No newline at end of file
+pub static CLIENT_SECRET: &str =  r#"client_secret"#;
+/// Has to be registered for the client, see `register-oauth-client --redirect-uri`.
+pub static CLIENT_REDIRECT_URI: &str = "http://localhost:7879/service";
\ No newline at end of file
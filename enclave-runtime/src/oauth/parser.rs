@@ -83,28 +83,40 @@ pub fn parse_access_token_request(request: &Request) -> Result<AccessTokenReques
             .ok_or((ErrorCode::InvalidClient, "Missing client_secret header", "https://datatracker.ietf.org/doc/html/rfc6749#section-4.1.1"))
             .unwrap().as_str().unwrap();
 
-    let username = request
-            .body
-            .get("username")
-            .ok_or((ErrorCode::InvalidGrant, "Missing username header", "https://datatracker.ietf.org/doc/html/rfc6749#section-4.3"))
-            .unwrap().as_str().unwrap();
-
-    let password = request
-            .body
-            .get("password")
-            .ok_or((ErrorCode::InvalidGrant, "Missing password header", "https://datatracker.ietf.org/doc/html/rfc6749#section-4.3"))
-            .unwrap().as_str().unwrap();
+    // Depending on the grant type either the user credentials, the second factor or the
+    // refresh token are provided, missing fields are rejected in `validate_access_token_request`.
+    let username = optional_body_field(request, "username");
+    let password = optional_body_field(request, "password");
+    let mfa_token = optional_body_field(request, "mfa_token");
+    let totp_code = optional_body_field(request, "totp_code");
+    let refresh_token = optional_body_field(request, "refresh_token");
+    let redirect_uri = optional_body_field(request, "redirect_uri");
+    let scope = optional_body_field(request, "scope");
 
     Ok(AccessTokenRequest {
         request: request.clone(),
         grant_type,
         client_id: client_id.to_string(),
         client_secret: client_secret.to_string(),
-        username: username.to_string(),
-        password: password.to_string(),
+        username,
+        password,
+        mfa_token,
+        totp_code,
+        refresh_token,
+        redirect_uri,
+        scope,
     })
 }
 
+fn optional_body_field(request: &Request, field_name: &str) -> String {
+    request
+        .body
+        .get(field_name)
+        .and_then(|val| val.as_str())
+        .unwrap_or_default()
+        .to_owned()
+}
+
 
 
 
@@ -0,0 +1,95 @@
+extern crate sgx_tstd as sgx;
+use std::collections::HashMap;
+use std::string::{String, ToString};
+use std::vec;
+use itp_oauth_client_registry::{mocks::OAuthClientRegistryMock, OAuthClient, OAuthGrantType, RegisterOAuthClient};
+use super::credential_checks::verify_client_in;
+use super::oauth_client::token_request_body;
+use super::oauth_client_config::*;
+use super::parser::parse_access_token_request;
+use super::token_base::*;
+use super::types::*;
+
+/// The token request the client sends for a login has to pass the client verification
+/// against a registration made with `register-oauth-client`.
+pub fn token_request_of_client_passes_client_verification() {
+    let registry = registry_with_client();
+    let access_token_request = login_token_request(serde_json::json!({}));
+
+    assert!(verify_client_in(&registry, &access_token_request).is_ok());
+}
+
+pub fn token_request_with_unregistered_redirect_uri_is_rejected() {
+    let registry = registry_with_client();
+    let access_token_request = login_token_request(serde_json::json!({
+        "redirect_uri": "http://localhost:7879/elsewhere",
+    }));
+
+    let result = verify_client_in(&registry, &access_token_request);
+
+    assert!(matches!(result, Err((ErrorCode::InvalidRequest, _, _))));
+}
+
+pub fn token_request_with_unregistered_scope_is_rejected() {
+    let registry = registry_with_client();
+    let access_token_request = login_token_request(serde_json::json!({
+        "scope": "profile admin",
+    }));
+
+    let result = verify_client_in(&registry, &access_token_request);
+
+    assert!(matches!(result, Err((ErrorCode::InvalidScope, _, _))));
+}
+
+pub fn refresh_token_can_only_be_redeemed_once_by_its_client() {
+    let access_token = generate_token();
+    let refresh_token = generate_refresh_token(CLIENT_ID, &access_token);
+
+    assert!(redeem_refresh_token(&refresh_token, "other_client_id").is_none());
+    assert_eq!(redeem_refresh_token(&refresh_token, CLIENT_ID), Some(access_token.clone()));
+    assert!(!get_token_validity(&access_token));
+    assert!(redeem_refresh_token(&refresh_token, CLIENT_ID).is_none());
+}
+
+pub fn revoked_access_token_is_no_longer_valid() {
+    let access_token = generate_token();
+    let refresh_token = generate_refresh_token(CLIENT_ID, &access_token);
+
+    assert!(!revoke_token(&access_token, "other_client_id"));
+    assert!(revoke_token(&access_token, CLIENT_ID));
+    assert!(!get_token_validity(&access_token));
+    assert!(redeem_refresh_token(&refresh_token, CLIENT_ID).is_none());
+}
+
+fn registry_with_client() -> OAuthClientRegistryMock {
+    let registry = OAuthClientRegistryMock::default();
+    registry.register_client(OAuthClient::new(
+        CLIENT_ID.to_string(),
+        vec![CLIENT_REDIRECT_URI.to_string()],
+        vec![OAuthGrantType::Password],
+        vec!["profile".to_string()],
+        bcrypt::hash_with_salt(CLIENT_SECRET, 4, [7u8; 16]).unwrap().to_string(),
+    )).unwrap();
+    registry
+}
+
+/// Token request of the client for the login form, with the fields of `overrides` replaced.
+fn login_token_request(overrides: serde_json::Value) -> AccessTokenRequest {
+    let login_form = serde_json::json!({ "username": "user", "password": "asdf" });
+    let mut body = token_request_body(&login_form);
+    for (field, value) in overrides.as_object().unwrap() {
+        body[field.as_str()] = value.clone();
+    }
+
+    let request = Request {
+        request_line: RequestLine {
+            method: HttpMethod::Post,
+            path: "/token".to_string(),
+            http_version: "HTTP/1.1".to_string(),
+        },
+        headers: HashMap::<String, String>::new(),
+        body,
+    };
+
+    parse_access_token_request(&request).unwrap()
+}
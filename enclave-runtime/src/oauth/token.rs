@@ -2,20 +2,36 @@ extern crate sgx_tstd as std;
 use std::collections::HashMap;
 use std::string::{String, ToString};
 use std::time::SystemTime;
+use itp_audit_log::{AuditEvent, TokenFingerprint};
 
 use super::types::*;
 use super::token_base::*;
 use super::credential_checks::*;
+use super::audit::audit;
+use super::mfa::*;
 
 pub fn validate_access_token_request(access_token_request: &AccessTokenRequest) -> Result<(), (ErrorCode, String, String)> {
-    let fields = [
-        ("client_id", &access_token_request.client_id),
-        ("client_secret", &access_token_request.client_secret),
-        ("username", &access_token_request.username),
-        ("password", &access_token_request.password),
-    ];
-
-    for (field_name, field_value) in &fields {
+    let fields: &[(&str, &String)] = match access_token_request.grant_type {
+        GrantType::MfaOtp => &[
+            ("client_id", &access_token_request.client_id),
+            ("client_secret", &access_token_request.client_secret),
+            ("mfa_token", &access_token_request.mfa_token),
+            ("totp_code", &access_token_request.totp_code),
+        ],
+        GrantType::RefreshToken => &[
+            ("client_id", &access_token_request.client_id),
+            ("client_secret", &access_token_request.client_secret),
+            ("refresh_token", &access_token_request.refresh_token),
+        ],
+        _ => &[
+            ("client_id", &access_token_request.client_id),
+            ("client_secret", &access_token_request.client_secret),
+            ("username", &access_token_request.username),
+            ("password", &access_token_request.password),
+        ],
+    };
+
+    for (field_name, field_value) in fields {
         if field_value.is_empty() {
             let error_description = format!("{} is empty", field_name);
             let error_uri = "https://datatracker.ietf.org/doc/html/rfc6749#section-4.3.2".to_string();
@@ -23,16 +39,73 @@ pub fn validate_access_token_request(access_token_request: &AccessTokenRequest)
         }
     }
 
-    match verify_client(access_token_request.client_id.as_str(), access_token_request.client_secret.as_str()) {
+    let client_id = access_token_request.client_id.clone();
+
+    match verify_client(access_token_request) {
+        Ok(()) => {
+            match access_token_request.grant_type {
+                GrantType::MfaOtp => verify_second_factor(access_token_request),
+                // The refresh token itself is redeemed in `refresh_token_response`
+                GrantType::RefreshToken => Ok(()),
+                _ => verify_first_factor(access_token_request),
+            }
+        }
+        Err((error, error_description, error_uri)) => {
+            audit(AuditEvent::ClientAuthenticationFailed { client_id });
+            Err((error, error_description, error_uri))
+        }
+    }
+}
+
+/// Checks the user credentials. Users enrolled for TOTP are not logged in yet,
+/// they get an `MfaRequired` error and have to continue with the `mfa_otp` grant.
+fn verify_first_factor(access_token_request: &AccessTokenRequest) -> Result<(), (ErrorCode, String, String)> {
+    let client_id = access_token_request.client_id.clone();
+    let username = access_token_request.username.clone();
+
+    match verify_user(access_token_request.username.as_str(), access_token_request.password.as_str()) {
+        Ok(()) if is_mfa_enrolled(&username) => {
+            Err((ErrorCode::MfaRequired,
+                "A TOTP code is required as second factor".to_string(),
+                "https://datatracker.ietf.org/doc/html/rfc6238".to_string()))
+        }
         Ok(()) => {
-            verify_user(access_token_request.username.as_str(), access_token_request.password.as_str())
+            audit(AuditEvent::LoginSucceeded { client_id, username });
+            Ok(())
         }
         Err((error, error_description, error_uri)) => {
+            audit(AuditEvent::LoginFailed { client_id, username });
             Err((error, error_description, error_uri))
         }
     }
 }
 
+/// Redeems the `mfa_token` of a previous password grant and checks the TOTP code.
+/// The challenge is consumed either way, so a wrong code requires logging in again.
+fn verify_second_factor(access_token_request: &AccessTokenRequest) -> Result<(), (ErrorCode, String, String)> {
+    let client_id = access_token_request.client_id.clone();
+
+    let username = match redeem_mfa_challenge(&access_token_request.mfa_token, &client_id) {
+        Some(username) => username,
+        None => return Err((ErrorCode::InvalidGrant,
+                "MFA token not valid or expired".to_string(),
+                "https://datatracker.ietf.org/doc/html/rfc6749#section-5.2".to_string()))
+    };
+
+    match verify_totp_code(&username, &access_token_request.totp_code) {
+        true => {
+            audit(AuditEvent::LoginSucceeded { client_id, username });
+            Ok(())
+        }
+        false => {
+            audit(AuditEvent::LoginFailed { client_id, username });
+            Err((ErrorCode::InvalidGrant,
+                "TOTP code not valid".to_string(),
+                "https://datatracker.ietf.org/doc/html/rfc6238".to_string()))
+        }
+    }
+}
+
 pub fn expiry_response(token: &str) -> Response {
     let response_line = ResponseLine {
         http_version: "HTTP/1.1".to_string(),
@@ -59,7 +132,57 @@ pub fn expiry_response(token: &str) -> Response {
     }
 }
 
-pub fn access_token_response() -> Response {
+pub fn access_token_response(client_id: &str) -> Response {
+    let token = generate_token();
+    audit(AuditEvent::TokenIssued {
+        client_id: client_id.to_string(),
+        token: TokenFingerprint::from_token(&token),
+    });
+
+    token_response(client_id, &token)
+}
+
+/// Exchanges the refresh token for a new access token (and refresh token),
+/// the previous ones are revoked see:
+/// https://datatracker.ietf.org/doc/html/rfc6749#section-6
+pub fn refresh_token_response(client_id: &str, refresh_token: &str) -> Result<Response, (ErrorCode, String, String)> {
+    let previous = match redeem_refresh_token(refresh_token, client_id) {
+        Some(previous) => previous,
+        None => return Err((ErrorCode::InvalidGrant,
+                "Refresh token not valid".to_string(),
+                "https://datatracker.ietf.org/doc/html/rfc6749#section-6".to_string()))
+    };
+
+    let token = generate_token();
+    audit(AuditEvent::TokenRefreshed {
+        client_id: client_id.to_string(),
+        previous: TokenFingerprint::from_token(&previous),
+        token: TokenFingerprint::from_token(&token),
+    });
+
+    Ok(token_response(client_id, &token))
+}
+
+/// Response to a revocation request, it does not tell whether the token was valid see:
+/// https://datatracker.ietf.org/doc/html/rfc7009#section-2.2
+pub fn revocation_response() -> Response {
+    let response_line = ResponseLine {
+        http_version: "HTTP/1.1".to_string(),
+        status_code: 200,
+        response_type: HttpResponseType::Success,
+    };
+
+    let mut headers = HashMap::new();
+    headers.insert("Content-Type".to_string(), "application/json".to_string());
+
+    Response {
+        response_line,
+        headers,
+        body: serde_json::json!({})
+    }
+}
+
+fn token_response(client_id: &str, token: &str) -> Response {
     let response_line = ResponseLine {
         http_version: "HTTP/1.1".to_string(),
         status_code: 404,
@@ -69,9 +192,9 @@ pub fn access_token_response() -> Response {
     let mut headers = HashMap::new();
     headers.insert("Content-Type".to_string(), "application/json".to_string());
 
-    let token = generate_token();
+    let refresh_token = generate_refresh_token(client_id, token);
 
-    let expiry = get_token_expiry(token.as_str()).unwrap();
+    let expiry = get_token_expiry(token).unwrap();
 
     let expires_in_s = expiry.duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs() 
                     - SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
@@ -80,6 +203,55 @@ pub fn access_token_response() -> Response {
         "access_token": token,
         "token_type": TokenType::Bearer.to_string(),
         "expires_in_s": expires_in_s.to_string(),
+        "refresh_token": refresh_token,
+    });
+
+    Response {
+        response_line,
+        headers,
+        body
+    }
+}
+
+/// Response to a valid password grant of a user enrolled for TOTP. The `mfa_token`
+/// has to be redeemed together with a TOTP code using the `mfa_otp` grant.
+pub fn mfa_required_response(mfa_token: &str, error_description: &str, error_uri: &str) -> Response {
+    let response_line = ResponseLine {
+        http_version: "HTTP/1.1".to_string(),
+        status_code: 401,
+        response_type: HttpResponseType::ClientError,
+    };
+
+    let mut headers = HashMap::new();
+    headers.insert("Content-Type".to_string(), "application/json".to_string());
+
+    let body = serde_json::json!({
+        "error": ErrorCode::MfaRequired.to_string(),
+        "error_description": error_description,
+        "error_uri": error_uri,
+        "mfa_token": mfa_token,
+    });
+
+    Response {
+        response_line,
+        headers,
+        body
+    }
+}
+
+/// Response to a successful TOTP enrollment, the provisioning URI is the QR code payload.
+pub fn mfa_enrollment_response(provisioning_uri: &str) -> Response {
+    let response_line = ResponseLine {
+        http_version: "HTTP/1.1".to_string(),
+        status_code: 200,
+        response_type: HttpResponseType::Success,
+    };
+
+    let mut headers = HashMap::new();
+    headers.insert("Content-Type".to_string(), "application/json".to_string());
+
+    let body = serde_json::json!({
+        "provisioning_uri": provisioning_uri,
     });
 
     Response {
@@ -19,12 +19,15 @@ fn get_token_base() -> std::sync::SgxMutexGuard<'static, TokenBase> {
 #[derive(Debug)]
 struct TokenBase {
     tokens: HashMap<String, SystemTime>,
+    /// Refresh tokens and the client and access token they were issued with
+    refresh_tokens: HashMap<String, (String, String)>,
 }
 
 impl TokenBase {
     fn new() -> TokenBase {
         TokenBase {
             tokens: HashMap::new(),
+            refresh_tokens: HashMap::new(),
         }
     }
 
@@ -36,6 +39,32 @@ impl TokenBase {
         self.tokens.get(&token.to_string()).copied()
     }
 
+    fn insert_refresh_token(&mut self, refresh_token: &String, client_id: &str, access_token: &str) {
+        self.refresh_tokens.insert(refresh_token.to_owned(), (client_id.to_string(), access_token.to_string()));
+    }
+
+    /// Removes the refresh token and its access token, if it was issued to the client.
+    fn remove_refresh_token(&mut self, refresh_token: &str, client_id: &str) -> Option<String> {
+        match self.refresh_tokens.get(refresh_token) {
+            Some((issued_to, _)) if issued_to == client_id => {}
+            _ => return None,
+        }
+        let (_, access_token) = self.refresh_tokens.remove(refresh_token)?;
+        self.tokens.remove(&access_token);
+        Some(access_token)
+    }
+
+    /// Removes the access token and the refresh tokens issued with it, if it was issued to the client.
+    fn remove_access_token(&mut self, access_token: &str, client_id: &str) -> bool {
+        let issued_to_client = self.refresh_tokens.values()
+            .any(|(issued_to, token)| issued_to == client_id && token == access_token);
+        if !issued_to_client || self.tokens.remove(access_token).is_none() {
+            return false
+        }
+        self.refresh_tokens.retain(|_, (_, token)| token != access_token);
+        true
+    }
+
     fn is_token_valid(&self, token: &str) -> bool {
         match self.get_expiry(token) {
             Some(expiry) => expiry > SystemTime::now(),
@@ -56,6 +85,33 @@ pub fn generate_token() -> String {
     token
 }
 
+/// The refresh token is bound to the client and the access token it was issued with.
+pub fn generate_refresh_token(client_id: &str, access_token: &str) -> String {
+    let mut refresh_token = generate_random_token();
+
+    while get_token_base().refresh_tokens.contains_key(&refresh_token) {
+        refresh_token = generate_random_token();
+    }
+
+    get_token_base().insert_refresh_token(&refresh_token, client_id, access_token);
+    refresh_token
+}
+
+/// Revokes the refresh token and the access token issued with it, which is returned.
+/// Only the client the refresh token was issued to can redeem it.
+pub fn redeem_refresh_token(refresh_token: &str, client_id: &str) -> Option<String> {
+    get_token_base().remove_refresh_token(refresh_token, client_id)
+}
+
+/// Revokes an access or refresh token of the client, see:
+/// https://datatracker.ietf.org/doc/html/rfc7009#section-2.1
+/// Returns false if the token was not issued to the client (anymore).
+pub fn revoke_token(token: &str, client_id: &str) -> bool {
+    let mut token_base = get_token_base();
+    token_base.remove_refresh_token(token, client_id).is_some()
+        || token_base.remove_access_token(token, client_id)
+}
+
 pub fn get_token_expiry(token: &str) -> Option<SystemTime> {
     get_token_base().get_expiry(token)
 }
@@ -64,7 +120,7 @@ pub fn get_token_validity(token: &str) -> bool {
     get_token_base().is_token_valid(token)
 }
 
-fn generate_random_token() -> String {
+pub fn generate_random_token() -> String {
     // For now this will do ...
     const CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ\
                             abcdefghijklmnopqrstuvwxyz\
@@ -231,6 +231,48 @@ pub fn response_with_error_content_from_error(error_response: &ErrorResponse) ->
     }
 }
 
+pub fn response_with_totp_prompt(mfa_token: &str) -> Response {
+    let response_line = ResponseLine {
+        http_version: "HTTP/1.1".to_string(),
+        status_code: 200,
+        response_type: HttpResponseType::Success,
+    };
+
+    let mut headers = HashMap::new();
+    headers.insert("Content-Type".to_string(), "text/html".to_string());
+
+    let body = serde_json::json!({
+        "html_content": html_totp_prompt(mfa_token),
+    });
+
+    Response {
+        response_line,
+        headers,
+        body
+    }
+}
+
+pub fn response_with_mfa_enrollment(content: &str) -> Response {
+    let response_line = ResponseLine {
+        http_version: "HTTP/1.1".to_string(),
+        status_code: 200,
+        response_type: HttpResponseType::Success,
+    };
+
+    let mut headers = HashMap::new();
+    headers.insert("Content-Type".to_string(), "text/html".to_string());
+
+    let body = serde_json::json!({
+        "html_content": html_mfa_enrollment(content),
+    });
+
+    Response {
+        response_line,
+        headers,
+        body
+    }
+}
+
 pub fn request_expiry(token: &str) -> String {
     let request_line = RequestLine {
         method: HttpMethod::Get,
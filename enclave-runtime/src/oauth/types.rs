@@ -96,6 +96,13 @@ pub enum GrantType {
     /// adding any additional parameters necessary see:
     /// https://datatracker.ietf.org/doc/html/rfc6749#section-4.5
     Extension,
+    /// Extension grant redeeming an `mfa_token` (handed out after a successful
+    /// password grant of a TOTP enrolled user) together with a TOTP code see:
+    /// https://datatracker.ietf.org/doc/html/rfc6238
+    MfaOtp,
+    /// The client exchanges a refresh token for a new access token see:
+    /// https://datatracker.ietf.org/doc/html/rfc6749#section-6
+    RefreshToken,
 }
 
 impl ToString for GrantType {
@@ -106,6 +113,8 @@ impl ToString for GrantType {
             GrantType::ResourceOwnerPasswordCredentials => "password".to_string(),
             GrantType::ClientCredentials => "client_credentials".to_string(),
             GrantType::Extension => "extension".to_string(),
+            GrantType::MfaOtp => "mfa_otp".to_string(),
+            GrantType::RefreshToken => "refresh_token".to_string(),
         }
     }
 }
@@ -120,6 +129,8 @@ impl FromStr for GrantType {
             "password" => Ok(GrantType::ResourceOwnerPasswordCredentials),
             "client_credentials" => Ok(GrantType::ClientCredentials),
             "extension" => Ok(GrantType::Extension),
+            "mfa_otp" => Ok(GrantType::MfaOtp),
+            "refresh_token" => Ok(GrantType::RefreshToken),
             _ => Err(()),
         }
     }
@@ -179,6 +190,9 @@ pub enum ErrorCode {
     UnsupportedGrantType,
     /// The requested scope is invalid
     InvalidScope,
+    /// The user credentials are valid, but the user has to provide a
+    /// second factor (TOTP code) using the `mfa_token` of the response
+    MfaRequired,
 }
 
 impl FromStr for ErrorCode {
@@ -192,6 +206,7 @@ impl FromStr for ErrorCode {
             "unauthorized_client" => Ok(ErrorCode::UnauthorizedClient),
             "unsupported_grant_type" => Ok(ErrorCode::UnsupportedGrantType),
             "invalid_scope" => Ok(ErrorCode::InvalidScope),
+            "mfa_required" => Ok(ErrorCode::MfaRequired),
             _ => Err(()),
         }
     }
@@ -206,6 +221,7 @@ impl ToString for ErrorCode {
             ErrorCode::UnauthorizedClient => "unauthorized_client".to_string(),
             ErrorCode::UnsupportedGrantType => "unsupported_grant_type".to_string(),
             ErrorCode::InvalidScope => "invalid_scope".to_string(),
+            ErrorCode::MfaRequired => "mfa_required".to_string(),
         }
     }
 }
@@ -264,7 +280,17 @@ pub struct AccessTokenRequest {
     /// The user credentials to gain access to the resource server see:
     /// https://datatracker.ietf.org/doc/html/rfc6749#section-4.3
     pub username: String,
-    pub password: String
+    pub password: String,
+    /// The second factor, only used by the `mfa_otp` grant
+    pub mfa_token: String,
+    pub totp_code: String,
+    /// Only used by the `refresh_token` grant
+    pub refresh_token: String,
+    /// Optional, checked against the registration of the client see:
+    /// https://datatracker.ietf.org/doc/html/rfc6749#section-3.1.2 and
+    /// https://datatracker.ietf.org/doc/html/rfc6749#section-3.3
+    pub redirect_uri: String,
+    pub scope: String,
 }
 
 impl ToString for AccessTokenRequest {
@@ -274,7 +300,12 @@ impl ToString for AccessTokenRequest {
             "client_id": self.client_id,
             "client_secret": self.client_secret,
             "username": self.username,
-            "password": self.password
+            "password": self.password,
+            "mfa_token": self.mfa_token,
+            "totp_code": self.totp_code,
+            "refresh_token": self.refresh_token,
+            "redirect_uri": self.redirect_uri,
+            "scope": self.scope
         });
 
         let mut request = self.request.clone();
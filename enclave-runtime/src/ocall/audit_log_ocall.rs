@@ -0,0 +1,41 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::ocall::{ffi, OcallApi};
+use frame_support::ensure;
+use itp_ocall_api::EnclaveAuditLogOCallApi;
+use sgx_types::{sgx_status_t, SgxResult};
+use std::vec::Vec;
+
+impl EnclaveAuditLogOCallApi for OcallApi {
+	fn append_audit_record(&self, encoded_record: Vec<u8>) -> SgxResult<()> {
+		let mut rt: sgx_status_t = sgx_status_t::SGX_ERROR_UNEXPECTED;
+
+		let res = unsafe {
+			ffi::ocall_append_audit_record(
+				&mut rt as *mut sgx_status_t,
+				encoded_record.as_ptr(),
+				encoded_record.len() as u32,
+			)
+		};
+
+		ensure!(rt == sgx_status_t::SGX_SUCCESS, rt);
+		ensure!(res == sgx_status_t::SGX_SUCCESS, res);
+
+		Ok(())
+	}
+}
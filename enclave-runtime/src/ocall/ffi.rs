@@ -81,6 +81,12 @@ extern "C" {
 		metric_size: u32,
 	) -> sgx_status_t;
 
+	pub fn ocall_append_audit_record(
+		ret_val: *mut sgx_status_t,
+		encoded_record: *const u8,
+		encoded_record_size: u32,
+	) -> sgx_status_t;
+
 	pub fn ocall_propose_sidechain_blocks(
 		ret_val: *mut sgx_status_t,
 		signed_blocks: *const u8,
@@ -105,6 +111,14 @@ extern "C" {
 		sidechain_blocks_size: u32,
 	) -> sgx_status_t;
 
+	pub fn ocall_send_trusted_operations_to_peers(
+		ret_val: *mut sgx_status_t,
+		peer_urls: *const u8,
+		peer_urls_size: u32,
+		requests: *const u8,
+		requests_size: u32,
+	) -> sgx_status_t;
+
 	pub fn ocall_send_to_parentchain(
 		ret_val: *mut sgx_status_t,
 		extrinsics: *const u8,
@@ -16,6 +16,7 @@
 */
 
 mod attestation_ocall;
+mod audit_log_ocall;
 mod ffi;
 mod ipfs_ocall;
 mod metrics_ocall;
@@ -20,7 +20,7 @@ use crate::ocall::{ffi, OcallApi};
 use codec::{Decode, Encode};
 use frame_support::ensure;
 use itp_ocall_api::EnclaveSidechainOCallApi;
-use itp_types::{BlockHash, ShardIdentifier};
+use itp_types::{BlockHash, PalletString, Request, ShardIdentifier};
 use log::*;
 use sgx_types::{sgx_status_t, SgxResult};
 use std::vec::Vec;
@@ -109,4 +109,28 @@ impl EnclaveSidechainOCallApi for OcallApi {
 
 		Ok(decoded_signed_blocks)
 	}
+	fn send_trusted_operations_to_peers(
+		&self,
+		peer_urls: Vec<PalletString>,
+		requests: Vec<Request>,
+	) -> SgxResult<()> {
+		let mut rt: sgx_status_t = sgx_status_t::SGX_ERROR_UNEXPECTED;
+		let peer_urls_encoded = peer_urls.encode();
+		let requests_encoded = requests.encode();
+
+		let res = unsafe {
+			ffi::ocall_send_trusted_operations_to_peers(
+				&mut rt as *mut sgx_status_t,
+				peer_urls_encoded.as_ptr(),
+				peer_urls_encoded.len() as u32,
+				requests_encoded.as_ptr(),
+				requests_encoded.len() as u32,
+			)
+		};
+
+		ensure!(rt == sgx_status_t::SGX_SUCCESS, rt);
+		ensure!(res == sgx_status_t::SGX_SUCCESS, res);
+
+		Ok(())
+	}
 }
@@ -0,0 +1,191 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+//! Publishes the STF events of sidechain blocks to the `author_subscribeEvents` subscribers.
+
+use codec::{Decode, Encode};
+use ita_stf::{
+	stf_events::{get_stf_events, StfEventRecord},
+	Getter, TrustedGetter,
+};
+use itc_direct_rpc_server::{rpc_subscriptions::RpcSubscriptions, SendRpcResponse};
+use itp_sgx_externalities::SgxExternalitiesTrait;
+use itp_stf_state_observer::traits::ObserveState;
+use itp_time_utils::now_as_millis;
+use itp_types::{AccountId, Request, ShardIdentifier, H256};
+use its_sidechain::consensus_common::{ConfirmBlockImport, Result as ConsensusResult};
+use log::*;
+use sp_core::blake2_256;
+use std::{
+	format,
+	string::String,
+	sync::{Arc, SgxRwLock as RwLock},
+	vec::Vec,
+};
+
+/// Time an event subscription credential is valid, before or after its timestamp.
+const SUBSCRIPTION_CREDENTIAL_VALIDITY_MS: u64 = 60_000;
+
+/// Subscribers only receive the events of a shard their account is authorised to see.
+pub type EventSubscriptionFilter = (ShardIdentifier, AccountId);
+
+/// Authenticates event subscription requests.
+///
+/// The request contains a signed `subscribe_events` trusted getter, which proves that the
+/// subscriber holds the account whose events it wants to receive. Its timestamp binds the
+/// credential to a short validity period, within which it is only accepted once, so an
+/// observed credential can not be replayed.
+#[derive(Default)]
+pub struct EventSubscriptionAuthenticator {
+	/// Hashes of the accepted credentials that are still valid, with their timestamp.
+	accepted_credentials: RwLock<Vec<(H256, u64)>>,
+}
+
+impl EventSubscriptionAuthenticator {
+	pub fn authenticate(&self, request: Request) -> Result<EventSubscriptionFilter, String> {
+		self.authenticate_at(request, now_as_millis())
+	}
+
+	fn authenticate_at(
+		&self,
+		request: Request,
+		now: u64,
+	) -> Result<EventSubscriptionFilter, String> {
+		let getter = Getter::decode(&mut request.cyphertext.as_slice())
+			.map_err(|e| format!("Could not decode getter: {:?}", e))?;
+
+		let trusted_getter_signed = match getter {
+			Getter::trusted(g) => g,
+			Getter::public(_) => return Err("Expected a trusted getter".into()),
+		};
+		let (account, timestamp) = match &trusted_getter_signed.getter {
+			TrustedGetter::subscribe_events(account, timestamp) => (account.clone(), *timestamp),
+			_ => return Err("Expected a subscribe_events trusted getter".into()),
+		};
+		if !trusted_getter_signed.verify_signature() {
+			return Err("Invalid signature of subscribe_events trusted getter".into())
+		}
+		if now.abs_diff(timestamp) > SUBSCRIPTION_CREDENTIAL_VALIDITY_MS {
+			return Err("Event subscription credential has expired".into())
+		}
+
+		// The signature is not hashed, so a re-encoded signature does not make a new credential.
+		let credential_hash: H256 = blake2_256(&trusted_getter_signed.getter.encode()).into();
+		let mut accepted_credentials =
+			self.accepted_credentials.write().map_err(|_| String::from("Lock poisoning"))?;
+		accepted_credentials.retain(|(_, timestamp)| {
+			now.abs_diff(*timestamp) <= SUBSCRIPTION_CREDENTIAL_VALIDITY_MS
+		});
+		if accepted_credentials.iter().any(|(hash, _)| *hash == credential_hash) {
+			return Err("Event subscription credential was already used".into())
+		}
+		accepted_credentials.push((credential_hash, timestamp));
+
+		Ok((request.shard, account))
+	}
+}
+
+/// Publishes the STF events of the last sidechain block of `shard` to the subscribers.
+///
+/// The events are read from the current state of the shard, every subscriber only receives
+/// the events of its shard that are visible to its account.
+pub fn publish_events<Responder, StateObserver>(
+	event_subscriptions: &RpcSubscriptions<Responder, EventSubscriptionFilter>,
+	state_observer: &StateObserver,
+	shard: &ShardIdentifier,
+) where
+	Responder: SendRpcResponse<Hash = H256>,
+	StateObserver: ObserveState,
+	StateObserver::StateType: SgxExternalitiesTrait,
+{
+	if event_subscriptions.number_of_subscriptions() == 0 {
+		return
+	}
+
+	let events =
+		match state_observer.observe_state(shard, |state| state.execute_with(get_stf_events)) {
+			Ok(events) => events,
+			Err(e) => {
+				error!("Failed to read the STF events of shard {:?}: {:?}", shard, e);
+				return
+			},
+		};
+
+	if events.is_empty() {
+		return
+	}
+
+	event_subscriptions.notify_with(|(subscribed_shard, account)| {
+		if subscribed_shard != shard {
+			return None
+		}
+		let visible_events: Vec<&StfEventRecord> =
+			events.iter().filter(|r| r.event.is_visible_to(account)).collect();
+		(!visible_events.is_empty()).then(|| visible_events.encode())
+	});
+}
+
+/// Confirms the import of a sidechain block and publishes its STF events to the subscribers.
+///
+/// The events of our own blocks are published after block production instead, because
+/// they are not imported.
+pub struct EventPublishingImportConfirmation<ConfirmationHandler, Responder, StateObserver>
+where
+	Responder: SendRpcResponse<Hash = H256>,
+{
+	confirmation_handler: Arc<ConfirmationHandler>,
+	event_subscriptions: Arc<RpcSubscriptions<Responder, EventSubscriptionFilter>>,
+	state_observer: Arc<StateObserver>,
+}
+
+impl<ConfirmationHandler, Responder, StateObserver>
+	EventPublishingImportConfirmation<ConfirmationHandler, Responder, StateObserver>
+where
+	Responder: SendRpcResponse<Hash = H256>,
+	StateObserver: ObserveState,
+	StateObserver::StateType: SgxExternalitiesTrait,
+{
+	pub fn new(
+		confirmation_handler: Arc<ConfirmationHandler>,
+		event_subscriptions: Arc<RpcSubscriptions<Responder, EventSubscriptionFilter>>,
+		state_observer: Arc<StateObserver>,
+	) -> Self {
+		EventPublishingImportConfirmation {
+			confirmation_handler,
+			event_subscriptions,
+			state_observer,
+		}
+	}
+}
+
+impl<SidechainHeader, ConfirmationHandler, Responder, StateObserver>
+	ConfirmBlockImport<SidechainHeader>
+	for EventPublishingImportConfirmation<ConfirmationHandler, Responder, StateObserver>
+where
+	ConfirmationHandler: ConfirmBlockImport<SidechainHeader>,
+	Responder: SendRpcResponse<Hash = H256>,
+	StateObserver: ObserveState,
+	StateObserver::StateType: SgxExternalitiesTrait,
+{
+	fn confirm_import(
+		&self,
+		header: &SidechainHeader,
+		shard: &ShardIdentifier,
+	) -> ConsensusResult<()> {
+		publish_events(self.event_subscriptions.as_ref(), self.state_observer.as_ref(), shard);
+		self.confirmation_handler.confirm_import(header, shard)
+	}
+}
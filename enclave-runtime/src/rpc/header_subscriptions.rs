@@ -0,0 +1,65 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Publishes the headers of imported sidechain blocks to the `chain_subscribeAllHeads` subscribers.
+
+use codec::Encode;
+use itc_direct_rpc_server::{rpc_subscriptions::RpcSubscriptions, SendRpcResponse};
+use itp_types::{ShardIdentifier, H256};
+use its_sidechain::consensus_common::{ConfirmBlockImport, Result as ConsensusResult};
+use std::sync::Arc;
+
+/// Confirms the import of a sidechain block and publishes its header to the subscribers.
+///
+/// Our own blocks are not imported, their headers are published after block production.
+pub struct HeaderPublishingImportConfirmation<ConfirmationHandler, Responder>
+where
+	Responder: SendRpcResponse<Hash = H256>,
+{
+	confirmation_handler: Arc<ConfirmationHandler>,
+	header_subscriptions: Arc<RpcSubscriptions<Responder>>,
+}
+
+impl<ConfirmationHandler, Responder>
+	HeaderPublishingImportConfirmation<ConfirmationHandler, Responder>
+where
+	Responder: SendRpcResponse<Hash = H256>,
+{
+	pub fn new(
+		confirmation_handler: Arc<ConfirmationHandler>,
+		header_subscriptions: Arc<RpcSubscriptions<Responder>>,
+	) -> Self {
+		HeaderPublishingImportConfirmation { confirmation_handler, header_subscriptions }
+	}
+}
+
+impl<SidechainHeader, ConfirmationHandler, Responder> ConfirmBlockImport<SidechainHeader>
+	for HeaderPublishingImportConfirmation<ConfirmationHandler, Responder>
+where
+	SidechainHeader: Encode,
+	ConfirmationHandler: ConfirmBlockImport<SidechainHeader>,
+	Responder: SendRpcResponse<Hash = H256>,
+{
+	fn confirm_import(
+		&self,
+		header: &SidechainHeader,
+		shard: &ShardIdentifier,
+	) -> ConsensusResult<()> {
+		self.header_subscriptions.notify(header.encode());
+		self.confirmation_handler.confirm_import(header, shard)
+	}
+}
@@ -15,5 +15,7 @@
 
 */
 
+pub mod event_subscriptions;
+pub mod header_subscriptions;
 pub mod rpc_response_channel;
 pub mod worker_api_direct;
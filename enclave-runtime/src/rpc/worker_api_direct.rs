@@ -20,22 +20,43 @@ use crate::{
 		generate_dcap_ra_extrinsic_from_quote_internal,
 		generate_ias_ra_extrinsic_from_der_cert_internal,
 	},
+	initialization::global_components::{
+		GLOBAL_OCALL_API_COMPONENT, GLOBAL_SIDECHAIN_IMPORT_QUEUE_COMPONENT,
+		GLOBAL_STATE_HANDLER_COMPONENT, GLOBAL_STATE_OBSERVER_COMPONENT,
+	},
+	rpc::event_subscriptions::{EventSubscriptionAuthenticator, EventSubscriptionFilter},
 	utils::get_validator_accessor_from_solo_or_parachain,
 };
 use codec::Encode;
 use core::result::Result;
-use ita_sgx_runtime::Runtime;
-use itc_parentchain::light_client::{concurrent_access::ValidatorAccess, ExtrinsicSender};
+use ita_sgx_runtime::{Runtime, VERSION};
+use itc_direct_rpc_server::{rpc_subscriptions::RpcSubscriptions, SendRpcResponse};
+use itc_parentchain::light_client::{
+	concurrent_access::ValidatorAccess, ExtrinsicSender, LightClientState,
+};
+use itp_component_container::ComponentGetter;
+use itp_ocall_api::EnclaveAttestationOCallApi;
 use itp_primitives_cache::{GetPrimitives, GLOBAL_PRIMITIVES_CACHE};
 use itp_rpc::RpcReturnValue;
-use itp_sgx_crypto::Rsa3072Seal;
+use itp_sgx_crypto::{Ed25519Seal, Rsa3072Seal};
+use itp_sgx_io::StaticSealedIO;
 use itp_stf_executor::getter_executor::ExecuteGetter;
+use itp_stf_state_handler::query_shard_state::QueryShardState;
+use itp_stf_state_observer::traits::ObserveState;
 use itp_top_pool_author::traits::AuthorApi;
-use itp_types::{DirectRequestStatus, Request, ShardIdentifier, H256};
+use itp_types::{
+	AccountId, DirectRequestStatus, LastSidechainBlock, Request, ShardIdentifier, SystemHealth,
+	SystemVersion, TrustedOperationStatus, H256,
+};
 use itp_utils::{FromHexPrefixed, ToHexPrefixed};
 use its_primitives::types::block::SignedBlock;
-use its_sidechain::rpc_handler::{direct_top_pool_api, import_block_api};
+use its_sidechain::{
+	rpc_handler::{direct_top_pool_api, import_block_api, operation_status_api},
+	state::SidechainSystemExt,
+	validateer_fetch::ValidateerFetch,
+};
 use jsonrpc_core::{serde_json::json, IoHandler, Params, Value};
+use sp_core::Pair;
 use sp_runtime::OpaqueExtrinsic;
 use std::{borrow::ToOwned, format, str, string::String, sync::Arc, vec::Vec};
 
@@ -53,15 +74,21 @@ fn get_all_rpc_methods_string(io_handler: &IoHandler) -> String {
 	format!("methods: [{}]", method_string)
 }
 
-pub fn public_api_rpc_handler<R, G>(top_pool_author: Arc<R>, getter_executor: Arc<G>) -> IoHandler
+pub fn public_api_rpc_handler<R, G, S>(
+	top_pool_author: Arc<R>,
+	getter_executor: Arc<G>,
+	header_subscriptions: Arc<RpcSubscriptions<S>>,
+	event_subscriptions: Arc<RpcSubscriptions<S, EventSubscriptionFilter>>,
+) -> IoHandler
 where
 	R: AuthorApi<H256, H256> + Send + Sync + 'static,
 	G: ExecuteGetter + Send + Sync + 'static,
+	S: SendRpcResponse<Hash = H256> + 'static,
 {
 	let io = IoHandler::new();
 
 	// Add direct TOP pool rpc methods
-	let mut io = direct_top_pool_api::add_top_pool_direct_rpc_methods(top_pool_author, io);
+	let mut io = direct_top_pool_api::add_top_pool_direct_rpc_methods(top_pool_author.clone(), io);
 
 	// author_getShieldingKey
 	let rsa_pubkey_name: &str = "author_getShieldingKey";
@@ -117,9 +144,35 @@ where
 
 	// chain_subscribeAllHeads
 	let chain_subscribe_all_heads_name: &str = "chain_subscribeAllHeads";
-	io.add_sync_method(chain_subscribe_all_heads_name, |_: Params| {
-		let parsed = "world";
-		Ok(Value::String(format!("hello, {}", parsed)))
+	io.add_sync_method(chain_subscribe_all_heads_name, move |_: Params| {
+		let subscription_id = header_subscriptions.subscribe();
+		// The connection is only kept open for a trusted operation status.
+		let json_value = RpcReturnValue::new(
+			subscription_id.encode(),
+			true,
+			DirectRequestStatus::TrustedOperationStatus(TrustedOperationStatus::Submitted),
+		);
+		Ok(json!(json_value.to_hex()))
+	});
+
+	// author_subscribeEvents
+	let author_subscribe_events_name: &str = "author_subscribeEvents";
+	let event_subscription_authenticator = EventSubscriptionAuthenticator::default();
+	io.add_sync_method(author_subscribe_events_name, move |params: Params| {
+		let json_value = match subscribe_events_inner(
+			event_subscriptions.as_ref(),
+			&event_subscription_authenticator,
+			params,
+		) {
+			Ok(subscription_id) => RpcReturnValue::new(
+				subscription_id.encode(),
+				true,
+				DirectRequestStatus::TrustedOperationStatus(TrustedOperationStatus::Submitted),
+			)
+			.to_hex(),
+			Err(error) => compute_hex_encoded_return_error(error.as_str()),
+		};
+		Ok(json!(json_value))
 	});
 
 	// state_getMetadata
@@ -133,8 +186,8 @@ where
 	// state_getRuntimeVersion
 	let state_get_runtime_version_name: &str = "state_getRuntimeVersion";
 	io.add_sync_method(state_get_runtime_version_name, |_: Params| {
-		let parsed = "world";
-		Ok(Value::String(format!("hello, {}", parsed)))
+		let json_value = RpcReturnValue::new(VERSION.encode(), false, DirectRequestStatus::Ok);
+		Ok(json!(json_value.to_hex()))
 	});
 
 	// state_executeGetter
@@ -186,23 +239,47 @@ where
 
 	// system_health
 	let state_health_name: &str = "system_health";
-	io.add_sync_method(state_health_name, |_: Params| {
-		let parsed = "world";
-		Ok(Value::String(format!("hello, {}", parsed)))
+	io.add_sync_method(state_health_name, move |_: Params| {
+		let json_value = match system_health_inner(top_pool_author.as_ref()) {
+			Ok(health) =>
+				RpcReturnValue::new(health.encode(), false, DirectRequestStatus::Ok).to_hex(),
+			Err(error) => compute_hex_encoded_return_error(error.as_str()),
+		};
+		Ok(json!(json_value))
 	});
 
 	// system_name
 	let state_name_name: &str = "system_name";
 	io.add_sync_method(state_name_name, |_: Params| {
-		let parsed = "world";
-		Ok(Value::String(format!("hello, {}", parsed)))
+		let name: &str = env!("CARGO_PKG_NAME");
+		let json_value = RpcReturnValue::new(name.encode(), false, DirectRequestStatus::Ok);
+		Ok(json!(json_value.to_hex()))
 	});
 
 	// system_version
 	let state_version_name: &str = "system_version";
 	io.add_sync_method(state_version_name, |_: Params| {
-		let parsed = "world";
-		Ok(Value::String(format!("hello, {}", parsed)))
+		let json_value = match system_version_inner() {
+			Ok(version) =>
+				RpcReturnValue::new(version.encode(), false, DirectRequestStatus::Ok).to_hex(),
+			Err(error) => compute_hex_encoded_return_error(error.as_str()),
+		};
+		Ok(json!(json_value))
+	});
+
+	// oracle_query
+	#[cfg(feature = "teeracle")]
+	io.add_sync_method("oracle_query", move |params: Params| {
+		let json_value = match oracle_query_inner(params) {
+			Ok(signed_response) => RpcReturnValue {
+				do_watch: false,
+				value: signed_response.encode(),
+				status: DirectRequestStatus::Ok,
+			}
+			.to_hex(),
+			Err(error) => compute_hex_encoded_return_error(error.as_str()),
+		};
+		Ok(json!(json_value))
 	});
 
 	// returns all rpcs methods
@@ -233,6 +310,111 @@ fn execute_getter_inner<G: ExecuteGetter>(
 	Ok(getter_result)
 }
 
+fn subscribe_events_inner<S: SendRpcResponse<Hash = H256>>(
+	event_subscriptions: &RpcSubscriptions<S, EventSubscriptionFilter>,
+	authenticator: &EventSubscriptionAuthenticator,
+	params: Params,
+) -> Result<H256, String> {
+	let hex_encoded_params = params.parse::<Vec<String>>().map_err(|e| format!("{:?}", e))?;
+
+	if hex_encoded_params.len() != 1 {
+		return Err(format!(
+			"Wrong number of arguments for event subscription: {}, expected: {}",
+			hex_encoded_params.len(),
+			1
+		))
+	}
+
+	let request = Request::from_hex(&hex_encoded_params[0]).map_err(|e| format!("{:?}", e))?;
+
+	let filter = authenticator.authenticate(request)?;
+	Ok(event_subscriptions.subscribe_with_filter(filter))
+}
+
+fn system_health_inner<R: AuthorApi<H256, H256>>(
+	top_pool_author: &R,
+) -> Result<SystemHealth, String> {
+	let is_syncing = !GLOBAL_SIDECHAIN_IMPORT_QUEUE_COMPONENT
+		.get()
+		.map_err(|e| format!("{:?}", e))?
+		.is_empty()
+		.map_err(|e| format!("{:?}", e))?;
+
+	let validator_access =
+		get_validator_accessor_from_solo_or_parachain().map_err(|e| format!("{:?}", e))?;
+	let latest_parentchain_header = validator_access
+		.execute_on_validator(|v| v.latest_finalized_header())
+		.map_err(|e| format!("{:?}", e))?;
+	let own_account = AccountId::from(
+		Ed25519Seal::unseal_from_static_file().map_err(|e| format!("{:?}", e))?.public(),
+	);
+	let peers = GLOBAL_OCALL_API_COMPONENT
+		.get()
+		.map_err(|e| format!("{:?}", e))?
+		.current_validateers(&latest_parentchain_header)
+		.map_err(|e| format!("{:?}", e))?
+		.iter()
+		.filter(|validateer| validateer.pubkey != own_account)
+		.count() as u32;
+
+	let pending_trusted_calls = top_pool_author
+		.get_shards()
+		.into_iter()
+		.map(|shard| top_pool_author.get_pending_trusted_calls(shard).len() as u64)
+		.sum();
+
+	let state_observer = GLOBAL_STATE_OBSERVER_COMPONENT.get().map_err(|e| format!("{:?}", e))?;
+	let mut last_sidechain_block: Option<LastSidechainBlock> = None;
+	for shard in GLOBAL_STATE_HANDLER_COMPONENT
+		.get()
+		.map_err(|e| format!("{:?}", e))?
+		.list_shards()
+		.map_err(|e| format!("{:?}", e))?
+	{
+		let (number, hash) = state_observer
+			.observe_state(&shard, |state| (state.get_block_number(), state.get_last_block_hash()))
+			.map_err(|e| format!("{:?}", e))?;
+		if let (Some(number), Some(hash)) = (number, hash) {
+			if last_sidechain_block.as_ref().map_or(true, |last| number > last.number) {
+				last_sidechain_block = Some(LastSidechainBlock { shard, number, hash });
+			}
+		}
+	}
+
+	Ok(SystemHealth { is_syncing, peers, pending_trusted_calls, last_sidechain_block })
+}
+
+fn system_version_inner() -> Result<SystemVersion, String> {
+	let mrenclave = GLOBAL_OCALL_API_COMPONENT
+		.get()
+		.map_err(|e| format!("{:?}", e))?
+		.get_mrenclave_of_self()
+		.map_err(|e| format!("{:?}", e))?
+		.m;
+
+	Ok(SystemVersion { version: env!("CARGO_PKG_VERSION").into(), mrenclave })
+}
+
+#[cfg(feature = "teeracle")]
+fn oracle_query_inner(
+	params: Params,
+) -> Result<ita_oracle::oracle_query::SignedOracleQueryResponse, String> {
+	let hex_encoded_params = params.parse::<Vec<String>>().map_err(|e| format!("{:?}", e))?;
+
+	if hex_encoded_params.len() != 1 {
+		return Err(format!(
+			"Wrong number of arguments for oracle query: {}, expected: {}",
+			hex_encoded_params.len(),
+			1
+		))
+	}
+
+	let query = ita_oracle::oracle_query::OracleQuery::from_hex(&hex_encoded_params[0])
+		.map_err(|e| format!("{:?}", e))?;
+
+	crate::teeracle::execute_oracle_query(&query).map_err(|e| format!("{:?}", e))
+}
+
 fn forward_dcap_quote_inner(params: Params) -> Result<OpaqueExtrinsic, String> {
 	let hex_encoded_params = params.parse::<Vec<String>>().map_err(|e| format!("{:?}", e))?;
 
@@ -287,13 +469,18 @@ fn attesteer_forward_ias_attestation_report_inner(
 	Ok(ext)
 }
 
-pub fn sidechain_io_handler<ImportFn, Error>(import_fn: ImportFn) -> IoHandler
+pub fn sidechain_io_handler<ImportFn, Error, IsPendingFn>(
+	import_fn: ImportFn,
+	is_pending_fn: IsPendingFn,
+) -> IoHandler
 where
 	ImportFn: Fn(SignedBlock) -> Result<(), Error> + Sync + Send + 'static,
 	Error: std::fmt::Debug,
+	IsPendingFn: Fn(&H256) -> bool + Sync + Send + 'static,
 {
 	let io = IoHandler::new();
-	import_block_api::add_import_block_rpc_method(import_fn, io)
+	let io = import_block_api::add_import_block_rpc_method(import_fn, io);
+	operation_status_api::add_operation_status_rpc_method(is_pending_fn, io)
 }
 
 #[cfg(feature = "test")]
@@ -0,0 +1,123 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+//! Export and import of shard states as signed archives, see [`StateArchive`].
+
+use crate::{
+	error::{Error, Result},
+	initialization::global_components::{
+		GLOBAL_ATTESTATION_HANDLER_COMPONENT, GLOBAL_OCALL_API_COMPONENT,
+		GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT, GLOBAL_STATE_HANDLER_COMPONENT,
+	},
+	utils::get_validator_accessor_from_solo_or_parachain,
+};
+use codec::{Decode, Encode};
+use ita_stf::{State as StfState, StateType as StfStateType};
+use itc_parentchain::light_client::{concurrent_access::ValidatorAccess, LightClientState};
+use itp_attestation_handler::AttestationHandler;
+use itp_component_container::ComponentGetter;
+use itp_sgx_crypto::{key_repository::AccessKey, Aes, Ed25519Seal};
+use itp_sgx_externalities::SgxExternalitiesTrait;
+use itp_sgx_io::StaticSealedIO;
+use itp_stf_state_handler::{handle_state::HandleState, state_archive::StateArchive};
+use itp_types::ShardIdentifier;
+use its_sidechain::validateer_fetch::ValidateerFetch;
+use log::*;
+use sgx_crypto_helper::rsa3072::Rsa3072PubKey;
+use sgx_rand::{Rng, StdRng};
+use sp_core::{ed25519, Pair};
+use std::vec::Vec;
+
+/// Export the current state of a shard, encrypted to the `target_shielding_key`.
+///
+/// Returns the encoded archive.
+pub(crate) fn export_state(
+	shard: &ShardIdentifier,
+	target_shielding_key: &Rsa3072PubKey,
+) -> Result<Vec<u8>> {
+	let state_handler = GLOBAL_STATE_HANDLER_COMPONENT.get()?;
+	let signer = Ed25519Seal::unseal_from_static_file()?;
+
+	let encoded_state = state_handler.execute_on_current(shard, |state, _| state.state.encode())?;
+
+	let archive = StateArchive::create(
+		*shard,
+		encoded_state,
+		new_state_key()?,
+		target_shielding_key,
+		&signer,
+	)?;
+	info!("Exported state of shard {:?}", shard);
+	Ok(archive.encode())
+}
+
+/// Import a state archive, replacing the state of its shard.
+///
+/// The archive must have been exported by an enclave with the same MRENCLAVE as this enclave,
+/// see [`trusted_archive_signers`]. The previous state of the shard remains available in the
+/// state snapshot history.
+pub(crate) fn import_state(mut encoded_archive: &[u8]) -> Result<ShardIdentifier> {
+	let state_handler = GLOBAL_STATE_HANDLER_COMPONENT.get()?;
+	let shielding_key = GLOBAL_SHIELDING_KEY_REPOSITORY_COMPONENT.get()?.retrieve_key()?;
+
+	let archive = StateArchive::decode(&mut encoded_archive)?;
+	let (shard, encoded_state) = archive.open(&shielding_key, &trusted_archive_signers()?)?;
+
+	let state = StfStateType::decode(&mut encoded_state.as_slice())?;
+	state_handler.reset(StfState::new(state), &shard)?;
+	info!("Imported state of shard {:?}", shard);
+	Ok(shard)
+}
+
+/// Signing keys of the enclaves whose state archives we accept.
+///
+/// These are our own key and the keys of all enclaves that are registered with our MRENCLAVE
+/// in the enclave registry of the parentchain, as verified by our light client. Both the key
+/// and the MRENCLAVE are therefore attested and not just claimed by the archive or the host.
+fn trusted_archive_signers() -> Result<Vec<ed25519::Public>> {
+	let mut trusted_signers = vec![Ed25519Seal::unseal_from_static_file()?.public()];
+
+	let validator_access = match get_validator_accessor_from_solo_or_parachain() {
+		Ok(v) => v,
+		Err(e) => {
+			warn!("No parentchain light client, only trusting our own state archives: {:?}", e);
+			return Ok(trusted_signers)
+		},
+	};
+	let latest_parentchain_header =
+		validator_access.execute_on_validator(|v| v.latest_finalized_header())?;
+	let mrenclave = GLOBAL_ATTESTATION_HANDLER_COMPONENT.get()?.get_mrenclave()?;
+
+	trusted_signers.extend(
+		GLOBAL_OCALL_API_COMPONENT
+			.get()?
+			.current_validateers(&latest_parentchain_header)?
+			.into_iter()
+			.filter(|enclave| enclave.mr_enclave == mrenclave)
+			.map(|enclave| ed25519::Public::from_raw(enclave.pubkey.into())),
+	);
+	Ok(trusted_signers)
+}
+
+/// Fresh random state key, e.g. to encrypt a single state archive with.
+pub(crate) fn new_state_key() -> Result<Aes> {
+	let mut rng = StdRng::new().map_err(|e| Error::Other(format!("{:?}", e).into()))?;
+	let mut key = [0u8; 16];
+	let mut init_vec = [0u8; 16];
+	rng.fill_bytes(&mut key);
+	rng.fill_bytes(&mut init_vec);
+	Ok(Aes::new(key, init_vec))
+}
@@ -0,0 +1,73 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Rotation of the state file key, and re-encryption of the state files with the current
+//! state file key.
+//!
+//! The state file key is local to this enclave. The state key shared with the other
+//! validateers, e.g. to encrypt the state diffs of sidechain blocks, is not rotated.
+
+use crate::{
+	error::Result,
+	initialization::global_components::{
+		GLOBAL_RETIRED_STATE_KEYS_REPOSITORY_COMPONENT, GLOBAL_STATE_FILE_KEY_REPOSITORY_COMPONENT,
+		GLOBAL_STATE_HANDLER_COMPONENT,
+	},
+	state_archive::new_state_key,
+};
+use itp_component_container::ComponentGetter;
+use itp_sgx_crypto::key_repository::{forget_retired_keys, rotate_key, AccessKey};
+use itp_stf_state_handler::re_encrypt_state::ReEncryptState;
+use log::*;
+
+/// Replace the state file key with a new random key.
+///
+/// The replaced key is retired, it remains available to read state files until they are
+/// re-encrypted by [`re_encrypt_state`].
+pub(crate) fn rotate_state_key() -> Result<()> {
+	let state_file_key_repository = GLOBAL_STATE_FILE_KEY_REPOSITORY_COMPONENT.get()?;
+	let retired_state_keys_repository = GLOBAL_RETIRED_STATE_KEYS_REPOSITORY_COMPONENT.get()?;
+
+	rotate_key(
+		state_file_key_repository.as_ref(),
+		retired_state_keys_repository.as_ref(),
+		new_state_key()?,
+	)?;
+	info!("Rotated the state file key, state files will be re-encrypted with the new key");
+	Ok(())
+}
+
+/// Re-encrypt all state snapshots with the current state file key, including legacy state files
+/// without authenticated encryption. Afterwards, the retired state keys are no longer needed.
+///
+/// Returns the number of re-encrypted snapshots.
+pub(crate) fn re_encrypt_state() -> Result<usize> {
+	let state_handler = GLOBAL_STATE_HANDLER_COMPONENT.get()?;
+	let retired_state_keys_repository = GLOBAL_RETIRED_STATE_KEYS_REPOSITORY_COMPONENT.get()?;
+
+	// Keys retired while re-encrypting may still protect snapshots written meanwhile.
+	let retired_state_keys = retired_state_keys_repository.retrieve_key()?;
+	let re_encrypted_snapshots = state_handler.re_encrypt_states()?;
+	forget_retired_keys(retired_state_keys_repository.as_ref(), &retired_state_keys)?;
+
+	info!(
+		"Re-encrypted {} state snapshot(s), forgot {} retired state key(s)",
+		re_encrypted_snapshots,
+		retired_state_keys.len()
+	);
+	Ok(re_encrypted_snapshots)
+}
@@ -17,7 +17,9 @@
 
 use crate::{
 	error::{Error, Result},
-	initialization::global_components::GLOBAL_OCALL_API_COMPONENT,
+	initialization::global_components::{
+		GLOBAL_OCALL_API_COMPONENT, GLOBAL_ORACLE_SOURCE_CONFIG_STORE_COMPONENT,
+	},
 	utils::{
 		get_extrinsic_factory_from_solo_or_parachain,
 		get_node_metadata_repository_from_solo_or_parachain,
@@ -26,18 +28,25 @@ use crate::{
 use codec::{Decode, Encode};
 use core::slice;
 use ita_oracle::{
-	create_coin_gecko_oracle, create_coin_market_cap_oracle, create_open_meteo_weather_oracle,
+	create_exchange_rate_aggregator, create_on_demand_oracle, create_open_meteo_weather_oracle,
 	metrics_exporter::ExportMetrics,
+	oracle_query::{OracleQuery, SignedOracleQueryResponse},
 	oracles::{
-		exchange_rate_oracle::{ExchangeRateOracle, GetExchangeRate},
-		weather_oracle::{GetLongitude, WeatherOracle},
+		exchange_rate_aggregator::GetAggregatedExchangeRate,
+		on_demand_oracle::ExecuteOracleQuery,
+		weather_oracle::{GetWeatherReport, WeatherOracle},
 	},
-	traits::OracleSource,
-	types::{TradingInfo, TradingPair, WeatherInfo, WeatherQuery},
+	source_config::{parse_source_configs, EXCHANGE_RATE_FEED},
+	source_config_store::{AccessOracleSourceConfigs, UpdateOracleSourceConfigs},
+	traits::ExecuteOracleRequest,
+	types::{TradingPair, WeatherInfo, WeatherQuery, WeatherReport},
 };
 use itp_component_container::ComponentGetter;
 use itp_extrinsics_factory::CreateExtrinsics;
 use itp_node_api::metadata::{pallet_teeracle::TeeracleCallIndexes, provider::AccessNodeMetadata};
+use itp_settings::teeracle::{MAX_EXCHANGE_RATE_DEVIATION_PERCENT_LIMIT, WEATHER_FORECAST_HOURS};
+use itp_sgx_crypto::Ed25519Seal;
+use itp_sgx_io::StaticSealedIO;
 use itp_types::OpaqueCall;
 use itp_utils::write_slice_and_whitespace_pad;
 use log::*;
@@ -53,34 +62,42 @@ fn update_weather_data_internal(weather_info: WeatherInfo) -> Result<Vec<OpaqueE
 
 	let open_meteo_weather_oracle = create_open_meteo_weather_oracle(ocall_api);
 
-	match get_longitude(weather_info, open_meteo_weather_oracle) {
+	match get_weather_report(weather_info, open_meteo_weather_oracle) {
 		Ok(opaque_call) => extrinsic_calls.push(opaque_call),
 		Err(e) => {
-			error!("[-] Failed to get the newest longitude from OpenMeteo. {:?}", e);
+			error!("[-] Failed to get the newest weather report from OpenMeteo. {:?}", e);
 		},
 	};
 	let extrinsics = extrinsics_factory.create_extrinsics(extrinsic_calls.as_slice(), None)?;
 	Ok(extrinsics)
 }
 
-fn get_longitude<OracleSourceType, MetricsExporter>(
+fn get_weather_report<OracleSourceType, MetricsExporter>(
 	weather_info: WeatherInfo,
 	oracle: WeatherOracle<OracleSourceType, MetricsExporter>,
 ) -> Result<OpaqueCall>
 where
-	OracleSourceType: OracleSource<
+	OracleSourceType: ExecuteOracleRequest<
 		WeatherInfo,
-		OracleRequestResult = std::result::Result<f32, ita_oracle::error::Error>,
+		OracleRequestResult = std::result::Result<WeatherReport, ita_oracle::error::Error>,
 	>,
 	MetricsExporter: ExportMetrics<WeatherInfo>,
 {
-	let longitude =
-		oracle.get_longitude(weather_info.clone()).map_err(|e| Error::Other(e.into()))?;
+	let weather_report = oracle
+		.get_weather_report(weather_info.clone())
+		.map_err(|e| Error::Other(e.into()))?;
 
 	let base_url = oracle.get_base_url().map_err(|e| Error::Other(e.into()))?;
 	let source_base_url = base_url.as_str();
 
-	println!("Update the longitude:  {}, for source {}", longitude, source_base_url);
+	println!(
+		"Update the weather: {} °C, {} mm, {} km/h at {}, for source {}",
+		weather_report.temperature,
+		weather_report.precipitation,
+		weather_report.wind_speed,
+		weather_report.time,
+		source_base_url
+	);
 
 	let node_metadata_repository = get_node_metadata_repository_from_solo_or_parachain()?;
 
@@ -93,7 +110,7 @@ where
 		call_ids,
 		weather_info.weather_query.key().as_bytes().to_vec(),
 		source_base_url.as_bytes().to_vec(),
-		longitude.encode(),
+		weather_report.encode(),
 	));
 
 	Ok(call)
@@ -128,7 +145,8 @@ pub unsafe extern "C" fn update_weather_data_xt(
 		},
 	};
 
-	let weather_query = WeatherQuery { longitude, latitude, hourly: " ".into() };
+	let weather_query =
+		WeatherQuery { longitude, latitude, forecast_hours: WEATHER_FORECAST_HOURS };
 	let weather_info = WeatherInfo { weather_query };
 
 	let extrinsics = match update_weather_data_internal(weather_info) {
@@ -151,25 +169,36 @@ pub unsafe extern "C" fn update_weather_data_xt(
 	sgx_status_t::SGX_SUCCESS
 }
 
-/// For now get the crypto/fiat currency exchange rate from coingecko and CoinMarketCap.
+/// Get the median crypto/fiat currency exchange rates of all sources, for all the
+/// (SCALE encoded) trading pairs, as a single batched extrinsic. Rates deviating more than
+/// `max_deviation_percent` from the median of all sources are rejected.
 #[no_mangle]
 pub unsafe extern "C" fn update_market_data_xt(
-	crypto_currency_ptr: *const u8,
-	crypto_currency_size: u32,
-	fiat_currency_ptr: *const u8,
-	fiat_currency_size: u32,
+	trading_pairs_ptr: *const u8,
+	trading_pairs_size: u32,
+	max_deviation_percent: u32,
 	unchecked_extrinsic: *mut u8,
 	unchecked_extrinsic_size: u32,
 ) -> sgx_status_t {
-	let mut crypto_currency_slice =
-		slice::from_raw_parts(crypto_currency_ptr, crypto_currency_size as usize);
-	let crypto_currency: String = Decode::decode(&mut crypto_currency_slice).unwrap();
+	let mut trading_pairs_slice =
+		slice::from_raw_parts(trading_pairs_ptr, trading_pairs_size as usize);
+	let trading_pairs: Vec<TradingPair> = match Decode::decode(&mut trading_pairs_slice) {
+		Ok(trading_pairs) => trading_pairs,
+		Err(e) => {
+			error!("Could not decode trading pairs: {:?}", e);
+			return sgx_status_t::SGX_ERROR_UNEXPECTED
+		},
+	};
 
-	let mut fiat_currency_slice =
-		slice::from_raw_parts(fiat_currency_ptr, fiat_currency_size as usize);
-	let fiat_currency: String = Decode::decode(&mut fiat_currency_slice).unwrap();
+	if max_deviation_percent > MAX_EXCHANGE_RATE_DEVIATION_PERCENT_LIMIT {
+		error!(
+			"Max exchange rate deviation of {}% exceeds the limit of {}%",
+			max_deviation_percent, MAX_EXCHANGE_RATE_DEVIATION_PERCENT_LIMIT
+		);
+		return sgx_status_t::SGX_ERROR_INVALID_PARAMETER
+	}
 
-	let extrinsics = match update_market_data_internal(crypto_currency, fiat_currency) {
+	let extrinsics = match update_market_data_internal(trading_pairs, max_deviation_percent) {
 		Ok(xts) => xts,
 		Err(e) => {
 			error!("Update market data failed: {:?}", e);
@@ -194,59 +223,37 @@ pub unsafe extern "C" fn update_market_data_xt(
 }
 
 fn update_market_data_internal(
-	crypto_currency: String,
-	fiat_currency: String,
+	trading_pairs: Vec<TradingPair>,
+	max_deviation_percent: u32,
 ) -> Result<Vec<OpaqueExtrinsic>> {
 	let extrinsics_factory = get_extrinsic_factory_from_solo_or_parachain()?;
 	let ocall_api = GLOBAL_OCALL_API_COMPONENT.get()?;
 
-	let mut extrinsic_calls: Vec<OpaqueCall> = Vec::new();
+	let source_configs = GLOBAL_ORACLE_SOURCE_CONFIG_STORE_COMPONENT
+		.get()?
+		.source_configs_of_feed(EXCHANGE_RATE_FEED)
+		.map_err(|e| Error::Other(e.into()))?;
 
-	// Get the exchange rate
-	let trading_pair = TradingPair { crypto_currency, fiat_currency };
+	// Get the median exchange rates of all sources (CoinGecko, CoinMarketCap, configured ones)
+	let exchange_rate_aggregator =
+		create_exchange_rate_aggregator(ocall_api, max_deviation_percent, source_configs);
 
-	let coin_gecko_oracle = create_coin_gecko_oracle(ocall_api.clone());
+	let extrinsic_calls = get_aggregated_exchange_rates(&trading_pairs, exchange_rate_aggregator)?;
 
-	match get_exchange_rate(trading_pair.clone(), coin_gecko_oracle) {
-		Ok(opaque_call) => extrinsic_calls.push(opaque_call),
-		Err(e) => {
-			error!("[-] Failed to get the newest exchange rate from CoinGecko. {:?}", e);
-		},
+	// One extrinsic for all trading pairs, a failing one doesn't keep the others from updating.
+	let extrinsics = match extrinsic_calls.len() {
+		0 => Vec::new(),
+		1 => extrinsics_factory.create_extrinsics(extrinsic_calls.as_slice(), None)?,
+		_ => vec![extrinsics_factory.create_batch_extrinsic(extrinsic_calls.as_slice(), None)?],
 	};
-
-	let coin_market_cap_oracle = create_coin_market_cap_oracle(ocall_api);
-	match get_exchange_rate(trading_pair, coin_market_cap_oracle) {
-		Ok(oc) => extrinsic_calls.push(oc),
-		Err(e) => {
-			error!("[-] Failed to get the newest exchange rate from CoinMarketCap. {:?}", e);
-		},
-	};
-
-	let extrinsics = extrinsics_factory.create_extrinsics(extrinsic_calls.as_slice(), None)?;
 	Ok(extrinsics)
 }
 
-fn get_exchange_rate<OracleSourceType, MetricsExporter>(
-	trading_pair: TradingPair,
-	oracle: ExchangeRateOracle<OracleSourceType, MetricsExporter>,
-) -> Result<OpaqueCall>
-where
-	OracleSourceType: OracleSource<TradingInfo>,
-	MetricsExporter: ExportMetrics<TradingInfo>,
-{
-	let (rate, base_url) = oracle
-		.get_exchange_rate(trading_pair.clone())
-		.map_err(|e| Error::Other(e.into()))?;
-
-	let source_base_url = base_url.as_str();
-
-	println!(
-		"Update the exchange rate:  {} = {:?} for source {}",
-		trading_pair.clone().key(),
-		rate,
-		source_base_url,
-	);
-
+/// Calls updating the exchange rates, for all trading pairs that could be aggregated.
+fn get_aggregated_exchange_rates<ExchangeRateAggregator: GetAggregatedExchangeRate>(
+	trading_pairs: &[TradingPair],
+	aggregator: ExchangeRateAggregator,
+) -> Result<Vec<OpaqueCall>> {
 	let node_metadata_repository = get_node_metadata_repository_from_solo_or_parachain()?;
 
 	let call_ids = node_metadata_repository
@@ -254,12 +261,75 @@ where
 		.map_err(Error::NodeMetadataProvider)?
 		.map_err(|e| Error::Other(format!("{:?}", e).into()))?;
 
-	let call = OpaqueCall::from_tuple(&(
-		call_ids,
-		source_base_url.as_bytes().to_vec(),
-		trading_pair.key().as_bytes().to_vec(),
-		Some(rate),
-	));
+	let mut calls = Vec::new();
+	for (trading_pair, aggregated) in trading_pairs
+		.iter()
+		.zip(aggregator.get_aggregated_exchange_rates(trading_pairs))
+	{
+		let aggregated = match aggregated {
+			Ok(aggregated) => aggregated,
+			Err(e) => {
+				error!("[-] Failed to aggregate the exchange rate of {:?}: {:?}", trading_pair, e);
+				continue
+			},
+		};
+		let data_source = aggregated.data_source();
+
+		println!(
+			"Update the exchange rate:  {} = {:?} for source {} of {} sources (rejected: {:?})",
+			trading_pair.clone().key(),
+			aggregated.rate,
+			data_source,
+			aggregated.number_of_sources(),
+			aggregated.rejected_sources,
+		);
+
+		calls.push(OpaqueCall::from_tuple(&(
+			call_ids,
+			data_source.as_bytes().to_vec(),
+			trading_pair.clone().key().as_bytes().to_vec(),
+			Some(aggregated.rate),
+		)));
+	}
 
-	Ok(call)
+	Ok(calls)
+}
+
+/// Replace the runtime-configured oracle sources. The configs are passed as JSON array,
+/// validated against the domain policy compiled into the enclave and sealed.
+#[no_mangle]
+pub unsafe extern "C" fn set_oracle_source_configs(
+	configs_json: *const u8,
+	configs_json_size: u32,
+) -> sgx_status_t {
+	let configs_json_slice = slice::from_raw_parts(configs_json, configs_json_size as usize);
+
+	if let Err(e) = set_oracle_source_configs_internal(configs_json_slice) {
+		error!("Setting the oracle source configs failed: {:?}", e);
+		return sgx_status_t::SGX_ERROR_UNEXPECTED
+	}
+
+	sgx_status_t::SGX_SUCCESS
+}
+
+fn set_oracle_source_configs_internal(configs_json: &[u8]) -> Result<()> {
+	let configs = parse_source_configs(configs_json).map_err(|e| Error::Other(e.into()))?;
+
+	GLOBAL_ORACLE_SOURCE_CONFIG_STORE_COMPONENT
+		.get()?
+		.set_source_configs(configs)
+		.map_err(|e| Error::Other(e.into()))
+}
+
+/// Fetch the queried value now and sign the response with the enclave key.
+pub(crate) fn execute_oracle_query(query: &OracleQuery) -> Result<SignedOracleQueryResponse> {
+	let config_store = GLOBAL_ORACLE_SOURCE_CONFIG_STORE_COMPONENT.get()?;
+	let ocall_api = GLOBAL_OCALL_API_COMPONENT.get()?;
+
+	let response = create_on_demand_oracle(config_store, ocall_api)
+		.execute_oracle_query(query)
+		.map_err(|e| Error::Other(e.into()))?;
+
+	let signer = Ed25519Seal::unseal_from_static_file().map_err(Error::Crypto)?;
+	Ok(SignedOracleQueryResponse::new_signed(response, &signer))
 }
@@ -16,23 +16,36 @@
 
 */
 
-use crate::{rpc::worker_api_direct::public_api_rpc_handler, Hash};
+use crate::{
+	rpc::worker_api_direct::public_api_rpc_handler,
+	test::mocks::rpc_responder_mock::RpcResponderMock, Hash,
+};
 use codec::{Decode, Encode};
 use ita_stf::{Getter, TrustedGetter, TrustedGetterSigned};
 use itc_direct_rpc_server::{
 	create_determine_watch, rpc_connection_registry::ConnectionRegistry,
-	rpc_ws_handler::RpcWsHandler,
+	rpc_subscriptions::RpcSubscriptions, rpc_ws_handler::RpcWsHandler, RpcConnectionRegistry,
 };
 use itc_tls_websocket_server::{ConnectionToken, WebSocketMessageHandler};
 use itp_rpc::{RpcRequest, RpcReturnValue};
 use itp_stf_executor::{getter_executor::GetterExecutor, mocks::GetStateMock};
+use itp_stf_primitives::types::KeyPair;
 use itp_stf_state_observer::mock::ObserveStateMock;
+use itp_time_utils::now_as_millis;
 use itp_top_pool_author::mocks::AuthorApiMock;
 use itp_types::{AccountId, DirectRequestStatus, Request, ShardIdentifier};
 use itp_utils::{FromHexPrefixed, ToHexPrefixed};
-use sp_core::ed25519::Signature;
+use sp_core::{
+	ed25519::{self, Signature},
+	Pair,
+};
 use sp_runtime::MultiSignature;
-use std::{string::ToString, sync::Arc, vec::Vec};
+use std::{
+	boxed::Box,
+	string::{String, ToString},
+	sync::Arc,
+	vec::Vec,
+};
 
 pub fn get_state_request_works() {
 	type TestState = u64;
@@ -45,7 +58,14 @@ pub fn get_state_request_works() {
 	let getter_executor =
 		Arc::new(GetterExecutor::<_, GetStateMock<TestState>>::new(state_observer));
 	let top_pool_author = Arc::new(AuthorApiMock::default());
-	let io_handler = public_api_rpc_handler(top_pool_author, getter_executor);
+	let header_subscriptions = Arc::new(RpcSubscriptions::new(Arc::new(RpcResponderMock::new())));
+	let event_subscriptions = Arc::new(RpcSubscriptions::new(Arc::new(RpcResponderMock::new())));
+	let io_handler = public_api_rpc_handler(
+		top_pool_author,
+		getter_executor,
+		header_subscriptions,
+		event_subscriptions,
+	);
 	let rpc_handler = Arc::new(RpcWsHandler::new(io_handler, watch_extractor, connection_registry));
 
 	let getter = Getter::trusted(TrustedGetterSigned::new(
@@ -77,3 +97,123 @@ pub fn get_state_request_works() {
 		Option::decode(&mut rpc_return_value.value.as_slice()).unwrap();
 	assert_eq!(decoded_value, Some(state.encode()));
 }
+
+pub fn subscribe_all_heads_keeps_connection_open() {
+	let connection_registry = Arc::new(ConnectionRegistry::<Hash, ConnectionToken>::new());
+	let watch_extractor = Arc::new(create_determine_watch::<Hash>());
+
+	let state_observer = Arc::new(ObserveStateMock::<u64>::new(0u64));
+	let getter_executor = Arc::new(GetterExecutor::<_, GetStateMock<u64>>::new(state_observer));
+	let top_pool_author = Arc::new(AuthorApiMock::default());
+	let header_subscriptions = Arc::new(RpcSubscriptions::new(Arc::new(RpcResponderMock::new())));
+	let event_subscriptions = Arc::new(RpcSubscriptions::new(Arc::new(RpcResponderMock::new())));
+	let io_handler = public_api_rpc_handler(
+		top_pool_author,
+		getter_executor,
+		header_subscriptions.clone(),
+		event_subscriptions,
+	);
+	let rpc_handler =
+		Arc::new(RpcWsHandler::new(io_handler, watch_extractor, connection_registry.clone()));
+
+	let request_string =
+		RpcRequest::compose_jsonrpc_call("chain_subscribeAllHeads".to_string(), vec![]).unwrap();
+
+	rpc_handler.handle_message(ConnectionToken(1), request_string).unwrap();
+
+	assert_eq!(1, header_subscriptions.number_of_subscriptions());
+	assert!(!connection_registry.is_empty());
+}
+
+pub fn subscribe_events_requires_fresh_signed_subscription_getter() {
+	let connection_registry = Arc::new(ConnectionRegistry::<Hash, ConnectionToken>::new());
+	let watch_extractor = Arc::new(create_determine_watch::<Hash>());
+
+	let state_observer = Arc::new(ObserveStateMock::<u64>::new(0u64));
+	let getter_executor = Arc::new(GetterExecutor::<_, GetStateMock<u64>>::new(state_observer));
+	let top_pool_author = Arc::new(AuthorApiMock::default());
+	let header_subscriptions = Arc::new(RpcSubscriptions::new(Arc::new(RpcResponderMock::new())));
+	let event_subscriptions = Arc::new(RpcSubscriptions::new(Arc::new(RpcResponderMock::new())));
+	let io_handler = public_api_rpc_handler(
+		top_pool_author,
+		getter_executor,
+		header_subscriptions,
+		event_subscriptions.clone(),
+	);
+	let rpc_handler =
+		Arc::new(RpcWsHandler::new(io_handler, watch_extractor, connection_registry.clone()));
+
+	let pair = ed25519::Pair::from_seed(b"12345678901234567890123456789012");
+	let account: AccountId = pair.public().into();
+	let shard = ShardIdentifier::default();
+	let now = now_as_millis();
+	let signed_getter = |timestamp| {
+		Getter::trusted(
+			TrustedGetter::subscribe_events(account.clone(), timestamp)
+				.sign(&KeyPair::Ed25519(Box::new(pair.clone()))),
+		)
+	};
+
+	let unsigned_getter = Getter::trusted(TrustedGetterSigned::new(
+		TrustedGetter::subscribe_events(account.clone(), now),
+		MultiSignature::Ed25519(Signature::from_raw([0u8; 64])),
+	));
+	rpc_handler
+		.handle_message(ConnectionToken(1), subscribe_events_request(shard, unsigned_getter))
+		.unwrap();
+
+	let expired_getter = signed_getter(now - 3_600_000);
+	rpc_handler
+		.handle_message(ConnectionToken(2), subscribe_events_request(shard, expired_getter))
+		.unwrap();
+
+	assert_eq!(0, event_subscriptions.number_of_subscriptions());
+	assert!(connection_registry.is_empty());
+
+	rpc_handler
+		.handle_message(ConnectionToken(3), subscribe_events_request(shard, signed_getter(now)))
+		.unwrap();
+
+	assert_eq!(1, event_subscriptions.number_of_subscriptions());
+	assert!(!connection_registry.is_empty());
+
+	// A replayed credential is rejected.
+	rpc_handler
+		.handle_message(ConnectionToken(4), subscribe_events_request(shard, signed_getter(now)))
+		.unwrap();
+
+	assert_eq!(1, event_subscriptions.number_of_subscriptions());
+}
+
+pub fn subscribe_events_without_params_is_rejected() {
+	let connection_registry = Arc::new(ConnectionRegistry::<Hash, ConnectionToken>::new());
+	let watch_extractor = Arc::new(create_determine_watch::<Hash>());
+
+	let state_observer = Arc::new(ObserveStateMock::<u64>::new(0u64));
+	let getter_executor = Arc::new(GetterExecutor::<_, GetStateMock<u64>>::new(state_observer));
+	let top_pool_author = Arc::new(AuthorApiMock::default());
+	let header_subscriptions = Arc::new(RpcSubscriptions::new(Arc::new(RpcResponderMock::new())));
+	let event_subscriptions = Arc::new(RpcSubscriptions::new(Arc::new(RpcResponderMock::new())));
+	let io_handler = public_api_rpc_handler(
+		top_pool_author,
+		getter_executor,
+		header_subscriptions,
+		event_subscriptions.clone(),
+	);
+	let rpc_handler =
+		Arc::new(RpcWsHandler::new(io_handler, watch_extractor, connection_registry.clone()));
+
+	let request_string =
+		RpcRequest::compose_jsonrpc_call("author_subscribeEvents".to_string(), vec![]).unwrap();
+
+	rpc_handler.handle_message(ConnectionToken(1), request_string).unwrap();
+
+	assert_eq!(0, event_subscriptions.number_of_subscriptions());
+	assert!(connection_registry.is_empty());
+}
+
+fn subscribe_events_request(shard: ShardIdentifier, getter: Getter) -> String {
+	let request = Request { shard, cyphertext: getter.encode() };
+	RpcRequest::compose_jsonrpc_call("author_subscribeEvents".to_string(), vec![request.to_hex()])
+		.unwrap()
+}
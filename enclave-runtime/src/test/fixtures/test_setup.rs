@@ -38,8 +38,9 @@ use itp_types::{Block, MrEnclave};
 use sp_core::{crypto::Pair, ed25519 as spEd25519};
 use std::sync::Arc;
 
-pub type TestRpcResponder = RpcResponderMock<ExtrinsicHash<SidechainApi<Block>>>;
-pub type TestTopPool = BasicPool<SidechainApi<Block>, Block, TestRpcResponder>;
+pub type TestSidechainApi = SidechainApi<Block, HandleStateMock, TestStf>;
+pub type TestRpcResponder = RpcResponderMock<ExtrinsicHash<TestSidechainApi>>;
+pub type TestTopPool = BasicPool<TestSidechainApi, Block, TestRpcResponder>;
 pub type TestShieldingKeyRepo = KeyRepositoryMock<ShieldingCryptoMock>;
 pub type TestTopPoolAuthor = Author<
 	TestTopPool,
@@ -70,7 +71,7 @@ pub fn test_setup() -> (
 	let state_handler = Arc::new(HandleStateMock::default());
 	let (state, shard) =
 		init_state(state_handler.as_ref(), enclave_call_signer(&shielding_key).public().into());
-	let top_pool = test_top_pool();
+	let top_pool = test_top_pool(state_handler.clone());
 	let mrenclave = OcallApi.get_mrenclave_of_self().unwrap().m;
 
 	let node_metadata_repo = Arc::new(NodeMetadataRepository::new(NodeMetadataMock::new()));
@@ -97,8 +98,8 @@ pub fn test_setup() -> (
 	)
 }
 
-pub fn test_top_pool() -> TestTopPool {
-	let chain_api = Arc::new(SidechainApi::<Block>::new());
+pub fn test_top_pool(state_handler: Arc<HandleStateMock>) -> TestTopPool {
+	let chain_api = Arc::new(TestSidechainApi::new(state_handler));
 	let top_pool =
 		BasicPool::create(Default::default(), chain_api, Arc::new(TestRpcResponder::new()));
 
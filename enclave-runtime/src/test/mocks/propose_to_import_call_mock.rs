@@ -20,8 +20,8 @@ use crate::test::mocks::types::TestBlockImporter;
 use codec::{Decode, Encode};
 use itp_ocall_api::{EnclaveOnChainOCallApi, EnclaveSidechainOCallApi, Result};
 use itp_types::{
-	storage::StorageEntryVerified, BlockHash, Header as ParentchainHeader, ShardIdentifier,
-	WorkerRequest, WorkerResponse, H256,
+	storage::StorageEntryVerified, BlockHash, Header as ParentchainHeader, PalletString, Request,
+	ShardIdentifier, WorkerRequest, WorkerResponse, H256,
 };
 use its_primitives::types::block::SignedBlock as SignedSidechainBlockType;
 use its_sidechain::consensus_common::BlockImport;
@@ -109,4 +109,12 @@ impl EnclaveSidechainOCallApi for ProposeToImportOCallApi {
 	) -> SgxResult<Vec<SignedSidechainBlock>> {
 		Ok(Vec::new())
 	}
+
+	fn send_trusted_operations_to_peers(
+		&self,
+		_peer_urls: Vec<PalletString>,
+		_requests: Vec<Request>,
+	) -> SgxResult<()> {
+		Ok(())
+	}
 }
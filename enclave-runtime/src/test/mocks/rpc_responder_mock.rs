@@ -46,4 +46,12 @@ where
 	fn send_state(&self, _hash: Self::Hash, _state_encoded: Vec<u8>) -> DirectRpcResult<()> {
 		Ok(())
 	}
+
+	fn send_subscription_update(
+		&self,
+		_hash: Self::Hash,
+		_update_encoded: Vec<u8>,
+	) -> DirectRpcResult<()> {
+		Ok(())
+	}
 }
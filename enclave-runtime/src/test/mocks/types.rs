@@ -65,8 +65,9 @@ pub type TestStfExecutor =
 
 pub type TestRpcResponder = RpcResponderMock<H256>;
 
-pub type TestTopPool =
-	BasicPool<SidechainApi<ParentchainBlock>, ParentchainBlock, TestRpcResponder>;
+pub type TestSidechainApi = SidechainApi<ParentchainBlock, TestStateHandler, TestStf>;
+
+pub type TestTopPool = BasicPool<TestSidechainApi, ParentchainBlock, TestRpcResponder>;
 
 pub type TestTopPoolAuthor = Author<
 	TestTopPool,
@@ -98,7 +98,7 @@ pub fn produce_sidechain_block_and_import_it() {
 		state_handler.clone(),
 		node_metadata_repo.clone(),
 	));
-	let top_pool = create_top_pool();
+	let top_pool = create_top_pool(state_handler.clone());
 
 	let top_pool_author = Arc::new(TestTopPoolAuthor::new(
 		top_pool,
@@ -87,7 +87,7 @@ pub fn ensure_events_get_reset_upon_block_proposal() {
 		state_handler.clone(),
 		node_metadata_repo.clone(),
 	));
-	let top_pool = create_top_pool();
+	let top_pool = create_top_pool(state_handler.clone());
 
 	let top_pool_author = Arc::new(TestTopPoolAuthor::new(
 		top_pool,
@@ -32,11 +32,15 @@ use crate::{
 	tls_ra,
 };
 use codec::Decode;
-use ita_sgx_runtime::Parentchain;
+use ita_sgx_runtime::{FeeTreasury, Parentchain};
 use ita_stf::{
+	fees::call_fee,
 	helpers::{account_key_hash, set_block_number},
 	stf_sgx_tests,
-	test_genesis::{endowed_account as funded_pair, unendowed_account},
+	test_genesis::{
+		endowed_account as funded_pair, second_endowed_account, unendowed_account,
+		SECOND_ENDOWED_ACC_FUNDS,
+	},
 	AccountInfo, Getter, State, StatePayload, TrustedCall, TrustedCallSigned, TrustedGetter,
 	TrustedOperation,
 };
@@ -49,7 +53,7 @@ use itp_stf_executor::{
 use itp_stf_interface::{
 	parentchain_pallet::ParentchainPalletInterface,
 	system_pallet::{SystemPalletAccountInterface, SystemPalletEventInterface},
-	StateCallInterface,
+	StateCallInterface, StateGetterInterface,
 };
 use itp_stf_primitives::types::ShardIdentifier;
 use itp_stf_state_handler::handle_state::HandleState;
@@ -79,7 +83,11 @@ pub extern "C" fn test_main_entrance() -> size_t {
 		itp_attestation_handler::attestation_handler::tests::decode_spid_works,
 		stf_sgx_tests::enclave_account_initialization_works,
 		stf_sgx_tests::shield_funds_increments_signer_account_nonce,
+		stf_sgx_tests::shield_funds_emits_event_visible_to_beneficiary,
+		stf_sgx_tests::executed_call_is_only_found_for_its_signer,
 		stf_sgx_tests::test_root_account_exists_after_initialization,
+		stf_sgx_tests::shield_transfer_and_unshield_assets_works,
+		stf_sgx_tests::unshielding_assets_minted_on_the_sidechain_fails,
 		itp_stf_state_handler::test::sgx_tests::test_write_and_load_state_works,
 		itp_stf_state_handler::test::sgx_tests::test_sgx_state_decode_encode_works,
 		itp_stf_state_handler::test::sgx_tests::test_encrypt_decrypt_state_type_works,
@@ -88,6 +96,11 @@ pub extern "C" fn test_main_entrance() -> size_t {
 		itp_stf_state_handler::test::sgx_tests::test_state_handler_file_backend_is_initialized,
 		itp_stf_state_handler::test::sgx_tests::test_multiple_state_updates_create_snapshots_up_to_cache_size,
 		itp_stf_state_handler::test::sgx_tests::test_state_files_from_handler_can_be_loaded_again,
+		itp_stf_state_handler::test::sgx_tests::test_delta_snapshots_from_handler_can_be_loaded_again,
+		itp_stf_state_handler::test::sgx_tests::test_verify_states_rolls_back_truncated_state_snapshot,
+		itp_stf_state_handler::test::sgx_tests::test_modified_state_file_fails_to_load,
+		itp_stf_state_handler::test::sgx_tests::test_legacy_state_file_is_loaded_and_re_encrypted,
+		itp_stf_state_handler::test::sgx_tests::test_state_file_is_re_encrypted_after_key_rotation,
 		itp_stf_state_handler::test::sgx_tests::test_file_io_get_state_hash_works,
 		itp_stf_state_handler::test::sgx_tests::test_list_state_ids_ignores_files_not_matching_the_pattern,
 		itp_stf_state_handler::test::sgx_tests::test_in_memory_state_initializes_from_shard_directory,
@@ -101,13 +114,16 @@ pub extern "C" fn test_main_entrance() -> size_t {
 		test_create_state_diff,
 		test_executing_call_updates_account_nonce,
 		test_call_set_update_parentchain_block,
-		test_invalid_nonce_call_is_not_executed,
+		test_call_with_future_nonce_is_not_executed,
+		test_calls_with_consecutive_nonces_are_executed_in_order,
 		test_signature_must_match_public_sender_in_call,
 		test_non_root_shielding_call_is_not_executed,
 		test_shielding_call_with_enclave_self_is_executed,
 		test_retrieve_events,
 		test_retrieve_event_count,
 		test_reset_events,
+		test_call_fee_is_credited_to_treasury,
+		test_call_whose_fee_cannot_be_paid_is_rejected_by_top_pool,
 		rpc::worker_api_direct::tests::test_given_io_handler_methods_then_retrieve_all_names_as_string,
 		handle_state_mock::tests::initialized_shards_list_is_empty,
 		handle_state_mock::tests::shard_exists_after_inserting,
@@ -151,6 +167,15 @@ pub extern "C" fn test_main_entrance() -> size_t {
 		tls_ra::tests::test_state_and_key_provisioning,
 		// RPC tests
 		direct_rpc_tests::get_state_request_works,
+		direct_rpc_tests::subscribe_all_heads_keeps_connection_open,
+		direct_rpc_tests::subscribe_events_requires_fresh_signed_subscription_getter,
+		direct_rpc_tests::subscribe_events_without_params_is_rejected,
+		// OAuth tests
+		crate::oauth::tests::token_request_of_client_passes_client_verification,
+		crate::oauth::tests::token_request_with_unregistered_redirect_uri_is_rejected,
+		crate::oauth::tests::token_request_with_unregistered_scope_is_rejected,
+		crate::oauth::tests::refresh_token_can_only_be_redeemed_once_by_its_client,
+		crate::oauth::tests::revoked_access_token_is_no_longer_valid,
 
 		// EVM tests
 		run_evm_tests,
@@ -361,7 +386,8 @@ fn test_create_block_and_confirmation_works() {
 
 fn test_create_state_diff() {
 	// given
-	let (top_pool_author, _, shard, mrenclave, shielding_key, _, stf_executor) = test_setup();
+	let (top_pool_author, mut state, shard, mrenclave, shielding_key, _, stf_executor) =
+		test_setup();
 
 	let block_composer = BlockComposer::<Block, SignedBlock, _, _>::new(
 		test_account(),
@@ -371,8 +397,9 @@ fn test_create_state_diff() {
 	let sender = funded_pair();
 	let receiver = unfunded_public();
 
-	let signed_call = TrustedCall::balance_transfer(sender.public().into(), receiver.into(), 1000)
-		.sign(&sender.clone().into(), 0, &mrenclave, &shard);
+	let call = TrustedCall::balance_transfer(sender.public().into(), receiver.into(), 1000);
+	let fee = state.execute_with(|| call_fee(&call, 0));
+	let signed_call = call.sign(&sender.clone().into(), 0, &mrenclave, &shard);
 	let trusted_operation = direct_top(signed_call);
 
 	submit_operation_to_top_pool(
@@ -412,10 +439,11 @@ fn test_create_state_diff() {
 		get_from_state_diff(&state_diff, &account_key_hash::<AccountId>(&receiver.into()));
 
 	// state diff should consist of the following updates:
-	// (last_hash, sidechain block_number, sender_funds, receiver_funds, [no clear, after polkadot_v0.9.26 update], events)
-	assert_eq!(state_diff.len(), 6);
+	// (last_hash, sidechain block_number, sender_funds, receiver_funds, [no clear, after polkadot_v0.9.26 update], events, stf events,
+	// total issuance, because the fee is burned without a treasury)
+	assert_eq!(state_diff.len(), 8);
 	assert_eq!(receiver_acc_info.data.free, 1000);
-	assert_eq!(sender_acc_info.data.free, 1000);
+	assert_eq!(sender_acc_info.data.free, 1000 - fee);
 }
 
 fn test_executing_call_updates_account_nonce() {
@@ -477,11 +505,11 @@ fn test_signature_must_match_public_sender_in_call() {
 
 	// create accounts
 	let sender = funded_pair();
-	let receiver = unfunded_public();
+	let other_funded = second_endowed_account();
 
 	let trusted_operation =
-		TrustedCall::balance_transfer(receiver.into(), sender.public().into(), 1000)
-			.sign(&sender.clone().into(), 10, &mrenclave, &shard)
+		TrustedCall::balance_transfer(other_funded.public().into(), sender.public().into(), 100)
+			.sign(&sender.clone().into(), 0, &mrenclave, &shard)
 			.into_trusted_operation(true);
 
 	submit_operation_to_top_pool(
@@ -499,7 +527,7 @@ fn test_signature_must_match_public_sender_in_call() {
 	assert!(!executed_batch.executed_operations[0].is_success());
 }
 
-fn test_invalid_nonce_call_is_not_executed() {
+fn test_call_with_future_nonce_is_not_executed() {
 	// given
 	let (top_pool_author, _, shard, mrenclave, shielding_key, _, stf_executor) = test_setup();
 
@@ -524,7 +552,45 @@ fn test_invalid_nonce_call_is_not_executed() {
 	let executed_batch = execute_trusted_calls(&shard, stf_executor.as_ref(), &top_pool_author);
 
 	// then
-	assert!(!executed_batch.executed_operations[0].is_success());
+	assert!(executed_batch.executed_operations.is_empty());
+}
+
+fn test_calls_with_consecutive_nonces_are_executed_in_order() {
+	// given
+	let (top_pool_author, _, shard, mrenclave, shielding_key, _, stf_executor) = test_setup();
+
+	let sender = funded_pair();
+	let receiver = unfunded_public();
+
+	// Submit the successor first, it has to wait in the future queue for its predecessor.
+	for nonce in [1, 0] {
+		let trusted_operation =
+			TrustedCall::balance_transfer(sender.public().into(), receiver.into(), 100)
+				.sign(&sender.clone().into(), nonce, &mrenclave, &shard)
+				.into_trusted_operation(true);
+
+		submit_operation_to_top_pool(
+			top_pool_author.as_ref(),
+			&trusted_operation,
+			&shielding_key,
+			shard,
+		)
+		.unwrap();
+	}
+
+	// when
+	let mut execution_result =
+		execute_trusted_calls(&shard, stf_executor.as_ref(), &top_pool_author);
+
+	// then
+	assert_eq!(2, execution_result.executed_operations.len());
+	assert!(execution_result.executed_operations.iter().all(|o| o.is_success()));
+
+	let nonce = TestStf::get_account_nonce(
+		&mut execution_result.state_after_execution,
+		&sender.public().into(),
+	);
+	assert_eq!(nonce, 2);
 }
 
 fn test_non_root_shielding_call_is_not_executed() {
@@ -604,7 +670,8 @@ pub fn test_retrieve_events() {
 	let repo = Arc::new(NodeMetadataRepository::<NodeMetadataMock>::default());
 	TestStf::execute_call(&mut state, trusted_call, &mut opaque_vec, repo).unwrap();
 
-	assert_eq!(TestStf::get_events(&mut state).len(), 3);
+	// Withdraw (fee), NewAccount, Endowed and Transfer.
+	assert_eq!(TestStf::get_events(&mut state).len(), 4);
 }
 
 pub fn test_retrieve_event_count() {
@@ -629,7 +696,7 @@ pub fn test_retrieve_event_count() {
 	TestStf::execute_call(&mut state, trusted_call, &mut opaque_vec, repo).unwrap();
 
 	let event_count = TestStf::get_event_count(&mut state);
-	assert_eq!(event_count, 3);
+	assert_eq!(event_count, 4);
 }
 
 pub fn test_reset_events() {
@@ -652,7 +719,7 @@ pub fn test_reset_events() {
 	let receiver_acc_info = TestStf::get_account_data(&mut state, &receiver.public().into());
 	assert_eq!(receiver_acc_info.free, transfer_value);
 	// Ensure that there really have been events generated.
-	assert_eq!(TestStf::get_events(&mut state).len(), 3);
+	assert_eq!(TestStf::get_events(&mut state).len(), 4);
 
 	// Remove the events.
 	TestStf::reset_events(&mut state);
@@ -661,6 +728,74 @@ pub fn test_reset_events() {
 	assert_eq!(TestStf::get_events(&mut state).len(), 0);
 }
 
+pub fn test_call_fee_is_credited_to_treasury() {
+	// given
+	let (_, mut state, shard, mrenclave, ..) = test_setup();
+	let sender = funded_pair();
+	let sender_account: AccountId = sender.public().into();
+	let treasury: AccountId = second_endowed_account().public().into();
+	state.execute_with(|| FeeTreasury::put(treasury.clone()));
+
+	let call = TrustedCall::balance_transfer(
+		sender_account.clone(),
+		unendowed_account().public().into(),
+		1000,
+	);
+	let fee_estimate_getter = TrustedGetter::fee_estimate(sender_account.clone(), call.clone())
+		.sign(&sender.clone().into());
+	let fee_estimate: u128 = Decode::decode(
+		&mut TestStf::execute_getter(&mut state, Getter::trusted(fee_estimate_getter))
+			.unwrap()
+			.as_slice(),
+	)
+	.unwrap();
+	let sender_funds_before = TestStf::get_account_data(&mut state, &sender_account).free;
+
+	// when
+	let repo = Arc::new(NodeMetadataRepository::<NodeMetadataMock>::default());
+	TestStf::execute_call(
+		&mut state,
+		call.sign(&sender.into(), 0, &mrenclave, &shard),
+		&mut Vec::new(),
+		repo,
+	)
+	.unwrap();
+
+	// then
+	assert!(fee_estimate > 0);
+	assert_eq!(
+		TestStf::get_account_data(&mut state, &sender_account).free,
+		sender_funds_before - 1000 - fee_estimate
+	);
+	assert_eq!(
+		TestStf::get_account_data(&mut state, &treasury).free,
+		SECOND_ENDOWED_ACC_FUNDS + fee_estimate
+	);
+}
+
+pub fn test_call_whose_fee_cannot_be_paid_is_rejected_by_top_pool() {
+	// given
+	let (top_pool_author, _, shard, mrenclave, shielding_key, ..) = test_setup();
+	let sender = unendowed_account();
+
+	let trusted_operation =
+		TrustedCall::balance_transfer(sender.public().into(), funded_pair().public().into(), 10)
+			.sign(&sender.into(), 0, &mrenclave, &shard)
+			.into_trusted_operation(true);
+
+	// when
+	let submission_result = submit_operation_to_top_pool(
+		top_pool_author.as_ref(),
+		&trusted_operation,
+		&shielding_key,
+		shard,
+	);
+
+	// then
+	assert!(submission_result.is_err());
+	assert!(top_pool_author.get_pending_trusted_calls(shard).is_empty());
+}
+
 fn execute_trusted_calls(
 	shard: &ShardIdentifier,
 	stf_executor: &TestStfExecutor,
@@ -77,7 +77,7 @@ pub fn process_indirect_call_in_top_pool() {
 	let state_handler = Arc::new(TestStateHandler::default());
 	let (_, shard_id) = init_state(state_handler.as_ref(), signer.public().into());
 
-	let top_pool = create_top_pool();
+	let top_pool = create_top_pool(state_handler.clone());
 
 	let top_pool_author = Arc::new(TestTopPoolAuthor::new(
 		top_pool,
@@ -110,7 +110,7 @@ pub fn submit_shielding_call_to_top_pool() {
 	let (state, shard_id) = init_state(state_handler.as_ref(), signer.public().into());
 	let state_observer = Arc::new(ObserveStateMock::new(state));
 
-	let top_pool = create_top_pool();
+	let top_pool = create_top_pool(state_handler.clone());
 
 	let top_pool_author = Arc::new(TestTopPoolAuthor::new(
 		top_pool,
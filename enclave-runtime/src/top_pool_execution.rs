@@ -18,10 +18,12 @@
 use crate::{
 	error::Result,
 	initialization::global_components::{
+		GLOBAL_EVENT_SUBSCRIPTIONS_COMPONENT, GLOBAL_HEADER_SUBSCRIPTIONS_COMPONENT,
 		GLOBAL_OCALL_API_COMPONENT, GLOBAL_SIDECHAIN_BLOCK_COMPOSER_COMPONENT,
 		GLOBAL_SIDECHAIN_IMPORT_QUEUE_WORKER_COMPONENT, GLOBAL_STATE_HANDLER_COMPONENT,
-		GLOBAL_TOP_POOL_AUTHOR_COMPONENT,
+		GLOBAL_STATE_OBSERVER_COMPONENT, GLOBAL_TOP_POOL_AUTHOR_COMPONENT,
 	},
+	rpc::event_subscriptions::publish_events,
 	sync::{EnclaveLock, EnclaveStateRWLock},
 	utils::{
 		get_extrinsic_factory_from_solo_or_parachain, get_stf_executor_from_solo_or_parachain,
@@ -45,7 +47,8 @@ use itp_sgx_crypto::Ed25519Seal;
 use itp_sgx_io::StaticSealedIO;
 use itp_stf_state_handler::query_shard_state::QueryShardState;
 use itp_time_utils::duration_now;
-use itp_types::{Block, OpaqueCall, H256};
+use itp_top_pool_author::traits::AuthorApi;
+use itp_types::{AccountId, Block, OpaqueCall, H256};
 use its_primitives::{
 	traits::{
 		Block as SidechainBlockTrait, Header as HeaderTrait, ShardIdentifierFor, SignedBlock,
@@ -62,9 +65,11 @@ use log::*;
 use sgx_types::sgx_status_t;
 use sp_core::Pair;
 use sp_runtime::{
-	generic::SignedBlock as SignedParentchainBlock, traits::Block as BlockTrait, MultiSignature,
+	generic::SignedBlock as SignedParentchainBlock,
+	traits::{Block as BlockTrait, Header as ParentchainHeaderTrait},
+	MultiSignature,
 };
-use std::{sync::Arc, time::Instant, vec::Vec};
+use std::{string::String, sync::Arc, time::Instant, vec::Vec};
 
 #[no_mangle]
 pub unsafe extern "C" fn execute_trusted_calls() -> sgx_status_t {
@@ -146,6 +151,16 @@ fn execute_top_pool_trusted_calls_internal() -> Result<()> {
 
 			log_remaining_slot_duration(&slot, "Before AURA");
 
+			// Gossip before producing blocks, so peers can prune the operations when importing our block.
+			if let Err(e) = gossip_trusted_operations(
+				top_pool_author.as_ref(),
+				ocall_api.as_ref(),
+				&slot.last_imported_parentchain_head,
+				&authority.public(),
+			) {
+				warn!("Failed to gossip trusted operations to peers: {:?}", e);
+			}
+
 			let shards = state_handler.list_shards()?;
 			let env = ProposerFactory::<Block, _, _, _>::new(
 				top_pool_author,
@@ -169,6 +184,20 @@ fn execute_top_pool_trusted_calls_internal() -> Result<()> {
 
 			log_remaining_slot_duration(&slot, "After AURA");
 
+			// Our own blocks are not imported, so we publish their headers and events here.
+			let header_subscriptions = GLOBAL_HEADER_SUBSCRIPTIONS_COMPONENT.get()?;
+			let event_subscriptions = GLOBAL_EVENT_SUBSCRIPTIONS_COMPONENT.get()?;
+			let state_observer = GLOBAL_STATE_OBSERVER_COMPONENT.get()?;
+			for block in blocks.iter() {
+				let header = block.block().header();
+				header_subscriptions.notify(header.encode());
+				publish_events(
+					event_subscriptions.as_ref(),
+					state_observer.as_ref(),
+					&header.shard_id(),
+				);
+			}
+
 			send_blocks_and_extrinsics::<Block, _, _, _, _>(
 				blocks,
 				opaque_calls,
@@ -275,6 +304,46 @@ where
 	Ok(())
 }
 
+/// Gossips the trusted calls submitted to this validateer since the last slot to its peer
+/// validateers. This way, a call is included in the next block, regardless of who authors it.
+fn gossip_trusted_operations<Author, OCallApi, ParentchainHeader>(
+	top_pool_author: &Author,
+	ocall_api: &OCallApi,
+	parentchain_header: &ParentchainHeader,
+	own_public: &sp_core::ed25519::Public,
+) -> Result<()>
+where
+	Author: AuthorApi<H256, H256>,
+	OCallApi: ValidateerFetch + EnclaveSidechainOCallApi,
+	ParentchainHeader: ParentchainHeaderTrait<Hash = H256>,
+{
+	let tops_to_gossip = top_pool_author.take_tops_to_gossip();
+	if tops_to_gossip.is_empty() {
+		return Ok(())
+	}
+
+	let own_account = AccountId::from(*own_public);
+	let peer_urls: Vec<_> = ocall_api
+		.current_validateers(parentchain_header)?
+		.into_iter()
+		.filter(|validateer| validateer.pubkey != own_account)
+		.map(|validateer| validateer.url)
+		.collect();
+	if peer_urls.is_empty() {
+		return Ok(())
+	}
+
+	let (hashes, requests): (Vec<_>, Vec<_>) = tops_to_gossip.into_iter().unzip();
+	debug!("Gossiping {} trusted operation(s) to {} peer(s)", hashes.len(), peer_urls.len());
+	ocall_api.send_trusted_operations_to_peers(peer_urls.clone(), requests)?;
+
+	top_pool_author.on_tops_gossiped(
+		&hashes,
+		peer_urls.iter().map(|url| String::from_utf8_lossy(url).into_owned()).collect(),
+	);
+	Ok(())
+}
+
 fn log_remaining_slot_duration<B: BlockTrait<Hash = H256>>(
 	slot_info: &SlotInfo<B>,
 	stage_name: &str,
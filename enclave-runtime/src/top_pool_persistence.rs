@@ -0,0 +1,77 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Persistence of the TOP pool across worker restarts.
+
+use crate::{
+	error::{Error, Result},
+	initialization::global_components::GLOBAL_TOP_POOL_AUTHOR_COMPONENT,
+};
+use itp_component_container::ComponentGetter;
+use itp_sgx_io::SealedIO;
+use itp_top_pool_author::{sealing::TopPoolSeal, traits::AuthorApi};
+use jsonrpc_core::futures::executor;
+use log::*;
+use sgx_types::sgx_status_t;
+
+#[no_mangle]
+pub unsafe extern "C" fn persist_top_pool() -> sgx_status_t {
+	if let Err(e) = persist_top_pool_internal() {
+		error!("Failed to persist the TOP pool: {:?}", e);
+		return e.into()
+	}
+
+	sgx_status_t::SGX_SUCCESS
+}
+
+/// Seal all pending trusted calls of the TOP pool, replacing the previously sealed ones.
+fn persist_top_pool_internal() -> Result<()> {
+	let top_pool_author = GLOBAL_TOP_POOL_AUTHOR_COMPONENT.get()?;
+
+	let pending_operations = top_pool_author.pending_operations();
+	TopPoolSeal.seal(&pending_operations)?;
+
+	debug!("Persisted {} pending operation(s) of the TOP pool", pending_operations.len());
+	Ok(())
+}
+
+/// Re-submit the trusted calls that were pending when the TOP pool was last persisted.
+///
+/// The operations are revalidated on submission, those that are no longer valid are dropped
+/// and reported as `Invalid` to their watchers.
+pub(crate) fn restore_top_pool() -> Result<()> {
+	if !TopPoolSeal.exists() {
+		info!("No persisted TOP pool found, starting with an empty pool");
+		return Ok(())
+	}
+
+	let top_pool_author = GLOBAL_TOP_POOL_AUTHOR_COMPONENT.get()?;
+
+	let persisted_operations = TopPoolSeal.unseal()?;
+	let number_of_persisted_operations = persisted_operations.len();
+	let results = executor::block_on(top_pool_author.restore_operations(persisted_operations))
+		.map_err(|e| Error::Other(format!("{:?}", e).into()))?;
+
+	let number_of_restored_operations = results.iter().filter(|r| r.is_ok()).count();
+	info!(
+		"Restored {} of {} persisted operation(s) to the TOP pool, dropped {}",
+		number_of_restored_operations,
+		number_of_persisted_operations,
+		number_of_persisted_operations - number_of_restored_operations
+	);
+	Ok(())
+}
@@ -17,9 +17,11 @@
 
 use clap::ArgMatches;
 use itc_rest_client::rest_client::Url;
+use itp_settings::files::{DEFAULT_MAX_STATE_DELTA_CHAIN_LENGTH, DEFAULT_MAX_STATE_SNAPSHOTS};
+use itp_types::{StateSnapshotRetention, TopPoolLimits};
 use parse_duration::parse;
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::{str::FromStr, time::Duration};
 
 static DEFAULT_NODE_SERVER: &str = "ws://127.0.0.1";
 static DEFAULT_NODE_PORT: &str = "9944";
@@ -52,6 +54,10 @@ pub struct Config {
 	pub metrics_server_port: String,
 	/// Port for the untrusted HTTP server (e.g. for `is_initialized`)
 	pub untrusted_http_port: String,
+	/// Retention of the state snapshots kept by the enclave
+	pub state_snapshot_retention: StateSnapshotRetention,
+	/// Limits of the trusted operation pool of the enclave
+	pub top_pool_limits: TopPoolLimits,
 	/// Config of the 'run' subcommand
 	pub run_config: Option<RunConfig>,
 }
@@ -71,6 +77,8 @@ impl Config {
 		enable_metrics_server: bool,
 		metrics_server_port: String,
 		untrusted_http_port: String,
+		state_snapshot_retention: StateSnapshotRetention,
+		top_pool_limits: TopPoolLimits,
 		run_config: Option<RunConfig>,
 	) -> Self {
 		Self {
@@ -86,6 +94,8 @@ impl Config {
 			enable_metrics_server,
 			metrics_server_port,
 			untrusted_http_port,
+			state_snapshot_retention,
+			top_pool_limits,
 			run_config,
 		}
 	}
@@ -149,6 +159,8 @@ impl From<&ArgMatches<'_>> for Config {
 		let metrics_server_port = m.value_of("metrics-port").unwrap_or(DEFAULT_METRICS_PORT);
 		let untrusted_http_port =
 			m.value_of("untrusted-http-port").unwrap_or(DEFAULT_UNTRUSTED_HTTP_PORT);
+		let state_snapshot_retention = state_snapshot_retention(m);
+		let top_pool_limits = top_pool_limits(m);
 		let run_config = m.subcommand_matches("run").map(RunConfig::from);
 
 		Self::new(
@@ -167,11 +179,57 @@ impl From<&ArgMatches<'_>> for Config {
 			is_metrics_server_enabled,
 			metrics_server_port.to_string(),
 			untrusted_http_port.to_string(),
+			state_snapshot_retention,
+			top_pool_limits,
 			run_config,
 		)
 	}
 }
 
+fn state_snapshot_retention(m: &ArgMatches<'_>) -> StateSnapshotRetention {
+	let max_snapshots = m
+		.value_of("max-state-snapshots")
+		.map(|n| {
+			n.parse::<u32>()
+				.unwrap_or_else(|e| panic!("max-state-snapshots parsing error {:?}", e))
+		})
+		.unwrap_or(DEFAULT_MAX_STATE_SNAPSHOTS);
+	if max_snapshots == 0 {
+		panic!("max-state-snapshots must be at least 1");
+	}
+	let max_age_secs = m.value_of("max-state-snapshot-age").map(|a| {
+		parse(a)
+			.unwrap_or_else(|e| panic!("max-state-snapshot-age parsing error {:?}", e))
+			.as_secs()
+	});
+	let max_delta_chain_length = m
+		.value_of("max-state-delta-chain")
+		.map(|n| {
+			n.parse::<u32>()
+				.unwrap_or_else(|e| panic!("max-state-delta-chain parsing error {:?}", e))
+		})
+		.unwrap_or(DEFAULT_MAX_STATE_DELTA_CHAIN_LENGTH);
+
+	StateSnapshotRetention { max_snapshots, max_age_secs, max_delta_chain_length }
+}
+
+fn top_pool_limits(m: &ArgMatches<'_>) -> TopPoolLimits {
+	let parse_limit = |name: &str, default: u32| {
+		m.value_of(name)
+			.map(|n| n.parse::<u32>().unwrap_or_else(|e| panic!("{} parsing error {:?}", name, e)))
+			.unwrap_or(default)
+	};
+	let defaults = TopPoolLimits::default();
+
+	TopPoolLimits {
+		max_ready: parse_limit("max-pool-ready", defaults.max_ready),
+		max_future: parse_limit("max-pool-future", defaults.max_future),
+		max_per_shard: parse_limit("max-pool-per-shard", defaults.max_per_shard),
+		max_per_sender: parse_limit("max-pool-per-sender", defaults.max_per_sender),
+		..defaults
+	}
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct RunConfig {
 	/// Skip remote attestation. Set this flag if running enclave in SW mode
@@ -184,6 +242,14 @@ pub struct RunConfig {
 	pub shard: Option<String>,
 	/// Optional teeracle update interval
 	pub teeracle_update_interval: Option<Duration>,
+	/// Optional location the teeracle publishes the weather of
+	pub weather_location: Option<WeatherLocation>,
+	/// Trading pairs the teeracle updates the exchange rates of, the default ones if empty
+	pub teeracle_trading_pairs: Vec<TradingPair>,
+	/// Optional maximal deviation (in percent) of an exchange rate from the median of all sources
+	pub teeracle_max_deviation_percent: Option<u32>,
+	/// Optional path to a JSON file with additional oracle source configs
+	pub oracle_sources_config: Option<String>,
 	/// Marblerun's Prometheus endpoint base URL
 	pub marblerun_base_url: Option<String>,
 }
@@ -197,13 +263,102 @@ impl From<&ArgMatches<'_>> for RunConfig {
 		let teeracle_update_interval = m.value_of("teeracle-interval").map(|i| {
 			parse(i).unwrap_or_else(|e| panic!("teeracle-interval parsing error {:?}", e))
 		});
+		let weather_location = m.value_of("weather-location").map(|l| {
+			WeatherLocation::from_str(l)
+				.unwrap_or_else(|e| panic!("weather-location parsing error: {}", e))
+		});
+		let teeracle_trading_pairs = m
+			.value_of("teeracle-trading-pairs")
+			.map(|pairs| {
+				pairs
+					.split(',')
+					.map(|pair| {
+						TradingPair::from_str(pair).unwrap_or_else(|e| {
+							panic!("teeracle-trading-pairs parsing error: {}", e)
+						})
+					})
+					.collect()
+			})
+			.unwrap_or_default();
+		let teeracle_max_deviation_percent = m.value_of("teeracle-max-deviation").map(|d| {
+			d.parse()
+				.unwrap_or_else(|e| panic!("teeracle-max-deviation parsing error: {:?}", e))
+		});
+		let oracle_sources_config = m.value_of("oracle-sources").map(|s| s.to_string());
 		let marblerun_base_url = m.value_of("marblerun-url").map(|i| {
 			Url::parse(i)
 				.unwrap_or_else(|e| panic!("marblerun-url parsing error: {:?}", e))
 				.to_string()
 		});
 
-		Self { skip_ra, dev, request_state, shard, teeracle_update_interval, marblerun_base_url }
+		Self {
+			skip_ra,
+			dev,
+			request_state,
+			shard,
+			teeracle_update_interval,
+			weather_location,
+			teeracle_trading_pairs,
+			teeracle_max_deviation_percent,
+			oracle_sources_config,
+			marblerun_base_url,
+		}
+	}
+}
+
+/// Coordinates of a location, as decimal degrees.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WeatherLocation {
+	pub latitude: String,
+	pub longitude: String,
+}
+
+impl FromStr for WeatherLocation {
+	type Err = String;
+
+	/// Parse `<latitude>,<longitude>`, e.g. `52.52,13.41`.
+	fn from_str(location: &str) -> Result<Self, Self::Err> {
+		let (latitude, longitude) = location
+			.split_once(',')
+			.ok_or_else(|| format!("expected <latitude>,<longitude>, got {}", location))?;
+		let (latitude, longitude) = (latitude.trim(), longitude.trim());
+		for (name, value, bound) in
+			[("latitude", latitude, 90f64), ("longitude", longitude, 180f64)]
+		{
+			match value.parse::<f64>() {
+				Ok(v) if v.abs() <= bound => {},
+				_ => return Err(format!("invalid {}: {}", name, value)),
+			}
+		}
+		Ok(WeatherLocation { latitude: latitude.to_string(), longitude: longitude.to_string() })
+	}
+}
+
+/// Crypto/fiat currency pair the teeracle updates the exchange rate of.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TradingPair {
+	pub crypto_currency: String,
+	pub fiat_currency: String,
+}
+
+impl FromStr for TradingPair {
+	type Err = String;
+
+	/// Parse `<crypto>/<fiat>`, e.g. `TEER/USD`.
+	fn from_str(pair: &str) -> Result<Self, Self::Err> {
+		let (crypto_currency, fiat_currency) = pair
+			.split_once('/')
+			.ok_or_else(|| format!("expected <crypto>/<fiat>, got {}", pair))?;
+		let (crypto_currency, fiat_currency) = (crypto_currency.trim(), fiat_currency.trim());
+		for currency in [crypto_currency, fiat_currency] {
+			if currency.is_empty() || !currency.chars().all(|c| c.is_ascii_alphanumeric()) {
+				return Err(format!("invalid currency: {}", currency))
+			}
+		}
+		Ok(TradingPair {
+			crypto_currency: crypto_currency.to_string(),
+			fiat_currency: fiat_currency.to_string(),
+		})
 	}
 }
 
@@ -247,9 +402,80 @@ mod test {
 		assert!(config.mu_ra_external_address.is_none());
 		assert!(!config.enable_metrics_server);
 		assert_eq!(config.untrusted_http_port, DEFAULT_UNTRUSTED_HTTP_PORT);
+		assert_eq!(config.state_snapshot_retention.max_snapshots, DEFAULT_MAX_STATE_SNAPSHOTS);
+		assert!(config.state_snapshot_retention.max_age_secs.is_none());
+		assert_eq!(
+			config.state_snapshot_retention.max_delta_chain_length,
+			DEFAULT_MAX_STATE_DELTA_CHAIN_LENGTH
+		);
+		assert_eq!(config.top_pool_limits, TopPoolLimits::default());
 		assert!(config.run_config.is_none());
 	}
 
+	#[test]
+	fn state_snapshot_retention_parsing_works() {
+		let mut args = ArgMatches::default();
+		args.args = HashMap::from([
+			("max-state-snapshots", Default::default()),
+			("max-state-snapshot-age", Default::default()),
+			("max-state-delta-chain", Default::default()),
+		]);
+		// Workaround because MatchedArg is private.
+		args.args.get_mut("max-state-snapshots").unwrap().vals = vec!["20".into()];
+		args.args.get_mut("max-state-snapshot-age").unwrap().vals = vec!["2h".into()];
+		args.args.get_mut("max-state-delta-chain").unwrap().vals = vec!["5".into()];
+
+		let config = Config::from(&args);
+
+		assert_eq!(
+			config.state_snapshot_retention,
+			StateSnapshotRetention {
+				max_snapshots: 20,
+				max_age_secs: Some(7200),
+				max_delta_chain_length: 5
+			}
+		);
+	}
+
+	#[test]
+	#[should_panic]
+	fn state_snapshot_retention_parsing_panics_for_zero_snapshots() {
+		let mut args = ArgMatches::default();
+		args.args = HashMap::from([("max-state-snapshots", Default::default())]);
+		args.args.get_mut("max-state-snapshots").unwrap().vals = vec!["0".into()];
+
+		let _config = Config::from(&args);
+	}
+
+	#[test]
+	fn top_pool_limits_parsing_works() {
+		let mut args = ArgMatches::default();
+		args.args = HashMap::from([
+			("max-pool-ready", Default::default()),
+			("max-pool-future", Default::default()),
+			("max-pool-per-shard", Default::default()),
+			("max-pool-per-sender", Default::default()),
+		]);
+		// Workaround because MatchedArg is private.
+		args.args.get_mut("max-pool-ready").unwrap().vals = vec!["1000".into()];
+		args.args.get_mut("max-pool-future").unwrap().vals = vec!["100".into()];
+		args.args.get_mut("max-pool-per-shard").unwrap().vals = vec!["500".into()];
+		args.args.get_mut("max-pool-per-sender").unwrap().vals = vec!["10".into()];
+
+		let config = Config::from(&args);
+
+		assert_eq!(
+			config.top_pool_limits,
+			TopPoolLimits {
+				max_ready: 1000,
+				max_future: 100,
+				max_per_shard: 500,
+				max_per_sender: 10,
+				..Default::default()
+			}
+		);
+	}
+
 	#[test]
 	fn worker_ip_is_set_correcty_for_set_ws_external_flag() {
 		let expected_worker_ip = "0.0.0.0";
@@ -321,6 +547,9 @@ mod test {
 		assert_eq!(run_config.skip_ra, false);
 		assert!(run_config.shard.is_none());
 		assert!(run_config.teeracle_update_interval.is_none());
+		assert!(run_config.teeracle_trading_pairs.is_empty());
+		assert!(run_config.teeracle_max_deviation_percent.is_none());
+		assert!(run_config.oracle_sources_config.is_none());
 	}
 
 	#[test]
@@ -334,10 +563,14 @@ mod test {
 			("skip-ra", Default::default()),
 			("shard", Default::default()),
 			("teeracle-interval", Default::default()),
+			("teeracle-max-deviation", Default::default()),
+			("oracle-sources", Default::default()),
 		]);
 		// Workaround because MatchedArg is private.
 		args.args.get_mut("shard").unwrap().vals = vec![shard_identifier.into()];
 		args.args.get_mut("teeracle-interval").unwrap().vals = vec!["42s".into()];
+		args.args.get_mut("teeracle-max-deviation").unwrap().vals = vec!["10".into()];
+		args.args.get_mut("oracle-sources").unwrap().vals = vec!["oracle_sources.json".into()];
 
 		let run_config = RunConfig::from(&args);
 
@@ -346,6 +579,38 @@ mod test {
 		assert_eq!(run_config.skip_ra, true);
 		assert_eq!(run_config.shard.unwrap(), shard_identifier.to_string());
 		assert_eq!(run_config.teeracle_update_interval.unwrap(), Duration::from_secs(42));
+		assert_eq!(run_config.teeracle_max_deviation_percent, Some(10));
+		assert_eq!(run_config.oracle_sources_config.unwrap(), "oracle_sources.json".to_string());
+	}
+
+	#[test]
+	fn weather_location_parsing_works() {
+		let location = WeatherLocation::from_str("52.52, -13.41").unwrap();
+
+		assert_eq!(location.latitude, "52.52".to_string());
+		assert_eq!(location.longitude, "-13.41".to_string());
+	}
+
+	#[test]
+	fn weather_location_parsing_fails_for_invalid_coordinates() {
+		assert!(WeatherLocation::from_str("52.52").is_err());
+		assert!(WeatherLocation::from_str("91.0,13.41").is_err());
+		assert!(WeatherLocation::from_str("52.52,13.41&hourly=x").is_err());
+	}
+
+	#[test]
+	fn trading_pair_parsing_works() {
+		let trading_pair = TradingPair::from_str(" DOT/CHF").unwrap();
+
+		assert_eq!(trading_pair.crypto_currency, "DOT".to_string());
+		assert_eq!(trading_pair.fiat_currency, "CHF".to_string());
+	}
+
+	#[test]
+	fn trading_pair_parsing_fails_for_invalid_pairs() {
+		assert!(TradingPair::from_str("TEER").is_err());
+		assert!(TradingPair::from_str("TEER/").is_err());
+		assert!(TradingPair::from_str("TEER/US&D").is_err());
 	}
 
 	#[test]
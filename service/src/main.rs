@@ -18,7 +18,7 @@
 #![cfg_attr(test, feature(assert_matches))]
 
 #[cfg(feature = "teeracle")]
-use crate::teeracle::start_interval_market_update;
+use crate::teeracle::{set_oracle_source_configs, start_interval_market_update};
 
 #[cfg(not(feature = "dcap"))]
 use crate::utils::check_files;
@@ -30,12 +30,13 @@ use crate::{
 	initialized_service::{
 		start_is_initialized_server, InitializationHandler, IsInitialized, TrackInitialization,
 	},
-	ocall_bridge::{
-		bridge_api::Bridge as OCallBridge, component_factory::OCallBridgeComponentFactory,
-	},
+	ocall_bridge::component_factory::OCallBridgeComponentFactory,
 	parentchain_handler::{HandleParentchain, ParentchainHandler},
 	prometheus_metrics::{start_metrics_server, EnclaveMetricsReceiver, MetricsHandler},
-	sidechain_setup::{sidechain_init_block_production, sidechain_start_untrusted_rpc_server},
+	sidechain_setup::{
+		sidechain_init_block_production, sidechain_start_top_pool_persistence,
+		sidechain_start_untrusted_rpc_server,
+	},
 	sync_block_broadcaster::SyncBlockBroadcaster,
 	utils::extract_shard,
 	worker::Worker,
@@ -71,7 +72,10 @@ use its_peer_fetch::{
 	block_fetch_client::BlockFetcher, untrusted_peer_fetch::UntrustedPeerFetcher,
 };
 use its_primitives::types::block::SignedBlock as SignedSidechainBlock;
-use its_storage::{interface::FetchBlocks, BlockPruner, SidechainStorageLock};
+use its_storage::{
+	interface::{FetchBlocks, QueryBlocks},
+	BlockPruner, SidechainStorageLock,
+};
 use log::*;
 use my_node_runtime::{Hash, Header, RuntimeEvent};
 use sgx_types::*;
@@ -85,7 +89,13 @@ use sgx_verify::extract_tcb_info_from_raw_dcap_quote;
 use sp_core::crypto::{AccountId32, Ss58Codec};
 use sp_keyring::AccountKeyring;
 use sp_runtime::traits::Header as HeaderTrait;
-use std::{path::PathBuf, str, sync::Arc, thread, time::Duration};
+use std::{
+	path::{Path, PathBuf},
+	str,
+	sync::Arc,
+	thread,
+	time::Duration,
+};
 use teerex_primitives::ShardIdentifier;
 
 mod account_funding;
@@ -165,16 +175,20 @@ fn main() {
 	let enclave_metrics_receiver = Arc::new(EnclaveMetricsReceiver {});
 
 	// initialize o-call bridge with a concrete factory implementation
-	OCallBridge::initialize(Arc::new(OCallBridgeComponentFactory::new(
-		node_api_factory.clone(),
-		sync_block_broadcaster,
-		enclave.clone(),
-		sidechain_blockstorage.clone(),
-		peer_updater,
-		peer_sidechain_block_fetcher,
-		tokio_handle.clone(),
-		enclave_metrics_receiver,
-	)));
+	setup::initialize_ocall_bridge(
+		enclave.as_ref(),
+		Arc::new(OCallBridgeComponentFactory::new(
+			node_api_factory.clone(),
+			sync_block_broadcaster,
+			enclave.clone(),
+			sidechain_blockstorage.clone(),
+			peer_updater,
+			peer_sidechain_block_fetcher,
+			tokio_handle.clone(),
+			enclave_metrics_receiver,
+		)),
+	)
+	.unwrap();
 
 	/*if let Some(run_config) = &config.run_config {
 		let shard = extract_shard(&run_config.shard, enclave.as_ref());
@@ -277,6 +291,38 @@ fn main() {
 			enclave.as_ref(),
 			&extract_shard(&sub_matches.value_of("shard").map(|s| s.to_string()), enclave.as_ref()),
 		);
+	} else if let Some(sub_matches) = matches.subcommand_matches("export-state") {
+		let shard =
+			extract_shard(&sub_matches.value_of("shard").map(|s| s.to_string()), enclave.as_ref());
+		// Without a target, the archive is a backup that only this enclave can import.
+		let target_shielding_key = match sub_matches.value_of("target-shielding-key") {
+			Some(key_file) => setup::read_shielding_key_file(Path::new(key_file)).unwrap(),
+			None => enclave.get_rsa_shielding_pubkey().unwrap(),
+		};
+		setup::export_state(
+			enclave.as_ref(),
+			&shard,
+			&target_shielding_key,
+			Path::new(sub_matches.value_of("output").unwrap()),
+		)
+		.unwrap();
+	} else if let Some(sub_matches) = matches.subcommand_matches("import-state") {
+		// The enclave looks up the exporting enclave in the enclave registry of the parentchain,
+		// so its light client has to be initialized and synced first.
+		let node_api =
+			node_api_factory.create_api().expect("Failed to create parentchain node API");
+		let parentchain_handler =
+			ParentchainHandler::new_with_automatic_light_client_allocation(node_api, enclave.clone())
+				.unwrap();
+		let last_synced_header = parentchain_handler.init_parentchain_components().unwrap();
+		parentchain_handler.sync_parentchain(last_synced_header).unwrap();
+
+		setup::import_state(enclave.as_ref(), Path::new(sub_matches.value_of("archive").unwrap()))
+			.unwrap();
+	} else if let Some(sub_matches) = matches.subcommand_matches("verify-state") {
+		setup::verify_state(enclave.as_ref(), sub_matches.is_present("rollback")).unwrap();
+	} else if let Some(sub_matches) = matches.subcommand_matches("rotate-state-key") {
+		setup::rotate_state_key(enclave.as_ref(), sub_matches.is_present("re-encrypt")).unwrap();
 	} else if let Some(sub_matches) = matches.subcommand_matches("test") {
 		if sub_matches.is_present("provisioning-server") {
 			println!("*** Running Enclave MU-RA TLS server\n");
@@ -329,7 +375,12 @@ fn oauth<E, T, D, InitializationHandler, WorkerModeProvider>(
 		+ TeeracleApi
 		+ OAuth
 		+ Clone,
-	D: BlockPruner + FetchBlocks<SignedSidechainBlock> + Sync + Send + 'static,
+	D: BlockPruner
+		+ FetchBlocks<SignedSidechainBlock>
+		+ QueryBlocks<SignedSidechainBlock>
+		+ Sync
+		+ Send
+		+ 'static,
 	InitializationHandler: TrackInitialization + IsInitialized + Sync + Send + 'static,
 	WorkerModeProvider: ProvideWorkerMode,
 {
@@ -514,9 +565,15 @@ fn oauth<E, T, D, InitializationHandler, WorkerModeProvider>(
 	// initialize teeracle interval
 	#[cfg(feature = "teeracle")]
 	if WorkerModeProvider::worker_mode() == WorkerMode::Teeracle {
+		if let Some(path) = run_config.oracle_sources_config.as_deref() {
+			set_oracle_source_configs(enclave.as_ref(), path);
+		}
 		start_interval_market_update(
 			&node_api,
 			run_config.teeracle_update_interval,
+			&run_config.teeracle_trading_pairs,
+			run_config.teeracle_max_deviation_percent,
+			run_config.weather_location.as_ref(),
 			enclave.as_ref(),
 			&teeracle_tokio_handle,
 		);
@@ -537,14 +594,17 @@ fn oauth<E, T, D, InitializationHandler, WorkerModeProvider>(
 		// Initialize the sidechain
 		if WorkerModeProvider::worker_mode() == WorkerMode::Sidechain {
 			last_synced_header = sidechain_init_block_production(
-				enclave,
+				enclave.clone(),
 				&register_enclave_xt_header,
 				we_are_primary_validateer,
 				parentchain_handler.clone(),
 				sidechain_storage,
 				&last_synced_header,
+				&config.top_pool_limits,
 			)
 			.unwrap();
+			sidechain_start_top_pool_persistence(enclave, tokio_handle_getter.get_handle())
+				.unwrap();
 		}
 
 		// ------------------------------------------------------------------------
@@ -588,7 +648,12 @@ fn start_worker<E, T, D, InitializationHandler, WorkerModeProvider>(
 		+ TlsRemoteAttestation
 		+ TeeracleApi
 		+ Clone,
-	D: BlockPruner + FetchBlocks<SignedSidechainBlock> + Sync + Send + 'static,
+	D: BlockPruner
+		+ FetchBlocks<SignedSidechainBlock>
+		+ QueryBlocks<SignedSidechainBlock>
+		+ Sync
+		+ Send
+		+ 'static,
 	InitializationHandler: TrackInitialization + IsInitialized + Sync + Send + 'static,
 	WorkerModeProvider: ProvideWorkerMode,
 {
@@ -625,6 +690,19 @@ fn start_worker<E, T, D, InitializationHandler, WorkerModeProvider>(
 		info!("State provisioning server stopped.");
 	});
 
+	// ------------------------------------------------------------------------
+	// re-encrypt state files not encrypted with the current state key yet,
+	// e.g. after a key rotation or legacy state files without authenticated encryption
+	let enclave_for_state_re_encryption = enclave.clone();
+	thread::Builder::new()
+		.name("state_re_encryption".to_owned())
+		.spawn(move || match enclave_for_state_re_encryption.re_encrypt_state() {
+			Ok(re_encrypted_snapshots) =>
+				info!("Re-encrypted {} state snapshot(s)", re_encrypted_snapshots),
+			Err(e) => error!("Failed to re-encrypt the state snapshots: {:?}", e),
+		})
+		.unwrap();
+
 	let tokio_handle = tokio_handle_getter.get_handle();
 
 	#[cfg(feature = "teeracle")]
@@ -775,9 +853,15 @@ fn start_worker<E, T, D, InitializationHandler, WorkerModeProvider>(
 	// initialize teeracle interval
 	#[cfg(feature = "teeracle")]
 	if WorkerModeProvider::worker_mode() == WorkerMode::Teeracle {
+		if let Some(path) = run_config.oracle_sources_config.as_deref() {
+			set_oracle_source_configs(enclave.as_ref(), path);
+		}
 		start_interval_market_update(
 			&node_api,
 			run_config.teeracle_update_interval,
+			&run_config.teeracle_trading_pairs,
+			run_config.teeracle_max_deviation_percent,
+			run_config.weather_location.as_ref(),
 			enclave.as_ref(),
 			&teeracle_tokio_handle,
 		);
@@ -794,14 +878,17 @@ fn start_worker<E, T, D, InitializationHandler, WorkerModeProvider>(
 		// Initialize the sidechain
 		if WorkerModeProvider::worker_mode() == WorkerMode::Sidechain {
 			last_synced_header = sidechain_init_block_production(
-				enclave,
+				enclave.clone(),
 				&register_enclave_xt_header,
 				we_are_primary_validateer,
 				parentchain_handler.clone(),
 				sidechain_storage,
 				&last_synced_header,
+				&config.top_pool_limits,
 			)
 			.unwrap();
+			sidechain_start_top_pool_persistence(enclave, tokio_handle_getter.get_handle())
+				.unwrap();
 		}
 
 		// ------------------------------------------------------------------------
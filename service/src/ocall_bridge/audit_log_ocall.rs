@@ -0,0 +1,77 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+	Copyright (C) 2017-2019 Baidu, Inc. All Rights Reserved.
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::ocall_bridge::bridge_api::{AuditLogBridge, OCallBridgeError, OCallBridgeResult};
+use codec::Encode;
+use log::*;
+use std::{fs::OpenOptions, io::Write, path::PathBuf};
+
+/// Appends the audit records handed out by the enclave to a file.
+///
+/// The records are signed and hash-chained inside the enclave, so the file itself
+/// does not need any protection to be tamper-evident.
+pub struct AuditLogOCall {
+	log_file: PathBuf,
+}
+
+impl AuditLogOCall {
+	pub fn new(log_file: PathBuf) -> Self {
+		AuditLogOCall { log_file }
+	}
+}
+
+impl AuditLogBridge for AuditLogOCall {
+	fn append_audit_record(&self, encoded_record: Vec<u8>) -> OCallBridgeResult<()> {
+		trace!("Appending audit record ({} bytes) to {:?}", encoded_record.len(), self.log_file);
+
+		// Length prefix the record (SCALE encoding of a byte vector), so the log can be decoded again.
+		let mut log_file = OpenOptions::new()
+			.create(true)
+			.append(true)
+			.open(&self.log_file)
+			.map_err(|e| OCallBridgeError::AppendAuditRecord(e.to_string()))?;
+		log_file
+			.write_all(&encoded_record.encode())
+			.map_err(|e| OCallBridgeError::AppendAuditRecord(e.to_string()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Decode;
+	use std::fs;
+
+	#[test]
+	fn appended_records_can_be_decoded_again() {
+		let log_file = std::env::temp_dir().join("audit_log_ocall_test.bin");
+		let _ = fs::remove_file(&log_file);
+		let audit_log_ocall = AuditLogOCall::new(log_file.clone());
+
+		audit_log_ocall.append_audit_record(vec![1, 2, 3]).unwrap();
+		audit_log_ocall.append_audit_record(vec![4, 5]).unwrap();
+
+		let content = fs::read(&log_file).unwrap();
+		let mut input = content.as_slice();
+		assert_eq!(vec![1u8, 2, 3], Vec::<u8>::decode(&mut input).unwrap());
+		assert_eq!(vec![4u8, 5], Vec::<u8>::decode(&mut input).unwrap());
+		assert!(input.is_empty());
+
+		fs::remove_file(&log_file).unwrap();
+	}
+}
@@ -88,6 +88,14 @@ impl Bridge {
 			.get_metrics_api()
 	}
 
+	pub fn get_audit_log_api() -> Arc<dyn AuditLogBridge> {
+		COMPONENT_FACTORY
+			.read()
+			.as_ref()
+			.expect("Component factory has not been set. Use `initialize()`")
+			.get_audit_log_api()
+	}
+
 	pub fn initialize(component_factory: Arc<dyn GetOCallBridgeComponents + Send + Sync>) {
 		debug!("Initializing OCall bridge with component factory");
 
@@ -112,6 +120,9 @@ pub trait GetOCallBridgeComponents {
 
 	/// Metrics OCall API.
 	fn get_metrics_api(&self) -> Arc<dyn MetricsBridge>;
+
+	/// Audit log OCall API.
+	fn get_audit_log_api(&self) -> Arc<dyn AuditLogBridge>;
 }
 
 /// OCall bridge errors
@@ -127,10 +138,14 @@ pub enum OCallBridgeError {
 	GetIasSocket(String),
 	#[error("UpdateMetric Error: {0}")]
 	UpdateMetric(String),
+	#[error("AppendAuditRecord Error: {0}")]
+	AppendAuditRecord(String),
 	#[error("Propose sidechain block failed: {0}")]
 	ProposeSidechainBlock(String),
 	#[error("Failed to fetch sidechain blocks from peer: {0}")]
 	FetchSidechainBlocksFromPeer(String),
+	#[error("Failed to send trusted operations to peers: {0}")]
+	SendTrustedOperationsToPeers(String),
 	#[error("Sending extrinsics to parentchain failed: {0}")]
 	SendExtrinsicsToParentchain(String),
 	#[error("IPFS Error: {0}")]
@@ -208,6 +223,12 @@ pub trait MetricsBridge {
 	fn update_metric(&self, metric_encoded: Vec<u8>) -> OCallBridgeResult<()>;
 }
 
+/// Trait for persisting the enclave's audit log.
+#[cfg_attr(test, automock)]
+pub trait AuditLogBridge {
+	fn append_audit_record(&self, encoded_record: Vec<u8>) -> OCallBridgeResult<()>;
+}
+
 /// Trait for all the OCalls related to sidechain operations
 #[cfg_attr(test, automock)]
 pub trait SidechainBridge {
@@ -221,6 +242,12 @@ pub trait SidechainBridge {
 		maybe_until_block_hash_encoded: Vec<u8>,
 		shard_identifier_encoded: Vec<u8>,
 	) -> OCallBridgeResult<Vec<u8>>;
+
+	fn send_trusted_operations_to_peers(
+		&self,
+		peer_urls_encoded: Vec<u8>,
+		requests_encoded: Vec<u8>,
+	) -> OCallBridgeResult<()>;
 }
 
 /// type for IPFS
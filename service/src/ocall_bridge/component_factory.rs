@@ -18,9 +18,10 @@
 
 use crate::{
 	ocall_bridge::{
+		audit_log_ocall::AuditLogOCall,
 		bridge_api::{
-			GetOCallBridgeComponents, IpfsBridge, MetricsBridge, RemoteAttestationBridge,
-			SidechainBridge, WorkerOnChainBridge,
+			AuditLogBridge, GetOCallBridgeComponents, IpfsBridge, MetricsBridge,
+			RemoteAttestationBridge, SidechainBridge, WorkerOnChainBridge,
 		},
 		ipfs_ocall::IpfsOCall,
 		metrics_ocall::MetricsOCall,
@@ -35,10 +36,11 @@ use crate::{
 };
 use itp_enclave_api::remote_attestation::RemoteAttestationCallBacks;
 use itp_node_api::node_api_factory::CreateNodeApi;
+use itp_settings::files::AUDIT_LOG_FILE;
 use its_peer_fetch::FetchBlocksFromPeer;
 use its_primitives::types::block::SignedBlock as SignedSidechainBlock;
 use its_storage::BlockStorage;
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 /// Concrete implementation, should be moved out of the OCall Bridge, into the worker
 /// since the OCall bridge itself should not know any concrete types to ensure
@@ -162,4 +164,8 @@ impl<
 	fn get_metrics_api(&self) -> Arc<dyn MetricsBridge> {
 		Arc::new(MetricsOCall::new(self.metrics_receiver.clone()))
 	}
+
+	fn get_audit_log_api(&self) -> Arc<dyn AuditLogBridge> {
+		Arc::new(AuditLogOCall::new(PathBuf::from(AUDIT_LOG_FILE)))
+	}
 }
@@ -0,0 +1,48 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+	Copyright (C) 2017-2019 Baidu, Inc. All Rights Reserved.
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::ocall_bridge::bridge_api::{AuditLogBridge, Bridge};
+use log::*;
+use sgx_types::sgx_status_t;
+use std::{slice, sync::Arc};
+
+#[no_mangle]
+pub unsafe extern "C" fn ocall_append_audit_record(
+	encoded_record_ptr: *const u8,
+	encoded_record_size: u32,
+) -> sgx_status_t {
+	append_audit_record(encoded_record_ptr, encoded_record_size, Bridge::get_audit_log_api())
+}
+
+fn append_audit_record(
+	encoded_record_ptr: *const u8,
+	encoded_record_size: u32,
+	audit_log_api: Arc<dyn AuditLogBridge>,
+) -> sgx_status_t {
+	let encoded_record: Vec<u8> = unsafe {
+		Vec::from(slice::from_raw_parts(encoded_record_ptr, encoded_record_size as usize))
+	};
+
+	match audit_log_api.append_audit_record(encoded_record) {
+		Ok(_) => sgx_status_t::SGX_SUCCESS,
+		Err(e) => {
+			error!("append_audit_record o-call failed: {:?}", e);
+			sgx_status_t::SGX_ERROR_UNEXPECTED
+		},
+	}
+}
@@ -21,6 +21,7 @@
 //! These should just be wrappers that transform the C-API structures and call the
 //! actual implementation of the OCalls (using the traits defined in the bridge_api).
 
+pub mod append_audit_record;
 pub mod fetch_sidechain_blocks_from_peer;
 pub mod get_ias_socket;
 pub mod get_quote;
@@ -30,6 +31,7 @@ pub mod init_quote;
 pub mod ipfs;
 pub mod propose_sidechain_blocks;
 pub mod send_to_parentchain;
+pub mod send_trusted_operations_to_peers;
 pub mod store_sidechain_blocks;
 pub mod update_metric;
 pub mod worker_request;
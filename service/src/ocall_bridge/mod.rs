@@ -21,6 +21,7 @@
 pub mod bridge_api;
 pub mod component_factory;
 
+mod audit_log_ocall;
 mod ffi;
 mod ipfs_ocall;
 mod metrics_ocall;
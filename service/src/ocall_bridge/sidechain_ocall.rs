@@ -23,9 +23,13 @@ use crate::{
 	GetTokioHandle,
 };
 use codec::{Decode, Encode};
-use itp_types::{BlockHash, ShardIdentifier};
+use itc_rpc_client::direct_client::{DirectApi, DirectClient as DirectWorkerApi};
+use itp_rpc::RpcRequest;
+use itp_types::{BlockHash, Request, ShardIdentifier};
+use itp_utils::ToHexPrefixed;
 use its_peer_fetch::FetchBlocksFromPeer;
 use its_primitives::{traits::Block, types::SignedBlock as SignedSidechainBlock};
+use its_rpc_handler::constants::RPC_METHOD_NAME_SUBMIT_GOSSIPED_TOPS;
 use its_storage::BlockStorage;
 use log::*;
 use std::sync::Arc;
@@ -187,6 +191,55 @@ where
 
 		Ok(signed_sidechain_blocks.encode())
 	}
+
+	fn send_trusted_operations_to_peers(
+		&self,
+		peer_urls_encoded: Vec<u8>,
+		requests_encoded: Vec<u8>,
+	) -> OCallBridgeResult<()> {
+		let peer_urls: Vec<String> =
+			Decode::decode(&mut peer_urls_encoded.as_slice()).map_err(|_| {
+				OCallBridgeError::SendTrustedOperationsToPeers(
+					"Failed to decode peer urls".to_string(),
+				)
+			})?;
+
+		let requests: Vec<Request> =
+			Decode::decode(&mut requests_encoded.as_slice()).map_err(|_| {
+				OCallBridgeError::SendTrustedOperationsToPeers(
+					"Failed to decode trusted operations".to_string(),
+				)
+			})?;
+
+		if requests.is_empty() || peer_urls.is_empty() {
+			return Ok(())
+		}
+
+		let jsonrpc_call = RpcRequest::compose_jsonrpc_call(
+			RPC_METHOD_NAME_SUBMIT_GOSSIPED_TOPS.to_string(),
+			requests.iter().map(|r| r.to_hex()).collect(),
+		)
+		.map_err(|e| OCallBridgeError::SendTrustedOperationsToPeers(format!("{:?}", e)))?;
+
+		debug!(
+			"[O-call] gossiping {} trusted operation(s) to {} peer(s)",
+			requests.len(),
+			peer_urls.len()
+		);
+
+		// Don't block the enclave until all peers have responded.
+		let tokio_handle = self.tokio_handle.get_handle();
+		for url in peer_urls {
+			let jsonrpc_call = jsonrpc_call.clone();
+			tokio_handle.spawn_blocking(move || {
+				if let Err(e) = DirectWorkerApi::new(url.clone()).get(&jsonrpc_call) {
+					error!("Failed to gossip trusted operations to peer {}: {:?}", url, e);
+				}
+			});
+		}
+
+		Ok(())
+	}
 }
 
 #[cfg(test)]
@@ -248,6 +301,23 @@ mod tests {
 		assert_eq!(blocks, fetched_blocks_decoded);
 	}
 
+	#[test]
+	fn send_trusted_operations_to_peers_rejects_invalid_encoding() {
+		let sidechain_ocall = setup_sidechain_ocall_with_peer_blocks(HashMap::new());
+		let peer_urls: Vec<String> = vec![];
+		let requests = vec![Request { shard: H256::random(), cyphertext: vec![1u8, 2u8] }];
+
+		assert!(sidechain_ocall
+			.send_trusted_operations_to_peers(peer_urls.encode(), requests.encode())
+			.is_ok());
+		assert!(sidechain_ocall
+			.send_trusted_operations_to_peers(vec![1u8], requests.encode())
+			.is_err());
+		assert!(sidechain_ocall
+			.send_trusted_operations_to_peers(peer_urls.encode(), vec![1u8])
+			.is_err());
+	}
+
 	fn setup_sidechain_ocall_with_peer_blocks(
 		peer_blocks_map: HashMap<ShardIdentifier, Vec<SignedSidechainBlock>>,
 	) -> TestSidechainOCall {
@@ -47,4 +47,12 @@ impl SidechainBridge for SidechainBridgeMock {
 	) -> OCallBridgeResult<Vec<u8>> {
 		Ok(self.peer_blocks_encoded.clone())
 	}
+
+	fn send_trusted_operations_to_peers(
+		&self,
+		_peer_urls_encoded: Vec<u8>,
+		_requests_encoded: Vec<u8>,
+	) -> OCallBridgeResult<()> {
+		Ok(())
+	}
 }
@@ -172,9 +172,9 @@ impl ReceiveEnclaveMetrics for EnclaveMetricsReceiver {
 				ENCLAVE_SIDECHAIN_TOP_POOL_SIZE.dec();
 			},
 			#[cfg(feature = "teeracle")]
-			EnclaveMetric::ExchangeRateOracle(m) => update_teeracle_metrics(m)?,
+			EnclaveMetric::Oracle(m) => update_teeracle_metrics(m)?,
 			#[cfg(not(feature = "teeracle"))]
-			EnclaveMetric::ExchangeRateOracle(_) => {
+			EnclaveMetric::Oracle(_) => {
 				error!("Received Teeracle metric, but Teeracle feature is not enabled, ignoring metric item.")
 			},
 		}
@@ -16,16 +16,21 @@
 
 */
 
-use crate::error::{Error, ServiceResult};
+use crate::{
+	error::{Error, ServiceResult},
+	ocall_bridge::bridge_api::{Bridge as OCallBridge, GetOCallBridgeComponents},
+};
+use base58::ToBase58;
 use codec::Encode;
 use itp_enclave_api::{enclave_base::EnclaveBase, Enclave};
 use itp_settings::files::{
 	LAST_SLOT_BIN, LIGHT_CLIENT_DB, SHARDS_PATH, SHIELDING_KEY_FILE, SIDECHAIN_STORAGE_PATH,
 	SIGNING_KEY_FILE,
 };
-use itp_types::ShardIdentifier;
+use itp_types::{ShardIdentifier, StateVerificationReport};
 use log::*;
-use std::{fs, fs::File, path::Path};
+use sgx_crypto_helper::rsa3072::Rsa3072PubKey;
+use std::{fs, fs::File, path::Path, sync::Arc};
 
 /// Purge all worker files from the current working directory (cwd).
 pub(crate) fn purge_files_from_cwd() -> ServiceResult<()> {
@@ -92,6 +97,99 @@ pub(crate) fn generate_shielding_key_file(enclave: &Enclave) {
 	}
 }
 
+/// Read a shielding key, as written by `generate_shielding_key_file`.
+pub(crate) fn read_shielding_key_file(path: &Path) -> ServiceResult<Rsa3072PubKey> {
+	let key_json = fs::read(path).map_err(|e| Error::Custom(e.into()))?;
+	Ok(serde_json::from_slice(&key_json)?)
+}
+
+/// Export the state of a shard to the `archive_file`.
+///
+/// Only the enclave with the `target_shielding_key` will be able to import it.
+pub(crate) fn export_state<E: EnclaveBase>(
+	enclave: &E,
+	shard: &ShardIdentifier,
+	target_shielding_key: &Rsa3072PubKey,
+	archive_file: &Path,
+) -> ServiceResult<()> {
+	let archive = enclave.export_state(shard, target_shielding_key)?;
+	fs::write(archive_file, archive).map_err(|e| Error::Custom(e.into()))?;
+	println!("[+] Exported state of shard {:?} to '{}'", shard, archive_file.display());
+	Ok(())
+}
+
+/// Import the state archive in the `archive_file`, replacing the state of its shard.
+///
+/// Fails if the archive was not exported by an enclave the enclave trusts.
+pub(crate) fn import_state<E: EnclaveBase>(
+	enclave: &E,
+	archive_file: &Path,
+) -> ServiceResult<ShardIdentifier> {
+	let archive = fs::read(archive_file).map_err(|e| Error::Custom(e.into()))?;
+	let shard = enclave.import_state(&archive)?;
+	println!("[+] Imported state of shard {:?} from '{}'", shard, archive_file.display());
+	Ok(shard)
+}
+
+/// Verify the state snapshots of all shards and print a report for each shard.
+///
+/// With `rollback`, shards with corrupt snapshots are rolled back to their newest valid snapshot.
+pub(crate) fn verify_state<E: EnclaveBase>(enclave: &E, rollback: bool) -> ServiceResult<()> {
+	println!("[+] Verifying the state snapshots of all shards");
+	for report in enclave.verify_state(rollback)? {
+		println!("{}", format_verification_report(&report));
+	}
+	Ok(())
+}
+
+fn format_verification_report(report: &StateVerificationReport) -> String {
+	let mut lines = vec![format!(
+		"Shard {}: {} valid, {} corrupt snapshot(s)",
+		report.shard.encode().to_base58(),
+		report.valid_snapshots.len(),
+		report.corrupt_snapshots.len()
+	)];
+	for (state_id, corruption) in report.corrupt_snapshots.iter() {
+		lines.push(format!("  corrupt snapshot {}: {:?}", state_id, corruption));
+	}
+	match (report.rolled_back, report.valid_snapshots.first()) {
+		(true, Some(state_id)) => lines.push(format!("  rolled back to snapshot {}", state_id)),
+		(false, None) if !report.corrupt_snapshots.is_empty() =>
+			lines.push("  no valid snapshot left to roll back to".to_string()),
+		_ => {},
+	}
+	lines.join("\n")
+}
+
+/// Rotate the key the state files are encrypted with.
+///
+/// With `re_encrypt`, the state snapshots are re-encrypted with the new key right away.
+/// Otherwise, the worker re-encrypts them in the background when it is started next.
+pub(crate) fn rotate_state_key<E: EnclaveBase>(enclave: &E, re_encrypt: bool) -> ServiceResult<()> {
+	enclave.rotate_state_key()?;
+	println!("[+] Rotated the state key");
+	if re_encrypt {
+		let re_encrypted_snapshots = enclave.re_encrypt_state()?;
+		println!("[+] Re-encrypted {} state snapshot(s) with the new key", re_encrypted_snapshots);
+	} else {
+		println!("[+] State snapshots are re-encrypted when the worker is started next");
+	}
+	Ok(())
+}
+
+/// Initialize the OCall bridge and start the audit log of the enclave.
+///
+/// Starting the audit log persists its first record through an OCall, so it must not happen
+/// before the bridge is initialized.
+pub(crate) fn initialize_ocall_bridge<E: EnclaveBase>(
+	enclave: &E,
+	component_factory: Arc<dyn GetOCallBridgeComponents + Send + Sync>,
+) -> ServiceResult<()> {
+	OCallBridge::initialize(component_factory);
+	enclave.start_audit_log()?;
+	Ok(())
+}
+
 /// Purge all worker files in a given path.
 fn purge_files(root_directory: &Path) -> ServiceResult<()> {
 	remove_dir_if_it_exists(root_directory, SHARDS_PATH)?;
@@ -127,8 +225,20 @@ fn light_client_backup_file() -> String {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::{
+		ocall_bridge::bridge_api::{
+			AuditLogBridge, IpfsBridge, MetricsBridge, MockAuditLogBridge, RemoteAttestationBridge,
+			SidechainBridge, WorkerOnChainBridge,
+		},
+		tests::mocks::enclave_api_mock::EnclaveMock,
+	};
 	use itp_settings::files::SHARDS_PATH;
-	use std::{fs, path::PathBuf};
+	use itp_types::StateSnapshotCorruption;
+	use std::{
+		fs,
+		path::PathBuf,
+		sync::atomic::{AtomicUsize, Ordering},
+	};
 
 	#[test]
 	fn purge_files_deletes_all_relevant_files() {
@@ -170,6 +280,116 @@ mod tests {
 		assert!(purge_files(&root_directory).is_ok());
 	}
 
+	#[test]
+	fn exported_state_archive_can_be_imported_again() {
+		let test_directory_handle = TestDirectoryHandle::new(PathBuf::from(
+			"test_exported_state_archive_can_be_imported_again",
+		));
+		let archive_file = test_directory_handle.path().join("state_archive.bin");
+		let shard = ShardIdentifier::from([3u8; 32]);
+		let enclave = EnclaveMock;
+
+		export_state(&enclave, &shard, &Rsa3072PubKey::default(), &archive_file).unwrap();
+		let imported_shard = import_state(&enclave, &archive_file).unwrap();
+
+		assert_eq!(imported_shard, shard);
+	}
+
+	#[test]
+	fn import_state_fails_for_archive_of_untrusted_enclave() {
+		let test_directory_handle = TestDirectoryHandle::new(PathBuf::from(
+			"test_import_state_fails_for_archive_of_untrusted_enclave",
+		));
+		let archive_file = test_directory_handle.path().join("state_archive.bin");
+		let enclave = EnclaveMock;
+
+		let archive_of_other_enclave = (ShardIdentifier::default(), [9u8; 32]).encode();
+		fs::write(&archive_file, archive_of_other_enclave).unwrap();
+
+		assert!(import_state(&enclave, &archive_file).is_err());
+	}
+
+	#[test]
+	fn format_verification_report_lists_corrupt_snapshots() {
+		let report = StateVerificationReport {
+			shard: ShardIdentifier::default(),
+			valid_snapshots: vec![20, 10],
+			corrupt_snapshots: vec![(30, StateSnapshotCorruption::Unreadable)],
+			rolled_back: true,
+		};
+
+		assert_eq!(
+			format!(
+				"Shard {}: 2 valid, 1 corrupt snapshot(s)\n  corrupt snapshot 30: Unreadable\n  rolled back to snapshot 20",
+				ShardIdentifier::default().encode().to_base58()
+			),
+			format_verification_report(&report)
+		);
+	}
+
+	#[test]
+	fn format_verification_report_warns_if_no_valid_snapshot_is_left() {
+		let report = StateVerificationReport {
+			shard: ShardIdentifier::default(),
+			valid_snapshots: vec![],
+			corrupt_snapshots: vec![(30, StateSnapshotCorruption::HashMismatch)],
+			rolled_back: false,
+		};
+
+		assert!(format_verification_report(&report)
+			.ends_with("  no valid snapshot left to roll back to"));
+	}
+
+	#[test]
+	fn audit_log_is_started_once_the_ocall_bridge_is_initialized() {
+		let persisted_records = Arc::new(AtomicUsize::new(0));
+		let mut audit_log_api = MockAuditLogBridge::new();
+		let records = persisted_records.clone();
+		audit_log_api.expect_append_audit_record().returning(move |_| {
+			records.fetch_add(1, Ordering::SeqCst);
+			Ok(())
+		});
+
+		// The enclave mock persists the start record through the bridge, like the enclave does.
+		initialize_ocall_bridge(
+			&EnclaveMock,
+			Arc::new(AuditLogOnlyComponentFactory { audit_log_api: Arc::new(audit_log_api) }),
+		)
+		.unwrap();
+
+		assert_eq!(1, persisted_records.load(Ordering::SeqCst));
+	}
+
+	struct AuditLogOnlyComponentFactory {
+		audit_log_api: Arc<MockAuditLogBridge>,
+	}
+
+	impl GetOCallBridgeComponents for AuditLogOnlyComponentFactory {
+		fn get_ra_api(&self) -> Arc<dyn RemoteAttestationBridge> {
+			unreachable!()
+		}
+
+		fn get_sidechain_api(&self) -> Arc<dyn SidechainBridge> {
+			unreachable!()
+		}
+
+		fn get_oc_api(&self) -> Arc<dyn WorkerOnChainBridge> {
+			unreachable!()
+		}
+
+		fn get_ipfs_api(&self) -> Arc<dyn IpfsBridge> {
+			unreachable!()
+		}
+
+		fn get_metrics_api(&self) -> Arc<dyn MetricsBridge> {
+			unreachable!()
+		}
+
+		fn get_audit_log_api(&self) -> Arc<dyn AuditLogBridge> {
+			self.audit_log_api.clone()
+		}
+	}
+
 	/// Directory handle to automatically initialize a directory
 	/// and upon dropping the reference, removing it again.
 	struct TestDirectoryHandle {
@@ -26,15 +26,21 @@ use itp_enclave_api::{
 };
 use itp_settings::{
 	files::{SIDECHAIN_PURGE_INTERVAL, SIDECHAIN_PURGE_LIMIT},
-	sidechain::SLOT_DURATION,
+	sidechain::{SLOT_DURATION, TOP_POOL_PERSIST_INTERVAL},
 };
-use itp_types::Header;
+use itp_types::{Header, TopPoolLimits};
 use its_consensus_slots::start_slot_worker;
 use its_primitives::types::block::SignedBlock as SignedSidechainBlock;
-use its_storage::{interface::FetchBlocks, start_sidechain_pruning_loop, BlockPruner};
+use its_storage::{
+	interface::{FetchBlocks, QueryBlocks},
+	start_sidechain_pruning_loop, BlockPruner,
+};
 use log::*;
-use std::{sync::Arc, thread};
-use tokio::runtime::Handle;
+use std::{process, sync::Arc, thread};
+use tokio::{
+	runtime::Handle,
+	signal::unix::{signal, SignalKind},
+};
 
 pub(crate) fn sidechain_start_untrusted_rpc_server<Enclave, SidechainStorage>(
 	config: &Config,
@@ -43,7 +49,12 @@ pub(crate) fn sidechain_start_untrusted_rpc_server<Enclave, SidechainStorage>(
 	tokio_handle: Handle,
 ) where
 	Enclave: DirectRequest + Clone,
-	SidechainStorage: BlockPruner + FetchBlocks<SignedSidechainBlock> + Sync + Send + 'static,
+	SidechainStorage: BlockPruner
+		+ FetchBlocks<SignedSidechainBlock>
+		+ QueryBlocks<SignedSidechainBlock>
+		+ Sync
+		+ Send
+		+ 'static,
 {
 	let untrusted_url = config.untrusted_worker_url();
 	println!("[+] Untrusted RPC server listening on {}", &untrusted_url);
@@ -61,6 +72,7 @@ pub(crate) fn sidechain_init_block_production<Enclave, SidechainStorage, Parentc
 	parentchain_handler: Arc<ParentchainHandler>,
 	sidechain_storage: Arc<SidechainStorage>,
 	last_synced_header: &Header,
+	top_pool_limits: &TopPoolLimits,
 ) -> ServiceResult<Header>
 where
 	Enclave: EnclaveBase + Sidechain,
@@ -83,7 +95,7 @@ where
 
 	// ------------------------------------------------------------------------
 	// Initialize sidechain components (has to be AFTER init_parentchain_components()
-	enclave.init_enclave_sidechain_components().unwrap();
+	enclave.init_enclave_sidechain_components(top_pool_limits).unwrap();
 
 	// ------------------------------------------------------------------------
 	// Start interval sidechain block production (execution of trusted calls, sidechain block production).
@@ -117,6 +129,58 @@ where
 	Ok(updated_header.unwrap_or_else(|| last_synced_header.clone()))
 }
 
+/// Periodically seal the pending trusted calls of the TOP pool, and once more on shutdown
+/// (SIGINT or SIGTERM), so they are restored when the worker restarts.
+///
+/// Has to be started AFTER the sidechain components are initialized, otherwise the sealed
+/// operations are overwritten before they are restored.
+pub(crate) fn sidechain_start_top_pool_persistence<Enclave>(
+	enclave: Arc<Enclave>,
+	tokio_handle: Handle,
+) -> ServiceResult<()>
+where
+	Enclave: Sidechain,
+{
+	let persistence_enclave_api = enclave.clone();
+	println!("[+] Spawning thread for TOP pool persistence");
+	thread::Builder::new()
+		.name("top_pool_persistence_timer".to_owned())
+		.spawn(move || loop {
+			thread::sleep(TOP_POOL_PERSIST_INTERVAL);
+			persist_top_pool(persistence_enclave_api.as_ref());
+		})
+		.map_err(|e| Error::Custom(Box::new(e)))?;
+
+	tokio_handle.spawn(async move {
+		let mut terminate = match signal(SignalKind::terminate()) {
+			Ok(s) => s,
+			Err(e) => {
+				error!(
+					"Failed to register SIGTERM handler, TOP pool will not be persisted: {:?}",
+					e
+				);
+				return
+			},
+		};
+		tokio::select! {
+			_ = tokio::signal::ctrl_c() => {},
+			_ = terminate.recv() => {},
+		}
+		println!("[!] Shutting down, persisting the TOP pool");
+		persist_top_pool(enclave.as_ref());
+		process::exit(0);
+	});
+
+	Ok(())
+}
+
+/// Seal the pending trusted operations of the TOP pool in the enclave.
+fn persist_top_pool<E: Sidechain>(enclave_api: &E) {
+	if let Err(e) = enclave_api.persist_top_pool() {
+		error!("Failed to persist the TOP pool: {:?}", e);
+	};
+}
+
 /// Execute trusted operations in the enclave.
 fn execute_trusted_calls<E: Sidechain>(enclave_api: &E) {
 	if let Err(e) = enclave_api.execute_trusted_calls() {
@@ -15,15 +15,21 @@
 
 */
 
-use crate::teeracle::interval_scheduling::schedule_on_repeating_intervals;
+use crate::{
+	config::{TradingPair, WeatherLocation},
+	teeracle::interval_scheduling::schedule_on_repeating_intervals,
+};
 use codec::{Decode, Encode};
 use itp_enclave_api::teeracle_api::TeeracleApi;
 use itp_node_api::api_client::ParentchainApi;
-use itp_settings::teeracle::DEFAULT_MARKET_DATA_UPDATE_INTERVAL;
+use itp_settings::teeracle::{
+	DEFAULT_MARKET_DATA_UPDATE_INTERVAL, DEFAULT_MAX_EXCHANGE_RATE_DEVIATION_PERCENT,
+	DEFAULT_TRADING_PAIRS,
+};
 use itp_utils::hex::hex_encode;
 use log::*;
 use sp_runtime::OpaqueExtrinsic;
-use std::time::Duration;
+use std::{fs, time::Duration};
 use substrate_api_client::{SubmitAndWatch, XtStatus};
 use teeracle_metrics::{increment_number_of_request_failures, set_extrinsics_inclusion_success};
 use tokio::runtime::Handle;
@@ -31,18 +37,52 @@ use tokio::runtime::Handle;
 pub(crate) mod interval_scheduling;
 pub(crate) mod teeracle_metrics;
 
+/// Upload the oracle source configs from the given JSON file to the enclave, which validates
+/// and seals them. Panics if the file can't be read or the enclave rejects the configs.
+pub(crate) fn set_oracle_source_configs<E: TeeracleApi>(enclave_api: &E, path: &str) {
+	let configs_json = fs::read_to_string(path)
+		.unwrap_or_else(|e| panic!("Failed to read oracle sources config {}: {:?}", path, e));
+	enclave_api
+		.set_oracle_source_configs(&configs_json)
+		.unwrap_or_else(|e| panic!("Enclave rejected the oracle sources config {}: {:?}", path, e));
+	info!("Set the oracle source configs from {}", path);
+}
+
 /// Send extrinsic to chain according to the market data update interval in the settings
-/// with the current market data (for now only exchange rate).
+/// with the current market data (exchange rates and, if a location is given, the weather).
 pub(crate) fn start_interval_market_update<E: TeeracleApi>(
 	api: &ParentchainApi,
 	maybe_interval: Option<Duration>,
+	trading_pairs: &[TradingPair],
+	maybe_max_deviation_percent: Option<u32>,
+	maybe_weather_location: Option<&WeatherLocation>,
 	enclave_api: &E,
 	tokio_handle: &Handle,
 ) {
+	let trading_pairs: Vec<(String, String)> = if trading_pairs.is_empty() {
+		DEFAULT_TRADING_PAIRS
+			.iter()
+			.map(|(crypto, fiat)| (crypto.to_string(), fiat.to_string()))
+			.collect()
+	} else {
+		trading_pairs
+			.iter()
+			.map(|pair| (pair.crypto_currency.clone(), pair.fiat_currency.clone()))
+			.collect()
+	};
+	let max_deviation_percent =
+		maybe_max_deviation_percent.unwrap_or(DEFAULT_MAX_EXCHANGE_RATE_DEVIATION_PERCENT);
 	let updates_to_run = || {
-		execute_market_update(api, enclave_api, tokio_handle);
-		// TODO: Refactor and add this back according to ISSUE: https://github.com/integritee-network/worker/issues/1300
-		// execute_weather_update(api, enclave_api, tokio_handle);
+		execute_market_update(
+			api,
+			&trading_pairs,
+			max_deviation_percent,
+			enclave_api,
+			tokio_handle,
+		);
+		if let Some(location) = maybe_weather_location {
+			execute_weather_update(api, location, enclave_api, tokio_handle);
+		}
 	};
 	info!("Teeracle will update now");
 	updates_to_run();
@@ -52,20 +92,21 @@ pub(crate) fn start_interval_market_update<E: TeeracleApi>(
 	schedule_on_repeating_intervals(updates_to_run, interval);
 }
 
-#[allow(dead_code)]
 fn execute_weather_update<E: TeeracleApi>(
 	node_api: &ParentchainApi,
+	location: &WeatherLocation,
 	enclave: &E,
 	tokio_handle: &Handle,
 ) {
-	let updated_extrinsic = match enclave.update_weather_data_xt("54.32", "15.37") {
-		Err(e) => {
-			error!("{:?}", e);
-			increment_number_of_request_failures();
-			return
-		},
-		Ok(r) => r,
-	};
+	let updated_extrinsic =
+		match enclave.update_weather_data_xt(&location.longitude, &location.latitude) {
+			Err(e) => {
+				error!("{:?}", e);
+				increment_number_of_request_failures();
+				return
+			},
+			Ok(r) => r,
+		};
 
 	let extrinsics = match <Vec<OpaqueExtrinsic>>::decode(&mut updated_extrinsic.as_slice()) {
 		Ok(calls) => calls,
@@ -102,18 +143,21 @@ fn execute_weather_update<E: TeeracleApi>(
 
 fn execute_market_update<E: TeeracleApi>(
 	node_api: &ParentchainApi,
+	trading_pairs: &[(String, String)],
+	max_deviation_percent: u32,
 	enclave: &E,
 	tokio_handle: &Handle,
 ) {
-	// Get market data for usd (hardcoded)
-	let updated_extrinsic = match enclave.update_market_data_xt("TEER", "USD") {
-		Err(e) => {
-			error!("{:?}", e);
-			increment_number_of_request_failures();
-			return
-		},
-		Ok(r) => r,
-	};
+	// Get market data of all trading pairs, batched into a single extrinsic
+	let updated_extrinsic =
+		match enclave.update_market_data_xt(trading_pairs, max_deviation_percent) {
+			Err(e) => {
+				error!("{:?}", e);
+				increment_number_of_request_failures();
+				return
+			},
+			Ok(r) => r,
+		};
 
 	let extrinsics: Vec<OpaqueExtrinsic> = match Decode::decode(&mut updated_extrinsic.as_slice()) {
 		Ok(calls) => calls,
@@ -130,7 +174,7 @@ fn execute_market_update<E: TeeracleApi>(
 			let encoded_extrinsic = call.encode();
 			debug!("Hex encoded extrinsic to be sent: {}", hex_encode(&encoded_extrinsic));
 
-			println!("[>] Update the exchange rate (send the extrinsic)");
+			println!("[>] Update the exchange rates (send the extrinsic)");
 			let extrinsic_hash = match node_api_clone.submit_and_watch_opaque_extrinsic_until(
 				encoded_extrinsic.into(),
 				XtStatus::InBlock,
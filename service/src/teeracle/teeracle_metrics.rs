@@ -16,7 +16,7 @@
 */
 
 use crate::{error::ServiceResult, Error};
-use itp_enclave_metrics::ExchangeRateOracleMetric;
+use itp_enclave_metrics::OracleMetric;
 use lazy_static::lazy_static;
 use prometheus::{
 	register_gauge_vec, register_int_counter, register_int_counter_vec, register_int_gauge,
@@ -26,8 +26,8 @@ use prometheus::{
 lazy_static! {
 	/// Register Teeracle specific metrics
 
-	static ref EXCHANGE_RATE: GaugeVec =
-		register_gauge_vec!("integritee_teeracle_exchange_rate", "Exchange rates partitioned into source and trading pair", &["source", "trading_pair"])
+	static ref ORACLE_VALUE: GaugeVec =
+		register_gauge_vec!("integritee_teeracle_oracle_value", "Latest oracle values partitioned into source, feed and key (e.g. trading pair or location)", &["source", "feed", "key"])
 			.unwrap();
 	static ref RESPONSE_TIME: IntGaugeVec =
 		register_int_gauge_vec!("integritee_teeracle_response_times", "Response times in ms for requests that the oracle makes", &["source"])
@@ -35,6 +35,17 @@ lazy_static! {
 	static ref NUMBER_OF_REQUESTS: IntCounterVec =
 		register_int_counter_vec!("integritee_teeracle_number_of_requests", "Number of requests made per source", &["source"])
 			.unwrap();
+	static ref NUMBER_OF_SOURCE_FAILURES: IntCounterVec =
+		register_int_counter_vec!("integritee_teeracle_number_of_source_failures", "Number of requests per source that failed after all retries", &["source"])
+			.unwrap();
+
+	static ref NUMBER_OF_REJECTED_VALUES: IntCounterVec =
+		register_int_counter_vec!("integritee_teeracle_number_of_rejected_values", "Number of values rejected as outliers partitioned into source, feed and key", &["source", "feed", "key"])
+			.unwrap();
+
+	static ref CACHE_HIT_RATIO: GaugeVec =
+		register_gauge_vec!("integritee_teeracle_cache_hit_ratio", "Share of the response cache lookups per source that were served without a request", &["source"])
+			.unwrap();
 
 	static ref NUMBER_OF_REQUEST_FAILURES: IntCounter =
 		register_int_counter!("integritee_teeracle_request_failures", "Number of requests that failed")
@@ -54,23 +65,37 @@ pub(super) fn set_extrinsics_inclusion_success(is_successful: bool) {
 	EXTRINSIC_INCLUSION_SUCCESS.set(success_values);
 }
 
-pub fn update_teeracle_metrics(metric: ExchangeRateOracleMetric) -> ServiceResult<()> {
+pub fn update_teeracle_metrics(metric: OracleMetric) -> ServiceResult<()> {
 	match metric {
-		ExchangeRateOracleMetric::ExchangeRate(source, trading_pair, exchange_rate) =>
-			EXCHANGE_RATE
-				.get_metric_with_label_values(&[source.as_str(), trading_pair.as_str()])
-				.map(|m| m.set(exchange_rate.to_num()))
-				.map_err(|e| Error::Custom(e.into()))?,
+		OracleMetric::Value(source, feed, key, value) => ORACLE_VALUE
+			.get_metric_with_label_values(&[source.as_str(), feed.as_str(), key.as_str()])
+			.map(|m| m.set(value.to_num()))
+			.map_err(|e| Error::Custom(e.into()))?,
 
-		ExchangeRateOracleMetric::ResponseTime(source, t) => RESPONSE_TIME
+		OracleMetric::ResponseTime(source, t) => RESPONSE_TIME
 			.get_metric_with_label_values(&[source.as_str()])
 			.map(|m| m.set(t as i64))
 			.map_err(|e| Error::Custom(e.into()))?,
 
-		ExchangeRateOracleMetric::NumberRequestsIncrement(source) => NUMBER_OF_REQUESTS
+		OracleMetric::NumberRequestsIncrement(source) => NUMBER_OF_REQUESTS
+			.get_metric_with_label_values(&[source.as_str()])
+			.map(|m| m.inc())
+			.map_err(|e| Error::Custom(e.into()))?,
+
+		OracleMetric::NumberFailuresIncrement(source) => NUMBER_OF_SOURCE_FAILURES
 			.get_metric_with_label_values(&[source.as_str()])
 			.map(|m| m.inc())
 			.map_err(|e| Error::Custom(e.into()))?,
+
+		OracleMetric::RejectedValue(source, feed, key) => NUMBER_OF_REJECTED_VALUES
+			.get_metric_with_label_values(&[source.as_str(), feed.as_str(), key.as_str()])
+			.map(|m| m.inc())
+			.map_err(|e| Error::Custom(e.into()))?,
+
+		OracleMetric::CacheHitRatio(source, hit_ratio) => CACHE_HIT_RATIO
+			.get_metric_with_label_values(&[source.as_str()])
+			.map(|m| m.set(hit_ratio.to_num()))
+			.map_err(|e| Error::Custom(e.into()))?,
 	};
 	Ok(())
 }
@@ -21,6 +21,10 @@ use std::str;
 
 #[cfg(test)]
 use crate::config::Config;
+#[cfg(test)]
+use itp_settings::files::{DEFAULT_MAX_STATE_DELTA_CHAIN_LENGTH, DEFAULT_MAX_STATE_SNAPSHOTS};
+#[cfg(test)]
+use itp_types::StateSnapshotRetention;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Message {
@@ -49,6 +53,12 @@ pub fn local_worker_config(
 		false,
 		"8787".to_string(),
 		"4545".to_string(),
+		StateSnapshotRetention {
+			max_snapshots: DEFAULT_MAX_STATE_SNAPSHOTS,
+			max_age_secs: None,
+			max_delta_chain_length: DEFAULT_MAX_STATE_DELTA_CHAIN_LENGTH,
+		},
+		Default::default(),
 		None,
 	)
 }
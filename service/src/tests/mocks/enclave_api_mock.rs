@@ -15,6 +15,7 @@
 
 */
 
+use crate::ocall_bridge::bridge_api::Bridge;
 use codec::{Decode, Encode};
 use core::fmt::Debug;
 use frame_support::sp_runtime::traits::Block as ParentchainBlockTrait;
@@ -22,9 +23,14 @@ use itc_parentchain::primitives::{
 	ParentchainInitParams,
 	ParentchainInitParams::{Parachain, Solochain},
 };
-use itp_enclave_api::{enclave_base::EnclaveBase, sidechain::Sidechain, EnclaveResult};
+use itp_enclave_api::{
+	enclave_base::EnclaveBase, error::Error, sidechain::Sidechain, EnclaveResult,
+};
 use itp_settings::worker::MR_ENCLAVE_SIZE;
 use itp_storage::StorageProof;
+use itp_types::{
+	MrEnclave, ShardIdentifier, StateSnapshotRetention, StateVerificationReport, TopPoolLimits,
+};
 use sgx_crypto_helper::rsa3072::Rsa3072PubKey;
 use sp_core::ed25519;
 
@@ -32,11 +38,26 @@ use sp_core::ed25519;
 pub struct EnclaveMock;
 
 impl EnclaveBase for EnclaveMock {
-	fn init(&self, _mu_ra_url: &str, _untrusted_url: &str) -> EnclaveResult<()> {
+	fn init(
+		&self,
+		_mu_ra_url: &str,
+		_untrusted_url: &str,
+		_snapshot_retention: &StateSnapshotRetention,
+	) -> EnclaveResult<()> {
 		Ok(())
 	}
 
-	fn init_enclave_sidechain_components(&self) -> EnclaveResult<()> {
+	fn start_audit_log(&self) -> EnclaveResult<()> {
+		// Like the enclave, persist the start record through the OCall bridge.
+		Bridge::get_audit_log_api()
+			.append_audit_record(Vec::new())
+			.map_err(|e| Error::Other(e.into()))
+	}
+
+	fn init_enclave_sidechain_components(
+		&self,
+		_top_pool_limits: &TopPoolLimits,
+	) -> EnclaveResult<()> {
 		Ok(())
 	}
 
@@ -83,6 +104,35 @@ impl EnclaveBase for EnclaveMock {
 	fn get_mrenclave(&self) -> EnclaveResult<[u8; MR_ENCLAVE_SIZE]> {
 		Ok([1u8; MR_ENCLAVE_SIZE])
 	}
+
+	fn export_state(
+		&self,
+		shard: &ShardIdentifier,
+		_target_shielding_key: &Rsa3072PubKey,
+	) -> EnclaveResult<Vec<u8>> {
+		// The archive of the mock only consists of the shard and the exporting MRENCLAVE.
+		Ok((shard, self.get_mrenclave()?).encode())
+	}
+
+	fn import_state(&self, mut archive: &[u8]) -> EnclaveResult<ShardIdentifier> {
+		let (shard, mrenclave) = <(ShardIdentifier, MrEnclave)>::decode(&mut archive)?;
+		if mrenclave != self.get_mrenclave()? {
+			return Err(Error::Other("State archive of untrusted enclave".into()))
+		}
+		Ok(shard)
+	}
+
+	fn verify_state(&self, _rollback: bool) -> EnclaveResult<Vec<StateVerificationReport>> {
+		Ok(vec![])
+	}
+
+	fn rotate_state_key(&self) -> EnclaveResult<()> {
+		Ok(())
+	}
+
+	fn re_encrypt_state(&self) -> EnclaveResult<u32> {
+		Ok(0)
+	}
 }
 
 impl Sidechain for EnclaveMock {
@@ -99,4 +149,8 @@ impl Sidechain for EnclaveMock {
 	fn execute_trusted_calls(&self) -> EnclaveResult<()> {
 		todo!()
 	}
+
+	fn persist_top_pool(&self) -> EnclaveResult<()> {
+		Ok(())
+	}
 }
@@ -0,0 +1,94 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+//! Publicly visible parts of sidechain blocks, as returned by the block query RPC methods.
+
+use crate::{
+	traits::{Block as BlockTrait, SignedBlock as SignedBlockTrait},
+	types::{
+		block::{BlockHash, BlockNumber, SignedBlock, Timestamp},
+		header::{ShardIdentifier, SidechainHeader as Header},
+	},
+};
+use codec::{Decode, Encode};
+use sp_core::{ed25519, H256};
+use sp_std::vec::Vec;
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// Sidechain block without its encrypted state diff, which only the validateers can decrypt.
+///
+/// Trusted operations are only listed by their hash, so the details of a call are only
+/// known to its signer.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct BlockSummary {
+	pub hash: BlockHash,
+	pub header: Header,
+	pub block_author: ed25519::Public,
+	pub timestamp: Timestamp,
+	/// Parentchain header this block is based on.
+	pub layer_one_head: H256,
+	/// Hashes of the trusted operations included in this block.
+	pub signed_top_hashes: Vec<H256>,
+}
+
+impl From<&SignedBlock> for BlockSummary {
+	fn from(signed_block: &SignedBlock) -> Self {
+		let block = signed_block.block();
+		BlockSummary {
+			hash: signed_block.hash(),
+			header: *block.header(),
+			block_author: block.block_data.block_author,
+			timestamp: block.block_data.timestamp,
+			layer_one_head: block.block_data.layer_one_head,
+			signed_top_hashes: block.block_data.signed_top_hashes.clone(),
+		}
+	}
+}
+
+/// Inclusion of a trusted operation in a sidechain block.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct OperationInclusion {
+	pub shard: ShardIdentifier,
+	pub block_number: BlockNumber,
+	pub block_hash: BlockHash,
+}
+
+impl From<&SignedBlock> for OperationInclusion {
+	fn from(signed_block: &SignedBlock) -> Self {
+		OperationInclusion {
+			shard: signed_block.block().header.shard_id,
+			block_number: signed_block.block().header.block_number,
+			block_hash: signed_block.hash(),
+		}
+	}
+}
+
+/// Status of a trusted operation, as seen by a worker.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum OperationStatus {
+	/// Included in a sidechain block.
+	Included(OperationInclusion),
+	/// Waiting in the trusted operation pool to be included.
+	Pending,
+	/// Neither included nor pending: never submitted, dropped or failed.
+	Unknown,
+}
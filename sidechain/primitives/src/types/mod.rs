@@ -17,6 +17,7 @@
 
 pub mod block;
 pub mod block_data;
+pub mod block_summary;
 pub mod header;
 
 pub use block::*;
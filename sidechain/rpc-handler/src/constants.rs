@@ -20,3 +20,9 @@
 // RPC method names.
 pub const RPC_METHOD_NAME_IMPORT_BLOCKS: &str = "sidechain_importBlock";
 pub const RPC_METHOD_NAME_FETCH_BLOCKS_FROM_PEER: &str = "sidechain_fetchBlocksFromPeer";
+pub const RPC_METHOD_NAME_SUBMIT_GOSSIPED_TOPS: &str = "author_submitGossipedExtrinsics";
+pub const RPC_METHOD_NAME_GET_BLOCK_BY_HASH: &str = "sidechain_getBlockByHash";
+pub const RPC_METHOD_NAME_GET_BLOCK_BY_NUMBER: &str = "sidechain_getBlockByNumber";
+pub const RPC_METHOD_NAME_GET_BLOCK_OPERATIONS: &str = "sidechain_getBlockOperations";
+pub const RPC_METHOD_NAME_GET_OPERATION_INCLUSION: &str = "sidechain_getOperationInclusion";
+pub const RPC_METHOD_NAME_IS_OPERATION_PENDING: &str = "sidechain_isOperationPending";
@@ -24,6 +24,7 @@ use rust_base58::base58::FromBase58;
 #[cfg(feature = "sgx")]
 use base58::FromBase58;
 
+use crate::constants::RPC_METHOD_NAME_SUBMIT_GOSSIPED_TOPS;
 use codec::{Decode, Encode};
 use itp_rpc::RpcReturnValue;
 use itp_stf_primitives::types::AccountId;
@@ -79,6 +80,22 @@ where
 		Ok(json!(json_value))
 	});
 
+	// author_submitGossipedExtrinsics
+	let gossip_author = top_pool_author.clone();
+	io_handler.add_sync_method(RPC_METHOD_NAME_SUBMIT_GOSSIPED_TOPS, move |params: Params| {
+		let json_value =
+			match author_submit_gossiped_extrinsics_inner(gossip_author.clone(), params) {
+				Ok(hashes) => RpcReturnValue {
+					do_watch: false,
+					value: hashes.encode(),
+					status: DirectRequestStatus::Ok,
+				}
+				.to_hex(),
+				Err(error) => compute_hex_encoded_return_error(error.as_str()),
+			};
+		Ok(json!(json_value))
+	});
+
 	// author_pendingExtrinsics
 	let author_pending_extrinsic_name: &str = "author_pendingExtrinsics";
 	let pending_author = top_pool_author.clone();
@@ -194,3 +211,28 @@ fn author_submit_extrinsic_inner<R: AuthorApi<Hash, Hash> + Send + Sync + 'stati
 
 	response.map_err(|e| format!("{:?}", e))
 }
+
+/// Submits all trusted operations gossiped by a peer validateer, returns the hashes of those
+/// that were accepted. Operations already known to the pool are rejected by their hash.
+fn author_submit_gossiped_extrinsics_inner<R: AuthorApi<Hash, Hash> + Send + Sync + 'static>(
+	author: Arc<R>,
+	params: Params,
+) -> Result<Vec<Hash>, String> {
+	let hex_encoded_params = params.parse::<Vec<String>>().map_err(|e| format!("{:?}", e))?;
+
+	let mut submitted_hashes = Vec::with_capacity(hex_encoded_params.len());
+	for hex_encoded_request in hex_encoded_params.iter() {
+		let request = Request::from_hex(hex_encoded_request).map_err(|e| format!("{:?}", e))?;
+		let result = async { author.submit_gossiped_top(request.cyphertext, request.shard).await };
+
+		match executor::block_on(result) {
+			Ok(h) => {
+				debug!("Gossiped trusted operation submitted successfully ({:?})", h);
+				submitted_hashes.push(h);
+			},
+			Err(e) => debug!("Submitting gossiped trusted operation failed: {:?}", e),
+		}
+	}
+
+	Ok(submitted_hashes)
+}
@@ -0,0 +1,91 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+#[cfg(all(not(feature = "std"), feature = "sgx"))]
+use crate::sgx_reexport_prelude::*;
+
+use crate::constants::RPC_METHOD_NAME_IS_OPERATION_PENDING;
+use itp_utils::FromHexPrefixed;
+use jsonrpc_core::{IoHandler, Params, Value};
+use log::*;
+use sp_core::H256;
+use std::{string::String, vec::Vec};
+
+/// Tells whether a trusted operation is still in the pool, so the untrusted RPC server can
+/// distinguish pending operations from unknown ones.
+pub fn add_operation_status_rpc_method<IsPendingFn>(
+	is_pending_fn: IsPendingFn,
+	mut io_handler: IoHandler,
+) -> IoHandler
+where
+	IsPendingFn: Fn(&H256) -> bool + Sync + Send + 'static,
+{
+	io_handler.add_sync_method(RPC_METHOD_NAME_IS_OPERATION_PENDING, move |params: Params| {
+		debug!("{} rpc. Params: {:?}", RPC_METHOD_NAME_IS_OPERATION_PENDING, params);
+
+		let hex_encoded_params: Vec<String> = params.parse()?;
+		let operation_hash = hex_encoded_params
+			.first()
+			.and_then(|hash| H256::from_hex(hash).ok())
+			.ok_or_else(|| {
+				jsonrpc_core::error::Error::invalid_params_with_details(
+					"Could not decode operation hash",
+					hex_encoded_params,
+				)
+			})?;
+
+		Ok(Value::Bool(is_pending_fn(&operation_hash)))
+	});
+
+	io_handler
+}
+
+#[cfg(test)]
+pub mod tests {
+
+	use super::*;
+	use itp_utils::ToHexPrefixed;
+
+	fn io_handler() -> IoHandler {
+		let pending_hash = H256::repeat_byte(1);
+		add_operation_status_rpc_method(move |hash| *hash == pending_hash, IoHandler::new())
+	}
+
+	fn request(operation_hash: &H256) -> String {
+		format!(
+			r#"{{"jsonrpc":"2.0","method":"{}","params":["{}"],"id":1}}"#,
+			RPC_METHOD_NAME_IS_OPERATION_PENDING,
+			operation_hash.to_hex()
+		)
+	}
+
+	#[test]
+	pub fn operation_in_pool_is_pending() {
+		let response_string =
+			io_handler().handle_request_sync(&request(&H256::repeat_byte(1))).unwrap();
+
+		assert_eq!(response_string, r#"{"jsonrpc":"2.0","result":true,"id":1}"#);
+	}
+
+	#[test]
+	pub fn unknown_operation_is_not_pending() {
+		let response_string =
+			io_handler().handle_request_sync(&request(&H256::repeat_byte(2))).unwrap();
+
+		assert_eq!(response_string, r#"{"jsonrpc":"2.0","result":false,"id":1}"#);
+	}
+}
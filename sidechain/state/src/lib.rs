@@ -191,5 +191,6 @@ impl<T: SidechainState> SidechainSystemExt for T {
 		self.clear_with_name("System", "Events");
 		self.clear_with_name("System", "EventCount");
 		self.clear_prefix_with_name("System", "EventTopics");
+		self.clear_with_name("Stf", "Events");
 	}
 }
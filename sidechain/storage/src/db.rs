@@ -25,6 +25,7 @@ use std::path::PathBuf;
 /// (LAST_BLOCK_KEY, Shard) -> (Blockhash, BlockNr) (look up current blockchain state)
 /// (Shard , Block number) -> Blockhash (needed for block pruning)
 /// Blockhash -> Signed Block (actual block storage)
+/// (OPERATION_INCLUSION_KEY, Trusted operation hash) -> Blockhash (look up inclusion of operations)
 
 /// Interface struct to rocks DB
 pub struct SidechainDB {
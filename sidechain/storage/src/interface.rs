@@ -26,6 +26,7 @@ use its_primitives::{
 	types::{BlockHash, BlockNumber},
 };
 use parking_lot::RwLock;
+use sp_core::H256;
 use std::path::PathBuf;
 
 /// Lock wrapper around sidechain storage
@@ -79,6 +80,22 @@ pub trait FetchBlocks<SignedBlock: SignedBlockT> {
 	) -> Result<Vec<SignedBlock>>;
 }
 
+#[cfg_attr(test, automock)]
+pub trait QueryBlocks<SignedBlock: SignedBlockT> {
+	/// Query a block by its hash.
+	fn block_by_hash(&self, block_hash: &BlockHash) -> Result<Option<SignedBlock>>;
+
+	/// Query a block of a shard by its number.
+	fn block_by_number(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+		block_number: BlockNumber,
+	) -> Result<Option<SignedBlock>>;
+
+	/// Query the block that includes the trusted operation with the given hash.
+	fn block_including_operation(&self, operation_hash: &H256) -> Result<Option<SignedBlock>>;
+}
+
 impl<SignedBlock: SignedBlockT> BlockStorage<SignedBlock> for SidechainStorageLock<SignedBlock> {
 	fn store_blocks(&self, blocks: Vec<SignedBlock>) -> Result<()> {
 		self.storage.write().store_blocks(blocks)
@@ -111,3 +128,29 @@ impl<SignedBlock: SignedBlockT> FetchBlocks<SignedBlock> for SidechainStorageLoc
 			.get_blocks_in_range(block_hash_from, block_hash_until, shard_identifier)
 	}
 }
+
+impl<SignedBlock: SignedBlockT> QueryBlocks<SignedBlock> for SidechainStorageLock<SignedBlock> {
+	fn block_by_hash(&self, block_hash: &BlockHash) -> Result<Option<SignedBlock>> {
+		self.storage.read().get_block(block_hash)
+	}
+
+	fn block_by_number(
+		&self,
+		shard_identifier: &ShardIdentifierFor<SignedBlock>,
+		block_number: BlockNumber,
+	) -> Result<Option<SignedBlock>> {
+		let storage = self.storage.read();
+		match storage.get_block_hash(shard_identifier, block_number)? {
+			Some(block_hash) => storage.get_block(&block_hash),
+			None => Ok(None),
+		}
+	}
+
+	fn block_including_operation(&self, operation_hash: &H256) -> Result<Option<SignedBlock>> {
+		let storage = self.storage.read();
+		match storage.get_block_hash_of_operation(operation_hash)? {
+			Some(block_hash) => storage.get_block(&block_hash),
+			None => Ok(None),
+		}
+	}
+}
@@ -35,6 +35,9 @@ mod storage_tests_get_blocks_after;
 #[cfg(test)]
 mod storage_tests_get_blocks_in_range;
 
+#[cfg(test)]
+mod storage_tests_query_blocks;
+
 #[cfg(test)]
 mod test_utils;
 
@@ -42,7 +45,7 @@ mod test_utils;
 pub mod fetch_blocks_mock;
 
 pub use error::{Error, Result};
-pub use interface::{BlockPruner, BlockStorage, SidechainStorageLock};
+pub use interface::{BlockPruner, BlockStorage, QueryBlocks, SidechainStorageLock};
 
 pub fn start_sidechain_pruning_loop<D>(
 	storage: &Arc<D>,
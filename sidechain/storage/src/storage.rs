@@ -18,7 +18,10 @@
 use super::{db::SidechainDB, Error, Result};
 use codec::{Decode, Encode};
 use its_primitives::{
-	traits::{Block as BlockTrait, Header as HeaderTrait, SignedBlock as SignedBlockT},
+	traits::{
+		Block as BlockTrait, BlockData as BlockDataTrait, Header as HeaderTrait,
+		SignedBlock as SignedBlockT,
+	},
 	types::{BlockHash, BlockNumber},
 };
 use log::*;
@@ -29,6 +32,8 @@ use std::{collections::HashMap, fmt::Debug, path::PathBuf};
 const LAST_BLOCK_KEY: &[u8] = b"last_sidechainblock";
 /// key value of the stored shards vector
 const STORED_SHARDS_KEY: &[u8] = b"stored_shards";
+/// key prefix of the trusted operation hash -> block hash index
+const OPERATION_INCLUSION_KEY: &[u8] = b"operation_inclusion";
 
 /// ShardIdentifier type
 type ShardIdentifierFor<B> =
@@ -98,11 +103,15 @@ impl<SignedBlock: SignedBlockT> SidechainStorage<SignedBlock> {
 	}
 
 	/// gets the block of the given blockhash, if there is such a block
-	#[allow(unused)]
 	pub fn get_block(&self, block_hash: &BlockHash) -> Result<Option<SignedBlock>> {
 		self.db.get(block_hash)
 	}
 
+	/// gets the hash of the block that includes the given trusted operation, if there is such a block
+	pub fn get_block_hash_of_operation(&self, operation_hash: &H256) -> Result<Option<BlockHash>> {
+		self.db.get((OPERATION_INCLUSION_KEY, *operation_hash))
+	}
+
 	/// Get all blocks after (i.e. children of) a specified block.
 	pub fn get_blocks_after(
 		&self,
@@ -333,6 +342,11 @@ impl<SignedBlock: SignedBlockT> SidechainStorage<SignedBlock> {
 		// (Shard, Block number) -> Blockhash (for block pruning).
 		SidechainDB::add_to_batch(batch, (shard, block_number), hash);
 
+		// (operation_inclusion_key, Trusted operation hash) -> Blockhash (inclusion lookup).
+		for operation_hash in block.block().block_data().signed_top_hashes() {
+			SidechainDB::add_to_batch(batch, (OPERATION_INCLUSION_KEY, *operation_hash), hash);
+		}
+
 		// (last_block_key, shard) -> (Blockhash, BlockNr) current blockchain state.
 		let last_block = LastSidechainBlock { hash, number: block_number };
 		self.last_blocks.insert(shard, last_block); // add in memory
@@ -347,6 +361,15 @@ impl<SignedBlock: SignedBlockT> SidechainStorage<SignedBlock> {
 		block_number: &BlockNumber,
 		shard: &ShardIdentifierFor<SignedBlock>,
 	) {
+		// (operation_inclusion_key, Trusted operation hash) -> Blockhash.
+		match self.get_block(block_hash) {
+			Ok(Some(block)) =>
+				for operation_hash in block.block().block_data().signed_top_hashes() {
+					SidechainDB::delete_to_batch(batch, (OPERATION_INCLUSION_KEY, *operation_hash));
+				},
+			Ok(None) => {},
+			Err(e) => error!("Could not load block {:?} to delete its index: {:?}", block_hash, e),
+		}
 		// Block hash -> Signed Block.
 		SidechainDB::delete_to_batch(batch, block_hash);
 		// (Shard, Block number) -> Blockhash (for block pruning).
@@ -0,0 +1,83 @@
+/*
+	Copyright 2021 Integritee AG and Supercomputing Systems AG
+
+	Licensed under the Apache License, Version 2.0 (the "License");
+	you may not use this file except in compliance with the License.
+	You may obtain a copy of the License at
+
+		http://www.apache.org/licenses/LICENSE-2.0
+
+	Unless required by applicable law or agreed to in writing, software
+	distributed under the License is distributed on an "AS IS" BASIS,
+	WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+	See the License for the specific language governing permissions and
+	limitations under the License.
+
+*/
+
+use crate::{
+	interface::{QueryBlocks, SidechainStorageLock},
+	test_utils::{
+		create_signed_block_with_operations as create_signed_block, create_temp_dir, default_shard,
+		fill_storage_with_blocks, get_storage,
+	},
+	BlockStorage,
+};
+use its_primitives::{
+	traits::SignedBlock,
+	types::{BlockHash, SignedBlock as SignedSidechainBlock},
+};
+use sp_core::H256;
+
+#[test]
+fn query_blocks_by_number_and_hash_works() {
+	let block_1 = create_signed_block(1, BlockHash::default(), vec![]);
+	let block_2 = create_signed_block(2, block_1.hash(), vec![]);
+
+	let temp_dir = create_temp_dir();
+	let storage =
+		SidechainStorageLock::<SignedSidechainBlock>::new(temp_dir.path().to_path_buf()).unwrap();
+	storage.store_blocks(vec![block_1.clone(), block_2.clone()]).unwrap();
+
+	assert_eq!(storage.block_by_number(&default_shard(), 2).unwrap(), Some(block_2.clone()));
+	assert_eq!(storage.block_by_hash(&block_1.hash()).unwrap(), Some(block_1));
+	assert_eq!(storage.block_by_number(&default_shard(), 3).unwrap(), None);
+	assert_eq!(storage.block_by_hash(&BlockHash::from_low_u64_be(1)).unwrap(), None);
+}
+
+#[test]
+fn query_block_including_operation_works() {
+	let operation_hash = H256::from_low_u64_be(42);
+	let block_1 = create_signed_block(1, BlockHash::default(), vec![H256::from_low_u64_be(7)]);
+	let block_2 = create_signed_block(2, block_1.hash(), vec![operation_hash]);
+
+	let temp_dir = create_temp_dir();
+	let storage =
+		SidechainStorageLock::<SignedSidechainBlock>::new(temp_dir.path().to_path_buf()).unwrap();
+	storage.store_blocks(vec![block_1, block_2.clone()]).unwrap();
+
+	assert_eq!(storage.block_including_operation(&operation_hash).unwrap(), Some(block_2));
+	assert_eq!(storage.block_including_operation(&H256::from_low_u64_be(8)).unwrap(), None);
+}
+
+#[test]
+fn pruned_blocks_are_removed_from_operation_index() {
+	let operation_hash = H256::from_low_u64_be(42);
+	let block_1 = create_signed_block(1, BlockHash::default(), vec![operation_hash]);
+	let block_2 = create_signed_block(2, block_1.hash(), vec![]);
+	let block_3 = create_signed_block(3, block_2.hash(), vec![]);
+
+	let temp_dir = fill_storage_with_blocks(vec![block_1.clone(), block_2, block_3]);
+
+	{
+		let mut sidechain_db = get_storage(temp_dir.path().to_path_buf());
+		assert_eq!(
+			sidechain_db.get_block_hash_of_operation(&operation_hash).unwrap(),
+			Some(block_1.hash())
+		);
+
+		sidechain_db.prune_shards(1);
+
+		assert_eq!(sidechain_db.get_block_hash_of_operation(&operation_hash).unwrap(), None);
+	}
+}